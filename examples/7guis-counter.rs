@@ -23,6 +23,6 @@ fn main() -> cushy::Result {
         .width(Lp::inches(3))
         .into_window()
         .titled("Counter")
-        .resize_to_fit(true)
+        .size_to_content()
         .run()
 }
@@ -25,6 +25,6 @@ fn main() -> cushy::Result {
         .width(Lp::inches(4))
         .into_window()
         .titled("Temperature Converter")
-        .resize_to_fit(true)
+        .size_to_content()
         .run()
 }
@@ -54,7 +54,7 @@ fn main() -> cushy::Result {
         .width(Lp::inches(4))
         .into_window()
         .titled("Timer")
-        .resize_to_fit(true)
+        .size_to_content()
         .run_in(pending)
 }
 
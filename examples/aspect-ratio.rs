@@ -0,0 +1,31 @@
+use cushy::kludgine::Color;
+use cushy::reactive::value::{Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::image::Aspect;
+use cushy::widgets::Space;
+use cushy::Run;
+
+fn aspect_ratio() -> impl MakeWidget {
+    let cover = Dynamic::new(false);
+    let mode = cover.map_each(|cover| if *cover { Aspect::Fill } else { Aspect::Fit });
+
+    "Cover"
+        .into_checkbox(cover)
+        .and(
+            Space::colored(Color::CORNFLOWERBLUE)
+                .aspect_ratio(16. / 9.)
+                .mode(mode)
+                .expand(),
+        )
+        .into_rows()
+        .expand()
+}
+
+fn main() -> cushy::Result {
+    aspect_ratio().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(aspect_ratio).untested_still_frame();
+}
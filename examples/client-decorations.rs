@@ -0,0 +1,38 @@
+use cushy::figures::Zero;
+use cushy::reactive::value::{Destination, Dynamic, Source};
+use cushy::styles::Dimension;
+use cushy::widget::MakeWidget;
+use cushy::widgets::TitleBar;
+use cushy::window::ClientDecorations;
+use cushy::Run;
+
+fn main() -> cushy::Result {
+    let rounded = Dynamic::new(true);
+    let maximized = Dynamic::new(false);
+    let decorations = rounded.map_each(|rounded| {
+        let decorations = ClientDecorations::default();
+        if *rounded {
+            decorations
+        } else {
+            decorations.corner_radius(Dimension::ZERO)
+        }
+    });
+
+    let contents = "This window's shadow and corners are drawn by Cushy."
+        .and("Rounded Corners".into_checkbox(rounded))
+        .and("Maximize".into_button().on_click({
+            let maximized = maximized.clone();
+            move |_| maximized.toggle()
+        }))
+        .into_rows()
+        .centered()
+        .expand();
+
+    TitleBar::new("Client Decorations")
+        .and(contents)
+        .into_rows()
+        .into_window()
+        .client_decorations(decorations)
+        .maximized(maximized)
+        .run()
+}
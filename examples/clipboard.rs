@@ -0,0 +1,84 @@
+use cushy::figures::units::Lp;
+use cushy::reactive::value::{Destination, Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::input::InputValue;
+use cushy::{Cushy, Run};
+use image::{Rgba, RgbaImage};
+
+fn clipboard() -> impl MakeWidget {
+    let clipboard = Cushy::current().clipboard();
+    let text = Dynamic::<String>::default();
+    let status = Dynamic::new(String::from("Ready"));
+    let changes = clipboard
+        .changes()
+        .map_each(|changes| format!("Clipboard changes observed: {changes}"));
+
+    let copy_text = "Copy Text".into_button().on_click({
+        let clipboard = clipboard.clone();
+        let text = text.clone();
+        let status = status.clone();
+        move |_| match clipboard.set_text(text.get()) {
+            Ok(()) => status.set(String::from("Copied text")),
+            Err(err) => status.set(format!("Error copying text: {err}")),
+        }
+    });
+    let paste_text = "Paste Text".into_button().on_click({
+        let clipboard = clipboard.clone();
+        let text = text.clone();
+        let status = status.clone();
+        move |_| match clipboard.get_text() {
+            Ok(pasted) => {
+                text.set(pasted);
+                status.set(String::from("Pasted text"));
+            }
+            Err(err) => status.set(format!("Error pasting text: {err}")),
+        }
+    });
+    let copy_image = "Copy Gradient".into_button().on_click({
+        let clipboard = clipboard.clone();
+        let status = status.clone();
+        move |_| {
+            let gradient = RgbaImage::from_fn(256, 256, |x, y| {
+                Rgba([
+                    x.try_into().unwrap_or(0),
+                    y.try_into().unwrap_or(0),
+                    128,
+                    255,
+                ])
+            });
+            match clipboard.set_image(gradient) {
+                Ok(()) => status.set(String::from("Copied a 256x256 image")),
+                Err(err) => status.set(format!("Error copying image: {err}")),
+            }
+        }
+    });
+    let paste_image = "Inspect Image".into_button().on_click({
+        let status = status.clone();
+        move |_| match clipboard.get_image() {
+            Ok(image) => status.set(format!(
+                "The clipboard contains a {}x{} image",
+                image.width(),
+                image.height()
+            )),
+            Err(err) => status.set(format!("Error reading image: {err}")),
+        }
+    });
+
+    text.into_input()
+        .and(copy_text.and(paste_text).into_columns())
+        .and(copy_image.and(paste_image).into_columns())
+        .and(status)
+        .and(changes)
+        .into_rows()
+        .width(Lp::inches(4))
+        .centered()
+}
+
+fn main() -> cushy::Result {
+    clipboard().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(clipboard).untested_still_frame();
+}
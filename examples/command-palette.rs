@@ -0,0 +1,56 @@
+use cushy::kludgine::app::winit::keyboard::ModifiersState;
+use cushy::reactive::value::{Destination, Dynamic};
+use cushy::widget::{MakeWidget, HANDLED};
+use cushy::widgets::command_palette::{Command, CommandPalette};
+use cushy::widgets::layers::Modal;
+use cushy::{ModifiersStateExt, Run};
+
+fn command_palette() -> impl MakeWidget {
+    let modal = Modal::new();
+    let last_command = Dynamic::new(String::from("No command invoked yet"));
+
+    let palette = CommandPalette::new(&modal);
+    for (name, shortcut) in [
+        ("New File", Some("n")),
+        ("Open File", Some("o")),
+        ("Save File", Some("s")),
+        ("Close Window", Some("w")),
+        ("Toggle Theme", None),
+        ("Show Keyboard Shortcuts", None),
+    ] {
+        let last_command = last_command.clone();
+        let mut command = Command::new(name, move || last_command.set(format!("Invoked {name}")));
+        if let Some(key) = shortcut {
+            command = command.shortcut((key, ModifiersState::PRIMARY));
+        }
+        palette.push(command);
+    }
+
+    "Press Ctrl+Shift+P to open the command palette"
+        .and("Open Command Palette".into_button().on_click({
+            let palette = palette.clone();
+            move |_| palette.show()
+        }))
+        .and(last_command)
+        .into_rows()
+        .centered()
+        .expand()
+        .with_shortcut("p", ModifiersState::PRIMARY | ModifiersState::SHIFT, {
+            let palette = palette.clone();
+            move |_| {
+                palette.show();
+                HANDLED
+            }
+        })
+        .and(modal)
+        .into_layers()
+}
+
+fn main() -> cushy::Result {
+    command_palette().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(command_palette).untested_still_frame();
+}
@@ -0,0 +1,60 @@
+use cushy::reactive::value::{Destination, Dynamic};
+use cushy::widget::MakeWidget;
+use cushy::widgets::layers::OverlayLayer;
+use cushy::widgets::menu::{Menu, MenuItem};
+use cushy::Run;
+
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    Cut,
+    Copy,
+    Paste,
+    SelectAll,
+    Uppercase,
+    Lowercase,
+}
+
+fn context_menu() -> impl MakeWidget {
+    let overlay = OverlayLayer::default();
+    let last_action = Dynamic::new(String::from(
+        "Right click the area above, or focus it and press Shift+F10.",
+    ));
+
+    let transform = Menu::new()
+        .with(MenuItem::new(Action::Uppercase, "Uppercase"))
+        .with(MenuItem::new(Action::Lowercase, "Lowercase"));
+    let menu = Menu::new()
+        .on_selected({
+            let last_action = last_action.clone();
+            move |action| last_action.set(format!("Selected {action:?}"))
+        })
+        .with(MenuItem::new(Action::Cut, "Cut"))
+        .with(MenuItem::new(Action::Copy, "Copy"))
+        .with(MenuItem::build(Action::Paste).text("Paste").disabled())
+        .with_separator()
+        .with(MenuItem::new(Action::SelectAll, "Select All"))
+        .with(
+            MenuItem::build(Action::Uppercase)
+                .text("Transform")
+                .submenu(transform),
+        );
+
+    "Right click me"
+        .centered()
+        .expand()
+        .context_menu(&overlay, menu)
+        .and(last_action)
+        .into_rows()
+        .expand()
+        .and(overlay)
+        .into_layers()
+}
+
+fn main() -> cushy::Result {
+    context_menu().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(context_menu).untested_still_frame();
+}
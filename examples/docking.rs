@@ -0,0 +1,68 @@
+use cushy::reactive::value::{Destination, Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::dock::{DockArea, DockLayout, DockPanel};
+use cushy::widgets::input::InputValue;
+use cushy::Run;
+
+fn docking() -> impl MakeWidget {
+    let default_layout = DockLayout::columns(
+        DockLayout::tabs(["files"]),
+        DockLayout::rows(
+            DockLayout::tabs(["editor", "preview"]),
+            DockLayout::tabs(["terminal", "problems"]),
+        )
+        .ratio(0.7),
+    )
+    .ratio(0.25);
+
+    let dock = DockArea::new()
+        .with(DockPanel::new(
+            "files",
+            "Files",
+            "src/\nexamples/\nCargo.toml",
+        ))
+        .with(DockPanel::new(
+            "editor",
+            "Editor",
+            Dynamic::new(String::from("fn main() {}"))
+                .into_input()
+                .expand(),
+        ))
+        .with(DockPanel::new("preview", "Preview", "Nothing to preview"))
+        .with(DockPanel::new("terminal", "Terminal", "$ cargo run"))
+        .with(DockPanel::new("problems", "Problems", "No problems"))
+        .with_layout(&default_layout);
+
+    // Layouts can be saved and restored. With the `serde` feature enabled,
+    // DockLayout can be persisted to disk.
+    let saved = Dynamic::new(dock.layout());
+
+    "Save Layout"
+        .into_button()
+        .on_click({
+            let dock = dock.clone();
+            let saved = saved.clone();
+            move |_| saved.set(dock.layout())
+        })
+        .and("Restore Layout".into_button().on_click({
+            let dock = dock.clone();
+            move |_| dock.set_layout(&saved.get())
+        }))
+        .and("Reset Layout".into_button().on_click({
+            let dock = dock.clone();
+            move |_| dock.set_layout(&default_layout)
+        }))
+        .into_columns()
+        .and(dock.expand())
+        .into_rows()
+        .expand()
+}
+
+fn main() -> cushy::Result {
+    docking().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(docking).untested_still_frame();
+}
@@ -0,0 +1,59 @@
+use cushy::reactive::value::{Dynamic, Source};
+use cushy::widget::{MakeWidget, WidgetList};
+use cushy::widgets::drag::{DragSource, DropTarget};
+use cushy::Run;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Side {
+    Left,
+    Right,
+}
+
+fn drag_and_drop() -> impl MakeWidget {
+    let left = Dynamic::new(vec![
+        String::from("Apples"),
+        String::from("Bananas"),
+        String::from("Cherries"),
+    ]);
+    let right = Dynamic::new(vec![String::from("Dates")]);
+
+    list(Side::Left, &left, &right)
+        .expand()
+        .and(list(Side::Right, &right, &left).expand())
+        .into_columns()
+        .expand()
+}
+
+fn list(side: Side, items: &Dynamic<Vec<String>>, other: &Dynamic<Vec<String>>) -> impl MakeWidget {
+    let rows = items.map_each(move |items| {
+        items
+            .iter()
+            .map(|item| {
+                DragSource::new(item.as_str().contain(), (side, item.clone()))
+                    .ghost(item.as_str().contain())
+                    .make_widget()
+            })
+            .collect::<WidgetList>()
+    });
+
+    DropTarget::<(Side, String)>::new(rows.into_rows().align_top().expand())
+        .on_hover(move |(from, _), _location| *from != side)
+        .on_drop({
+            let items = items.clone();
+            let other = other.clone();
+            move |(_, item), _location| {
+                other.lock().retain(|existing| existing != &item);
+                items.lock().push(item);
+            }
+        })
+        .contain()
+}
+
+fn main() -> cushy::Result {
+    drag_and_drop().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(drag_and_drop).untested_still_frame();
+}
@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use cushy::reactive::value::{Destination, Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::drag::FileDropTarget;
+use cushy::window::{DropEvent, MakeWindow};
+use cushy::Run;
+
+fn file_drop() -> impl MakeWidget {
+    let dropped = Dynamic::new(Vec::<PathBuf>::new());
+    let listing = dropped.map_each(|paths| {
+        if paths.is_empty() {
+            String::from("Drop files here")
+        } else {
+            paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    });
+
+    FileDropTarget::new(listing.centered().expand())
+        .on_drop(move |path, _location| dropped.lock().push(path))
+        .expand()
+}
+
+fn main() -> cushy::Result {
+    let hovering = Dynamic::new(0_usize);
+    let status = hovering.map_each(|count| match count {
+        0 => String::from("Drag files from your file manager onto this window."),
+        1 => String::from("Hovering 1 file"),
+        count => format!("Hovering {count} files"),
+    });
+
+    file_drop()
+        .and(status)
+        .into_rows()
+        .into_window()
+        .on_file_hover({
+            let hovering = hovering.clone();
+            move |event| match event.drop {
+                DropEvent::Hover(_) => *hovering.lock() += 1,
+                DropEvent::Dropped(_) | DropEvent::Cancelled => hovering.set(0),
+            }
+        })
+        .on_file_drop(move |event| {
+            hovering.set(0);
+            if let DropEvent::Dropped(path) = event.drop {
+                println!("Dropped on window: {}", path.display());
+            }
+        })
+        .run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(file_drop).untested_still_frame();
+}
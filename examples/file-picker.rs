@@ -17,6 +17,7 @@ fn main(app: &mut App) -> cushy::Result {
     let chosen_paths = Dynamic::<Vec<PathBuf>>::default();
     let picker_mode = Dynamic::default();
     let pick_multiple = Dynamic::new(false);
+    let use_builtin = Dynamic::new(false);
     let results = chosen_paths.map_each(|paths| {
         if paths.is_empty() {
             "None".make_widget()
@@ -53,9 +54,11 @@ fn main(app: &mut App) -> cushy::Result {
                             picker_mode.map_each(|kind| !matches!(kind, PickerMode::SaveFile)),
                         ),
                 )
+                .and(use_builtin.to_checkbox().labelled_by("Use Built-in Picker"))
                 .and(picker_buttons(
                     &picker_mode,
                     &pick_multiple,
+                    &use_builtin,
                     app,
                     &window,
                     &modal,
@@ -82,10 +85,11 @@ enum PickerMode {
     PickFolder,
 }
 
-fn file_picker() -> FilePicker {
+fn file_picker(use_builtin: bool) -> FilePicker {
     FilePicker::new()
         .with_title("Pick a Rust source file")
         .with_types([("Rust Source", ["rs"])])
+        .using_builtin_dialog(use_builtin)
 }
 
 fn display_single_result(
@@ -109,6 +113,7 @@ fn display_multiple_results(
 fn picker_buttons(
     mode: &Dynamic<PickerMode>,
     pick_multiple: &Dynamic<bool>,
+    use_builtin: &Dynamic<bool>,
     app: &App,
     window: &WindowHandle,
     modal: &Modal,
@@ -116,18 +121,26 @@ fn picker_buttons(
 ) -> impl MakeWidget {
     "Show in Modal layer"
         .into_button()
-        .on_click(show_picker_in(modal, chosen_paths, mode, pick_multiple))
+        .on_click(show_picker_in(
+            modal,
+            chosen_paths,
+            mode,
+            pick_multiple,
+            use_builtin,
+        ))
         .and("Show above window".into_button().on_click(show_picker_in(
             window,
             chosen_paths,
             mode,
             pick_multiple,
+            use_builtin,
         )))
         .and("Show in app".into_button().on_click(show_picker_in(
             app,
             chosen_paths,
             mode,
             pick_multiple,
+            use_builtin,
         )))
         .into_rows()
 }
@@ -137,27 +150,26 @@ fn show_picker_in(
     chosen_paths: &Dynamic<Vec<PathBuf>>,
     mode: &Dynamic<PickerMode>,
     pick_multiple: &Dynamic<bool>,
+    use_builtin: &Dynamic<bool>,
 ) -> impl FnMut(Option<ButtonClick>) + Send + 'static {
     let target = target.clone();
     let chosen_paths = chosen_paths.clone();
     let mode = mode.clone();
     let pick_multiple = pick_multiple.clone();
+    let use_builtin = use_builtin.clone();
     move |_| {
+        let picker = file_picker(use_builtin.get());
         match mode.get() {
-            PickerMode::SaveFile => {
-                file_picker().save_file(&target, display_single_result(&chosen_paths))
-            }
+            PickerMode::SaveFile => picker.save_file(&target, display_single_result(&chosen_paths)),
             PickerMode::PickFile if pick_multiple.get() => {
-                file_picker().pick_files(&target, display_multiple_results(&chosen_paths))
-            }
-            PickerMode::PickFile => {
-                file_picker().pick_file(&target, display_single_result(&chosen_paths))
+                picker.pick_files(&target, display_multiple_results(&chosen_paths))
             }
+            PickerMode::PickFile => picker.pick_file(&target, display_single_result(&chosen_paths)),
             PickerMode::PickFolder if pick_multiple.get() => {
-                file_picker().pick_folders(&target, display_multiple_results(&chosen_paths))
+                picker.pick_folders(&target, display_multiple_results(&chosen_paths))
             }
             PickerMode::PickFolder => {
-                file_picker().pick_folder(&target, display_single_result(&chosen_paths))
+                picker.pick_folder(&target, display_single_result(&chosen_paths))
             }
         };
     }
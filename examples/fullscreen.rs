@@ -1,32 +1,52 @@
+use cushy::kludgine::app::winit::monitor::MonitorHandle;
 use cushy::kludgine::app::winit::window::Fullscreen;
 use cushy::kludgine::app::Monitor;
-use cushy::reactive::value::Dynamic;
+use cushy::reactive::value::{Dynamic, Source};
 use cushy::widget::{MakeWidget, WidgetList};
+use cushy::window::PendingWindow;
 use cushy::{App, Open};
 
 #[cushy::main]
 fn main(app: &mut App) -> cushy::Result {
     let monitors = app.monitors().expect("monitors api not supported");
     let fullscreen = Dynamic::new(None);
+    let current_monitor = Dynamic::new(None);
+    let pending = PendingWindow::default();
+    let window = pending.handle();
 
-    fullscreen
-        .new_radio(None)
-        .labelled_by("Not Fullscreen")
-        .and(
-            monitors
-                .available
-                .iter()
-                .enumerate()
-                .map(|(index, monitor)| monitor_modes(index, monitor, &fullscreen))
-                .collect::<WidgetList>()
-                .into_rows(),
+    pending
+        .with_root(
+            current_monitor
+                .map_each(|monitor: &Option<MonitorHandle>| {
+                    let name = monitor
+                        .as_ref()
+                        .and_then(MonitorHandle::name)
+                        .unwrap_or_else(|| String::from("Unknown"));
+                    format!("Currently on {name}")
+                })
+                .and(
+                    "Toggle Borderless Fullscreen"
+                        .into_button()
+                        .on_click(move |_| window.toggle_fullscreen(Fullscreen::Borderless(None))),
+                )
+                .into_columns()
+                .and(fullscreen.new_radio(None).labelled_by("Not Fullscreen"))
+                .and(
+                    monitors
+                        .available
+                        .iter()
+                        .enumerate()
+                        .map(|(index, monitor)| monitor_modes(index, monitor, &fullscreen))
+                        .collect::<WidgetList>()
+                        .into_rows(),
+                )
+                .into_rows()
+                .pad()
+                .vertical_scroll()
+                .expand(),
         )
-        .into_rows()
-        .pad()
-        .vertical_scroll()
-        .expand()
-        .into_window()
         .fullscreen(fullscreen)
+        .current_monitor(current_monitor)
         .open(app)?;
     Ok(())
 }
@@ -0,0 +1,45 @@
+use cushy::reactive::value::{Destination, Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::window::{Magnifier, MagnifierFocus};
+use cushy::Run;
+
+fn main() -> cushy::Result {
+    let magnifier = Magnifier::new();
+    let follow_focus = Dynamic::new(false);
+    follow_focus
+        .for_each({
+            let focus = magnifier.focus().clone();
+            move |follow_focus| {
+                focus.set(if *follow_focus {
+                    MagnifierFocus::FocusedWidget
+                } else {
+                    MagnifierFocus::Cursor
+                });
+            }
+        })
+        .persist();
+
+    "Zoom In"
+        .into_button()
+        .on_click({
+            let magnifier = magnifier.clone();
+            move |_| magnifier.zoom_in()
+        })
+        .and("Zoom Out".into_button().on_click({
+            let magnifier = magnifier.clone();
+            move |_| magnifier.zoom_out()
+        }))
+        .and("Reset".into_button().on_click({
+            let magnifier = magnifier.clone();
+            move |_| magnifier.reset()
+        }))
+        .into_columns()
+        .and("Follow Focused Widget".into_checkbox(follow_focus))
+        .and("Use Ctrl/Cmd + Alt + =, -, and 0 to zoom from the keyboard.")
+        .and(Dynamic::<String>::default().into_input())
+        .into_rows()
+        .centered()
+        .into_window()
+        .magnifier(magnifier)
+        .run()
+}
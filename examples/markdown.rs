@@ -0,0 +1,49 @@
+use cushy::reactive::value::Dynamic;
+use cushy::widget::MakeWidget;
+use cushy::widgets::{Markdown, TextEditor};
+use cushy::Run;
+
+const INITIAL_SOURCE: &str = r#"# Markdown
+
+Cushy can display **CommonMark** text, including *emphasis*, `inline code`,
+and [links](https://github.com/khonsulabs/cushy).
+
+## Lists
+
+- Unordered lists
+- [x] Task lists
+1. Ordered lists
+2. With numbers
+
+> Block quotes are displayed in a container.
+
+```rust
+fn main() {
+    println!("Code blocks use a monospace font.");
+}
+```
+"#;
+
+fn markdown() -> impl MakeWidget {
+    let source = Dynamic::new(String::from(INITIAL_SOURCE));
+
+    TextEditor::new(source.clone())
+        .expand()
+        .and(
+            Markdown::new(source)
+                .on_link(|url| println!("Clicked {url}"))
+                .vertical_scroll()
+                .expand(),
+        )
+        .into_columns()
+        .expand()
+}
+
+fn main() -> cushy::Result {
+    markdown().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(markdown).untested_still_frame();
+}
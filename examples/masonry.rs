@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use cushy::figures::units::Lp;
+use cushy::kludgine::Color;
+use cushy::reactive::value::Dynamic;
+use cushy::widget::{MakeWidget, WidgetInstance, WidgetList};
+use cushy::widgets::{Masonry, Space};
+use cushy::Run;
+use rand::{thread_rng, Rng};
+
+fn tile() -> WidgetInstance {
+    let mut rng = thread_rng();
+    let color = Color::new(rng.gen(), rng.gen(), rng.gen(), 255);
+    Space::colored(color)
+        .height(Lp::points(rng.gen_range(40..160)))
+        .make_widget()
+}
+
+fn main() -> cushy::Result {
+    let tiles = Dynamic::new((0..24).map(|_| tile()).collect::<WidgetList>());
+
+    let add = "Add Tile".into_button().on_click({
+        let tiles = tiles.clone();
+        move |_| {
+            let mut tiles = tiles.lock();
+            let index = thread_rng().gen_range(0..=tiles.len());
+            tiles.insert(index, tile());
+        }
+    });
+    let remove = "Remove Tile".into_button().on_click({
+        let tiles = tiles.clone();
+        move |_| {
+            let mut tiles = tiles.lock();
+            if !tiles.is_empty() {
+                let index = thread_rng().gen_range(0..tiles.len());
+                tiles.remove(index);
+            }
+        }
+    });
+
+    add.and(remove)
+        .into_columns()
+        .and(
+            Masonry::new(tiles)
+                .animated(Duration::from_millis(250))
+                .vertical_scroll()
+                .expand(),
+        )
+        .into_rows()
+        .run()
+}
@@ -0,0 +1,61 @@
+use cushy::reactive::value::{Destination, Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::layers::Modal;
+use cushy::Run;
+
+fn modal_result() -> impl MakeWidget {
+    let modal = Modal::new();
+    let name = Dynamic::new(String::from("nobody"));
+    let greeting = name.map_each(|name| format!("Hello, {name}!"));
+
+    "Change Name"
+        .into_button()
+        .on_click({
+            let modal = modal.clone();
+            let name = name.clone();
+            move |_| {
+                let input = Dynamic::<String>::default();
+                let result = modal.present_for_result(|result| {
+                    "What is your name?"
+                        .and(input.clone().into_input())
+                        .and(
+                            "Save"
+                                .into_button()
+                                .on_click({
+                                    let result = result.clone();
+                                    move |_| result.complete(input.get())
+                                })
+                                .into_default()
+                                .and("Cancel".into_button().on_click(move |_| result.dismiss()))
+                                .into_columns()
+                                .centered(),
+                        )
+                        .into_rows()
+                        .contain()
+                });
+                let name = name.clone();
+                result
+                    .for_each(move |result| {
+                        if let Some(new_name) = result {
+                            name.set(new_name.clone());
+                        }
+                    })
+                    .persist();
+            }
+        })
+        .and(greeting)
+        .into_rows()
+        .centered()
+        .expand()
+        .and(modal)
+        .into_layers()
+}
+
+fn main() -> cushy::Result {
+    modal_result().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(modal_result).untested_still_frame();
+}
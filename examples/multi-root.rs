@@ -0,0 +1,35 @@
+use cushy::reactive::value::{Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::roots::{RootRegion, Roots};
+use cushy::widgets::slider::Slidable;
+use cushy::Run;
+
+fn main() -> cushy::Result {
+    let split = Dynamic::new(0.5_f32);
+    let counter = Dynamic::new(0_u32);
+
+    let left = counter
+        .map_each(|count| format!("Clicked {count} times"))
+        .and("Click Me".into_button().on_click({
+            let counter = counter.clone();
+            move |_| {
+                *counter.lock() += 1;
+            }
+        }))
+        .into_rows()
+        .centered()
+        .contain();
+    let right = "An independent widget tree"
+        .and(split.clone().slider_between(0.1, 0.9))
+        .into_rows()
+        .centered()
+        .contain();
+
+    Roots::new()
+        .with_root(split.map_each(|split| RootRegion::left(*split)), left)
+        .with_root(
+            split.map_each(|split| RootRegion::right(1. - *split)),
+            right,
+        )
+        .run()
+}
@@ -0,0 +1,38 @@
+use std::thread;
+use std::time::Duration;
+
+use cushy::kludgine::app::winit::window::WindowLevel;
+use cushy::kludgine::Color;
+use cushy::reactive::value::{Destination, Dynamic};
+use cushy::widget::MakeWidget;
+use cushy::window::HitTestMask;
+use cushy::Run;
+
+fn main() -> cushy::Result {
+    let hittest = Dynamic::new(true);
+
+    "This translucent window floats above other applications."
+        .and("Click Through for 5 Seconds".into_button().on_click({
+            let hittest = hittest.clone();
+            move |_| {
+                // While click-through is enabled, this window can't be
+                // clicked, so hit testing is restored after a delay.
+                hittest.set(false);
+                let hittest = hittest.clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_secs(5));
+                    hittest.set(true);
+                });
+            }
+        }))
+        .into_rows()
+        .centered()
+        .into_window()
+        .transparent()
+        .decorated(false)
+        .clear_color(Color::new(0, 0, 0, 160))
+        .window_level(WindowLevel::AlwaysOnTop)
+        .hit_test_mask(HitTestMask::Widgets)
+        .cursor_hittest(hittest)
+        .run()
+}
@@ -0,0 +1,15 @@
+use cushy::widget::MakeWidget;
+use cushy::window::WindowPersistence;
+use cushy::Run;
+
+fn main() -> cushy::Result {
+    let persistence =
+        WindowPersistence::file(std::env::temp_dir().join("cushy-persisted-window.txt"));
+
+    "Move, resize, or maximize this window, then run this example again."
+        .centered()
+        .expand()
+        .into_window()
+        .persisted(&persistence)
+        .run()
+}
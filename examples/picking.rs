@@ -0,0 +1,58 @@
+use cushy::figures::units::Px;
+use cushy::figures::{FloatConversion, Point};
+use cushy::kludgine::shapes::Shape;
+use cushy::kludgine::{Color, DrawableExt, Origin};
+use cushy::reactive::value::Source;
+use cushy::widget::MakeWidget;
+use cushy::widgets::canvas::PickingBuffer;
+use cushy::widgets::Canvas;
+use cushy::Run;
+use rand::{thread_rng, Rng};
+
+const DOTS: u32 = 5_000;
+
+fn main() -> cushy::Result {
+    let mut rng = thread_rng();
+    let dots = (0..DOTS)
+        .map(|_| {
+            (
+                Point::new(rng.gen_range(0.0..1.0_f32), rng.gen_range(0.0..1.0_f32)),
+                Px::new(rng.gen_range(3..12)),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let picking = PickingBuffer::new();
+    let hovered = picking.picked().map_each(|picked| match picked {
+        Some(id) => format!("Hovering dot {id}"),
+        None => String::from("Hover a dot"),
+    });
+
+    let canvas = Canvas::new({
+        let picking = picking.clone();
+        move |context| {
+            let size = context.gfx.size();
+            let picked = picking.picked().get_tracking_redraw(context);
+            let mut ids = picking.frame();
+            for (id, (position, radius)) in (0..).zip(&dots) {
+                let center = Point::new(
+                    Px::from(position.x * size.width.into_float()),
+                    Px::from(position.y * size.height.into_float()),
+                );
+                let color = if picked == Some(id) {
+                    Color::RED
+                } else {
+                    Color::WHITE.with_alpha(128)
+                };
+                context.gfx.draw_shape(
+                    Shape::filled_circle(*radius, color, Origin::Center).translate_by(center),
+                );
+                ids.fill_circle(center, *radius, id);
+            }
+            ids.render(context);
+        }
+    })
+    .picking(picking);
+
+    hovered.and(canvas.expand()).into_rows().expand().run()
+}
@@ -0,0 +1,68 @@
+use cushy::figures::units::Lp;
+use cushy::reactive::value::{Destination, Dynamic, Switchable};
+use cushy::widget::{MakeWidget, WidgetList};
+use cushy::widgets::portal::PortalTarget;
+use cushy::widgets::Space;
+use cushy::Run;
+
+fn portal() -> impl MakeWidget {
+    let target = PortalTarget::new();
+    let show_dropdown = Dynamic::new(false);
+    let selected = Dynamic::new(String::from("Nothing selected"));
+
+    // The dropdown is owned by the scrolled content, but it is displayed by
+    // the portal target. This allows it to extend beyond the clipped region of
+    // the scroll view.
+    let dropdown = show_dropdown.clone().switcher({
+        let target = target.clone();
+        let selected = selected.clone();
+        move |show, show_dropdown| {
+            if *show {
+                ["Apple", "Banana", "Cherry", "Dragonfruit", "Elderberry"]
+                    .into_iter()
+                    .map(|fruit| {
+                        let selected = selected.clone();
+                        let show_dropdown = show_dropdown.clone();
+                        fruit.into_button().on_click(move |_| {
+                            selected.set(String::from(fruit));
+                            show_dropdown.set(false);
+                        })
+                    })
+                    .collect::<WidgetList>()
+                    .into_rows()
+                    .contain()
+                    .portal(&target)
+                    .anchored()
+                    .make_widget()
+            } else {
+                Space::clear().make_widget()
+            }
+        }
+    });
+
+    let scrolled = "Choose a fruit"
+        .into_checkbox(show_dropdown)
+        .and(dropdown)
+        .and("The scroll area ends below this line.")
+        .into_rows()
+        .vertical_scroll()
+        .height(Lp::inches(1))
+        .contain();
+
+    selected
+        .and(scrolled)
+        .into_rows()
+        .centered()
+        .expand()
+        .and(target)
+        .into_layers()
+}
+
+fn main() -> cushy::Result {
+    portal().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(portal).untested_still_frame();
+}
@@ -0,0 +1,64 @@
+use cushy::reactive::value::{Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::window::Presenter;
+use cushy::{App, Open};
+
+#[cushy::main]
+fn main(app: &mut App) -> cushy::Result {
+    let monitors = app.monitors().expect("monitors api not supported");
+    // Present on the last monitor that isn't the primary monitor, if one is
+    // available.
+    let monitor = monitors
+        .available
+        .iter()
+        .rev()
+        .find(|monitor| Some(*monitor) != monitors.primary.as_ref())
+        .or(monitors.primary.as_ref())
+        .or(monitors.available.first())
+        .cloned()
+        .expect("at least one monitor");
+
+    let presenter = Presenter::new();
+    let count = Dynamic::new(0_u32);
+    // Widgets can only be shown in one window, so the mirrored window builds
+    // its own copy of the contents from the same dynamic.
+    let contents = |count: &Dynamic<u32>| {
+        count
+            .map_each(|count| format!("Clicked {count} times"))
+            .h1()
+            .and("Click Me".into_button().on_click({
+                let count = count.clone();
+                move |_| *count.lock() += 1
+            }))
+            .into_rows()
+            .centered()
+    };
+
+    presenter.mirror(contents(&count)).open(app)?;
+
+    let highlight = presenter.pointer_highlight().enabled().clone();
+    contents(&count)
+        .and(
+            presenter
+                .presenting()
+                .map_each(|presenting| {
+                    if *presenting {
+                        "Stop Presenting"
+                    } else {
+                        "Present"
+                    }
+                })
+                .into_button()
+                .on_click({
+                    let presenter = presenter.clone();
+                    move |_| presenter.toggle(&monitor)
+                }),
+        )
+        .and("Highlight Pointer".into_checkbox(highlight))
+        .into_rows()
+        .centered()
+        .into_window()
+        .pointer_highlight(presenter.pointer_highlight().clone())
+        .open(app)?;
+    Ok(())
+}
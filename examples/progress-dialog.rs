@@ -0,0 +1,37 @@
+use std::thread;
+use std::time::Duration;
+
+use cushy::dialog::ProgressDialog;
+use cushy::widget::MakeWidget;
+use cushy::widgets::layers::Modal;
+use cushy::Run;
+
+fn main() -> cushy::Result {
+    let modal = Modal::new();
+
+    "Copy Files"
+        .into_button()
+        .on_click({
+            let modal = modal.clone();
+            move |_| {
+                ProgressDialog::new("Copying Files")
+                    .cancellable()
+                    .spawn(&modal, |progress| {
+                        const FILES: u16 = 50;
+                        for file in 0..FILES {
+                            if progress.is_cancelled() {
+                                return;
+                            }
+                            progress.set_message(format!("Copying file {}", file + 1));
+                            thread::sleep(Duration::from_millis(100));
+                            progress.set_progress(f32::from(file + 1) / f32::from(FILES));
+                        }
+                    });
+            }
+        })
+        .centered()
+        .expand()
+        .and(modal)
+        .into_layers()
+        .run()
+}
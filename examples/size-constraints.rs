@@ -0,0 +1,45 @@
+use cushy::figures::units::Lp;
+use cushy::figures::Size;
+use cushy::styles::{Dimension, OverflowPolicy};
+use cushy::widget::MakeWidget;
+use cushy::Run;
+
+const LOREM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do \
+                     eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim \
+                     ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut \
+                     aliquip ex ea commodo consequat.";
+
+fn size(width: Lp, height: Lp) -> Size<Dimension> {
+    Size::new(Dimension::Lp(width), Dimension::Lp(height))
+}
+
+fn size_constraints() -> impl MakeWidget {
+    let at_least = "At least 2x1 inches"
+        .contain()
+        .min_size(size(Lp::inches(2), Lp::inches(1)));
+    let clipped = LOREM
+        .contain()
+        .overflow(OverflowPolicy::Clip)
+        .max_size(size(Lp::inches(2), Lp::inches(1)));
+    let scrolled = LOREM
+        .and(LOREM)
+        .into_rows()
+        .contain()
+        .overflow(OverflowPolicy::Scroll)
+        .max_size(size(Lp::inches(2), Lp::inches(1)));
+
+    at_least
+        .and(clipped)
+        .and(scrolled)
+        .into_columns()
+        .centered()
+}
+
+fn main() -> cushy::Result {
+    size_constraints().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(size_constraints).untested_still_frame();
+}
@@ -1,4 +1,4 @@
-use cushy::animation::{LinearInterpolate, PercentBetween};
+use cushy::animation::{LinearInterpolate, PercentBetween, ZeroToOne};
 use cushy::figures::units::Lp;
 use cushy::figures::Ranged;
 use cushy::reactive::value::{Destination, Dynamic, ForEach, Source};
@@ -13,6 +13,7 @@ fn main() -> cushy::Result {
     let enabled = Dynamic::new(true);
     u8_slider()
         .and(u8_range_slider())
+        .and(price_range_slider())
         .and(enum_slider())
         .into_rows()
         .with_enabled(enabled.clone())
@@ -44,6 +45,19 @@ fn u8_slider() -> impl MakeWidget {
         .into_rows()
 }
 
+fn price_range_slider() -> impl MakeWidget {
+    let prices = Dynamic::new((20_u16, 80_u16));
+    let prices_text = prices.map_each(|(start, end)| format!("${start} to ${end}"));
+
+    prices
+        .slider_between(0, 200)
+        .ticks(20)
+        .snap_to_ticks(true)
+        .minimum_gap(ZeroToOne::new(0.1))
+        .and(prices_text.centered())
+        .into_rows()
+}
+
 fn u8_range_slider() -> impl MakeWidget {
     let range = Dynamic::new(42..=127);
     let start = range.map_each(|range| *range.start());
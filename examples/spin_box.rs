@@ -0,0 +1,41 @@
+use cushy::reactive::value::{Dynamic, MapEach, Validations};
+use cushy::widget::MakeWidget;
+use cushy::widgets::SpinBox;
+use cushy::Run;
+
+fn spin_box() -> impl MakeWidget {
+    let validations = Validations::default();
+    let quantity = Dynamic::new(1_u32);
+    let price = Dynamic::new(2.5_f32);
+    let total = (&quantity, &price)
+        .map_each(|(quantity, price)| format!("Total: {:.2}", *quantity as f32 * price));
+
+    "Quantity"
+        .and(
+            SpinBox::new(quantity)
+                .minimum(1)
+                .maximum(99)
+                .hint("1 to 99")
+                .validations(&validations),
+        )
+        .and("Price")
+        .and(
+            SpinBox::new(price)
+                .minimum(0.)
+                .step(0.25)
+                .validations(&validations),
+        )
+        .and(total)
+        .into_rows()
+        .width(cushy::figures::units::Lp::inches(3))
+        .centered()
+}
+
+fn main() -> cushy::Result {
+    spin_box().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(spin_box).untested_still_frame();
+}
@@ -0,0 +1,40 @@
+use cushy::figures::units::Lp;
+use cushy::reactive::value::{Dynamic, MapEach};
+use cushy::widget::MakeWidget;
+use cushy::widgets::Split;
+use cushy::Run;
+
+fn split() -> impl MakeWidget {
+    let sidebar = Dynamic::new(0.25);
+    let editor = Dynamic::new(0.7);
+
+    let status = (&sidebar, &editor).map_each(|(sidebar, editor)| {
+        format!(
+            "Sidebar: {:.0}%, Editor: {:.0}%",
+            sidebar * 100.,
+            editor * 100.
+        )
+    });
+
+    Split::columns(
+        "Sidebar",
+        Split::rows("Editor", "Terminal")
+            .ratio(editor)
+            .second_size(Lp::inches(1)..),
+    )
+    .ratio(sidebar)
+    .first_size(Lp::inches(1)..Lp::inches(4))
+    .expand()
+    .and(status)
+    .into_rows()
+    .expand()
+}
+
+fn main() -> cushy::Result {
+    split().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(split).untested_still_frame();
+}
@@ -0,0 +1,74 @@
+use cushy::kludgine::Color;
+use cushy::reactive::value::{Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::rich_text::{RichText, SpanStyle};
+use cushy::widgets::TextEditor;
+use cushy::Run;
+
+const KEYWORDS: &[&str] = &["fn", "let", "mut", "pub", "struct", "impl", "use", "return"];
+
+/// A minimal highlighter that styles keywords, string literals, and comments.
+///
+/// A real application could convert the output of a crate like `syntect` into
+/// [`RichText`] in the same way.
+fn highlight(source: &str) -> RichText {
+    let keyword = SpanStyle::default().color(Color::MEDIUMPURPLE).bold();
+    let string = SpanStyle::default().color(Color::DARKSEAGREEN);
+    let comment = SpanStyle::default().color(Color::GRAY).italic();
+
+    let mut text = RichText::new(source);
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        if let Some(start) = line.find("//") {
+            text.style_range(offset + start..offset + line.len(), comment);
+        }
+
+        let mut in_string = None;
+        for (index, ch) in line.char_indices() {
+            if ch == '"' {
+                if let Some(start) = in_string.take() {
+                    text.style_range(offset + start..offset + index + 1, string);
+                } else {
+                    in_string = Some(index);
+                }
+            }
+        }
+
+        let mut word_start = None;
+        for (index, ch) in line.char_indices().chain([(line.len(), ' ')]) {
+            if ch.is_alphanumeric() || ch == '_' {
+                word_start.get_or_insert(index);
+            } else if let Some(start) = word_start.take() {
+                if KEYWORDS.contains(&&line[start..index]) {
+                    text.style_range(offset + start..offset + index, keyword);
+                }
+            }
+        }
+
+        offset += line.len();
+    }
+    text
+}
+
+fn syntax_highlight() -> impl MakeWidget {
+    let source = Dynamic::new(String::from(
+        "// Edit this code to see it highlighted.\nfn main() {\n    let mut greeting = \"Hello\";\n    println!(\"{greeting}, World!\");\n}\n",
+    ));
+    let highlighted = source.map_each(|source| highlight(source));
+
+    TextEditor::new(source)
+        .highlighted(highlighted.clone())
+        .expand()
+        .and("Preview".h5())
+        .and(highlighted)
+        .into_rows()
+}
+
+fn main() -> cushy::Result {
+    syntax_highlight().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(syntax_highlight).untested_still_frame();
+}
@@ -0,0 +1,38 @@
+use cushy::reactive::value::{Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::input::SelectionState;
+use cushy::widgets::TextEditor;
+use cushy::Run;
+
+fn text_editor() -> impl MakeWidget {
+    let notes = Dynamic::new(String::from(
+        "Cushy's TextEditor supports editing multiple lines of text.\n\nLong lines are wrapped to fit the width of the editor, and the editor scrolls to keep the cursor visible.",
+    ));
+    let selection = Dynamic::<SelectionState>::default();
+    let status = (&notes, &selection).map_each(|(notes, selection)| {
+        let lines = notes.lines().count();
+        match selection.start {
+            Some(start) if start.offset != selection.cursor.offset => format!(
+                "{lines} lines, {} bytes selected",
+                start.offset.abs_diff(selection.cursor.offset)
+            ),
+            _ => format!("{lines} lines, cursor at byte {}", selection.cursor.offset),
+        }
+    });
+
+    TextEditor::new(notes)
+        .with_selection(selection)
+        .placeholder("Notes")
+        .expand()
+        .and(status.small().align_left())
+        .into_rows()
+}
+
+fn main() -> cushy::Result {
+    text_editor().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(text_editor).untested_still_frame();
+}
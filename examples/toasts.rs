@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use cushy::reactive::value::{Destination, Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::toast::Toasts;
+use cushy::Run;
+
+fn toasts() -> impl MakeWidget {
+    let toasts = Toasts::new().max_visible(4);
+    let count = Dynamic::new(0_usize);
+
+    let info = "Info".into_button().on_click({
+        let toasts = toasts.clone();
+        let count = count.clone();
+        move |_| {
+            *count.lock() += 1;
+            toasts.show(format!("Informational toast #{}", count.get()));
+        }
+    });
+    let success = "Success".into_button().on_click({
+        let toasts = toasts.clone();
+        move |_| {
+            toasts
+                .build("The operation completed successfully.")
+                .success()
+                .timeout(Duration::from_secs(2))
+                .show();
+        }
+    });
+    let warning = "Warning".into_button().on_click({
+        let toasts = toasts.clone();
+        move |_| {
+            toasts
+                .build("Disk space is running low.")
+                .warning()
+                .persistent()
+                .show();
+        }
+    });
+    let error = "Error with Action".into_button().on_click({
+        let toasts = toasts.clone();
+        move |_| {
+            toasts
+                .build("The file could not be deleted.")
+                .error()
+                .action("Retry", || println!("Retrying"))
+                .show();
+        }
+    });
+
+    info.and(success)
+        .and(warning)
+        .and(error)
+        .into_columns()
+        .centered()
+        .expand()
+        .and(toasts)
+        .into_layers()
+}
+
+fn main() -> cushy::Result {
+    toasts().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(toasts).untested_still_frame();
+}
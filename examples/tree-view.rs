@@ -0,0 +1,59 @@
+use cushy::reactive::value::{Dynamic, Source};
+use cushy::widget::MakeWidget;
+use cushy::widgets::tree_view::{TreeNode, TreeView};
+use cushy::Run;
+
+fn file(path: &'static str) -> TreeNode<&'static str> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    TreeNode::new(path, name)
+}
+
+fn directory(
+    path: &'static str,
+    children: impl IntoIterator<Item = TreeNode<&'static str>>,
+) -> TreeNode<&'static str> {
+    file(path).with_children(children)
+}
+
+fn tree_view() -> impl MakeWidget {
+    let selected = Dynamic::new(None);
+    let tree = vec![
+        directory(
+            "src",
+            [
+                directory(
+                    "src/widgets",
+                    [
+                        file("src/widgets/button.rs"),
+                        file("src/widgets/tree_view.rs"),
+                    ],
+                )
+                .expanded(true),
+                file("src/lib.rs"),
+                file("src/main.rs"),
+            ],
+        )
+        .expanded(true),
+        directory("examples", [file("examples/tree-view.rs")]),
+        file("Cargo.toml"),
+    ];
+
+    TreeView::new(tree, selected.clone())
+        .vertical_scroll()
+        .expand()
+        .and(
+            selected
+                .map_each(|selected| format!("Selected: {}", selected.unwrap_or("None")))
+                .align_left(),
+        )
+        .into_rows()
+}
+
+fn main() -> cushy::Result {
+    tree_view().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(tree_view).untested_still_frame();
+}
@@ -0,0 +1,60 @@
+use cushy::figures::{Point, Size};
+use cushy::kludgine::app::winit::event::{ElementState, MouseButton};
+use cushy::reactive::value::Dynamic;
+use cushy::widget::MakeWidget;
+use cushy::window::{Rgb8, VirtualApp, VirtualRecorderError};
+use cushy::{Application, Open};
+
+#[macro_use]
+mod shared;
+
+fn main() -> Result<(), VirtualRecorderError> {
+    let mut app = VirtualApp::<Rgb8>::new()?;
+
+    // Windows opened using the virtual app's handle, including windows opened
+    // by widgets, become virtual windows.
+    let mut app_handle = app.as_app();
+    let main = app.open(
+        "Open Palette"
+            .into_button()
+            .on_click(move |_| {
+                let _ = "Tool Palette"
+                    .centered()
+                    .into_window()
+                    .inner_size(Dynamic::new(Size::upx(200, 150)))
+                    .open(&mut app_handle);
+            })
+            .centered()
+            .into_window()
+            .inner_size(Dynamic::new(Size::upx(320, 240))),
+    );
+    app.refresh()?;
+
+    // Click the button in the center of the main window.
+    let origin = app.position(main).expect("main window open");
+    app.cursor_moved(origin + Point::px(160, 120));
+    app.mouse_input(ElementState::Pressed, MouseButton::Left);
+    app.mouse_input(ElementState::Released, MouseButton::Left);
+    app.refresh()?;
+
+    // The palette opened above the main window and received focus.
+    let windows = app.windows();
+    assert_eq!(windows.len(), 2);
+    let palette = windows[1];
+    assert_eq!(app.focused(), Some(palette));
+
+    // Each window is captured separately.
+    app.window(main)
+        .expect("main window open")
+        .image()
+        .save("examples/virtual-app-main.png")
+        .unwrap();
+    app.window(palette)
+        .expect("palette open")
+        .image()
+        .save("examples/virtual-app-palette.png")
+        .unwrap();
+    Ok(())
+}
+
+adapter_required_test!(main);
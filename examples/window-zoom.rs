@@ -6,16 +6,24 @@ use cushy::Run;
 
 fn main() -> cushy::Result<()> {
     let zoom = Dynamic::new(Fraction::ONE);
+    let simulate_high_dpi = Dynamic::new(false);
+    let effective_scale = Dynamic::new(Fraction::ONE);
     zoom.map_each(|z| z.to_string())
         .and(
             zoom.clone()
                 .slider_between(Fraction::new(1, 4), Fraction::new(4, 1)),
         )
+        .and("Simulate a 2x Display".into_checkbox(simulate_high_dpi.clone()))
+        .and(effective_scale.map_each(|scale| format!("Effective Scale: {scale}")))
         .into_rows()
         .fit_horizontally()
         .pad()
         .expand()
         .into_window()
         .zoom(zoom)
+        .content_scale(
+            simulate_high_dpi.map_each(|simulate| simulate.then_some(Fraction::new(2, 1))),
+        )
+        .effective_scale(effective_scale)
         .run()
 }
@@ -0,0 +1,53 @@
+use cushy::figures::units::Lp;
+use cushy::figures::{Size, Zero};
+use cushy::kludgine::Color;
+use cushy::reactive::value::{Destination, Dynamic, Source};
+use cushy::styles::{Dimension, Edges};
+use cushy::widget::MakeWidget;
+use cushy::Run;
+
+fn card(
+    label: &str,
+    color: Color,
+    offset: Lp,
+    index: i32,
+    raised: &Dynamic<i32>,
+) -> impl MakeWidget {
+    let z_index = raised.map_each(move |raised| if *raised == index { 1 } else { 0 });
+    label
+        .into_button()
+        .on_click({
+            let raised = raised.clone();
+            move |_| raised.set(index)
+        })
+        .size(Size::new(Lp::inches(2), Lp::inches(1)))
+        .background_color(color)
+        .pad_by(
+            Edges::<Dimension>::default()
+                .with_left(offset)
+                .with_top(offset),
+        )
+        .align_left()
+        .align_top()
+        .z_index(z_index)
+}
+
+fn z_index() -> impl MakeWidget {
+    let raised = Dynamic::new(0);
+
+    card("Red", Color::RED, Lp::ZERO, 0, &raised)
+        .and(card("Green", Color::GREEN, Lp::points(36), 1, &raised))
+        .and(card("Blue", Color::BLUE, Lp::inches(1), 2, &raised))
+        .into_layers()
+        .size(Size::new(Lp::inches(3), Lp::inches(2)))
+        .centered()
+}
+
+fn main() -> cushy::Result {
+    z_index().run()
+}
+
+#[test]
+fn runs() {
+    cushy::example!(z_index).untested_still_frame();
+}
@@ -0,0 +1,121 @@
+use cushy::context::{AsEventContext, GraphicsContext, LayoutContext, Trackable};
+use cushy::figures::units::{Px, UPx};
+use cushy::figures::{IntoSigned, IntoUnsigned, Point, Rect, ScreenScale, Size, Zero};
+use cushy::kludgine::Color;
+use cushy::reactive::value::{IntoValue, Value};
+use cushy::styles::components::IntrinsicPadding;
+use cushy::widget::{MakeWidget, MountedChildren, Widget, WidgetList};
+use cushy::widgets::Space;
+use cushy::ConstraintLimit;
+
+// ANCHOR: shortest-column
+/// Returns the index of the shortest column, preferring the leftmost column
+/// when multiple columns have the same height.
+fn shortest_column(heights: &[Px]) -> usize {
+    heights
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, height)| **height)
+        .map_or(0, |(index, _)| index)
+}
+// ANCHOR_END: shortest-column
+
+fn custom_container() -> impl MakeWidget {
+    // ANCHOR: definition
+    #[derive(Debug)]
+    struct Masonry {
+        columns: i32,
+        children: Value<WidgetList>,
+        mounted: MountedChildren,
+    }
+
+    impl Masonry {
+        pub fn new(columns: i32, children: impl IntoValue<WidgetList>) -> Self {
+            Self {
+                columns: columns.max(1),
+                children: children.into_value(),
+                mounted: MountedChildren::default(),
+            }
+        }
+    }
+    // ANCHOR_END: definition
+
+    impl Widget for Masonry {
+        // ANCHOR: layout
+        fn layout(
+            &mut self,
+            available_space: Size<ConstraintLimit>,
+            context: &mut LayoutContext<'_, '_, '_, '_>,
+        ) -> Size<UPx> {
+            // Mount and unmount children as the list changes.
+            self.children.invalidate_when_changed(context);
+            self.mounted
+                .synchronize_with(&self.children, &mut context.as_event_context());
+
+            let gap = context.get(&IntrinsicPadding).into_px(context.gfx.scale());
+            let width = available_space.width.max().into_signed();
+            let column_width = ((width - gap * (self.columns - 1)) / self.columns).max(Px::ZERO);
+            let child_space = Size::new(
+                ConstraintLimit::Fill(column_width.into_unsigned()),
+                ConstraintLimit::SizeToFit(available_space.height.max()),
+            );
+
+            let mut heights = vec![Px::ZERO; self.columns as usize];
+            for child in self.mounted.children() {
+                // Measure the child, then place it at the bottom of the
+                // shortest column.
+                let size = context.for_other(child).layout(child_space).into_signed();
+                let column = shortest_column(&heights);
+                let origin = Point::new((column_width + gap) * column as i32, heights[column]);
+                context.set_child_layout(
+                    child,
+                    Rect::new(origin, Size::new(column_width, size.height)),
+                );
+                heights[column] = origin.y + size.height + gap;
+            }
+
+            let height = heights
+                .into_iter()
+                .max()
+                .map_or(Px::ZERO, |height| (height - gap).max(Px::ZERO));
+            Size::new(width, height).into_unsigned()
+        }
+        // ANCHOR_END: layout
+
+        // ANCHOR: redraw
+        fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+            context.redraw_children(self.mounted.children());
+        }
+        // ANCHOR_END: redraw
+    }
+
+    let tiles = [
+        (Color::RED, 80),
+        (Color::ORANGE, 40),
+        (Color::GOLD, 120),
+        (Color::GREEN, 60),
+        (Color::TEAL, 100),
+        (Color::BLUE, 40),
+        (Color::PURPLE, 80),
+        (Color::PINK, 60),
+    ]
+    .into_iter()
+    .map(|(color, height)| Space::colored(color).height(Px::new(height)))
+    .collect::<WidgetList>();
+    Masonry::new(3, tiles)
+}
+
+fn main() {
+    cushy::example!(custom_container).untested_still_frame();
+}
+
+#[test]
+fn runs() {
+    main();
+}
+
+#[test]
+fn places_in_shortest_column() {
+    assert_eq!(shortest_column(&[Px::new(10), Px::new(5), Px::new(5)]), 1);
+    assert_eq!(shortest_column(&[Px::ZERO; 3]), 0);
+}
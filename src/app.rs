@@ -9,11 +9,12 @@ use kludgine::app::winit::error::EventLoopError;
 use kludgine::app::{AppEvent, AsApplication, ExecutingApp, Monitors, UnrecoverableError};
 use parking_lot::{Mutex, MutexGuard};
 
+use crate::clipboard::{self, ClipboardChanges};
 use crate::fonts::FontCollection;
 #[cfg(feature = "localization")]
 use crate::localization::Localizations;
 use crate::window::sealed::WindowCommand;
-use crate::window::WindowHandle;
+use crate::window::{ScrollSource, VirtualWindowRequests, WindowHandle, WindowsRegistry};
 use crate::{animation, initialize_tracing};
 
 /// A Cushy application that has not started running yet.
@@ -158,7 +159,10 @@ impl Run for PendingApp {
     fn run(self) -> crate::Result {
         let _guard = self.cushy.enter_runtime();
         animation::spawn(self.cushy.clone());
-        self.app.run()
+        let result = self.app.run();
+        #[cfg(feature = "serde")]
+        crate::reactive::persist::flush();
+        result
     }
 }
 
@@ -364,6 +368,23 @@ impl<T> BoxableGuard<'_> for T {}
 
 struct AppSettings {
     multi_click_threshold: Duration,
+    scroll_multipliers: ScrollMultipliers,
+}
+
+struct ScrollMultipliers {
+    wheel: f32,
+    touchpad: f32,
+    touch: f32,
+}
+
+impl ScrollMultipliers {
+    fn get_mut(&mut self, source: ScrollSource) -> &mut f32 {
+        match source {
+            ScrollSource::Wheel => &mut self.wheel,
+            ScrollSource::Touchpad => &mut self.touchpad,
+            ScrollSource::Touch => &mut self.touch,
+        }
+    }
 }
 
 static RUNNING_CUSHY: Mutex<Option<Cushy>> = const { Mutex::new(None) };
@@ -393,10 +414,22 @@ impl Cushy {
                 clipboard: Clipboard::new()
                     .ok()
                     .map(|clipboard| Arc::new(Mutex::new(clipboard))),
+                clipboard_changes: ClipboardChanges::default(),
                 fonts: FontCollection::default(),
                 settings: Mutex::new(AppSettings {
                     multi_click_threshold: Duration::from_millis(500),
+                    scroll_multipliers: ScrollMultipliers {
+                        wheel: 1.,
+                        touchpad: 1.,
+                        touch: 1.,
+                    },
                 }),
+                windows: WindowsRegistry::default(),
+                #[cfg(all(
+                    feature = "global-hotkeys",
+                    any(target_os = "windows", target_os = "macos", target_os = "linux")
+                ))]
+                global_hotkeys: crate::global_hotkeys::HotkeyCallbacks::default(),
                 #[cfg(feature = "localization")]
                 localizations: Localizations::default(),
             }),
@@ -441,6 +474,24 @@ impl Cushy {
         self.data.settings.lock().multi_click_threshold = threshold;
     }
 
+    /// Returns the amount scroll events from `source` are multiplied by before
+    /// they are delivered to widgets.
+    ///
+    /// The default multiplier for all sources is `1.0`.
+    #[must_use]
+    pub fn scroll_multiplier(&self, source: ScrollSource) -> f32 {
+        *self.data.settings.lock().scroll_multipliers.get_mut(source)
+    }
+
+    /// Sets the amount scroll events from `source` are multiplied by before
+    /// they are delivered to widgets.
+    ///
+    /// This allows users to adjust the scrolling speed of each kind of
+    /// device. Negative multipliers invert the scroll direction.
+    pub fn set_scroll_multiplier(&self, source: ScrollSource, multiplier: f32) {
+        *self.data.settings.lock().scroll_multipliers.get_mut(source) = multiplier;
+    }
+
     /// Returns a locked mutex guard to the OS's clipboard, if one was able to be
     /// initialized when the window opened.
     #[must_use]
@@ -448,6 +499,12 @@ impl Cushy {
         self.data.clipboard.as_ref().map(|mutex| mutex.lock())
     }
 
+    /// Returns a handle to the OS's clipboard.
+    #[must_use]
+    pub fn clipboard(&self) -> clipboard::Clipboard {
+        clipboard::Clipboard::new(self.clone())
+    }
+
     /// Returns the font collection that will be loaded in all Cushy windows.
     #[must_use]
     pub fn fonts(&self) -> &FontCollection {
@@ -481,8 +538,15 @@ impl Default for Cushy {
 
 pub(crate) struct CushyData {
     pub(crate) clipboard: Option<Arc<Mutex<Clipboard>>>,
+    pub(crate) clipboard_changes: ClipboardChanges,
     pub(crate) fonts: FontCollection,
     settings: Mutex<AppSettings>,
+    pub(crate) windows: WindowsRegistry,
+    #[cfg(all(
+        feature = "global-hotkeys",
+        any(target_os = "windows", target_os = "macos", target_os = "linux")
+    ))]
+    pub(crate) global_hotkeys: crate::global_hotkeys::HotkeyCallbacks,
     #[cfg(feature = "localization")]
     pub(crate) localizations: Localizations,
 }
@@ -504,6 +568,7 @@ impl Application for PendingApp {
         App {
             app: Some(self.app.as_app()),
             cushy: self.cushy.clone(),
+            virtual_windows: None,
         }
     }
 }
@@ -513,6 +578,7 @@ impl Application for PendingApp {
 pub struct App {
     app: Option<kludgine::app::App<WindowCommand>>,
     cushy: Cushy,
+    virtual_windows: Option<VirtualWindowRequests>,
 }
 
 impl App {
@@ -520,18 +586,62 @@ impl App {
         Self {
             app: None,
             cushy: Cushy::current(),
+            virtual_windows: None,
         }
     }
 
+    /// Returns an app whose windows are opened as virtual windows by a
+    /// [`VirtualApp`](crate::window::VirtualApp).
+    pub(crate) fn virtual_app(requests: VirtualWindowRequests) -> Self {
+        Self {
+            app: None,
+            cushy: Cushy::current(),
+            virtual_windows: Some(requests),
+        }
+    }
+
+    /// Returns the queue of windows waiting to be opened by this app's
+    /// [`VirtualApp`](crate::window::VirtualApp), if this app is virtual.
+    pub(crate) const fn virtual_windows(&self) -> Option<&VirtualWindowRequests> {
+        self.virtual_windows.as_ref()
+    }
+
     /// Returns a snapshot of information about the monitors connected to this
     /// device.
     ///
+    /// Each [`Monitor`](kludgine::app::Monitor) lists its supported video
+    /// modes, which can be used with
+    /// [`Fullscreen::Exclusive`](kludgine::app::winit::window::Fullscreen::Exclusive)
+    /// to change a monitor's resolution while a window is fullscreen.
+    ///
     /// Returns None if the app is not currently running.
     #[must_use]
     pub fn monitors(&self) -> Option<Monitors> {
         self.app.as_ref().and_then(kludgine::app::App::monitors)
     }
 
+    /// Returns the registry of this app's open windows.
+    ///
+    /// See [`WindowsRegistry`] for how to coordinate multiple windows.
+    #[must_use]
+    pub fn windows(&self) -> WindowsRegistry {
+        self.cushy.data.windows.clone()
+    }
+
+    /// Returns the global hotkeys registry for this app.
+    ///
+    /// Global hotkeys are handled even while none of this app's windows are
+    /// focused. See [`GlobalHotkeys`](crate::global_hotkeys::GlobalHotkeys)
+    /// for more information.
+    #[cfg(all(
+        feature = "global-hotkeys",
+        any(target_os = "windows", target_os = "macos", target_os = "linux")
+    ))]
+    #[must_use]
+    pub fn global_hotkeys(&self) -> crate::global_hotkeys::GlobalHotkeys {
+        crate::global_hotkeys::GlobalHotkeys::new(self.clone())
+    }
+
     /// Creates a guard that prevents this app from shutting down.
     ///
     /// If the app is not currently running, this function returns None.
@@ -0,0 +1,248 @@
+//! Access to the operating system's clipboard.
+//!
+//! The [`Clipboard`] type supports reading and writing text and images. It can
+//! be obtained from [`Cushy::clipboard()`], which is accessible from any
+//! widget context using `context.cushy().clipboard()`.
+
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub use arboard::Error;
+use arboard::ImageData;
+use image::RgbaImage;
+use parking_lot::Mutex;
+
+use crate::reactive::value::{Dynamic, DynamicReader};
+use crate::Cushy;
+
+/// A handle to the operating system's clipboard.
+///
+/// All functions return [`Error::ClipboardNotSupported`] if the clipboard
+/// could not be initialized when the application started.
+///
+/// ```rust
+/// use cushy::Cushy;
+///
+/// let clipboard = Cushy::current().clipboard();
+/// if clipboard.set_text("Hello, World!").is_ok() {
+///     println!("{:?}", clipboard.get_text());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Clipboard {
+    cushy: Cushy,
+}
+
+impl Clipboard {
+    pub(crate) const fn new(cushy: Cushy) -> Self {
+        Self { cushy }
+    }
+
+    fn with_clipboard<R>(
+        &self,
+        f: impl FnOnce(&mut arboard::Clipboard) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let mut clipboard = self
+            .cushy
+            .clipboard_guard()
+            .ok_or(Error::ClipboardNotSupported)?;
+        f(&mut clipboard)
+    }
+
+    /// Returns the text currently stored on the clipboard.
+    ///
+    /// Returns [`Error::ContentNotAvailable`] if the clipboard does not
+    /// contain text.
+    pub fn get_text(&self) -> Result<String, Error> {
+        self.with_clipboard(arboard::Clipboard::get_text)
+    }
+
+    /// Replaces the contents of the clipboard with `text`.
+    pub fn set_text(&self, text: impl Into<String>) -> Result<(), Error> {
+        let text = text.into();
+        let fingerprint = Fingerprint::of(Some(&text), None);
+        self.with_clipboard(|clipboard| clipboard.set_text(text))?;
+        self.cushy.data.clipboard_changes.changed(fingerprint);
+        Ok(())
+    }
+
+    /// Returns the image currently stored on the clipboard.
+    ///
+    /// Returns [`Error::ContentNotAvailable`] if the clipboard does not
+    /// contain an image.
+    pub fn get_image(&self) -> Result<RgbaImage, Error> {
+        let image = self.with_clipboard(arboard::Clipboard::get_image)?;
+        rgba_image(image)
+    }
+
+    /// Replaces the contents of the clipboard with `image`.
+    pub fn set_image(&self, image: RgbaImage) -> Result<(), Error> {
+        let width = usize::try_from(image.width()).map_err(|_| Error::ConversionFailure)?;
+        let height = usize::try_from(image.height()).map_err(|_| Error::ConversionFailure)?;
+        let fingerprint = Fingerprint::of(None, Some((width, height, image.as_raw())));
+        let image = ImageData {
+            width,
+            height,
+            bytes: Cow::Owned(image.into_raw()),
+        };
+        self.with_clipboard(|clipboard| clipboard.set_image(image))?;
+        self.cushy.data.clipboard_changes.changed(fingerprint);
+        Ok(())
+    }
+
+    /// Removes all contents from the clipboard.
+    pub fn clear(&self) -> Result<(), Error> {
+        self.with_clipboard(arboard::Clipboard::clear)?;
+        self.cushy
+            .data
+            .clipboard_changes
+            .changed(Fingerprint::of(None, None));
+        Ok(())
+    }
+
+    /// Returns a reader that is updated each time the clipboard's contents
+    /// change.
+    ///
+    /// The contained value is the number of changes that have been observed.
+    /// Changes made using this type are always observed. The operating system
+    /// does not notify applications when other applications change the
+    /// clipboard, so Cushy checks the contents of the clipboard for changes
+    /// when any of its windows gain focus, as long as this function has been
+    /// called at least once. [`Self::refresh()`] can be used to check for
+    /// changes at other times.
+    #[must_use]
+    pub fn changes(&self) -> DynamicReader<usize> {
+        let changes = &self.cushy.data.clipboard_changes;
+        if !changes.observed.swap(true, Ordering::Relaxed) {
+            self.refresh();
+        }
+        changes.generation.create_reader()
+    }
+
+    /// Checks whether the contents of the clipboard have changed since they
+    /// were last observed, updating [`Self::changes()`] if they have.
+    pub fn refresh(&self) {
+        let _result = self.with_clipboard(|clipboard| {
+            let text = clipboard.get_text().ok();
+            let image = clipboard.get_image().ok();
+            self.cushy.data.clipboard_changes.refreshed(
+                text.as_deref(),
+                image
+                    .as_ref()
+                    .map(|image| (image.width, image.height, &*image.bytes)),
+            );
+            Ok(())
+        });
+    }
+}
+
+fn rgba_image(image: ImageData<'_>) -> Result<RgbaImage, Error> {
+    let width = u32::try_from(image.width).map_err(|_| Error::ConversionFailure)?;
+    let height = u32::try_from(image.height).map_err(|_| Error::ConversionFailure)?;
+    RgbaImage::from_raw(width, height, image.bytes.into_owned()).ok_or(Error::ConversionFailure)
+}
+
+/// An image's width, height, and RGBA bytes.
+type ImageContents<'a> = (usize, usize, &'a [u8]);
+
+/// The lengths of the clipboard's contents, which can be compared without
+/// reading the contents.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Metadata {
+    text: Option<usize>,
+    image: Option<(usize, usize, usize)>,
+}
+
+impl Metadata {
+    fn of(text: Option<&str>, image: Option<ImageContents<'_>>) -> Self {
+        Self {
+            text: text.map(str::len),
+            image: image.map(|(width, height, bytes)| (width, height, bytes.len())),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Fingerprint {
+    metadata: Metadata,
+    hash: u64,
+}
+
+impl Fingerprint {
+    fn of(text: Option<&str>, image: Option<ImageContents<'_>>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        image.map(|(_, _, bytes)| bytes).hash(&mut hasher);
+        Self {
+            metadata: Metadata::of(text, image),
+            hash: hasher.finish(),
+        }
+    }
+}
+
+/// Tracks changes to the clipboard's contents.
+#[derive(Default)]
+pub(crate) struct ClipboardChanges {
+    generation: Dynamic<usize>,
+    fingerprint: Mutex<Option<Fingerprint>>,
+    observed: AtomicBool,
+}
+
+impl ClipboardChanges {
+    fn changed(&self, fingerprint: Fingerprint) {
+        let mut last = self.fingerprint.lock();
+        if last.replace(fingerprint) != Some(fingerprint) {
+            drop(last);
+            *self.generation.lock() += 1;
+        }
+    }
+
+    /// Records the clipboard's current contents, only hashing them when their
+    /// lengths match the last observed contents.
+    fn refreshed(&self, text: Option<&str>, image: Option<ImageContents<'_>>) {
+        let mut last = self.fingerprint.lock();
+        let metadata = Metadata::of(text, image);
+        if last.is_some_and(|last| last.metadata == metadata) {
+            let current = Fingerprint::of(text, image);
+            if last.replace(current) != Some(current) {
+                drop(last);
+                *self.generation.lock() += 1;
+            }
+        } else {
+            *last = Some(Fingerprint::of(text, image));
+            drop(last);
+            *self.generation.lock() += 1;
+        }
+    }
+
+    pub(crate) fn is_observed(&self) -> bool {
+        self.observed.load(Ordering::Relaxed)
+    }
+}
+
+#[test]
+fn refresh_detects_changes() {
+    use crate::reactive::value::Source;
+
+    let changes = ClipboardChanges::default();
+    changes.refreshed(Some("hello"), None);
+    assert_eq!(changes.generation.get(), 1);
+    changes.refreshed(Some("hello"), None);
+    assert_eq!(changes.generation.get(), 1);
+    // Same length, different contents.
+    changes.refreshed(Some("world"), None);
+    assert_eq!(changes.generation.get(), 2);
+    changes.refreshed(Some("world!"), None);
+    assert_eq!(changes.generation.get(), 3);
+    changes.refreshed(None, Some((1, 1, &[0, 0, 0, 255])));
+    assert_eq!(changes.generation.get(), 4);
+    changes.refreshed(None, Some((1, 1, &[0, 0, 0, 255])));
+    assert_eq!(changes.generation.get(), 4);
+    // Setting contents records them without reading them back.
+    changes.changed(Fingerprint::of(Some("set"), None));
+    assert_eq!(changes.generation.get(), 5);
+    changes.refreshed(Some("set"), None);
+    assert_eq!(changes.generation.get(), 5);
+}
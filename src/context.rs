@@ -1,32 +1,41 @@
 //! Types that provide access to the Cushy runtime.
 use std::borrow::Cow;
+use std::mem;
 use std::ops::{Deref, DerefMut};
 
 use figures::units::{Lp, Px, UPx};
-use figures::{IntoSigned, Point, Rect, Round, ScreenScale, Size, Zero};
+use figures::{IntoSigned, IntoUnsigned, Point, Rect, Round, ScreenScale, Size, Zero};
 use kludgine::app::winit::event::{Ime, MouseButton, MouseScrollDelta, TouchPhase};
 use kludgine::app::winit::window::Cursor;
-use kludgine::cosmic_text::{FamilyOwned, Style, Weight};
-use kludgine::shapes::{Shape, StrokeOptions};
+use kludgine::cosmic_text::{self, FamilyOwned, Style, Weight};
+use kludgine::shapes::{CornerRadii, Shape, StrokeOptions};
+use kludgine::text::Text;
 use kludgine::{Color, Kludgine, KludgineId};
 #[cfg(feature = "localization")]
 use unic_langid::LanguageIdentifier;
 
 use crate::animation::ZeroToOne;
-use crate::fonts::{LoadedFont, LoadedFontFace};
-use crate::graphics::{FontState, Graphics};
+use crate::fonts::{FontFaceInfo, LoadedFont, LoadedFontFace};
+use crate::graphics::{Brush, FontState, Graphics, Path};
 #[cfg(feature = "localization")]
 use crate::localization::Localizations;
 use crate::reactive::value::{IntoValue, Source, Value};
 use crate::styles::components::{
-    CornerRadius, FontFamily, FontStyle, FontWeight, HighlightColor, LayoutOrder, LineHeight,
-    Opacity, OutlineWidth, TextSize, WidgetBackground,
+    BoxShadow, CornerRadius, FontFamily, FontStyle, FontWeight, HighlightColor, LayoutOrder,
+    LineHeight, MaxSize, MinSize, Opacity, OutlineWidth, Overflow, TextSize, WidgetBackground,
+    WidgetBackgroundGradient, ZIndex,
+};
+use crate::styles::{
+    AppliedComponent, ComponentDefinition, Dimension, FontFamilyList, OverflowPolicy,
+    ResolvedComponent, StyleSource, Styles, Theme, ThemePair,
 };
-use crate::styles::{ComponentDefinition, Dimension, FontFamilyList, Styles, Theme, ThemePair};
 use crate::tree::Tree;
 use crate::widget::{EventHandling, MountedWidget, RootBehavior, WidgetId, WidgetInstance};
+use crate::widgets::container::{render_shadow, ContainerShadow};
+use crate::widgets::scroll::ScrollBehavior;
 use crate::window::{
-    CursorState, DeviceId, KeyEvent, PlatformWindow, ThemeMode, WidgetCursorState,
+    Announcement, CursorState, DeviceId, Gesture, KeyEvent, PlatformWindow, Politeness,
+    ScrollSource, ThemeMode, WidgetCursorState,
 };
 use crate::ConstraintLimit;
 
@@ -158,19 +167,48 @@ impl<'context> EventContext<'context> {
             .mouse_wheel(device_id, delta, phase, self)
     }
 
+    /// Invokes [`Widget::gesture()`](crate::widget::Widget::gesture) on this
+    /// context's widget and returns the result.
+    pub fn gesture(&mut self, gesture: Gesture) -> EventHandling {
+        self.current_node
+            .clone()
+            .lock()
+            .as_widget()
+            .gesture(gesture, self)
+    }
+
+    /// Returns the kind of device that produced the most recent scroll event.
+    ///
+    /// While handling
+    /// [`Widget::mouse_wheel()`](crate::widget::Widget::mouse_wheel), this is
+    /// the source of the event being handled.
+    #[must_use]
+    pub fn scroll_source(&self) -> ScrollSource {
+        self.cursor.scroll_source
+    }
+
+    /// Sets the portion of the scroll event being handled that this widget did
+    /// not use.
+    ///
+    /// When [`Widget::mouse_wheel()`](crate::widget::Widget::mouse_wheel)
+    /// returns [`HANDLED`](crate::widget::HANDLED) after this function is
+    /// called, `remaining` is delivered to this widget's parents. This allows
+    /// a widget to handle scrolling on one axis while its parents handle the
+    /// other axis.
+    pub fn set_unhandled_scroll(&mut self, remaining: MouseScrollDelta) {
+        self.cursor.unhandled_scroll = Some(remaining);
+    }
+
     pub(crate) fn hover(&mut self, location: Point<Px>) {
         let changes = self.tree.hover(Some(&self.current_node));
 
         let mut cursor = None;
         for hover in changes.hovered.into_iter().rev() {
             let mut context = self.for_other(&hover);
-            let Some(last_layout) = context.last_layout() else {
+            let Some(relative) = context.window_to_widget(location) else {
                 continue;
             };
-            let widget_cursor = hover
-                .lock()
-                .as_widget()
-                .hover(location - last_layout.origin, &mut context);
+            let widget_cursor = hover.lock().as_widget().hover(relative, &mut context);
 
             if cursor.is_none() {
                 cursor = widget_cursor;
@@ -237,6 +275,18 @@ impl<'context> EventContext<'context> {
     }
 
     fn apply_pending_focus(&mut self) {
+        // When the focused widget has been removed or the window has been
+        // reactivated, return focus to the most recently focused widget.
+        let restore = self.tree.take_focus_to_restore();
+        if let Some(restore) = restore.filter(|_| {
+            self.pending_state
+                .focus
+                .map_or(true, |focus| self.tree.widget(focus).is_none())
+        }) {
+            self.pending_state.focus = Some(restore);
+            self.pending_state.focus_is_advancing = true;
+        }
+
         let mut focus_changes = 0;
         while focus_changes < Self::MAX_PENDING_CHANGE_CYCLES {
             let focus = self.pending_state.focus.and_then(|w| self.tree.widget(w));
@@ -318,10 +368,9 @@ impl<'context> EventContext<'context> {
         if let Some(location) = self.cursor.location {
             for widget in self.tree.widgets_under_point(location) {
                 let mut widget_context = self.for_other(&widget);
-                let Some(widget_layout) = widget_context.last_layout() else {
+                let Some(relative) = widget_context.window_to_widget(location) else {
                     continue;
                 };
-                let relative = location - widget_layout.origin;
                 let widget_hover = Some(WidgetCursorState {
                     id: widget.id(),
                     last_hovered: location,
@@ -347,6 +396,10 @@ impl<'context> EventContext<'context> {
         // First, look within the current focus for any focusable children if we
         // are advancing the focus. If we are reversing, we will wait to
         // consider children as part of the reverse scan.
+        if let Some(ordered) = self.ordered_focus_after(&focus, advance) {
+            return Some(ordered);
+        }
+
         let stop_at = focus.id();
         if advance {
             if let Some(focus) = self.next_focus_within(&focus, None, stop_at, advance) {
@@ -354,8 +407,12 @@ impl<'context> EventContext<'context> {
             }
         }
 
-        // Now, look for the next widget in each hierarchy
+        // Now, look for the next widget in each hierarchy. Widgets that trap
+        // focus are treated as the root of the search.
         let root = loop {
+            if focus.traps_focus() {
+                break focus;
+            }
             if let Some(focus) = self.next_focus_sibling(&focus, stop_at, advance) {
                 return Some(focus);
             }
@@ -364,12 +421,7 @@ impl<'context> EventContext<'context> {
             };
             // If we're reversing focus, we need to consider the parent itself
             // as a focus target.
-            let accept_focus = !advance
-                && parent
-                    .lock()
-                    .as_widget()
-                    .accept_focus(&mut self.for_other(&parent));
-            if accept_focus {
+            if !advance && self.accepts_tab_focus(&parent) {
                 return Some(parent.id());
             }
             focus = parent;
@@ -377,10 +429,7 @@ impl<'context> EventContext<'context> {
 
         // We've exhausted a forward scan, we can now start searching the final
         // parent, which is the root.
-        let mut child_context = self.for_other(&root);
-        let accept_focus = root.lock().as_widget().accept_focus(&mut child_context);
-        drop(child_context);
-        if accept_focus {
+        if self.accepts_tab_focus(&root) {
             Some(root.id())
         } else if stop_at == root.id() {
             // We cycled completely.
@@ -437,12 +486,7 @@ impl<'context> EventContext<'context> {
         // before evaluating the children's children, but when reversing this is
         // done after evaluating the children's children.
         for child in children {
-            let accept_focus = advance
-                && child
-                    .lock()
-                    .as_widget()
-                    .accept_focus(&mut self.for_other(&child));
-            if accept_focus {
+            if advance && self.accepts_tab_focus(&child) {
                 return Some(child.id());
             } else if stop_at == child.id() && advance {
                 // We cycled completely, and the original widget didn't accept
@@ -459,11 +503,7 @@ impl<'context> EventContext<'context> {
                 // focusing this child.
                 if stop_at == child.id() {
                     return None;
-                } else if child
-                    .lock()
-                    .as_widget()
-                    .accept_focus(&mut self.for_other(&child))
-                {
+                } else if self.accepts_tab_focus(&child) {
                     return Some(child.id());
                 }
             }
@@ -472,6 +512,72 @@ impl<'context> EventContext<'context> {
         None
     }
 
+    /// Returns true if `widget` can be focused using keyboard focus
+    /// navigation.
+    fn accepts_tab_focus(&mut self, widget: &MountedWidget) -> bool {
+        !widget.instance().skips_focus()
+            && widget
+                .lock()
+                .as_widget()
+                .accept_focus(&mut self.for_other(widget))
+    }
+
+    /// Returns the next widget to focus after `focus` when widgets within its
+    /// focus root have an explicit focus order.
+    ///
+    /// Returns `None` if no explicit order applies, in which case the focus
+    /// should move in the default order.
+    fn ordered_focus_after(&mut self, focus: &MountedWidget, advance: bool) -> Option<WidgetId> {
+        let mut root = focus.clone();
+        while !root.traps_focus() {
+            let Some(parent) = root.parent() else {
+                break;
+            };
+            root = parent;
+        }
+        if !self.tree.has_focus_order_within(root.node_id) {
+            return None;
+        }
+
+        let mut ring = Vec::new();
+        if self.accepts_tab_focus(&root) {
+            ring.push(root.clone());
+        }
+        self.collect_focus_ring(&root, &mut ring);
+        // Sorting is stable, keeping widgets with the same order in their
+        // default order.
+        ring.sort_by_key(|widget| {
+            let order = widget.instance().focus_order();
+            (order.is_none(), order)
+        });
+
+        let index = ring.iter().position(|widget| widget.id() == focus.id())?;
+        let next = if advance {
+            (index + 1) % ring.len()
+        } else {
+            (index + ring.len() - 1) % ring.len()
+        };
+        Some(ring[next].id())
+    }
+
+    /// Pushes all visible widgets within `parent` that accept keyboard focus
+    /// onto `ring` in their default focus order.
+    fn collect_focus_ring(&mut self, parent: &MountedWidget, ring: &mut Vec<MountedWidget>) {
+        let order = self.get(&LayoutOrder);
+        for child in parent.visually_ordered_children(order) {
+            let visible = child.last_layout().map_or(false, |layout| {
+                layout.size.width > 0 && layout.size.height > 0
+            });
+            if !visible {
+                continue;
+            }
+            if self.accepts_tab_focus(&child) {
+                ring.push(child.clone());
+            }
+            self.collect_focus_ring(&child, ring);
+        }
+    }
+
     /// Advances the focus to the next widget after this widget in the
     /// configured focus order.
     ///
@@ -513,6 +619,62 @@ impl<'context> EventContext<'context> {
         self.pending_state.focus_is_advancing = advance;
     }
 
+    /// Moves the focus to the nearest focusable widget in `direction` from
+    /// this widget. Returns true if a widget was found.
+    ///
+    /// If a neighbor was assigned for `direction` using
+    /// [`MakeWidget::with_focus_neighbor()`](crate::widget::MakeWidget::with_focus_neighbor),
+    /// it is focused. Otherwise, the widgets' last layouts are used to find the
+    /// nearest widget that accepts keyboard focus within the window or the
+    /// nearest widget that traps focus.
+    pub fn focus_in_direction(&mut self, direction: FocusDirection) -> bool {
+        if let Some(neighbor) = self
+            .current_node
+            .instance()
+            .focus_neighbor(direction)
+            .and_then(|neighbor| self.tree.widget(neighbor))
+        {
+            self.for_other(&neighbor).focus();
+            return true;
+        }
+
+        let Some(origin) = self.current_node.last_layout() else {
+            return false;
+        };
+        let mut root = self.current_node.clone();
+        while !root.traps_focus() {
+            let Some(parent) = root.parent() else {
+                break;
+            };
+            root = parent;
+        }
+
+        let mut candidates = Vec::new();
+        self.collect_focus_ring(&root, &mut candidates);
+        let mut nearest: Option<(Px, MountedWidget)> = None;
+        for candidate in candidates {
+            if candidate.id() == self.current_node.id() {
+                continue;
+            }
+            let Some(distance) = candidate
+                .last_layout()
+                .and_then(|layout| spatial_distance(origin, layout, direction))
+            else {
+                continue;
+            };
+            if nearest.as_ref().is_some_and(|(best, _)| *best <= distance) {
+                continue;
+            }
+            nearest = Some((distance, candidate));
+        }
+
+        let Some((_, nearest)) = nearest else {
+            return false;
+        };
+        self.for_other(&nearest).focus();
+        true
+    }
+
     /// Invokes
     /// [`Widget::root_behavior()`](crate::widget::Widget::root_behavior) on
     /// this context's widget and returns the result.
@@ -525,6 +687,68 @@ impl<'context> EventContext<'context> {
     }
 }
 
+/// A direction to move keyboard focus in.
+///
+/// See [`EventContext::focus_in_direction()`] for more information.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FocusDirection {
+    /// Towards the top of the window.
+    Up,
+    /// Towards the bottom of the window.
+    Down,
+    /// Towards the left side of the window.
+    Left,
+    /// Towards the right side of the window.
+    Right,
+}
+
+impl FocusDirection {
+    const fn is_horizontal(self) -> bool {
+        matches!(self, FocusDirection::Left | FocusDirection::Right)
+    }
+
+    const fn is_forward(self) -> bool {
+        matches!(self, FocusDirection::Right | FocusDirection::Down)
+    }
+}
+
+/// Returns how far `candidate` is from `origin` when moving in `direction`, or
+/// `None` if `candidate` is not in `direction`.
+fn spatial_distance(
+    origin: Rect<Px>,
+    candidate: Rect<Px>,
+    direction: FocusDirection,
+) -> Option<Px> {
+    // Measure along the direction of travel and across it.
+    let axes = |rect: Rect<Px>| {
+        let (start, end) = rect.extents();
+        if direction.is_horizontal() {
+            (start.x, end.x, start.y, end.y)
+        } else {
+            (start.y, end.y, start.x, end.x)
+        }
+    };
+    let (origin_start, origin_end, origin_cross_start, origin_cross_end) = axes(origin);
+    let (start, end, cross_start, cross_end) = axes(candidate);
+
+    // Candidates must be centered beyond the origin's center.
+    let gap = if direction.is_forward() {
+        if start + end <= origin_start + origin_end {
+            return None;
+        }
+        start - origin_end
+    } else {
+        if start + end >= origin_start + origin_end {
+            return None;
+        }
+        origin_start - end
+    };
+    // Candidates that do not line up with the origin are penalized, which
+    // favors moving in a straight line.
+    let misalignment = cross_start.max(origin_cross_start) - cross_end.min(origin_cross_end);
+    Some(gap.max(Px::ZERO) + misalignment.max(Px::ZERO) * 2)
+}
+
 impl<'context> Deref for EventContext<'context> {
     type Target = WidgetContext<'context>;
 
@@ -596,22 +820,64 @@ impl<'clip, 'gfx, 'pass> GraphicsContext<'_, 'clip, 'gfx, 'pass> {
         Widget: ManageWidget,
         Widget::Managed: MapManagedWidget<GraphicsContext<'child, 'child, 'gfx, 'pass>>,
     {
-        let opacity = self.get(&Opacity);
-        widget.manage(self).map(|widget| {
-            let widget = self.widget.for_other(&widget);
-            let layout = widget.last_layout().map_or_else(
-                || Rect::from(self.gfx.clip_rect().size).into_signed(),
-                |rect| rect - self.gfx.region().origin,
-            );
-            let mut gfx = self.gfx.clipped_to(layout);
-            gfx.opacity *= opacity;
+        widget.manage(self).map(|managed| {
+            let (layout, shadow) = {
+                let widget = self.widget.for_other(&managed);
+                let scale = self.gfx.scale();
+                let layout = widget.last_layout().map_or_else(
+                    || Rect::from(self.gfx.clip_rect().size).into_signed(),
+                    |rect| rect - self.gfx.region().origin,
+                );
+                let shadow = widget.last_layout().and_then(|_| {
+                    let shadow = widget.get(&BoxShadow).into_px(scale).ceil();
+                    (shadow.offset != Point::ZERO || shadow.blur_radius > 0 || shadow.spread > 0)
+                        .then(|| {
+                            (
+                                shadow,
+                                widget.get(&CornerRadius).into_px(scale).ceil(),
+                                widget.get(&Opacity),
+                            )
+                        })
+                });
+                (layout, shadow)
+            };
+            if let Some((shadow, radii, opacity)) = shadow {
+                self.redraw_box_shadow(layout, radii, &shadow, opacity);
+            }
+
+            let widget = self.widget.for_other(&managed);
             GraphicsContext {
                 widget,
-                gfx: Exclusive::Owned(gfx),
+                gfx: Exclusive::Owned(self.gfx.clipped_to(layout)),
             }
         })
     }
 
+    /// Draws `shadow` behind a widget occupying `area`.
+    fn redraw_box_shadow(
+        &mut self,
+        area: Rect<Px>,
+        radii: CornerRadii<Px>,
+        shadow: &ContainerShadow<Px>,
+        opacity: ZeroToOne,
+    ) {
+        // The shadow is rendered relative to the top-left of the area
+        // containing both the widget and its shadow.
+        let leading = shadow.offset.min(Point::ZERO).abs() + shadow.spread;
+        let shadow_area = Rect::new(
+            area.origin - leading,
+            area.size + shadow.offset.abs() + shadow.spread * 2,
+        );
+        let content = Rect::new(leading, area.size);
+        render_shadow(
+            &content,
+            radii,
+            shadow,
+            Color::WHITE.with_alpha_f32(*opacity),
+            &mut self.clipped_to(shadow_area),
+        );
+    }
+
     /// Sets the current font family.
     pub fn set_font_family(&mut self, family: FamilyOwned) {
         self.font_state.current_font_family = None;
@@ -632,6 +898,17 @@ impl<'clip, 'gfx, 'pass> GraphicsContext<'_, 'clip, 'gfx, 'pass> {
         self.font_state.find_available_font_family(list)
     }
 
+    /// Returns information about every font face available to render text,
+    /// including faces provided by the operating system.
+    ///
+    /// Measuring each face requires loading its data, which can be slow when
+    /// many system fonts are installed. This function is intended for tools
+    /// such as inspectors and should not be called every frame.
+    #[must_use]
+    pub fn font_faces(&mut self) -> Vec<FontFaceInfo> {
+        self.widget.font_state.font_faces(self.gfx.font_system())
+    }
+
     /// Sets the font family to the first family in `list`.
     pub fn set_available_font_family(&mut self, list: &FontFamilyList) {
         if self.font_state.current_font_family.as_ref() != Some(list) {
@@ -657,23 +934,30 @@ impl<'clip, 'gfx, 'pass> GraphicsContext<'_, 'clip, 'gfx, 'pass> {
         }
     }
 
-    /// Fills the background of this widget with `color`, honoring the current
+    /// Fills the background of this widget with `brush`, honoring the current
     /// [`CornerRadius`] setting.
     ///
-    /// If the alpha channel of `color` is 0, this function does nothing.
-    pub fn fill(&mut self, color: Color) {
-        if color.alpha() > 0 {
-            let visible_rect = Rect::from(self.gfx.region().size);
+    /// If `brush` is fully transparent, this function does nothing.
+    pub fn fill(&mut self, brush: impl Into<Brush>) {
+        let brush = brush.into();
+        if !brush.is_visible() {
+            return;
+        }
+        let visible_rect = Rect::from(self.gfx.region().size);
 
-            let radii = self.get(&CornerRadius);
-            let radii = radii.map(|r| r.into_px(self.gfx.scale()));
+        let radii = self.get(&CornerRadius);
+        let radii = radii.map(|r| r.into_px(self.gfx.scale()));
 
-            let focus_ring = if radii.is_zero() {
-                Shape::filled_rect(visible_rect, color)
-            } else {
-                Shape::filled_round_rect(visible_rect, radii, color)
-            };
-            self.gfx.draw_shape(&focus_ring);
+        match brush {
+            Brush::Color(color) if !radii.is_zero() => {
+                self.gfx
+                    .draw_shape(&Shape::filled_round_rect(visible_rect, radii, color));
+            }
+            Brush::Gradient(gradient) if !radii.is_zero() => {
+                self.gfx
+                    .fill_path(&Path::round_rect(visible_rect, radii), gradient);
+            }
+            brush => self.gfx.fill_rect(visible_rect, brush),
         }
     }
 
@@ -738,6 +1022,26 @@ impl<'clip, 'gfx, 'pass> GraphicsContext<'_, 'clip, 'gfx, 'pass> {
         self.current_font_settings().apply(self);
     }
 
+    /// Returns the size of `text` when rendered using `settings`.
+    ///
+    /// If `wrap_width` is provided, the text will be wrapped to fit within the
+    /// width. After measuring, this context's [current font
+    /// settings](Self::current_font_settings) are applied.
+    pub fn measure_text(
+        &mut self,
+        text: &str,
+        settings: &FontSettings,
+        wrap_width: Option<Px>,
+    ) -> Size<UPx> {
+        settings.apply(self);
+        let measured = self.gfx.measure_text(
+            Text::new(text, Color::WHITE)
+                .align(cosmic_text::Align::Left, wrap_width.unwrap_or(Px::MAX)),
+        );
+        self.apply_current_font_settings();
+        measured.size.into_unsigned().ceil()
+    }
+
     /// Invokes [`Widget::redraw()`](crate::widget::Widget::redraw) on this
     /// context's widget.
     pub fn redraw(&mut self) {
@@ -745,19 +1049,61 @@ impl<'clip, 'gfx, 'pass> GraphicsContext<'_, 'clip, 'gfx, 'pass> {
             return;
         }
 
-        self.tree.note_widget_rendered(self.current_node.node_id);
+        self.tree.note_widget_rendered(
+            self.current_node.node_id,
+            self.gfx.transform(),
+            self.gfx.clip_rect(),
+        );
         let widget = self.current_node.clone();
         let mut widget = widget.lock();
         if !widget.as_widget().full_control_redraw() {
+            self.apply_opacity(self.get(&Opacity));
+
             let background = self.get(&WidgetBackground);
             self.fill(background);
+            let gradient = self.get(&WidgetBackgroundGradient);
+            if !gradient.is_empty() {
+                self.fill(gradient);
+            }
 
             self.apply_current_font_settings();
-            self.apply_opacity(self.get(&Opacity));
         }
 
         widget.as_widget().redraw(self);
     }
+
+    /// Redraws each widget in `children` in ascending order of their
+    /// [`ZIndex`].
+    ///
+    /// Children with equal [`ZIndex`] values are drawn in the order they are
+    /// provided. Widgets that draw multiple, potentially overlapping children
+    /// should use this function to allow the children to control their
+    /// stacking order.
+    pub fn redraw_children<'child>(
+        &mut self,
+        children: impl IntoIterator<Item = &'child MountedWidget>,
+    ) {
+        let mut children = children
+            .into_iter()
+            .map(|child| (self.widget.for_other(child).get(&ZIndex), child))
+            .collect::<Vec<_>>();
+        children.sort_by_key(|(z_index, _)| *z_index);
+        for (_, child) in children {
+            self.for_other(child).redraw();
+        }
+    }
+
+    /// Redraws `child`, clipping its drawing to `clip`.
+    ///
+    /// `clip` is relative to this widget's region. Each child is always
+    /// clipped to the layout set using
+    /// [`LayoutContext::set_child_layout()`]; this function allows clipping a
+    /// child to a smaller region, such as when a child is partially scrolled
+    /// out of view. Portions of `child` that are clipped are not drawn and do
+    /// not receive mouse input.
+    pub fn redraw_child_clipped(&mut self, child: &MountedWidget, clip: Rect<Px>) {
+        self.clipped_to(clip).for_other(child).redraw();
+    }
 }
 
 impl Drop for GraphicsContext<'_, '_, '_, '_> {
@@ -854,20 +1200,42 @@ impl<'context, 'clip, 'gfx, 'pass> LayoutContext<'context, 'clip, 'gfx, 'pass> {
 
     /// Invokes [`Widget::layout()`](crate::widget::Widget::layout) on this
     /// context's widget and returns the result.
+    ///
+    /// The [`MinSize`], [`MaxSize`], and [`Overflow`] components are applied
+    /// to the constraints given to the widget and to the size it returns.
     pub fn layout(&mut self, available_space: Size<ConstraintLimit>) -> Size<UPx> {
         if self.persist_layout {
             if let Some(cached) = self.graphics.current_node.begin_layout(available_space) {
                 return cached;
             }
         }
-        let result = self
+        let scale = self.gfx.scale();
+        let min = self.get(&MinSize).map(|min| min.into_upx(scale));
+        let max = self.get(&MaxSize).map(|max| {
+            if max == Dimension::MAX {
+                UPx::MAX
+            } else {
+                max.into_upx(scale)
+            }
+        });
+        let overflow = self.get(&Overflow);
+        let constraints = Size::new(
+            constrain_limit(available_space.width, min.width, max.width),
+            constrain_limit(available_space.height, min.height, max.height),
+        );
+        let mut result = self
             .graphics
             .current_node
             .clone()
             .lock()
             .as_widget()
-            .layout(available_space, self)
-            .map(Round::ceil);
+            .layout(constraints, self)
+            .map(Round::ceil)
+            .max(min)
+            .min(max);
+        if overflow != OverflowPolicy::Visible {
+            result = result.min(available_space.map(ConstraintLimit::max));
+        }
         if self.persist_layout {
             self.graphics
                 .current_node
@@ -876,6 +1244,40 @@ impl<'context, 'clip, 'gfx, 'pass> LayoutContext<'context, 'clip, 'gfx, 'pass> {
         result
     }
 
+    /// Returns the size `widget` requests when laid out within
+    /// `available_space`, without adding it to the widget hierarchy.
+    ///
+    /// If `widget` is not already mounted, it is temporarily added to the
+    /// widget tree as a child of this context's widget so that it inherits the
+    /// same styles it would as a child. While measuring, neither
+    /// [`Widget::mounted()`](crate::widget::Widget::mounted) nor
+    /// [`Widget::unmounted()`](crate::widget::Widget::unmounted) are invoked
+    /// for the widget or any children it adds during layout. The widget is
+    /// removed from the tree before this function returns, and its layout is
+    /// not persisted.
+    pub fn measure(
+        &mut self,
+        widget: &WidgetInstance,
+        available_space: Size<ConstraintLimit>,
+    ) -> Size<UPx> {
+        if let Some(mounted) = self.tree.widget(widget.id()) {
+            return self
+                .for_other(&mounted)
+                .as_temporary()
+                .layout(available_space);
+        }
+
+        let was_measuring = mem::replace(&mut self.pending_state.measuring, true);
+        let mounted = self.push_child(widget.clone());
+        let size = self
+            .for_other(&mounted)
+            .as_temporary()
+            .layout(available_space);
+        self.remove_child(&mounted);
+        self.pending_state.measuring = was_measuring;
+        size
+    }
+
     /// Sets the layout for `child` to `layout`.
     ///
     /// `layout` is relative to the current widget's controls.
@@ -887,6 +1289,13 @@ impl<'context, 'clip, 'gfx, 'pass> LayoutContext<'context, 'clip, 'gfx, 'pass> {
     }
 }
 
+fn constrain_limit(limit: ConstraintLimit, min: UPx, max: UPx) -> ConstraintLimit {
+    match limit {
+        ConstraintLimit::Fill(size) => ConstraintLimit::Fill(size.min(max).max(min)),
+        ConstraintLimit::SizeToFit(size) => ConstraintLimit::SizeToFit(size.min(max).max(min)),
+    }
+}
+
 impl AsEventContext for LayoutContext<'_, '_, '_, '_> {
     fn as_event_context(&mut self) -> EventContext<'_> {
         self.graphics.as_event_context()
@@ -918,10 +1327,29 @@ pub trait AsEventContext {
     fn push_child(&mut self, child: WidgetInstance) -> MountedWidget {
         let mut context = self.as_event_context();
         let pushed_widget = context.tree.push_boxed(child, Some(&context.current_node));
+        if context.pending_state.measuring {
+            return pushed_widget;
+        }
         pushed_widget
             .lock()
             .as_widget()
             .mounted(&mut context.for_other(&pushed_widget));
+        if let Some(preserved) = pushed_widget.instance().state_key().and_then(|key| {
+            context
+                .tree
+                .take_preserved_state(pushed_widget.node_id, key)
+        }) {
+            let mut restore_context = context.for_other(&pushed_widget);
+            if let Some(state) = preserved.state {
+                pushed_widget
+                    .lock()
+                    .as_widget()
+                    .restore_state(state, &mut restore_context);
+            }
+            if preserved.focused {
+                restore_context.focus();
+            }
+        }
         pushed_widget
     }
 
@@ -938,7 +1366,9 @@ pub trait AsEventContext {
                     continue;
                 };
                 let child = unmount_context.widget.widget().clone();
-                child.lock().as_widget().unmounted(&mut unmount_context);
+                if !unmount_context.widget.pending_state.measuring {
+                    child.lock().as_widget().unmounted(&mut unmount_context);
+                }
                 unmount_context.widget.tree.remove_child(
                     &child,
                     &mut unmount_context.widget.pending_state.unmount_queue,
@@ -971,7 +1401,7 @@ pub struct WidgetContext<'context> {
     pub(crate) tree: Tree,
     window: &'context mut dyn PlatformWindow,
     theme: Cow<'context, ThemePair>,
-    cursor: &'context mut CursorState,
+    pub(crate) cursor: &'context mut CursorState,
     pending_state: PendingState<'context>,
     font_state: &'context mut FontState,
     effective_styles: Styles,
@@ -1011,6 +1441,7 @@ impl<'context> WidgetContext<'context> {
                 focus_is_advancing: false,
                 unmount_queue: Vec::new(),
                 unmounting: false,
+                measuring: false,
             }),
             tree,
             effective_styles,
@@ -1147,6 +1578,45 @@ impl<'context> WidgetContext<'context> {
         self.current_node.last_layout()
     }
 
+    /// Converts `location` from window coordinates to coordinates relative to
+    /// this widget's [`last_layout()`](Self::last_layout).
+    ///
+    /// Any transforms applied by parent widgets, such as
+    /// [`Transformed`](crate::widgets::Transformed), are reversed. Returns
+    /// `None` if this widget has not been laid out.
+    #[must_use]
+    pub fn window_to_widget(&self, location: Point<Px>) -> Option<Point<Px>> {
+        self.tree
+            .window_to_widget(self.current_node.node_id, location)
+    }
+
+    /// Announces `message` to users of assistive technologies, such as screen
+    /// readers.
+    ///
+    /// This is useful for surfacing information that is otherwise only
+    /// presented visually, such as validation errors or the completion of a
+    /// background task. Announcements are delivered when the window is next
+    /// redrawn. See [`Window::on_announcement`](crate::window::Window::on_announcement)
+    /// for how announcements reach assistive technologies.
+    pub fn announce(&mut self, message: impl Into<String>, politeness: Politeness) {
+        self.tree.announce(Announcement {
+            message: message.into(),
+            politeness,
+        });
+        self.set_needs_redraw();
+    }
+
+    /// Scrolls any [`Scroll`](crate::widgets::Scroll) widgets containing this
+    /// widget so that this widget is visible.
+    ///
+    /// The scroll happens the next time the containing scroll views are
+    /// redrawn. Each scroll view is adjusted by the minimum amount needed to
+    /// show this widget within its bounds.
+    pub fn scroll_into_view(&mut self, behavior: ScrollBehavior) {
+        self.tree.request_reveal(self.current_node.id(), behavior);
+        self.set_needs_redraw();
+    }
+
     /// Sets the currently focused widget to this widget.
     ///
     /// Widget events relating to focus changes are deferred until after the all
@@ -1330,6 +1800,56 @@ impl<'context> WidgetContext<'context> {
         self.effective_styles.try_get(query, self)
     }
 
+    /// Queries the widget hierarchy for a single style component, returning
+    /// the value along with where the value came from.
+    ///
+    /// This returns the same value as [`get()`](Self::get).
+    #[must_use]
+    pub fn get_with_source<Component: ComponentDefinition>(
+        &self,
+        query: &Component,
+    ) -> ResolvedComponent<Component::ComponentType> {
+        match self.effective_styles.try_get(query, self) {
+            Some(value) => ResolvedComponent {
+                value,
+                source: self
+                    .tree
+                    .style_source(self.current_node.node_id, &query.name())
+                    .unwrap_or(StyleSource::Inline),
+            },
+            None => ResolvedComponent {
+                value: query.default_value(self),
+                source: StyleSource::Default,
+            },
+        }
+    }
+
+    /// Returns every style component set for this widget, sorted by name.
+    ///
+    /// Components that are not set, whose values are provided by the
+    /// component's default, are not included. Use
+    /// [`get_with_source()`](Self::get_with_source) to resolve a specific
+    /// component.
+    #[must_use]
+    pub fn applied_styles(&self) -> Vec<AppliedComponent> {
+        let mut applied = self
+            .effective_styles
+            .clone()
+            .into_iter()
+            .map(|(name, value)| AppliedComponent {
+                source: self
+                    .tree
+                    .style_source(self.current_node.node_id, &name)
+                    .unwrap_or(StyleSource::Inline),
+                value: value.get(),
+                name,
+            })
+            .collect::<Vec<_>>();
+        applied
+            .sort_by(|a, b| (&*a.name.group, &*a.name.name).cmp(&(&*b.name.group, &*b.name.name)));
+        applied
+    }
+
     /// Returns the window containing this widget.
     #[must_use]
     pub const fn window(&self) -> &dyn PlatformWindow {
@@ -1438,6 +1958,9 @@ struct PendingWidgetState {
     active: Option<WidgetId>,
     unmounting: bool,
     unmount_queue: Vec<WidgetId>,
+    /// When true, widgets are being added to the tree only to be measured, and
+    /// their mounted and unmounted hooks are not invoked.
+    measuring: bool,
 }
 
 impl PendingState<'_> {
@@ -1573,7 +2096,8 @@ impl<T> Trackable for T where T: sealed::Trackable {}
 
 pub(crate) mod sealed {
     use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::Arc;
+    use std::sync::{Arc, OnceLock};
+    use std::thread::ThreadId;
 
     use kempt::Set;
     use parking_lot::{Mutex, MutexGuard};
@@ -1592,9 +2116,18 @@ pub(crate) mod sealed {
         refresh_sent: Arc<AtomicBool>,
         sync_sent: Arc<AtomicBool>,
         invalidated: Arc<Mutex<Set<WidgetId>>>,
+        thread: Arc<OnceLock<ThreadId>>,
     }
 
     impl InvalidationStatus {
+        pub fn set_thread(&self) {
+            let _result = self.thread.set(std::thread::current().id());
+        }
+
+        pub fn thread(&self) -> Option<ThreadId> {
+            self.thread.get().copied()
+        }
+
         pub fn should_send_refresh(&self) -> bool {
             self.refresh_sent
                 .compare_exchange(false, true, Ordering::Release, Ordering::Acquire)
@@ -1638,3 +2171,107 @@ pub(crate) mod sealed {
         }
     }
 }
+
+#[test]
+fn spatial_distances() {
+    let origin = Rect::new(Point::px(100, 100), Size::px(50, 20));
+    let right = Rect::new(Point::px(170, 100), Size::px(50, 20));
+    let below_right = Rect::new(Point::px(160, 140), Size::px(50, 20));
+    let left = Rect::new(Point::px(0, 95), Size::px(50, 20));
+
+    assert_eq!(
+        spatial_distance(origin, right, FocusDirection::Right),
+        Some(Px::new(20))
+    );
+    // 10px away horizontally, and 20px out of alignment vertically.
+    assert_eq!(
+        spatial_distance(origin, below_right, FocusDirection::Right),
+        Some(Px::new(50))
+    );
+    assert_eq!(spatial_distance(origin, right, FocusDirection::Left), None);
+    assert_eq!(
+        spatial_distance(origin, left, FocusDirection::Left),
+        Some(Px::new(50))
+    );
+    assert_eq!(
+        spatial_distance(origin, below_right, FocusDirection::Down),
+        Some(Px::new(40))
+    );
+    assert_eq!(spatial_distance(origin, right, FocusDirection::Up), None);
+}
+
+#[test]
+fn style_sources() {
+    use crate::reactive::value::Dynamic;
+    use crate::styles::components::TextColor;
+    use crate::styles::{ComponentName, NamedComponent};
+    use crate::widget::{MakeWidget, MakeWidgetWithTag, Widget, WidgetTag};
+
+    #[derive(Debug, Default)]
+    struct Introspection {
+        text_color: Option<ResolvedComponent<Color>>,
+        text_size: Option<ResolvedComponent<Dimension>>,
+        highlight: Option<ResolvedComponent<Color>>,
+        applied: Vec<AppliedComponent>,
+    }
+
+    #[derive(Debug)]
+    struct Probe(Dynamic<Introspection>);
+
+    impl Widget for Probe {
+        fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+            let mut found = self.0.lock();
+            found.text_color = Some(context.get_with_source(&TextColor));
+            found.text_size = Some(context.get_with_source(&TextSize));
+            found.highlight = Some(context.get_with_source(&HighlightColor));
+            found.applied = context.applied_styles();
+        }
+    }
+
+    let found = Dynamic::<Introspection>::default();
+    let (tag, outer) = WidgetTag::new();
+    let mut recorder = Probe(found.clone())
+        .with(&TextSize, Px::new(20))
+        .align_left()
+        .with(&TextColor, Color::RED)
+        .make_with_tag(tag)
+        .build_recorder()
+        .size(Size::squared(UPx::new(16)))
+        .finish()
+        .expect("error creating recorder");
+    recorder.refresh().expect("error rendering");
+
+    let found = found.lock();
+    assert_eq!(
+        found.text_color,
+        Some(ResolvedComponent {
+            value: Color::RED,
+            source: StyleSource::Inherited(outer),
+        })
+    );
+    assert_eq!(
+        found.text_size,
+        Some(ResolvedComponent {
+            value: Dimension::from(Px::new(20)),
+            source: StyleSource::Inline,
+        })
+    );
+    assert_eq!(
+        found.highlight.as_ref().map(|resolved| resolved.source),
+        Some(StyleSource::Default)
+    );
+
+    let source_of = |name: Cow<'_, ComponentName>| {
+        found
+            .applied
+            .iter()
+            .find(|applied| applied.name == *name)
+            .map(|applied| applied.source)
+    };
+    assert_eq!(
+        source_of(TextColor.name()),
+        Some(StyleSource::Inherited(outer))
+    );
+    assert_eq!(source_of(TextSize.name()), Some(StyleSource::Inline));
+    assert_eq!(source_of(HighlightColor.name()), None);
+}
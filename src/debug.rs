@@ -4,11 +4,26 @@ use std::fmt::Debug;
 
 use alot::OrderedLots;
 
-use crate::reactive::value::{Dynamic, DynamicReader, ForEach, Source, WeakDynamic};
+use crate::reactive::value::{
+    Dynamic, DynamicReader, ForEach, IntoValue, Source, Value, WeakDynamic,
+};
+use crate::styles::ThemePair;
 use crate::widget::{MakeWidget, WidgetInstance, WidgetList};
 use crate::widgets::grid::{Grid, GridWidgets};
 use crate::window::{MakeWindow, Window};
 
+mod contrast;
+mod tree_snapshot;
+mod vision;
+
+pub(crate) use self::contrast::dominant_colors;
+pub use self::contrast::{
+    ContrastIssue, ContrastReport, WCAG_AA_CONTRAST, WCAG_AA_LARGE_TEXT_CONTRAST,
+};
+pub(crate) use self::tree_snapshot::short_type_name;
+pub use self::tree_snapshot::{TreeSnapshot, WidgetSnapshot, UPDATE_SNAPSHOTS_VAR};
+pub use self::vision::ColorVisionDeficiency;
+
 /// A widget that can provide extra information when debugging.
 #[derive(Clone, Default)]
 pub struct DebugContext {
@@ -62,6 +77,46 @@ impl DebugContext {
         });
     }
 
+    /// Adds a control to this context that selects a
+    /// [`ColorVisionDeficiency`] to simulate, and returns the selected
+    /// deficiency.
+    ///
+    /// The returned value can be applied to a window using
+    /// [`PostProcess::color_vision_deficiency()`](crate::window::PostProcess::color_vision_deficiency).
+    /// The control is removed once the returned value is dropped.
+    #[must_use]
+    pub fn color_vision_deficiency(&self) -> Dynamic<Option<ColorVisionDeficiency>> {
+        let deficiency = Dynamic::new(None);
+        self.observe("Color Vision", &deficiency, |deficiency| {
+            std::iter::once(deficiency.new_radio(None).labelled_by("Normal"))
+                .chain(ColorVisionDeficiency::ALL.into_iter().map(|option| {
+                    deficiency
+                        .new_radio(Some(option))
+                        .labelled_by(option.to_string())
+                }))
+                .collect::<WidgetList>()
+                .into_rows()
+        });
+        deficiency
+    }
+
+    /// Adds a [`ContrastReport`] for `theme` to this context and returns the
+    /// report.
+    ///
+    /// If `theme` is dynamic, the report is updated each time `theme` changes.
+    /// The report is removed from this context once the returned value is
+    /// dropped.
+    pub fn check_contrast(&self, theme: impl IntoValue<ThemePair>) -> Dynamic<ContrastReport> {
+        let report = match theme.into_value() {
+            Value::Constant(theme) => Dynamic::new(ContrastReport::for_theme_pair(&theme)),
+            Value::Dynamic(theme) => theme.map_each(ContrastReport::for_theme_pair),
+        };
+        self.observe("Contrast", &report, |report| {
+            report.map_each(ToString::to_string)
+        });
+        report
+    }
+
     /// Returns a new child context with the given `label`.
     ///
     /// This creates a nested hierarchy of debug contexts. If a section with the
@@ -0,0 +1,224 @@
+use std::fmt::{self, Display, Formatter};
+
+use ahash::AHashMap;
+use figures::units::Px;
+use figures::Rect;
+use kludgine::Color;
+
+use crate::styles::{ColorExt, ColorTheme, FixedTheme, SurfaceTheme, Theme, ThemePair};
+use crate::widget::WidgetId;
+
+/// The minimum contrast ratio WCAG level AA requires for normal text.
+pub const WCAG_AA_CONTRAST: f32 = 4.5;
+
+/// The minimum contrast ratio WCAG level AA requires for large text.
+pub const WCAG_AA_LARGE_TEXT_CONTRAST: f32 = 3.0;
+
+/// A pair of colors whose contrast is below the required ratio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastIssue {
+    /// A description of where the colors are used.
+    pub description: String,
+    /// The widget the colors were found in, if the issue was found by
+    /// scanning a rendered frame.
+    pub widget: Option<WidgetId>,
+    /// The region of the window that was scanned, if the issue was found by
+    /// scanning a rendered frame.
+    pub region: Option<Rect<Px>>,
+    /// The color of the text or other foreground content.
+    pub foreground: Color,
+    /// The color the foreground is drawn on.
+    pub background: Color,
+    /// The WCAG contrast ratio between the two colors.
+    pub ratio: f32,
+}
+
+impl Display for ContrastIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:.2}:1", self.description, self.ratio)
+    }
+}
+
+/// A list of colors whose contrast is below a required ratio.
+///
+/// Reports can be created by checking a [`ThemePair`] using
+/// [`for_theme_pair()`](Self::for_theme_pair), or by scanning a rendered frame
+/// using
+/// [`VirtualRecorder::contrast_report()`](crate::window::VirtualRecorder::contrast_report).
+/// [`DebugContext::check_contrast()`](crate::debug::DebugContext::check_contrast)
+/// shows a report in the debugger window.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContrastReport {
+    /// The issues found.
+    pub issues: Vec<ContrastIssue>,
+}
+
+impl ContrastReport {
+    /// Returns a report of each foreground and background color pair in
+    /// `theme` whose contrast is below [`WCAG_AA_CONTRAST`].
+    #[must_use]
+    pub fn for_theme_pair(theme: &ThemePair) -> Self {
+        let mut report = Self::default();
+        report.check_theme("light", &theme.light);
+        report.check_theme("dark", &theme.dark);
+        report.check_fixed("primary fixed", &theme.primary_fixed);
+        report.check_fixed("secondary fixed", &theme.secondary_fixed);
+        report.check_fixed("tertiary fixed", &theme.tertiary_fixed);
+        report
+    }
+
+    /// Returns a report of each foreground and background color pair in
+    /// `theme` whose contrast is below [`WCAG_AA_CONTRAST`].
+    #[must_use]
+    pub fn for_theme(theme: &Theme) -> Self {
+        let mut report = Self::default();
+        report.check_theme("theme", theme);
+        report
+    }
+
+    /// Returns true if no issues were found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn check_theme(&mut self, mode: &str, theme: &Theme) {
+        self.check_color(mode, "primary", &theme.primary);
+        self.check_color(mode, "secondary", &theme.secondary);
+        self.check_color(mode, "tertiary", &theme.tertiary);
+        self.check_color(mode, "error", &theme.error);
+        self.check_surface(mode, &theme.surface);
+    }
+
+    fn check_color(&mut self, mode: &str, name: &str, theme: &ColorTheme) {
+        self.check(
+            format!("{mode} {name} on_color on color"),
+            theme.on_color,
+            theme.color,
+        );
+        self.check(
+            format!("{mode} {name} on_container on container"),
+            theme.on_container,
+            theme.container,
+        );
+    }
+
+    fn check_surface(&mut self, mode: &str, theme: &SurfaceTheme) {
+        for (name, background) in [
+            ("color", theme.color),
+            ("dim_color", theme.dim_color),
+            ("bright_color", theme.bright_color),
+            ("lowest_container", theme.lowest_container),
+            ("low_container", theme.low_container),
+            ("container", theme.container),
+            ("high_container", theme.high_container),
+            ("highest_container", theme.highest_container),
+        ] {
+            self.check(
+                format!("{mode} surface on_color on {name}"),
+                theme.on_color,
+                background,
+            );
+        }
+        self.check(
+            format!("{mode} surface on_color_variant on color"),
+            theme.on_color_variant,
+            theme.color,
+        );
+    }
+
+    fn check_fixed(&mut self, name: &str, theme: &FixedTheme) {
+        self.check(
+            format!("{name} on_color on color"),
+            theme.on_color,
+            theme.color,
+        );
+        self.check(
+            format!("{name} on_color_variant on color"),
+            theme.on_color_variant,
+            theme.color,
+        );
+    }
+
+    fn check(&mut self, description: String, foreground: Color, background: Color) {
+        let ratio = foreground.contrast_ratio(background);
+        if ratio < WCAG_AA_CONTRAST {
+            self.issues.push(ContrastIssue {
+                description,
+                widget: None,
+                region: None,
+                foreground,
+                background,
+                ratio,
+            });
+        }
+    }
+}
+
+impl Display for ContrastReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.issues.is_empty() {
+            return f.write_str("No contrast issues found");
+        }
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            Display::fmt(issue, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the background and foreground colors of a rendered region.
+///
+/// The background is the most common color. The foreground is the color that
+/// contrasts the most with the background among the colors that make up at
+/// least 5% of the remaining pixels, which excludes most antialiased edges.
+/// Returns `None` if the region only contains a single color.
+pub(crate) fn dominant_colors(pixels: impl IntoIterator<Item = Color>) -> Option<(Color, Color)> {
+    let mut counts = AHashMap::<[u8; 3], usize>::new();
+    let mut total = 0;
+    for pixel in pixels {
+        *counts
+            .entry([pixel.red(), pixel.green(), pixel.blue()])
+            .or_default() += 1;
+        total += 1;
+    }
+    let to_color = |[red, green, blue]: [u8; 3]| Color::new(red, green, blue, 255);
+
+    let (&background, &background_count) = counts.iter().max_by_key(|(_, count)| **count)?;
+    let background = to_color(background);
+    let remaining = total - background_count;
+    counts
+        .into_iter()
+        .map(|(color, count)| (to_color(color), count))
+        .filter(|(color, count)| *color != background && *count * 20 >= remaining)
+        .map(|(color, _)| color)
+        .max_by(|a, b| {
+            a.contrast_ratio(background)
+                .total_cmp(&b.contrast_ratio(background))
+        })
+        .map(|foreground| (background, foreground))
+}
+
+#[test]
+fn contrast() {
+    let ratio = Color::BLACK.contrast_ratio(Color::WHITE);
+    assert!((ratio - 21.).abs() < 0.01);
+    assert!((Color::WHITE.contrast_ratio(Color::WHITE) - 1.).abs() < f32::EPSILON);
+    // #777777 on white is a well-known example that fails WCAG AA.
+    assert!(Color::new(0x77, 0x77, 0x77, 255).contrast_ratio(Color::WHITE) < WCAG_AA_CONTRAST);
+
+    let gray = Color::new(0x80, 0x80, 0x80, 255);
+    let pixels = std::iter::repeat(Color::WHITE)
+        .take(80)
+        .chain(std::iter::repeat(Color::BLACK).take(40))
+        // Antialiased edges are ignored.
+        .chain(std::iter::once(gray));
+    assert_eq!(dominant_colors(pixels), Some((Color::WHITE, Color::BLACK)));
+    assert_eq!(
+        dominant_colors(std::iter::repeat(Color::WHITE).take(4)),
+        None
+    );
+}
@@ -0,0 +1,216 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+use figures::units::Px;
+use figures::Rect;
+
+use crate::widget::WidgetId;
+
+/// The environment variable that causes
+/// [`TreeSnapshot::assert_matches()`] to overwrite baselines rather than
+/// compare against them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "CUSHY_UPDATE_SNAPSHOTS";
+
+/// A textual snapshot of a window's widget tree.
+///
+/// Snapshots capture the structure of a window rather than its pixels: each
+/// widget's type, location in the tree, bounds, explicitly attached style
+/// components, and a summary of its contents. Comparing a snapshot against a
+/// stored baseline catches structural regressions, such as a widget
+/// disappearing or moving, that pixel comparisons can miss or report without
+/// explanation.
+///
+/// Snapshots are created using
+/// [`VirtualRecorder::tree_snapshot()`](crate::window::VirtualRecorder::tree_snapshot).
+/// The [`Display`] implementation produces text that is stable between runs:
+/// [`WidgetId`]s are not included, and widgets are identified by their path
+/// from the root of the tree instead.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeSnapshot {
+    /// The widgets in the tree, in depth-first order.
+    pub widgets: Vec<WidgetSnapshot>,
+}
+
+impl TreeSnapshot {
+    /// Returns a line-by-line diff between `baseline` and this snapshot, or
+    /// `None` if they match.
+    ///
+    /// Lines only in `baseline` are prefixed with `- `, lines only in this
+    /// snapshot are prefixed with `+ `, and unchanged lines are prefixed with
+    /// two spaces.
+    #[must_use]
+    pub fn diff(&self, baseline: &str) -> Option<String> {
+        line_diff(baseline, &self.to_string())
+    }
+
+    /// Asserts that this snapshot matches the baseline stored at `path`.
+    ///
+    /// If no baseline exists at `path`, or if the [`UPDATE_SNAPSHOTS_VAR`]
+    /// environment variable is set, this snapshot is written to `path`
+    /// instead.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the snapshot does not match the baseline,
+    /// showing the differences, or if the baseline cannot be read or written.
+    #[track_caller]
+    pub fn assert_matches(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() || !path.exists() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("error creating snapshot directory");
+            }
+            std::fs::write(path, self.to_string()).expect("error writing snapshot");
+            return;
+        }
+
+        let baseline = std::fs::read_to_string(path).expect("error reading snapshot");
+        if let Some(diff) = self.diff(&baseline) {
+            panic!(
+                "widget tree does not match {}. Set {UPDATE_SNAPSHOTS_VAR} to update it.\n{diff}",
+                path.display()
+            );
+        }
+    }
+}
+
+impl Display for TreeSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for widget in &self.widgets {
+            writeln!(f, "{widget}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A widget in a [`TreeSnapshot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WidgetSnapshot {
+    /// The id of the widget.
+    ///
+    /// Widget ids are not stable between runs, and are not included when
+    /// displaying a snapshot.
+    pub widget: WidgetId,
+    /// The indices of each widget from the root to this widget, separated by
+    /// periods.
+    pub path: String,
+    /// The number of ancestors this widget has.
+    pub depth: usize,
+    /// The name of the widget's type, without module paths.
+    pub type_name: String,
+    /// The widget's last layout in window coordinates, if it has been laid
+    /// out.
+    pub bounds: Option<Rect<Px>>,
+    /// The names and values of the style components attached to this widget,
+    /// sorted by name.
+    pub styles: Vec<(String, String)>,
+    /// A summary of the widget's contents, such as the text of a label.
+    ///
+    /// Only widgets without children are summarized, as summaries of other
+    /// widgets repeat the summaries of their children.
+    pub summary: Option<String>,
+}
+
+impl Display for WidgetSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let indent = self.depth * 2;
+        write!(f, "{:indent$}{} [{}]", "", self.type_name, self.path)?;
+        if let Some(bounds) = self.bounds {
+            write!(
+                f,
+                " @ {},{} {}x{}",
+                bounds.origin.x.get(),
+                bounds.origin.y.get(),
+                bounds.size.width.get(),
+                bounds.size.height.get()
+            )?;
+        } else {
+            f.write_str(" (not laid out)")?;
+        }
+        if let Some(summary) = &self.summary {
+            write!(f, " {summary}")?;
+        }
+        for (name, value) in &self.styles {
+            write!(f, "\n{:indent$}  {name} = {value}", "")?;
+        }
+        Ok(())
+    }
+}
+
+/// Removes the module paths from a type name returned from
+/// [`std::any::type_name`].
+pub(crate) fn short_type_name(name: &str) -> String {
+    fn push_segment(short: &mut String, path: &str) {
+        short.push_str(path.rsplit("::").next().unwrap_or(path));
+    }
+
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (index, ch) in name.char_indices() {
+        if matches!(
+            ch,
+            '<' | '>' | ',' | ' ' | '(' | ')' | '[' | ']' | '&' | ';'
+        ) {
+            push_segment(&mut short, &name[segment_start..index]);
+            short.push(ch);
+            segment_start = index + ch.len_utf8();
+        }
+    }
+    push_segment(&mut short, &name[segment_start..]);
+    short
+}
+
+/// Returns the lines that differ between `baseline` and `current`, or `None`
+/// if they contain the same lines.
+fn line_diff(baseline: &str, current: &str) -> Option<String> {
+    let old = baseline.lines().collect::<Vec<_>>();
+    let new = current.lines().collect::<Vec<_>>();
+    if old == new {
+        return None;
+    }
+
+    // The length of the longest common subsequence of old[i..] and new[j..].
+    let mut common = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            diff.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    Some(diff.join("\n"))
+}
+
+#[test]
+fn snapshots_diff_by_line() {
+    assert_eq!(
+        short_type_name("cushy::widgets::label::Label<alloc::string::String>"),
+        "Label<String>"
+    );
+    assert_eq!(short_type_name("cushy::widgets::stack::Stack"), "Stack");
+
+    assert_eq!(line_diff("a\nb\n", "a\nb"), None);
+    assert_eq!(
+        line_diff("a\nb\nc", "a\nc\nd").as_deref(),
+        Some("  a\n- b\n  c\n+ d")
+    );
+    assert_eq!(line_diff("a", "b").as_deref(), Some("- a\n+ b"));
+}
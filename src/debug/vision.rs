@@ -0,0 +1,114 @@
+use std::fmt::{self, Display, Formatter};
+
+use kludgine::Color;
+
+/// A color vision deficiency that can be simulated to check how an interface
+/// appears to users with color blindness.
+///
+/// Deficiencies can be simulated for an entire window using
+/// [`PostProcess::color_vision_deficiency()`](crate::window::PostProcess::color_vision_deficiency)
+/// or for individual colors using [`simulate()`](Self::simulate). The
+/// simulations use the full-severity matrices published by Machado, Oliveira,
+/// and Fernandes (2009).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ColorVisionDeficiency {
+    /// The absence of red-sensitive cones.
+    Protanopia,
+    /// The absence of green-sensitive cones.
+    Deuteranopia,
+    /// The absence of blue-sensitive cones.
+    Tritanopia,
+    /// The absence of all color vision.
+    Achromatopsia,
+}
+
+impl ColorVisionDeficiency {
+    /// All simulated color vision deficiencies.
+    pub const ALL: [Self; 4] = [
+        Self::Protanopia,
+        Self::Deuteranopia,
+        Self::Tritanopia,
+        Self::Achromatopsia,
+    ];
+
+    /// Returns the matrix that converts a linear RGB color into how it is
+    /// perceived with this deficiency.
+    #[must_use]
+    pub const fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            Self::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            Self::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            Self::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+            Self::Achromatopsia => [
+                [0.2126, 0.7152, 0.0722],
+                [0.2126, 0.7152, 0.0722],
+                [0.2126, 0.7152, 0.0722],
+            ],
+        }
+    }
+
+    /// Returns `color` as it is perceived with this deficiency.
+    #[must_use]
+    pub fn simulate(self, color: Color) -> Color {
+        fn to_linear(channel: f32) -> f32 {
+            if channel <= 0.040_45 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        }
+        fn to_srgb(channel: f32) -> f32 {
+            let channel = channel.clamp(0., 1.);
+            if channel <= 0.003_130_8 {
+                channel * 12.92
+            } else {
+                channel.powf(1. / 2.4) * 1.055 - 0.055
+            }
+        }
+
+        let rgb = [
+            to_linear(color.red_f32()),
+            to_linear(color.green_f32()),
+            to_linear(color.blue_f32()),
+        ];
+        let [red, green, blue] = self
+            .matrix()
+            .map(|row| to_srgb(row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]));
+        Color::new_f32(red, green, blue, color.alpha_f32())
+    }
+
+    /// Returns the value used to select this deficiency in the simulation
+    /// shader. `0` indicates no simulation.
+    pub(crate) fn shader_index(deficiency: Option<Self>) -> f32 {
+        match deficiency {
+            None => 0.,
+            Some(Self::Protanopia) => 1.,
+            Some(Self::Deuteranopia) => 2.,
+            Some(Self::Tritanopia) => 3.,
+            Some(Self::Achromatopsia) => 4.,
+        }
+    }
+}
+
+impl Display for ColorVisionDeficiency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Protanopia => "Protanopia",
+            Self::Deuteranopia => "Deuteranopia",
+            Self::Tritanopia => "Tritanopia",
+            Self::Achromatopsia => "Achromatopsia",
+        })
+    }
+}
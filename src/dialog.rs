@@ -1,4 +1,4 @@
-//! Modal dialogs such as message boxes and file pickers.
+//! Modal dialogs such as message boxes, file pickers, and progress indicators.
 
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
@@ -11,16 +11,20 @@ use parking_lot::Mutex;
 
 use crate::reactive::value::{Destination, Dynamic, Source};
 use crate::styles::components::{PrimaryColor, WidgetBackground};
-use crate::styles::DynamicComponent;
-use crate::widget::{MakeWidget, OnceCallback, SharedCallback, WidgetList};
+use crate::styles::{DynamicComponent, Edges};
+use crate::widget::{MakeWidget, OnceCallback, SharedCallback, WidgetInstance, WidgetList};
 use crate::widgets::button::{ButtonKind, ClickCounter};
 use crate::widgets::input::InputValue;
 use crate::widgets::layers::{Modal, ModalHandle, ModalTarget};
 use crate::widgets::Custom;
-use crate::ModifiersExt;
+use crate::window::{PendingWindow, WindowHandle};
+use crate::{App, ModifiersExt, Open};
 
 #[cfg(feature = "native-dialogs")]
 mod native;
+mod progress;
+
+pub use self::progress::{ProgressDialog, ProgressHandle};
 
 #[derive(Clone, Debug)]
 struct MessageButtons {
@@ -118,6 +122,24 @@ impl OptionalCallback {
     }
 }
 
+/// The information needed to report which button dismissed a message box.
+///
+/// The title and caption are only copied if a telemetry sink is installed
+/// when the message box is opened.
+struct DialogResult(Option<(String, String)>);
+
+impl DialogResult {
+    fn new(title: &str, button: &str) -> Self {
+        Self(telemetry::is_enabled().then(|| (title.to_string(), button.to_string())))
+    }
+
+    fn emit(self) {
+        if let Some((title, button)) = self.0 {
+            telemetry::emit(|| TelemetryEvent::DialogResult { title, button });
+        }
+    }
+}
+
 #[derive(Default, Clone, Eq, PartialEq, Copy, Debug)]
 enum MessageLevel {
     Error,
@@ -317,27 +339,32 @@ where
             MessageButtonsKind::OkCancel => ("OK", None),
             MessageButtonsKind::YesNo => ("Yes", Some("No")),
         };
+        let ok_caption = coalesce_empty(&message.buttons.affirmative.caption, default_affirmative);
         let on_ok = message.buttons.affirmative.callback.clone();
-        let mut dialog = dialog.with_default_button(
-            coalesce_empty(&message.buttons.affirmative.caption, default_affirmative),
-            move || on_ok.invoke(),
-        );
+        let ok_result = DialogResult::new(&message.title, ok_caption);
+        let mut dialog = dialog.with_default_button(ok_caption, move || {
+            ok_result.emit();
+            on_ok.invoke();
+        });
         if let (Some(negative), Some(default_negative)) =
             (&message.buttons.negative, default_negative)
         {
+            let negative_caption = coalesce_empty(&negative.caption, default_negative);
             let on_negative = negative.callback.clone();
-            dialog = dialog.with_button(
-                coalesce_empty(&negative.caption, default_negative),
-                move || {
-                    on_negative.invoke();
-                },
-            );
+            let negative_result = DialogResult::new(&message.title, negative_caption);
+            dialog = dialog.with_button(negative_caption, move || {
+                negative_result.emit();
+                on_negative.invoke();
+            });
         }
 
         if let Some(cancel) = &message.buttons.cancel {
+            let cancel_caption = coalesce_empty(&cancel.caption, "Cancel");
             let on_cancel = cancel.callback.clone();
+            let cancel_result = DialogResult::new(&message.title, cancel_caption);
             dialog
-                .with_cancel_button(coalesce_empty(&cancel.caption, "Cancel"), move || {
+                .with_cancel_button(cancel_caption, move || {
+                    cancel_result.emit();
                     on_cancel.invoke();
                 })
                 .show();
@@ -355,6 +382,7 @@ pub struct FilePicker {
     file_name: String,
     title: String,
     can_create_directories: Option<bool>,
+    builtin: bool,
 }
 
 impl Default for FilePicker {
@@ -373,6 +401,7 @@ impl FilePicker {
             file_name: String::new(),
             title: String::new(),
             can_create_directories: None,
+            builtin: false,
         }
     }
 
@@ -397,6 +426,20 @@ impl FilePicker {
         self
     }
 
+    /// Sets whether Cushy's built-in file picker should be used instead of the
+    /// platform's native dialog and returns self.
+    ///
+    /// The built-in picker is always used when the `native-dialogs` feature is
+    /// disabled, when the platform's native dialogs are unavailable at runtime,
+    /// or when the picker is shown in a [`Modal`] layer. This allows
+    /// applications to use the same [`FilePicker`] API regardless of whether
+    /// native dialogs are available.
+    #[must_use]
+    pub fn using_builtin_dialog(mut self, builtin: bool) -> Self {
+        self.builtin = builtin;
+        self
+    }
+
     /// Adds the list of type filters to the dialog and returns self.
     ///
     /// These type filters are used for the dialog to only show related files
@@ -558,6 +601,17 @@ enum ModeCallback {
     Multiple(OnceCallback<Option<Vec<PathBuf>>>),
 }
 
+impl ModeCallback {
+    fn cancel(self) {
+        match self {
+            ModeCallback::Single(cb) => cb.invoke(None),
+            ModeCallback::Multiple(cb) => cb.invoke(None),
+        }
+    }
+}
+
+type SharedModeCallback = Arc<Mutex<Option<ModeCallback>>>;
+
 enum Mode {
     File(OnceCallback<Option<PathBuf>>),
     SaveFile(OnceCallback<Option<PathBuf>>),
@@ -602,6 +656,36 @@ impl Mode {
         Self::Folders(OnceCallback::new(callback))
     }
 
+    /// Returns this mode with `on_dismiss` being invoked before the original
+    /// callback.
+    fn dismissing<Dismiss>(self, on_dismiss: Dismiss) -> Self
+    where
+        Dismiss: FnOnce() + Send + 'static,
+    {
+        match self {
+            Mode::File(cb) => Mode::file(move |result| {
+                on_dismiss();
+                cb.invoke(result);
+            }),
+            Mode::SaveFile(cb) => Mode::save_file(move |result| {
+                on_dismiss();
+                cb.invoke(result);
+            }),
+            Mode::Files(cb) => Mode::files(move |result| {
+                on_dismiss();
+                cb.invoke(result);
+            }),
+            Mode::Folder(cb) => Mode::folder(move |result| {
+                on_dismiss();
+                cb.invoke(result);
+            }),
+            Mode::Folders(cb) => Mode::folders(move |result| {
+                on_dismiss();
+                cb.invoke(result);
+            }),
+        }
+    }
+
     fn into_callback(self) -> ModeCallback {
         match self {
             Mode::File(once_callback)
@@ -624,101 +708,277 @@ impl Mode {
     }
 }
 
+fn show_picker_in_modal(modal: &Modal, picker: &FilePicker, mode: Mode) {
+    let handle = modal.new_handle();
+    let mode = mode.dismissing({
+        let modal = modal.clone();
+        move || modal.dismiss()
+    });
+    handle.present(FilePickerWidget::new(handle.clone(), picker.clone(), mode));
+}
+
 impl PickFile for Modal {
     fn pick_file<Callback>(&self, picker: &FilePicker, callback: Callback)
     where
         Callback: FnOnce(Option<PathBuf>) + Send + 'static,
     {
-        let modal = self.clone();
-        let handle = self.new_handle();
-        handle.present(FilePickerWidget {
-            handle: handle.clone(),
-            picker: picker.clone(),
-            mode: Mode::file(move |result| {
-                modal.dismiss();
-                callback(result);
-            }),
-        });
+        show_picker_in_modal(self, picker, Mode::file(callback));
     }
 
     fn save_file<Callback>(&self, picker: &FilePicker, callback: Callback)
     where
         Callback: FnOnce(Option<PathBuf>) + Send + 'static,
     {
-        let modal = self.clone();
-        let handle = self.new_handle();
-        handle.present(FilePickerWidget {
-            handle: handle.clone(),
-            picker: picker.clone(),
-            mode: Mode::save_file(move |result| {
-                modal.dismiss();
-                callback(result);
-            }),
-        });
+        show_picker_in_modal(self, picker, Mode::save_file(callback));
     }
 
     fn pick_files<Callback>(&self, picker: &FilePicker, callback: Callback)
     where
         Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
     {
-        let modal = self.clone();
-        let handle = self.new_handle();
-        handle.present(FilePickerWidget {
-            handle: handle.clone(),
-            picker: picker.clone(),
-            mode: Mode::files(move |result| {
-                modal.dismiss();
-                callback(result);
-            }),
-        });
+        show_picker_in_modal(self, picker, Mode::files(callback));
     }
 
     fn pick_folder<Callback>(&self, picker: &FilePicker, callback: Callback)
     where
         Callback: FnOnce(Option<PathBuf>) + Send + 'static,
     {
-        let modal = self.clone();
-        let handle = self.new_handle();
-        handle.present(FilePickerWidget {
-            handle: handle.clone(),
-            picker: picker.clone(),
-            mode: Mode::folder(move |result| {
-                modal.dismiss();
-                callback(result);
-            }),
-        });
+        show_picker_in_modal(self, picker, Mode::folder(callback));
     }
 
     fn pick_folders<Callback>(&self, picker: &FilePicker, callback: Callback)
     where
         Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
     {
-        let modal = self.clone();
-        let handle = self.new_handle();
-        handle.present(FilePickerWidget {
-            handle: handle.clone(),
-            picker: picker.clone(),
-            mode: Mode::folders(move |result| {
-                modal.dismiss();
-                callback(result);
-            }),
+        show_picker_in_modal(self, picker, Mode::folders(callback));
+    }
+}
+
+/// Returns true if `picker` should be shown using the built-in file picker
+/// rather than the platform's native dialog.
+fn uses_builtin_picker(picker: &FilePicker) -> bool {
+    #[cfg(feature = "native-dialogs")]
+    {
+        picker.builtin || !native::file_dialogs_available()
+    }
+    #[cfg(not(feature = "native-dialogs"))]
+    {
+        let _ = picker;
+        true
+    }
+}
+
+fn show_picker_in_window(window: &WindowHandle, picker: &FilePicker, mode: Mode) {
+    if uses_builtin_picker(picker) {
+        let picker = picker.clone();
+        window.execute(move |context| {
+            if let Some(app) = context.app() {
+                show_builtin_picker_in_app(app, &picker, mode);
+            }
         });
+    } else {
+        #[cfg(feature = "native-dialogs")]
+        native::show_picker_in_window(window, picker, mode);
+    }
+}
+
+impl PickFile for WindowHandle {
+    fn pick_file<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
+    {
+        show_picker_in_window(self, picker, Mode::file(callback));
+    }
+
+    fn save_file<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
+    {
+        show_picker_in_window(self, picker, Mode::save_file(callback));
+    }
+
+    fn pick_files<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
+    {
+        show_picker_in_window(self, picker, Mode::files(callback));
+    }
+
+    fn pick_folder<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
+    {
+        show_picker_in_window(self, picker, Mode::folder(callback));
+    }
+
+    fn pick_folders<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
+    {
+        show_picker_in_window(self, picker, Mode::folders(callback));
+    }
+}
+
+fn show_picker_in_app(app: &App, picker: &FilePicker, mode: Mode) {
+    if uses_builtin_picker(picker) {
+        show_builtin_picker_in_app(app, picker, mode);
+    } else {
+        #[cfg(feature = "native-dialogs")]
+        native::show_picker_in_app(app, picker, mode);
+    }
+}
+
+/// Opens the built-in file picker in its own window.
+fn show_builtin_picker_in_app(app: &App, picker: &FilePicker, mode: Mode) {
+    let window = PendingWindow::default();
+    let handle = window.handle();
+    let title = picker.title(mode.kind()).to_string();
+    let modal = Modal::new();
+    let picker = FilePickerWidget::new(
+        modal.new_handle(),
+        picker.clone(),
+        mode.dismissing(move || handle.request_close()),
+    );
+    let callback = picker.callback.clone();
+
+    let result = window
+        .with_root(picker.and(modal).into_layers())
+        .titled(title)
+        .on_close(move || {
+            // If the window was closed without a choice being made, the
+            // picker was cancelled.
+            if let Some(callback) = callback.lock().take() {
+                callback.cancel();
+            }
+        })
+        .open(&mut app.clone());
+    if let Err(err) = result {
+        tracing::error!("error opening file picker window: {err}");
+    }
+}
+
+impl PickFile for App {
+    fn pick_file<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
+    {
+        show_picker_in_app(self, picker, Mode::file(callback));
+    }
+
+    fn save_file<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
+    {
+        show_picker_in_app(self, picker, Mode::save_file(callback));
+    }
+
+    fn pick_files<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
+    {
+        show_picker_in_app(self, picker, Mode::files(callback));
+    }
+
+    fn pick_folder<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
+    {
+        show_picker_in_app(self, picker, Mode::folder(callback));
+    }
+
+    fn pick_folders<Callback>(&self, picker: &FilePicker, callback: Callback)
+    where
+        Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
+    {
+        show_picker_in_app(self, picker, Mode::folders(callback));
     }
 }
 
 struct FilePickerWidget {
     handle: ModalHandle,
     picker: FilePicker,
-    mode: Mode,
+    kind: ModeKind,
+    callback: SharedModeCallback,
 }
 
-impl MakeWidget for FilePickerWidget {
-    #[allow(clippy::too_many_lines)]
-    fn make_widget(self) -> crate::widget::WidgetInstance {
-        let kind = self.mode.kind();
-        let callback = Arc::new(Mutex::new(Some(self.mode.into_callback())));
+impl FilePickerWidget {
+    fn new(handle: ModalHandle, picker: FilePicker, mode: Mode) -> Self {
+        Self {
+            handle,
+            picker,
+            kind: mode.kind(),
+            callback: Arc::new(Mutex::new(Some(mode.into_callback()))),
+        }
+    }
+}
 
-        let title = if self.picker.title.is_empty() {
+fn directory_tree_entry(
+    path: &Path,
+    depth: usize,
+    is_current: bool,
+    browsing_directory: &Dynamic<PathBuf>,
+) -> WidgetInstance {
+    let name = path
+        .file_name()
+        .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy())
+        .into_owned();
+    let indent = i32::try_from(depth).unwrap_or(i32::MAX).saturating_mul(8);
+    let browsing_directory = browsing_directory.clone();
+    let path = path.to_path_buf();
+    name.align_left()
+        .into_button()
+        .kind(ButtonKind::Transparent)
+        .on_click(move |_| {
+            browsing_directory.set(path.clone());
+        })
+        .with_dynamic(
+            &WidgetBackground,
+            DynamicComponent::new(move |ctx| is_current.then(|| ctx.get(&PrimaryColor).into())),
+        )
+        .pad_by(Edges::default().with_left(Lp::points(indent)))
+        .make_widget()
+}
+
+fn directory_tree(browsing_directory: &Dynamic<PathBuf>) -> impl MakeWidget {
+    let browsing = browsing_directory.clone();
+    browsing_directory.map_each(move |current| {
+        let mut entries = WidgetList::new();
+        let mut ancestors = current.ancestors().collect::<Vec<_>>();
+        ancestors.reverse();
+        for (depth, ancestor) in ancestors.iter().enumerate() {
+            entries.push(directory_tree_entry(
+                ancestor,
+                depth,
+                *ancestor == current.as_path(),
+                &browsing,
+            ));
+        }
+
+        if let Ok(children) = fs::read_dir(current) {
+            let mut children = children
+                .filter_map(Result::ok)
+                .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect::<Vec<_>>();
+            children.sort();
+            for child in children {
+                entries.push(directory_tree_entry(
+                    &child,
+                    ancestors.len(),
+                    false,
+                    &browsing,
+                ));
+            }
+        }
+
+        entries.into_rows().make_widget()
+    })
+}
+
+impl FilePicker {
+    fn title(&self, kind: ModeKind) -> &str {
+        if self.title.is_empty() {
             match kind {
                 ModeKind::File => "Select a file",
                 ModeKind::SaveFile => "Save file",
@@ -727,8 +987,17 @@ impl MakeWidget for FilePickerWidget {
                 ModeKind::Folders => "Select one or more folders",
             }
         } else {
-            &self.picker.title
-        };
+            &self.title
+        }
+    }
+}
+
+impl MakeWidget for FilePickerWidget {
+    #[allow(clippy::too_many_lines)]
+    fn make_widget(self) -> WidgetInstance {
+        let kind = self.kind;
+        let callback = self.callback;
+        let title = self.picker.title(kind).to_string();
 
         let caption = match kind {
             ModeKind::File | ModeKind::Files | ModeKind::Folder | ModeKind::Folders => "Select",
@@ -960,18 +1229,24 @@ impl MakeWidget for FilePickerWidget {
         title
             .and(click_duration_probe)
             .into_columns()
-            .and(file_ui.width(Lp::inches(6)).height(Lp::inches(4)))
+            .and(
+                directory_tree(&browsing_directory)
+                    .vertical_scroll()
+                    .width(Lp::inches(2))
+                    .and(file_ui.expand())
+                    .into_columns()
+                    .width(Lp::inches(8))
+                    .height(Lp::inches(4)),
+            )
             .and(
                 "Cancel"
                     .into_button()
                     .on_click({
                         let mode = callback.clone();
-                        move |_| match mode.lock().take() {
-                            Some(ModeCallback::Single(cb)) => cb.invoke(None),
-                            Some(ModeCallback::Multiple(cb)) => {
-                                cb.invoke(None);
+                        move |_| {
+                            if let Some(callback) = mode.lock().take() {
+                                callback.cancel();
                             }
-                            None => {}
                         }
                     })
                     .into_escape()
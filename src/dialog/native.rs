@@ -1,14 +1,14 @@
-use std::path::PathBuf;
 use std::thread;
 
 use rfd::{FileDialog, MessageDialog, MessageDialogResult};
 
 use super::{
     coalesce_empty, FilePicker, MessageBox, MessageButtons, MessageButtonsKind, MessageLevel, Mode,
-    OpenMessageBox, PickFile,
+    OpenMessageBox,
 };
+use crate::telemetry::{self, TelemetryEvent};
 use crate::window::WindowHandle;
-use crate::App;
+use crate::{App, Lazy};
 
 impl MessageButtons {
     fn as_rfd_buttons(&self) -> rfd::MessageButtons {
@@ -85,15 +85,15 @@ impl OpenMessageBox for WindowHandle {
             };
             app.execute(move |_app| {
                 let mut dialog = MessageDialog::new()
-                    .set_title(message.title)
+                    .set_title(&message.title)
                     .set_buttons(message.buttons.as_rfd_buttons())
-                    .set_description(message.description)
+                    .set_description(&message.description)
                     .set_level(message.level.into());
                 if let Some(winit) = winit {
                     dialog = dialog.set_parent(&winit);
                 }
                 thread::spawn(move || {
-                    handle_message_result(&dialog.show(), &message.buttons);
+                    handle_message_result(&dialog.show(), &message);
                 });
             });
         });
@@ -106,19 +106,44 @@ impl OpenMessageBox for App {
         let message = message.clone();
         self.execute(move |_app| {
             let dialog = MessageDialog::new()
-                .set_title(message.title)
+                .set_title(&message.title)
                 .set_buttons(message.buttons.as_rfd_buttons())
-                .set_description(message.description)
+                .set_description(&message.description)
                 .set_level(message.level.into());
             thread::spawn(move || {
-                handle_message_result(&dialog.show(), &message.buttons);
+                handle_message_result(&dialog.show(), &message);
                 drop(shutdown_guard);
             });
         });
     }
 }
 
-fn handle_message_result(result: &MessageDialogResult, buttons: &MessageButtons) {
+fn handle_message_result(result: &MessageDialogResult, message: &MessageBox) {
+    let buttons = &message.buttons;
+    let (default_affirmative, default_negative) = match buttons.kind {
+        MessageButtonsKind::YesNo => ("Yes", Some("No")),
+        MessageButtonsKind::OkCancel => ("OK", None),
+    };
+    telemetry::emit(|| {
+        let button = match result {
+            MessageDialogResult::Ok | MessageDialogResult::Yes => {
+                coalesce_empty(&buttons.affirmative.caption, default_affirmative)
+            }
+            MessageDialogResult::No => buttons
+                .negative
+                .as_ref()
+                .map_or("No", |negative| coalesce_empty(&negative.caption, "No")),
+            MessageDialogResult::Cancel => buttons
+                .cancel
+                .as_ref()
+                .map_or("Cancel", |cancel| coalesce_empty(&cancel.caption, "Cancel")),
+            MessageDialogResult::Custom(caption) => caption,
+        };
+        TelemetryEvent::DialogResult {
+            title: message.title.clone(),
+            button: button.to_string(),
+        }
+    });
     match result {
         MessageDialogResult::Ok | MessageDialogResult::Yes => {
             buttons.affirmative.callback.invoke();
@@ -150,11 +175,6 @@ fn handle_message_result(result: &MessageDialogResult, buttons: &MessageButtons)
             }
         }
         MessageDialogResult::Custom(caption) => {
-            let (default_affirmative, default_negative) = match buttons.kind {
-                MessageButtonsKind::YesNo => ("Yes", Some("No")),
-                MessageButtonsKind::OkCancel => ("OK", None),
-            };
-
             if coalesce_empty(&buttons.affirmative.caption, default_affirmative) == caption {
                 buttons.affirmative.callback.invoke();
             } else if let Some(negative) = buttons.negative.as_ref().filter(|negative| {
@@ -201,7 +221,41 @@ fn create_file_dialog(picker: FilePicker) -> FileDialog {
     dialog
 }
 
-fn show_picker_in_window(window: &WindowHandle, picker: &FilePicker, mode: Mode) {
+/// Returns true if the platform's native file dialogs can be shown.
+///
+/// On Linux and the BSDs, native file dialogs are shown through the XDG desktop
+/// portal, which is only reachable when a D-Bus session bus is available. Other
+/// platforms always provide native dialogs.
+pub(super) fn file_dialogs_available() -> bool {
+    static AVAILABLE: Lazy<bool> = Lazy::new(session_bus_available);
+    *AVAILABLE
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn session_bus_available() -> bool {
+    std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some()
+        || std::env::var_os("XDG_RUNTIME_DIR")
+            .is_some_and(|dir| std::path::Path::new(&dir).join("bus").exists())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+fn session_bus_available() -> bool {
+    true
+}
+
+pub(super) fn show_picker_in_window(window: &WindowHandle, picker: &FilePicker, mode: Mode) {
     let picker = picker.clone();
     window.execute(move |context| {
         // Get access to the winit handle from the window thread.
@@ -232,44 +286,7 @@ fn show_picker_in_window(window: &WindowHandle, picker: &FilePicker, mode: Mode)
     });
 }
 
-impl PickFile for WindowHandle {
-    fn pick_file<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
-    {
-        show_picker_in_window(self, picker, Mode::file(callback));
-    }
-
-    fn save_file<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
-    {
-        show_picker_in_window(self, picker, Mode::save_file(callback));
-    }
-
-    fn pick_files<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
-    {
-        show_picker_in_window(self, picker, Mode::files(callback));
-    }
-
-    fn pick_folder<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
-    {
-        show_picker_in_window(self, picker, Mode::folder(callback));
-    }
-
-    fn pick_folders<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
-    {
-        show_picker_in_window(self, picker, Mode::folders(callback));
-    }
-}
-
-fn show_picker_in_app(app: &App, picker: &FilePicker, mode: Mode) {
+pub(super) fn show_picker_in_app(app: &App, picker: &FilePicker, mode: Mode) {
     let picker = picker.clone();
     app.execute(move |_| {
         let dialog = create_file_dialog(picker);
@@ -285,40 +302,3 @@ fn show_picker_in_app(app: &App, picker: &FilePicker, mode: Mode) {
         });
     });
 }
-
-impl PickFile for App {
-    fn pick_file<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
-    {
-        show_picker_in_app(self, picker, Mode::file(callback));
-    }
-
-    fn save_file<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
-    {
-        show_picker_in_app(self, picker, Mode::save_file(callback));
-    }
-
-    fn pick_files<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
-    {
-        show_picker_in_app(self, picker, Mode::files(callback));
-    }
-
-    fn pick_folder<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<PathBuf>) + Send + 'static,
-    {
-        show_picker_in_app(self, picker, Mode::folder(callback));
-    }
-
-    fn pick_folders<Callback>(&self, picker: &FilePicker, callback: Callback)
-    where
-        Callback: FnOnce(Option<Vec<PathBuf>>) + Send + 'static,
-    {
-        show_picker_in_app(self, picker, Mode::folders(callback));
-    }
-}
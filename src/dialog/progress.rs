@@ -0,0 +1,272 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use figures::units::Lp;
+
+use crate::animation::{IntoAnimate, Spawn, ZeroToOne};
+use crate::reactive::value::{Destination, Dynamic, DynamicReader, Source};
+use crate::widget::MakeWidget;
+use crate::widgets::layers::{ModalHandle, ModalTarget};
+use crate::widgets::progress::{Progress, ProgressBar};
+
+/// The amount of time that must elapse before a time remaining estimate is
+/// displayed.
+const ESTIMATE_WARMUP: Duration = Duration::from_secs(1);
+
+/// Estimates longer than this are too unreliable to display.
+const MAX_ESTIMATE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A modal dialog that displays the progress of a long-running operation.
+///
+/// Opening a progress dialog returns a [`ProgressHandle`] that can be moved to
+/// a background task to report progress. When the last handle is dropped or
+/// [`ProgressHandle::finish()`] is called, the dialog is dismissed.
+///
+/// To avoid the dialog flickering in and out of view for operations that
+/// complete quickly, the dialog remains visible for at least
+/// [`minimum_display_time`](Self::minimum_display_time), which defaults to
+/// 500 milliseconds.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct ProgressDialog {
+    title: String,
+    message: String,
+    cancellable: bool,
+    minimum_display_time: Duration,
+}
+
+impl ProgressDialog {
+    /// Returns a new progress dialog with the given title.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: String::new(),
+            cancellable: false,
+            minimum_display_time: Duration::from_millis(500),
+        }
+    }
+
+    /// Sets the initial message displayed beneath the title and returns self.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Adds a cancel button to the dialog and returns self.
+    ///
+    /// Cancellation is cooperative: the background task must check
+    /// [`ProgressHandle::is_cancelled()`] and stop its work.
+    pub fn cancellable(mut self) -> Self {
+        self.cancellable = true;
+        self
+    }
+
+    /// Sets the minimum amount of time the dialog is shown for and returns
+    /// self.
+    pub fn minimum_display_time(mut self, duration: Duration) -> Self {
+        self.minimum_display_time = duration;
+        self
+    }
+
+    /// Opens this dialog in `target`, returning a handle that can be used to
+    /// update the dialog's progress.
+    ///
+    /// A target can be a [`Modal`](crate::widgets::layers::Modal) layer or a
+    /// [`ModalHandle`].
+    #[must_use]
+    pub fn open(&self, target: &impl ModalTarget) -> ProgressHandle {
+        let modal = target.new_handle();
+        let state = Arc::new(ProgressState {
+            modal,
+            progress: Dynamic::new(Progress::Indeterminant),
+            message: Dynamic::new(self.message.clone()),
+            remaining: Dynamic::new(None),
+            cancelled: Dynamic::new(false),
+            finished: AtomicBool::new(false),
+            opened_at: Instant::now(),
+            minimum_display_time: self.minimum_display_time,
+        });
+
+        let remaining = state
+            .remaining
+            .map_each(|remaining| remaining.map_or_else(String::new, format_remaining));
+        let mut contents = self
+            .title
+            .as_str()
+            .h5()
+            .and(state.message.clone())
+            .and(ProgressBar::new(state.progress.clone()))
+            .and(remaining.small());
+
+        if self.cancellable {
+            let cancelled = state.cancelled.clone();
+            contents.push(
+                "Cancel"
+                    .into_button()
+                    .on_click({
+                        let cancelled = cancelled.clone();
+                        move |_| {
+                            cancelled.set(true);
+                        }
+                    })
+                    .into_escape()
+                    .with_enabled(cancelled.map_each(|cancelled| !cancelled))
+                    .align_right(),
+            );
+        }
+
+        state
+            .modal
+            .present(contents.into_rows().width(Lp::inches(4)).contain());
+
+        ProgressHandle(state)
+    }
+
+    /// Opens this dialog in `target` and executes `task` on a background
+    /// thread with a handle to report its progress.
+    ///
+    /// The dialog is dismissed when `task` returns.
+    pub fn spawn<Task>(&self, target: &impl ModalTarget, task: Task)
+    where
+        Task: FnOnce(&ProgressHandle) + Send + 'static,
+    {
+        let handle = self.open(target);
+        thread::spawn(move || {
+            task(&handle);
+            handle.finish();
+        });
+    }
+}
+
+/// A handle to an open [`ProgressDialog`].
+///
+/// This type can be cloned and sent to other threads. When the last handle is
+/// dropped, the dialog is dismissed.
+#[derive(Clone)]
+pub struct ProgressHandle(Arc<ProgressState>);
+
+impl ProgressHandle {
+    /// Sets the fraction of the operation that has been completed.
+    ///
+    /// As progress is reported, an estimate of the time remaining is displayed
+    /// in the dialog.
+    pub fn set_progress(&self, fraction: impl Into<ZeroToOne>) {
+        let fraction = fraction.into();
+        self.0.progress.set(Progress::Percent(fraction));
+
+        self.0.remaining.set(estimate_remaining(
+            self.0.opened_at.elapsed(),
+            fraction.into_f32(),
+        ));
+    }
+
+    /// Switches the dialog to display an indeterminant progress indicator.
+    pub fn set_indeterminant(&self) {
+        self.0.progress.set(Progress::Indeterminant);
+        self.0.remaining.set(None);
+    }
+
+    /// Sets the message displayed beneath the dialog's title.
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.0.message.set(message.into());
+    }
+
+    /// Returns true if the user has requested the operation be cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.get()
+    }
+
+    /// Returns a reader that contains true once the user has requested the
+    /// operation be cancelled.
+    #[must_use]
+    pub fn cancelled(&self) -> DynamicReader<bool> {
+        self.0.cancelled.create_reader()
+    }
+
+    /// Returns a reader containing the current estimate of the time remaining
+    /// for the operation to complete.
+    #[must_use]
+    pub fn time_remaining(&self) -> DynamicReader<Option<Duration>> {
+        self.0.remaining.create_reader()
+    }
+
+    /// Dismisses the dialog.
+    ///
+    /// If the dialog has not been shown for its minimum display time, the
+    /// dialog will be dismissed once the minimum display time has elapsed.
+    pub fn finish(&self) {
+        self.0.finish();
+    }
+}
+
+struct ProgressState {
+    modal: ModalHandle,
+    progress: Dynamic<Progress>,
+    message: Dynamic<String>,
+    remaining: Dynamic<Option<Duration>>,
+    cancelled: Dynamic<bool>,
+    finished: AtomicBool,
+    opened_at: Instant,
+    minimum_display_time: Duration,
+}
+
+impl ProgressState {
+    fn finish(&self) {
+        if self.finished.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        let displayed_for = self.opened_at.elapsed();
+        if let Some(remaining) = self.minimum_display_time.checked_sub(displayed_for) {
+            let modal = self.modal.clone();
+            remaining.on_complete(move || modal.dismiss()).launch();
+        } else {
+            self.modal.dismiss();
+        }
+    }
+}
+
+impl Drop for ProgressState {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Returns the estimated time remaining for an operation that has completed
+/// `fraction` of its work in `elapsed`.
+///
+/// Returns `None` until enough time has passed to produce a meaningful
+/// estimate, or if the estimate is longer than [`MAX_ESTIMATE`].
+fn estimate_remaining(elapsed: Duration, fraction: f32) -> Option<Duration> {
+    if elapsed < ESTIMATE_WARMUP || fraction <= 0. {
+        return None;
+    }
+    let total = elapsed.as_secs_f32() / fraction;
+    Duration::try_from_secs_f32((total - elapsed.as_secs_f32()).max(0.))
+        .ok()
+        .filter(|remaining| *remaining <= MAX_ESTIMATE)
+}
+
+fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_secs();
+    if seconds < 60 {
+        format!("About {} seconds remaining", seconds.max(1))
+    } else if seconds < 60 * 60 {
+        format!("About {} minutes remaining", seconds.div_ceil(60))
+    } else {
+        format!("About {} hours remaining", seconds.div_ceil(60 * 60))
+    }
+}
+
+#[test]
+fn tiny_progress_has_no_estimate() {
+    let elapsed = ESTIMATE_WARMUP * 2;
+    assert_eq!(estimate_remaining(elapsed, 1e-9), None);
+    assert_eq!(estimate_remaining(elapsed, f32::MIN_POSITIVE), None);
+    assert_eq!(estimate_remaining(elapsed, 0.5), Some(elapsed));
+    assert_eq!(estimate_remaining(elapsed, 1.), Some(Duration::ZERO));
+    assert_eq!(estimate_remaining(ESTIMATE_WARMUP / 2, 0.5), None);
+}
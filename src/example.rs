@@ -124,6 +124,10 @@ impl Example {
                 .save(&path)
                 .expect("error saving file");
             println!("Wrote {}", path.display());
+            let path = target_dir().join(format!("{}.tree.txt", self.name));
+            std::fs::write(&path, self.recorder.tree_snapshot().to_string())
+                .expect("error saving file");
+            println!("Wrote {}", path.display());
 
             if errored {
                 std::process::exit(-1);
@@ -1,11 +1,13 @@
 //! Types for loading fonts to use in Cushy.
 use std::sync::Arc;
 
+use ahash::AHashMap;
 use alot::{LotId, Lots};
 use kludgine::cosmic_text::fontdb::{self, Language};
-use kludgine::cosmic_text::{Stretch, Style, Weight};
+use kludgine::cosmic_text::{FamilyOwned, Stretch, Style, Weight};
 
-use crate::reactive::value::Dynamic;
+use crate::reactive::value::{Dynamic, DynamicRead};
+use crate::styles::FontFamilyList;
 
 /// A collection of fonts that can be loaded into Cushy.
 #[derive(Clone, Default, PartialEq)]
@@ -34,6 +36,49 @@ impl FontCollection {
         self.push_inner(font_data);
     }
 
+    /// Pushes `font_data` into this collection, delaying loading it until
+    /// after a window using this collection has rendered its first frame.
+    ///
+    /// Parsing font data can take a noticeable amount of time. Fonts that
+    /// aren't needed to display the first frame, such as fonts only used in
+    /// dialogs or secondary screens, can be deferred to shorten the time it
+    /// takes for a window to first appear.
+    pub fn push_deferred(&self, font_data: Vec<u8>) {
+        self.0.lock().deferred.push(Arc::new(font_data));
+    }
+
+    /// Adds `font_data` to this collection to be loaded after the first frame
+    /// has been rendered and returns self.
+    ///
+    /// See [`FontCollection::push_deferred`] for more information.
+    #[must_use]
+    pub fn with_deferred(self, font_data: Vec<u8>) -> Self {
+        self.push_deferred(font_data);
+        self
+    }
+
+    /// Moves all deferred fonts into this collection, returning true if any
+    /// fonts were moved.
+    pub(crate) fn load_deferred(&self) -> bool {
+        if self.0.read().deferred.is_empty() {
+            return false;
+        }
+
+        let mut data = self.0.lock();
+        for font in std::mem::take(&mut data.deferred) {
+            data.fonts.push(font);
+        }
+        true
+    }
+
+    /// Pushes `font_data` containing an icon font whose family name is
+    /// `family` into this collection, returning an [`IconFont`] that can be
+    /// used to display its icons.
+    pub fn push_icon_font(&self, family: impl Into<String>, font_data: Vec<u8>) -> IconFont {
+        self.push(font_data);
+        IconFont::new(family)
+    }
+
     fn push_inner(&self, font_data: Vec<u8>) -> LotId {
         self.0.lock().fonts.push(Arc::new(font_data))
     }
@@ -63,6 +108,7 @@ impl<'a> Iterator for FontIter<'a> {
 #[derive(Default)]
 pub(crate) struct FontCollectionData {
     fonts: Lots<Arc<Vec<u8>>>,
+    deferred: Vec<Arc<Vec<u8>>>,
 }
 
 impl FontCollectionData {
@@ -127,3 +173,178 @@ pub struct LoadedFontFace {
     /// The stretch of the font face.
     pub stretch: Stretch,
 }
+
+/// Information about a font face that is available to render text.
+///
+/// Font faces are listed using
+/// [`GraphicsContext::font_faces()`](crate::context::GraphicsContext::font_faces).
+#[derive(Debug, Clone)]
+pub struct FontFaceInfo {
+    /// The font database ID for this face.
+    pub id: fontdb::ID,
+    /// The names of the families contained in this face, and the corresponding
+    /// language of the name.
+    pub families: Vec<(String, Language)>,
+    /// The PostScript name of this face.
+    pub post_script_name: String,
+    /// The weight of the font face.
+    pub weight: Weight,
+    /// The style of the font face.
+    pub style: Style,
+    /// The stretch of the font face.
+    pub stretch: Stretch,
+    /// True if every glyph in this face has the same advance width.
+    pub monospaced: bool,
+    /// Where this face was loaded from.
+    pub source: FontFaceSource,
+    /// The metrics of this face, or `None` if the face's data could not be
+    /// loaded.
+    pub metrics: Option<FontMetrics>,
+}
+
+/// The origin of a [`FontFaceInfo`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FontFaceSource {
+    /// The face was provided by the operating system or the font system's
+    /// defaults.
+    System,
+    /// The face was loaded from the application's [`FontCollection`].
+    App,
+    /// The face was loaded from the window's [`FontCollection`].
+    Window,
+}
+
+/// Measurements of a font face, in font units.
+///
+/// Font units can be converted to pixels by multiplying by the font size and
+/// dividing by [`units_per_em`](Self::units_per_em).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct FontMetrics {
+    /// The number of font units in one em.
+    pub units_per_em: u16,
+    /// The distance from the baseline to the top of the tallest glyphs.
+    pub ascender: i16,
+    /// The distance from the baseline to the bottom of the lowest glyphs. This
+    /// value is typically negative.
+    pub descender: i16,
+    /// The recommended additional space between lines.
+    pub line_gap: i16,
+    /// The height of lowercase letters, if specified by the face.
+    pub x_height: Option<i16>,
+    /// The height of capital letters, if specified by the face.
+    pub capital_height: Option<i16>,
+}
+
+/// A font that contains icons, such as Material Symbols or Font Awesome.
+///
+/// Icons can be displayed using an [`Icon`](crate::widgets::Icon) by codepoint
+/// or by name. Names are registered using [`IconFont::with_icon`]. Names that
+/// have not been registered are rendered as text, which allows fonts that use
+/// ligatures to map names to icons, such as Material Symbols, to be used
+/// without registering any names.
+///
+/// The font data must be loaded separately, either by using
+/// [`FontCollection::push_icon_font`] or by pushing the data into a
+/// [`FontCollection`] directly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconFont {
+    family: FontFamilyList,
+    icons: Arc<AHashMap<String, char>>,
+}
+
+impl IconFont {
+    /// Returns an icon font that uses the font family named `family`.
+    #[must_use]
+    pub fn new(family: impl Into<String>) -> Self {
+        Self {
+            family: FontFamilyList::from(FamilyOwned::Name(family.into())),
+            icons: Arc::default(),
+        }
+    }
+
+    /// Registers `codepoint` as the icon named `name` and returns self.
+    #[must_use]
+    pub fn with_icon(mut self, name: impl Into<String>, codepoint: char) -> Self {
+        Arc::make_mut(&mut self.icons).insert(name.into(), codepoint);
+        self
+    }
+
+    /// Registers each name and codepoint pair in `icons` and returns self.
+    #[must_use]
+    pub fn with_icons<Name>(mut self, icons: impl IntoIterator<Item = (Name, char)>) -> Self
+    where
+        Name: Into<String>,
+    {
+        Arc::make_mut(&mut self.icons).extend(
+            icons
+                .into_iter()
+                .map(|(name, codepoint)| (name.into(), codepoint)),
+        );
+        self
+    }
+
+    /// Returns the font family list used to render this font's icons.
+    #[must_use]
+    pub const fn family(&self) -> &FontFamilyList {
+        &self.family
+    }
+
+    /// Returns the codepoint registered for `name`, if one has been
+    /// registered.
+    #[must_use]
+    pub fn icon(&self, name: &str) -> Option<char> {
+        self.icons.get(name).copied()
+    }
+
+    /// Returns the text to render to display the icon named `name`.
+    pub(crate) fn text_for(&self, name: &str) -> String {
+        self.icon(name)
+            .map_or_else(|| name.to_string(), String::from)
+    }
+}
+
+#[test]
+fn icon_font_names() {
+    let font = IconFont::new("Icons")
+        .with_icon("save", '\u{e161}')
+        .with_icons([("open", '\u{e2c7}'), ("close", '\u{e5cd}')]);
+    let original = font.clone();
+    let font = font.with_icon("save", '\u{e162}');
+
+    assert_eq!(font.icon("save"), Some('\u{e162}'));
+    assert_eq!(font.icon("open"), Some('\u{e2c7}'));
+    assert_eq!(font.icon("missing"), None);
+    assert_eq!(font.text_for("close"), "\u{e5cd}");
+    // Unregistered names are rendered as text, supporting ligature fonts.
+    assert_eq!(font.text_for("home"), "home");
+    // Registering icons does not affect clones of the font.
+    assert_eq!(original.icon("save"), Some('\u{e161}'));
+    assert_eq!(
+        font.family(),
+        &FontFamilyList::from(FamilyOwned::Name(String::from("Icons")))
+    );
+}
+
+#[test]
+fn deferred_fonts_load_once() {
+    let collection = FontCollection::default()
+        .with(vec![1])
+        .with_deferred(vec![2])
+        .with_deferred(vec![3]);
+    let loaded = |collection: &FontCollection| {
+        let data = collection.0.read();
+        let mut fonts = data
+            .fonts(collection)
+            .map(|(_, data)| data[0])
+            .collect::<Vec<_>>();
+        fonts.sort_unstable();
+        fonts
+    };
+    assert_eq!(loaded(&collection), [1]);
+
+    assert!(collection.load_deferred());
+    assert_eq!(loaded(&collection), [1, 2, 3]);
+
+    assert!(!collection.load_deferred());
+    assert_eq!(loaded(&collection), [1, 2, 3]);
+}
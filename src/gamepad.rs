@@ -0,0 +1,455 @@
+//! Input from game controllers.
+//!
+//! Controllers are polled on a background thread using [`gilrs`] once the
+//! first window is opened or [`connected()`] is first called. The state of
+//! each controller's buttons and axes is exposed as [`Dynamic`]s, allowing
+//! widgets to react to controller input.
+//!
+//! While a window is focused, controllers can also drive its interface:
+//!
+//! - The D-pad and left stick move focus in the pressed direction, using the
+//!   same search as
+//!   [`EventContext::focus_in_direction()`](crate::context::EventContext::focus_in_direction).
+//! - The [confirm button](CONFIRM_BUTTON) activates the focused widget, or the
+//!   window's default widget if no widget is focused, similar to pressing
+//!   Space or Enter.
+//! - The [cancel button](CANCEL_BUTTON) activates the window's escape widget,
+//!   similar to pressing Escape.
+//!
+//! ```rust,no_run
+//! use cushy::gamepad::{self, Button};
+//! use cushy::reactive::value::Source;
+//!
+//! if let Some(gamepad) = gamepad::connected().get().first() {
+//!     let start_pressed = gamepad.button(Button::Start);
+//!     let trigger = gamepad.button_value(Button::RightTrigger2);
+//! }
+//! ```
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, OnceLock};
+
+use ahash::AHashMap;
+pub use gilrs::{Axis, Button};
+use gilrs::{EventType, GamepadId, Gilrs};
+use parking_lot::Mutex;
+
+use crate::context::FocusDirection;
+use crate::reactive::value::{Destination, Dynamic, Source};
+use crate::window::{WindowHandle, WindowsRegistry};
+
+/// The button that activates the focused widget.
+///
+/// On most controllers, this is the bottom face button: A on Xbox controllers
+/// and Cross on PlayStation controllers.
+pub const CONFIRM_BUTTON: Button = Button::South;
+
+/// The button that activates a window's escape widget.
+///
+/// On most controllers, this is the right face button: B on Xbox controllers
+/// and Circle on PlayStation controllers.
+pub const CANCEL_BUTTON: Button = Button::East;
+
+/// How far a stick must be pushed before it moves focus.
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// How far a pushed stick must return towards the center before it can move
+/// focus again.
+///
+/// This is lower than [`STICK_THRESHOLD`] so that a stick resting near the
+/// threshold doesn't move focus repeatedly.
+const STICK_RELEASE_THRESHOLD: f32 = 0.3;
+
+/// Returns the currently connected controllers.
+///
+/// The returned value is updated as controllers are connected and
+/// disconnected.
+#[must_use]
+pub fn connected() -> Dynamic<Vec<Gamepad>> {
+    state().connected.clone()
+}
+
+/// A connected game controller.
+#[derive(Clone)]
+pub struct Gamepad(Arc<GamepadData>);
+
+struct GamepadData {
+    id: GamepadId,
+    name: String,
+    connected: Dynamic<bool>,
+    buttons: Mutex<AHashMap<Button, Dynamic<f32>>>,
+    axes: Mutex<AHashMap<Axis, Dynamic<f32>>>,
+    stick: Mutex<StickPosition>,
+}
+
+impl Gamepad {
+    fn new(id: GamepadId, name: String) -> Self {
+        Self(Arc::new(GamepadData {
+            id,
+            name,
+            connected: Dynamic::new(true),
+            buttons: Mutex::default(),
+            axes: Mutex::default(),
+            stick: Mutex::default(),
+        }))
+    }
+
+    /// Returns the name of this controller.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Returns a dynamic that is true while this controller is connected.
+    #[must_use]
+    pub fn is_connected(&self) -> Dynamic<bool> {
+        self.0.connected.clone()
+    }
+
+    /// Returns how far `button` is pressed, ranging from `0.0` to `1.0`.
+    ///
+    /// Most buttons are either `0.0` or `1.0`, while analog triggers report
+    /// values in between.
+    #[must_use]
+    pub fn button_value(&self, button: Button) -> Dynamic<f32> {
+        self.0.buttons.lock().entry(button).or_default().clone()
+    }
+
+    /// Returns a dynamic that is true while `button` is pressed.
+    #[must_use]
+    pub fn button(&self, button: Button) -> Dynamic<bool> {
+        self.button_value(button).map_each(|value| *value > 0.5)
+    }
+
+    /// Returns the position of `axis`, ranging from `-1.0` to `1.0`.
+    ///
+    /// For sticks, positive values are up and to the right.
+    #[must_use]
+    pub fn axis(&self, axis: Axis) -> Dynamic<f32> {
+        self.0.axes.lock().entry(axis).or_default().clone()
+    }
+
+    fn button_changed(&self, button: Button, value: f32) {
+        self.button_value(button).set(value);
+    }
+
+    /// Updates `axis` and returns the direction focus should move, if the
+    /// left stick has been pushed past the threshold.
+    fn axis_changed(&self, axis: Axis, value: f32) -> Option<FocusDirection> {
+        self.axis(axis).set(value);
+        self.0.stick.lock().update(axis, value)
+    }
+}
+
+/// The direction the left stick is pushed along each axis: `-1`, `0`, or `1`.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+struct StickPosition([i8; 2]);
+
+impl StickPosition {
+    /// Updates `axis` and returns the direction focus should move if the stick
+    /// was newly pushed past the threshold.
+    ///
+    /// Focus only moves once each time the stick is pushed. The stick must
+    /// return within [`STICK_RELEASE_THRESHOLD`] of the center before it can
+    /// move focus in the same direction again.
+    fn update(&mut self, axis: Axis, value: f32) -> Option<FocusDirection> {
+        let (index, negative, positive) = match axis {
+            Axis::LeftStickX => (0, FocusDirection::Left, FocusDirection::Right),
+            Axis::LeftStickY => (1, FocusDirection::Down, FocusDirection::Up),
+            _ => return None,
+        };
+        let position = if value >= STICK_THRESHOLD {
+            1
+        } else if value <= -STICK_THRESHOLD {
+            -1
+        } else if value.abs() > STICK_RELEASE_THRESHOLD {
+            self.0[index]
+        } else {
+            0
+        };
+        if self.0[index] == position {
+            return None;
+        }
+        self.0[index] = position;
+        match position {
+            1 => Some(positive),
+            -1 => Some(negative),
+            _ => None,
+        }
+    }
+}
+
+impl Debug for Gamepad {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Gamepad")
+            .field("id", &self.0.id)
+            .field("name", &self.0.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Gamepad {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// An action a controller performs on the focused window.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum GamepadIntent {
+    /// The confirm button was pressed or released.
+    Confirm(bool),
+    /// The cancel button was pressed or released.
+    Cancel(bool),
+    /// Focus should move in a direction.
+    Navigate(FocusDirection),
+}
+
+struct GamepadState {
+    connected: Dynamic<Vec<Gamepad>>,
+    windows: Mutex<Vec<GamepadWindow>>,
+}
+
+/// A window that controllers can drive.
+struct GamepadWindow {
+    handle: WindowHandle,
+    focused: Dynamic<bool>,
+    open_windows: WindowsRegistry,
+}
+
+impl GamepadState {
+    fn gamepad(&self, id: GamepadId) -> Option<Gamepad> {
+        self.connected
+            .map_ref(|gamepads| gamepads.iter().find(|gamepad| gamepad.0.id == id).cloned())
+    }
+
+    fn connect(&self, id: GamepadId, name: &str) {
+        if self.gamepad(id).is_none() {
+            self.connected
+                .lock()
+                .push(Gamepad::new(id, name.to_string()));
+        }
+    }
+
+    fn disconnect(&self, id: GamepadId) {
+        let mut connected = self.connected.lock();
+        if let Some(index) = connected.iter().position(|gamepad| gamepad.0.id == id) {
+            let gamepad = connected.remove(index);
+            drop(connected);
+            gamepad.0.connected.set(false);
+        }
+    }
+
+    /// Sends `intent` to the focused window.
+    ///
+    /// Windows that have been closed are forgotten.
+    fn send(&self, intent: GamepadIntent) {
+        self.windows.lock().retain(|window| {
+            if !window.open_windows.contains(&window.handle) {
+                false
+            } else if window.focused.get() {
+                window.handle.send_gamepad_intent(intent)
+            } else {
+                true
+            }
+        });
+    }
+}
+
+fn state() -> &'static GamepadState {
+    static STATE: OnceLock<GamepadState> = OnceLock::new();
+    STATE.get_or_init(|| {
+        std::thread::Builder::new()
+            .name(String::from("cushy-gamepad"))
+            .spawn(poll_gamepads)
+            .expect("error spawning gamepad thread");
+        GamepadState {
+            connected: Dynamic::default(),
+            windows: Mutex::default(),
+        }
+    })
+}
+
+/// Allows controllers to drive `window` while `focused` is true.
+///
+/// `window` stops receiving input once it is removed from `open_windows`.
+pub(crate) fn register_window(
+    window: WindowHandle,
+    focused: Dynamic<bool>,
+    open_windows: WindowsRegistry,
+) {
+    state().windows.lock().push(GamepadWindow {
+        handle: window,
+        focused,
+        open_windows,
+    });
+}
+
+fn poll_gamepads() {
+    let mut gilrs = match Gilrs::new() {
+        Ok(gilrs) => gilrs,
+        Err(gilrs::Error::NotImplemented(gilrs)) => {
+            tracing::warn!("gamepads are not supported on this platform");
+            gilrs
+        }
+        Err(err) => {
+            tracing::error!("error initializing gamepad support: {err}");
+            return;
+        }
+    };
+    let state = state();
+
+    for (id, gamepad) in gilrs.gamepads() {
+        state.connect(id, gamepad.name());
+    }
+
+    while let Some(event) = gilrs.next_event_blocking(None) {
+        let id = event.id;
+        match event.event {
+            EventType::Connected => state.connect(id, gilrs.gamepad(id).name()),
+            EventType::Disconnected => state.disconnect(id),
+            EventType::ButtonChanged(button, value, _) => {
+                if let Some(gamepad) = state.gamepad(id) {
+                    gamepad.button_changed(button, value);
+                }
+            }
+            EventType::ButtonPressed(button, _) => {
+                if let Some(intent) = button_intent(button, true) {
+                    state.send(intent);
+                }
+            }
+            EventType::ButtonReleased(button, _) => {
+                if let Some(intent) = button_intent(button, false) {
+                    state.send(intent);
+                }
+            }
+            EventType::AxisChanged(axis, value, _) => {
+                if let Some(direction) = state
+                    .gamepad(id)
+                    .and_then(|gamepad| gamepad.axis_changed(axis, value))
+                {
+                    state.send(GamepadIntent::Navigate(direction));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn button_intent(button: Button, pressed: bool) -> Option<GamepadIntent> {
+    match button {
+        CONFIRM_BUTTON => Some(GamepadIntent::Confirm(pressed)),
+        CANCEL_BUTTON => Some(GamepadIntent::Cancel(pressed)),
+        Button::DPadUp if pressed => Some(GamepadIntent::Navigate(FocusDirection::Up)),
+        Button::DPadDown if pressed => Some(GamepadIntent::Navigate(FocusDirection::Down)),
+        Button::DPadLeft if pressed => Some(GamepadIntent::Navigate(FocusDirection::Left)),
+        Button::DPadRight if pressed => Some(GamepadIntent::Navigate(FocusDirection::Right)),
+        _ => None,
+    }
+}
+
+#[test]
+fn button_intents() {
+    assert_eq!(
+        button_intent(CONFIRM_BUTTON, true),
+        Some(GamepadIntent::Confirm(true))
+    );
+    assert_eq!(
+        button_intent(CONFIRM_BUTTON, false),
+        Some(GamepadIntent::Confirm(false))
+    );
+    assert_eq!(
+        button_intent(CANCEL_BUTTON, true),
+        Some(GamepadIntent::Cancel(true))
+    );
+    assert_eq!(
+        button_intent(CANCEL_BUTTON, false),
+        Some(GamepadIntent::Cancel(false))
+    );
+    for (button, direction) in [
+        (Button::DPadUp, FocusDirection::Up),
+        (Button::DPadDown, FocusDirection::Down),
+        (Button::DPadLeft, FocusDirection::Left),
+        (Button::DPadRight, FocusDirection::Right),
+    ] {
+        assert_eq!(
+            button_intent(button, true),
+            Some(GamepadIntent::Navigate(direction))
+        );
+        // Releasing the D-pad doesn't move focus a second time.
+        assert_eq!(button_intent(button, false), None);
+    }
+    assert_eq!(button_intent(Button::Start, true), None);
+}
+
+#[test]
+fn stick_navigation_threshold_and_hysteresis() {
+    let mut stick = StickPosition::default();
+    // Small movements are ignored.
+    assert_eq!(stick.update(Axis::LeftStickX, 0.4), None);
+    // Crossing the threshold moves focus once.
+    assert_eq!(
+        stick.update(Axis::LeftStickX, 0.6),
+        Some(FocusDirection::Right)
+    );
+    assert_eq!(stick.update(Axis::LeftStickX, 0.9), None);
+    // Dipping just below the threshold doesn't release the stick.
+    assert_eq!(stick.update(Axis::LeftStickX, 0.45), None);
+    assert_eq!(stick.update(Axis::LeftStickX, 0.6), None);
+    // Returning towards the center allows moving focus again.
+    assert_eq!(stick.update(Axis::LeftStickX, 0.1), None);
+    assert_eq!(
+        stick.update(Axis::LeftStickX, 0.7),
+        Some(FocusDirection::Right)
+    );
+    // Flicking to the other side moves focus immediately.
+    assert_eq!(
+        stick.update(Axis::LeftStickX, -0.7),
+        Some(FocusDirection::Left)
+    );
+
+    // Each axis is tracked independently, and positive Y is up.
+    assert_eq!(
+        stick.update(Axis::LeftStickY, 0.8),
+        Some(FocusDirection::Up)
+    );
+    assert_eq!(stick.update(Axis::LeftStickY, 0.0), None);
+    assert_eq!(
+        stick.update(Axis::LeftStickY, -0.8),
+        Some(FocusDirection::Down)
+    );
+    assert_eq!(stick.update(Axis::LeftStickX, -0.8), None);
+
+    // Other axes never move focus.
+    assert_eq!(stick.update(Axis::RightStickX, 1.0), None);
+}
+
+#[test]
+fn closed_windows_are_forgotten() {
+    let open_windows = WindowsRegistry::default();
+    let open = crate::window::PendingWindow::default().handle();
+    let closed = crate::window::PendingWindow::default().handle();
+    open_windows.register(open.clone());
+    let state = GamepadState {
+        connected: Dynamic::default(),
+        windows: Mutex::new(vec![
+            GamepadWindow {
+                handle: open.clone(),
+                focused: Dynamic::new(false),
+                open_windows: open_windows.clone(),
+            },
+            GamepadWindow {
+                handle: closed,
+                focused: Dynamic::new(false),
+                open_windows: open_windows.clone(),
+            },
+        ]),
+    };
+
+    state.send(GamepadIntent::Confirm(true));
+    assert_eq!(state.windows.lock().len(), 1);
+
+    open_windows.unregister(&open);
+    state.send(GamepadIntent::Confirm(false));
+    assert!(state.windows.lock().is_empty());
+}
@@ -0,0 +1,274 @@
+//! Keyboard shortcuts that are handled while no window is focused.
+//!
+//! Global hotkeys are registered with the operating system using
+//! [`global_hotkey`], allowing an app to respond to a shortcut even while
+//! another app is focused. This is commonly used to summon a utility app's
+//! window. Callbacks are invoked on the app's main thread.
+//!
+//! Global hotkeys are supported on Windows, macOS, and Linux using X11.
+//!
+//! ```rust,no_run
+//! use cushy::global_hotkeys::{Code, HotKey, Modifiers};
+//! use cushy::{App, Application, Open, PendingApp, Run};
+//!
+//! let app = PendingApp::default();
+//! app.as_app()
+//!     .global_hotkeys()
+//!     .register(
+//!         HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Space),
+//!         |app: &mut App| {
+//!             let _ = "Summoned!".open(app);
+//!         },
+//!     )
+//!     .persist();
+//! app.run().unwrap();
+//! ```
+
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use ahash::AHashMap;
+pub use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use parking_lot::Mutex;
+
+use crate::reactive::value::{Destination, Dynamic, DynamicReader};
+use crate::{App, Application};
+
+thread_local! {
+    // The manager must be created and used on the thread running the event
+    // loop on some platforms.
+    static MANAGER: RefCell<Option<GlobalHotKeyManager>> = const { RefCell::new(None) };
+}
+
+type HotkeyCallback = Arc<Mutex<dyn FnMut(&mut App) + Send>>;
+
+/// The callbacks for the registered hotkeys of an app, keyed by hotkey id.
+#[derive(Clone, Default)]
+pub(crate) struct HotkeyCallbacks(Arc<Mutex<AHashMap<u32, HotkeyCallback>>>);
+
+impl HotkeyCallbacks {
+    /// Returns the callback to invoke when the hotkey with `id` changes to
+    /// `state`, if any.
+    fn callback_for(&self, id: u32, state: HotKeyState) -> Option<HotkeyCallback> {
+        if state == HotKeyState::Pressed {
+            self.0.lock().get(&id).cloned()
+        } else {
+            None
+        }
+    }
+}
+
+/// The global hotkeys registered by an [`App`].
+///
+/// This type is returned from [`App::global_hotkeys()`].
+#[derive(Clone)]
+pub struct GlobalHotkeys {
+    app: App,
+}
+
+impl GlobalHotkeys {
+    pub(crate) const fn new(app: App) -> Self {
+        Self { app }
+    }
+
+    /// Registers `hotkey` with the operating system, invoking `on_pressed` on
+    /// the app's main thread each time it is pressed.
+    ///
+    /// Registration is performed on the app's main thread. The returned
+    /// handle's [`status()`](GlobalHotkey::status) reports whether the
+    /// registration succeeded. Registration can fail if another app has
+    /// already registered the same hotkey.
+    ///
+    /// The hotkey is unregistered when the returned handle is dropped, unless
+    /// [`GlobalHotkey::persist`] is called.
+    pub fn register<F>(&self, hotkey: HotKey, on_pressed: F) -> GlobalHotkey
+    where
+        F: FnMut(&mut App) + Send + 'static,
+    {
+        let status = Dynamic::new(HotkeyStatus::Pending);
+        let callback: HotkeyCallback = Arc::new(Mutex::new(on_pressed));
+        let app = self.app.clone();
+        let sent = self.app.execute({
+            let status = status.clone();
+            move |_| {
+                let result = with_manager(&app, |manager| manager.register(hotkey));
+                match result {
+                    Ok(()) => {
+                        app.cushy()
+                            .data
+                            .global_hotkeys
+                            .0
+                            .lock()
+                            .insert(hotkey.id(), callback);
+                        status.set(HotkeyStatus::Registered);
+                    }
+                    Err(err) => {
+                        tracing::error!("error registering global hotkey {hotkey:?}: {err}");
+                        status.set(HotkeyStatus::Failed(err.to_string()));
+                    }
+                }
+            }
+        });
+        if !sent {
+            status.set(HotkeyStatus::Failed(String::from("the app is not running")));
+        }
+
+        GlobalHotkey {
+            hotkey,
+            status: status.into_reader(),
+            app: Some(self.app.clone()),
+        }
+    }
+}
+
+impl Debug for GlobalHotkeys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlobalHotkeys")
+            .field(
+                "registered",
+                &self.app.cushy().data.global_hotkeys.0.lock().len(),
+            )
+            .finish()
+    }
+}
+
+/// Invokes `callback` with this thread's hotkey manager, creating it if
+/// needed.
+fn with_manager<R>(
+    app: &App,
+    callback: impl FnOnce(&GlobalHotKeyManager) -> Result<R, global_hotkey::Error>,
+) -> Result<R, global_hotkey::Error> {
+    MANAGER.with_borrow_mut(|manager| {
+        let manager = if let Some(manager) = manager {
+            manager
+        } else {
+            let callbacks = app.cushy().data.global_hotkeys.clone();
+            let app = Mutex::new(app.clone());
+            // The event handler may be invoked from a background thread.
+            GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+                let Some(callback) = callbacks.callback_for(event.id, event.state) else {
+                    return;
+                };
+                let app = app.lock().clone();
+                let mut callback_app = app.clone();
+                let _ = app.execute(move |_| {
+                    (callback.lock())(&mut callback_app);
+                });
+            }));
+            manager.insert(GlobalHotKeyManager::new()?)
+        };
+        callback(manager)
+    })
+}
+
+/// The registration status of a [`GlobalHotkey`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum HotkeyStatus {
+    /// The hotkey has not been registered yet.
+    Pending,
+    /// The hotkey is registered and will invoke its callback when pressed.
+    Registered,
+    /// The hotkey could not be registered.
+    Failed(String),
+}
+
+/// A handle to a hotkey registered with [`GlobalHotkeys::register`].
+///
+/// The hotkey is unregistered when this handle is dropped.
+#[must_use = "the hotkey is unregistered when the handle is dropped"]
+pub struct GlobalHotkey {
+    hotkey: HotKey,
+    status: DynamicReader<HotkeyStatus>,
+    app: Option<App>,
+}
+
+impl GlobalHotkey {
+    /// Returns the hotkey this handle registered.
+    #[must_use]
+    pub const fn hotkey(&self) -> HotKey {
+        self.hotkey
+    }
+
+    /// Returns a reader of this hotkey's registration status.
+    #[must_use]
+    pub fn status(&self) -> DynamicReader<HotkeyStatus> {
+        self.status.clone()
+    }
+
+    /// Keeps this hotkey registered until the app exits.
+    pub fn persist(mut self) {
+        self.app = None;
+    }
+}
+
+impl Debug for GlobalHotkey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlobalHotkey")
+            .field("hotkey", &self.hotkey)
+            .field("status", &self.status)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for GlobalHotkey {
+    fn drop(&mut self) {
+        let Some(app) = self.app.take() else {
+            return;
+        };
+        let hotkey = self.hotkey;
+        // Unregistering is performed on the main thread after any pending
+        // registration has completed.
+        let _ = app.clone().execute(move |_| {
+            let registered = app
+                .cushy()
+                .data
+                .global_hotkeys
+                .0
+                .lock()
+                .remove(&hotkey.id())
+                .is_some();
+            if registered {
+                if let Err(err) = with_manager(&app, |manager| manager.unregister(hotkey)) {
+                    tracing::error!("error unregistering global hotkey {hotkey:?}: {err}");
+                }
+            }
+        });
+    }
+}
+
+#[test]
+fn registering_requires_a_running_app() {
+    use crate::reactive::value::Source;
+
+    let app = App::virtual_app(crate::window::VirtualWindowRequests::default());
+    let hotkey = HotKey::new(Some(Modifiers::CONTROL), Code::KeyK);
+    let registered = app.global_hotkeys().register(hotkey, |_| {});
+    assert_eq!(registered.hotkey(), hotkey);
+    assert_eq!(
+        registered.status().get(),
+        HotkeyStatus::Failed(String::from("the app is not running"))
+    );
+    assert!(app.cushy().data.global_hotkeys.0.lock().is_empty());
+}
+
+#[test]
+fn callbacks_are_invoked_on_press() {
+    let callbacks = HotkeyCallbacks::default();
+    let hotkey = HotKey::new(None, Code::F13);
+    callbacks
+        .0
+        .lock()
+        .insert(hotkey.id(), Arc::new(Mutex::new(|_: &mut App| {})));
+
+    assert!(callbacks
+        .callback_for(hotkey.id(), HotKeyState::Pressed)
+        .is_some());
+    assert!(callbacks
+        .callback_for(hotkey.id(), HotKeyState::Released)
+        .is_none());
+    assert!(callbacks
+        .callback_for(HotKey::new(None, Code::F14).id(), HotKeyState::Pressed)
+        .is_none());
+}
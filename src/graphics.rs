@@ -4,8 +4,8 @@ use std::ops::{Deref, DerefMut};
 
 use figures::units::{Px, UPx};
 use figures::{
-    self, Fraction, IntoSigned, IntoUnsigned, Point, Rect, Round, ScreenScale, ScreenUnit, Size,
-    Zero,
+    self, Angle, FloatConversion, Fraction, IntoSigned, IntoUnsigned, Point, Rect, Round,
+    ScreenScale, ScreenUnit, Size, Zero,
 };
 use intentional::Assert;
 use kempt::{map, Map};
@@ -19,15 +19,28 @@ use kludgine::{
 };
 
 use crate::animation::ZeroToOne;
-use crate::fonts::{FontCollection, LoadedFontFace, LoadedFontId};
+use crate::fonts::{
+    FontCollection, FontFaceInfo, FontFaceSource, FontMetrics, LoadedFontFace, LoadedFontId,
+};
 use crate::reactive::value::{DynamicRead, Generation, Source};
 use crate::styles::FontFamilyList;
 
+mod gradient;
+mod path;
+mod transform;
+
+pub use kludgine::shapes::{LineCap, LineJoin};
+
+pub use self::gradient::{Brush, ColorStop, Gradient, GradientShape};
+pub use self::path::{Contour, DashPattern, Path, PathBuilder, Segment, Stroke};
+pub use self::transform::Transform;
+
 /// A 2d graphics context
 pub struct Graphics<'clip, 'gfx, 'pass> {
     renderer: RenderContext<'clip, 'gfx, 'pass>,
     region: Rect<Px>,
     pub(crate) opacity: ZeroToOne,
+    transform: Option<Transform>,
 }
 
 enum RenderContext<'clip, 'gfx, 'pass> {
@@ -43,6 +56,7 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
             region: renderer.clip_rect().into_signed(),
             renderer: RenderContext::Renderer(renderer),
             opacity: ZeroToOne::ONE,
+            transform: None,
         }
     }
 
@@ -54,6 +68,9 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
     ///
     /// In general, this is handled automatically. This function should only be
     /// needed when using [`inner_graphics()`](Self::inner_graphics).
+    ///
+    /// This offset does not include the effects of any
+    /// [`transform()`](Self::transform).
     #[must_use]
     pub fn translation(&self) -> Point<Px> {
         let clip_origin = self.renderer.clip_rect().origin.into_signed();
@@ -82,22 +99,10 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
     /// operations will be relative to the origin of `clip`.
     pub fn clipped_to(&mut self, clip: Rect<Px>) -> Graphics<'_, 'gfx, 'pass> {
         let region = clip + self.region.origin;
-
-        // If the current region has a negative component, we need to adjust the
-        // clipped rect before we perform an intersection in unsigned space.
-        let mut effective_region = region;
-        if region.origin.x < 0 {
-            effective_region.size.width += region.origin.x;
-            effective_region.origin.x = Px::ZERO;
-        }
-        if region.origin.y < 0 {
-            effective_region.size.height += region.origin.y;
-            effective_region.origin.y = Px::ZERO;
-        }
         let new_clip = self
             .renderer
             .clip_rect()
-            .intersection(&effective_region.into_unsigned())
+            .intersection(&effective_clip(region, self.transform))
             .map(|intersection| intersection - self.renderer.clip_rect().origin)
             .unwrap_or_default();
 
@@ -105,6 +110,64 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
             renderer: RenderContext::Clipped(self.renderer.clipped_to(new_clip)),
             region,
             opacity: self.opacity,
+            transform: self.transform,
+        }
+    }
+
+    /// Returns the transform applied to all drawing operations, if any.
+    ///
+    /// The transform maps the window coordinates that [`region()`](Self::region)
+    /// is expressed in to the window coordinates that are ultimately rendered
+    /// to.
+    #[must_use]
+    pub fn transform(&self) -> Option<Transform> {
+        self.transform
+    }
+
+    /// Applies `transform` to all future drawing operations, after any
+    /// existing transform.
+    ///
+    /// `transform` operates in window coordinates. Text, shapes, and textured
+    /// shapes are fully transformed. Textures drawn with
+    /// [`draw_texture()`](Self::draw_texture) are drawn unrotated, scaled to
+    /// fill the transformed destination's bounding box. Custom
+    /// [`RenderOperation`]s and drawing performed using
+    /// [`inner_graphics()`](Self::inner_graphics) are not transformed.
+    pub fn apply_transform(&mut self, transform: Transform) {
+        self.transform = Some(
+            self.transform
+                .map_or(transform, |existing| transform.then(existing)),
+        );
+    }
+
+    /// Sets the transform applied to all drawing operations.
+    pub fn set_transform(&mut self, transform: Option<Transform>) {
+        self.transform = transform;
+    }
+
+    /// Adjusts a drawable's placement to render relative to this context's
+    /// region, applying the current transform.
+    fn position<Unit>(
+        &self,
+        translation: &mut Point<Unit>,
+        rotation: &mut Option<Angle>,
+        scale: &mut Option<f32>,
+    ) where
+        Unit: ScreenUnit,
+    {
+        let pixel_scale = self.scale();
+        if let Some(transform) = &self.transform {
+            let clip_origin = self.renderer.clip_rect().origin.into_signed();
+            let logical = translation.into_px(pixel_scale) + self.region.origin;
+            *translation =
+                Point::<Unit>::from_px(transform.apply(logical) - clip_origin, pixel_scale);
+            *rotation = Some(Angle::degrees_f(
+                rotation.map_or(0., |rotation| rotation.into_degrees::<f32>())
+                    + transform.rotation.into_degrees::<f32>(),
+            ));
+            *scale = Some(scale.map_or(transform.scale, |scale| scale * transform.scale));
+        } else {
+            *translation += Point::<Unit>::from_px(self.translation(), pixel_scale);
         }
     }
 
@@ -150,13 +213,45 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
         self.renderer.scale()
     }
 
-    /// Fills the entire context with `color`.
+    /// Fills the entire context with `brush`.
+    ///
+    /// If `brush` is fully transparent, this function does nothing.
+    pub fn fill(&mut self, brush: impl Into<Brush>) {
+        let rect = Rect::from(self.region.size);
+        self.fill_rect(rect, brush);
+    }
+
+    /// Fills `rect` with `brush`.
     ///
-    /// If the alpha channel of `color` is 0, this function does nothing.
-    pub fn fill(&mut self, color: Color) {
-        if color.alpha() > 0 {
-            let rect = Rect::from(self.region.size);
-            self.draw_shape(&Shape::filled_rect(rect, color));
+    /// If `brush` is fully transparent, this function does nothing.
+    pub fn fill_rect<Unit>(&mut self, rect: Rect<Unit>, brush: impl Into<Brush>)
+    where
+        Unit: Zero + ShaderScalable + ScreenUnit + FloatConversion<Float = f32> + Copy,
+    {
+        match brush.into() {
+            Brush::Color(color) => {
+                if color.alpha() > 0 {
+                    self.draw_shape(&Shape::filled_rect(rect, color));
+                }
+            }
+            Brush::Gradient(gradient) => {
+                let bounds = Rect::new(
+                    rect.origin.into_float(),
+                    Size::new(rect.size.width.into_float(), rect.size.height.into_float()),
+                );
+                let top_left = bounds.origin;
+                let bottom_right = Point::new(
+                    top_left.x + bounds.size.width,
+                    top_left.y + bounds.size.height,
+                );
+                let outline = [
+                    top_left,
+                    Point::new(bottom_right.x, top_left.y),
+                    bottom_right,
+                    Point::new(top_left.x, bottom_right.y),
+                ];
+                self.fill_gradient::<Unit>(&gradient, &outline, bounds);
+            }
         }
     }
 
@@ -171,10 +266,102 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
                 .opacity
                 .map_or(*self.opacity, |opacity| opacity * *self.opacity),
         );
-        shape.translation += Point::<Unit>::from_px(self.translation(), self.scale());
+        self.position(
+            &mut shape.translation,
+            &mut shape.rotation,
+            &mut shape.scale,
+        );
         self.renderer.draw_shape(shape);
     }
 
+    /// Fills each contour of `path` with `brush`.
+    ///
+    /// When filling with a [`Gradient`], the gradient is positioned relative
+    /// to the bounding box of the entire path.
+    pub fn fill_path<Unit>(&mut self, path: &Path<Unit>, brush: impl Into<Brush>)
+    where
+        Unit: Zero + ShaderScalable + figures::ScreenUnit + FloatConversion<Float = f32> + Copy,
+    {
+        match brush.into() {
+            Brush::Color(color) => {
+                for contour in path.contours() {
+                    self.draw_shape(&contour.to_kludgine().fill(color));
+                }
+            }
+            Brush::Gradient(gradient) => {
+                let outlines = path
+                    .contours()
+                    .iter()
+                    .map(Contour::flatten)
+                    .collect::<Vec<_>>();
+                let mut points = outlines.iter().flatten();
+                let Some(first) = points.next() else {
+                    return;
+                };
+                let (min, max) = points.fold((*first, *first), |(min, max), point| {
+                    (
+                        Point::new(min.x.min(point.x), min.y.min(point.y)),
+                        Point::new(max.x.max(point.x), max.y.max(point.y)),
+                    )
+                });
+                let bounds = Rect::new(min, Size::new(max.x - min.x, max.y - min.y));
+                for outline in &outlines {
+                    self.fill_gradient::<Unit>(&gradient, outline, bounds);
+                }
+            }
+        }
+    }
+
+    fn fill_gradient<Unit>(
+        &mut self,
+        gradient: &Gradient,
+        outline: &[Point<f32>],
+        bounds: Rect<f32>,
+    ) where
+        Unit: Zero + ShaderScalable + figures::ScreenUnit + FloatConversion<Float = f32> + Copy,
+    {
+        let to_unit = |(point, color): (Point<f32>, Color)| {
+            (
+                Point::new(Unit::from_float(point.x), Unit::from_float(point.y)),
+                color,
+            )
+        };
+        for region in gradient.tessellate(outline, bounds) {
+            let mut vertices = region.into_iter().map(to_unit);
+            let Some(start) = vertices.next() else {
+                continue;
+            };
+            let shape = vertices
+                .fold(kludgine::shapes::PathBuilder::new(start), |path, vertex| {
+                    path.line_to(vertex)
+                })
+                .close()
+                .filled();
+            self.draw_shape(&shape);
+        }
+    }
+
+    /// Strokes each contour of `path` using `stroke`.
+    ///
+    /// `stroke` can be a [`StrokeOptions`](kludgine::shapes::StrokeOptions)
+    /// for a solid stroke, or a [`Stroke`] with a [`DashPattern`].
+    pub fn stroke_path<Unit>(&mut self, path: &Path<Unit>, stroke: impl Into<Stroke<Unit>>)
+    where
+        Unit: Zero + ShaderScalable + figures::ScreenUnit + FloatConversion<Float = f32> + Copy,
+    {
+        let stroke = stroke.into();
+        let dashed;
+        let path = if let Some(dashes) = &stroke.dashes {
+            dashed = dashes.apply(path);
+            &dashed
+        } else {
+            path
+        };
+        for contour in path.contours() {
+            self.draw_shape(&contour.to_kludgine().stroke(stroke.options));
+        }
+    }
+
     /// Draws `texture` at `destination`, scaling as necessary.
     pub fn draw_texture<Unit>(
         &mut self,
@@ -185,9 +372,24 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
         Unit: figures::ScreenUnit + ShaderScalable,
         i32: From<<Unit as IntoSigned>::Signed>,
     {
-        let translate = Point::<Unit>::from_px(self.translation(), self.scale());
-        self.renderer
-            .draw_texture(texture, destination + translate, *(self.opacity * opacity));
+        let opacity = *(self.opacity * opacity);
+        if let Some(transform) = &self.transform {
+            let scale = self.scale();
+            let clip_origin = self.renderer.clip_rect().origin.into_signed();
+            let destination = Rect::new(
+                destination.origin.into_px(scale),
+                destination.size.into_px(scale),
+            ) + self.region.origin;
+            self.renderer.draw_texture(
+                texture,
+                transform.bounds_of(destination) - clip_origin,
+                opacity,
+            );
+        } else {
+            let translate = Point::<Unit>::from_px(self.translation(), self.scale());
+            self.renderer
+                .draw_texture(texture, destination + translate, opacity);
+        }
     }
 
     /// Draws a shape that was created with texture coordinates, applying the
@@ -209,7 +411,11 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
                 .opacity
                 .map_or(*effective_opacity, |opacity| opacity * *effective_opacity),
         );
-        shape.translation += Point::<Unit>::from_px(self.translation(), self.scale());
+        self.position(
+            &mut shape.translation,
+            &mut shape.rotation,
+            &mut shape.scale,
+        );
         self.renderer.draw_textured_shape(shape, texture);
     }
 
@@ -233,7 +439,7 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
             text.opacity
                 .map_or(*self.opacity, |opacity| opacity * *self.opacity),
         );
-        text.translation += Point::<Unit>::from_px(self.translation(), self.scale());
+        self.position(&mut text.translation, &mut text.rotation, &mut text.scale);
         self.renderer.draw_text(text);
     }
 
@@ -258,7 +464,11 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
                 .opacity
                 .map_or(*self.opacity, |opacity| opacity * *self.opacity),
         );
-        buffer.translation += Point::<Unit>::from_px(self.translation(), self.scale());
+        self.position(
+            &mut buffer.translation,
+            &mut buffer.rotation,
+            &mut buffer.scale,
+        );
         self.renderer
             .draw_text_buffer(buffer, default_color, origin);
     }
@@ -295,7 +505,7 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
             text.opacity
                 .map_or(*self.opacity, |opacity| opacity * *self.opacity),
         );
-        text.translation += Point::<Unit>::from_px(self.translation(), self.scale());
+        self.position(&mut text.translation, &mut text.rotation, &mut text.scale);
         self.renderer.draw_measured_text(text, origin);
     }
 
@@ -339,6 +549,25 @@ impl<'gfx, 'pass> Graphics<'_, 'gfx, 'pass> {
     }
 }
 
+/// Returns the area of the window covered by `region` once `transform` is
+/// applied, excluding any portion with negative coordinates.
+///
+/// Clip rectangles are unsigned, so the portion of the transformed region that
+/// extends above or to the left of the window must be removed before it can be
+/// intersected with the current clip rectangle.
+fn effective_clip(region: Rect<Px>, transform: Option<Transform>) -> Rect<UPx> {
+    let mut effective = transform.map_or(region, |transform| transform.bounds_of(region));
+    if effective.origin.x < 0 {
+        effective.size.width = (effective.size.width + effective.origin.x).max(Px::ZERO);
+        effective.origin.x = Px::ZERO;
+    }
+    if effective.origin.y < 0 {
+        effective.size.height = (effective.size.height + effective.origin.y).max(Px::ZERO);
+        effective.origin.y = Px::ZERO;
+    }
+    effective.into_unsigned()
+}
+
 impl Deref for Graphics<'_, '_, '_> {
     type Target = Kludgine;
 
@@ -604,6 +833,61 @@ impl FontState {
         }
     }
 
+    /// Returns information about every face in `font_system`.
+    pub fn font_faces(&self, font_system: &mut FontSystem) -> Vec<FontFaceInfo> {
+        let mut faces = font_system
+            .db()
+            .faces()
+            .map(|face| FontFaceInfo {
+                id: face.id,
+                families: face.families.clone(),
+                post_script_name: face.post_script_name.clone(),
+                weight: face.weight,
+                style: face.style,
+                stretch: face.stretch,
+                monospaced: face.monospaced,
+                source: self.face_source(face.id),
+                metrics: None,
+            })
+            .collect::<Vec<_>>();
+        for face in &mut faces {
+            face.metrics = font_system.get_font(face.id).map(|font| {
+                let face = font.rustybuzz();
+                FontMetrics {
+                    units_per_em: face.units_per_em(),
+                    ascender: face.ascender(),
+                    descender: face.descender(),
+                    line_gap: face.line_gap(),
+                    x_height: face.x_height(),
+                    capital_height: face.capital_height(),
+                }
+            });
+        }
+        faces
+    }
+
+    fn face_source(&self, id: fontdb::ID) -> FontFaceSource {
+        self.loaded_fonts
+            .iter()
+            .find(|field| field.value.faces.iter().any(|face| face.id == id))
+            .map_or(FontFaceSource::System, |field| {
+                if field.key().collection == self.app_fonts.0.as_ptr() {
+                    FontFaceSource::App
+                } else {
+                    FontFaceSource::Window
+                }
+            })
+    }
+
+    /// Moves any deferred fonts into the app and window font collections,
+    /// returning true if any fonts will be loaded during the next frame.
+    pub fn load_deferred(&self) -> bool {
+        // Both collections must be visited, so short-circuiting is avoided.
+        let app = self.app_fonts.load_deferred();
+        let window = self.window_fonts.load_deferred();
+        app || window
+    }
+
     #[must_use]
     pub fn next_frame(&mut self, db: &mut cosmic_text::fontdb::Database) -> bool {
         self.current_font_family = None;
@@ -783,3 +1067,83 @@ where
         self.render(region, opacity, graphics);
     }
 }
+
+#[test]
+fn transformed_clips_exclude_negative_coordinates() {
+    let rect = |x: i32, y: i32, width: i32, height: i32| {
+        Rect::new(
+            Point::new(Px::new(x), Px::new(y)),
+            Size::new(Px::new(width), Px::new(height)),
+        )
+    };
+    let urect = |x: u32, y: u32, width: u32, height: u32| {
+        Rect::new(
+            Point::new(UPx::new(x), UPx::new(y)),
+            Size::new(UPx::new(width), UPx::new(height)),
+        )
+    };
+
+    assert_eq!(
+        effective_clip(rect(10, 10, 20, 10), None),
+        urect(10, 10, 20, 10)
+    );
+    assert_eq!(
+        effective_clip(rect(-5, -5, 20, 10), None),
+        urect(0, 0, 15, 5)
+    );
+
+    // A region with a positive origin can be moved into negative coordinates
+    // by its transform. Rotating by 90 degrees maps x 10..30 to y 10..30 and
+    // y 10..20 to x -20..-10, which the translation moves to -5..5.
+    let transform = Transform::rotated(Angle::degrees(90))
+        .then(Transform::translated(Point::new(Px::new(15), Px::ZERO)));
+    assert_eq!(
+        effective_clip(rect(10, 10, 20, 10), Some(transform)),
+        urect(0, 10, 5, 20)
+    );
+    // Regions transformed entirely off of the window are empty.
+    assert_eq!(
+        effective_clip(
+            rect(10, 10, 20, 10),
+            Some(Transform::rotated(Angle::degrees(90)))
+        )
+        .size
+        .width,
+        UPx::ZERO
+    );
+}
+
+#[test]
+fn font_faces_report_source_and_metrics() {
+    let roboto = include_bytes!("../assets/RobotoFlex.ttf");
+    let mut font_system =
+        FontSystem::new_with_locale_and_db(String::from("en-US"), fontdb::Database::new());
+    font_system.db_mut().load_font_data(roboto.to_vec());
+    let window_fonts = FontCollection::default().with(roboto.to_vec());
+    let app_fonts = FontCollection::default();
+    let mut state = FontState::new(font_system.db_mut(), window_fonts, app_fonts.clone());
+    app_fonts.push(roboto.to_vec());
+    state.update_fonts(font_system.db_mut());
+
+    let faces = state.font_faces(&mut font_system);
+    for source in [
+        FontFaceSource::System,
+        FontFaceSource::App,
+        FontFaceSource::Window,
+    ] {
+        assert!(
+            faces.iter().any(|face| face.source == source),
+            "missing {source:?} face"
+        );
+    }
+    for face in &faces {
+        assert!(face
+            .families
+            .iter()
+            .any(|(family, _)| family == "Roboto Flex"));
+        let metrics = face.metrics.expect("face data loaded");
+        assert!(metrics.units_per_em > 0);
+        assert!(metrics.ascender > 0);
+        assert!(metrics.descender < 0);
+    }
+}
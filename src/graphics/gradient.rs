@@ -0,0 +1,403 @@
+use figures::{Point, Rect, Size};
+use kludgine::Color;
+
+use crate::animation::LinearInterpolate;
+use crate::styles::{Component, RequireInvalidation};
+
+/// The number of cells along each axis that radial gradients are divided into
+/// when tessellating.
+const RADIAL_CELLS: u8 = 24;
+
+/// A color at a position along a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    /// The position of this stop, where `0.0` is the start of the gradient and
+    /// `1.0` is the end of the gradient.
+    pub offset: f32,
+    /// The color of the gradient at `offset`.
+    pub color: Color,
+}
+
+impl ColorStop {
+    /// Returns a stop of `color` at `offset`.
+    #[must_use]
+    pub const fn new(offset: f32, color: Color) -> Self {
+        Self { offset, color }
+    }
+}
+
+impl From<(f32, Color)> for ColorStop {
+    fn from((offset, color): (f32, Color)) -> Self {
+        Self::new(offset, color)
+    }
+}
+
+/// The shape of a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientShape {
+    /// Colors change along the line from `start` to `end`.
+    Linear {
+        /// The location of the gradient's first stop.
+        start: Point<f32>,
+        /// The location of the gradient's last stop.
+        end: Point<f32>,
+    },
+    /// Colors change moving outward from `center`.
+    Radial {
+        /// The location of the gradient's first stop.
+        center: Point<f32>,
+        /// The distance from `center` of the gradient's last stop.
+        radius: f32,
+    },
+}
+
+/// A fill that smoothly transitions between colors.
+///
+/// The locations of a gradient are relative to the bounds of the area being
+/// filled: `(0.0, 0.0)` is the top-left corner and `(1.0, 1.0)` is the
+/// bottom-right corner. This allows the same gradient to be used to fill areas
+/// of any size, such as the backgrounds of widgets using
+/// [`WidgetBackgroundGradient`](crate::styles::components::WidgetBackgroundGradient).
+///
+/// Areas before the first stop are filled with the first stop's color, and
+/// areas after the last stop are filled with the last stop's color.
+///
+/// ```rust
+/// use cushy::figures::Point;
+/// use cushy::graphics::Gradient;
+/// use cushy::kludgine::Color;
+///
+/// // A vertical gradient from red at the top to blue at the bottom.
+/// let gradient = Gradient::linear(
+///     Point::new(0.5, 0.),
+///     Point::new(0.5, 1.),
+///     [(0., Color::RED), (1., Color::BLUE)],
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    shape: GradientShape,
+    stops: Vec<ColorStop>,
+}
+
+impl Gradient {
+    /// Returns a gradient that transitions from the color of the first stop at
+    /// `start` to the color of the last stop at `end`.
+    #[must_use]
+    pub fn linear(
+        start: Point<f32>,
+        end: Point<f32>,
+        stops: impl IntoIterator<Item = impl Into<ColorStop>>,
+    ) -> Self {
+        Self::new(GradientShape::Linear { start, end }, stops)
+    }
+
+    /// Returns a gradient that transitions from the color of the first stop at
+    /// `center` to the color of the last stop `radius` away from `center`.
+    ///
+    /// Because `radius` is relative to the size of the filled area, the
+    /// gradient is elliptical when the filled area is not square.
+    #[must_use]
+    pub fn radial(
+        center: Point<f32>,
+        radius: f32,
+        stops: impl IntoIterator<Item = impl Into<ColorStop>>,
+    ) -> Self {
+        Self::new(GradientShape::Radial { center, radius }, stops)
+    }
+
+    fn new(shape: GradientShape, stops: impl IntoIterator<Item = impl Into<ColorStop>>) -> Self {
+        let mut stops = stops.into_iter().map(Into::into).collect::<Vec<_>>();
+        for stop in &mut stops {
+            stop.offset = stop.offset.clamp(0., 1.);
+        }
+        stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        Self { shape, stops }
+    }
+
+    /// Returns the shape of this gradient.
+    #[must_use]
+    pub const fn shape(&self) -> GradientShape {
+        self.shape
+    }
+
+    /// Returns the color stops of this gradient, ordered by their offset.
+    #[must_use]
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    /// Returns true if this gradient has no color stops.
+    ///
+    /// Filling with an empty gradient draws nothing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stops.is_empty()
+    }
+
+    /// Returns the color of this gradient at `offset`.
+    #[must_use]
+    pub fn color_at(&self, offset: f32) -> Color {
+        let Some(first) = self.stops.first() else {
+            return Color::CLEAR_BLACK;
+        };
+        if offset <= first.offset {
+            return first.color;
+        }
+        for pair in self.stops.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if offset <= to.offset {
+                let span = to.offset - from.offset;
+                return if span > 0. {
+                    from.color.lerp(&to.color, (offset - from.offset) / span)
+                } else {
+                    to.color
+                };
+            }
+        }
+        self.stops[self.stops.len() - 1].color
+    }
+
+    /// Returns the position along this gradient of `point` when filling
+    /// `bounds`.
+    fn offset_of(&self, point: Point<f32>, bounds: Rect<f32>) -> f32 {
+        match self.shape {
+            GradientShape::Linear { start, end } => {
+                let start = relative_point(start, bounds);
+                let direction = relative_point(end, bounds) - start;
+                let length_squared = direction.x * direction.x + direction.y * direction.y;
+                if length_squared > 0. {
+                    let delta = point - start;
+                    (delta.x * direction.x + delta.y * direction.y) / length_squared
+                } else {
+                    1.
+                }
+            }
+            GradientShape::Radial { center, radius } => {
+                let delta = point - relative_point(center, bounds);
+                let radius = Size::new(bounds.size.width * radius, bounds.size.height * radius);
+                if radius.width > 0. && radius.height > 0. {
+                    (delta.x / radius.width).hypot(delta.y / radius.height)
+                } else {
+                    1.
+                }
+            }
+        }
+    }
+
+    /// Divides the polygon `outline` into polygons whose vertices are
+    /// colored such that interpolating the colors across each polygon
+    /// approximates this gradient filling `bounds`.
+    pub(crate) fn tessellate(
+        &self,
+        outline: &[Point<f32>],
+        bounds: Rect<f32>,
+    ) -> Vec<Vec<(Point<f32>, Color)>> {
+        if self.is_empty() || outline.len() < 3 {
+            return Vec::new();
+        }
+        let mut regions = Vec::new();
+        match self.shape {
+            GradientShape::Linear { .. } => {
+                // Within each band between two stops, the color changes
+                // linearly with the position along the gradient, which is
+                // reproduced exactly by interpolating vertex colors.
+                let mut boundaries = vec![f32::NEG_INFINITY];
+                boundaries.extend(self.stops.iter().map(|stop| stop.offset));
+                boundaries.push(f32::INFINITY);
+                for band in boundaries.windows(2) {
+                    let (low, high) = (band[0], band[1]);
+                    if low >= high {
+                        continue;
+                    }
+                    let mut region = outline.to_vec();
+                    if low.is_finite() {
+                        region = clip(&region, |point| self.offset_of(point, bounds) - low);
+                    }
+                    if high.is_finite() {
+                        region = clip(&region, |point| high - self.offset_of(point, bounds));
+                    }
+                    regions.push(region);
+                }
+            }
+            GradientShape::Radial { .. } => {
+                let cell = Size::new(
+                    bounds.size.width / f32::from(RADIAL_CELLS),
+                    bounds.size.height / f32::from(RADIAL_CELLS),
+                );
+                for row in 0..RADIAL_CELLS {
+                    let top = bounds.origin.y + cell.height * f32::from(row);
+                    let bottom = top + cell.height;
+                    for column in 0..RADIAL_CELLS {
+                        let left = bounds.origin.x + cell.width * f32::from(column);
+                        let right = left + cell.width;
+                        let mut region = clip(outline, |point| point.x - left);
+                        region = clip(&region, |point| right - point.x);
+                        region = clip(&region, |point| point.y - top);
+                        region = clip(&region, |point| bottom - point.y);
+                        regions.push(region);
+                    }
+                }
+            }
+        }
+
+        regions
+            .into_iter()
+            .filter(|region| region.len() >= 3)
+            .map(|region| {
+                region
+                    .into_iter()
+                    .map(|point| (point, self.color_at(self.offset_of(point, bounds))))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Default for Gradient {
+    /// Returns an empty gradient.
+    fn default() -> Self {
+        Self {
+            shape: GradientShape::Linear {
+                start: Point::new(0., 0.),
+                end: Point::new(0., 1.),
+            },
+            stops: Vec::new(),
+        }
+    }
+}
+
+impl From<Gradient> for Component {
+    fn from(value: Gradient) -> Self {
+        Component::custom(value)
+    }
+}
+
+impl TryFrom<Component> for Gradient {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Custom(custom) => custom
+                .downcast()
+                .cloned()
+                .ok_or_else(|| Component::Custom(custom)),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for Gradient {
+    fn requires_invalidation(&self) -> bool {
+        false
+    }
+}
+
+/// A way to fill an area.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Brush {
+    /// A single color.
+    Color(Color),
+    /// A [`Gradient`].
+    Gradient(Gradient),
+}
+
+impl Brush {
+    /// Returns true if filling with this brush can change any pixels.
+    #[must_use]
+    pub fn is_visible(&self) -> bool {
+        match self {
+            Brush::Color(color) => color.alpha() > 0,
+            Brush::Gradient(gradient) => gradient.stops.iter().any(|stop| stop.color.alpha() > 0),
+        }
+    }
+}
+
+impl From<Color> for Brush {
+    fn from(color: Color) -> Self {
+        Self::Color(color)
+    }
+}
+
+impl From<Gradient> for Brush {
+    fn from(gradient: Gradient) -> Self {
+        Self::Gradient(gradient)
+    }
+}
+
+fn relative_point(point: Point<f32>, bounds: Rect<f32>) -> Point<f32> {
+    Point::new(
+        bounds.origin.x + point.x * bounds.size.width,
+        bounds.origin.y + point.y * bounds.size.height,
+    )
+}
+
+/// Clips `polygon` to the area where `distance` is not negative.
+///
+/// `distance` must be an affine function of the point for the intersections
+/// to be computed correctly.
+fn clip(polygon: &[Point<f32>], distance: impl Fn(Point<f32>) -> f32) -> Vec<Point<f32>> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 2);
+    let Some(&last) = polygon.last() else {
+        return clipped;
+    };
+    let mut previous = (last, distance(last));
+    for &point in polygon {
+        let current = (point, distance(point));
+        if (previous.1 >= 0.) != (current.1 >= 0.) {
+            let t = previous.1 / (previous.1 - current.1);
+            clipped.push(Point::new(
+                previous.0.x + (current.0.x - previous.0.x) * t,
+                previous.0.y + (current.0.y - previous.0.y) * t,
+            ));
+        }
+        if current.1 >= 0. {
+            clipped.push(point);
+        }
+        previous = current;
+    }
+    clipped
+}
+
+#[test]
+fn gradients() {
+    let gradient = Gradient::linear(
+        Point::new(0., 0.),
+        Point::new(1., 0.),
+        [(1., Color::WHITE), (0., Color::BLACK)],
+    );
+    assert_eq!(gradient.stops()[0].color, Color::BLACK);
+    assert_eq!(gradient.color_at(-1.), Color::BLACK);
+    assert_eq!(gradient.color_at(2.), Color::WHITE);
+    assert_eq!(
+        gradient.color_at(0.5),
+        Color::BLACK.lerp(&Color::WHITE, 0.5)
+    );
+
+    // A horizontal gradient over a square is split into a band before,
+    // between, and after its stops. The outer bands have no area.
+    let bounds = Rect::new(Point::new(0., 0.), Size::new(10., 10.));
+    let square = [
+        Point::new(0., 0.),
+        Point::new(10., 0.),
+        Point::new(10., 10.),
+        Point::new(0., 10.),
+    ];
+    let regions = gradient.tessellate(&square, bounds);
+    assert_eq!(regions.len(), 1);
+    for (point, color) in &regions[0] {
+        let expected = if point.x > 5. {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        };
+        assert_eq!(*color, expected);
+    }
+
+    let radial = Gradient::radial(Point::new(0.5, 0.5), 0.5, [(0., Color::RED)]);
+    assert_eq!(
+        radial.tessellate(&square, bounds).len(),
+        usize::from(RADIAL_CELLS) * usize::from(RADIAL_CELLS)
+    );
+    assert!(Gradient::default().tessellate(&square, bounds).is_empty());
+}
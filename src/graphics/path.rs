@@ -0,0 +1,690 @@
+use figures::{FloatConversion, Point, Rect};
+use kludgine::shapes::{self, CornerRadii, LineCap, LineJoin, StrokeOptions};
+
+/// The number of line segments each Bézier curve is divided into when
+/// flattening a path for dashing.
+const CURVE_STEPS: u8 = 16;
+
+/// The distance along each edge that the control points of a cubic Bézier
+/// curve approximating a quarter circle are placed, relative to the radius.
+const QUARTER_CIRCLE_CONTROL: f32 = 0.552_284_8;
+
+/// A shape made of one or more contours of lines and Bézier curves.
+///
+/// Paths are drawn using [`Graphics::fill_path`](super::Graphics::fill_path)
+/// and [`Graphics::stroke_path`](super::Graphics::stroke_path), which
+/// tessellate each contour independently. When filling, contours are not used
+/// to cut holes in each other.
+///
+/// ```rust
+/// use cushy::figures::units::Px;
+/// use cushy::figures::Point;
+/// use cushy::graphics::Path;
+///
+/// // A triangle with a rounded base.
+/// let path = Path::builder(Point::new(Px::new(50), Px::new(0)))
+///     .line_to(Point::new(Px::new(100), Px::new(80)))
+///     .quad_to(
+///         Point::new(Px::new(50), Px::new(120)),
+///         Point::new(Px::new(0), Px::new(80)),
+///     )
+///     .close()
+///     .build();
+/// assert_eq!(path.contours().len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path<Unit> {
+    contours: Vec<Contour<Unit>>,
+}
+
+impl<Unit> Path<Unit>
+where
+    Unit: Copy,
+{
+    /// Returns a builder for a path whose first contour begins at `start`.
+    #[must_use]
+    pub fn builder(start: Point<Unit>) -> PathBuilder<Unit> {
+        PathBuilder::new(start)
+    }
+
+    /// Returns a closed path connecting each of `points` with straight lines.
+    ///
+    /// If `points` is empty, the returned path will be empty.
+    #[must_use]
+    pub fn polygon(points: impl IntoIterator<Item = Point<Unit>>) -> Self {
+        let mut points = points.into_iter();
+        let Some(first) = points.next() else {
+            return Self {
+                contours: Vec::new(),
+            };
+        };
+        points
+            .fold(PathBuilder::new(first), PathBuilder::line_to)
+            .close()
+            .build()
+    }
+
+    /// Returns the contours that make up this path.
+    #[must_use]
+    pub fn contours(&self) -> &[Contour<Unit>] {
+        &self.contours
+    }
+
+    /// Returns true if this path contains no contours.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.contours.is_empty()
+    }
+}
+
+impl<Unit> Path<Unit>
+where
+    Unit: FloatConversion<Float = f32> + Copy,
+{
+    /// Returns a closed path outlining `rect` with corners rounded by `radii`.
+    ///
+    /// Each radius is limited to half of the smaller dimension of `rect`.
+    #[must_use]
+    pub fn round_rect(rect: Rect<Unit>, radii: CornerRadii<Unit>) -> Self {
+        let (left, top) = (rect.origin.x.into_float(), rect.origin.y.into_float());
+        let (width, height) = (rect.size.width.into_float(), rect.size.height.into_float());
+        let (right, bottom) = (left + width, top + height);
+        let limit = width.abs().min(height.abs()) / 2.;
+        let radius = |radius: Unit| radius.into_float().clamp(0., limit);
+
+        let top_left = radius(radii.top_left);
+        PathBuilder::new(Point::new(
+            Unit::from_float(left + top_left),
+            Unit::from_float(top),
+        ))
+        .round_corner(
+            Point::new(right, top),
+            radius(radii.top_right),
+            Point::new(-1., 0.),
+            Point::new(0., 1.),
+        )
+        .round_corner(
+            Point::new(right, bottom),
+            radius(radii.bottom_right),
+            Point::new(0., -1.),
+            Point::new(-1., 0.),
+        )
+        .round_corner(
+            Point::new(left, bottom),
+            radius(radii.bottom_left),
+            Point::new(1., 0.),
+            Point::new(0., -1.),
+        )
+        .round_corner(
+            Point::new(left, top),
+            top_left,
+            Point::new(0., 1.),
+            Point::new(1., 0.),
+        )
+        .close()
+        .build()
+    }
+}
+
+/// A continuous series of segments within a [`Path`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour<Unit> {
+    start: Point<Unit>,
+    segments: Vec<Segment<Unit>>,
+    closed: bool,
+}
+
+impl<Unit> Contour<Unit>
+where
+    Unit: Copy,
+{
+    fn new(start: Point<Unit>) -> Self {
+        Self {
+            start,
+            segments: Vec::new(),
+            closed: false,
+        }
+    }
+
+    /// Returns the first point of this contour.
+    #[must_use]
+    pub fn start(&self) -> Point<Unit> {
+        self.start
+    }
+
+    /// Returns the segments of this contour.
+    #[must_use]
+    pub fn segments(&self) -> &[Segment<Unit>] {
+        &self.segments
+    }
+
+    /// Returns true if the last point of this contour is connected to its
+    /// first point.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub(crate) fn to_kludgine(&self) -> shapes::Path<Unit, false> {
+        let builder =
+            self.segments
+                .iter()
+                .fold(
+                    shapes::PathBuilder::new(self.start),
+                    |builder, segment| match *segment {
+                        Segment::Line(end) => builder.line_to(end),
+                        Segment::Quadratic { control, end } => {
+                            builder.quadratic_curve_to(control, end)
+                        }
+                        Segment::Cubic {
+                            control1,
+                            control2,
+                            end,
+                        } => builder.cubic_curve_to(control1, control2, end),
+                    },
+                );
+        if self.closed {
+            builder.close()
+        } else {
+            builder.build()
+        }
+    }
+}
+
+impl<Unit> Contour<Unit>
+where
+    Unit: FloatConversion<Float = f32> + Copy,
+{
+    /// Returns the points of this contour with each curve approximated by
+    /// straight lines.
+    pub(crate) fn flatten(&self) -> Vec<Point<f32>> {
+        let mut points = vec![self.start.into_float()];
+        let mut position = self.start.into_float();
+        for segment in &self.segments {
+            match *segment {
+                Segment::Line(_) => {}
+                Segment::Quadratic { control, end } => {
+                    let control = control.into_float();
+                    let end = end.into_float();
+                    points.extend((1..CURVE_STEPS).map(|step| {
+                        let t = f32::from(step) / f32::from(CURVE_STEPS);
+                        lerp(lerp(position, control, t), lerp(control, end, t), t)
+                    }));
+                }
+                Segment::Cubic {
+                    control1,
+                    control2,
+                    end,
+                } => {
+                    let control1 = control1.into_float();
+                    let control2 = control2.into_float();
+                    let end = end.into_float();
+                    points.extend((1..CURVE_STEPS).map(|step| {
+                        let t = f32::from(step) / f32::from(CURVE_STEPS);
+                        let a = lerp(position, control1, t);
+                        let b = lerp(control1, control2, t);
+                        let c = lerp(control2, end, t);
+                        lerp(lerp(a, b, t), lerp(b, c, t), t)
+                    }));
+                }
+            }
+            position = segment.end().into_float();
+            points.push(position);
+        }
+        if self.closed {
+            points.push(self.start.into_float());
+        }
+        points
+    }
+}
+
+/// A segment of a [`Contour`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment<Unit> {
+    /// A straight line ending at the contained point.
+    Line(Point<Unit>),
+    /// A quadratic Bézier curve.
+    Quadratic {
+        /// The curve's control point.
+        control: Point<Unit>,
+        /// The end of the curve.
+        end: Point<Unit>,
+    },
+    /// A cubic Bézier curve.
+    Cubic {
+        /// The curve's first control point.
+        control1: Point<Unit>,
+        /// The curve's second control point.
+        control2: Point<Unit>,
+        /// The end of the curve.
+        end: Point<Unit>,
+    },
+}
+
+impl<Unit> Segment<Unit>
+where
+    Unit: Copy,
+{
+    /// Returns the final point of this segment.
+    #[must_use]
+    pub fn end(&self) -> Point<Unit> {
+        match self {
+            Segment::Line(end) | Segment::Quadratic { end, .. } | Segment::Cubic { end, .. } => {
+                *end
+            }
+        }
+    }
+}
+
+/// Builds a [`Path`] out of lines and Bézier curves.
+#[derive(Debug)]
+pub struct PathBuilder<Unit> {
+    path: Path<Unit>,
+    current: Contour<Unit>,
+}
+
+impl<Unit> PathBuilder<Unit>
+where
+    Unit: Copy,
+{
+    /// Returns a builder for a path whose first contour begins at `start`.
+    #[must_use]
+    pub fn new(start: Point<Unit>) -> Self {
+        Self {
+            path: Path {
+                contours: Vec::new(),
+            },
+            current: Contour::new(start),
+        }
+    }
+
+    /// Ends the current contour without closing it, and begins a new contour
+    /// at `point`.
+    #[must_use]
+    pub fn move_to(mut self, point: Point<Unit>) -> Self {
+        self.finish_contour(false);
+        self.current = Contour::new(point);
+        self
+    }
+
+    /// Adds a straight line from the current position to `end`.
+    #[must_use]
+    pub fn line_to(mut self, end: Point<Unit>) -> Self {
+        self.current.segments.push(Segment::Line(end));
+        self
+    }
+
+    /// Adds a quadratic Bézier curve from the current position to `end`, using
+    /// `control` as the curve's control point.
+    #[must_use]
+    pub fn quad_to(mut self, control: Point<Unit>, end: Point<Unit>) -> Self {
+        self.current
+            .segments
+            .push(Segment::Quadratic { control, end });
+        self
+    }
+
+    /// Adds a cubic Bézier curve from the current position to `end`, using
+    /// `control1` and `control2` as the curve's control points.
+    #[must_use]
+    pub fn cubic_to(
+        mut self,
+        control1: Point<Unit>,
+        control2: Point<Unit>,
+        end: Point<Unit>,
+    ) -> Self {
+        self.current.segments.push(Segment::Cubic {
+            control1,
+            control2,
+            end,
+        });
+        self
+    }
+
+    /// Closes the current contour by connecting its last point to its first
+    /// point.
+    ///
+    /// Segments added after closing a contour begin a new contour at the
+    /// start of the closed contour.
+    #[must_use]
+    pub fn close(mut self) -> Self {
+        self.finish_contour(true);
+        self
+    }
+
+    /// Returns the built path. The current contour is left open.
+    #[must_use]
+    pub fn build(mut self) -> Path<Unit> {
+        self.finish_contour(false);
+        self.path
+    }
+
+    fn finish_contour(&mut self, closed: bool) {
+        if self.current.segments.is_empty() {
+            return;
+        }
+        let mut contour = std::mem::replace(&mut self.current, Contour::new(self.current.start));
+        contour.closed = closed;
+        self.path.contours.push(contour);
+    }
+}
+
+impl<Unit> PathBuilder<Unit>
+where
+    Unit: FloatConversion<Float = f32> + Copy,
+{
+    /// Adds a line to the start of a rounded `corner`, followed by a quarter
+    /// circle of `radius` to its end. `from` and `to` are the directions from
+    /// `corner` of the start and end of the rounded corner.
+    fn round_corner(
+        self,
+        corner: Point<f32>,
+        radius: f32,
+        from: Point<f32>,
+        to: Point<f32>,
+    ) -> Self {
+        let unit =
+            |point: Point<f32>| Point::new(Unit::from_float(point.x), Unit::from_float(point.y));
+        let start = lerp(corner, corner + from, radius);
+        let end = lerp(corner, corner + to, radius);
+        let builder = self.line_to(unit(start));
+        if radius > 0. {
+            let control = radius * QUARTER_CIRCLE_CONTROL;
+            builder.cubic_to(
+                unit(lerp(start, start - from, control)),
+                unit(lerp(end, end - to, control)),
+                unit(end),
+            )
+        } else {
+            builder
+        }
+    }
+}
+
+/// A repeating pattern of dashes and gaps used when stroking a [`Path`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DashPattern<Unit> {
+    lengths: Vec<Unit>,
+    offset: Unit,
+}
+
+impl<Unit> DashPattern<Unit>
+where
+    Unit: FloatConversion<Float = f32> + Copy,
+{
+    /// Returns a pattern that alternates between dashes and gaps of `lengths`.
+    ///
+    /// The first length is a dash, the second is a gap, and so on. If an odd
+    /// number of lengths is provided, the lengths are repeated to produce an
+    /// even number of lengths.
+    #[must_use]
+    pub fn new(lengths: impl IntoIterator<Item = Unit>) -> Self {
+        Self {
+            lengths: lengths.into_iter().collect(),
+            offset: Unit::from_float(0.),
+        }
+    }
+
+    /// Sets the distance into the pattern at which each contour begins, and
+    /// returns self.
+    ///
+    /// Animating this value produces a "marching ants" effect.
+    #[must_use]
+    pub fn offset(mut self, offset: Unit) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Returns the lengths of the dashes and gaps of this pattern.
+    #[must_use]
+    pub fn lengths(&self) -> &[Unit] {
+        &self.lengths
+    }
+
+    /// Returns a path containing an open contour for each dash produced when
+    /// applying this pattern to `path`.
+    ///
+    /// Curves are approximated using straight lines. If this pattern has no
+    /// positive lengths, a copy of `path` is returned.
+    #[must_use]
+    pub fn apply(&self, path: &Path<Unit>) -> Path<Unit> {
+        let mut lengths = self
+            .lengths
+            .iter()
+            .map(|length| length.into_float().max(0.))
+            .collect::<Vec<_>>();
+        if lengths.len() % 2 == 1 {
+            lengths = lengths.repeat(2);
+        }
+        let total = lengths.iter().sum::<f32>();
+        if total <= 0. || !total.is_finite() {
+            return path.clone();
+        }
+
+        let mut dashed = Path {
+            contours: Vec::new(),
+        };
+        for contour in &path.contours {
+            let mut walker = DashWalker::new(&lengths, self.offset.into_float().rem_euclid(total));
+            let points = contour.flatten();
+            if walker.is_dash() {
+                walker.dash.push(points[0]);
+            }
+            for edge in points.windows(2) {
+                walker.walk(edge[0], edge[1], &mut dashed);
+            }
+            walker.finish_dash(&mut dashed);
+        }
+        dashed
+    }
+}
+
+struct DashWalker<'a> {
+    lengths: &'a [f32],
+    index: usize,
+    remaining: f32,
+    dash: Vec<Point<f32>>,
+}
+
+impl<'a> DashWalker<'a> {
+    fn new(lengths: &'a [f32], mut offset: f32) -> Self {
+        let mut walker = Self {
+            lengths,
+            index: 0,
+            remaining: lengths[0],
+            dash: Vec::new(),
+        };
+        while offset > 0. {
+            if offset >= walker.remaining {
+                offset -= walker.remaining;
+                walker.advance();
+            } else {
+                walker.remaining -= offset;
+                offset = 0.;
+            }
+        }
+        walker
+    }
+
+    fn is_dash(&self) -> bool {
+        self.index % 2 == 0
+    }
+
+    fn advance(&mut self) {
+        self.index = (self.index + 1) % self.lengths.len();
+        self.remaining = self.lengths[self.index];
+    }
+
+    fn walk<Unit>(&mut self, from: Point<f32>, to: Point<f32>, dashed: &mut Path<Unit>)
+    where
+        Unit: FloatConversion<Float = f32> + Copy,
+    {
+        let delta = to - from;
+        let length = delta.x.hypot(delta.y);
+        let mut traveled = 0.;
+        while length - traveled >= self.remaining {
+            traveled += self.remaining;
+            let point = if length > 0. {
+                lerp(from, to, traveled / length)
+            } else {
+                to
+            };
+            if self.is_dash() {
+                self.dash.push(point);
+                self.finish_dash(dashed);
+            }
+            self.advance();
+            if self.is_dash() {
+                self.dash.push(point);
+            }
+        }
+        self.remaining -= length - traveled;
+        if self.is_dash() {
+            self.dash.push(to);
+        }
+    }
+
+    fn finish_dash<Unit>(&mut self, dashed: &mut Path<Unit>)
+    where
+        Unit: FloatConversion<Float = f32> + Copy,
+    {
+        let mut points = self
+            .dash
+            .drain(..)
+            .map(|point| Point::new(Unit::from_float(point.x), Unit::from_float(point.y)));
+        let Some(start) = points.next() else {
+            return;
+        };
+        let segments = points.map(Segment::Line).collect::<Vec<_>>();
+        if !segments.is_empty() {
+            dashed.contours.push(Contour {
+                start,
+                segments,
+                closed: false,
+            });
+        }
+    }
+}
+
+fn lerp(a: Point<f32>, b: Point<f32>, t: f32) -> Point<f32> {
+    Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+/// Options for stroking a [`Path`].
+///
+/// Any [`StrokeOptions`] can be converted into a solid stroke.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke<Unit> {
+    /// The width, color, caps, and joins of the stroke.
+    pub options: StrokeOptions<Unit>,
+    /// The dash pattern to apply. When `None`, the stroke is solid.
+    pub dashes: Option<DashPattern<Unit>>,
+}
+
+impl<Unit> Stroke<Unit> {
+    /// Returns a solid stroke using `options`.
+    #[must_use]
+    pub const fn new(options: StrokeOptions<Unit>) -> Self {
+        Self {
+            options,
+            dashes: None,
+        }
+    }
+
+    /// Applies `pattern` to this stroke and returns self.
+    #[must_use]
+    pub fn dashed(mut self, pattern: DashPattern<Unit>) -> Self {
+        self.dashes = Some(pattern);
+        self
+    }
+
+    /// Sets the cap drawn at the start and end of each contour and dash, and
+    /// returns self.
+    #[must_use]
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.options.start_cap = cap;
+        self.options.end_cap = cap;
+        self
+    }
+
+    /// Sets the style used to join consecutive segments, and returns self.
+    #[must_use]
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.options.line_join = join;
+        self
+    }
+}
+
+impl<Unit> From<StrokeOptions<Unit>> for Stroke<Unit> {
+    fn from(options: StrokeOptions<Unit>) -> Self {
+        Self::new(options)
+    }
+}
+
+#[test]
+fn contours() {
+    use figures::units::Px;
+
+    let point = |x: i32, y: i32| Point::new(Px::new(x), Px::new(y));
+
+    // Moving without drawing does not produce a contour.
+    let path = Path::builder(point(0, 0)).move_to(point(5, 5)).build();
+    assert!(path.is_empty());
+
+    // Segments after closing begin a new contour at the closed contour's
+    // start.
+    let path = Path::builder(point(0, 0))
+        .line_to(point(10, 0))
+        .line_to(point(10, 10))
+        .close()
+        .line_to(point(0, 10))
+        .move_to(point(20, 20))
+        .cubic_to(point(25, 15), point(30, 25), point(35, 20))
+        .build();
+    assert_eq!(path.contours().len(), 3);
+    assert!(path.contours()[0].is_closed());
+    assert_eq!(path.contours()[1].start(), point(0, 0));
+    assert!(!path.contours()[2].is_closed());
+
+    assert_eq!(
+        Path::polygon([point(0, 0), point(1, 1)]).contours().len(),
+        1
+    );
+    assert!(Path::<Px>::polygon([]).is_empty());
+}
+
+#[test]
+fn dashes() {
+    use figures::units::Px;
+    use figures::Zero;
+
+    let line = Path::builder(Point::new(Px::ZERO, Px::ZERO))
+        .line_to(Point::new(Px::new(10), Px::ZERO))
+        .build();
+    let dash_ranges = |pattern: &DashPattern<Px>| {
+        pattern
+            .apply(&line)
+            .contours()
+            .iter()
+            .map(|contour| {
+                let end = contour.segments().last().expect("empty dash").end();
+                (contour.start().x.get(), end.x.get())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let pattern = DashPattern::new([Px::new(2), Px::new(3)]);
+    assert_eq!(dash_ranges(&pattern), [(0, 2), (5, 7)]);
+
+    let pattern = pattern.offset(Px::new(1));
+    assert_eq!(dash_ranges(&pattern), [(0, 1), (4, 6), (9, 10)]);
+
+    // An odd number of lengths repeats the pattern.
+    assert_eq!(
+        dash_ranges(&DashPattern::new([Px::new(4)])),
+        [(0, 4), (8, 10)]
+    );
+
+    // Patterns without any length leave the path unchanged.
+    assert_eq!(DashPattern::new([Px::ZERO, Px::ZERO]).apply(&line), line);
+}
@@ -0,0 +1,165 @@
+use figures::units::Px;
+use figures::{Angle, FloatConversion, Point, Rect, Size};
+
+/// A transformation composed of a uniform scale, a rotation, and a
+/// translation.
+///
+/// When applied to a point, the point is first scaled, then rotated around the
+/// origin, and finally translated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// The uniform scale factor.
+    pub scale: f32,
+    /// The clockwise rotation.
+    pub rotation: Angle,
+    /// The translation, in pixels.
+    pub translation: Point<f32>,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            scale: 1.,
+            rotation: Angle::degrees(0),
+            translation: Point::new(0., 0.),
+        }
+    }
+}
+
+impl Transform {
+    /// Returns a transform that scales by `scale`.
+    #[must_use]
+    pub fn scaled(scale: f32) -> Self {
+        Self {
+            scale,
+            ..Self::default()
+        }
+    }
+
+    /// Returns a transform that rotates by `rotation`.
+    #[must_use]
+    pub fn rotated(rotation: Angle) -> Self {
+        Self {
+            rotation,
+            ..Self::default()
+        }
+    }
+
+    /// Returns a transform that translates by `translation`.
+    #[must_use]
+    pub fn translated(translation: Point<Px>) -> Self {
+        Self {
+            translation: translation.into_float(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns a transform that applies `self` followed by `next`.
+    #[must_use]
+    pub fn then(self, next: Self) -> Self {
+        Self {
+            scale: self.scale * next.scale,
+            rotation: Angle::degrees_f(
+                self.rotation.into_degrees::<f32>() + next.rotation.into_degrees::<f32>(),
+            ),
+            translation: next.apply_f32(self.translation),
+        }
+    }
+
+    /// Returns the transform that reverses `self`.
+    ///
+    /// A transform with a scale of zero has no inverse and returns a transform
+    /// that maps every point to a distant location.
+    #[must_use]
+    pub fn inverse(self) -> Self {
+        let scale = if self.scale.abs() < f32::EPSILON {
+            f32::MAX
+        } else {
+            1. / self.scale
+        };
+        let rotation = Angle::degrees_f(-self.rotation.into_degrees::<f32>());
+        let translation = rotate(
+            Point::new(-self.translation.x, -self.translation.y),
+            rotation,
+        );
+        Self {
+            scale,
+            rotation,
+            translation: Point::new(translation.x * scale, translation.y * scale),
+        }
+    }
+
+    /// Returns true if this transform does not alter points.
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns `point` after applying this transform.
+    #[must_use]
+    pub fn apply(&self, point: Point<Px>) -> Point<Px> {
+        self.apply_f32(point.into_float())
+            .map(|coord| Px::from_float(coord.round()))
+    }
+
+    /// Returns `point` after applying this transform.
+    #[must_use]
+    pub fn apply_f32(&self, point: Point<f32>) -> Point<f32> {
+        let rotated = rotate(
+            Point::new(point.x * self.scale, point.y * self.scale),
+            self.rotation,
+        );
+        rotated + self.translation
+    }
+
+    /// Returns the smallest rectangle that contains `rect` after applying
+    /// this transform.
+    #[must_use]
+    pub fn bounds_of(&self, rect: Rect<Px>) -> Rect<Px> {
+        let left = rect.origin.x;
+        let top = rect.origin.y;
+        let right = left + rect.size.width;
+        let bottom = top + rect.size.height;
+        let corners = [
+            self.apply(Point::new(left, top)),
+            self.apply(Point::new(right, top)),
+            self.apply(Point::new(right, bottom)),
+            self.apply(Point::new(left, bottom)),
+        ];
+        let mut min = corners[0];
+        let mut max = corners[0];
+        for corner in &corners[1..] {
+            min = min.min(*corner);
+            max = max.max(*corner);
+        }
+        Rect::new(min, Size::new(max.x - min.x, max.y - min.y))
+    }
+}
+
+fn rotate(point: Point<f32>, angle: Angle) -> Point<f32> {
+    let (sin, cos) = angle.into_degrees::<f32>().to_radians().sin_cos();
+    Point::new(point.x * cos - point.y * sin, point.x * sin + point.y * cos)
+}
+
+#[test]
+fn transforms() {
+    let transform = Transform::scaled(2.)
+        .then(Transform::rotated(Angle::degrees(90)))
+        .then(Transform::translated(Point::new(Px::new(10), Px::new(0))));
+    let point = Point::new(Px::new(5), Px::new(0));
+    let transformed = transform.apply(point);
+    assert_eq!(transformed, Point::new(Px::new(10), Px::new(10)));
+    assert_eq!(transform.inverse().apply(transformed), point);
+
+    let bounds = Transform::rotated(Angle::degrees(90)).bounds_of(Rect::new(
+        Point::new(Px::new(0), Px::new(0)),
+        Size::new(Px::new(20), Px::new(10)),
+    ));
+    assert_eq!(
+        bounds,
+        Rect::new(
+            Point::new(Px::new(-10), Px::new(0)),
+            Size::new(Px::new(10), Px::new(20))
+        )
+    );
+}
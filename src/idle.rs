@@ -0,0 +1,246 @@
+//! Detecting when the user has stopped interacting with the application.
+//!
+//! Every keyboard, mouse, touch, and input method event delivered to any Cushy
+//! window is noted as user activity. [`idle_after()`] returns a [`Dynamic`]
+//! that becomes true once no input has been received for a given duration, and
+//! becomes false again as soon as input resumes. [`on_idle()`] invokes a
+//! callback each time the application becomes idle.
+//!
+//! [`IdleLock`](crate::widgets::IdleLock) uses this module to hide a window's
+//! contents, optionally behind a password prompt, while the application is
+//! idle.
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use cushy::idle;
+//! use cushy::reactive::value::Source;
+//! use cushy::widgets::label::Displayable;
+//!
+//! let idle = idle::idle_after(Duration::from_secs(5 * 60));
+//! let status = idle
+//!     .map_each(|idle| if *idle { "Away" } else { "Active" })
+//!     .into_label();
+//! ```
+
+use std::time::{Duration, Instant};
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::reactive::value::{Destination, Dynamic, Source, WeakDynamic};
+use crate::reactive::CallbackHandle;
+use crate::Lazy;
+
+static ACTIVITY: Lazy<Activity> = Lazy::new(|| Activity {
+    state: Mutex::new(ActivityState::new(Instant::now())),
+    changed: Condvar::new(),
+});
+
+struct Activity {
+    state: Mutex<ActivityState>,
+    changed: Condvar,
+}
+
+struct ActivityState {
+    last_input: Instant,
+    watchers: Vec<Watcher>,
+    watching: bool,
+}
+
+struct Watcher {
+    idle: WeakDynamic<bool>,
+    timeout: Duration,
+    is_idle: bool,
+}
+
+/// Returns a dynamic that contains true while no input has been received by
+/// any window for `timeout`.
+///
+/// Input received before the first call to a function in this module is not
+/// tracked, so the application is considered to have received input at that
+/// time.
+#[must_use]
+pub fn idle_after(timeout: Duration) -> Dynamic<bool> {
+    let mut state = ACTIVITY.state.lock();
+    let idle = state.watch(timeout, Instant::now());
+    if !state.watching {
+        state.watching = true;
+        std::thread::spawn(watch_for_idle);
+    }
+    drop(state);
+    ACTIVITY.changed.notify_one();
+    idle
+}
+
+/// Invokes `on_idle` each time no input has been received by any window for
+/// `timeout`.
+///
+/// `on_idle` is invoked again only after input has resumed and then stopped
+/// for `timeout` again. The callback is uninstalled when the returned handle
+/// is dropped.
+pub fn on_idle<F>(timeout: Duration, mut on_idle: F) -> CallbackHandle
+where
+    F: FnMut() + Send + 'static,
+{
+    let idle = idle_after(timeout);
+    idle.clone().for_each_subsequent(move |is_idle| {
+        // The callback keeps the dynamic alive, which keeps it being watched
+        // until the callback is uninstalled.
+        let _idle = &idle;
+        if *is_idle {
+            on_idle();
+        }
+    })
+}
+
+/// Returns the time the most recent input was received by any window.
+#[must_use]
+pub fn last_input() -> Instant {
+    ACTIVITY.state.lock().last_input
+}
+
+/// Notes that the user interacted with the application.
+///
+/// Cushy calls this function for all input delivered to its windows. Apps can
+/// call this function to count other interactions, such as input from a
+/// device Cushy does not receive events for, as activity.
+pub fn note_input() {
+    let resumed = ACTIVITY.state.lock().note_input(Instant::now());
+    if resumed {
+        ACTIVITY.changed.notify_one();
+    }
+}
+
+fn watch_for_idle() {
+    let mut state = ACTIVITY.state.lock();
+    loop {
+        match state.update(Instant::now()) {
+            Some(delay) => {
+                ACTIVITY.changed.wait_for(&mut state, delay);
+            }
+            None => ACTIVITY.changed.wait(&mut state),
+        }
+    }
+}
+
+impl ActivityState {
+    fn new(now: Instant) -> Self {
+        Self {
+            last_input: now,
+            watchers: Vec::new(),
+            watching: false,
+        }
+    }
+
+    /// Returns a dynamic that becomes true once no input has been received
+    /// for `timeout`, as of `now`.
+    fn watch(&mut self, timeout: Duration, now: Instant) -> Dynamic<bool> {
+        let is_idle = now.saturating_duration_since(self.last_input) >= timeout;
+        let idle = Dynamic::new(is_idle);
+        self.watchers.push(Watcher {
+            idle: idle.downgrade(),
+            timeout,
+            is_idle,
+        });
+        idle
+    }
+
+    /// Records input received at `now`, returning true if any watcher was
+    /// idle.
+    fn note_input(&mut self, now: Instant) -> bool {
+        self.last_input = now;
+        let mut resumed = false;
+        self.watchers.retain_mut(|watcher| {
+            let Some(idle) = watcher.idle.upgrade() else {
+                return false;
+            };
+            if watcher.is_idle {
+                watcher.is_idle = false;
+                resumed = true;
+                idle.set(false);
+            }
+            true
+        });
+        resumed
+    }
+
+    /// Marks every watcher whose timeout has elapsed as of `now` as idle,
+    /// returning how long until the next watcher becomes idle.
+    fn update(&mut self, now: Instant) -> Option<Duration> {
+        let since_input = now.saturating_duration_since(self.last_input);
+        let mut next_check: Option<Duration> = None;
+        self.watchers.retain_mut(|watcher| {
+            let Some(idle) = watcher.idle.upgrade() else {
+                return false;
+            };
+            if !watcher.is_idle {
+                if since_input >= watcher.timeout {
+                    watcher.is_idle = true;
+                    idle.set(true);
+                } else {
+                    let remaining = watcher.timeout - since_input;
+                    next_check = Some(next_check.map_or(remaining, |next| next.min(remaining)));
+                }
+            }
+            true
+        });
+        next_check
+    }
+}
+
+#[test]
+fn idle_resumes_on_input() {
+    let start = Instant::now();
+    let mut state = ActivityState::new(start);
+    let idle = state.watch(Duration::from_secs(10), start);
+    assert!(!idle.get());
+
+    assert_eq!(
+        state.update(start + Duration::from_secs(4)),
+        Some(Duration::from_secs(6))
+    );
+    assert!(!idle.get());
+    assert_eq!(state.update(start + Duration::from_secs(10)), None);
+    assert!(idle.get());
+
+    assert!(state.note_input(start + Duration::from_secs(12)));
+    assert!(!idle.get());
+    assert_eq!(state.last_input, start + Duration::from_secs(12));
+    // Further input doesn't resume watchers that are already active.
+    assert!(!state.note_input(start + Duration::from_secs(13)));
+
+    assert_eq!(
+        state.update(start + Duration::from_secs(20)),
+        Some(Duration::from_secs(3))
+    );
+    state.update(start + Duration::from_secs(23));
+    assert!(idle.get());
+}
+
+#[test]
+fn shortest_timeout_is_checked_next() {
+    let start = Instant::now();
+    let mut state = ActivityState::new(start);
+    let long = state.watch(Duration::from_secs(10), start);
+    let short = state.watch(Duration::from_secs(2), start);
+
+    assert_eq!(state.update(start), Some(Duration::from_secs(2)));
+    assert_eq!(
+        state.update(start + Duration::from_secs(2)),
+        Some(Duration::from_secs(8))
+    );
+    assert!(short.get());
+    assert!(!long.get());
+}
+
+#[test]
+fn dropped_watchers_are_removed() {
+    let start = Instant::now();
+    let mut state = ActivityState::new(start);
+    let idle = state.watch(Duration::from_secs(1), start);
+    assert_eq!(state.watchers.len(), 1);
+
+    drop(idle);
+    state.note_input(start);
+    assert!(state.watchers.is_empty());
+}
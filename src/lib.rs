@@ -20,11 +20,24 @@ mod names;
 #[macro_use]
 pub mod styles;
 mod app;
+pub mod clipboard;
 pub mod debug;
 pub mod fonts;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+#[cfg(all(
+    feature = "global-hotkeys",
+    any(target_os = "windows", target_os = "macos", target_os = "linux")
+))]
+pub mod global_hotkeys;
+pub mod idle;
 pub mod reactive;
+pub mod splash;
+pub mod telemetry;
 mod tick;
 mod tree;
+#[cfg(feature = "updater")]
+pub mod updater;
 pub mod widget;
 pub mod widgets;
 pub mod window;
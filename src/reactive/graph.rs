@@ -0,0 +1,547 @@
+//! Diagnostics for the graph formed by reactive values.
+//!
+//! While tracking is enabled, Cushy records each time a [`Dynamic`] notifies
+//! its observers, which [`Dynamic`]s are updated by the callbacks of another,
+//! and which widgets are invalidated when a [`Dynamic`] changes. A
+//! [`GraphSnapshot`] of this information can be printed, exported to the
+//! [DOT](https://graphviz.org/doc/info/lang.html) format, or searched for
+//! feedback loops.
+//!
+//! Values are only included in the graph once they have been observed while
+//! tracking is enabled.
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use cushy::reactive::graph;
+//! use cushy::reactive::value::{Destination, Dynamic, Source};
+//!
+//! graph::start_tracking(Duration::from_secs(10));
+//!
+//! let count = Dynamic::new(0);
+//! graph::label(&count, "count");
+//! let doubled = count.map_each(|count| count * 2);
+//! graph::label(&doubled, "doubled");
+//! count.set(1);
+//!
+//! println!("{}", graph::snapshot(Duration::from_secs(5)));
+//! graph::stop_tracking();
+//! ```
+
+use std::any::type_name;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use ahash::{AHashMap, AHashSet};
+use parking_lot::Mutex;
+
+use super::value::Dynamic;
+use crate::widget::WidgetId;
+use crate::Lazy;
+
+static TRACKING: AtomicBool = AtomicBool::new(false);
+static GRAPH: Lazy<Mutex<GraphState>> = Lazy::new(|| Mutex::new(GraphState::new()));
+
+thread_local! {
+    static EXECUTING: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Begins recording the reactive graph.
+///
+/// Notifications older than `history` are discarded. Snapshots can only
+/// report notifications that occurred within this duration.
+pub fn start_tracking(history: Duration) {
+    GRAPH.lock().history = history;
+    TRACKING.store(true, Ordering::Release);
+}
+
+/// Stops recording the reactive graph and discards all recorded information.
+pub fn stop_tracking() {
+    TRACKING.store(false, Ordering::Release);
+    let mut graph = GRAPH.lock();
+    graph.nodes.clear();
+    graph.next_id = 0;
+}
+
+/// Returns true if the reactive graph is being recorded.
+#[must_use]
+pub fn is_tracking() -> bool {
+    TRACKING.load(Ordering::Acquire)
+}
+
+/// Labels `dynamic` with `label` in the recorded graph.
+///
+/// This function does nothing if tracking is not enabled.
+pub fn label<T>(dynamic: &Dynamic<T>, label: impl Into<String>) {
+    if !is_tracking() {
+        return;
+    }
+    let mut graph = GRAPH.lock();
+    graph.node(dynamic.graph_id(), type_name::<T>()).label = Some(label.into());
+}
+
+/// Returns a snapshot of the recorded reactive graph, including the number of
+/// notifications that occurred within the most recent `period`.
+#[must_use]
+pub fn snapshot(period: Duration) -> GraphSnapshot {
+    let mut graph = GRAPH.lock();
+    let now = Instant::now();
+    let history = graph.history;
+    let ids = graph
+        .nodes
+        .iter()
+        .map(|(key, node)| (*key, node.id))
+        .collect::<AHashMap<_, _>>();
+    let mut nodes = graph
+        .nodes
+        .values_mut()
+        .map(|node| {
+            node.prune(now, history);
+            let recent = node
+                .notifications
+                .iter()
+                .rev()
+                .take_while(|notified| now.saturating_duration_since(**notified) <= period)
+                .count();
+            let mut edges = node
+                .edges
+                .iter()
+                .filter_map(|(target, count)| {
+                    ids.get(target).map(|target| EdgeSnapshot {
+                        target: *target,
+                        count: *count,
+                    })
+                })
+                .collect::<Vec<_>>();
+            edges.sort_by_key(|edge| edge.target);
+            let mut widgets = node.widgets.iter().copied().collect::<Vec<_>>();
+            widgets.sort_unstable();
+            NodeSnapshot {
+                id: node.id,
+                type_name: node.type_name,
+                label: node.label.clone(),
+                recent_notifications: recent,
+                total_notifications: node.total_notifications,
+                invalidations: node.invalidations,
+                redraws: node.redraws,
+                edges,
+                widgets,
+            }
+        })
+        .collect::<Vec<_>>();
+    nodes.sort_by(|a, b| {
+        b.recent_notifications
+            .cmp(&a.recent_notifications)
+            .then(a.id.cmp(&b.id))
+    });
+    GraphSnapshot { nodes, period }
+}
+
+/// Records that the dynamic identified by `id` is notifying its observers.
+pub(super) fn record_change<T>(id: usize, invalidated_widgets: usize, redraws: usize) {
+    if !is_tracking() {
+        return;
+    }
+    let source = EXECUTING.get();
+    let mut graph = GRAPH.lock();
+    let history = graph.history;
+    let node = graph.node(id, type_name::<T>());
+    let now = Instant::now();
+    node.notifications.push_back(now);
+    node.total_notifications += 1;
+    node.invalidations += invalidated_widgets as u64;
+    node.redraws += redraws as u64;
+    node.prune(now, history);
+
+    if let Some(source) = source {
+        if let Some(source) = graph.nodes.get_mut(&source) {
+            *source.edges.entry(id).or_default() += 1;
+        }
+    }
+}
+
+/// Records that `widget` is invalidated when the dynamic identified by `id`
+/// changes.
+pub(super) fn record_observer<T>(id: usize, widget: WidgetId) {
+    if !is_tracking() {
+        return;
+    }
+    GRAPH
+        .lock()
+        .node(id, type_name::<T>())
+        .widgets
+        .insert(widget);
+}
+
+/// Returns a description of the dynamic identified by `id` for use in
+/// diagnostics.
+pub(super) fn describe(id: usize, type_name: &'static str) -> String {
+    if is_tracking() {
+        if let Some(node) = GRAPH.lock().nodes.get(&id) {
+            return match &node.label {
+                Some(label) => format!("{label} ({type_name})"),
+                None => format!("{} ({type_name})", node.id),
+            };
+        }
+    }
+    type_name.to_string()
+}
+
+/// Removes the dynamic identified by `id` from the graph.
+pub(super) fn forget(id: usize) {
+    if !is_tracking() {
+        return;
+    }
+    GRAPH.lock().nodes.remove(&id);
+}
+
+/// Attributes all changes made by the current thread to the dynamic
+/// identified by `id` until the returned guard is dropped.
+pub(super) fn executing_callbacks(id: usize) -> ExecutingCallbacks {
+    ExecutingCallbacks(EXECUTING.replace(Some(id)))
+}
+
+pub(super) struct ExecutingCallbacks(Option<usize>);
+
+impl Drop for ExecutingCallbacks {
+    fn drop(&mut self) {
+        EXECUTING.set(self.0);
+    }
+}
+
+struct GraphState {
+    nodes: AHashMap<usize, NodeState>,
+    next_id: u64,
+    history: Duration,
+}
+
+impl GraphState {
+    fn new() -> Self {
+        Self {
+            nodes: AHashMap::new(),
+            next_id: 0,
+            history: Duration::from_secs(60),
+        }
+    }
+
+    fn node(&mut self, id: usize, type_name: &'static str) -> &mut NodeState {
+        let next_id = &mut self.next_id;
+        self.nodes.entry(id).or_insert_with(|| {
+            let id = NodeId(*next_id);
+            *next_id += 1;
+            NodeState {
+                id,
+                type_name,
+                label: None,
+                notifications: VecDeque::new(),
+                total_notifications: 0,
+                invalidations: 0,
+                redraws: 0,
+                edges: AHashMap::new(),
+                widgets: AHashSet::new(),
+            }
+        })
+    }
+}
+
+struct NodeState {
+    id: NodeId,
+    type_name: &'static str,
+    label: Option<String>,
+    notifications: VecDeque<Instant>,
+    total_notifications: u64,
+    invalidations: u64,
+    redraws: u64,
+    edges: AHashMap<usize, u64>,
+    widgets: AHashSet<WidgetId>,
+}
+
+impl NodeState {
+    fn prune(&mut self, now: Instant, history: Duration) {
+        while self
+            .notifications
+            .front()
+            .is_some_and(|notified| now.saturating_duration_since(*notified) > history)
+        {
+            self.notifications.pop_front();
+        }
+    }
+}
+
+/// A unique identifier of a value in a [`GraphSnapshot`].
+///
+/// Ids are assigned in the order values are first observed and are reset
+/// when [`stop_tracking()`] is called.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct NodeId(u64);
+
+impl Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A recorded reactive value.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NodeSnapshot {
+    /// The id of this value.
+    pub id: NodeId,
+    /// The name of the type contained in this value.
+    pub type_name: &'static str,
+    /// The label provided to [`label()`], if any.
+    pub label: Option<String>,
+    /// The number of times this value notified its observers within the
+    /// snapshot's period.
+    pub recent_notifications: usize,
+    /// The total number of times this value notified its observers since it
+    /// was first observed.
+    pub total_notifications: u64,
+    /// The total number of widget invalidations caused by changes to this
+    /// value.
+    pub invalidations: u64,
+    /// The total number of window redraws requested by changes to this value.
+    pub redraws: u64,
+    /// The values that have been updated by this value's callbacks.
+    pub edges: Vec<EdgeSnapshot>,
+    /// The widgets that are invalidated when this value changes.
+    pub widgets: Vec<WidgetId>,
+}
+
+impl NodeSnapshot {
+    /// Returns the label of this node, or its type name if it has no label.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.label.as_deref().unwrap_or(self.type_name)
+    }
+}
+
+/// A value being updated by the callbacks of another value.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct EdgeSnapshot {
+    /// The value that was updated.
+    pub target: NodeId,
+    /// The number of times the value was updated.
+    pub count: u64,
+}
+
+/// A snapshot of the recorded reactive graph.
+///
+/// The [`Display`] implementation lists every value, ordered by the number of
+/// recent notifications.
+#[derive(Debug, Clone)]
+pub struct GraphSnapshot {
+    nodes: Vec<NodeSnapshot>,
+    period: Duration,
+}
+
+impl GraphSnapshot {
+    /// Returns the recorded values, ordered by the number of recent
+    /// notifications.
+    #[must_use]
+    pub fn nodes(&self) -> &[NodeSnapshot] {
+        &self.nodes
+    }
+
+    /// Returns the recorded value with `id`, if present.
+    #[must_use]
+    pub fn node(&self, id: NodeId) -> Option<&NodeSnapshot> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Returns each group of values that update each other in a loop.
+    ///
+    /// Feedback loops are not always problematic, as Cushy stops propagating
+    /// changes when a value is set to an equal value. However, loops that
+    /// continuously notify are a common source of wasted work.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<NodeId>> {
+        let mut tarjan = Tarjan {
+            snapshot: self,
+            index: 0,
+            indices: AHashMap::new(),
+            stack: Vec::new(),
+            on_stack: AHashSet::new(),
+            cycles: Vec::new(),
+        };
+        for node in &self.nodes {
+            if !tarjan.indices.contains_key(&node.id) {
+                tarjan.visit(node.id);
+            }
+        }
+        tarjan.cycles
+    }
+
+    /// Returns this graph in the [DOT](https://graphviz.org/doc/info/lang.html)
+    /// format.
+    ///
+    /// Values are labeled with their name and the number of recent
+    /// notifications, and edges are labeled with the number of updates.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph reactive {\n");
+        for node in &self.nodes {
+            let _ = writeln!(
+                dot,
+                "  n{} [label={:?}];",
+                node.id.0,
+                format!(
+                    "{} {}\n{} notifications",
+                    node.id,
+                    node.name(),
+                    node.recent_notifications
+                )
+            );
+            for edge in &node.edges {
+                let _ = writeln!(
+                    dot,
+                    "  n{} -> n{} [label=\"{}\"];",
+                    node.id.0, edge.target.0, edge.count
+                );
+            }
+            if !node.widgets.is_empty() {
+                let _ = writeln!(
+                    dot,
+                    "  n{}_widgets [shape=box, label=\"{} widgets\"];\n  n{} -> n{}_widgets [style=dashed];",
+                    node.id.0,
+                    node.widgets.len(),
+                    node.id.0,
+                    node.id.0
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl Display for GraphSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} values, notifications in the last {:?}:",
+            self.nodes.len(),
+            self.period
+        )?;
+        for node in &self.nodes {
+            write!(
+                f,
+                "{} {}: {} recent, {} total, {} redraws",
+                node.id,
+                node.name(),
+                node.recent_notifications,
+                node.total_notifications,
+                node.redraws
+            )?;
+            if !node.widgets.is_empty() {
+                write!(f, ", {} observing widgets", node.widgets.len())?;
+            }
+            writeln!(f)?;
+            for edge in &node.edges {
+                let target = self.node(edge.target).map_or("", NodeSnapshot::name);
+                writeln!(f, "  -> {} {target} ({} updates)", edge.target, edge.count)?;
+            }
+        }
+
+        for cycle in self.cycles() {
+            f.write_str("feedback loop:")?;
+            for id in cycle {
+                write!(f, " {id}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tarjan's algorithm for finding strongly connected components.
+struct Tarjan<'a> {
+    snapshot: &'a GraphSnapshot,
+    index: usize,
+    indices: AHashMap<NodeId, (usize, usize)>,
+    stack: Vec<NodeId>,
+    on_stack: AHashSet<NodeId>,
+    cycles: Vec<Vec<NodeId>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, id: NodeId) -> usize {
+        let index = self.index;
+        self.index += 1;
+        self.indices.insert(id, (index, index));
+        self.stack.push(id);
+        self.on_stack.insert(id);
+
+        let mut low_link = index;
+        let mut self_loop = false;
+        if let Some(node) = self.snapshot.node(id) {
+            for edge in &node.edges {
+                self_loop |= edge.target == id;
+                if let Some((target_index, _)) = self.indices.get(&edge.target) {
+                    if self.on_stack.contains(&edge.target) {
+                        low_link = low_link.min(*target_index);
+                    }
+                } else {
+                    low_link = low_link.min(self.visit(edge.target));
+                }
+            }
+        }
+        self.indices.insert(id, (index, low_link));
+
+        if low_link == index {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(&member);
+                component.push(member);
+                if member == id {
+                    break;
+                }
+            }
+            if component.len() > 1 || self_loop {
+                component.reverse();
+                self.cycles.push(component);
+            }
+        }
+        low_link
+    }
+}
+
+#[test]
+fn cycles() {
+    fn node(id: u64, targets: &[u64]) -> NodeSnapshot {
+        NodeSnapshot {
+            id: NodeId(id),
+            type_name: "i32",
+            label: None,
+            recent_notifications: 0,
+            total_notifications: 0,
+            invalidations: 0,
+            redraws: 0,
+            edges: targets
+                .iter()
+                .map(|target| EdgeSnapshot {
+                    target: NodeId(*target),
+                    count: 1,
+                })
+                .collect(),
+            widgets: Vec::new(),
+        }
+    }
+
+    let snapshot = GraphSnapshot {
+        nodes: vec![
+            node(0, &[1]),
+            node(1, &[2]),
+            node(2, &[0, 3]),
+            node(3, &[]),
+            node(4, &[4]),
+        ],
+        period: Duration::ZERO,
+    };
+    assert_eq!(
+        snapshot.cycles(),
+        vec![vec![NodeId(0), NodeId(1), NodeId(2)], vec![NodeId(4)]]
+    );
+}
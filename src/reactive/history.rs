@@ -0,0 +1,538 @@
+//! Undo and redo support for [`Dynamic`]s.
+//!
+//! A [`History`] wraps a [`Dynamic`] and records each change made to it, no
+//! matter whether the change was made by code or by a widget bound to the
+//! dynamic. Changes can then be reverted using [`History::undo()`] and
+//! reapplied using [`History::redo()`].
+//!
+//! Each [`History`] records its changes into an [`UndoStack`]. Multiple
+//! histories can share a single stack by creating them with
+//! [`UndoStack::track()`], which allows a document made of many dynamics to be
+//! undone one change at a time. Changes to several dynamics can be combined
+//! into a single undoable step using [`UndoStack::transaction()`].
+//!
+//! Changes to the same dynamic in quick succession, such as each keystroke
+//! while typing into an input, are coalesced into a single step. See
+//! [`UndoStack::coalesce_within()`] to configure this behavior.
+//!
+//! ```rust
+//! use cushy::reactive::history::UndoStack;
+//! use cushy::reactive::value::{Destination, Dynamic, Source};
+//! use cushy::widget::MakeWidget;
+//!
+//! let document = UndoStack::new();
+//! let title = document.track(Dynamic::new(String::from("Untitled")));
+//! let pinned = document.track(Dynamic::new(false));
+//!
+//! let editor = title
+//!     .value()
+//!     .clone()
+//!     .into_input()
+//!     .and("Pinned".into_checkbox(pinned.value().clone()))
+//!     .and(
+//!         "Undo"
+//!             .into_button()
+//!             .on_click({
+//!                 let document = document.clone();
+//!                 move |_| {
+//!                     document.undo();
+//!                 }
+//!             })
+//!             .with_enabled(document.can_undo().clone()),
+//!     )
+//!     .and(
+//!         "Redo"
+//!             .into_button()
+//!             .on_click({
+//!                 let document = document.clone();
+//!                 move |_| {
+//!                     document.redo();
+//!                 }
+//!             })
+//!             .with_enabled(document.can_redo().clone()),
+//!     )
+//!     .into_rows();
+//! ```
+
+use std::any::Any;
+use std::fmt::{self, Debug};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, Source};
+
+/// A [`Dynamic`] whose changes can be undone and redone.
+///
+/// Changes are recorded into an [`UndoStack`], which may be shared with other
+/// histories. The undo and redo functions on this type operate on the entire
+/// stack.
+pub struct History<T> {
+    value: Dynamic<T>,
+    stack: UndoStack,
+}
+
+impl<T> History<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    /// Returns a history that records changes to `value` into a new
+    /// [`UndoStack`].
+    #[must_use]
+    pub fn new(value: impl IntoDynamic<T>) -> Self {
+        UndoStack::new().track(value)
+    }
+
+    /// Returns the dynamic whose changes are recorded.
+    #[must_use]
+    pub const fn value(&self) -> &Dynamic<T> {
+        &self.value
+    }
+
+    /// Returns the stack this history records changes into.
+    #[must_use]
+    pub const fn stack(&self) -> &UndoStack {
+        &self.stack
+    }
+
+    /// Reverts the most recent change recorded in this history's stack.
+    ///
+    /// Returns false if there were no changes to undo.
+    pub fn undo(&self) -> bool {
+        self.stack.undo()
+    }
+
+    /// Reapplies the most recently undone change in this history's stack.
+    ///
+    /// Returns false if there were no changes to redo.
+    pub fn redo(&self) -> bool {
+        self.stack.redo()
+    }
+
+    /// Returns a dynamic that contains true when there are changes to undo.
+    #[must_use]
+    pub fn can_undo(&self) -> &Dynamic<bool> {
+        self.stack.can_undo()
+    }
+
+    /// Returns a dynamic that contains true when there are changes to redo.
+    #[must_use]
+    pub fn can_redo(&self) -> &Dynamic<bool> {
+        self.stack.can_redo()
+    }
+}
+
+impl<T> Clone for History<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+impl<T> Debug for History<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("History")
+            .field("value", &self.value)
+            .field("stack", &self.stack)
+            .finish()
+    }
+}
+
+/// A list of undoable changes to one or more [`Dynamic`]s.
+///
+/// Dynamics are added to a stack using [`track()`](Self::track). Each change
+/// to a tracked dynamic becomes a step that can be undone, unless it is
+/// coalesced with the previous change or made during a
+/// [transaction](Self::transaction).
+#[derive(Clone)]
+pub struct UndoStack {
+    data: Arc<Mutex<StackData>>,
+    can_undo: Dynamic<bool>,
+    can_redo: Dynamic<bool>,
+}
+
+impl UndoStack {
+    /// Returns an empty stack.
+    #[must_use]
+    pub fn new() -> Self {
+        let can_undo = Dynamic::new(false);
+        let can_redo = Dynamic::new(false);
+        Self {
+            data: Arc::new(Mutex::new(StackData {
+                undo: Vec::new(),
+                redo: Vec::new(),
+                values: Vec::new(),
+                transaction: None,
+                transaction_depth: 0,
+                last_change: None,
+                coalesce_within: Duration::from_millis(500),
+                limit: None,
+                can_undo: can_undo.clone(),
+                can_redo: can_redo.clone(),
+            })),
+            can_undo,
+            can_redo,
+        }
+    }
+
+    /// Sets how close together changes to the same dynamic must be to be
+    /// combined into a single step, and returns self.
+    ///
+    /// The default is 500 milliseconds. A duration of zero disables
+    /// coalescing.
+    #[must_use]
+    pub fn coalesce_within(self, duration: Duration) -> Self {
+        self.data.lock().coalesce_within = duration;
+        self
+    }
+
+    /// Sets the maximum number of steps that can be undone, and returns self.
+    ///
+    /// When a change is recorded while the stack is full, the oldest step is
+    /// discarded. By default, the number of steps is unlimited.
+    #[must_use]
+    pub fn with_limit(self, steps: usize) -> Self {
+        let mut data = self.data.lock();
+        data.limit = Some(steps);
+        data.enforce_limit();
+        drop(data);
+        self
+    }
+
+    /// Returns a [`History`] that records each change to `value` into this
+    /// stack.
+    #[must_use]
+    pub fn track<T>(&self, value: impl IntoDynamic<T>) -> History<T>
+    where
+        T: Clone + PartialEq + Send + Sync + 'static,
+    {
+        let value = value.into_dynamic();
+        let mut data = self.data.lock();
+        let index = data.values.len();
+        data.values.push(Arc::new(TrackedDynamic {
+            recorded: Mutex::new(value.get()),
+            dynamic: value.clone(),
+        }));
+        drop(data);
+
+        let stack = Arc::downgrade(&self.data);
+        value
+            .on_change(move || {
+                if let Some(data) = Weak::upgrade(&stack) {
+                    data.lock().sync(index);
+                }
+            })
+            .persist();
+
+        History {
+            value,
+            stack: self.clone(),
+        }
+    }
+
+    /// Reverts the most recent step.
+    ///
+    /// Returns false if there were no steps to undo.
+    pub fn undo(&self) -> bool {
+        let mut data = self.data.lock();
+        data.sync_all();
+        let Some(step) = data.undo.pop() else {
+            return false;
+        };
+        for change in step.changes.iter().rev() {
+            data.values[change.index].apply(&*change.before);
+        }
+        data.redo.push(step);
+        data.last_change = None;
+        data.update_status();
+        true
+    }
+
+    /// Reapplies the most recently undone step.
+    ///
+    /// Returns false if there were no steps to redo. Recording a new change
+    /// discards all steps that can be redone.
+    pub fn redo(&self) -> bool {
+        let mut data = self.data.lock();
+        data.sync_all();
+        let Some(step) = data.redo.pop() else {
+            return false;
+        };
+        for change in &step.changes {
+            data.values[change.index].apply(&*change.after);
+        }
+        data.undo.push(step);
+        data.last_change = None;
+        data.update_status();
+        true
+    }
+
+    /// Returns a dynamic that contains true when there are steps to undo.
+    #[must_use]
+    pub const fn can_undo(&self) -> &Dynamic<bool> {
+        &self.can_undo
+    }
+
+    /// Returns a dynamic that contains true when there are steps to redo.
+    #[must_use]
+    pub const fn can_redo(&self) -> &Dynamic<bool> {
+        &self.can_redo
+    }
+
+    /// Records any pending changes and prevents the next change from being
+    /// coalesced with them.
+    ///
+    /// This is useful to separate logically distinct edits, such as when an
+    /// input loses focus.
+    pub fn checkpoint(&self) {
+        let mut data = self.data.lock();
+        data.sync_all();
+        data.last_change = None;
+    }
+
+    /// Invokes `changes`, recording all changes made to tracked dynamics while
+    /// it executes as a single step, and returns the result.
+    ///
+    /// Transactions can be nested. Changes are recorded as a single step when
+    /// the outermost transaction completes.
+    pub fn transaction<R>(&self, changes: impl FnOnce() -> R) -> R {
+        let mut data = self.data.lock();
+        data.sync_all();
+        if data.transaction_depth == 0 {
+            data.transaction = Some(Step::default());
+        }
+        data.transaction_depth += 1;
+        drop(data);
+
+        // Ending the transaction in drop ensures the stack remains usable if
+        // `changes` panics.
+        let _transaction = TransactionGuard(self);
+        changes()
+    }
+
+    /// Removes all steps from this stack.
+    pub fn clear(&self) {
+        let mut data = self.data.lock();
+        data.sync_all();
+        data.undo.clear();
+        data.redo.clear();
+        data.last_change = None;
+        data.update_status();
+    }
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for UndoStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = self.data.lock();
+        f.debug_struct("UndoStack")
+            .field("undo", &data.undo.len())
+            .field("redo", &data.redo.len())
+            .field("tracked", &data.values.len())
+            .finish_non_exhaustive()
+    }
+}
+
+struct TransactionGuard<'a>(&'a UndoStack);
+
+impl Drop for TransactionGuard<'_> {
+    fn drop(&mut self) {
+        let mut data = self.0.data.lock();
+        data.sync_all();
+        data.transaction_depth -= 1;
+        if data.transaction_depth == 0 {
+            if let Some(step) = data
+                .transaction
+                .take()
+                .filter(|step| !step.changes.is_empty())
+            {
+                data.push_step(step);
+            }
+            data.last_change = None;
+            data.update_status();
+        }
+    }
+}
+
+struct StackData {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    values: Vec<Arc<dyn TrackedValue>>,
+    transaction: Option<Step>,
+    transaction_depth: usize,
+    last_change: Option<(usize, Instant)>,
+    coalesce_within: Duration,
+    limit: Option<usize>,
+    can_undo: Dynamic<bool>,
+    can_redo: Dynamic<bool>,
+}
+
+impl StackData {
+    /// Records the change to the value at `index`, if it has changed since it
+    /// was last recorded.
+    fn sync(&mut self, index: usize) {
+        let value = self.values[index].clone();
+        if let Some((before, after)) = value.take_change() {
+            self.record(Change {
+                index,
+                before,
+                after,
+            });
+            self.update_status();
+        }
+    }
+
+    fn sync_all(&mut self) {
+        for index in 0..self.values.len() {
+            self.sync(index);
+        }
+    }
+
+    fn record(&mut self, change: Change) {
+        self.redo.clear();
+        let index = change.index;
+        if let Some(transaction) = &mut self.transaction {
+            transaction.merge(change);
+            return;
+        }
+
+        let now = Instant::now();
+        let coalesce = self.last_change.is_some_and(|(last_index, changed_at)| {
+            last_index == index && now.saturating_duration_since(changed_at) < self.coalesce_within
+        });
+        self.last_change = Some((index, now));
+        match self.undo.last_mut() {
+            Some(step) if coalesce => step.merge(change),
+            _ => self.push_step(Step {
+                changes: vec![change],
+            }),
+        }
+    }
+
+    fn push_step(&mut self, step: Step) {
+        self.undo.push(step);
+        self.enforce_limit();
+    }
+
+    fn enforce_limit(&mut self) {
+        if let Some(limit) = self.limit {
+            let excess = self.undo.len().saturating_sub(limit);
+            self.undo.drain(..excess);
+        }
+    }
+
+    fn update_status(&self) {
+        self.can_undo.set(!self.undo.is_empty());
+        self.can_redo.set(!self.redo.is_empty());
+    }
+}
+
+#[derive(Default)]
+struct Step {
+    changes: Vec<Change>,
+}
+
+impl Step {
+    /// Adds `change` to this step. If this step already contains a change to
+    /// the same value, the existing change is updated to end at the new
+    /// value.
+    fn merge(&mut self, change: Change) {
+        if let Some(existing) = self
+            .changes
+            .iter_mut()
+            .find(|existing| existing.index == change.index)
+        {
+            existing.after = change.after;
+        } else {
+            self.changes.push(change);
+        }
+    }
+}
+
+struct Change {
+    index: usize,
+    before: Box<dyn Any + Send + Sync>,
+    after: Box<dyn Any + Send + Sync>,
+}
+
+trait TrackedValue: Send + Sync + 'static {
+    /// Returns the previously recorded value and the current value, if the
+    /// value has changed since it was last recorded.
+    fn take_change(&self) -> Option<(Box<dyn Any + Send + Sync>, Box<dyn Any + Send + Sync>)>;
+
+    /// Sets the value to `value` without recording a change.
+    fn apply(&self, value: &(dyn Any + Send + Sync));
+}
+
+struct TrackedDynamic<T> {
+    dynamic: Dynamic<T>,
+    /// The value as of the last recorded change.
+    recorded: Mutex<T>,
+}
+
+impl<T> TrackedValue for TrackedDynamic<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    fn take_change(&self) -> Option<(Box<dyn Any + Send + Sync>, Box<dyn Any + Send + Sync>)> {
+        let current = self.dynamic.get();
+        let mut recorded = self.recorded.lock();
+        if *recorded == current {
+            return None;
+        }
+        let before = std::mem::replace(&mut *recorded, current.clone());
+        Some((Box::new(before), Box::new(current)))
+    }
+
+    fn apply(&self, value: &(dyn Any + Send + Sync)) {
+        let Some(value) = value.downcast_ref::<T>() else {
+            return;
+        };
+        // The recorded value is updated first so that the change notification
+        // for this update is not recorded as a new change.
+        *self.recorded.lock() = value.clone();
+        self.dynamic.set(value.clone());
+    }
+}
+
+#[test]
+fn transactions_undo_as_one_step() {
+    let stack = UndoStack::new();
+    let count = stack.track(Dynamic::new(0));
+    let name = stack.track(Dynamic::new(String::new()));
+
+    count.value().set(1);
+    stack.checkpoint();
+    stack.transaction(|| {
+        count.value().set(2);
+        name.value().set(String::from("two"));
+    });
+    assert!(stack.can_undo().get());
+
+    assert!(count.undo());
+    assert_eq!(count.value().get(), 1);
+    assert_eq!(name.value().get(), "");
+    assert!(stack.can_redo().get());
+
+    assert!(stack.undo());
+    assert_eq!(count.value().get(), 0);
+    assert!(!stack.undo());
+    assert!(!stack.can_undo().get());
+
+    assert!(stack.redo());
+    assert!(stack.redo());
+    assert_eq!(count.value().get(), 2);
+    assert_eq!(name.value().get(), "two");
+    assert!(!stack.can_redo().get());
+}
@@ -0,0 +1,404 @@
+//! Automatically saving [`Dynamic`]s to disk.
+//!
+//! [`Persist`] loads a dynamic's initial value from a [`Store`], and saves the
+//! dynamic's value back to the store each time it changes. Writes are
+//! debounced, so that rapid changes such as dragging a slider are only written
+//! once the value stops changing.
+//!
+//! Changes that haven't been written yet are saved when the dynamic is
+//! dropped, when [`flush()`] is called, and when
+//! [`PendingApp::run()`](crate::Run::run) returns.
+//!
+//! Values can be stored anywhere by implementing [`Store`]. Cushy provides
+//! file-based stores for common formats:
+//!
+//! - [`JsonFile`] requires the `json` feature.
+//! - [`RonFile`] requires the `ron` feature.
+//!
+//! This module requires the `serde` feature.
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "json")]
+//! # fn example() {
+//! use cushy::reactive::persist::{JsonFile, Persist};
+//! use cushy::widget::MakeWidget;
+//!
+//! let dark_mode = Persist::new(JsonFile::new("dark-mode.json")).load_or(false);
+//! let settings = "Dark Mode".into_checkbox(dark_mode);
+//! # }
+//! ```
+
+use std::fmt::{self, Debug};
+use std::io;
+#[cfg(any(feature = "json", feature = "ron"))]
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::reactive::value::{Destination, Dynamic, Source};
+
+/// A location that a persisted value is loaded from and saved to.
+pub trait Store<T>: Send + Sync + 'static {
+    /// Returns the stored value, or `None` if no value has been stored.
+    fn load(&self) -> io::Result<Option<T>>;
+
+    /// Stores `value`, replacing any previously stored value.
+    fn save(&self, value: &T) -> io::Result<()>;
+}
+
+/// Loads a [`Dynamic`]'s value from a [`Store`], and saves the value each
+/// time it changes.
+///
+/// If the store cannot be read, the error is logged and the default value is
+/// used. Errors saving the value are logged.
+pub struct Persist<T> {
+    store: Arc<dyn Store<T>>,
+    delay: Duration,
+}
+
+impl<T> Persist<T>
+where
+    T: Serialize + DeserializeOwned + Clone + PartialEq + Send + Sync + 'static,
+{
+    /// Returns a persistence that loads from and saves to `store`.
+    #[must_use]
+    pub fn new(store: impl Store<T>) -> Self {
+        Self {
+            store: Arc::new(store),
+            delay: Duration::from_millis(250),
+        }
+    }
+
+    /// Sets how long a value must stop changing before it is saved, and
+    /// returns self.
+    ///
+    /// The default delay is 250 milliseconds.
+    #[must_use]
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Returns a dynamic containing the stored value, or `default` if no value
+    /// has been stored.
+    #[must_use]
+    pub fn load_or(self, default: T) -> Dynamic<T> {
+        self.load_or_else(|| default)
+    }
+
+    /// Returns a dynamic containing the stored value, or `T::default()` if no
+    /// value has been stored.
+    #[must_use]
+    pub fn load_or_default(self) -> Dynamic<T>
+    where
+        T: Default,
+    {
+        self.load_or_else(T::default)
+    }
+
+    /// Returns a dynamic containing the stored value, or the result of
+    /// `default` if no value has been stored.
+    #[must_use]
+    pub fn load_or_else(self, default: impl FnOnce() -> T) -> Dynamic<T> {
+        let dynamic = Dynamic::new(self.load().unwrap_or_else(default));
+        self.save_changes(&dynamic);
+        dynamic
+    }
+
+    /// Replaces the contents of `dynamic` with the stored value, if one has
+    /// been stored, and saves `dynamic` each time it changes.
+    pub fn attach(self, dynamic: &Dynamic<T>) {
+        if let Some(stored) = self.load() {
+            dynamic.set(stored);
+        }
+        self.save_changes(dynamic);
+    }
+
+    fn load(&self) -> Option<T> {
+        self.store.load().unwrap_or_else(|err| {
+            tracing::warn!("error loading persisted value: {err}");
+            None
+        })
+    }
+
+    fn save_changes(self, dynamic: &Dynamic<T>) {
+        let saver = Arc::new(Saver {
+            store: self.store,
+            unsaved: Mutex::new(None),
+        });
+        let weak: Weak<dyn Flush> = Arc::downgrade(&saver);
+        let mut savers = SAVERS.lock();
+        savers.retain(|saver| saver.strong_count() > 0);
+        savers.push(weak);
+        drop(savers);
+
+        dynamic
+            .for_each_subsequent_cloned({
+                let saver = saver.clone();
+                move |value| *saver.unsaved.lock() = Some(value)
+            })
+            .persist();
+        dynamic
+            .debounced_with_delay(self.delay)
+            .for_each_subsequent(move |_| saver.save_unsaved())
+            .persist();
+    }
+}
+
+/// Saves all persisted values that have changed since they were last saved.
+///
+/// This is called automatically when [`PendingApp::run()`](crate::Run::run)
+/// returns. Apps that exit by other means, such as by calling
+/// [`std::process::exit()`], should call this function first to ensure recent
+/// changes are not lost.
+pub fn flush() {
+    let savers = SAVERS
+        .lock()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect::<Vec<_>>();
+    for saver in savers {
+        saver.flush();
+    }
+}
+
+/// Every value being persisted, allowing unsaved changes to be flushed.
+static SAVERS: Mutex<Vec<Weak<dyn Flush>>> = Mutex::new(Vec::new());
+
+trait Flush: Send + Sync {
+    fn flush(&self);
+}
+
+/// Saves a persisted value, keeping track of the most recent change that
+/// hasn't been saved yet.
+struct Saver<T> {
+    store: Arc<dyn Store<T>>,
+    unsaved: Mutex<Option<T>>,
+}
+
+impl<T> Saver<T> {
+    fn save_unsaved(&self) {
+        let Some(value) = self.unsaved.lock().take() else {
+            return;
+        };
+        if let Err(err) = self.store.save(&value) {
+            tracing::warn!("error saving persisted value: {err}");
+        }
+    }
+}
+
+impl<T> Flush for Saver<T>
+where
+    T: Send,
+{
+    fn flush(&self) {
+        self.save_unsaved();
+    }
+}
+
+impl<T> Drop for Saver<T> {
+    fn drop(&mut self) {
+        self.save_unsaved();
+    }
+}
+
+impl<T> Debug for Persist<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Persist")
+            .field("delay", &self.delay)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`Store`] that saves values as JSON in a file.
+///
+/// This type requires the `json` feature.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone)]
+pub struct JsonFile {
+    path: PathBuf,
+}
+
+#[cfg(feature = "json")]
+impl JsonFile {
+    /// Returns a store that saves values to `path`.
+    ///
+    /// Any missing parent directories are created when a value is saved.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> Store<T> for JsonFile
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn load(&self) -> io::Result<Option<T>> {
+        read_file(&self.path)?
+            .map(|contents| serde_json::from_str(&contents).map_err(io::Error::from))
+            .transpose()
+    }
+
+    fn save(&self, value: &T) -> io::Result<()> {
+        write_file(&self.path, &serde_json::to_string_pretty(value)?)
+    }
+}
+
+/// A [`Store`] that saves values as [RON](https://github.com/ron-rs/ron) in a
+/// file.
+///
+/// This type requires the `ron` feature.
+#[cfg(feature = "ron")]
+#[derive(Debug, Clone)]
+pub struct RonFile {
+    path: PathBuf,
+}
+
+#[cfg(feature = "ron")]
+impl RonFile {
+    /// Returns a store that saves values to `path`.
+    ///
+    /// Any missing parent directories are created when a value is saved.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(feature = "ron")]
+impl<T> Store<T> for RonFile
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn load(&self) -> io::Result<Option<T>> {
+        read_file(&self.path)?
+            .map(|contents| {
+                ron::from_str(&contents)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            })
+            .transpose()
+    }
+
+    fn save(&self, value: &T) -> io::Result<()> {
+        let contents = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        write_file(&self.path, &contents)
+    }
+}
+
+/// Returns the contents of `path`, or `None` if it does not exist.
+#[cfg(any(feature = "json", feature = "ron"))]
+fn read_file(path: &Path) -> io::Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Replaces the contents of `path` with `contents`.
+///
+/// The contents are written to a temporary file that is then renamed, which
+/// prevents a partially written file from being loaded if the app exits while
+/// saving.
+#[cfg(any(feature = "json", feature = "ron"))]
+fn write_file(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    std::fs::write(&temporary, contents)?;
+    std::fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+#[derive(Default, Clone)]
+struct MemoryStore(Arc<Mutex<Option<u32>>>);
+
+#[cfg(test)]
+impl Store<u32> for MemoryStore {
+    fn load(&self) -> io::Result<Option<u32>> {
+        Ok(*self.0.lock())
+    }
+
+    fn save(&self, value: &u32) -> io::Result<()> {
+        *self.0.lock() = Some(*value);
+        Ok(())
+    }
+}
+
+#[test]
+fn load_errors_use_default() {
+    struct Unreadable;
+
+    impl Store<u32> for Unreadable {
+        fn load(&self) -> io::Result<Option<u32>> {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "corrupt"))
+        }
+
+        fn save(&self, _value: &u32) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    assert_eq!(Persist::new(Unreadable).load_or(7).get(), 7);
+}
+
+#[test]
+fn flush_saves_pending_changes() {
+    let store = MemoryStore::default();
+    *store.0.lock() = Some(1);
+    let value = Persist::new(store.clone())
+        .delay(Duration::from_secs(60))
+        .load_or_default();
+    assert_eq!(value.get(), 1);
+
+    value.set(2);
+    assert_eq!(*store.0.lock(), Some(1));
+    flush();
+    assert_eq!(*store.0.lock(), Some(2));
+
+    // Flushing again has nothing left to save.
+    *store.0.lock() = Some(3);
+    flush();
+    assert_eq!(*store.0.lock(), Some(3));
+}
+
+#[cfg(test)]
+#[cfg(any(feature = "json", feature = "ron"))]
+fn round_trip<S>(store: impl Fn() -> S, path: &Path)
+where
+    S: Store<Vec<String>>,
+{
+    let _ = std::fs::remove_file(path);
+    assert_eq!(Store::<Vec<String>>::load(&store()).unwrap(), None);
+
+    let value = vec![String::from("a"), String::from("b")];
+    store().save(&value).unwrap();
+    assert_eq!(store().load().unwrap(), Some(value));
+
+    std::fs::write(path, "not valid").unwrap();
+    assert!(Store::<Vec<String>>::load(&store()).is_err());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_file_round_trip() {
+    let path = std::env::temp_dir().join(format!("cushy-persist-{}.json", std::process::id()));
+    round_trip(|| JsonFile::new(&path), &path);
+}
+
+#[test]
+#[cfg(feature = "ron")]
+fn ron_file_round_trip() {
+    let path = std::env::temp_dir().join(format!("cushy-persist-{}.ron", std::process::id()));
+    round_trip(|| RonFile::new(&path), &path);
+}
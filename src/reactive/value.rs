@@ -22,7 +22,7 @@ use parking_lot::{Condvar, Mutex, MutexGuard};
 use crate::animation::{AnimationHandle, DynamicTransition, IntoAnimate, LinearInterpolate, Spawn};
 use crate::context::{self, Trackable, WidgetContext};
 use crate::reactive::{
-    defer_execute_callbacks, CallbackCollection, CallbackDisconnected, CallbackHandle,
+    defer_execute_callbacks, graph, CallbackCollection, CallbackDisconnected, CallbackHandle,
     CallbackHandleData, CallbackHandleInner, CallbackKind, ChangeCallbacks, ChangeCallbacksData,
     IntoOption,
 };
@@ -625,6 +625,12 @@ pub trait Destination<T> {
     /// Stores `new_value` in this dynamic. Before returning from this function,
     /// all observers will be notified that the contents have been updated.
     ///
+    /// If `new_value` is equal to the currently stored value, the contents are
+    /// left unchanged and observers are not notified. This prevents sources
+    /// that repeatedly write identical values from causing downstream
+    /// callbacks, layouts, and redraws. Use [`Self::set_if_changed()`] to
+    /// detect whether the value was updated.
+    ///
     /// If the calling thread has exclusive access to the contents of this
     /// dynamic, this call will return None and the value will not be updated.
     /// If detecting this is important, use [`Self::try_replace()`].
@@ -646,6 +652,20 @@ pub trait Destination<T> {
         let _old = self.replace(new_value);
     }
 
+    /// Stores `new_value` in this dynamic if it is not equal to the currently
+    /// stored value. Returns true if the value was updated and observers were
+    /// notified.
+    ///
+    /// Returns false without notifying observers if `new_value` is equal to
+    /// the currently stored value or if the calling thread already has
+    /// exclusive access to the contents of this dynamic.
+    fn set_if_changed(&self, new_value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.try_replace(new_value).is_ok()
+    }
+
     /// Replaces the current value with `new_value` if the current value is
     /// equal to `expected_current`.
     ///
@@ -1211,6 +1231,18 @@ where
 
 /// An instance of a value that provides APIs to observe and react to its
 /// contents.
+///
+/// # Update Cycles
+///
+/// Callbacks can change the values they observe, such as two values whose
+/// callbacks update each other. Each change is propagated so that the values
+/// can settle. If a chain of updates would notify a value's observers about
+/// the same generation twice, or changes the same value 16 times without
+/// settling, the new value is stored but the value's callbacks are not invoked
+/// again. A debug message naming each value in the cycle is logged instead.
+/// While [tracking](crate::reactive::graph::start_tracking) is enabled, values
+/// can be given names for these messages using
+/// [`graph::label`](crate::reactive::graph::label).
 pub struct Dynamic<T>(Arc<DynamicData<T>>);
 
 impl<T> Dynamic<T> {
@@ -1228,6 +1260,11 @@ impl<T> Dynamic<T> {
         Arc::as_ptr(&self.0).cast()
     }
 
+    /// Returns the id of this value in the [reactive graph](super::graph).
+    pub(super) fn graph_id(&self) -> usize {
+        self.0.graph_id()
+    }
+
     /// Returns a weak reference to this dynamic.
     ///
     /// This is powered by [`Arc`]/[`Weak`] and follows the same semantics for
@@ -1932,6 +1969,14 @@ impl<T> DynamicData<T> {
     pub fn invalidate_when_changed(&self, window: WindowHandle, widget: WidgetId) {
         let mut state = self.state::<true>().expect("deadlocked");
         state.invalidation.widgets.insert((window, widget));
+        drop(state);
+        graph::record_observer::<T>(self.graph_id(), widget);
+    }
+
+    fn graph_id(&self) -> usize {
+        // The lock is shared with this value's callbacks, which allows
+        // identifying the value while its callbacks are executing.
+        Arc::as_ptr(&self.lock) as usize
     }
 
     pub fn map_mut<R>(&self, map: impl FnOnce(Mutable<T>) -> R) -> Result<R, DeadlockError> {
@@ -1953,6 +1998,12 @@ impl<T> DynamicData<T> {
     }
 }
 
+impl<T> Drop for DynamicData<T> {
+    fn drop(&mut self) {
+        graph::forget(self.graph_id());
+    }
+}
+
 fn dynamic_for_each<T, F>(this: &Arc<DynamicData<T>>, map: F) -> CallbackHandle
 where
     F: FnMut() -> Result<(), CallbackDisconnected> + Send + 'static,
@@ -2076,12 +2127,21 @@ impl<T> State<T> {
 
     fn note_changed(&mut self) -> ChangeCallbacks {
         self.wrapped.generation = self.wrapped.generation.next();
+        graph::record_change::<T>(
+            Arc::as_ptr(&self.callbacks.lock) as usize,
+            self.invalidation.widgets.len(),
+            self.invalidation.windows.len(),
+        );
 
         if !InvalidationBatch::take_invalidations(&mut self.invalidation) {
             self.invalidation.invoke();
         }
 
-        ChangeCallbacks::new(self.callbacks.clone())
+        ChangeCallbacks::new(
+            self.callbacks.clone(),
+            self.wrapped.generation,
+            std::any::type_name::<T>(),
+        )
     }
 
     fn debug(&self, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result
@@ -4434,6 +4494,22 @@ fn compare_swap() {
     assert_eq!(dynamic.get(), 0);
 }
 
+#[test]
+fn set_if_changed() {
+    let dynamic = Dynamic::new(1);
+    let initial = dynamic.generation();
+
+    assert!(!dynamic.set_if_changed(1));
+    dynamic.set(1);
+    assert_eq!(dynamic.generation(), initial);
+
+    assert!(dynamic.set_if_changed(2));
+    let updated = dynamic.generation();
+    assert_ne!(updated, initial);
+    dynamic.set(2);
+    assert_eq!(dynamic.generation(), updated);
+}
+
 #[test]
 fn ref_counts() {
     let dynamic = Dynamic::new(1);
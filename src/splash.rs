@@ -0,0 +1,204 @@
+//! A splash screen shown while an application starts.
+//!
+//! Applications that perform slow work before their main window can be shown,
+//! such as loading assets or connecting to a server, can display a [`Splash`]
+//! using [`PendingApp::on_startup_with_splash`]. The splash window is opened
+//! as soon as the event loop starts, and the startup task reports its progress
+//! using a [`SplashProgress`].
+//!
+//! ```rust,no_run
+//! use cushy::splash::Splash;
+//! use cushy::widget::MakeWidget;
+//! use cushy::{Open, PendingApp, Run};
+//!
+//! fn main() -> cushy::Result {
+//!     let mut app = PendingApp::default();
+//!     app.on_startup_with_splash(
+//!         Splash::new("My App"),
+//!         |app, progress| -> cushy::Result {
+//!             progress.set_message("Loading assets");
+//!             progress.set_progress(0.5);
+//!             progress
+//!                 .close_when_shown("Hello, World!".into_window())
+//!                 .open(app)?;
+//!             Ok(())
+//!         },
+//!     )?;
+//!     app.run()
+//! }
+//! ```
+
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::animation::ZeroToOne;
+use crate::app::{PendingApp, StartupResult};
+use crate::reactive::value::{Destination, Dynamic};
+use crate::widget::{MakeWidget, WidgetInstance};
+use crate::widgets::progress::{Progress, Progressable};
+use crate::window::{Window, WindowBehavior, WindowHandle};
+use crate::App;
+
+/// A window displayed while an application starts.
+///
+/// A splash window displays its contents above a message and a progress bar.
+/// It has no decorations, cannot be resized, and is centered on the monitor
+/// it appears on.
+#[derive(Debug, Clone)]
+pub struct Splash {
+    contents: WidgetInstance,
+}
+
+impl Splash {
+    /// Returns a splash screen that displays `contents`, such as an
+    /// [`Image`](crate::widgets::Image) of the application's logo.
+    #[must_use]
+    pub fn new(contents: impl MakeWidget) -> Self {
+        Self {
+            contents: contents.make_widget(),
+        }
+    }
+
+    fn open(self, progress: &SplashProgress, app: &mut PendingApp) -> crate::Result<WindowHandle> {
+        let mut window = self
+            .contents
+            .and(progress.message.clone())
+            .and(progress.progress.clone().progress_bar())
+            .into_rows()
+            .pad()
+            .into_window()
+            .decorated(false)
+            .resizable(false)
+            .size_to_content();
+        // The splash screen is drawn before any other windows, so it avoids
+        // the cost of creating multisampled render targets.
+        window.multisample_count = NonZeroU32::MIN;
+        window.open_centered(app)
+    }
+}
+
+/// Reports the progress of an application's startup to a [`Splash`].
+#[derive(Debug, Clone)]
+pub struct SplashProgress {
+    message: Dynamic<String>,
+    progress: Dynamic<Progress>,
+    splash: Dynamic<Option<WindowHandle>>,
+    handed_off: Arc<AtomicBool>,
+}
+
+impl SplashProgress {
+    fn new() -> Self {
+        Self {
+            message: Dynamic::default(),
+            progress: Dynamic::default(),
+            splash: Dynamic::default(),
+            handed_off: Arc::default(),
+        }
+    }
+
+    /// Sets the message displayed above the progress bar.
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.message.set(message.into());
+    }
+
+    /// Sets the portion of the startup that has been completed.
+    pub fn set_progress(&self, progress: impl Into<ZeroToOne>) {
+        self.progress.set(Progress::Percent(progress.into()));
+    }
+
+    /// Displays an indeterminant progress bar.
+    pub fn set_indeterminant(&self) {
+        self.progress.set(Progress::Indeterminant);
+    }
+
+    /// Returns `window` configured to close the splash screen once it has
+    /// rendered its first frame.
+    ///
+    /// By default, the splash screen closes as soon as the startup function
+    /// returns. When the main window is opened using the window returned from
+    /// this function, the splash screen instead remains visible until the main
+    /// window has drawn its contents, preventing a blank window from being
+    /// shown.
+    pub fn close_when_shown<Behavior>(&self, window: Window<Behavior>) -> Window<Behavior>
+    where
+        Behavior: WindowBehavior,
+    {
+        self.handed_off.store(true, Ordering::Relaxed);
+        let this = self.clone();
+        window.on_first_frame(move || this.close())
+    }
+
+    /// Closes the splash screen.
+    pub fn close(&self) {
+        if let Some(splash) = self.splash.take() {
+            splash.request_close();
+        }
+    }
+}
+
+impl PendingApp {
+    /// Displays `splash` as soon as the application starts, and then executes
+    /// `on_startup`.
+    ///
+    /// `on_startup` receives a [`SplashProgress`] that updates the splash
+    /// screen. The splash screen is closed when `on_startup` returns, unless
+    /// the main window was configured using
+    /// [`SplashProgress::close_when_shown`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the splash window cannot be opened.
+    pub fn on_startup_with_splash<F, R>(
+        &mut self,
+        splash: Splash,
+        on_startup: F,
+    ) -> crate::Result<()>
+    where
+        F: FnOnce(&mut App, &SplashProgress) -> R + Send + 'static,
+        R: StartupResult,
+    {
+        let progress = SplashProgress::new();
+        let handle = splash.open(&progress, self)?;
+        progress.splash.set(Some(handle));
+
+        self.on_startup(move |app| {
+            let result = on_startup(app, &progress).into_result();
+            if result.is_err() || !progress.handed_off.load(Ordering::Relaxed) {
+                progress.close();
+            }
+            result
+        });
+        Ok(())
+    }
+}
+
+#[test]
+fn progress_updates_and_hands_off_closing() {
+    use crate::reactive::value::Source;
+    use crate::widgets::Space;
+    use crate::window::PendingWindow;
+
+    let progress = SplashProgress::new();
+    progress.set_message("Loading assets");
+    progress.set_progress(0.5);
+    assert_eq!(progress.message.get(), "Loading assets");
+    assert_eq!(
+        progress.progress.get(),
+        Progress::Percent(ZeroToOne::new(0.5))
+    );
+    progress.set_indeterminant();
+    assert_eq!(progress.progress.get(), Progress::Indeterminant);
+
+    progress.splash.set(Some(PendingWindow::default().handle()));
+    assert!(!progress.handed_off.load(Ordering::Relaxed));
+    let _window = progress.close_when_shown(Window::for_widget(Space::clear()));
+    assert!(progress.handed_off.load(Ordering::Relaxed));
+    // Handing off closing leaves the splash open until the first frame.
+    assert!(progress.splash.map_ref(Option::is_some));
+
+    progress.close();
+    assert!(progress.splash.map_ref(Option::is_none));
+    // Closing again is a no-op.
+    progress.close();
+}
@@ -27,7 +27,7 @@ use crate::context::{Trackable, WidgetContext};
 use crate::names::Name;
 use crate::reactive::value::{Dynamic, IntoValue, Source, Value};
 use crate::utils::Lazy;
-use crate::widget::MakeWidget;
+use crate::widget::{MakeWidget, WidgetId};
 use crate::widgets::input::CowString;
 use crate::widgets::ComponentProbe;
 
@@ -260,6 +260,17 @@ impl Styles {
         }
     }
 
+    /// Returns true if this collection contains a component named `name`.
+    ///
+    /// If `inheritable_only` is true, components that are not inherited by
+    /// [`inherit_from()`](Self::inherit_from) are ignored.
+    pub(crate) fn contains(&self, name: &ComponentName, inheritable_only: bool) -> bool {
+        self.0
+            .components
+            .get(name)
+            .is_some_and(|stored| stored.inheritable || !inheritable_only)
+    }
+
     /// Returns this collection of styles without any local style definitions.
     #[must_use]
     pub fn into_inherited(self) -> Self {
@@ -479,6 +490,13 @@ pub enum Component {
     HorizontalAlign(HorizontalAlign),
     /// A vertical alignment.
     VerticalAlign(VerticalAlign),
+    /// A two-dimensional measurement.
+    Size(Size<Dimension>),
+    /// A policy for handling content that does not fit within a widget's
+    /// bounds.
+    OverflowPolicy(OverflowPolicy),
+    /// A signed integer.
+    Integer(i32),
 
     /// A custom component type.
     Custom(CustomComponent),
@@ -839,6 +857,11 @@ impl From<Lp> for Dimension {
     }
 }
 
+impl Dimension {
+    /// The largest representable dimension.
+    pub const MAX: Self = Dimension::Px(Px::MAX);
+}
+
 impl Zero for Dimension {
     const ZERO: Self = Dimension::Px(Px::ZERO);
 
@@ -1200,6 +1223,48 @@ impl From<&'static Lazy<ComponentName>> for ComponentName {
     }
 }
 
+/// Where the value of a style component for a widget came from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum StyleSource {
+    /// The component was attached to the widget itself, such as by using
+    /// [`MakeWidget::with()`].
+    Inline,
+    /// The component was attached to an ancestor of the widget, and inherited
+    /// from the ancestor with this id.
+    Inherited(WidgetId),
+    /// The component was not set, and the component's default value is used.
+    /// Most default values are derived from the current theme.
+    Default,
+}
+
+/// A style component that is set for a widget.
+///
+/// See
+/// [`WidgetContext::applied_styles()`](crate::context::WidgetContext::applied_styles)
+/// for more information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedComponent {
+    /// The name of the component.
+    pub name: ComponentName,
+    /// The current value of the component.
+    pub value: Component,
+    /// Where this component was set.
+    pub source: StyleSource,
+}
+
+/// The resolved value of a style component and where the value came from.
+///
+/// See
+/// [`WidgetContext::get_with_source()`](crate::context::WidgetContext::get_with_source)
+/// for more information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedComponent<T> {
+    /// The value of the component.
+    pub value: T,
+    /// Where the value came from.
+    pub source: StyleSource,
+}
+
 /// A type that represents a named style component.
 pub trait NamedComponent: Sized {
     /// Returns the name of the style component.
@@ -1695,6 +1760,30 @@ impl ThemePair {
             shadow: scheme.neutral.color(1),
         }
     }
+
+    /// Returns a print-friendly variant of this theme.
+    ///
+    /// Both the light and dark themes of the returned pair are this pair's
+    /// light theme with a white background and black text. Shadows and scrims
+    /// are fully transparent, which prevents shadows from being drawn unless
+    /// they specify their own color.
+    #[must_use]
+    pub fn print_friendly(&self) -> Self {
+        let mut theme = self.light;
+        theme.surface.color = Color::WHITE;
+        theme.surface.bright_color = Color::WHITE;
+        theme.surface.lowest_container = Color::WHITE;
+        theme.surface.on_color = Color::BLACK;
+        Self {
+            light: theme,
+            dark: theme,
+            primary_fixed: self.primary_fixed,
+            secondary_fixed: self.secondary_fixed,
+            tertiary_fixed: self.tertiary_fixed,
+            scrim: Color::CLEAR_BLACK,
+            shadow: Color::CLEAR_BLACK,
+        }
+    }
 }
 
 impl From<ColorScheme> for ThemePair {
@@ -2081,6 +2170,26 @@ pub trait ColorExt: Copy {
     fn most_contrasting(self, others: &[Self]) -> Self
     where
         Self: Copy;
+
+    /// Returns the relative luminance of this color, as defined by the Web
+    /// Content Accessibility Guidelines (WCAG).
+    ///
+    /// The result ranges from 0.0 for black to 1.0 for white. The alpha
+    /// channel is ignored.
+    #[must_use]
+    fn relative_luminance(self) -> f32;
+
+    /// Returns the WCAG contrast ratio between this color and `other`.
+    ///
+    /// The result ranges from 1.0 for identical luminances to 21.0 for black
+    /// and white. WCAG level AA requires a ratio of at least 4.5 for normal
+    /// text and 3.0 for large text.
+    #[must_use]
+    fn contrast_ratio(self, other: Self) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
 }
 
 impl ColorExt for Color {
@@ -2148,6 +2257,20 @@ impl ColorExt for Color {
 
         most_contrasting
     }
+
+    fn relative_luminance(self) -> f32 {
+        fn linear(channel: f32) -> f32 {
+            if channel <= 0.040_45 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        0.2126 * linear(self.red_f32())
+            + 0.7152 * linear(self.green_f32())
+            + 0.0722 * linear(self.blue_f32())
+    }
 }
 
 /// A color composed of hue, saturation, and lightness.
@@ -2882,6 +3005,143 @@ impl RequireInvalidation for VerticalAlign {
     }
 }
 
+impl From<i32> for Component {
+    fn from(value: i32) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl TryFrom<Component> for i32 {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Integer(value) => Ok(value),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for i32 {
+    fn requires_invalidation(&self) -> bool {
+        false
+    }
+}
+
+impl From<Size<Dimension>> for Component {
+    fn from(value: Size<Dimension>) -> Self {
+        Self::Size(value)
+    }
+}
+
+impl TryFrom<Component> for Size<Dimension> {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Size(size) => Ok(size),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for Size<Dimension> {
+    fn requires_invalidation(&self) -> bool {
+        true
+    }
+}
+
+/// A policy for handling a widget's content when it does not fit within the
+/// space available to the widget.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// The widget's measured size is used even when it is larger than the
+    /// space available. The parent widget decides how to handle the extra
+    /// space.
+    #[default]
+    Visible,
+    /// The widget's size is limited to the space available, and any content
+    /// that extends beyond the widget's bounds is clipped.
+    Clip,
+    /// The widget's size is limited to the space available, and the content
+    /// that extends beyond the widget's bounds can be scrolled.
+    ///
+    /// Scrolling is provided by [`Style`](crate::widgets::Style), which places
+    /// its contents in a [`Scroll`](crate::widgets::Scroll) while this policy
+    /// applies to it. Other widgets clip their content as if [`Self::Clip`]
+    /// were used.
+    Scroll,
+}
+
+impl From<OverflowPolicy> for Component {
+    fn from(value: OverflowPolicy) -> Self {
+        Self::OverflowPolicy(value)
+    }
+}
+
+impl TryFrom<Component> for OverflowPolicy {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::OverflowPolicy(policy) => Ok(policy),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for OverflowPolicy {
+    fn requires_invalidation(&self) -> bool {
+        true
+    }
+}
+
+/// The height a widget appears to be raised above its parent.
+///
+/// Each level selects a theme-provided shadow that is drawn behind the widget.
+/// See [`Elevation`](components::Elevation) and
+/// [`BoxShadow`](components::BoxShadow) for more information.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ElevationLevel {
+    /// The widget is not raised, and no shadow is drawn.
+    #[default]
+    Flat,
+    /// The widget is slightly raised, such as a card or a button.
+    Low,
+    /// The widget is raised, such as a toolbar or a hovered card.
+    Medium,
+    /// The widget floats above other content, such as a menu or a popover.
+    High,
+    /// The widget floats above all other content, such as a modal dialog.
+    Highest,
+}
+
+impl From<ElevationLevel> for Component {
+    fn from(value: ElevationLevel) -> Self {
+        Component::custom(value)
+    }
+}
+
+impl TryFrom<Component> for ElevationLevel {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Custom(custom) => custom
+                .downcast()
+                .copied()
+                .ok_or_else(|| Component::Custom(custom)),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for ElevationLevel {
+    fn requires_invalidation(&self) -> bool {
+        false
+    }
+}
+
 /// A [`Component`] that resolves its value at runtime.
 #[derive(Clone)]
 pub struct DynamicComponent(Arc<dyn DynamicComponentResolver>);
@@ -2957,3 +3217,16 @@ impl DynamicComponent {
         self.0.resolve_component(context)
     }
 }
+
+#[test]
+fn print_friendly_theme() {
+    let theme = ThemePair::default();
+    let print = theme.print_friendly();
+    assert_eq!(print.light, print.dark);
+    assert_eq!(print.light.surface.color, Color::WHITE);
+    assert_eq!(print.light.surface.on_color, Color::BLACK);
+    // Other colors of the light theme are kept.
+    assert_eq!(print.light.primary, theme.light.primary);
+    assert_eq!(print.shadow.alpha(), 0);
+    assert_eq!(print.scrim.alpha(), 0);
+}
@@ -1,15 +1,19 @@
 //! All style components supported by the built-in widgets.
 
 use figures::units::Lp;
+use figures::{Point, Size, Zero};
 use kludgine::cosmic_text::{FamilyOwned, Style, Weight};
 use kludgine::shapes::CornerRadii;
 use kludgine::Color;
 
 use crate::animation::easings::{EaseInOutQuadradic, EaseInQuadradic, EaseOutQuadradic};
 use crate::animation::{EasingFunction, ZeroToOne};
+use crate::graphics::Gradient;
 use crate::styles::{
-    Dimension, FocusableWidgets, FontFamilyList, HorizontalAlign, VerticalAlign, VisualOrder,
+    Dimension, ElevationLevel, FocusableWidgets, FontFamilyList, HorizontalAlign, OverflowPolicy,
+    VerticalAlign, VisualOrder,
 };
+use crate::widgets::container::ContainerShadow;
 use crate::window::ThemeMode;
 
 /// Defines a set of style components for Cushy.
@@ -244,6 +248,10 @@ define_components! {
                 ThemeMode::Light => Color::CLEAR_WHITE,
             }
         })
+        /// A [`Gradient`] drawn over a widget's [`WidgetBackground`].
+        ///
+        /// An empty gradient, the default, draws nothing.
+        WidgetBackgroundGradient(Gradient, "widget_background_gradient", Gradient::default())
         /// A [`Color`] to be used to accent a widget.
         WidgetAccentColor(Color, "widget_accent_color", .primary.color)
         /// A [`Color`] to be used to accent a disabled widget.
@@ -312,12 +320,122 @@ define_components! {
         /// The [`FontFamilyList`] to apply to h6 headings.
         Heading6FontFamily(FontFamilyList, "heading_font_family_6", @HeadingFontFamily)
 
-        /// The opaqueness of drawing calls
+        /// The opacity of a widget and its children.
+        ///
+        /// This value is multiplied with the opacity of the widget's parents
+        /// when drawing, which allows fading an entire widget hierarchy. When
+        /// inherited, this value is applied again by each child it is
+        /// inherited by. [`MakeWidget::opacity()`](crate::widget::MakeWidget::opacity)
+        /// applies this component without it being inherited.
         Opacity(ZeroToOne, "opacity", ZeroToOne::ONE)
 
         /// The horizontal alignment of the content of a widget.
         HorizontalAlignment(HorizontalAlign, "align", HorizontalAlign::default())
         /// The vertical alignment of the content of a widget.
         VerticalAlignment(VerticalAlign, "vertical-align", VerticalAlign::default())
+
+        /// The minimum size of a widget. This component is honored for every
+        /// widget during layout, and is usually applied using
+        /// [`MakeWidget::min_size`](crate::widget::MakeWidget::min_size) to
+        /// prevent child widgets from inheriting it.
+        MinSize(Size<Dimension>, "min_size", Size::new(Dimension::ZERO, Dimension::ZERO))
+        /// The maximum size of a widget. This component is honored for every
+        /// widget during layout, and is usually applied using
+        /// [`MakeWidget::max_size`](crate::widget::MakeWidget::max_size) to
+        /// prevent child widgets from inheriting it.
+        MaxSize(Size<Dimension>, "max_size", Size::new(Dimension::MAX, Dimension::MAX))
+        /// The policy for handling a widget's content that does not fit within
+        /// the space available. This component is honored for every widget
+        /// during layout, and is usually applied using
+        /// [`MakeWidget::overflow`](crate::widget::MakeWidget::overflow) to
+        /// prevent child widgets from inheriting it.
+        Overflow(OverflowPolicy, "overflow", OverflowPolicy::Visible)
+        /// The stacking order of a widget relative to its siblings.
+        ///
+        /// Widgets that contain multiple children draw their children in
+        /// ascending order of this component, with children that have equal
+        /// values drawn in their original order. Widgets drawn later are drawn
+        /// above earlier widgets and receive mouse events first.
+        ZIndex(i32, "z_index", 0)
+
+        /// The [`ElevationLevel`] of a widget, which selects the default
+        /// [`BoxShadow`] drawn behind it.
+        Elevation(ElevationLevel, "elevation", ElevationLevel::default())
+        /// The shadow drawn behind a widget.
+        ///
+        /// The shadow is drawn by the widget's parent immediately before the
+        /// widget is drawn, which allows the shadow to extend beyond the
+        /// widget's bounds without affecting its layout. The shadow is
+        /// positioned using the widget's [`CornerRadius`].
+        ///
+        /// By default, the shadow for the widget's [`Elevation`] is used.
+        BoxShadow(ContainerShadow, "box_shadow", |context| {
+            match context.get(&Elevation) {
+                ElevationLevel::Flat => ContainerShadow::default(),
+                ElevationLevel::Low => context.get(&LowElevationShadow),
+                ElevationLevel::Medium => context.get(&MediumElevationShadow),
+                ElevationLevel::High => context.get(&HighElevationShadow),
+                ElevationLevel::Highest => context.get(&HighestElevationShadow),
+            }
+        })
+        /// The shadow drawn behind widgets with an [`Elevation`] of
+        /// [`ElevationLevel::Low`].
+        LowElevationShadow(ContainerShadow, "low_elevation_shadow", elevation_shadow(1, 3, 0))
+        /// The shadow drawn behind widgets with an [`Elevation`] of
+        /// [`ElevationLevel::Medium`].
+        MediumElevationShadow(ContainerShadow, "medium_elevation_shadow", elevation_shadow(3, 6, 0))
+        /// The shadow drawn behind widgets with an [`Elevation`] of
+        /// [`ElevationLevel::High`].
+        HighElevationShadow(ContainerShadow, "high_elevation_shadow", elevation_shadow(6, 12, 1))
+        /// The shadow drawn behind widgets with an [`Elevation`] of
+        /// [`ElevationLevel::Highest`].
+        HighestElevationShadow(ContainerShadow, "highest_elevation_shadow", elevation_shadow(10, 20, 2))
+    }
+}
+
+/// Returns a drop shadow measured in points.
+fn elevation_shadow(offset: i32, blur: i32, spread: i32) -> ContainerShadow {
+    ContainerShadow::new(Point::new(
+        Dimension::ZERO,
+        Dimension::Lp(Lp::points(offset)),
+    ))
+    .blur_radius(Dimension::Lp(Lp::points(blur)))
+    .spread(Dimension::Lp(Lp::points(spread)))
+}
+
+#[test]
+fn elevation_components() {
+    use crate::styles::Component;
+
+    assert!(matches!(
+        ElevationLevel::try_from(Component::from(ElevationLevel::High)),
+        Ok(ElevationLevel::High)
+    ));
+    assert!(ElevationLevel::try_from(Component::from(Color::RED)).is_err());
+
+    let low = elevation_shadow(1, 3, 0);
+    assert!(matches!(
+        ContainerShadow::try_from(Component::from(low)),
+        Ok(shadow) if shadow == low
+    ));
+    assert_eq!(low.offset.y, Dimension::Lp(Lp::points(1)));
+    assert_eq!(low.blur_radius, Dimension::Lp(Lp::points(3)));
+    assert_eq!(low.color, None);
+
+    // Each elevation level casts a larger shadow than the level below it.
+    let shadows = [
+        elevation_shadow(1, 3, 0),
+        elevation_shadow(3, 6, 0),
+        elevation_shadow(6, 12, 1),
+        elevation_shadow(10, 20, 2),
+    ];
+    for pair in shadows.windows(2) {
+        let (Dimension::Lp(lower), Dimension::Lp(higher)) =
+            (pair[0].blur_radius, pair[1].blur_radius)
+        else {
+            unreachable!("elevation shadows are measured in points")
+        };
+        assert!(lower < higher);
     }
+    assert!(ElevationLevel::Flat < ElevationLevel::Highest);
 }
@@ -0,0 +1,218 @@
+//! Opt-in instrumentation hooks for UX analytics.
+//!
+//! Cushy does not collect or transmit any data on its own. Applications that
+//! want insight into how their interfaces are used can install a
+//! [`TelemetrySink`] using [`set_sink`]. Once installed, Cushy reports
+//! structured [`TelemetryEvent`]s to the sink from a small, fixed set of
+//! emission points:
+//!
+//! - Windows opening and closing.
+//! - Buttons being clicked and keyboard shortcuts being invoked.
+//! - The button chosen to dismiss a [`MessageBox`](crate::dialog::MessageBox).
+//! - Periodic summaries of how long each window took to lay out and redraw.
+//!
+//! When no sink is installed, no events are constructed.
+//!
+//! ```rust
+//! use cushy::telemetry::{self, TelemetryEvent};
+//!
+//! telemetry::set_sink(|event: &TelemetryEvent| {
+//!     if let TelemetryEvent::FrameTimes { window, times } = event {
+//!         println!("{window:?}: p99 {:?}", times.p99);
+//!     }
+//! });
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use kludgine::app::winit::keyboard::{Key, ModifiersState};
+use parking_lot::RwLock;
+
+use crate::widget::WidgetId;
+use crate::window::WindowHandle;
+
+static SINK: RwLock<Option<Arc<dyn TelemetrySink>>> = RwLock::new(None);
+
+/// The number of frames summarized by each [`TelemetryEvent::FrameTimes`].
+pub const FRAME_SAMPLES: usize = 120;
+
+/// Installs `sink` as the destination for all [`TelemetryEvent`]s, replacing
+/// any previously installed sink.
+pub fn set_sink(sink: impl TelemetrySink) {
+    *SINK.write() = Some(Arc::new(sink));
+}
+
+/// Removes the installed [`TelemetrySink`], if any.
+pub fn clear_sink() {
+    *SINK.write() = None;
+}
+
+/// Returns true if a [`TelemetrySink`] is currently installed.
+#[must_use]
+pub fn is_enabled() -> bool {
+    SINK.read().is_some()
+}
+
+/// Reports the event returned from `event` to the installed sink.
+///
+/// `event` is only invoked if a sink is installed.
+pub(crate) fn emit(event: impl FnOnce() -> TelemetryEvent) {
+    let sink = SINK.read().clone();
+    if let Some(sink) = sink {
+        sink.event(&event());
+    }
+}
+
+/// A receiver of [`TelemetryEvent`]s.
+///
+/// Events may be reported from any thread, and are reported while Cushy is in
+/// the middle of processing the action that caused them. Implementors should
+/// avoid blocking, and should queue any expensive work elsewhere.
+pub trait TelemetrySink: Send + Sync + 'static {
+    /// Receives `event`.
+    fn event(&self, event: &TelemetryEvent);
+}
+
+impl<F> TelemetrySink for F
+where
+    F: Fn(&TelemetryEvent) + Send + Sync + 'static,
+{
+    fn event(&self, event: &TelemetryEvent) {
+        self(event);
+    }
+}
+
+/// A structured event reported to a [`TelemetrySink`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum TelemetryEvent {
+    /// A window was opened.
+    WindowOpened {
+        /// The window that was opened.
+        window: WindowHandle,
+    },
+    /// A window was closed.
+    WindowClosed {
+        /// The window that was closed.
+        window: WindowHandle,
+    },
+    /// A user action was invoked.
+    ActionInvoked(ActionSource),
+    /// A [`MessageBox`](crate::dialog::MessageBox) was dismissed.
+    DialogResult {
+        /// The title of the dismissed dialog.
+        title: String,
+        /// The caption of the button that dismissed the dialog.
+        button: String,
+    },
+    /// A summary of the most recent [`FRAME_SAMPLES`] frames of a window.
+    FrameTimes {
+        /// The window the frames were rendered in.
+        window: WindowHandle,
+        /// The summary of the frame times.
+        times: FrameTimes,
+    },
+}
+
+/// The source of a [`TelemetryEvent::ActionInvoked`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ActionSource {
+    /// A [`Button`](crate::widgets::Button) was clicked.
+    Button(WidgetId),
+    /// A keyboard shortcut was invoked.
+    Shortcut {
+        /// The logical key that was pressed.
+        key: Key,
+        /// The modifiers that were held when the key was pressed.
+        modifiers: ModifiersState,
+    },
+}
+
+/// Percentiles of the time it took to lay out and redraw a set of frames.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct FrameTimes {
+    /// The number of frames summarized.
+    pub frames: usize,
+    /// The median frame time.
+    pub p50: Duration,
+    /// The 90th percentile frame time.
+    pub p90: Duration,
+    /// The 99th percentile frame time.
+    pub p99: Duration,
+    /// The slowest frame time.
+    pub max: Duration,
+}
+
+impl FrameTimes {
+    /// Returns the percentiles of `samples`, or `None` if `samples` is empty.
+    ///
+    /// `samples` is sorted in place.
+    #[must_use]
+    pub fn from_samples(samples: &mut [Duration]) -> Option<Self> {
+        let max = samples.len().checked_sub(1)?;
+        samples.sort_unstable();
+        let percentile = |p: usize| samples[(max * p + 50) / 100];
+        Some(Self {
+            frames: samples.len(),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: samples[max],
+        })
+    }
+}
+
+/// Accumulates frame times for a single window.
+#[derive(Debug)]
+pub(crate) struct FrameTimeTracker {
+    window: WindowHandle,
+    samples: Vec<Duration>,
+}
+
+impl FrameTimeTracker {
+    pub(crate) fn new(window: WindowHandle) -> Self {
+        Self {
+            window,
+            samples: Vec::new(),
+        }
+    }
+
+    pub(crate) fn window(&self) -> &WindowHandle {
+        &self.window
+    }
+
+    pub(crate) fn record(&mut self, elapsed: Duration) {
+        if !is_enabled() {
+            self.samples.clear();
+            return;
+        }
+        self.samples.push(elapsed);
+        if self.samples.len() >= FRAME_SAMPLES {
+            if let Some(times) = FrameTimes::from_samples(&mut self.samples) {
+                emit(|| TelemetryEvent::FrameTimes {
+                    window: self.window.clone(),
+                    times,
+                });
+            }
+            self.samples.clear();
+        }
+    }
+}
+
+#[test]
+fn frame_time_percentiles() {
+    assert_eq!(FrameTimes::from_samples(&mut []), None);
+
+    let mut samples = (1..=100)
+        .rev()
+        .map(Duration::from_millis)
+        .collect::<Vec<_>>();
+    let times = FrameTimes::from_samples(&mut samples).expect("not empty");
+    assert_eq!(times.frames, 100);
+    assert_eq!(times.p50, Duration::from_millis(51));
+    assert_eq!(times.p90, Duration::from_millis(90));
+    assert_eq!(times.p99, Duration::from_millis(99));
+    assert_eq!(times.max, Duration::from_millis(100));
+}
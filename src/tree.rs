@@ -1,20 +1,28 @@
+use std::any::Any;
 use std::mem;
 use std::sync::{Arc, Weak};
 
 use ahash::AHashMap;
 use alot::{LotId, Lots};
 use figures::units::{Px, UPx};
-use figures::{Point, Rect, Size};
+use figures::{IntoSigned, Point, Rect, Size};
 use parking_lot::Mutex;
 #[cfg(feature = "localization")]
 use unic_langid::LanguageIdentifier;
 
+use crate::debug::{short_type_name, TreeSnapshot, WidgetSnapshot};
+use crate::graphics::Transform;
 use crate::reactive::value::Value;
-use crate::styles::{Styles, ThemePair, VisualOrder};
+use crate::styles::{ComponentName, StyleSource, Styles, ThemePair, VisualOrder};
 use crate::widget::{MountedWidget, WidgetId, WidgetInstance};
-use crate::window::{ThemeMode, WindowHandle};
+use crate::widgets::scroll::ScrollBehavior;
+use crate::window::{Announcement, ThemeMode, WindowHandle};
 use crate::ConstraintLimit;
 
+/// The maximum number of previously focused widgets remembered for restoring
+/// focus.
+const FOCUS_HISTORY_LIMIT: usize = 32;
+
 #[derive(Clone, Default)]
 pub struct Tree {
     data: Arc<Mutex<TreeData>>,
@@ -42,12 +50,14 @@ impl Tree {
             parent: parent_id,
             last_layout_query: None,
             layout: None,
+            render_transform: None,
             associated_styles: None,
             effective_styles,
             theme: None,
             theme_mode: None,
             #[cfg(feature = "localization")]
             locale: None,
+            focus_trap: false,
         });
         data.nodes_by_id.insert(id, node_id);
         if widget.is_default() {
@@ -56,6 +66,9 @@ impl Tree {
         if widget.is_escape() {
             data.escapes.push(node_id);
         }
+        if widget.focus_order().is_some() {
+            data.focus_ordered.push(node_id);
+        }
         if let Some(parent) = parent_id {
             let parent = &mut data.nodes[parent];
             parent.children.push(node_id);
@@ -102,8 +115,6 @@ impl Tree {
 
     pub(crate) fn new_frame(&self, invalidations: impl IntoIterator<Item = WidgetId>) {
         let mut data = self.data.lock();
-        data.render_info.clear();
-
         for id in invalidations {
             let Some(id) = data.nodes_by_id.get(&id).copied() else {
                 continue;
@@ -113,12 +124,107 @@ impl Tree {
         }
     }
 
-    pub(crate) fn note_widget_rendered(&self, widget: LotId) {
+    pub(crate) fn request_reveal(&self, widget: WidgetId, behavior: ScrollBehavior) {
+        self.data.lock().reveal_requests.push((widget, behavior));
+    }
+
+    pub(crate) fn reveal_requests(&self) -> Vec<(WidgetId, ScrollBehavior)> {
+        self.data.lock().reveal_requests.clone()
+    }
+
+    pub(crate) fn pending_reveal_requests(&self) -> usize {
+        self.data.lock().reveal_requests.len()
+    }
+
+    /// Removes the first `count` reveal requests. Requests made after
+    /// [`Self::pending_reveal_requests`] was called are kept for the next
+    /// frame.
+    pub(crate) fn clear_reveal_requests(&self, count: usize) {
+        self.data.lock().reveal_requests.drain(..count);
+    }
+
+    pub(crate) fn announce(&self, announcement: Announcement) {
+        self.data.lock().announcements.push(announcement);
+    }
+
+    pub(crate) fn take_announcements(&self) -> Vec<Announcement> {
+        mem::take(&mut self.data.lock().announcements)
+    }
+
+    /// Stores `states` to be restored into widgets mounted beneath `scope`.
+    pub(crate) fn preserve_states(&self, scope: LotId, states: AHashMap<String, PreservedState>) {
+        self.data.lock().preserved_states.insert(scope, states);
+    }
+
+    /// Removes and returns the state preserved for `key` by the nearest
+    /// ancestor of `node` that is preserving state.
+    pub(crate) fn take_preserved_state(&self, node: LotId, key: &str) -> Option<PreservedState> {
         let mut data = self.data.lock();
-        let Some(layout) = data.nodes.get(widget).and_then(|node| node.layout) else {
+        if data.preserved_states.is_empty() {
+            return None;
+        }
+        let mut ancestor = data.nodes.get(node)?.parent;
+        while let Some(scope) = ancestor {
+            if let Some(state) = data
+                .preserved_states
+                .get_mut(&scope)
+                .and_then(|states| states.remove(key))
+            {
+                return Some(state);
+            }
+            ancestor = data.nodes.get(scope)?.parent;
+        }
+        None
+    }
+
+    /// Discards any state preserved by `scope` that was not restored.
+    pub(crate) fn discard_preserved_states(&self, scope: LotId) {
+        self.data.lock().preserved_states.remove(&scope);
+    }
+
+    pub(crate) fn clear_render_info(&self) {
+        self.data.lock().render_info.clear();
+    }
+
+    /// Records that `widget` was rendered clipped to `clip`, allowing it to
+    /// receive mouse input in the portion of its layout that was visible.
+    pub(crate) fn note_widget_rendered(
+        &self,
+        widget: LotId,
+        transform: Option<Transform>,
+        clip: Rect<UPx>,
+    ) {
+        let mut data = self.data.lock();
+        let Some(node) = data.nodes.get_mut(widget) else {
+            return;
+        };
+        let Some(layout) = node.layout else {
             return;
         };
-        data.render_info.push(widget, layout);
+        node.render_transform = transform;
+        // The clip of a transformed widget isn't in the same coordinate space
+        // as its layout, so its entire layout is used instead.
+        let visible = if transform.is_some() {
+            Some(layout)
+        } else {
+            layout.intersection(&clip.into_signed())
+        };
+        if let Some(visible) = visible {
+            data.render_info.push(widget, visible, transform);
+        }
+    }
+
+    /// Converts `location` in window coordinates to be relative to the
+    /// origin of `widget`'s last layout, reversing any transform that was
+    /// applied when the widget was last rendered.
+    pub(crate) fn window_to_widget(&self, widget: LotId, location: Point<Px>) -> Option<Point<Px>> {
+        let data = self.data.lock();
+        let node = data.nodes.get(widget)?;
+        let layout = node.layout?;
+        let location = node
+            .render_transform
+            .map_or(location, |transform| transform.inverse().apply(location));
+        Some(location - layout.origin)
     }
 
     pub(crate) fn begin_layout(
@@ -221,11 +327,98 @@ impl Tree {
         ordered
     }
 
+    pub(crate) fn snapshot(&self) -> TreeSnapshot {
+        let data = self.data.lock();
+        let mut pending = data
+            .nodes
+            .entries()
+            .filter(|(_, node)| node.parent.is_none())
+            .enumerate()
+            .map(|(index, (id, _))| (id, 0, index.to_string()))
+            .collect::<Vec<_>>();
+        pending.reverse();
+        let mut captured = Vec::new();
+        while let Some((id, depth, path)) = pending.pop() {
+            let node = &data.nodes[id];
+            for (index, child) in node.children.iter().enumerate().rev() {
+                pending.push((*child, depth + 1, format!("{path}.{index}")));
+            }
+            captured.push((
+                node.widget.clone(),
+                node.children.is_empty(),
+                depth,
+                path,
+                node.layout,
+                node.associated_styles.clone(),
+            ));
+        }
+        // Widgets are locked while summarizing them, which must not be done
+        // while the tree is locked.
+        drop(data);
+
+        let widgets = captured
+            .into_iter()
+            .map(|(widget, leaf, depth, path, bounds, styles)| {
+                let mut styles = styles
+                    .map(|styles| {
+                        styles
+                            .get()
+                            .into_iter()
+                            .map(|(name, component)| {
+                                (
+                                    format!("{}.{}", &*name.group, &*name.name),
+                                    format!("{:?}", component.get()),
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                styles.sort();
+                let type_name = short_type_name(widget.lock().type_name());
+                WidgetSnapshot {
+                    widget: widget.id(),
+                    path,
+                    depth,
+                    type_name,
+                    bounds,
+                    styles,
+                    summary: leaf.then(|| format!("{widget:?}")),
+                }
+            })
+            .collect();
+        TreeSnapshot { widgets }
+    }
+
     pub(crate) fn effective_styles(&self, id: LotId) -> Styles {
         let data = self.data.lock();
         data.nodes[id].effective_styles.clone()
     }
 
+    /// Returns where the component named `name` that applies to `id` was
+    /// attached, or `None` if no ancestor of `id` provides it.
+    pub(crate) fn style_source(&self, id: LotId, name: &ComponentName) -> Option<StyleSource> {
+        let data = self.data.lock();
+        let mut ancestor = data.nodes[id].parent;
+        let mut is_parent = true;
+        while let Some(node_id) = ancestor {
+            let node = &data.nodes[node_id];
+            if node
+                .associated_styles
+                .as_ref()
+                .is_some_and(|styles| styles.get().contains(name, !is_parent))
+            {
+                return Some(if is_parent {
+                    StyleSource::Inline
+                } else {
+                    StyleSource::Inherited(node.widget.id())
+                });
+            }
+            ancestor = node.parent;
+            is_parent = false;
+        }
+        None
+    }
+
     pub(crate) fn hover(&self, new_hover: Option<&MountedWidget>) -> HoverResults {
         let mut data = self.data.lock();
         let hovered = new_hover
@@ -253,7 +446,53 @@ impl Tree {
 
     pub fn focus(&self, new_focus: Option<WidgetId>) -> Result<Option<MountedWidget>, ()> {
         let mut data = self.data.lock();
-        data.update_tracked_widget(new_focus, self, |data| &mut data.focus)
+        let result = data.update_tracked_widget(new_focus, self, |data| &mut data.focus);
+        if result.is_ok() {
+            data.focus_lost = false;
+            if let Some(focused) = new_focus.and_then(|id| data.widget_from_id(id, self)) {
+                if focused.widget.restores_focus() {
+                    data.focus_history.retain(|id| *id != focused.id());
+                    if data.focus_history.len() == FOCUS_HISTORY_LIMIT {
+                        data.focus_history.remove(0);
+                    }
+                    data.focus_history.push(focused.id());
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the most recently focused widget that is still mounted if the
+    /// focused widget was lost without another widget being focused.
+    pub(crate) fn take_focus_to_restore(&self) -> Option<WidgetId> {
+        let mut data = self.data.lock();
+        if !mem::take(&mut data.focus_lost) {
+            return None;
+        }
+        data.focus_history
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| data.nodes_by_id.contains_key(id))
+    }
+
+    /// Notifies the tree that the window has gained or lost activation.
+    ///
+    /// If a widget was focused when the window was deactivated and no widget
+    /// is focused when it is reactivated, focus will be restored.
+    pub(crate) fn window_activation_changed(&self, active: bool) {
+        let mut data = self.data.lock();
+        if active {
+            if mem::take(&mut data.focused_when_deactivated)
+                && data
+                    .focus
+                    .map_or(true, |focus| data.nodes.get(focus).is_none())
+            {
+                data.focus_lost = true;
+            }
+        } else {
+            data.focused_when_deactivated = data.focus.is_some();
+        }
     }
 
     pub fn previous_focus(&self, focus: WidgetId) -> Option<MountedWidget> {
@@ -324,6 +563,36 @@ impl Tree {
         self.data.lock().defaults.last().copied()
     }
 
+    pub(crate) fn set_focus_trap(&self, id: LotId, trap: bool) {
+        if let Some(node) = self.data.lock().nodes.get_mut(id) {
+            node.focus_trap = trap;
+        }
+    }
+
+    pub(crate) fn is_focus_trap(&self, id: LotId) -> bool {
+        self.data
+            .lock()
+            .nodes
+            .get(id)
+            .map_or(false, |node| node.focus_trap)
+    }
+
+    /// Returns true if any widget within `root`, including `root` itself, has
+    /// an explicit focus order.
+    pub(crate) fn has_focus_order_within(&self, root: LotId) -> bool {
+        let data = self.data.lock();
+        data.focus_ordered.iter().any(|ordered| {
+            let mut id = Some(*ordered);
+            while let Some(current) = id {
+                if current == root {
+                    return true;
+                }
+                id = data.nodes.get(current).and_then(|node| node.parent);
+            }
+            false
+        })
+    }
+
     pub(crate) fn escape_widget(&self) -> Option<LotId> {
         self.data.lock().escapes.last().copied()
     }
@@ -350,6 +619,23 @@ impl Tree {
         data.render_info.widgets_under_point(point, &data, self)
     }
 
+    /// Returns the id and window region of each untransformed widget without
+    /// children that was rendered in the last frame.
+    pub(crate) fn rendered_leaves(&self) -> Vec<(WidgetId, Rect<Px>)> {
+        let data = self.data.lock();
+        data.render_info
+            .order
+            .iter()
+            .filter(|area| area.inverse.is_none())
+            .filter_map(|area| {
+                let node = data.nodes.get(area.node)?;
+                node.children
+                    .is_empty()
+                    .then(|| (node.widget.id(), Rect::from_extents(area.min, area.max)))
+            })
+            .collect()
+    }
+
     pub(crate) fn parent(&self, id: LotId) -> Option<LotId> {
         let data = self.data.lock();
         data.nodes.get(id).expect("missing widget").parent
@@ -447,8 +733,24 @@ struct TreeData {
     hover: Option<LotId>,
     defaults: Vec<LotId>,
     escapes: Vec<LotId>,
+    focus_ordered: Vec<LotId>,
     render_info: RenderInfo,
     previous_focuses: AHashMap<WidgetId, WidgetId>,
+    focus_history: Vec<WidgetId>,
+    focus_lost: bool,
+    focused_when_deactivated: bool,
+    reveal_requests: Vec<(WidgetId, ScrollBehavior)>,
+    announcements: Vec<Announcement>,
+    preserved_states: AHashMap<LotId, AHashMap<String, PreservedState>>,
+}
+
+/// State transferred from a widget being replaced to the widget replacing it.
+pub(crate) struct PreservedState {
+    /// The state returned from
+    /// [`Widget::preserve_state()`](crate::widget::Widget::preserve_state).
+    pub state: Option<Box<dyn Any + Send>>,
+    /// True if the replaced widget was focused.
+    pub focused: bool,
 }
 
 impl TreeData {
@@ -526,6 +828,10 @@ impl TreeData {
         if let Some(next_focus) = removed_node.widget.next_focus() {
             self.previous_focuses.remove(&next_focus);
         }
+        if self.focus == Some(child) {
+            self.focus = None;
+            self.focus_lost = true;
+        }
         self.render_info.order.retain(|info| info.node != child);
         if removed_node.widget.is_default() {
             self.defaults.retain(|id| *id != child);
@@ -533,6 +839,10 @@ impl TreeData {
         if removed_node.widget.is_escape() {
             self.escapes.retain(|id| *id != child);
         }
+        if removed_node.widget.focus_order().is_some() {
+            self.focus_ordered.retain(|id| *id != child);
+        }
+        self.preserved_states.remove(&child);
     }
 
     pub(crate) fn widget_hierarchy(&self, mut widget: LotId, tree: &Tree) -> Vec<MountedWidget> {
@@ -592,8 +902,8 @@ struct RenderInfo {
 }
 
 impl RenderInfo {
-    pub fn push(&mut self, node: LotId, region: Rect<Px>) {
-        let area = RenderArea::new(node, region);
+    pub fn push(&mut self, node: LotId, region: Rect<Px>, transform: Option<Transform>) {
+        let area = RenderArea::new(node, region, transform);
         self.order.push(area);
     }
 
@@ -613,11 +923,7 @@ impl RenderInfo {
         // amount of depth.
         let mut hits = Vec::with_capacity(self.order.len().min(256));
         for area in self.order.iter().rev() {
-            if area.min.x <= point.x
-                && area.min.y <= point.y
-                && area.max.x >= point.x
-                && area.max.y >= point.y
-            {
+            if area.contains(point) {
                 let Some(widget) = tree_data.widget_from_node(area.node, tree) else {
                     continue;
                 };
@@ -628,17 +934,33 @@ impl RenderInfo {
     }
 }
 
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy)]
 struct RenderArea {
     node: LotId,
     min: Point<Px>,
     max: Point<Px>,
+    inverse: Option<Transform>,
 }
 
 impl RenderArea {
-    fn new(node: LotId, area: Rect<Px>) -> Self {
+    fn new(node: LotId, area: Rect<Px>, transform: Option<Transform>) -> Self {
         let (min, max) = area.saturating_extents();
-        Self { node, min, max }
+        Self {
+            node,
+            min,
+            max,
+            inverse: transform.map(Transform::inverse),
+        }
+    }
+
+    /// Returns true if `point`, in window coordinates, is within this area
+    /// once the transform it was rendered with is reversed.
+    fn contains(&self, point: Point<Px>) -> bool {
+        let point = self.inverse.map_or(point, |inverse| inverse.apply(point));
+        self.min.x <= point.x
+            && self.min.y <= point.y
+            && self.max.x >= point.x
+            && self.max.y >= point.y
     }
 }
 
@@ -647,6 +969,7 @@ struct Node {
     children: Vec<LotId>,
     parent: Option<LotId>,
     layout: Option<Rect<Px>>,
+    render_transform: Option<Transform>,
     last_layout_query: Option<CachedLayoutQuery>,
     associated_styles: Option<Value<Styles>>,
     effective_styles: Styles,
@@ -654,6 +977,7 @@ struct Node {
     theme_mode: Option<Value<ThemeMode>>,
     #[cfg(feature = "localization")]
     locale: Option<Value<LanguageIdentifier>>,
+    focus_trap: bool,
 }
 
 impl Node {
@@ -683,3 +1007,67 @@ impl WeakTree {
         self.0.upgrade().map(|data| Tree { data })
     }
 }
+
+#[test]
+fn transformed_areas_hit_test_through_inverse() {
+    use figures::{Angle, Zero};
+
+    let node = Lots::new().push(());
+    let area = Rect::new(Point::ZERO, Size::new(Px::new(20), Px::new(10)));
+    // Rotating by 90 degrees and moving right by 10 pixels draws the area
+    // from x 0..10 and y 0..20.
+    let transform = Transform::rotated(Angle::degrees(90))
+        .then(Transform::translated(Point::new(Px::new(10), Px::ZERO)));
+    let rendered = RenderArea::new(node, area, Some(transform));
+
+    assert!(rendered.contains(Point::new(Px::new(5), Px::new(15))));
+    // This point is inside of the untransformed area, but outside of where it
+    // was drawn.
+    assert!(!rendered.contains(Point::new(Px::new(15), Px::new(5))));
+
+    let untransformed = RenderArea::new(node, area, None);
+    assert!(untransformed.contains(Point::new(Px::new(15), Px::new(5))));
+    assert!(!untransformed.contains(Point::new(Px::new(5), Px::new(15))));
+}
+
+#[test]
+fn focus_restores_to_most_recent_remaining_widget() {
+    use crate::widget::MakeWidget;
+    use crate::widgets::Space;
+
+    let tree = Tree::default();
+    let root = tree.push_boxed(Space::clear().make_widget(), None);
+    let first = tree.push_boxed(Space::clear().make_widget(), Some(&root));
+    let skipped = tree.push_boxed(
+        Space::clear().make_widget().without_focus_restore(),
+        Some(&root),
+    );
+    let dialog = tree.push_boxed(Space::clear().make_widget(), Some(&root));
+
+    tree.focus(Some(first.id())).expect("focus changed");
+    tree.focus(Some(skipped.id())).expect("focus changed");
+    tree.focus(Some(dialog.id())).expect("focus changed");
+    // Removing an unfocused widget does not restore focus.
+    tree.remove_child(&skipped, &mut Vec::new());
+    assert_eq!(tree.take_focus_to_restore(), None);
+
+    // Removing the focused widget restores the most recently focused widget
+    // that remains, only once.
+    tree.remove_child(&dialog, &mut Vec::new());
+    assert_eq!(tree.focused_widget(), None);
+    assert_eq!(tree.take_focus_to_restore(), Some(first.id()));
+    assert_eq!(tree.take_focus_to_restore(), None);
+
+    // Focus is restored when a window is reactivated after losing its focus.
+    tree.focus(Some(first.id())).expect("focus changed");
+    tree.window_activation_changed(false);
+    tree.focus(None).expect("focus changed");
+    tree.window_activation_changed(true);
+    assert_eq!(tree.take_focus_to_restore(), Some(first.id()));
+
+    // Reactivating a window that kept its focus does not change focus.
+    tree.focus(Some(first.id())).expect("focus changed");
+    tree.window_activation_changed(false);
+    tree.window_activation_changed(true);
+    assert_eq!(tree.take_focus_to_restore(), None);
+}
@@ -0,0 +1,736 @@
+//! Checking for and installing newer versions of an application.
+//!
+//! An [`UpdateChecker`] downloads a small release manifest from an endpoint
+//! chosen by the application and compares the advertised version against the
+//! running version. The result is exposed as a [`Dynamic`], allowing any
+//! widget to react to an update becoming available. [`UpdateChecker::banner`]
+//! provides a prebuilt notification that offers to install the update, and
+//! [`UpdateChecker::prompt`] displays the same choices in a
+//! [`MessageBox`].
+//!
+//! This module requires the `updater` feature.
+//!
+//! ## Release manifest format
+//!
+//! By default, the endpoint is expected to return plain text:
+//!
+//! ```text
+//! 1.2.0
+//! https://example.com/downloads/my-app-1.2.0.msi
+//! 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08
+//! Release notes for this version.
+//! More release notes...
+//! ```
+//!
+//! The first line is the version of the latest release. The optional second
+//! line is the URL of the installer for that release. When an installer URL
+//! is present, the third line must be the hex-encoded SHA-256 digest of the
+//! installer, which is verified before the installer is saved. Any remaining
+//! lines are the release notes. Other formats can be supported using
+//! [`UpdateChecker::with_parser`].
+//!
+//! ```rust,no_run
+//! use cushy::updater::UpdateChecker;
+//! use cushy::widget::MakeWidget;
+//!
+//! let updates = UpdateChecker::new(
+//!     env!("CARGO_PKG_VERSION"),
+//!     "https://example.com/my-app/latest.txt",
+//! );
+//! updates.check();
+//! let ui = updates.banner().and("My App").into_rows();
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::Arc;
+use std::{fs, thread};
+
+use sha2::{Digest, Sha256};
+
+use crate::dialog::{MessageBox, MessageButton, OpenMessageBox};
+use crate::reactive::value::{Destination, Dynamic, DynamicReader, MapEach, Source, Switchable};
+use crate::widget::{MakeWidget, SharedCallback, WidgetInstance};
+use crate::widgets::Space;
+
+type ParseFn = dyn Fn(&str) -> Result<Release, UpdateError> + Send + Sync;
+
+/// Checks an endpoint for newer versions of an application.
+///
+/// Cloning an update checker produces a handle to the same checker.
+#[derive(Clone)]
+pub struct UpdateChecker {
+    current_version: String,
+    endpoint: String,
+    parse: Arc<ParseFn>,
+    status: Dynamic<UpdateStatus>,
+    skipped_version: Dynamic<Option<String>>,
+    dismissed: Dynamic<bool>,
+    on_downloaded: SharedCallback<PathBuf>,
+}
+
+impl UpdateChecker {
+    /// Returns a checker that compares `current_version` against the release
+    /// manifest downloaded from `endpoint`.
+    ///
+    /// No requests are made until [`check()`](Self::check) is called.
+    #[must_use]
+    pub fn new(current_version: impl Into<String>, endpoint: impl Into<String>) -> Self {
+        Self {
+            current_version: current_version.into(),
+            endpoint: endpoint.into(),
+            parse: Arc::new(Release::parse),
+            status: Dynamic::new(UpdateStatus::NotChecked),
+            skipped_version: Dynamic::default(),
+            dismissed: Dynamic::new(false),
+            on_downloaded: SharedCallback::new(|installer: PathBuf| {
+                if let Err(err) = launch_installer(&installer) {
+                    tracing::error!("error launching installer {installer:?}: {err}");
+                }
+            }),
+        }
+    }
+
+    /// Parses the release manifest using `parse` and returns self.
+    #[must_use]
+    pub fn with_parser<F>(mut self, parse: F) -> Self
+    where
+        F: Fn(&str) -> Result<Release, UpdateError> + Send + Sync + 'static,
+    {
+        self.parse = Arc::new(parse);
+        self
+    }
+
+    /// Uses `skipped` to store the version the user chose to skip, and returns
+    /// self.
+    ///
+    /// Updates to the skipped version are not offered by the
+    /// [`banner()`](Self::banner). Applications can persist this value in
+    /// their settings to remember the user's choice between launches.
+    #[must_use]
+    pub fn with_skipped_version(mut self, skipped: Dynamic<Option<String>>) -> Self {
+        self.skipped_version = skipped;
+        self
+    }
+
+    /// Invokes `on_downloaded` with the path of the installer once it has
+    /// been downloaded, and returns self.
+    ///
+    /// By default, the installer is launched using [`launch_installer`].
+    #[must_use]
+    pub fn on_installer_downloaded<F>(mut self, on_downloaded: F) -> Self
+    where
+        F: FnMut(PathBuf) + Send + 'static,
+    {
+        self.on_downloaded = SharedCallback::new(on_downloaded);
+        self
+    }
+
+    /// Returns the version this checker compares releases against.
+    #[must_use]
+    pub fn current_version(&self) -> &str {
+        &self.current_version
+    }
+
+    /// Returns a reader for the current state of this checker.
+    #[must_use]
+    pub fn status(&self) -> DynamicReader<UpdateStatus> {
+        self.status.create_reader()
+    }
+
+    /// Returns the version the user chose to skip.
+    #[must_use]
+    pub const fn skipped_version(&self) -> &Dynamic<Option<String>> {
+        &self.skipped_version
+    }
+
+    /// Checks the endpoint for a newer release in the background.
+    ///
+    /// Does nothing if a check or download is already in progress.
+    pub fn check(&self) {
+        let started = self.status.map_mut(|mut status| {
+            if matches!(
+                *status,
+                UpdateStatus::Checking | UpdateStatus::Downloading(_)
+            ) {
+                false
+            } else {
+                *status = UpdateStatus::Checking;
+                true
+            }
+        });
+        if !started {
+            return;
+        }
+
+        let this = self.clone();
+        thread::spawn(move || {
+            let status = match fetch(&this.endpoint).and_then(|body| (this.parse)(&body)) {
+                Ok(release) if is_newer(&release.version, &this.current_version) => {
+                    this.dismissed.set(false);
+                    UpdateStatus::Available(release)
+                }
+                Ok(_) => UpdateStatus::UpToDate,
+                Err(err) => {
+                    tracing::warn!("error checking for updates: {err}");
+                    UpdateStatus::Failed(err)
+                }
+            };
+            this.status.set(status);
+        });
+    }
+
+    /// Downloads the installer for the available release in the background,
+    /// and then invokes the
+    /// [`on_installer_downloaded`](Self::on_installer_downloaded) callback.
+    ///
+    /// Does nothing if no release with an installer is available.
+    pub fn install(&self) {
+        let release = self.status.map_mut(|mut status| match &*status {
+            UpdateStatus::Available(release) if release.installer.is_some() => {
+                let release = release.clone();
+                *status = UpdateStatus::Downloading(release.clone());
+                Some(release)
+            }
+            _ => None,
+        });
+        let Some(release) = release else {
+            return;
+        };
+
+        let this = self.clone();
+        thread::spawn(move || {
+            let Some(installer) = &release.installer else {
+                return;
+            };
+            match download_installer(installer, &std::env::temp_dir()) {
+                Ok(installer) => {
+                    this.status.set(UpdateStatus::Downloaded {
+                        release,
+                        installer: installer.clone(),
+                    });
+                    this.on_downloaded.invoke(installer);
+                }
+                Err(err) => {
+                    tracing::warn!("error downloading update: {err}");
+                    this.status.set(UpdateStatus::Failed(err));
+                }
+            }
+        });
+    }
+
+    /// Skips the available release.
+    ///
+    /// The skipped version is stored in
+    /// [`skipped_version()`](Self::skipped_version).
+    pub fn skip(&self) {
+        if let UpdateStatus::Available(release) = self.status.get() {
+            self.skipped_version.set(Some(release.version));
+        }
+    }
+
+    /// Hides the [`banner()`](Self::banner) until the next time an update is
+    /// found.
+    pub fn dismiss(&self) {
+        self.dismissed.set(true);
+    }
+
+    /// Returns a widget that is shown when an update is available.
+    ///
+    /// The banner displays the new version and offers to install the update,
+    /// to remind the user later, or to skip the version. When no update is
+    /// available, or the available version was skipped or dismissed, the
+    /// banner is empty.
+    #[must_use]
+    pub fn banner(&self) -> WidgetInstance {
+        let this = self.clone();
+        (&self.status, &self.skipped_version, &self.dismissed)
+            .map_each(|(status, skipped, dismissed)| match status {
+                UpdateStatus::Available(release)
+                    if !dismissed && skipped.as_ref() != Some(&release.version) =>
+                {
+                    Some(release.clone())
+                }
+                _ => None,
+            })
+            .switcher(move |release, _| {
+                let Some(release) = release else {
+                    return Space::clear().make_widget();
+                };
+                let buttons = if release.installer.is_some() {
+                    "Install"
+                        .into_button()
+                        .on_click({
+                            let this = this.clone();
+                            move |_| this.install()
+                        })
+                        .make_widget()
+                        .and(
+                            "Later"
+                                .into_button()
+                                .on_click({
+                                    let this = this.clone();
+                                    move |_| this.dismiss()
+                                })
+                                .make_widget(),
+                        )
+                } else {
+                    "Dismiss"
+                        .into_button()
+                        .on_click({
+                            let this = this.clone();
+                            move |_| this.dismiss()
+                        })
+                        .make_widget()
+                        .and(Space::clear())
+                };
+                format!("Version {} is available.", release.version)
+                    .expand_horizontally()
+                    .and(buttons.into_columns())
+                    .and(
+                        "Skip"
+                            .into_button()
+                            .on_click({
+                                let this = this.clone();
+                                move |_| this.skip()
+                            })
+                            .make_widget(),
+                    )
+                    .into_columns()
+                    .contain()
+                    .make_widget()
+            })
+            .make_widget()
+    }
+
+    /// Opens a [`MessageBox`] in `open_in` offering to install the available
+    /// update.
+    ///
+    /// Does nothing if no update is available.
+    pub fn prompt(&self, open_in: &impl OpenMessageBox) {
+        let UpdateStatus::Available(release) = self.status.get() else {
+            return;
+        };
+
+        let message = MessageBox::build(format!("Version {} is available.", release.version))
+            .with_explanation(release.notes.clone());
+        let message = if release.installer.is_some() {
+            message
+                .with_yes(MessageButton::custom("Install", {
+                    let this = self.clone();
+                    move || this.install()
+                }))
+                .with_no(MessageButton::custom("Skip", {
+                    let this = self.clone();
+                    move || this.skip()
+                }))
+                .with_cancel(MessageButton::custom("Later", {
+                    let this = self.clone();
+                    move || this.dismiss()
+                }))
+                .finish()
+        } else {
+            message.finish()
+        };
+        message.open(open_in);
+    }
+}
+
+impl fmt::Debug for UpdateChecker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateChecker")
+            .field("current_version", &self.current_version)
+            .field("endpoint", &self.endpoint)
+            .field("status", &self.status)
+            .field("skipped_version", &self.skipped_version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The state of an [`UpdateChecker`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateStatus {
+    /// No check has been performed.
+    NotChecked,
+    /// The endpoint is being checked for a newer release.
+    Checking,
+    /// The running version is the latest release.
+    UpToDate,
+    /// A newer release is available.
+    Available(Release),
+    /// The installer for a release is being downloaded.
+    Downloading(Release),
+    /// The installer for a release has been downloaded.
+    Downloaded {
+        /// The release the installer is for.
+        release: Release,
+        /// The location of the downloaded installer.
+        installer: PathBuf,
+    },
+    /// An error occurred checking for or downloading an update.
+    Failed(UpdateError),
+}
+
+/// A released version of an application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// The version of this release.
+    pub version: String,
+    /// The installer for this release, if available.
+    pub installer: Option<Installer>,
+    /// The release notes for this release.
+    pub notes: String,
+}
+
+impl Release {
+    /// Parses a release from the default manifest format.
+    ///
+    /// See the [module documentation](self) for a description of the format.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UpdateError::InvalidManifest`] if the manifest does not
+    /// contain a version, or if it contains an installer URL without a valid
+    /// SHA-256 digest.
+    pub fn parse(manifest: &str) -> Result<Self, UpdateError> {
+        let mut lines = manifest.lines();
+        let version = lines
+            .next()
+            .map(str::trim)
+            .filter(|version| !version.is_empty())
+            .ok_or_else(|| UpdateError::InvalidManifest(String::from("missing version")))?;
+        let installer = match lines.next().map(str::trim).filter(|url| !url.is_empty()) {
+            Some(url) => {
+                let sha256 = lines.next().map(str::trim).unwrap_or_default();
+                Some(Installer::new(url, sha256)?)
+            }
+            None => None,
+        };
+        let notes = lines.collect::<Vec<_>>().join("\n");
+        Ok(Self {
+            version: version.to_string(),
+            installer,
+            notes: notes.trim().to_string(),
+        })
+    }
+}
+
+/// The location and expected contents of an installer for a [`Release`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Installer {
+    /// The URL to download the installer from.
+    pub url: String,
+    /// The SHA-256 digest of the installer.
+    pub sha256: [u8; 32],
+}
+
+impl Installer {
+    /// Returns an installer downloaded from `url` whose contents must match
+    /// the hex-encoded SHA-256 digest `sha256`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UpdateError::InvalidManifest`] if `sha256` is not 64
+    /// hexadecimal digits.
+    pub fn new(url: impl Into<String>, sha256: &str) -> Result<Self, UpdateError> {
+        let invalid = || UpdateError::InvalidManifest(String::from("invalid installer sha-256"));
+        let sha256 = sha256.as_bytes();
+        if sha256.len() != 64 || !sha256.iter().all(u8::is_ascii_hexdigit) {
+            return Err(invalid());
+        }
+        let mut digest = [0; 32];
+        for (byte, pair) in digest.iter_mut().zip(sha256.chunks_exact(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+        }
+        Ok(Self {
+            url: url.into(),
+            sha256: digest,
+        })
+    }
+
+    /// Returns an error if the SHA-256 digest of `contents` does not match
+    /// this installer's digest.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UpdateError::ChecksumMismatch`] if the digests differ.
+    pub fn verify(&self, contents: &[u8]) -> Result<(), UpdateError> {
+        if Sha256::digest(contents)[..] == self.sha256 {
+            Ok(())
+        } else {
+            Err(UpdateError::ChecksumMismatch)
+        }
+    }
+
+    /// Returns the name to save this installer as.
+    ///
+    /// The name is taken from the last segment of the URL's path, with any
+    /// characters other than ASCII letters, digits, `.`, `_`, and `-` replaced
+    /// by `_`.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        let name = self
+            .url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .unwrap_or_default()
+            .chars()
+            .map(|ch| {
+                if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-') {
+                    ch
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        // Leading periods would hide the file or refer to a parent directory.
+        let name = name.trim_start_matches('.');
+        if name.is_empty() {
+            String::from("installer")
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// Returns true if `version` is newer than `current`.
+///
+/// Versions are compared by their dot-separated numeric components. A version
+/// with a pre-release suffix, such as `1.0.0-beta.1`, is older than the same
+/// version without one. Pre-release suffixes are compared using the rules of
+/// semantic versioning: numeric identifiers are compared numerically and are
+/// older than alphanumeric identifiers.
+#[must_use]
+pub fn is_newer(version: &str, current: &str) -> bool {
+    compare_versions(version, current) == Ordering::Greater
+}
+
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.trim().trim_start_matches('v');
+        let version = version.split('+').next().unwrap_or_default();
+        let (release, pre_release) = match version.split_once('-') {
+            Some((release, pre_release)) => (release, Some(pre_release)),
+            None => (version, None),
+        };
+        let components = release
+            .split('.')
+            .map(|component| component.parse().unwrap_or(0))
+            .collect();
+        (components, pre_release)
+    }
+
+    let (a_components, a_pre) = split(a);
+    let (b_components, b_pre) = split(b);
+    let length = a_components.len().max(b_components.len());
+    for index in 0..length {
+        let a = a_components.get(index).copied().unwrap_or(0);
+        let b = b_components.get(index).copied().unwrap_or(0);
+        match a.cmp(&b) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+    }
+
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_pre_releases(a, b),
+    }
+}
+
+fn compare_pre_releases(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn fetch(url: &str) -> Result<String, UpdateError> {
+    ureq::get(url)
+        .call()
+        .map_err(|err| UpdateError::Request(err.to_string()))?
+        .into_string()
+        .map_err(|err| UpdateError::Request(err.to_string()))
+}
+
+/// Downloads `installer` into `directory`, returning the path of the
+/// downloaded file.
+///
+/// The downloaded contents are verified against the installer's SHA-256
+/// digest before being saved using [`Installer::file_name`].
+///
+/// # Errors
+///
+/// Returns an error if the installer cannot be downloaded or saved, or if its
+/// contents do not match the expected digest.
+pub fn download_installer(installer: &Installer, directory: &Path) -> Result<PathBuf, UpdateError> {
+    let response = ureq::get(&installer.url)
+        .call()
+        .map_err(|err| UpdateError::Request(err.to_string()))?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|err| UpdateError::Request(err.to_string()))?;
+    installer.verify(&bytes)?;
+
+    let path = directory.join(installer.file_name());
+    fs::write(&path, bytes).map_err(|err| UpdateError::Io(err.to_string()))?;
+    Ok(path)
+}
+
+/// Launches the installer at `path`.
+///
+/// On Windows, `.msi` packages are installed using `msiexec` and other files
+/// are executed directly. On other platforms, the operating system's default
+/// handler for the file is used.
+///
+/// The application is responsible for exiting if the installer requires it.
+///
+/// # Errors
+///
+/// Returns an error if the installer cannot be launched.
+pub fn launch_installer(path: &Path) -> io::Result<Child> {
+    if cfg!(target_os = "windows") {
+        if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("msi"))
+        {
+            Command::new("msiexec").arg("/i").arg(path).spawn()
+        } else {
+            Command::new(path).spawn()
+        }
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg(path).spawn()
+    } else {
+        Command::new("xdg-open").arg(path).spawn()
+    }
+}
+
+/// An error checking for or installing an update.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UpdateError {
+    /// The request to the server failed.
+    Request(String),
+    /// The release manifest could not be parsed.
+    InvalidManifest(String),
+    /// The installer could not be saved.
+    Io(String),
+    /// The downloaded installer did not match the SHA-256 digest in the
+    /// release manifest.
+    ChecksumMismatch,
+}
+
+impl Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateError::Request(err) => write!(f, "error requesting update: {err}"),
+            UpdateError::InvalidManifest(err) => write!(f, "invalid release manifest: {err}"),
+            UpdateError::Io(err) => write!(f, "error saving installer: {err}"),
+            UpdateError::ChecksumMismatch => {
+                f.write_str("downloaded installer does not match the expected sha-256")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UpdateError {}
+
+#[test]
+fn version_comparison() {
+    assert!(is_newer("1.2.0", "1.1.9"));
+    assert!(is_newer("1.10", "1.9.3"));
+    assert!(is_newer("v2.0.0", "1.99.99"));
+    assert!(is_newer("1.0.0", "1.0.0-beta.2"));
+    assert!(!is_newer("1.0.0", "1.0"));
+    assert!(!is_newer("1.0.0-rc.1", "1.0.0"));
+
+    // Pre-release identifiers follow the semantic versioning precedence rules.
+    assert!(is_newer("1.0.0-beta.11", "1.0.0-beta.2"));
+    assert!(is_newer("1.0.0-alpha.beta", "1.0.0-alpha.1"));
+    assert!(is_newer("1.0.0-alpha.1", "1.0.0-alpha"));
+    assert!(is_newer("1.0.0-rc.1", "1.0.0-beta.11"));
+    assert_eq!(
+        compare_versions("1.0.0-beta.2+build.5", "1.0.0-beta.2"),
+        Ordering::Equal
+    );
+}
+
+#[test]
+fn manifest_parsing() {
+    const TEST_SHA256: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    let release = Release::parse(&format!(
+        "1.2.0\nhttps://example.com/app.msi\n{TEST_SHA256}\nFixes.\n"
+    ))
+    .expect("valid manifest");
+    assert_eq!(release.version, "1.2.0");
+    let installer = release.installer.expect("installer");
+    assert_eq!(installer.url, "https://example.com/app.msi");
+    assert_eq!(installer.verify(b"test"), Ok(()));
+    assert_eq!(
+        installer.verify(b"tset"),
+        Err(UpdateError::ChecksumMismatch)
+    );
+    assert_eq!(release.notes, "Fixes.");
+
+    let release = Release::parse("1.2.0\n\nFixes.").expect("valid manifest");
+    assert_eq!(release.installer, None);
+    assert_eq!(release.notes, "Fixes.");
+
+    assert!(Release::parse("\n").is_err());
+    // Installers require a valid digest.
+    assert!(Release::parse("1.2.0\nhttps://example.com/app.msi\nFixes.").is_err());
+    assert!(Release::parse("1.2.0\nhttps://example.com/app.msi").is_err());
+    assert!(Release::parse(&format!(
+        "1.2.0\nhttps://example.com/app.msi\n+{}",
+        &TEST_SHA256[1..]
+    ))
+    .is_err());
+}
+
+#[test]
+fn installer_file_names() {
+    let file_name = |url: &str| {
+        Installer {
+            url: url.to_string(),
+            sha256: [0; 32],
+        }
+        .file_name()
+    };
+    assert_eq!(
+        file_name("https://example.com/app-1.2.msi?a=b#c"),
+        "app-1.2.msi"
+    );
+    assert_eq!(
+        file_name("https://example.com/my%20app.exe"),
+        "my_20app.exe"
+    );
+    assert_eq!(
+        file_name("https://example.com/..\\..\\app.exe"),
+        "_.._app.exe"
+    );
+    assert_eq!(file_name("https://example.com/.."), "installer");
+    assert_eq!(file_name("https://example.com/"), "installer");
+}
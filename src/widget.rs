@@ -10,7 +10,7 @@ use std::{slice, vec};
 
 use alot::LotId;
 use figures::units::{Px, UPx};
-use figures::{IntoSigned, IntoUnsigned, Point, Rect, Size, Zero};
+use figures::{Angle, IntoSigned, IntoUnsigned, Point, Rect, Size, Zero};
 use intentional::Assert;
 use kludgine::app::winit::event::{Ime, MouseButton, MouseScrollDelta, TouchPhase};
 use kludgine::app::winit::keyboard::ModifiersState;
@@ -20,36 +20,46 @@ use parking_lot::{Mutex, MutexGuard};
 #[cfg(feature = "localization")]
 use unic_langid::LanguageIdentifier;
 
+use crate::animation::ZeroToOne;
 use crate::app::Run;
 use crate::context::sealed::Trackable as _;
 use crate::context::{
-    AsEventContext, EventContext, GraphicsContext, LayoutContext, ManageWidget, WidgetContext,
+    AsEventContext, EventContext, FocusDirection, GraphicsContext, LayoutContext, ManageWidget,
+    WidgetContext,
 };
 use crate::reactive::channel::{BroadcastChannel, Broadcaster, Sender};
 use crate::reactive::value::{
     Dynamic, Generation, IntoDynamic, IntoValue, Source, Validation, Value,
 };
-use crate::styles::components::{HorizontalAlignment, IntrinsicPadding, VerticalAlignment};
+use crate::styles::components::{
+    BoxShadow, Elevation, HorizontalAlignment, IntrinsicPadding, MaxSize, MinSize, Opacity,
+    Overflow, VerticalAlignment, ZIndex,
+};
 use crate::styles::{
     ComponentDefinition, ContainerLevel, ContextFreeComponent, Dimension, DimensionRange, Edges,
-    FlexibleDimension, HorizontalAlign, IntoComponentValue, IntoDynamicComponentValue, Styles,
-    ThemePair, VisualOrder,
+    ElevationLevel, FlexibleDimension, HorizontalAlign, IntoComponentValue,
+    IntoDynamicComponentValue, OverflowPolicy, Styles, ThemePair, VisualOrder,
 };
 use crate::tree::{Tree, WeakTree};
 use crate::widgets::checkbox::{Checkable, CheckboxState};
+use crate::widgets::container::ContainerShadow;
 use crate::widgets::layers::{OverlayLayer, Tooltipped};
 use crate::widgets::list::List;
+use crate::widgets::menu::{ContextMenu, Menu};
+use crate::widgets::portal::{Portal, PortalTarget};
 use crate::widgets::shortcuts::{ShortcutKey, Shortcuts};
+use crate::widgets::visibility::Visibility;
 #[cfg(feature = "localization")]
 use crate::widgets::Localized;
 use crate::widgets::{
-    Align, Button, Checkbox, Collapse, Container, Disclose, Expand, Layers, Resize, Scroll, Space,
-    Stack, Style, Themed, ThemedMode, Validated, Wrap,
+    Align, AspectRatio, Button, CacheWarmup, Checkbox, Collapse, Container, Custom, Disclose,
+    Expand, FocusScope, Layers, Resize, Scroll, Space, Stack, Style, Themed, ThemedMode,
+    Transformed, Validated, Visible, WarmCaches, Wrap,
 };
 use crate::window::sealed::WindowCommand;
 use crate::window::{
-    DeviceId, KeyEvent, MakeWindow, Rgb8, RunningWindow, StandaloneWindowBuilder, ThemeMode,
-    VirtualRecorderBuilder, Window, WindowBehavior, WindowHandle, WindowLocal,
+    DeviceId, Gesture, KeyEvent, MakeWindow, Rgb8, RunningWindow, StandaloneWindowBuilder,
+    ThemeMode, VirtualRecorderBuilder, Window, WindowBehavior, WindowHandle, WindowLocal,
 };
 use crate::ConstraintLimit;
 
@@ -196,6 +206,36 @@ use crate::ConstraintLimit;
 /// the tracking widget's [`mouse_up()`](Self::mouse_up) function will be
 /// called.
 ///
+/// # Container Widgets
+///
+/// Widgets that contain other widgets use these APIs to manage their
+/// children:
+///
+/// - **Storing children:** [`WidgetRef`] stores a single child, and
+///   [`MountedChildren`] keeps a list of children mounted in sync with a
+///   `Value<WidgetList>`. [`MountedChildren::children()`] iterates the mounted
+///   children.
+/// - **Measuring:** [`LayoutContext::for_other()`] returns a context for a
+///   child, and calling [`layout()`](LayoutContext::layout) on it measures the
+///   child with the given constraints. A child may be measured multiple times
+///   during a single layout pass.
+/// - **Positioning:** [`LayoutContext::set_child_layout()`] assigns the
+///   region a child occupies, relative to the container. Each child that
+///   should be drawn must be positioned during every layout pass.
+/// - **Drawing:** [`GraphicsContext::redraw_children()`] draws children
+///   honoring their [`ZIndex`](crate::styles::components::ZIndex), and
+///   [`GraphicsContext::redraw_child_clipped()`] draws a child clipped to a
+///   smaller region. Children are always clipped to their assigned layout.
+/// - **Hit testing:** Mouse input is delivered to the children that were
+///   drawn under the cursor during the last frame. Containers do not need to
+///   register their children, and children that are not drawn do not receive
+///   mouse input.
+///
+/// The [guide][guide-containers] walks through implementing a masonry layout
+/// using these APIs.
+///
+/// [guide-containers]: https://cushy.rs/main/guide/about/containers.html
+///
 /// # User Input Focus
 ///
 /// A window can have a widget be *focused* for user input. For example, a text
@@ -474,9 +514,14 @@ pub trait Widget: Send + Debug + 'static {
     /// event has been handled or not.
     ///
     /// This function will only be invoked if it or a child is the currently
-    /// hovered widget. See [Hover State: Hit
+    /// hovered widget or is being scrolled by touch. See [Hover State: Hit
     /// Testing](Self#hover-state-hit-testing) for more information on how hover
     /// state is handled in Cushy.
+    ///
+    /// The kind of device that produced this event can be retrieved using
+    /// [`EventContext::scroll_source()`]. The user's preferred
+    /// [multiplier](crate::Cushy::scroll_multiplier) for the device has
+    /// already been applied to `delta`.
     #[allow(unused_variables)]
     #[allow(unused_variables)]
     fn mouse_wheel(
@@ -489,6 +534,43 @@ pub trait Widget: Send + Debug + 'static {
         IGNORED
     }
 
+    /// A touch [`Gesture`] has been sent to this widget. Returns whether the
+    /// event has been handled or not.
+    ///
+    /// Gestures are first sent to the widget beneath the finger that started
+    /// the gesture. If the gesture is ignored, it is sent to each parent until
+    /// it is handled.
+    #[allow(unused_variables)]
+    fn gesture(&mut self, gesture: Gesture, context: &mut EventContext<'_>) -> EventHandling {
+        IGNORED
+    }
+
+    /// Returns the transient state of this widget that should be transferred
+    /// to its replacement when its contents are swapped.
+    ///
+    /// This is only invoked on widgets that have a
+    /// [state key](MakeWidget::with_state_key) and are being replaced by a
+    /// parent that preserves state, such as
+    /// [`Switcher::preserving_state()`](crate::widgets::Switcher::preserving_state).
+    /// The returned value is passed to
+    /// [`restore_state()`](Self::restore_state) on the newly mounted widget
+    /// with the same key.
+    #[allow(unused_variables)]
+    #[must_use]
+    fn preserve_state(&mut self, context: &mut EventContext<'_>) -> Option<Box<dyn Any + Send>> {
+        None
+    }
+
+    /// Restores state previously returned from
+    /// [`preserve_state()`](Self::preserve_state) by the widget this widget is
+    /// replacing.
+    ///
+    /// This is invoked after this widget is mounted and before it is laid out.
+    /// Implementations should ignore `state` if it is not of the expected
+    /// type.
+    #[allow(unused_variables)]
+    fn restore_state(&mut self, state: Box<dyn Any + Send>, context: &mut EventContext<'_>) {}
+
     /// Returns a reference to a single child widget if this widget is a widget
     /// that primarily wraps a single other widget to customize its behavior.
     #[must_use]
@@ -837,6 +919,29 @@ pub trait WrapperWidget: Debug + Send + 'static {
     ) -> EventHandling {
         IGNORED
     }
+
+    /// A touch [`Gesture`] has been sent to this widget. Returns whether the
+    /// event has been handled or not.
+    #[allow(unused_variables)]
+    fn gesture(&mut self, gesture: Gesture, context: &mut EventContext<'_>) -> EventHandling {
+        IGNORED
+    }
+
+    /// Returns the transient state of this widget that should be transferred
+    /// to its replacement when its contents are swapped.
+    ///
+    /// See [`Widget::preserve_state()`] for more information.
+    #[allow(unused_variables)]
+    #[must_use]
+    fn preserve_state(&mut self, context: &mut EventContext<'_>) -> Option<Box<dyn Any + Send>> {
+        None
+    }
+
+    /// Restores state previously returned from
+    /// [`preserve_state()`](Self::preserve_state) by the widget this widget is
+    /// replacing.
+    #[allow(unused_variables)]
+    fn restore_state(&mut self, state: Box<dyn Any + Send>, context: &mut EventContext<'_>) {}
 }
 
 impl<T> Widget for T
@@ -970,6 +1075,18 @@ where
         T::mouse_wheel(self, device_id, delta, phase, context)
     }
 
+    fn gesture(&mut self, gesture: Gesture, context: &mut EventContext<'_>) -> EventHandling {
+        T::gesture(self, gesture, context)
+    }
+
+    fn preserve_state(&mut self, context: &mut EventContext<'_>) -> Option<Box<dyn Any + Send>> {
+        T::preserve_state(self, context)
+    }
+
+    fn restore_state(&mut self, state: Box<dyn Any + Send>, context: &mut EventContext<'_>) {
+        T::restore_state(self, state, context);
+    }
+
     fn advance_focus(
         &mut self,
         direction: VisualOrder,
@@ -1259,6 +1376,93 @@ pub trait MakeWidget: Sized {
         self.make_widget().into_escape()
     }
 
+    /// Prevents this widget from regaining focus automatically.
+    ///
+    /// When the focused widget is removed, such as when a modal or layer is
+    /// dismissed, focus returns to the most recently focused widget that is
+    /// still in the window. Focus is also restored when a window is
+    /// reactivated after its focused widget was lost. Widgets returned from
+    /// this function are skipped when choosing which widget to restore focus
+    /// to.
+    #[must_use]
+    fn without_focus_restore(self) -> WidgetInstance {
+        self.make_widget().without_focus_restore()
+    }
+
+    /// Assigns this widget an explicit position in the keyboard focus order.
+    ///
+    /// By default, Tab and Shift+Tab move focus in the order widgets appear
+    /// in the window. When any widget within the same focus root has an
+    /// explicit order, widgets with an explicit order are visited first, in
+    /// ascending `order`, followed by the remaining widgets in their default
+    /// order. Widgets sharing the same `order` are visited in their default
+    /// order.
+    ///
+    /// The focus root is the window, or the nearest widget that traps focus,
+    /// such as a [`FocusScope`] or a modal layer.
+    #[must_use]
+    fn with_focus_order(self, order: u32) -> WidgetInstance {
+        self.make_widget().with_focus_order(order)
+    }
+
+    /// Sets the widget to focus when moving focus in `direction` from this
+    /// widget.
+    ///
+    /// By default, directional focus navigation moves focus to the nearest
+    /// focusable widget in the requested direction. This function overrides
+    /// that choice for a single direction. See
+    /// [`Window::spatial_navigation()`](crate::window::Window::spatial_navigation)
+    /// and [`EventContext::focus_in_direction()`] for more information.
+    #[must_use]
+    fn with_focus_neighbor(
+        self,
+        direction: FocusDirection,
+        neighbor: impl Into<WidgetId>,
+    ) -> WidgetInstance {
+        self.make_widget().with_focus_neighbor(direction, neighbor)
+    }
+
+    /// Removes this widget from keyboard focus navigation.
+    ///
+    /// Tab and Shift+Tab will skip this widget, but it remains enabled and
+    /// can still be focused by clicking it or by calling
+    /// [`EventContext::focus()`](crate::context::EventContext::focus). Any
+    /// focusable children of this widget are still visited.
+    #[must_use]
+    fn skip_focus(self) -> WidgetInstance {
+        self.make_widget().skip_focus()
+    }
+
+    /// Assigns a stable key identifying this widget's transient state.
+    ///
+    /// When a parent that preserves state, such as
+    /// [`Switcher::preserving_state()`](crate::widgets::Switcher::preserving_state),
+    /// replaces its contents, the state of each widget with a key is
+    /// transferred to the widget in the new contents with the same key. This
+    /// includes keyboard focus and widget-specific state such as scroll
+    /// positions and text selections.
+    ///
+    /// Keys only need to be unique within the contents being replaced.
+    #[must_use]
+    fn with_state_key(self, key: impl Into<String>) -> WidgetInstance {
+        self.make_widget().with_state_key(key)
+    }
+
+    /// Sets this widget to coalesce high-frequency input events.
+    ///
+    /// While the cursor is over this widget or one of its children, or while
+    /// this widget or one of its children is being dragged, cursor movements
+    /// and mouse wheel events are delivered at most once per frame. Cursor
+    /// events deliver the latest position, and mouse wheel events deliver the
+    /// sum of the scrolled amounts.
+    ///
+    /// This is useful for widgets that do expensive work in response to the
+    /// cursor moving, such as charts that display a crosshair.
+    #[must_use]
+    fn coalesce_input(self) -> WidgetInstance {
+        self.make_widget().coalesce_input()
+    }
+
     /// Returns a collection of widgets using `self` and `other`.
     fn and(self, other: impl MakeWidget) -> WidgetList {
         let mut children = WidgetList::new();
@@ -1336,6 +1540,122 @@ pub trait MakeWidget: Sized {
         Resize::from_height(height, self)
     }
 
+    /// Prevents `self` from being laid out smaller than `size`.
+    ///
+    /// Unlike [`MakeWidget::size()`], this is applied using the
+    /// [`MinSize`](crate::styles::components::MinSize) component, which is
+    /// honored by the layout of every widget.
+    fn min_size(self, size: impl IntoValue<Size<Dimension>>) -> Style {
+        self.with_local(&MinSize, size)
+    }
+
+    /// Prevents `self` from being laid out larger than `size`.
+    ///
+    /// Unlike [`MakeWidget::size()`], this is applied using the
+    /// [`MaxSize`](crate::styles::components::MaxSize) component, which is
+    /// honored by the layout of every widget.
+    fn max_size(self, size: impl IntoValue<Size<Dimension>>) -> Style {
+        self.with_local(&MaxSize, size)
+    }
+
+    /// Sets the policy for handling the contents of `self` that do not fit
+    /// within the space available.
+    ///
+    /// When combining this function with [`MakeWidget::max_size()`], this
+    /// function should be called first so that the returned widget is laid
+    /// out within the maximum size.
+    fn overflow(self, policy: impl IntoValue<OverflowPolicy>) -> Style {
+        self.with_local(&Overflow, policy)
+    }
+
+    /// Sets the stacking order of `self` relative to its siblings.
+    ///
+    /// Widgets with a larger `z_index` are drawn above and receive mouse events
+    /// before their siblings with a smaller `z_index`. See
+    /// [`ZIndex`](crate::styles::components::ZIndex) for more information.
+    fn z_index(self, z_index: impl IntoValue<i32>) -> Style {
+        self.with_local(&ZIndex, z_index)
+    }
+
+    /// Draws `self` and all of its children with `opacity`.
+    ///
+    /// The opacity is multiplied with the opacity of any parent widgets.
+    /// Because [`ZeroToOne`] can be linearly interpolated, providing a
+    /// [`Dynamic`] allows fading `self` in and out using the animation system:
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use cushy::animation::{AnimationTarget, Spawn, ZeroToOne};
+    /// use cushy::figures::Zero;
+    /// use cushy::reactive::value::Dynamic;
+    /// use cushy::widget::MakeWidget;
+    ///
+    /// let opacity = Dynamic::new(ZeroToOne::ZERO);
+    /// let label = "Hello".opacity(opacity.clone());
+    ///
+    /// opacity
+    ///     .transition_to(ZeroToOne::ONE)
+    ///     .over(Duration::from_millis(250))
+    ///     .launch();
+    /// ```
+    fn opacity(self, opacity: impl IntoValue<ZeroToOne>) -> Style {
+        self.with_local(&Opacity, opacity)
+    }
+
+    /// Raises `self` to `level`, drawing the theme's shadow for `level` behind
+    /// it.
+    ///
+    /// See [`Elevation`](crate::styles::components::Elevation) for more
+    /// information.
+    fn elevation(self, level: impl IntoValue<ElevationLevel>) -> Style {
+        self.with_local(&Elevation, level)
+    }
+
+    /// Draws `shadow` behind `self`.
+    ///
+    /// Unlike [`Container::shadow()`](crate::widgets::Container::shadow), the
+    /// shadow does not affect the layout of `self`. See
+    /// [`BoxShadow`](crate::styles::components::BoxShadow) for more
+    /// information.
+    fn box_shadow(self, shadow: impl IntoValue<ContainerShadow>) -> Style {
+        self.with_local(&BoxShadow, shadow)
+    }
+
+    /// Constrains `self` to `ratio`, its width divided by its height.
+    ///
+    /// By default, `self` is sized to fit within the available space. Use
+    /// [`AspectRatio::cover()`] to cover the available space instead.
+    #[must_use]
+    fn aspect_ratio(self, ratio: impl IntoValue<f32>) -> AspectRatio {
+        AspectRatio::new(ratio, self)
+    }
+
+    /// Rotates `self` clockwise by `rotation` around its center.
+    ///
+    /// The returned widget occupies the bounding box of the rotated widget.
+    #[must_use]
+    fn rotated_by(self, rotation: impl IntoValue<Angle>) -> Transformed {
+        Transformed::new(self).rotated_by(rotation)
+    }
+
+    /// Scales `self` by `scale` around its center.
+    ///
+    /// The returned widget occupies the size of the scaled widget.
+    #[must_use]
+    fn scaled_by(self, scale: impl IntoValue<f32>) -> Transformed {
+        Transformed::new(self).scaled_by(scale)
+    }
+
+    /// Prepares the assets in `warmup` the first time `self` is drawn.
+    ///
+    /// This is intended to be used on a splash screen to prepare the text and
+    /// images that will be shown once the splash screen is dismissed.
+    #[must_use]
+    fn warm_caches(self, warmup: CacheWarmup) -> WarmCaches {
+        WarmCaches::new(warmup, self)
+    }
+
     /// Returns this widget as the contents of a clickable button.
     fn into_button(self) -> Button {
         Button::new(self)
@@ -1398,6 +1718,15 @@ pub trait MakeWidget: Sized {
         self.centered().fit_vertically()
     }
 
+    /// Returns a [`FocusScope`] that manages keyboard focus within `self`.
+    ///
+    /// By default, the scope restores focus to the previously focused widget
+    /// when it is unmounted.
+    #[must_use]
+    fn focus_scope(self) -> FocusScope {
+        FocusScope::new(self)
+    }
+
     /// Allows scrolling `self` both vertically and horizontally.
     #[must_use]
     fn scroll(self) -> Scroll {
@@ -1480,6 +1809,15 @@ pub trait MakeWidget: Sized {
         Collapse::vertical(collapse_when, self)
     }
 
+    /// Returns a widget that shows, hides, or collapses `self` based on
+    /// `visibility`.
+    ///
+    /// See [`Visibility`](crate::widgets::visibility::Visibility) for the
+    /// supported states.
+    fn visible(self, visibility: impl IntoValue<Visibility>) -> Visible {
+        Visible::new(visibility, self)
+    }
+
     /// Returns a new widget that allows hiding and showing `contents`.
     fn disclose(self) -> Disclose {
         Disclose::new(self)
@@ -1494,6 +1832,34 @@ pub trait MakeWidget: Sized {
     fn tooltip(self, layer: &OverlayLayer, tip: impl MakeWidget) -> Tooltipped {
         layer.new_tooltip(tip, self)
     }
+
+    /// Returns a widget that shows `menu` on `layer` when `self` is clicked
+    /// with the secondary mouse button.
+    fn context_menu<T>(self, layer: &OverlayLayer, menu: Menu<T>) -> ContextMenu
+    where
+        T: Unpin + Clone + Debug + Send + Sync + 'static,
+    {
+        ContextMenu::new(self, layer, menu)
+    }
+
+    /// Returns a widget that prevents pen and touch input within `self` from
+    /// performing the window's
+    /// [`GestureShortcuts`](crate::window::GestureShortcuts).
+    ///
+    /// Long presses and flicks are still delivered to `self` and its children
+    /// as [`Gesture`]s, but no secondary click or navigation key press is
+    /// generated when they are unhandled. This is useful for drawing surfaces.
+    fn without_gesture_shortcuts(self) -> Custom {
+        Custom::new(self).on_gesture(|gesture, _context| match gesture {
+            Gesture::LongPress { .. } | Gesture::Flick { .. } => HANDLED,
+            _ => IGNORED,
+        })
+    }
+
+    /// Returns a widget that displays `self` in `target` while it is mounted.
+    fn portal(self, target: &PortalTarget) -> Portal {
+        Portal::new(target, self)
+    }
 }
 
 /// A type that can create a [`WidgetInstance`] with a preallocated
@@ -1553,6 +1919,7 @@ pub const IGNORED: EventHandling = EventHandling::Continue(EventIgnored);
 pub(crate) trait AnyWidget: Widget {
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn type_name(&self) -> &'static str;
 }
 
 impl<T> AnyWidget for T
@@ -1566,6 +1933,10 @@ where
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
 }
 
 /// An instance of a [`Widget`].
@@ -1588,8 +1959,16 @@ struct WidgetInstanceData {
     id: WidgetId,
     default: bool,
     cancel: bool,
+    coalesce_input: bool,
+    restore_focus: bool,
+    skip_focus: bool,
+    focus_order: Option<u32>,
+    focus_neighbors: [Option<WidgetId>; 4],
+    state_key: Option<String>,
     next_focus: Value<Option<WidgetId>>,
     enabled: Value<bool>,
+    #[cfg(debug_assertions)]
+    locked_by: Mutex<Option<std::thread::ThreadId>>,
     widget: Box<Mutex<dyn AnyWidget>>,
 }
 
@@ -1606,6 +1985,14 @@ impl WidgetInstance {
                 next_focus: Value::default(),
                 default: false,
                 cancel: false,
+                coalesce_input: false,
+                restore_focus: true,
+                skip_focus: false,
+                focus_order: None,
+                focus_neighbors: [None; 4],
+                state_key: None,
+                #[cfg(debug_assertions)]
+                locked_by: Mutex::new(None),
                 widget: Box::new(Mutex::new(widget)),
                 enabled: Value::Constant(true),
             }),
@@ -1707,12 +2094,142 @@ impl WidgetInstance {
         self
     }
 
+    /// Sets this widget to coalesce high-frequency input events and returns
+    /// self.
+    ///
+    /// See [`MakeWidget::coalesce_input()`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// This function can only be called when one instance of the widget exists.
+    /// If any clones exist, a panic will occur.
+    #[must_use]
+    pub fn coalesce_input(mut self) -> WidgetInstance {
+        let data = Arc::get_mut(&mut self.data)
+            .expect("coalesce_input can only be called on newly created widget instances");
+        data.coalesce_input = true;
+        self
+    }
+
+    /// Prevents this widget from regaining focus automatically and returns
+    /// self.
+    ///
+    /// See [`MakeWidget::without_focus_restore()`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// This function can only be called when one instance of the widget exists.
+    /// If any clones exist, a panic will occur.
+    #[must_use]
+    pub fn without_focus_restore(mut self) -> WidgetInstance {
+        let data = Arc::get_mut(&mut self.data)
+            .expect("without_focus_restore can only be called on newly created widget instances");
+        data.restore_focus = false;
+        self
+    }
+
+    /// Assigns this widget an explicit position in the keyboard focus order
+    /// and returns self.
+    ///
+    /// See [`MakeWidget::with_focus_order()`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// This function can only be called when one instance of the widget exists.
+    /// If any clones exist, a panic will occur.
+    #[must_use]
+    pub fn with_focus_order(mut self, order: u32) -> WidgetInstance {
+        let data = Arc::get_mut(&mut self.data)
+            .expect("with_focus_order can only be called on newly created widget instances");
+        data.focus_order = Some(order);
+        self
+    }
+
+    /// Sets the widget to focus when moving focus in `direction` from this
+    /// widget and returns self.
+    ///
+    /// See [`MakeWidget::with_focus_neighbor()`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// This function can only be called when one instance of the widget exists.
+    /// If any clones exist, a panic will occur.
+    #[must_use]
+    pub fn with_focus_neighbor(
+        mut self,
+        direction: FocusDirection,
+        neighbor: impl Into<WidgetId>,
+    ) -> WidgetInstance {
+        let data = Arc::get_mut(&mut self.data)
+            .expect("with_focus_neighbor can only be called on newly created widget instances");
+        data.focus_neighbors[direction as usize] = Some(neighbor.into());
+        self
+    }
+
+    /// Removes this widget from keyboard focus navigation and returns self.
+    ///
+    /// See [`MakeWidget::skip_focus()`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// This function can only be called when one instance of the widget exists.
+    /// If any clones exist, a panic will occur.
+    #[must_use]
+    pub fn skip_focus(mut self) -> WidgetInstance {
+        let data = Arc::get_mut(&mut self.data)
+            .expect("skip_focus can only be called on newly created widget instances");
+        data.skip_focus = true;
+        self
+    }
+
+    /// Assigns a key identifying this widget's state and returns self.
+    ///
+    /// See [`MakeWidget::with_state_key()`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// This function can only be called when one instance of the widget exists.
+    /// If any clones exist, a panic will occur.
+    #[must_use]
+    pub fn with_state_key(mut self, key: impl Into<String>) -> WidgetInstance {
+        let data = Arc::get_mut(&mut self.data)
+            .expect("with_state_key can only be called on newly created widget instances");
+        data.state_key = Some(key.into());
+        self
+    }
+
     /// Locks the widget for exclusive access. Locking widgets should only be
     /// done for brief moments of time when you are certain no deadlocks can
     /// occur due to other widget locks being held.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, this function panics if the current thread has already
+    /// locked this widget, as waiting for the lock would never finish.
     #[must_use]
     pub fn lock(&self) -> WidgetGuard<'_> {
-        WidgetGuard(self.data.widget.lock())
+        #[cfg(debug_assertions)]
+        {
+            let current = std::thread::current().id();
+            let guard = self.data.widget.try_lock().unwrap_or_else(|| {
+                assert_ne!(
+                    *self.data.locked_by.lock(),
+                    Some(current),
+                    "widget {:?} is already locked by the current thread",
+                    self.data.id
+                );
+                self.data.widget.lock()
+            });
+            *self.data.locked_by.lock() = Some(current);
+            WidgetGuard {
+                guard,
+                locked_by: &self.data.locked_by,
+            }
+        }
+        #[cfg(not(debug_assertions))]
+        WidgetGuard {
+            guard: self.data.widget.lock(),
+        }
     }
 
     /// Returns the id of the widget that should receive focus after this
@@ -1740,6 +2257,56 @@ impl WidgetInstance {
         self.data.cancel
     }
 
+    /// Returns true if this widget coalesces high-frequency input events.
+    ///
+    /// See [`MakeWidget::coalesce_input()`] for more information.
+    #[must_use]
+    pub fn coalesces_input(&self) -> bool {
+        self.data.coalesce_input
+    }
+
+    /// Returns true if this widget can have its focus restored automatically.
+    ///
+    /// See [`MakeWidget::without_focus_restore()`] for more information.
+    #[must_use]
+    pub fn restores_focus(&self) -> bool {
+        self.data.restore_focus
+    }
+
+    /// Returns this widget's explicit position in the keyboard focus order,
+    /// if one was assigned.
+    ///
+    /// See [`MakeWidget::with_focus_order()`] for more information.
+    #[must_use]
+    pub fn focus_order(&self) -> Option<u32> {
+        self.data.focus_order
+    }
+
+    /// Returns the widget to focus when moving focus in `direction` from this
+    /// widget, if one was assigned.
+    ///
+    /// See [`MakeWidget::with_focus_neighbor()`] for more information.
+    #[must_use]
+    pub fn focus_neighbor(&self, direction: FocusDirection) -> Option<WidgetId> {
+        self.data.focus_neighbors[direction as usize]
+    }
+
+    /// Returns true if this widget is skipped by keyboard focus navigation.
+    ///
+    /// See [`MakeWidget::skip_focus()`] for more information.
+    #[must_use]
+    pub fn skips_focus(&self) -> bool {
+        self.data.skip_focus
+    }
+
+    /// Returns the key identifying this widget's state, if one was assigned.
+    ///
+    /// See [`MakeWidget::with_state_key()`] for more information.
+    #[must_use]
+    pub fn state_key(&self) -> Option<&str> {
+        self.data.state_key.as_deref()
+    }
+
     pub(crate) fn enabled(&self, context: &WindowHandle) -> bool {
         if let Value::Dynamic(dynamic) = &self.data.enabled {
             dynamic.inner_redraw_when_changed(context.clone());
@@ -2175,6 +2742,14 @@ impl MountedWidget {
         self.tree().set_layout(self.node_id, rect);
     }
 
+    /// Returns true if keyboard focus navigation should not leave this
+    /// widget's children.
+    pub(crate) fn traps_focus(&self) -> bool {
+        self.tree
+            .upgrade()
+            .map_or(false, |tree| tree.is_focus_trap(self.node_id))
+    }
+
     /// Returns the unique id of this widget instance.
     #[must_use]
     pub fn id(&self) -> WidgetId {
@@ -2267,6 +2842,19 @@ impl MountedWidget {
             .and_then(|id| tree.widget_from_node(id))
     }
 
+    /// Returns true if this widget or any of its parents coalesce
+    /// high-frequency input events.
+    pub(crate) fn coalesces_input(&self) -> bool {
+        let mut widget = Some(self.clone());
+        while let Some(current) = widget {
+            if current.widget.coalesces_input() {
+                return true;
+            }
+            widget = current.parent();
+        }
+        false
+    }
+
     /// Returns true if this node has a parent.
     #[must_use]
     pub fn has_parent(&self) -> bool {
@@ -2339,11 +2927,20 @@ impl PartialEq<WidgetInstance> for MountedWidget {
 ///
 /// This type is powered by a `Mutex`, which means care must be taken to prevent
 /// deadlocks.
-pub struct WidgetGuard<'a>(MutexGuard<'a, dyn AnyWidget>);
+pub struct WidgetGuard<'a> {
+    guard: MutexGuard<'a, dyn AnyWidget>,
+    #[cfg(debug_assertions)]
+    locked_by: &'a Mutex<Option<std::thread::ThreadId>>,
+}
 
 impl WidgetGuard<'_> {
     pub(crate) fn as_widget(&mut self) -> &mut dyn AnyWidget {
-        &mut *self.0
+        &mut *self.guard
+    }
+
+    /// Returns the name of the contained widget's type.
+    pub(crate) fn type_name(&self) -> &'static str {
+        self.guard.type_name()
     }
 
     /// Returns a reference to `T` if it is the type contained.
@@ -2352,7 +2949,7 @@ impl WidgetGuard<'_> {
     where
         T: 'static,
     {
-        self.0.as_any().downcast_ref()
+        self.guard.as_any().downcast_ref()
     }
 
     /// Returns an exclusive reference to `T` if it is the type contained.
@@ -2361,7 +2958,14 @@ impl WidgetGuard<'_> {
     where
         T: 'static,
     {
-        self.0.as_any_mut().downcast_mut()
+        self.guard.as_any_mut().downcast_mut()
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for WidgetGuard<'_> {
+    fn drop(&mut self) {
+        *self.locked_by.lock() = None;
     }
 }
 
@@ -3063,3 +3667,157 @@ impl Deref for WidgetTag {
         &self.0
     }
 }
+
+#[test]
+fn opacity_multiplies_with_parents() {
+    use crate::widgets::Space;
+
+    fn render(widget: impl MakeWidget) -> Color {
+        let mut recorder = widget
+            .build_recorder()
+            .with_alpha()
+            .size(Size::squared(UPx::new(8)))
+            .finish()
+            .expect("error creating recorder");
+        recorder.refresh().expect("error rendering");
+        recorder.pixel_color(Point::squared(UPx::new(4)))
+    }
+
+    let quarter = render(
+        Space::colored(Color::WHITE)
+            .expand()
+            .opacity(ZeroToOne::new(0.25)),
+    );
+    let nested = render(
+        Space::colored(Color::WHITE)
+            .expand()
+            .opacity(ZeroToOne::new(0.5))
+            .opacity(ZeroToOne::new(0.5)),
+    );
+    let opaque = render(Space::colored(Color::WHITE).expand());
+
+    assert!(quarter.alpha() < opaque.alpha());
+    assert!(quarter.alpha().abs_diff(nested.alpha()) <= 1);
+}
+
+#[test]
+fn explicit_focus_order_is_visited_first() {
+    use crate::widgets::focus_scope::{focus_probe, press_tab};
+
+    let focused = Dynamic::new(None);
+    let mut recorder = focus_probe("first", &focused)
+        .and(focus_probe("skipped", &focused).skip_focus())
+        .and(focus_probe("third", &focused))
+        .and(focus_probe("second", &focused).with_focus_order(2))
+        .and(focus_probe("zeroth", &focused).with_focus_order(0))
+        .into_rows()
+        .build_recorder()
+        .finish()
+        .expect("error creating recorder");
+    recorder.refresh().expect("error rendering");
+
+    let mut visited = Vec::new();
+    for _ in 0..4 {
+        press_tab(&mut recorder);
+        visited.push(focused.get().expect("focused"));
+    }
+    assert_eq!(visited, ["zeroth", "second", "first", "third"]);
+}
+
+#[test]
+fn containers_measure_position_and_clip_children() {
+    use kludgine::app::winit::event::ElementState;
+
+    use crate::widgets::{Custom, Space};
+    use crate::window::DeviceId;
+
+    /// Places two children side by side, drawing the second child clipped to
+    /// its top half.
+    #[derive(Debug)]
+    struct Halves {
+        children: Value<WidgetList>,
+        mounted: MountedChildren,
+    }
+
+    impl Widget for Halves {
+        fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+            let [first, second] = self.mounted.children() else {
+                unreachable!("two children")
+            };
+            let size = context.gfx.region().size;
+            context.for_other(first).redraw();
+            context.redraw_child_clipped(
+                second,
+                Rect::new(
+                    Point::new(size.width / 2, Px::ZERO),
+                    Size::new(size.width / 2, size.height / 2),
+                ),
+            );
+        }
+
+        fn layout(
+            &mut self,
+            available_space: Size<ConstraintLimit>,
+            context: &mut LayoutContext<'_, '_, '_, '_>,
+        ) -> Size<UPx> {
+            self.mounted
+                .synchronize_with(&self.children, &mut context.as_event_context());
+            let size = available_space.map(ConstraintLimit::max);
+            let half = Size::new(size.width / 2, size.height);
+            for (x, child) in [UPx::ZERO, half.width]
+                .into_iter()
+                .zip(self.mounted.children())
+            {
+                context
+                    .for_other(child)
+                    .layout(half.map(ConstraintLimit::Fill));
+                context.set_child_layout(
+                    child,
+                    Rect::new(Point::new(x, UPx::ZERO), half).into_signed(),
+                );
+            }
+            size
+        }
+    }
+
+    let clicks = Dynamic::new(0);
+    let clicked = clicks.clone();
+    let mut recorder = Halves {
+        children: Value::Constant(
+            Space::colored(Color::RED).and(
+                Custom::new(Space::colored(Color::BLUE))
+                    .on_hit_test(|_, _| true)
+                    .on_mouse_down(move |_, _, _, _| {
+                        *clicked.lock() += 1;
+                        HANDLED
+                    }),
+            ),
+        ),
+        mounted: MountedChildren::default(),
+    }
+    .build_recorder()
+    .size(Size::squared(UPx::new(16)))
+    .finish()
+    .expect("error creating recorder");
+    recorder.refresh().expect("error rendering");
+
+    recorder.assert_pixel_color(Point::new(UPx::new(4), UPx::new(8)), Color::RED, "first");
+    recorder.assert_pixel_color(Point::new(UPx::new(12), UPx::new(4)), Color::BLUE, "second");
+    assert_ne!(
+        recorder.pixel_color(Point::new(UPx::new(12), UPx::new(12))),
+        Color::BLUE,
+        "clipped portion was drawn"
+    );
+
+    let device = DeviceId::Virtual(0);
+    for (location, expected_clicks) in [(Point::px(12, 12), 0), (Point::px(12, 4), 1)] {
+        recorder.window.cursor_moved(device, location);
+        recorder
+            .window
+            .mouse_input(device, ElementState::Pressed, MouseButton::Left);
+        recorder
+            .window
+            .mouse_input(device, ElementState::Released, MouseButton::Left);
+        assert_eq!(clicks.get(), expected_clicks, "clicked at {location:?}");
+    }
+}
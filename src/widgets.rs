@@ -1,19 +1,36 @@
 //! Built-in [`Widget`](crate::widget::Widget) implementations.
 
 mod align;
+pub mod animated_image;
+mod aspect_ratio;
+pub mod audio;
 pub mod button;
-mod canvas;
+#[cfg(feature = "calendar")]
+pub mod calendar;
+pub mod canvas;
+pub mod chart;
 pub mod checkbox;
 mod collapse;
 pub mod color;
+pub mod command_palette;
 mod component_probe;
+pub mod connectors;
 pub mod container;
 mod custom;
 mod data;
 pub mod delimiter;
+#[cfg(feature = "diff")]
+pub mod diff_view;
 pub mod disclose;
+pub mod dock;
+pub mod drag;
 mod expand;
+pub mod focus_scope;
+pub mod gauge;
 pub mod grid;
+pub mod heatmap;
+pub mod icon;
+mod idle_lock;
 pub mod image;
 pub mod indicator;
 pub mod input;
@@ -22,59 +39,108 @@ pub mod layers;
 pub mod list;
 #[cfg(feature = "localization")]
 mod localized;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+pub mod masonry;
 pub mod menu;
+pub mod message_list;
 mod mode_switch;
 pub mod pile;
+pub mod portal;
 pub mod progress;
 pub mod radio;
 mod resize;
+pub mod rich_text;
+pub mod roots;
+pub mod scene;
 pub mod scroll;
 pub mod select;
 pub mod shortcuts;
 pub mod slider;
 mod space;
+pub mod sparkline;
+pub mod spin_box;
+pub mod split;
 pub mod stack;
 mod style;
 mod switcher;
+pub mod text_editor;
 mod themed;
 mod tilemap;
+mod title_bar;
+pub mod toast;
+mod transformed;
+pub mod tree_view;
+#[cfg(feature = "http")]
+pub mod url_image;
 pub mod validated;
 mod virtual_list;
+pub mod visibility;
+mod warm_caches;
 pub mod wrap;
 
 pub use self::align::Align;
+pub use self::animated_image::AnimatedImage;
+pub use self::aspect_ratio::AspectRatio;
 pub use self::button::Button;
+#[cfg(feature = "calendar")]
+pub use self::calendar::Calendar;
 pub use self::canvas::Canvas;
 pub use self::checkbox::Checkbox;
 pub use self::collapse::Collapse;
 pub use self::component_probe::ComponentProbe;
+pub use self::connectors::Connectors;
 pub use self::container::Container;
 pub use self::custom::Custom;
 pub use self::data::Data;
 pub use self::delimiter::Delimiter;
+#[cfg(feature = "diff")]
+pub use self::diff_view::DiffView;
 pub use self::disclose::Disclose;
 pub use self::expand::Expand;
+pub use self::focus_scope::FocusScope;
+pub use self::gauge::Gauge;
 pub use self::grid::Grid;
+pub use self::icon::Icon;
+pub use self::idle_lock::IdleLock;
 pub use self::image::Image;
 pub use self::input::Input;
 pub use self::label::Label;
 pub use self::layers::Layers;
 #[cfg(feature = "localization")]
 pub use self::localized::Localized;
+#[cfg(feature = "markdown")]
+pub use self::markdown::Markdown;
+pub use self::masonry::Masonry;
 pub use self::menu::Menu;
+pub use self::message_list::MessageList;
 pub use self::mode_switch::ThemedMode;
 pub use self::progress::ProgressBar;
 pub use self::radio::Radio;
 pub use self::resize::Resize;
+pub use self::rich_text::RichText;
+pub use self::roots::Roots;
 pub use self::scroll::Scroll;
 pub use self::select::Select;
 pub use self::slider::Slider;
 pub use self::space::Space;
+pub use self::sparkline::Sparkline;
+pub use self::spin_box::SpinBox;
+pub use self::split::Split;
 pub use self::stack::Stack;
 pub use self::style::Style;
 pub use self::switcher::Switcher;
+pub use self::text_editor::TextEditor;
 pub use self::themed::Themed;
 pub use self::tilemap::TileMap;
+pub use self::title_bar::TitleBar;
+pub use self::toast::Toasts;
+pub use self::transformed::Transformed;
+pub use self::tree_view::TreeView;
+#[cfg(feature = "http")]
+pub use self::url_image::UrlImage;
 pub use self::validated::Validated;
 pub use self::virtual_list::VirtualList;
+pub use self::visibility::Visible;
+pub use self::warm_caches::{CacheWarmup, WarmCaches};
 pub use self::wrap::Wrap;
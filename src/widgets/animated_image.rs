@@ -0,0 +1,331 @@
+//! A widget that plays an animated image.
+
+use std::io::Cursor;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::time::Duration;
+
+use image::codecs::png::PngDecoder;
+use image::error::{ParameterError, ParameterErrorKind};
+use image::{AnimationDecoder, DynamicImage, Frame, ImageError, ImageResult};
+use kludgine::{AnyTexture, LazyTexture};
+
+use crate::animation::{Animate, AnimationHandle, IntoAnimate, Spawn};
+use crate::context::GraphicsContext;
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, IntoValue, Source, Value};
+use crate::widget::{WidgetRef, WrapperWidget};
+use crate::widgets::image::ImageScaling;
+use crate::widgets::Image;
+
+/// The shortest delay a frame is displayed for.
+///
+/// Many encoders write a delay of `0` to mean "as fast as possible". Like most
+/// browsers, frames with shorter delays are slowed down to this delay.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// A widget that plays an animated image, such as a GIF or APNG.
+///
+/// Playback is driven by Cushy's animation system and can be controlled using
+/// a [`Dynamic<bool>`](Dynamic) provided to [`Self::playing`]. When playback
+/// reaches the last frame of an image that is not
+/// [looping](Self::looping), playback stops and the playing dynamic is set to
+/// `false`. Setting it to `true` again restarts playback from the current
+/// frame.
+///
+/// ```rust,no_run
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::AnimatedImage;
+///
+/// let playing = Dynamic::new(true);
+/// let animation = AnimatedImage::from_apng(&std::fs::read("animation.png").unwrap())
+///     .unwrap()
+///     .playing(playing.clone())
+///     .looping(false);
+/// ```
+#[derive(Debug)]
+pub struct AnimatedImage {
+    frames: Arc<[AnimationFrame]>,
+    frame: Dynamic<usize>,
+    playing: Dynamic<bool>,
+    looping: Value<bool>,
+    image: WidgetRef,
+    texture: Dynamic<AnyTexture>,
+    displayed: usize,
+    playback: AnimationHandle,
+}
+
+impl AnimatedImage {
+    /// Returns a widget that plays `frames`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `frames` is empty.
+    pub fn new(frames: impl IntoIterator<Item = AnimationFrame>) -> ImageResult<Self> {
+        let frames: Arc<[AnimationFrame]> = frames.into_iter().collect();
+        let first = frames
+            .first()
+            .ok_or_else(|| {
+                ImageError::Parameter(ParameterError::from_kind(ParameterErrorKind::NoMoreData))
+            })?
+            .texture
+            .clone();
+        let texture = Dynamic::new(first);
+        let frame = Dynamic::new(0);
+        Ok(Self {
+            image: WidgetRef::new(Image::new(texture.clone())),
+            texture,
+            displayed: 0,
+            frames,
+            playing: Dynamic::new(true),
+            looping: Value::Constant(true),
+            playback: AnimationHandle::new(),
+            frame,
+        })
+    }
+
+    /// Returns a widget that plays the frames decoded from `decoder`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frames cannot be decoded or if the image
+    /// contains no frames.
+    pub fn from_decoder<'a>(decoder: impl AnimationDecoder<'a>) -> ImageResult<Self> {
+        let frames = decoder.into_frames().collect_frames()?;
+        Self::new(frames.into_iter().map(AnimationFrame::from))
+    }
+
+    /// Returns a widget that plays the animated PNG contained in `bytes`.
+    ///
+    /// PNG images that are not animated are displayed as a single frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` cannot be decoded as a PNG image.
+    pub fn from_apng(bytes: &[u8]) -> ImageResult<Self> {
+        let decoder = PngDecoder::new(Cursor::new(bytes))?;
+        if decoder.is_apng()? {
+            Self::from_decoder(decoder.apng()?)
+        } else {
+            let image = DynamicImage::from_decoder(decoder)?;
+            Self::new([AnimationFrame::new(image, Duration::ZERO)])
+        }
+    }
+
+    /// Returns a widget that plays the GIF contained in `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` cannot be decoded as a GIF image.
+    #[cfg(feature = "gif")]
+    pub fn from_gif(bytes: &[u8]) -> ImageResult<Self> {
+        Self::from_decoder(image::codecs::gif::GifDecoder::new(Cursor::new(bytes))?)
+    }
+
+    /// Controls playback using `playing` and returns self.
+    ///
+    /// Playback begins when `playing` is `true` and pauses when it is
+    /// `false`. By default, the animation plays immediately.
+    #[must_use]
+    pub fn playing(mut self, playing: impl IntoDynamic<bool>) -> Self {
+        self.playing = playing.into_dynamic();
+        self
+    }
+
+    /// Sets whether playback restarts from the first frame after the last
+    /// frame is displayed and returns self.
+    ///
+    /// By default, animations loop.
+    #[must_use]
+    pub fn looping(mut self, looping: impl IntoValue<bool>) -> Self {
+        self.looping = looping.into_value();
+        self
+    }
+
+    /// Uses `frame` to store the index of the currently displayed frame and
+    /// returns self.
+    ///
+    /// Setting `frame` displays the frame at that index. Indexes beyond the
+    /// last frame display the last frame.
+    #[must_use]
+    pub fn frame(mut self, frame: impl IntoDynamic<usize>) -> Self {
+        self.frame = frame.into_dynamic();
+        self
+    }
+
+    /// Applies the `scaling` strategies and returns self.
+    #[must_use]
+    pub fn scaling(self, scaling: impl IntoValue<ImageScaling>) -> Self {
+        Self {
+            image: WidgetRef::new(Image::new(self.texture.clone()).scaling(scaling)),
+            ..self
+        }
+    }
+
+    /// Returns the number of frames in this animation.
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl WrapperWidget for AnimatedImage {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.image
+    }
+
+    fn redraw_background(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let index = self
+            .frame
+            .get_tracking_redraw(context)
+            .min(self.frames.len() - 1);
+        if index != self.displayed {
+            self.displayed = index;
+            let texture = &self.frames[index].texture;
+            self.texture
+                .map_mut(|mut current| *current = texture.clone());
+        }
+
+        // Images with a single frame have nothing to play.
+        if self.playing.get_tracking_redraw(context) && self.frames.len() > 1 {
+            if !self.playback.is_running() {
+                self.playback = Playback {
+                    frames: self.frames.clone(),
+                    frame: self.frame.clone(),
+                    playing: self.playing.clone(),
+                    looping: self.looping.clone(),
+                    elapsed: Duration::ZERO,
+                }
+                .spawn();
+            }
+        } else {
+            self.playback.clear();
+        }
+    }
+}
+
+/// A single frame of an [`AnimatedImage`].
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    /// The image to display.
+    pub texture: AnyTexture,
+    /// The duration to display this frame for.
+    pub delay: Duration,
+}
+
+impl AnimationFrame {
+    /// Returns a frame that displays `image` for `delay`.
+    #[must_use]
+    pub fn new(image: DynamicImage, delay: Duration) -> Self {
+        Self {
+            texture: AnyTexture::Lazy(LazyTexture::from_image(
+                image,
+                kludgine::wgpu::FilterMode::Linear,
+            )),
+            delay,
+        }
+    }
+}
+
+impl From<Frame> for AnimationFrame {
+    fn from(frame: Frame) -> Self {
+        let delay = Duration::from(frame.delay());
+        Self::new(DynamicImage::ImageRgba8(frame.into_buffer()), delay)
+    }
+}
+
+/// Advances the frame of an [`AnimatedImage`] as time elapses.
+struct Playback {
+    frames: Arc<[AnimationFrame]>,
+    frame: Dynamic<usize>,
+    playing: Dynamic<bool>,
+    looping: Value<bool>,
+    elapsed: Duration,
+}
+
+impl IntoAnimate for Playback {
+    type Animate = Self;
+
+    fn into_animate(self) -> Self::Animate {
+        self
+    }
+}
+
+impl Animate for Playback {
+    fn animate(&mut self, elapsed: Duration) -> ControlFlow<Duration> {
+        self.elapsed += elapsed;
+        let last = self.frames.len() - 1;
+        let mut frame = self.frame.get().min(last);
+        loop {
+            let delay = self.frames[frame].delay.max(MIN_FRAME_DELAY);
+            let Some(remaining) = self.elapsed.checked_sub(delay) else {
+                break;
+            };
+            self.elapsed = remaining;
+            if frame < last {
+                frame += 1;
+            } else if self.looping.get() {
+                frame = 0;
+            } else {
+                self.frame.set(frame);
+                self.playing.set(false);
+                return ControlFlow::Break(self.elapsed);
+            }
+        }
+        self.frame.set(frame);
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+fn test_playback(delays: &[u64], looping: bool) -> Playback {
+    Playback {
+        frames: delays
+            .iter()
+            .map(|delay| {
+                AnimationFrame::new(DynamicImage::new_rgba8(1, 1), Duration::from_millis(*delay))
+            })
+            .collect(),
+        frame: Dynamic::new(0),
+        playing: Dynamic::new(true),
+        looping: Value::Constant(looping),
+        elapsed: Duration::ZERO,
+    }
+}
+
+#[test]
+fn playback_steps_through_frames() {
+    let mut playback = test_playback(&[30, 30, 30], false);
+    assert!(playback.animate(Duration::from_millis(20)).is_continue());
+    assert_eq!(playback.frame.get(), 0);
+    assert!(playback.animate(Duration::from_millis(15)).is_continue());
+    assert_eq!(playback.frame.get(), 1);
+
+    // Reaching the end of an image that does not loop stops playback on the
+    // last frame.
+    assert_eq!(
+        playback.animate(Duration::from_millis(60)),
+        ControlFlow::Break(Duration::from_millis(5))
+    );
+    assert_eq!(playback.frame.get(), 2);
+    assert!(!playback.playing.get());
+}
+
+#[test]
+fn playback_loops() {
+    let mut playback = test_playback(&[30, 30], true);
+    assert!(playback.animate(Duration::from_millis(65)).is_continue());
+    assert_eq!(playback.frame.get(), 0);
+    assert!(playback.animate(Duration::from_millis(25)).is_continue());
+    assert_eq!(playback.frame.get(), 1);
+    assert!(playback.playing.get());
+}
+
+#[test]
+fn playback_limits_frame_delay() {
+    // Frames without a delay are displayed for the minimum delay.
+    let mut playback = test_playback(&[0, 0], true);
+    assert!(playback.animate(MIN_FRAME_DELAY / 2).is_continue());
+    assert_eq!(playback.frame.get(), 0);
+    assert!(playback.animate(MIN_FRAME_DELAY / 2).is_continue());
+    assert_eq!(playback.frame.get(), 1);
+}
@@ -0,0 +1,135 @@
+use figures::units::UPx;
+use figures::{FloatConversion, IntoSigned, Point, Rect, Size};
+
+use crate::context::{AsEventContext, LayoutContext};
+use crate::reactive::value::{IntoValue, Value};
+use crate::widget::{MakeWidget, WidgetRef, WrappedLayout, WrapperWidget};
+use crate::widgets::image::Aspect;
+use crate::ConstraintLimit;
+
+/// A widget that constrains its child to a width-to-height ratio.
+///
+/// The child's size is calculated from the available space:
+///
+/// - [`Aspect::Fit`]: The child is the largest size with the ratio that fits
+///   within the available space. This is the default mode, and is often
+///   referred to as *contain*.
+/// - [`Aspect::Fill`]: The child is the smallest size with the ratio that
+///   covers the available space. The portions of the child that extend beyond
+///   the available space are clipped. This mode is often referred to as
+///   *cover*.
+///
+/// When one dimension of the available space is unbounded, the child is sized
+/// using the bounded dimension. When both are unbounded, the child's measured
+/// width is used.
+#[derive(Debug)]
+pub struct AspectRatio {
+    ratio: Value<f32>,
+    mode: Value<Aspect>,
+    child: WidgetRef,
+}
+
+impl AspectRatio {
+    /// Returns a widget that constrains `child` to `ratio`, the child's width
+    /// divided by its height.
+    #[must_use]
+    pub fn new(ratio: impl IntoValue<f32>, child: impl MakeWidget) -> Self {
+        Self {
+            ratio: ratio.into_value(),
+            mode: Value::Constant(Aspect::Fit),
+            child: WidgetRef::new(child),
+        }
+    }
+
+    /// Sets the mode used to fit the child within the available space and
+    /// returns self.
+    #[must_use]
+    pub fn mode(mut self, mode: impl IntoValue<Aspect>) -> Self {
+        self.mode = mode.into_value();
+        self
+    }
+
+    /// Sizes the child to fit entirely within the available space and returns
+    /// self.
+    #[must_use]
+    pub fn contain(self) -> Self {
+        self.mode(Aspect::Fit)
+    }
+
+    /// Sizes the child to cover the entire available space, clipping the
+    /// portions that extend beyond it, and returns self.
+    #[must_use]
+    pub fn cover(self) -> Self {
+        self.mode(Aspect::Fill)
+    }
+}
+
+impl WrapperWidget for AspectRatio {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn layout_child(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> WrappedLayout {
+        let ratio = self.ratio.get_tracking_invalidate(context);
+        let mode = self.mode.get_tracking_invalidate(context);
+        let child = self.child.mounted(&mut context.as_event_context());
+        if !ratio.is_finite() || ratio <= 0. {
+            let size = context.for_other(&child).layout(available_space);
+            return WrappedLayout::aligned(size, available_space, context);
+        }
+
+        let bounds = available_space.map(ConstraintLimit::max);
+        let target = match (bounds.width < UPx::MAX, bounds.height < UPx::MAX) {
+            (true, true) => {
+                let bounds_ratio = bounds.width.into_float() / bounds.height.into_float();
+                if (bounds_ratio > ratio) == (mode == Aspect::Fit) {
+                    Size::new(
+                        bounds.height.into_float() * ratio,
+                        bounds.height.into_float(),
+                    )
+                } else {
+                    Size::new(bounds.width.into_float(), bounds.width.into_float() / ratio)
+                }
+            }
+            (true, false) => {
+                Size::new(bounds.width.into_float(), bounds.width.into_float() / ratio)
+            }
+            (false, true) => Size::new(
+                bounds.height.into_float() * ratio,
+                bounds.height.into_float(),
+            ),
+            (false, false) => {
+                let measured = context.for_other(&child).layout(available_space);
+                Size::new(
+                    measured.width.into_float(),
+                    measured.width.into_float() / ratio,
+                )
+            }
+        }
+        .map(UPx::from_float);
+
+        context
+            .for_other(&child)
+            .layout(target.map(ConstraintLimit::Fill));
+
+        if mode == Aspect::Fit || (target.width <= bounds.width && target.height <= bounds.height) {
+            WrappedLayout::aligned(target, available_space, context)
+        } else {
+            // Center the child, clipping the edges that extend beyond the
+            // available space.
+            let size = target.min(bounds);
+            let overflow = target.into_signed() - size.into_signed();
+            WrappedLayout {
+                child: Rect::new(
+                    Point::new(-overflow.width / 2, -overflow.height / 2),
+                    target.into_signed(),
+                ),
+                size,
+            }
+        }
+    }
+}
@@ -0,0 +1,638 @@
+//! Widgets for visualizing audio.
+
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use figures::units::{Lp, Px, UPx};
+use figures::{FloatConversion, Point, Rect, ScreenScale, Size, Zero};
+use intentional::Cast;
+use kludgine::app::winit::event::{MouseButton, MouseScrollDelta, TouchPhase};
+use kludgine::app::winit::window::CursorIcon;
+use kludgine::shapes::{PathBuilder, Shape};
+use kludgine::Color;
+
+use crate::context::{EventContext, GraphicsContext, LayoutContext, Trackable};
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, IntoValue, Source, Value};
+use crate::styles::components::HighlightColor;
+use crate::styles::Dimension;
+use crate::widget::{EventHandling, Widget, HANDLED, IGNORED};
+use crate::window::DeviceId;
+use crate::ConstraintLimit;
+
+/// The fewest samples a [`Waveform`] can be zoomed in to display.
+const MINIMUM_VISIBLE_SAMPLES: usize = 16;
+
+/// The zoom factor applied for each line scrolled over a [`Waveform`].
+const ZOOM_PER_LINE: f32 = 1.2;
+
+/// How long the peak of a [`LevelMeter`] is held before it begins to fall.
+const PEAK_HOLD: Duration = Duration::from_millis(1500);
+
+/// How quickly the held peak of a [`LevelMeter`] falls, in decibels per
+/// second.
+const PEAK_FALL_RATE: f32 = 20.;
+
+/// The interval a [`LevelMeter`] redraws at while its peak is falling.
+const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+
+/// Returns the level in decibels relative to full scale of a sample with
+/// `amplitude`.
+///
+/// An amplitude of `1.0` is `0` dB. Silence returns negative infinity.
+#[must_use]
+pub fn decibels(amplitude: f32) -> f32 {
+    20. * amplitude.abs().log10()
+}
+
+/// A widget that displays the minimum and maximum peaks of a buffer of audio
+/// samples.
+///
+/// Samples are expected to be in the range `-1.0..=1.0`. Each column of pixels
+/// displays the range of the samples it covers, which allows buffers
+/// containing millions of samples to be drawn as a single shape.
+///
+/// Scrolling vertically over the waveform zooms around the cursor, and
+/// scrolling horizontally pans the zoomed view. Clicking moves the playhead,
+/// and dragging selects a range of samples.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::audio::Waveform;
+///
+/// let samples = (0..44_100)
+///     .map(|index| (index as f32 / 100.).sin())
+///     .collect::<Vec<_>>();
+/// let playhead = Dynamic::new(0);
+/// let waveform = Waveform::new(samples).playhead(playhead.clone());
+/// ```
+#[derive(Debug)]
+pub struct Waveform {
+    samples: Value<Vec<f32>>,
+    visible: Dynamic<Option<Range<usize>>>,
+    selection: Dynamic<Option<Range<usize>>>,
+    playhead: Dynamic<usize>,
+    displayed: Range<usize>,
+    width: Px,
+    peaks: Vec<(f32, f32)>,
+    cursor: Option<Px>,
+    drag_start: Option<usize>,
+}
+
+impl Waveform {
+    /// Returns a widget that displays `samples`.
+    #[must_use]
+    pub fn new(samples: impl IntoValue<Vec<f32>>) -> Self {
+        Self {
+            samples: samples.into_value(),
+            visible: Dynamic::default(),
+            selection: Dynamic::default(),
+            playhead: Dynamic::default(),
+            displayed: 0..0,
+            width: Px::ZERO,
+            peaks: Vec::new(),
+            cursor: None,
+            drag_start: None,
+        }
+    }
+
+    /// Uses `visible` to control the range of samples that are displayed and
+    /// returns self.
+    ///
+    /// When `visible` contains `None`, all samples are displayed. Zooming the
+    /// waveform updates `visible`.
+    #[must_use]
+    pub fn visible_range(mut self, visible: impl IntoDynamic<Option<Range<usize>>>) -> Self {
+        self.visible = visible.into_dynamic();
+        self
+    }
+
+    /// Uses `selection` to store the selected range of samples and returns
+    /// self.
+    #[must_use]
+    pub fn selection(mut self, selection: impl IntoDynamic<Option<Range<usize>>>) -> Self {
+        self.selection = selection.into_dynamic();
+        self
+    }
+
+    /// Uses `playhead` to store the index of the sample the playhead is at
+    /// and returns self.
+    #[must_use]
+    pub fn playhead(mut self, playhead: impl IntoDynamic<usize>) -> Self {
+        self.playhead = playhead.into_dynamic();
+        self
+    }
+
+    fn sample_to_x(&self, sample: usize) -> Px {
+        let visible = self.displayed.len().max(1);
+        let offset = sample.saturating_sub(self.displayed.start).min(visible);
+        Px::from_float(self.width.into_float() * offset.cast::<f32>() / visible.cast::<f32>())
+    }
+
+    fn x_to_sample(&self, x: Px) -> usize {
+        if self.width <= Px::ZERO {
+            return self.displayed.start;
+        }
+        let fraction = (x.into_float() / self.width.into_float()).clamp(0., 1.);
+        self.displayed.start + (fraction * self.displayed.len().cast::<f32>()).cast::<usize>()
+    }
+}
+
+impl Widget for Waveform {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        self.samples.redraw_when_changed(context);
+        let visible = self.visible.get_tracking_redraw(context);
+        let selection = self.selection.get_tracking_redraw(context);
+        let playhead = self.playhead.get_tracking_redraw(context);
+
+        let size = context.gfx.region().size;
+        self.width = size.width;
+        let columns = usize::try_from(size.width.get()).unwrap_or_default();
+        self.samples.map(|samples| {
+            self.displayed = clamp_range(visible, samples.len());
+            calculate_peaks(&samples[self.displayed.clone()], columns, &mut self.peaks);
+        });
+
+        if let Some(selection) = selection {
+            let start = self.sample_to_x(selection.start);
+            let end = self.sample_to_x(selection.end);
+            if end > start {
+                context.gfx.draw_shape(&Shape::filled_rect(
+                    Rect::new(
+                        Point::new(start, Px::ZERO),
+                        Size::new(end - start, size.height),
+                    ),
+                    context.get(&WaveformSelectionColor),
+                ));
+            }
+        }
+
+        if let Some((&(_, first_max), rest)) = self.peaks.split_first() {
+            let half_height = size.height.into_float() / 2.;
+            let y = |amplitude: f32| {
+                Px::from_float(half_height - amplitude.clamp(-1., 1.) * half_height)
+            };
+            let mut path = PathBuilder::new(Point::new(Px::ZERO, y(first_max)));
+            for (x, &(_, max)) in rest.iter().enumerate() {
+                path = path.line_to(Point::new(Px::new(x.cast::<i32>() + 1), y(max)));
+            }
+            // Ensure silent portions of the waveform are still visible.
+            for (x, &(min, _)) in self.peaks.iter().enumerate().rev() {
+                path = path.line_to(Point::new(Px::new(x.cast::<i32>()), y(min) + 1));
+            }
+            context
+                .gfx
+                .draw_shape(&path.close().fill(context.get(&WaveformColor)));
+        }
+
+        if self.displayed.contains(&playhead) {
+            let width = Lp::points(1).into_px(context.gfx.scale()).max(Px::new(1));
+            context.gfx.draw_shape(&Shape::filled_rect(
+                Rect::new(
+                    Point::new(self.sample_to_x(playhead) - width / 2, Px::ZERO),
+                    Size::new(width, size.height),
+                ),
+                context.get(&PlayheadColor),
+            ));
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        let height = context.get(&WaveformHeight).into_upx(context.gfx.scale());
+        Size::new(
+            available_space.width.max(),
+            available_space.height.fill_or_fit(height),
+        )
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn hover(
+        &mut self,
+        location: Point<Px>,
+        _context: &mut EventContext<'_>,
+    ) -> Option<CursorIcon> {
+        self.cursor = Some(location.x);
+        Some(CursorIcon::Text)
+    }
+
+    fn unhover(&mut self, _context: &mut EventContext<'_>) {
+        self.cursor = None;
+    }
+
+    fn mouse_down(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        _context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button != MouseButton::Left {
+            return IGNORED;
+        }
+        let sample = self.x_to_sample(location.x);
+        self.drag_start = Some(sample);
+        self.playhead.set(sample);
+        self.selection.set(None);
+        HANDLED
+    }
+
+    fn mouse_drag(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        _context: &mut EventContext<'_>,
+    ) {
+        let Some(start) = self.drag_start else {
+            return;
+        };
+        let end = self.x_to_sample(location.x);
+        self.selection
+            .set((start != end).then(|| start.min(end)..start.max(end)));
+    }
+
+    fn mouse_up(
+        &mut self,
+        _location: Option<Point<Px>>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        _context: &mut EventContext<'_>,
+    ) {
+        self.drag_start = None;
+    }
+
+    fn mouse_wheel(
+        &mut self,
+        _device_id: DeviceId,
+        delta: MouseScrollDelta,
+        _phase: TouchPhase,
+        _context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        let total = self.samples.map(Vec::len);
+        if total == 0 || self.width <= Px::ZERO {
+            return IGNORED;
+        }
+        let (pan, zoom) = match delta {
+            MouseScrollDelta::LineDelta(x, y) => (x * self.width.into_float() / 10., y),
+            MouseScrollDelta::PixelDelta(delta) => (delta.x.cast(), delta.y.cast::<f32>() / 50.),
+        };
+
+        let visible = self.displayed.len().cast::<f32>();
+        let new_len = (visible / ZOOM_PER_LINE.powf(zoom))
+            .clamp(MINIMUM_VISIBLE_SAMPLES.min(total).cast(), total.cast());
+        // Keep the sample beneath the cursor in the same location.
+        let anchor_x = self.cursor.unwrap_or(self.width / 2);
+        let anchor_fraction = (anchor_x.into_float() / self.width.into_float()).clamp(0., 1.);
+        let anchor = self.displayed.start.cast::<f32>() + visible * anchor_fraction;
+        let samples_per_px = new_len / self.width.into_float();
+        let start = (anchor - new_len * anchor_fraction - pan * samples_per_px)
+            .clamp(0., total.cast::<f32>() - new_len);
+
+        let start = start.cast::<usize>();
+        let end = (start + new_len.cast::<usize>()).min(total);
+        let new_visible = if start == 0 && end == total {
+            None
+        } else {
+            Some(start..end)
+        };
+        if new_visible.as_ref().unwrap_or(&(0..total)) == &self.displayed {
+            IGNORED
+        } else {
+            self.visible.set(new_visible);
+            HANDLED
+        }
+    }
+}
+
+/// Returns `range` limited to `len`, or the full range if `range` is `None`.
+fn clamp_range(range: Option<Range<usize>>, len: usize) -> Range<usize> {
+    match range {
+        Some(range) => range.start.min(len)..range.end.clamp(range.start.min(len), len),
+        None => 0..len,
+    }
+}
+
+/// Fills `peaks` with the minimum and maximum sample for each of `columns`
+/// equally sized portions of `samples`.
+fn calculate_peaks(samples: &[f32], columns: usize, peaks: &mut Vec<(f32, f32)>) {
+    peaks.clear();
+    if samples.is_empty() || columns == 0 {
+        return;
+    }
+
+    let per_column = samples.len().cast::<f32>() / columns.cast::<f32>();
+    for column in 0..columns {
+        let start = (column.cast::<f32>() * per_column)
+            .cast::<usize>()
+            .min(samples.len() - 1);
+        let end = ((column + 1).cast::<f32>() * per_column)
+            .ceil()
+            .cast::<usize>()
+            .clamp(start + 1, samples.len());
+        let peak = samples[start..end]
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), sample| {
+                (min.min(*sample), max.max(*sample))
+            });
+        peaks.push(peak);
+    }
+}
+
+/// A widget that displays an audio level in decibels.
+///
+/// The meter displays the level using three colors: the
+/// [normal color](LevelMeterColor) below the [warning level](Self::warning),
+/// the [warning color](LevelMeterWarningColor) up to `0` dB, and the
+/// [clip color](LevelMeterClipColor) above `0` dB. The highest recent level is
+/// held for a moment before falling. When the level reaches `0` dB, a clip
+/// indicator is lit until the meter is clicked.
+///
+/// Updating the level only redraws the meter, and drawing the meter does not
+/// allocate, allowing levels to be updated every frame.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::audio::{decibels, LevelMeter};
+///
+/// let level = Dynamic::new(decibels(0.5));
+/// let meter = LevelMeter::new(level.clone()).minimum(-48.);
+/// ```
+#[derive(Debug)]
+pub struct LevelMeter {
+    level: Value<f32>,
+    minimum: f32,
+    warning: f32,
+    vertical: bool,
+    peak: f32,
+    peak_at: Instant,
+    last_redraw: Option<Instant>,
+    clipped: bool,
+}
+
+impl LevelMeter {
+    /// Returns a vertical meter that displays `level`, in decibels relative
+    /// to full scale.
+    #[must_use]
+    pub fn new(level: impl IntoValue<f32>) -> Self {
+        Self {
+            level: level.into_value(),
+            minimum: -60.,
+            warning: -12.,
+            vertical: true,
+            peak: f32::NEG_INFINITY,
+            peak_at: Instant::now(),
+            last_redraw: None,
+            clipped: false,
+        }
+    }
+
+    /// Displays the meter horizontally and returns self.
+    #[must_use]
+    pub fn horizontal(mut self) -> Self {
+        self.vertical = false;
+        self
+    }
+
+    /// Sets the lowest level displayed, in decibels, and returns self.
+    ///
+    /// The default minimum is `-60` dB.
+    #[must_use]
+    pub fn minimum(mut self, decibels: f32) -> Self {
+        self.minimum = decibels;
+        self
+    }
+
+    /// Sets the level at which the warning color is displayed, in decibels,
+    /// and returns self.
+    ///
+    /// The default warning level is `-12` dB.
+    #[must_use]
+    pub fn warning(mut self, decibels: f32) -> Self {
+        self.warning = decibels;
+        self
+    }
+
+    /// Returns the fraction of the meter that `decibels` fills.
+    fn fraction(&self, decibels: f32) -> f32 {
+        let fraction = (decibels - self.minimum) / -self.minimum;
+        if fraction.is_nan() {
+            0.
+        } else {
+            fraction.clamp(0., 1.)
+        }
+    }
+
+    fn update_peak(&mut self, level: f32, now: Instant) {
+        let elapsed = self
+            .last_redraw
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        self.last_redraw = Some(now);
+        if level >= self.peak {
+            self.peak = level;
+            self.peak_at = now;
+        } else if now.saturating_duration_since(self.peak_at) > PEAK_HOLD {
+            self.peak = (self.peak - PEAK_FALL_RATE * elapsed.as_secs_f32()).max(level);
+        }
+        if level >= 0. {
+            self.clipped = true;
+        }
+    }
+
+    /// Returns the rectangle of the portion of `track` between `start` and
+    /// `end`, which are fractions of the meter.
+    fn segment(&self, track: Rect<Px>, start: f32, end: f32) -> Rect<Px> {
+        if self.vertical {
+            let top = track.origin.y + track.size.height * (1. - end);
+            let bottom = track.origin.y + track.size.height * (1. - start);
+            Rect::new(
+                Point::new(track.origin.x, top),
+                Size::new(track.size.width, bottom - top),
+            )
+        } else {
+            let left = track.origin.x + track.size.width * start;
+            let right = track.origin.x + track.size.width * end;
+            Rect::new(
+                Point::new(left, track.origin.y),
+                Size::new(right - left, track.size.height),
+            )
+        }
+    }
+}
+
+impl Widget for LevelMeter {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let level = self.level.get_tracking_redraw(context);
+        self.update_peak(level, Instant::now());
+
+        let size = context.gfx.region().size;
+        let thickness = if self.vertical {
+            size.width
+        } else {
+            size.height
+        };
+        let gap = thickness / 2;
+        // The clip indicator is placed at the loud end of the meter.
+        let (clip, track) = if self.vertical {
+            (
+                Rect::new(Point::ZERO, Size::squared(thickness)),
+                Rect::new(
+                    Point::new(Px::ZERO, thickness + gap),
+                    Size::new(thickness, size.height - thickness - gap),
+                ),
+            )
+        } else {
+            (
+                Rect::new(
+                    Point::new(size.width - thickness, Px::ZERO),
+                    Size::squared(thickness),
+                ),
+                Rect::new(
+                    Point::ZERO,
+                    Size::new(size.width - thickness - gap, thickness),
+                ),
+            )
+        };
+
+        let track_color = context.get(&LevelMeterTrackColor);
+        let clip_color = context.get(&LevelMeterClipColor);
+        context.gfx.draw_shape(&Shape::filled_rect(
+            clip,
+            if self.clipped {
+                clip_color
+            } else {
+                track_color
+            },
+        ));
+        context
+            .gfx
+            .draw_shape(&Shape::filled_rect(track, track_color));
+
+        let warning = self.fraction(self.warning);
+        let filled = self.fraction(level);
+        let normal_color = context.get(&LevelMeterColor);
+        let warning_color = context.get(&LevelMeterWarningColor);
+        for (start, end, color) in [
+            (0., filled.min(warning), normal_color),
+            (warning, filled, warning_color),
+        ] {
+            if end > start {
+                context
+                    .gfx
+                    .draw_shape(&Shape::filled_rect(self.segment(track, start, end), color));
+            }
+        }
+
+        let peak = self.fraction(self.peak);
+        if peak > 0. {
+            let peak_size = Lp::points(2).into_px(context.gfx.scale()).max(Px::new(1));
+            let length = if self.vertical {
+                track.size.height
+            } else {
+                track.size.width
+            };
+            let peak_start = (peak - peak_size.into_float() / length.into_float().max(1.)).max(0.);
+            let color = if self.peak >= 0. {
+                clip_color
+            } else if peak > warning {
+                warning_color
+            } else {
+                normal_color
+            };
+            context.gfx.draw_shape(&Shape::filled_rect(
+                self.segment(track, peak_start, peak),
+                color,
+            ));
+        }
+
+        if self.peak > level.max(self.minimum) {
+            context.redraw_in(FRAME_INTERVAL);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        let scale = context.gfx.scale();
+        let thickness = context.get(&LevelMeterSize).into_upx(scale);
+        let length = Lp::inches(1).into_upx(scale);
+        if self.vertical {
+            Size::new(
+                available_space.width.fit_measured(thickness).min(thickness),
+                available_space.height.fill_or_fit(length),
+            )
+        } else {
+            Size::new(
+                available_space.width.fill_or_fit(length),
+                available_space
+                    .height
+                    .fit_measured(thickness)
+                    .min(thickness),
+            )
+        }
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn mouse_down(
+        &mut self,
+        _location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button == MouseButton::Left && self.clipped {
+            self.clipped = false;
+            context.set_needs_redraw();
+            HANDLED
+        } else {
+            IGNORED
+        }
+    }
+}
+
+define_components! {
+    Audio {
+        /// The color of the samples drawn by a [`Waveform`].
+        WaveformColor(Color, "waveform_color", .primary.color)
+        /// The color of the selected range of a [`Waveform`].
+        WaveformSelectionColor(Color, "waveform_selection_color", @HighlightColor)
+        /// The color of the playhead of a [`Waveform`].
+        PlayheadColor(Color, "playhead_color", .tertiary.color)
+        /// The height a [`Waveform`] requests when it is not filling its
+        /// available space.
+        WaveformHeight(Dimension, "waveform_height", Dimension::Lp(Lp::inches(1)))
+        /// The thickness of a [`LevelMeter`].
+        LevelMeterSize(Dimension, "level_meter_size", Dimension::Lp(Lp::points(8)))
+        /// The color of the unfilled portion of a [`LevelMeter`].
+        LevelMeterTrackColor(Color, "level_meter_track_color", .surface.highest_container)
+        /// The color of a [`LevelMeter`]'s level below its warning level.
+        LevelMeterColor(Color, "level_meter_color", .primary.color)
+        /// The color of a [`LevelMeter`]'s level above its warning level.
+        LevelMeterWarningColor(Color, "level_meter_warning_color", .tertiary.color)
+        /// The color of a [`LevelMeter`]'s clip indicator and peaks above `0`
+        /// dB.
+        LevelMeterClipColor(Color, "level_meter_clip_color", .error.color)
+    }
+}
+
+#[test]
+fn peaks() {
+    let mut peaks = Vec::new();
+    calculate_peaks(&[0., 0.5, -0.5, 1., -1., 0.25], 3, &mut peaks);
+    assert_eq!(peaks, [(0., 0.5), (-0.5, 1.), (-1., 0.25)]);
+
+    calculate_peaks(&[0.5, -0.5], 4, &mut peaks);
+    assert_eq!(peaks.len(), 4);
+    assert_eq!(clamp_range(Some(5..20), 10), 5..10);
+    assert_eq!(clamp_range(None, 10), 0..10);
+}
@@ -21,6 +21,7 @@ use crate::styles::components::{
     OutlineColor, OutlineWidth, SurfaceColor, TextColor,
 };
 use crate::styles::{ColorExt, Styles};
+use crate::telemetry::{self, ActionSource, TelemetryEvent};
 use crate::widget::{
     EventHandling, MakeWidget, Notify, SharedCallback, Widget, WidgetRef, HANDLED,
 };
@@ -183,6 +184,9 @@ impl Button {
     fn invoke_on_click(&mut self, button: Option<ButtonClick>, context: &WidgetContext<'_>) {
         if context.enabled() {
             if let Some(on_click) = self.on_click.as_mut() {
+                telemetry::emit(|| {
+                    TelemetryEvent::ActionInvoked(ActionSource::Button(context.widget().id()))
+                });
                 on_click.notify(button);
             }
         }
@@ -0,0 +1,1217 @@
+//! A calendar that displays events by month, by week, or as an agenda.
+
+use std::cmp::Reverse;
+
+use figures::units::{Lp, Px, UPx};
+use figures::{FloatConversion, Point, Rect, ScreenScale, Size, Zero};
+use intentional::Cast;
+use jiff::civil::{Date, DateTime, Time, Weekday};
+use jiff::ToSpan;
+use kludgine::app::winit::event::MouseButton;
+use kludgine::app::winit::window::CursorIcon;
+use kludgine::shapes::Shape;
+use kludgine::text::{Text, TextOrigin};
+use kludgine::{Color, DrawableExt};
+
+use crate::animation::ZeroToOne;
+use crate::context::{EventContext, GraphicsContext, LayoutContext, Trackable};
+use crate::reactive::value::{
+    Destination, Dynamic, IntoDynamic, IntoValue, MapEach, Source, Value,
+};
+use crate::styles::components::{TextColor, TextColorVariant};
+use crate::widget::{
+    Callback, EventHandling, MakeWidget, MakeWidgetWithTag, Widget, WidgetInstance, WidgetTag,
+    HANDLED, IGNORED,
+};
+use crate::widgets::label::Displayable;
+use crate::window::DeviceId;
+use crate::ConstraintLimit;
+
+/// The number of days displayed by [`CalendarMode::Agenda`].
+const AGENDA_DAYS: i64 = 14;
+/// The number of minutes that dragging timed events snaps to.
+const SNAP_MINUTES: i64 = 15;
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// An event displayed in a [`Calendar`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// The title of the event.
+    pub title: String,
+    /// The moment the event begins.
+    pub start: DateTime,
+    /// The moment the event ends. This moment is not part of the event.
+    pub end: DateTime,
+    /// If true, this event is displayed without times.
+    pub all_day: bool,
+    /// The color to display this event with. When `None`,
+    /// [`CalendarEventColor`] is used.
+    pub color: Option<Color>,
+}
+
+impl CalendarEvent {
+    /// Returns an event from `start` up to `end`.
+    pub fn timed(title: impl Into<String>, start: DateTime, end: DateTime) -> Self {
+        Self {
+            title: title.into(),
+            start,
+            end,
+            all_day: false,
+            color: None,
+        }
+    }
+
+    /// Returns an event lasting the entire day from `first` through `last`.
+    pub fn all_day(title: impl Into<String>, first: Date, last: Date) -> Self {
+        Self {
+            title: title.into(),
+            start: first.to_datetime(Time::midnight()),
+            end: last.saturating_add(1.days()).to_datetime(Time::midnight()),
+            all_day: true,
+            color: None,
+        }
+    }
+
+    /// Sets the color of this event and returns self.
+    #[must_use]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Returns the first day this event occurs on.
+    #[must_use]
+    pub fn first_day(&self) -> Date {
+        self.start.date()
+    }
+
+    /// Returns the last day this event occurs on.
+    #[must_use]
+    pub fn last_day(&self) -> Date {
+        if self.end > self.start && self.end.time() == Time::midnight() {
+            self.end.date().saturating_sub(1.days())
+        } else {
+            self.end.date().max(self.start.date())
+        }
+    }
+
+    fn occurs_on(&self, first: Date, last: Date) -> bool {
+        self.first_day() <= last && self.last_day() >= first
+    }
+
+    /// Returns true if this event is displayed as a bar across days rather
+    /// than at its times.
+    fn is_banner(&self) -> bool {
+        self.all_day || self.first_day() != self.last_day()
+    }
+
+    fn label(&self) -> String {
+        if self.is_banner() {
+            self.title.clone()
+        } else {
+            format!("{} {}", self.start.strftime("%H:%M"), self.title)
+        }
+    }
+}
+
+/// The way a [`Calendar`] displays its events.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum CalendarMode {
+    /// A grid of the weeks containing the current month.
+    #[default]
+    Month,
+    /// A column per day of the current week, with timed events positioned by
+    /// their time of day.
+    Week,
+    /// A list of the events occurring in the two weeks following the current
+    /// date.
+    Agenda,
+}
+
+impl CalendarMode {
+    /// Returns the date that navigating forward from `date` displays.
+    #[must_use]
+    pub fn next(self, date: Date) -> Date {
+        match self {
+            CalendarMode::Month => date.saturating_add(1.months()),
+            CalendarMode::Week => date.saturating_add(1.weeks()),
+            CalendarMode::Agenda => date.saturating_add(AGENDA_DAYS.days()),
+        }
+    }
+
+    /// Returns the date that navigating backward from `date` displays.
+    #[must_use]
+    pub fn previous(self, date: Date) -> Date {
+        match self {
+            CalendarMode::Month => date.saturating_sub(1.months()),
+            CalendarMode::Week => date.saturating_sub(1.weeks()),
+            CalendarMode::Agenda => date.saturating_sub(AGENDA_DAYS.days()),
+        }
+    }
+
+    fn title(self, date: Date, week_start: Weekday) -> String {
+        match self {
+            CalendarMode::Month => date.strftime("%B %Y").to_string(),
+            CalendarMode::Week => {
+                let first = start_of_week(date, week_start);
+                let last = first.saturating_add(6.days());
+                format!(
+                    "{} – {}",
+                    first.strftime("%b %-d"),
+                    last.strftime("%b %-d, %Y")
+                )
+            }
+            CalendarMode::Agenda => date.strftime("%B %-d, %Y").to_string(),
+        }
+    }
+}
+
+/// A change to a [`CalendarEvent`] requested by dragging it within a
+/// [`Calendar`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct EventChange {
+    /// The index of the event in the list of events provided to the calendar.
+    pub index: usize,
+    /// The new start of the event.
+    pub start: DateTime,
+    /// The new end of the event.
+    pub end: DateTime,
+}
+
+/// A widget that displays [`CalendarEvent`]s in a month grid, a week of
+/// days, or an agenda.
+///
+/// The calendar includes a toolbar to navigate to today, the previous or next
+/// period, and to change the [`CalendarMode`]. The displayed date and mode can
+/// be bound to [`Dynamic`]s using [`Calendar::date`] and [`Calendar::mode`].
+///
+/// Events can be dragged to move them, and timed events in the week view can
+/// be resized by dragging their bottom edge. The calendar never modifies its
+/// events itself: instead, [`Calendar::on_move`] and [`Calendar::on_resize`]
+/// are invoked with the requested [`EventChange`].
+///
+/// ```rust
+/// use cushy::reactive::value::{Destination, Dynamic};
+/// use cushy::widgets::calendar::{Calendar, CalendarEvent};
+/// use jiff::civil::date;
+///
+/// let events = Dynamic::new(vec![
+///     CalendarEvent::timed(
+///         "Standup",
+///         date(2024, 10, 14).at(9, 0, 0, 0),
+///         date(2024, 10, 14).at(9, 15, 0, 0),
+///     ),
+///     CalendarEvent::all_day("Conference", date(2024, 10, 16), date(2024, 10, 18)),
+/// ]);
+/// let calendar = Calendar::new(events.clone()).on_move(move |change| {
+///     events.map_mut(|mut events| {
+///         events[change.index].start = change.start;
+///         events[change.index].end = change.end;
+///     });
+/// });
+/// ```
+pub struct Calendar {
+    events: Value<Vec<CalendarEvent>>,
+    date: Dynamic<Date>,
+    mode: Dynamic<CalendarMode>,
+    week_start: Weekday,
+    on_move: Option<Callback<EventChange>>,
+    on_resize: Option<Callback<EventChange>>,
+}
+
+impl Calendar {
+    /// Returns a calendar displaying `events` in the current month.
+    #[must_use]
+    pub fn new(events: impl IntoValue<Vec<CalendarEvent>>) -> Self {
+        Self {
+            events: events.into_value(),
+            date: Dynamic::new(today()),
+            mode: Dynamic::default(),
+            week_start: Weekday::Sunday,
+            on_move: None,
+            on_resize: None,
+        }
+    }
+
+    /// Sets the date whose period is displayed and returns self.
+    ///
+    /// The calendar's navigation buttons update this value.
+    #[must_use]
+    pub fn date(mut self, date: impl IntoDynamic<Date>) -> Self {
+        self.date = date.into_dynamic();
+        self
+    }
+
+    /// Sets the way events are displayed and returns self.
+    ///
+    /// The calendar's mode buttons update this value.
+    #[must_use]
+    pub fn mode(mut self, mode: impl IntoDynamic<CalendarMode>) -> Self {
+        self.mode = mode.into_dynamic();
+        self
+    }
+
+    /// Sets the first day of each week and returns self.
+    ///
+    /// By default, weeks start on Sunday.
+    #[must_use]
+    pub fn week_starts_on(mut self, weekday: Weekday) -> Self {
+        self.week_start = weekday;
+        self
+    }
+
+    /// Invokes `on_move` when an event is dragged to a new time or day.
+    #[must_use]
+    pub fn on_move<F>(mut self, on_move: F) -> Self
+    where
+        F: FnMut(EventChange) + Send + 'static,
+    {
+        self.on_move = Some(Callback::new(on_move));
+        self
+    }
+
+    /// Invokes `on_resize` when the end of an event is dragged to a new time.
+    #[must_use]
+    pub fn on_resize<F>(mut self, on_resize: F) -> Self
+    where
+        F: FnMut(EventChange) + Send + 'static,
+    {
+        self.on_resize = Some(Callback::new(on_resize));
+        self
+    }
+}
+
+impl MakeWidgetWithTag for Calendar {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let week_start = self.week_start;
+        let title = (&self.date, &self.mode)
+            .map_each(move |(date, mode)| mode.title(*date, week_start))
+            .into_label();
+        let today_button = "Today".into_button().on_click({
+            let date = self.date.clone();
+            move |_| date.set(today())
+        });
+        let previous = "<".into_button().on_click({
+            let date = self.date.clone();
+            let mode = self.mode.clone();
+            move |_| date.set(mode.get().previous(date.get()))
+        });
+        let next = ">".into_button().on_click({
+            let date = self.date.clone();
+            let mode = self.mode.clone();
+            move |_| date.set(mode.get().next(date.get()))
+        });
+        let toolbar = today_button
+            .and(previous)
+            .and(next)
+            .and(title.expand())
+            .and(self.mode.new_select(CalendarMode::Month, "Month"))
+            .and(self.mode.new_select(CalendarMode::Week, "Week"))
+            .and(self.mode.new_select(CalendarMode::Agenda, "Agenda"))
+            .into_columns();
+
+        toolbar
+            .and(
+                CalendarView {
+                    events: self.events,
+                    date: self.date,
+                    mode: self.mode,
+                    week_start,
+                    on_move: self.on_move,
+                    on_resize: self.on_resize,
+                    chips: Vec::new(),
+                    geometry: None,
+                    drag: None,
+                }
+                .expand(),
+            )
+            .into_rows()
+            .make_with_tag(tag)
+    }
+}
+
+/// The widget that draws the events of a [`Calendar`].
+struct CalendarView {
+    events: Value<Vec<CalendarEvent>>,
+    date: Dynamic<Date>,
+    mode: Dynamic<CalendarMode>,
+    week_start: Weekday,
+    on_move: Option<Callback<EventChange>>,
+    on_resize: Option<Callback<EventChange>>,
+    chips: Vec<Chip>,
+    geometry: Option<Geometry>,
+    drag: Option<Drag>,
+}
+
+impl std::fmt::Debug for CalendarView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CalendarView")
+            .field("events", &self.events)
+            .field("date", &self.date)
+            .field("mode", &self.mode)
+            .field("week_start", &self.week_start)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A drawn event, remembered for hit testing.
+#[derive(Debug, Clone, Copy)]
+struct Chip {
+    index: usize,
+    rect: Rect<Px>,
+    /// True if this chip is positioned by its time in the week view, and can
+    /// be resized.
+    timed: bool,
+}
+
+/// The measurements used to convert drag distances into days and minutes.
+#[derive(Debug, Clone, Copy)]
+struct Geometry {
+    mode: CalendarMode,
+    day_width: Px,
+    /// The height of each week in the month view, or of each hour in the week
+    /// view.
+    row_height: Px,
+    resize_handle: Px,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    chip: Chip,
+    start: Point<Px>,
+    current: Point<Px>,
+    resizing: bool,
+}
+
+struct Style {
+    text: Color,
+    dimmed: Color,
+    grid: Color,
+    today: Color,
+    event: Color,
+    event_text: Color,
+    gap: Px,
+    chip_height: Px,
+    line_height: Px,
+}
+
+impl CalendarView {
+    fn style(context: &mut GraphicsContext<'_, '_, '_, '_>) -> Style {
+        let text = context.get(&TextColor);
+        let gap = Lp::points(2).into_px(context.gfx.scale());
+        let line_height = context.gfx.measure_text(Text::new("0", text)).size.height;
+        Style {
+            text,
+            dimmed: context.get(&TextColorVariant),
+            grid: context.get(&CalendarGridColor),
+            today: context.get(&CalendarTodayColor),
+            event: context.get(&CalendarEventColor),
+            event_text: context.get(&CalendarEventTextColor),
+            gap,
+            chip_height: line_height + gap * 2,
+            line_height,
+        }
+    }
+
+    fn draw_chip(
+        &mut self,
+        chip: Chip,
+        event: &CalendarEvent,
+        style: &Style,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        draw_chip(
+            chip.rect,
+            event.color.unwrap_or(style.event),
+            style.event_text,
+            &event.label(),
+            style.gap,
+            context,
+        );
+        self.chips.push(chip);
+    }
+
+    fn draw_weekday_header(
+        &self,
+        first: Date,
+        left: Px,
+        day_width: Px,
+        format: &str,
+        style: &Style,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) -> Px {
+        let today = today();
+        for column in 0..7 {
+            let day = first.saturating_add(i64::from(column).days());
+            let color = if day == today {
+                style.today
+            } else {
+                style.dimmed
+            };
+            let name = context
+                .gfx
+                .measure_text(Text::new(&day.strftime(format).to_string(), color));
+            let name_width = name.size.width;
+            context.gfx.draw_measured_text(
+                name.translate_by(Point::new(
+                    left + day_width * column + (day_width - name_width) / 2,
+                    style.gap,
+                )),
+                TextOrigin::TopLeft,
+            );
+        }
+        style.line_height + style.gap * 2
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn redraw_month(
+        &mut self,
+        date: Date,
+        events: &[CalendarEvent],
+        style: &Style,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        let size = context.gfx.region().size;
+        let grid_start = start_of_week(date.first_of_month(), self.week_start);
+        let day_width = size.width / 7;
+        let header =
+            self.draw_weekday_header(grid_start, Px::ZERO, day_width, "%a", style, context);
+        let row_height = (size.height - header) / 6;
+        if row_height <= 0 || day_width <= 0 {
+            return;
+        }
+        self.geometry = Some(Geometry {
+            mode: CalendarMode::Month,
+            day_width,
+            row_height,
+            resize_handle: Px::ZERO,
+        });
+
+        let today = today();
+        for row in 0..6 {
+            let row_top = header + row_height * row;
+            context.gfx.draw_shape(&Shape::filled_rect(
+                Rect::new(
+                    Point::new(Px::ZERO, row_top),
+                    Size::new(size.width, Px::new(1)),
+                ),
+                style.grid,
+            ));
+            for column in 0..7 {
+                let day = grid_start.saturating_add(i64::from(row * 7 + column).days());
+                let left = day_width * column;
+                if column > 0 {
+                    context.gfx.draw_shape(&Shape::filled_rect(
+                        Rect::new(Point::new(left, row_top), Size::new(Px::new(1), row_height)),
+                        style.grid,
+                    ));
+                }
+                let color = if day == today {
+                    style.today
+                } else if day.month() == date.month() {
+                    style.text
+                } else {
+                    style.dimmed
+                };
+                let number = context
+                    .gfx
+                    .measure_text(Text::new(&day.day().to_string(), color));
+                context.gfx.draw_measured_text(
+                    number.translate_by(Point::new(left + style.gap * 2, row_top + style.gap)),
+                    TextOrigin::TopLeft,
+                );
+                if day == today {
+                    context.gfx.draw_shape(&Shape::filled_rect(
+                        Rect::new(Point::new(left, row_top), Size::new(day_width, style.gap)),
+                        style.today,
+                    ));
+                }
+            }
+
+            let week_first = grid_start.saturating_add(i64::from(row * 7).days());
+            let week_last = week_first.saturating_add(6.days());
+            let mut entries = events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| event.occurs_on(week_first, week_last))
+                .map(|(index, event)| {
+                    let first = days_between(week_first, event.first_day()).clamp(0, 6);
+                    let last = days_between(week_first, event.last_day()).clamp(0, 6);
+                    (index, first.cast::<usize>(), last.cast::<usize>())
+                })
+                .collect::<Vec<_>>();
+            entries.sort_by_key(|&(index, first, last)| {
+                let event = &events[index];
+                (first, Reverse(last - first), !event.all_day, event.start)
+            });
+            let lanes = assign_lanes(
+                &entries
+                    .iter()
+                    .map(|&(_, first, last)| (first, last))
+                    .collect::<Vec<_>>(),
+            );
+
+            let chips_top = row_top + style.line_height + style.gap * 2;
+            let available = usize::try_from(
+                ((row_height - style.line_height - style.gap * 3) / (style.chip_height + 1)).get(),
+            )
+            .unwrap_or_default();
+            let lane_count = lanes.iter().max().map_or(0, |lane| lane + 1);
+            let displayed_lanes = if lane_count > available {
+                // Reserve a line for the number of hidden events.
+                available.saturating_sub(1)
+            } else {
+                available
+            };
+            let mut hidden = [0_usize; 7];
+            for (&(index, first, last), lane) in entries.iter().zip(lanes) {
+                if lane >= displayed_lanes {
+                    for count in &mut hidden[first..=last] {
+                        *count += 1;
+                    }
+                    continue;
+                }
+                let chip = Chip {
+                    index,
+                    rect: Rect::new(
+                        Point::new(
+                            day_width * first.cast::<i32>() + style.gap,
+                            chips_top + (style.chip_height + 1) * lane.cast::<i32>(),
+                        ),
+                        Size::new(
+                            day_width * (last - first + 1).cast::<i32>() - style.gap * 2,
+                            style.chip_height,
+                        ),
+                    ),
+                    timed: false,
+                };
+                self.draw_chip(chip, &events[index], style, context);
+            }
+            for (column, hidden) in hidden.into_iter().enumerate() {
+                if hidden == 0 {
+                    continue;
+                }
+                let more = context
+                    .gfx
+                    .measure_text(Text::new(&format!("+{hidden} more"), style.dimmed));
+                context.gfx.draw_measured_text(
+                    more.translate_by(Point::new(
+                        day_width * column.cast::<i32>() + style.gap * 2,
+                        chips_top + (style.chip_height + 1) * displayed_lanes.cast::<i32>(),
+                    )),
+                    TextOrigin::TopLeft,
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn redraw_week(
+        &mut self,
+        date: Date,
+        events: &[CalendarEvent],
+        style: &Style,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        let size = context.gfx.region().size;
+        let week_first = start_of_week(date, self.week_start);
+        let week_last = week_first.saturating_add(6.days());
+        let gutter = context
+            .gfx
+            .measure_text(Text::new("00:00", style.dimmed))
+            .size
+            .width
+            + style.gap * 4;
+        let day_width = (size.width - gutter) / 7;
+        if day_width <= 0 {
+            return;
+        }
+        let header =
+            self.draw_weekday_header(week_first, gutter, day_width, "%a %-d", style, context);
+
+        // Banners for all-day and multi-day events.
+        let mut banners = events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| event.is_banner() && event.occurs_on(week_first, week_last))
+            .map(|(index, event)| {
+                let first = days_between(week_first, event.first_day()).clamp(0, 6);
+                let last = days_between(week_first, event.last_day()).clamp(0, 6);
+                (index, first.cast::<usize>(), last.cast::<usize>())
+            })
+            .collect::<Vec<_>>();
+        banners.sort_by_key(|&(index, first, last)| {
+            (first, Reverse(last - first), events[index].start)
+        });
+        let lanes = assign_lanes(
+            &banners
+                .iter()
+                .map(|&(_, first, last)| (first, last))
+                .collect::<Vec<_>>(),
+        );
+        let lane_count = lanes.iter().max().map_or(1, |lane| lane + 1);
+        for (&(index, first, last), lane) in banners.iter().zip(lanes) {
+            let chip = Chip {
+                index,
+                rect: Rect::new(
+                    Point::new(
+                        gutter + day_width * first.cast::<i32>() + style.gap,
+                        header + (style.chip_height + 1) * lane.cast::<i32>(),
+                    ),
+                    Size::new(
+                        day_width * (last - first + 1).cast::<i32>() - style.gap * 2,
+                        style.chip_height,
+                    ),
+                ),
+                timed: false,
+            };
+            self.draw_chip(chip, &events[index], style, context);
+        }
+
+        let grid_top = header + (style.chip_height + 1) * lane_count.cast::<i32>() + style.gap * 2;
+        let hour_height = (size.height - grid_top) / 24;
+        if hour_height <= 0 {
+            return;
+        }
+        self.geometry = Some(Geometry {
+            mode: CalendarMode::Week,
+            day_width,
+            row_height: hour_height,
+            resize_handle: Lp::points(6).into_px(context.gfx.scale()),
+        });
+
+        // Hour lines and labels, skipping labels that would overlap.
+        let label_every = (style.line_height.get() + hour_height.get() - 1) / hour_height.get();
+        for hour in 0..24 {
+            let y = grid_top + hour_height * hour;
+            context.gfx.draw_shape(&Shape::filled_rect(
+                Rect::new(Point::new(gutter, y), Size::new(day_width * 7, Px::new(1))),
+                style.grid,
+            ));
+            if hour % label_every == 0 {
+                let label = context
+                    .gfx
+                    .measure_text(Text::new(&format!("{hour:02}:00"), style.dimmed));
+                context.gfx.draw_measured_text(
+                    label.translate_by(Point::new(style.gap * 2, y)),
+                    TextOrigin::TopLeft,
+                );
+            }
+        }
+
+        let today = today();
+        for column in 0..7 {
+            let day = week_first.saturating_add(i64::from(column).days());
+            let left = gutter + day_width * column;
+            context.gfx.draw_shape(&Shape::filled_rect(
+                Rect::new(
+                    Point::new(left, grid_top),
+                    Size::new(Px::new(1), hour_height * 24),
+                ),
+                style.grid,
+            ));
+
+            let mut timed = events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| !event.is_banner() && event.first_day() == day)
+                .map(|(index, event)| {
+                    let start = minute_of_day(event.start, day);
+                    let end = minute_of_day(event.end, day)
+                        .max(start + SNAP_MINUTES)
+                        .min(MINUTES_PER_DAY);
+                    (index, start, end)
+                })
+                .collect::<Vec<_>>();
+            timed.sort_by_key(|&(_, start, end)| (start, Reverse(end)));
+            let columns = assign_columns(
+                &timed
+                    .iter()
+                    .map(|&(_, start, end)| (start, end))
+                    .collect::<Vec<_>>(),
+            );
+            for (&(index, start, end), (event_column, event_columns)) in timed.iter().zip(columns) {
+                let event_columns = event_columns.cast::<i32>();
+                let top = grid_top + hour_height * start.cast::<i32>() / 60;
+                let bottom = grid_top + hour_height * end.cast::<i32>() / 60;
+                let chip = Chip {
+                    index,
+                    rect: Rect::new(
+                        Point::new(
+                            left + day_width * event_column.cast::<i32>() / event_columns
+                                + style.gap,
+                            top,
+                        ),
+                        Size::new(
+                            day_width / event_columns - style.gap * 2,
+                            bottom - top - Px::new(1),
+                        ),
+                    ),
+                    timed: true,
+                };
+                self.draw_chip(chip, &events[index], style, context);
+            }
+
+            if day == today {
+                let now = jiff::Zoned::now().datetime();
+                let y = grid_top + hour_height * minute_of_day(now, day).cast::<i32>() / 60;
+                context.gfx.draw_shape(&Shape::filled_rect(
+                    Rect::new(Point::new(left, y), Size::new(day_width, style.gap)),
+                    style.today,
+                ));
+            }
+        }
+    }
+
+    fn redraw_agenda(
+        &mut self,
+        date: Date,
+        events: &[CalendarEvent],
+        style: &Style,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        let size = context.gfx.region().size;
+        self.geometry = Some(Geometry {
+            mode: CalendarMode::Agenda,
+            day_width: size.width,
+            row_height: style.chip_height,
+            resize_handle: Px::ZERO,
+        });
+        let today = today();
+        let mut y = style.gap;
+        for offset in 0..AGENDA_DAYS {
+            if y >= size.height {
+                break;
+            }
+            let day = date.saturating_add(offset.days());
+            let mut occurring = events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| event.occurs_on(day, day))
+                .collect::<Vec<_>>();
+            if occurring.is_empty() {
+                continue;
+            }
+            occurring.sort_by_key(|(_, event)| (!event.is_banner(), event.start));
+
+            let color = if day == today {
+                style.today
+            } else {
+                style.text
+            };
+            let heading = context
+                .gfx
+                .measure_text(Text::new(&day.strftime("%A, %B %-d").to_string(), color));
+            context.gfx.draw_measured_text(
+                heading.translate_by(Point::new(style.gap * 2, y)),
+                TextOrigin::TopLeft,
+            );
+            y += style.line_height + style.gap;
+            for (index, event) in occurring {
+                let chip = Chip {
+                    index,
+                    rect: Rect::new(
+                        Point::new(style.gap * 2, y),
+                        Size::new(size.width - style.gap * 4, style.chip_height),
+                    ),
+                    timed: false,
+                };
+                self.draw_chip(chip, event, style, context);
+                y += style.chip_height + 1;
+            }
+            y += style.gap * 2;
+        }
+
+        if self.chips.is_empty() {
+            let empty = context
+                .gfx
+                .measure_text(Text::new("No upcoming events", style.dimmed));
+            context.gfx.draw_measured_text(
+                empty.translate_by(Point::new(style.gap * 2, y)),
+                TextOrigin::TopLeft,
+            );
+        }
+    }
+
+    fn chip_at(&self, location: Point<Px>) -> Option<Chip> {
+        self.chips
+            .iter()
+            .rev()
+            .find(|chip| chip.rect.contains(location))
+            .copied()
+    }
+
+    fn is_resize_handle(&self, chip: &Chip, location: Point<Px>) -> bool {
+        chip.timed
+            && self.geometry.is_some_and(|geometry| {
+                chip.rect.origin.y + chip.rect.size.height - location.y <= geometry.resize_handle
+            })
+    }
+
+    /// Returns the change that ending `drag` would produce.
+    fn change_for(&self, drag: &Drag) -> Option<EventChange> {
+        let geometry = self.geometry?;
+        let event = self
+            .events
+            .map(|events| events.get(drag.chip.index).cloned())?;
+        let delta = drag.current - drag.start;
+        let days = |distance: Px, size: Px| {
+            i64::from(
+                (distance.into_float() / size.into_float())
+                    .round()
+                    .cast::<i32>(),
+            )
+        };
+        let (start, end) = match geometry.mode {
+            CalendarMode::Agenda => return None,
+            CalendarMode::Month => {
+                let days =
+                    days(delta.x, geometry.day_width) + days(delta.y, geometry.row_height) * 7;
+                (
+                    event.start.saturating_add(days.days()),
+                    event.end.saturating_add(days.days()),
+                )
+            }
+            CalendarMode::Week => {
+                let minutes = if drag.chip.timed {
+                    let hours = delta.y.into_float() / geometry.row_height.into_float();
+                    let snaps = (hours * 60. / SNAP_MINUTES.cast::<f32>()).round();
+                    i64::from(snaps.cast::<i32>()) * SNAP_MINUTES
+                } else {
+                    0
+                };
+                if drag.resizing {
+                    let end = event
+                        .end
+                        .saturating_add(minutes.minutes())
+                        .max(event.start.saturating_add(SNAP_MINUTES.minutes()));
+                    (event.start, end)
+                } else {
+                    let days = days(delta.x, geometry.day_width);
+                    (
+                        event
+                            .start
+                            .saturating_add(days.days())
+                            .saturating_add(minutes.minutes()),
+                        event
+                            .end
+                            .saturating_add(days.days())
+                            .saturating_add(minutes.minutes()),
+                    )
+                }
+            }
+        };
+        (start != event.start || end != event.end).then_some(EventChange {
+            index: drag.chip.index,
+            start,
+            end,
+        })
+    }
+
+    fn draw_drag_preview(&self, style: &Style, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let Some(drag) = &self.drag else {
+            return;
+        };
+        let Some(event) = self
+            .events
+            .map(|events| events.get(drag.chip.index).cloned())
+        else {
+            return;
+        };
+        let delta = drag.current - drag.start;
+        let mut rect = drag.chip.rect;
+        if drag.resizing {
+            rect.size.height = (rect.size.height + delta.y).max(style.chip_height);
+        } else {
+            rect.origin += delta;
+        }
+        context.apply_opacity(ZeroToOne::new(0.6));
+        draw_chip(
+            rect,
+            event.color.unwrap_or(style.event),
+            style.event_text,
+            &event.label(),
+            style.gap,
+            context,
+        );
+    }
+}
+
+impl Widget for CalendarView {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        self.events.redraw_when_changed(context);
+        let date = self.date.get_tracking_redraw(context);
+        let mode = self.mode.get_tracking_redraw(context);
+        context.apply_current_font_settings();
+        let style = Self::style(context);
+
+        self.chips.clear();
+        self.geometry = None;
+        let events = self.events.get();
+        match mode {
+            CalendarMode::Month => self.redraw_month(date, &events, &style, context),
+            CalendarMode::Week => self.redraw_week(date, &events, &style, context),
+            CalendarMode::Agenda => self.redraw_agenda(date, &events, &style, context),
+        }
+        self.draw_drag_preview(&style, context);
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        let scale = context.gfx.scale();
+        Size::new(
+            available_space
+                .width
+                .fill_or_fit(Lp::inches(6).into_upx(scale)),
+            available_space
+                .height
+                .fill_or_fit(Lp::inches(4).into_upx(scale)),
+        )
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn hover(
+        &mut self,
+        location: Point<Px>,
+        _context: &mut EventContext<'_>,
+    ) -> Option<CursorIcon> {
+        if !matches!(self.geometry, Some(geometry) if geometry.mode != CalendarMode::Agenda) {
+            return None;
+        }
+        let chip = self.chip_at(location)?;
+        if self.is_resize_handle(&chip, location) {
+            Some(CursorIcon::NsResize)
+        } else {
+            Some(CursorIcon::Grab)
+        }
+    }
+
+    fn mouse_down(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        _context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button != MouseButton::Left
+            || matches!(self.geometry, Some(geometry) if geometry.mode == CalendarMode::Agenda)
+        {
+            return IGNORED;
+        }
+        let Some(chip) = self.chip_at(location) else {
+            return IGNORED;
+        };
+        self.drag = Some(Drag {
+            chip,
+            start: location,
+            current: location,
+            resizing: self.is_resize_handle(&chip, location),
+        });
+        HANDLED
+    }
+
+    fn mouse_drag(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) {
+        if let Some(drag) = &mut self.drag {
+            drag.current = location;
+            context.set_needs_redraw();
+        }
+    }
+
+    fn mouse_up(
+        &mut self,
+        location: Option<Point<Px>>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) {
+        let Some(mut drag) = self.drag.take() else {
+            return;
+        };
+        context.set_needs_redraw();
+        let Some(location) = location else {
+            return;
+        };
+        drag.current = location;
+        let Some(change) = self.change_for(&drag) else {
+            return;
+        };
+        let callback = if drag.resizing {
+            &mut self.on_resize
+        } else {
+            &mut self.on_move
+        };
+        if let Some(callback) = callback {
+            callback.invoke(change);
+        }
+    }
+}
+
+fn draw_chip(
+    rect: Rect<Px>,
+    background: Color,
+    foreground: Color,
+    label: &str,
+    padding: Px,
+    context: &mut GraphicsContext<'_, '_, '_, '_>,
+) {
+    if rect.size.width <= 0 || rect.size.height <= 0 {
+        return;
+    }
+    let mut gfx = context.gfx.clipped_to(rect);
+    gfx.draw_shape(&Shape::filled_rect(Rect::from(rect.size), background));
+    let text = gfx.measure_text(Text::new(label, foreground));
+    let top = (rect.size.height - text.size.height).min(padding * 2) / 2;
+    gfx.draw_measured_text(
+        text.translate_by(Point::new(padding * 2, top.max(Px::ZERO))),
+        TextOrigin::TopLeft,
+    );
+}
+
+fn today() -> Date {
+    jiff::Zoned::now().date()
+}
+
+/// Returns the first day of the week containing `date`.
+fn start_of_week(date: Date, week_start: Weekday) -> Date {
+    let offset =
+        (date.weekday().to_sunday_zero_offset() - week_start.to_sunday_zero_offset()).rem_euclid(7);
+    date.saturating_sub(i64::from(offset).days())
+}
+
+fn days_between(from: Date, to: Date) -> i64 {
+    i64::from((to - from).get_days())
+}
+
+/// Returns the number of minutes `moment` is into `day`, clamped to the
+/// bounds of the day.
+fn minute_of_day(moment: DateTime, day: Date) -> i64 {
+    match moment.date().cmp(&day) {
+        std::cmp::Ordering::Less => 0,
+        std::cmp::Ordering::Equal => i64::from(moment.hour()) * 60 + i64::from(moment.minute()),
+        std::cmp::Ordering::Greater => MINUTES_PER_DAY,
+    }
+}
+
+/// Assigns each inclusive range of columns to the first lane that has no
+/// overlapping range, returning the lane of each range.
+fn assign_lanes(ranges: &[(usize, usize)]) -> Vec<usize> {
+    let mut lanes: Vec<Vec<(usize, usize)>> = Vec::new();
+    ranges
+        .iter()
+        .map(|&(first, last)| {
+            let lane = lanes
+                .iter()
+                .position(|lane| {
+                    lane.iter()
+                        .all(|&(other_first, other_last)| last < other_first || first > other_last)
+                })
+                .unwrap_or_else(|| {
+                    lanes.push(Vec::new());
+                    lanes.len() - 1
+                });
+            lanes[lane].push((first, last));
+            lane
+        })
+        .collect()
+}
+
+/// Assigns each time range, sorted by start, to a column so that overlapping
+/// ranges are displayed side by side.
+///
+/// Returns the column of each range and the number of columns in the group of
+/// ranges it overlaps with.
+fn assign_columns(ranges: &[(i64, i64)]) -> Vec<(usize, usize)> {
+    let mut assigned = vec![(0, 1); ranges.len()];
+    let mut group_start = 0;
+    let mut group_end = i64::MIN;
+    let mut column_ends = Vec::<i64>::new();
+    for (index, &(start, end)) in ranges.iter().enumerate() {
+        if start >= group_end {
+            for entry in &mut assigned[group_start..index] {
+                entry.1 = column_ends.len();
+            }
+            group_start = index;
+            column_ends.clear();
+        }
+        let column = if let Some(column) = column_ends.iter().position(|&other| other <= start) {
+            column_ends[column] = end;
+            column
+        } else {
+            column_ends.push(end);
+            column_ends.len() - 1
+        };
+        assigned[index].0 = column;
+        group_end = group_end.max(end);
+    }
+    for entry in &mut assigned[group_start..] {
+        entry.1 = column_ends.len();
+    }
+    assigned
+}
+
+define_components! {
+    Calendar {
+        /// The color of the lines separating days in a [`Calendar`].
+        CalendarGridColor(Color, "grid_color", .surface.outline_variant)
+        /// The color used to highlight the current day in a [`Calendar`].
+        CalendarTodayColor(Color, "today_color", .primary.color)
+        /// The default background color of events in a [`Calendar`].
+        CalendarEventColor(Color, "event_color", .primary.container)
+        /// The text color of events in a [`Calendar`].
+        CalendarEventTextColor(Color, "event_text_color", .primary.on_container)
+    }
+}
+
+#[test]
+fn event_layout() {
+    use jiff::civil::date;
+
+    // Multi-day banners are stacked below earlier, overlapping banners.
+    assert_eq!(
+        assign_lanes(&[(0, 2), (1, 1), (3, 6), (2, 4)]),
+        [0, 1, 0, 1]
+    );
+
+    // Overlapping timed events share their group's width, while events that
+    // don't overlap anything use the full width.
+    assert_eq!(
+        assign_columns(&[(0, 60), (30, 90), (60, 120), (180, 240)]),
+        [(0, 2), (1, 2), (0, 2), (0, 1)]
+    );
+
+    let conference = CalendarEvent::all_day("Conference", date(2024, 10, 16), date(2024, 10, 18));
+    assert_eq!(conference.last_day(), date(2024, 10, 18));
+    let late = CalendarEvent::timed(
+        "Late",
+        date(2024, 10, 16).at(23, 0, 0, 0),
+        date(2024, 10, 17).at(0, 0, 0, 0),
+    );
+    assert!(!late.is_banner());
+    assert_eq!(minute_of_day(late.end, late.first_day()), MINUTES_PER_DAY);
+
+    // 2024-10-16 is a Wednesday.
+    assert_eq!(
+        start_of_week(date(2024, 10, 16), Weekday::Sunday),
+        date(2024, 10, 13)
+    );
+    assert_eq!(
+        start_of_week(date(2024, 10, 16), Weekday::Monday),
+        date(2024, 10, 14)
+    );
+    assert_eq!(
+        CalendarMode::Month.next(date(2024, 1, 31)),
+        date(2024, 2, 29)
+    );
+}
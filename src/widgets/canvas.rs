@@ -1,19 +1,31 @@
+//! A 2d drawable surface.
+
 use std::fmt::Debug;
 
-use figures::units::UPx;
-use figures::Size;
+use figures::units::{Px, UPx};
+use figures::{Point, Size};
+use kludgine::app::winit::window::CursorIcon;
 
-use crate::context::{GraphicsContext, LayoutContext};
-use crate::reactive::value::Dynamic;
+use crate::context::{EventContext, GraphicsContext, LayoutContext};
+use crate::reactive::value::{Destination, Dynamic};
 use crate::widget::Widget;
 use crate::{ConstraintLimit, Tick};
 
+mod picking;
+
+pub use self::picking::{PickingBuffer, PickingFrame};
+
 /// A 2d drawable surface.
+///
+/// The canvas's contents are drawn each time it is redrawn. For drawing large
+/// numbers of items that change individually, consider using a
+/// [`Scene`](crate::widgets::scene::Scene) instead.
 #[must_use]
 pub struct Canvas {
     render: Box<dyn RenderFunction>,
     tick: Option<Tick>,
     redraw: Dynamic<()>,
+    picking: Option<PickingBuffer>,
 }
 
 impl Canvas {
@@ -29,6 +41,7 @@ impl Canvas {
             render: Box::new(render),
             tick: None,
             redraw: Dynamic::new(()),
+            picking: None,
         }
     }
 
@@ -37,6 +50,14 @@ impl Canvas {
         self.tick = Some(tick);
         self
     }
+
+    /// Updates the [probe location](PickingBuffer::probe) of `picking` with
+    /// the location of the cursor while it hovers this canvas, and returns
+    /// self.
+    pub fn picking(mut self, picking: PickingBuffer) -> Self {
+        self.picking = Some(picking);
+        self
+    }
 }
 
 impl Widget for Canvas {
@@ -55,6 +76,27 @@ impl Widget for Canvas {
     ) -> Size<UPx> {
         available_space.map(ConstraintLimit::max)
     }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        self.picking.is_some()
+    }
+
+    fn hover(
+        &mut self,
+        location: Point<Px>,
+        _context: &mut EventContext<'_>,
+    ) -> Option<CursorIcon> {
+        if let Some(picking) = &self.picking {
+            picking.probe().set(Some(location));
+        }
+        None
+    }
+
+    fn unhover(&mut self, _context: &mut EventContext<'_>) {
+        if let Some(picking) = &self.picking {
+            picking.probe().set(None);
+        }
+    }
 }
 
 impl Debug for Canvas {
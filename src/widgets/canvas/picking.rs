@@ -0,0 +1,604 @@
+use std::borrow::Cow;
+use std::f32::consts::TAU;
+use std::sync::Arc;
+
+use figures::units::{Px, UPx};
+use figures::{FloatConversion, IntoUnsigned, Point, Rect, Size};
+use intentional::{Assert, Cast};
+use kludgine::{wgpu, RenderingGraphics};
+use parking_lot::Mutex;
+
+use crate::animation::ZeroToOne;
+use crate::context::GraphicsContext;
+use crate::graphics::RenderOperation;
+use crate::reactive::value::{Destination, Dynamic, Source};
+
+/// The format of the id buffer. Each pixel stores the id of the topmost
+/// primitive plus one, leaving zero to represent empty space.
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+/// The size of each vertex: a position and an id.
+const VERTEX_SIZE: u64 = 12;
+
+const SHADER: &str = r"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) @interpolate(flat) id: u32,
+}
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>, @location(1) id: u32) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    out.id = id;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) u32 {
+    return in.id;
+}
+";
+
+/// An id buffer for pixel-precise hit testing of custom-drawn content.
+///
+/// Each frame, a [`Canvas`](crate::widgets::Canvas) can describe the regions
+/// occupied by the elements it draws using a [`PickingFrame`], tagging each
+/// region with an id. The regions are rendered on the GPU into a buffer that
+/// stores the id of the topmost element for each pixel, and the id under the
+/// [probe location](Self::probe) is read back into [`Self::picked`].
+///
+/// Only a single pixel is read back each frame, which allows picking among
+/// thousands of drawn elements without testing each element's geometry on
+/// the CPU. The read back happens asynchronously, so [`Self::picked`] is
+/// updated a frame or more after the id buffer is rendered. The id buffer is
+/// only rendered and read again once the probe location, the widget's size, or
+/// the tagged regions change.
+///
+/// ```rust
+/// use cushy::figures::units::Px;
+/// use cushy::figures::Point;
+/// use cushy::kludgine::shapes::Shape;
+/// use cushy::kludgine::{Color, DrawableExt, Origin};
+/// use cushy::reactive::value::Source;
+/// use cushy::widgets::canvas::PickingBuffer;
+/// use cushy::widgets::Canvas;
+///
+/// let picking = PickingBuffer::new();
+/// let canvas = Canvas::new({
+///     let picking = picking.clone();
+///     move |context| {
+///         let mut ids = picking.frame();
+///         let picked = picking.picked().get_tracking_redraw(context);
+///         for id in 0..100_u32 {
+///             let center = Point::px(10 * id as i32, 50);
+///             let color = if picked == Some(id) {
+///                 Color::RED
+///             } else {
+///                 Color::WHITE
+///             };
+///             context.gfx.draw_shape(
+///                 Shape::filled_circle(Px::new(4), color, Origin::Center).translate_by(center),
+///             );
+///             ids.fill_circle(center, Px::new(4), id);
+///         }
+///         ids.render(context);
+///     }
+/// })
+/// .picking(picking);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PickingBuffer {
+    probe: Dynamic<Option<Point<Px>>>,
+    picked: Dynamic<Option<u32>>,
+    reading: Dynamic<bool>,
+    last_read: Arc<Mutex<Option<LastRead>>>,
+}
+
+/// The inputs of the most recently started read back.
+#[derive(Debug, PartialEq)]
+struct LastRead {
+    probe: Point<Px>,
+    size: Size<UPx>,
+    vertices: Vec<u8>,
+}
+
+impl PickingBuffer {
+    /// Returns a new picking buffer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the dynamic controlling which location is read back from the
+    /// id buffer.
+    ///
+    /// The location is relative to the widget rendering the
+    /// [`PickingFrame`]. When this buffer is attached to a
+    /// [`Canvas`](crate::widgets::Canvas) using
+    /// [`Canvas::picking`](crate::widgets::Canvas::picking), the probe is
+    /// updated with the cursor location automatically.
+    #[must_use]
+    pub const fn probe(&self) -> &Dynamic<Option<Point<Px>>> {
+        &self.probe
+    }
+
+    /// Returns the dynamic containing the id of the topmost element at the
+    /// [probe location](Self::probe) in the most recently read id buffer.
+    ///
+    /// Contains `None` if no probe location is set or no element was drawn at
+    /// the probe location.
+    #[must_use]
+    pub const fn picked(&self) -> &Dynamic<Option<u32>> {
+        &self.picked
+    }
+
+    /// Returns a new frame to describe the elements being drawn.
+    #[must_use]
+    pub fn frame(&self) -> PickingFrame {
+        PickingFrame {
+            buffer: self.clone(),
+            vertices: Vec::new(),
+        }
+    }
+
+    /// Returns true if the id at `probe` has already been read from an id
+    /// buffer of `size` rendered from `vertices`.
+    fn has_read(&self, probe: Point<Px>, size: Size<UPx>, vertices: &[u8]) -> bool {
+        self.last_read.lock().as_ref().is_some_and(|last| {
+            last.probe == probe && last.size == size && last.vertices == vertices
+        })
+    }
+
+    /// Records the inputs of a read back that is starting.
+    fn note_read(&self, probe: Point<Px>, size: Size<UPx>, vertices: Vec<u8>) {
+        *self.last_read.lock() = Some(LastRead {
+            probe,
+            size,
+            vertices,
+        });
+    }
+
+    /// Forgets the most recent read back, ensuring the next frame is read.
+    fn forget_read(&self) {
+        *self.last_read.lock() = None;
+    }
+}
+
+/// The regions occupied by elements being drawn, tagged with ids.
+///
+/// Regions are filled in the order they are added, and later regions cover
+/// earlier regions. All locations are relative to the widget rendering the
+/// frame. Ids must be less than `u32::MAX`.
+///
+/// Transformations applied to the graphics context, such as by
+/// [`Transformed`](crate::widgets::Transformed), are not applied to the id
+/// buffer.
+#[derive(Debug)]
+#[must_use]
+pub struct PickingFrame {
+    buffer: PickingBuffer,
+    vertices: Vec<(Point<f32>, u32)>,
+}
+
+impl PickingFrame {
+    /// Tags `rect` with `id`.
+    pub fn fill_rect(&mut self, rect: Rect<Px>, id: u32) {
+        let (top_left, bottom_right) = rect.extents();
+        self.fill_polygon(
+            &[
+                top_left,
+                Point::new(bottom_right.x, top_left.y),
+                bottom_right,
+                Point::new(top_left.x, bottom_right.y),
+            ],
+            id,
+        );
+    }
+
+    /// Tags the circle at `center` with `radius` with `id`.
+    pub fn fill_circle(&mut self, center: Point<Px>, radius: Px, id: u32) {
+        let center = center.into_float();
+        let radius = radius.into_float();
+        let segments = (radius / 2.).clamp(8., 64.).cast::<u32>();
+        let point_at = |segment: u32| {
+            let angle = segment.cast::<f32>() / segments.cast::<f32>() * TAU;
+            Point::new(
+                center.x + angle.cos() * radius,
+                center.y + angle.sin() * radius,
+            )
+        };
+        let id = stored_id(id);
+        for segment in 0..segments {
+            self.vertices.extend([
+                (center, id),
+                (point_at(segment), id),
+                (point_at(segment + 1), id),
+            ]);
+        }
+    }
+
+    /// Tags the convex polygon formed by `points` with `id`.
+    pub fn fill_polygon(&mut self, points: &[Point<Px>], id: u32) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+        let first = first.into_float();
+        let id = stored_id(id);
+        for pair in rest.windows(2) {
+            self.vertices.extend([
+                (first, id),
+                (pair[0].into_float(), id),
+                (pair[1].into_float(), id),
+            ]);
+        }
+    }
+
+    /// Tags the line from `start` to `end` that is `width` wide with `id`.
+    pub fn stroke_line(&mut self, start: Point<Px>, end: Point<Px>, width: Px, id: u32) {
+        let start = start.into_float();
+        let end = end.into_float();
+        let delta = end - start;
+        let length = delta.x.hypot(delta.y);
+        if length <= f32::EPSILON {
+            return;
+        }
+        let half_width = width.into_float() / 2.;
+        let offset = Point::new(
+            -delta.y / length * half_width,
+            delta.x / length * half_width,
+        );
+        let id = stored_id(id);
+        self.vertices.extend([
+            (start + offset, id),
+            (end + offset, id),
+            (end - offset, id),
+            (start + offset, id),
+            (end - offset, id),
+            (start - offset, id),
+        ]);
+    }
+
+    /// Renders the id buffer for the widget of `context` and begins reading
+    /// back the id at the [probe location](PickingBuffer::probe).
+    pub fn render(self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        context.redraw_when_changed(&self.buffer.probe);
+        context.redraw_when_changed(&self.buffer.reading);
+        let reading = self.buffer.reading.clone();
+        context.gfx.draw_with::<PickingOp>(self);
+        if reading.get() {
+            // Some backends only report that a read back has finished while
+            // the device is being polled, which happens each frame.
+            context.set_needs_redraw();
+        }
+    }
+}
+
+fn stored_id(id: u32) -> u32 {
+    assert!(id < u32::MAX, "picking ids must be less than u32::MAX");
+    id + 1
+}
+
+struct PickingOp {
+    pipeline: wgpu::RenderPipeline,
+    target: Option<(Size<UPx>, wgpu::Texture)>,
+    vertices: Option<wgpu::Buffer>,
+    readbacks: Vec<wgpu::Buffer>,
+    pending: Vec<PendingRead>,
+}
+
+/// A read back of a single id that is being mapped by the GPU.
+struct PendingRead {
+    buffer: wgpu::Buffer,
+    picking: PickingBuffer,
+    mapped: Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>,
+    stale: bool,
+}
+
+impl PickingOp {
+    /// Updates the picked ids of all read backs that have finished mapping.
+    fn finish_reads(&mut self) {
+        let mut index = 0;
+        while index < self.pending.len() {
+            let Some(result) = self.pending[index].mapped.lock().take() else {
+                index += 1;
+                continue;
+            };
+            let read = self.pending.remove(index);
+            let picked = match result {
+                Ok(()) => {
+                    let stored = {
+                        let mapped = read.buffer.slice(0..4).get_mapped_range();
+                        u32::from_ne_bytes([mapped[0], mapped[1], mapped[2], mapped[3]])
+                    };
+                    read.buffer.unmap();
+                    stored.checked_sub(1)
+                }
+                Err(err) => {
+                    tracing::error!("error reading picking buffer: {err}");
+                    None
+                }
+            };
+            if !read.stale {
+                read.picking.picked.set(picked);
+            }
+            self.readbacks.push(read.buffer);
+        }
+    }
+
+    fn is_reading(&self, picking: &PickingBuffer) -> bool {
+        self.pending
+            .iter()
+            .any(|read| read.picking.picked == picking.picked)
+    }
+}
+
+impl RenderOperation for PickingOp {
+    type DrawInfo = PickingFrame;
+    type Prepared = ();
+
+    fn new(graphics: &mut kludgine::Graphics<'_>) -> Self {
+        let device = graphics.device();
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("cushy picking"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cushy picking"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("cushy picking"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: VERTEX_SIZE,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Uint32],
+                }],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(FORMAT.into())],
+            }),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            target: None,
+            vertices: None,
+            readbacks: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn prepare(
+        &mut self,
+        frame: Self::DrawInfo,
+        region: Rect<Px>,
+        _opacity: ZeroToOne,
+        graphics: &mut kludgine::Graphics<'_>,
+    ) -> Self::Prepared {
+        // Poll without blocking so that finished read backs are reported.
+        graphics.device().poll(wgpu::Maintain::Poll);
+        self.finish_reads();
+
+        let size = region.size.into_unsigned();
+        let probe = frame
+            .buffer
+            .probe
+            .get()
+            .filter(|probe| Rect::from(region.size).contains(*probe));
+        let Some(probe) = probe.filter(|_| !frame.vertices.is_empty()) else {
+            for read in &mut self.pending {
+                if read.picking.picked == frame.buffer.picked {
+                    read.stale = true;
+                }
+            }
+            frame.buffer.forget_read();
+            frame.buffer.picked.set(None);
+            return;
+        };
+        if self.is_reading(&frame.buffer) {
+            // Wait for the previous read back to finish before starting
+            // another.
+            return;
+        }
+
+        let bytes = vertex_bytes(
+            &frame.vertices,
+            Size::new(
+                region.size.width.into_float(),
+                region.size.height.into_float(),
+            ),
+        );
+        if frame.buffer.has_read(probe, size, &bytes) {
+            // The id under the probe can't have changed since the last read
+            // back.
+            return;
+        }
+
+        let device = graphics.device();
+        let queue = graphics.queue();
+        if self
+            .target
+            .as_ref()
+            .map_or(true, |(target_size, _)| *target_size != size)
+        {
+            self.target = Some((
+                size,
+                device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("cushy picking"),
+                    size: wgpu::Extent3d {
+                        width: size.width.get(),
+                        height: size.height.get(),
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: FORMAT,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                    view_formats: &[],
+                }),
+            ));
+        }
+        let texture = &self.target.as_ref().assert("initialized above").1;
+
+        let byte_len = bytes.len().cast::<u64>();
+        if self
+            .vertices
+            .as_ref()
+            .map_or(true, |buffer| buffer.size() < byte_len)
+        {
+            self.vertices = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("cushy picking vertices"),
+                size: byte_len.next_power_of_two(),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        let vertices = self.vertices.as_ref().assert("initialized above");
+        queue.write_buffer(vertices, 0, &bytes);
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("cushy picking"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_vertex_buffer(0, vertices.slice(0..byte_len));
+            pass.draw(0..frame.vertices.len().cast::<u32>(), 0..1);
+        }
+        let readback = self.readbacks.pop().unwrap_or_else(|| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("cushy picking readback"),
+                size: u64::from(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+        let origin = probe.into_unsigned();
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: origin.x.get(),
+                    y: origin.y.get(),
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit([encoder.finish()]);
+        frame.buffer.note_read(probe, size, bytes);
+
+        // The result is read during a later frame, once the GPU has finished
+        // mapping the buffer. Clearing `reading` redraws the widget that
+        // rendered this frame.
+        let mapped = Arc::new(Mutex::new(None));
+        readback.slice(0..4).map_async(wgpu::MapMode::Read, {
+            let mapped = mapped.clone();
+            let reading = frame.buffer.reading.clone();
+            move |result| {
+                *mapped.lock() = Some(result);
+                reading.set(false);
+            }
+        });
+        frame.buffer.reading.set(true);
+        self.pending.push(PendingRead {
+            buffer: readback,
+            picking: frame.buffer,
+            mapped,
+            stale: false,
+        });
+    }
+
+    fn render(
+        &self,
+        _prepared: &Self::Prepared,
+        _region: Rect<Px>,
+        _opacity: ZeroToOne,
+        _graphics: &mut RenderingGraphics<'_, '_>,
+    ) {
+        // The id buffer is rendered offscreen during preparation.
+    }
+}
+
+/// Converts `vertices` into the vertex buffer format, mapping their positions
+/// from a region of `size` into normalized device coordinates.
+fn vertex_bytes(vertices: &[(Point<f32>, u32)], size: Size<f32>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(vertices.len() * VERTEX_SIZE.cast::<usize>());
+    for (position, id) in vertices {
+        let x = position.x / size.width * 2. - 1.;
+        let y = 1. - position.y / size.height * 2.;
+        bytes.extend_from_slice(&x.to_ne_bytes());
+        bytes.extend_from_slice(&y.to_ne_bytes());
+        bytes.extend_from_slice(&id.to_ne_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn vertices_map_to_device_coordinates() {
+    let bytes = vertex_bytes(&[(Point::new(25., 75.), 3)], Size::new(100., 100.));
+    assert_eq!(&bytes[0..4], &(-0.5_f32).to_ne_bytes());
+    assert_eq!(&bytes[4..8], &(-0.5_f32).to_ne_bytes());
+    assert_eq!(&bytes[8..12], &3_u32.to_ne_bytes());
+}
+
+#[test]
+fn unchanged_frames_are_not_read_again() {
+    let buffer = PickingBuffer::new();
+    let probe = Point::px(5, 5);
+    let size = Size::new(UPx::new(10), UPx::new(10));
+    let vertices = vertex_bytes(&[(Point::new(1., 1.), 1)], Size::new(10., 10.));
+    assert!(!buffer.has_read(probe, size, &vertices));
+
+    buffer.note_read(probe, size, vertices.clone());
+    assert!(buffer.has_read(probe, size, &vertices));
+    assert!(!buffer.has_read(Point::px(6, 5), size, &vertices));
+    assert!(!buffer.has_read(probe, Size::new(UPx::new(20), UPx::new(10)), &vertices));
+    let moved = vertex_bytes(&[(Point::new(2., 1.), 1)], Size::new(10., 10.));
+    assert!(!buffer.has_read(probe, size, &moved));
+
+    buffer.forget_read();
+    assert!(!buffer.has_read(probe, size, &vertices));
+}
@@ -0,0 +1,690 @@
+//! Widgets for charting series of values.
+
+use std::ops::RangeInclusive;
+
+use figures::units::{Lp, Px, UPx};
+use figures::{FloatConversion, Point, Rect, ScreenScale, Size, Zero};
+use intentional::Cast;
+use kludgine::app::winit::window::CursorIcon;
+use kludgine::shapes::{PathBuilder, Shape, StrokeOptions};
+use kludgine::text::{Text, TextOrigin};
+use kludgine::{Color, DrawableExt, Origin};
+
+use crate::context::{EventContext, GraphicsContext, LayoutContext, Trackable};
+use crate::reactive::value::{IntoValue, Source, Value};
+use crate::styles::components::{
+    ErrorColor, PrimaryColor, SecondaryColor, TertiaryColor, TextColor, TextColorVariant,
+};
+use crate::styles::Dimension;
+use crate::widget::Widget;
+use crate::ConstraintLimit;
+
+/// A named series of points displayed by a [`Chart`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    /// The name of this series, shown in the legend and tooltips.
+    pub name: String,
+    /// The `(x, y)` points of this series.
+    pub points: Vec<(f64, f64)>,
+    /// The color of this series. When `None`, a color from the current theme
+    /// is used.
+    pub color: Option<Color>,
+}
+
+impl Series {
+    /// Returns a series named `name` containing `points`.
+    pub fn new(name: impl Into<String>, points: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        Self {
+            name: name.into(),
+            points: points.into_iter().collect(),
+            color: None,
+        }
+    }
+
+    /// Returns a series named `name` where each value's x coordinate is its
+    /// index.
+    pub fn from_values(name: impl Into<String>, values: impl IntoIterator<Item = f64>) -> Self {
+        Self::new(
+            name,
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| (index.cast::<f64>(), value)),
+        )
+    }
+
+    /// Sets the color of this series and returns self.
+    #[must_use]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// The way a [`Chart`] displays its series.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ChartKind {
+    /// Each series is drawn as a line connecting its points.
+    Line,
+    /// Each point is drawn as a bar. Points that share the same x coordinate
+    /// are grouped together.
+    Bar,
+    /// Each point is drawn as a dot.
+    Scatter,
+}
+
+/// A widget that displays one or more [`Series`] as a line chart, bar chart,
+/// or scatter plot.
+///
+/// The axes are scaled automatically to fit the data and are labeled with
+/// evenly spaced ticks. Hovering a point shows a tooltip containing the
+/// point's series and value. Series without a color are assigned colors from
+/// the current theme.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::chart::{Chart, Series};
+///
+/// let series = Dynamic::new(vec![
+///     Series::from_values("Requests", [12., 18., 9., 22., 30.]),
+///     Series::from_values("Errors", [1., 0., 3., 2., 1.]),
+/// ]);
+/// let chart = Chart::line(series);
+/// ```
+#[derive(Debug)]
+pub struct Chart {
+    series: Value<Vec<Series>>,
+    kind: ChartKind,
+    x_range: Option<RangeInclusive<f64>>,
+    y_range: Option<RangeInclusive<f64>>,
+    cursor: Option<Point<Px>>,
+}
+
+impl Chart {
+    /// Returns a chart that displays `series` as `kind`.
+    #[must_use]
+    pub fn new(kind: ChartKind, series: impl IntoValue<Vec<Series>>) -> Self {
+        Self {
+            series: series.into_value(),
+            kind,
+            x_range: None,
+            y_range: None,
+            cursor: None,
+        }
+    }
+
+    /// Returns a chart that draws `series` as lines.
+    #[must_use]
+    pub fn line(series: impl IntoValue<Vec<Series>>) -> Self {
+        Self::new(ChartKind::Line, series)
+    }
+
+    /// Returns a chart that draws `series` as bars.
+    #[must_use]
+    pub fn bar(series: impl IntoValue<Vec<Series>>) -> Self {
+        Self::new(ChartKind::Bar, series)
+    }
+
+    /// Returns a chart that draws `series` as dots.
+    #[must_use]
+    pub fn scatter(series: impl IntoValue<Vec<Series>>) -> Self {
+        Self::new(ChartKind::Scatter, series)
+    }
+
+    /// Sets the range of the x axis and returns self.
+    ///
+    /// By default, the range is chosen to fit all points. This setting has no
+    /// effect on bar charts, which place each distinct x value in its own
+    /// group.
+    #[must_use]
+    pub fn x_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.x_range = Some(range);
+        self
+    }
+
+    /// Sets the range of the y axis and returns self.
+    ///
+    /// By default, the range is chosen to fit all points.
+    #[must_use]
+    pub fn y_range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.y_range = Some(range);
+        self
+    }
+
+    fn draw_legend(
+        series: &[Series],
+        colors: &[Color],
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) -> Px {
+        if series.len() < 2 {
+            return Px::ZERO;
+        }
+        let scale = context.gfx.scale();
+        let gap = Lp::points(4).into_px(scale);
+        let swatch = Lp::points(8).into_px(scale);
+        let text_color = context.get(&TextColor);
+        let mut height = Px::ZERO;
+        let mut x = Px::ZERO;
+        for (series, color) in series.iter().zip(colors) {
+            let name = context
+                .gfx
+                .measure_text(Text::new(&series.name, text_color));
+            height = height.max(name.size.height);
+            context.gfx.draw_shape(&Shape::filled_rect(
+                Rect::new(
+                    Point::new(x, (name.size.height - swatch) / 2),
+                    Size::squared(swatch),
+                ),
+                *color,
+            ));
+            x += swatch + gap;
+            let name_width = name.size.width;
+            context.gfx.draw_measured_text(
+                name.translate_by(Point::new(x, Px::ZERO)),
+                TextOrigin::TopLeft,
+            );
+            x += name_width + gap * 3;
+        }
+        height + gap * 2
+    }
+}
+
+impl Widget for Chart {
+    #[allow(clippy::too_many_lines)]
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        self.series.redraw_when_changed(context);
+        context.apply_current_font_settings();
+        let scale = context.gfx.scale();
+        let size = context.gfx.region().size;
+        let gap = Lp::points(4).into_px(scale);
+        let label_color = context.get(&TextColorVariant);
+        let grid_color = context.get(&ChartGridColor);
+        let line_width = context.get(&ChartLineWidth).into_px(scale).max(Px::new(1));
+        let point_size = context.get(&ChartPointSize).into_px(scale);
+        let palette = [
+            context.get(&PrimaryColor),
+            context.get(&TertiaryColor),
+            context.get(&SecondaryColor),
+            context.get(&ErrorColor),
+        ];
+
+        let series = self.series.get();
+        let colors = series
+            .iter()
+            .enumerate()
+            .map(|(index, series)| series.color.unwrap_or(palette[index % palette.len()]))
+            .collect::<Vec<_>>();
+        let is_bar = self.kind == ChartKind::Bar;
+        let categories = if is_bar {
+            categories(&series)
+        } else {
+            Vec::new()
+        };
+
+        let legend_height = Self::draw_legend(&series, &colors, context);
+
+        let Some((x_extents, y_extents)) = data_extents(&series, is_bar) else {
+            return;
+        };
+        let x_extents = self
+            .x_range
+            .clone()
+            .map_or(x_extents, RangeInclusive::into_inner);
+        let y_extents = self
+            .y_range
+            .clone()
+            .map_or(y_extents, RangeInclusive::into_inner);
+
+        let line_height = context
+            .gfx
+            .measure_text(Text::new("0", label_color))
+            .size
+            .height;
+        let plot_top = legend_height + line_height / 2;
+        let plot_bottom = size.height - line_height - gap;
+        if plot_bottom <= plot_top {
+            return;
+        }
+        let tick_spacing = Lp::points(48).into_px(scale).max(Px::new(1));
+        let y_axis = Axis::new(
+            y_extents,
+            usize::try_from(((plot_bottom - plot_top) / tick_spacing).get()).unwrap_or_default(),
+            self.y_range.is_none(),
+        );
+        let y_labels = y_axis
+            .ticks
+            .iter()
+            .map(|tick| {
+                context
+                    .gfx
+                    .measure_text(Text::new(&y_axis.format(*tick), label_color))
+            })
+            .collect::<Vec<_>>();
+        let label_width = y_labels
+            .iter()
+            .map(|label| label.size.width)
+            .max()
+            .unwrap_or_default();
+        let plot_left = label_width + gap;
+        let plot_right = size.width - line_width;
+        if plot_right <= plot_left {
+            return;
+        }
+        let plot = Rect::new(
+            Point::new(plot_left, plot_top),
+            Size::new(plot_right - plot_left, plot_bottom - plot_top),
+        );
+        let x_axis = (!is_bar).then(|| {
+            Axis::new(
+                x_extents,
+                usize::try_from((plot.size.width / (tick_spacing * 2)).get()).unwrap_or_default(),
+                self.x_range.is_none(),
+            )
+        });
+
+        let y_to_px = |y: f64| {
+            plot_bottom
+                - Px::from_float(
+                    (y_axis.fraction(y) * plot.size.height.into_float().cast::<f64>()).cast(),
+                )
+        };
+        let x_to_px = |x: f64| {
+            if let Some(axis) = &x_axis {
+                plot_left
+                    + Px::from_float(
+                        (axis.fraction(x) * plot.size.width.into_float().cast::<f64>()).cast(),
+                    )
+            } else {
+                let index = categories
+                    .binary_search_by(|category| category.total_cmp(&x))
+                    .unwrap_or_default();
+                plot_left
+                    + plot.size.width * (index.cast::<i32>() * 2 + 1)
+                        / (categories.len().cast::<i32>() * 2).max(1)
+            }
+        };
+
+        // Y axis grid and labels
+        for (tick, label) in y_axis.ticks.iter().zip(y_labels) {
+            let y = y_to_px(*tick);
+            context.gfx.draw_shape(&Shape::filled_rect(
+                Rect::new(
+                    Point::new(plot_left, y),
+                    Size::new(plot.size.width, Px::new(1)),
+                ),
+                grid_color,
+            ));
+            let label_size = label.size;
+            context.gfx.draw_measured_text(
+                label.translate_by(Point::new(
+                    label_width - label_size.width,
+                    y - label_size.height / 2,
+                )),
+                TextOrigin::TopLeft,
+            );
+        }
+
+        // X axis grid and labels
+        let x_ticks = if let Some(axis) = &x_axis {
+            axis.ticks
+                .iter()
+                .map(|tick| (*tick, axis.format(*tick)))
+                .collect::<Vec<_>>()
+        } else {
+            categories
+                .iter()
+                .map(|category| (*category, format_value(*category)))
+                .collect()
+        };
+        let mut next_label_left = None;
+        for (tick, label) in x_ticks {
+            let x = x_to_px(tick);
+            if x_axis.is_some() {
+                context.gfx.draw_shape(&Shape::filled_rect(
+                    Rect::new(
+                        Point::new(x, plot_top),
+                        Size::new(Px::new(1), plot.size.height),
+                    ),
+                    grid_color,
+                ));
+            }
+            let label = context.gfx.measure_text(Text::new(&label, label_color));
+            let label_width = label.size.width;
+            let left = x - label_width / 2;
+            // Skip labels that would overlap the previously drawn label.
+            if !matches!(next_label_left, Some(next) if left < next) {
+                next_label_left = Some(left + label_width + gap);
+                context.gfx.draw_measured_text(
+                    label.translate_by(Point::new(left, plot_bottom + gap)),
+                    TextOrigin::TopLeft,
+                );
+            }
+        }
+
+        // Series
+        let hover_radius = Lp::points(12).into_px(scale);
+        let mut hovered: Option<HoveredPoint> = None;
+        let mut consider_hover = |hover: HoveredPoint| {
+            if !matches!(&hovered, Some(best) if best.distance <= hover.distance) {
+                hovered = Some(hover);
+            }
+        };
+        let series_count = series.len().cast::<i32>().max(1);
+        let group_width = plot.size.width / categories.len().cast::<i32>().max(1);
+        let bar_width = (group_width * 3 / 4 / series_count).max(Px::new(1));
+        let baseline = y_to_px(0.);
+        for (series_index, (series, color)) in series.iter().zip(&colors).enumerate() {
+            let mut path: Option<PathBuilder<Px, false>> = None;
+            for &(x, y) in &series.points {
+                if !x.is_finite() || !y.is_finite() {
+                    continue;
+                }
+                let location = Point::new(x_to_px(x), y_to_px(y));
+                match self.kind {
+                    ChartKind::Line => {
+                        path = Some(match path {
+                            Some(path) => path.line_to(location),
+                            None => PathBuilder::new(location),
+                        });
+                    }
+                    ChartKind::Scatter => {
+                        context.gfx.draw_shape(
+                            Shape::filled_circle(point_size, *color, Origin::Center)
+                                .translate_by(location),
+                        );
+                    }
+                    ChartKind::Bar => {
+                        let left = location.x - bar_width * series_count / 2
+                            + bar_width * series_index.cast::<i32>();
+                        let rect = Rect::from_extents(
+                            Point::new(left, location.y.min(baseline)),
+                            Point::new(left + bar_width, location.y.max(baseline)),
+                        );
+                        context.gfx.draw_shape(&Shape::filled_rect(rect, *color));
+                        if self.cursor.is_some_and(|cursor| rect.contains(cursor)) {
+                            consider_hover(HoveredPoint {
+                                series: series_index,
+                                point: (x, y),
+                                location,
+                                distance: Px::ZERO,
+                            });
+                        }
+                        continue;
+                    }
+                }
+
+                if let Some(cursor) = self.cursor {
+                    let distance = distance(cursor, location);
+                    if distance <= hover_radius {
+                        consider_hover(HoveredPoint {
+                            series: series_index,
+                            point: (x, y),
+                            location,
+                            distance,
+                        });
+                    }
+                }
+            }
+            if let Some(path) = path {
+                context.gfx.draw_shape(
+                    &path
+                        .build()
+                        .stroke(StrokeOptions::px_wide(line_width).colored(*color)),
+                );
+            }
+        }
+
+        if let Some(hovered) = hovered {
+            if !is_bar {
+                context.gfx.draw_shape(
+                    Shape::filled_circle(point_size * 2, colors[hovered.series], Origin::Center)
+                        .translate_by(hovered.location),
+                );
+            }
+            let (x, y) = hovered.point;
+            let text = format!(
+                "{}\n{}, {}",
+                series[hovered.series].name,
+                format_value(x),
+                format_value(y)
+            );
+            draw_tooltip(&text, hovered.location, size, context);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        let scale = context.gfx.scale();
+        Size::new(
+            available_space
+                .width
+                .fill_or_fit(Lp::inches(4).into_upx(scale)),
+            available_space
+                .height
+                .fill_or_fit(Lp::inches(3).into_upx(scale)),
+        )
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn hover(&mut self, location: Point<Px>, context: &mut EventContext<'_>) -> Option<CursorIcon> {
+        self.cursor = Some(location);
+        context.set_needs_redraw();
+        None
+    }
+
+    fn unhover(&mut self, context: &mut EventContext<'_>) {
+        self.cursor = None;
+        context.set_needs_redraw();
+    }
+}
+
+struct HoveredPoint {
+    series: usize,
+    point: (f64, f64),
+    location: Point<Px>,
+    distance: Px,
+}
+
+/// An axis with evenly spaced ticks.
+#[derive(Debug, PartialEq)]
+struct Axis {
+    min: f64,
+    max: f64,
+    step: f64,
+    ticks: Vec<f64>,
+}
+
+impl Axis {
+    /// Returns an axis covering `extents` with approximately `target_ticks`
+    /// ticks. When `round` is true, the extents are expanded to the nearest
+    /// tick.
+    fn new((mut min, mut max): (f64, f64), target_ticks: usize, round: bool) -> Self {
+        if max < min {
+            std::mem::swap(&mut min, &mut max);
+        }
+        if max - min <= f64::EPSILON {
+            let padding = if min == 0. { 1. } else { min.abs() / 10. };
+            min -= padding;
+            max += padding;
+        }
+        let step = nice_step((max - min) / target_ticks.max(2).cast::<f64>());
+        if round {
+            min = (min / step).floor() * step;
+            max = (max / step).ceil() * step;
+        }
+
+        let first = (min / step).ceil() * step;
+        let tolerance = step * 1e-9;
+        let ticks = (0_u32..)
+            .map(|index| first + step * f64::from(index))
+            .take_while(|tick| *tick <= max + tolerance)
+            .collect();
+        Self {
+            min,
+            max,
+            step,
+            ticks,
+        }
+    }
+
+    /// Returns the position of `value` on this axis, where `0.` is the
+    /// minimum and `1.` is the maximum.
+    fn fraction(&self, value: f64) -> f64 {
+        (value - self.min) / (self.max - self.min)
+    }
+
+    /// Formats `tick` with enough precision to distinguish it from its
+    /// neighbors.
+    fn format(&self, tick: f64) -> String {
+        let precision = (-self.step.log10()).ceil().max(0.).cast::<usize>();
+        // Avoid displaying "-0" for values that are zero after rounding.
+        let tick = if tick.abs() < self.step / 2. {
+            0.
+        } else {
+            tick
+        };
+        format!("{tick:.precision$}")
+    }
+}
+
+/// Returns the smallest step of 1, 2, or 5 times a power of ten that is not
+/// less than `raw`.
+fn nice_step(raw: f64) -> f64 {
+    if !raw.is_finite() || raw <= 0. {
+        return 1.;
+    }
+    let magnitude = 10_f64.powf(raw.log10().floor());
+    let normalized = raw / magnitude;
+    let multiplier = if normalized <= 1. {
+        1.
+    } else if normalized <= 2. {
+        2.
+    } else if normalized <= 5. {
+        5.
+    } else {
+        10.
+    };
+    multiplier * magnitude
+}
+
+/// Returns the x and y extents of all finite points in `series`.
+fn data_extents(series: &[Series], include_zero: bool) -> Option<((f64, f64), (f64, f64))> {
+    let mut points = series
+        .iter()
+        .flat_map(|series| series.points.iter().copied())
+        .filter(|(x, y)| x.is_finite() && y.is_finite());
+    let (x, y) = points.next()?;
+    let ((min_x, max_x), (mut min_y, mut max_y)) = points.fold(
+        ((x, x), (y, y)),
+        |((min_x, max_x), (min_y, max_y)), (x, y)| {
+            ((min_x.min(x), max_x.max(x)), (min_y.min(y), max_y.max(y)))
+        },
+    );
+    if include_zero {
+        min_y = min_y.min(0.);
+        max_y = max_y.max(0.);
+    }
+    Some(((min_x, max_x), (min_y, max_y)))
+}
+
+/// Returns the sorted, distinct x values of all points in `series`.
+fn categories(series: &[Series]) -> Vec<f64> {
+    let mut categories = series
+        .iter()
+        .flat_map(|series| series.points.iter().map(|(x, _)| *x))
+        .filter(|x| x.is_finite())
+        .collect::<Vec<_>>();
+    categories.sort_by(f64::total_cmp);
+    categories.dedup();
+    categories
+}
+
+fn distance(a: Point<Px>, b: Point<Px>) -> Px {
+    let delta = (a - b).into_float();
+    Px::from_float(delta.x.hypot(delta.y))
+}
+
+/// Formats `value` for display in a tooltip.
+fn format_value(value: f64) -> String {
+    let formatted = format!("{value:.3}");
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+fn draw_tooltip(
+    text: &str,
+    anchor: Point<Px>,
+    size: Size<Px>,
+    context: &mut GraphicsContext<'_, '_, '_, '_>,
+) {
+    let padding = Lp::points(4).into_px(context.gfx.scale());
+    let measured = context
+        .gfx
+        .measure_text(Text::new(text, context.get(&ChartTooltipForeground)));
+    let box_size = measured.size + Size::squared(padding * 2);
+    let offset = Lp::points(12).into_px(context.gfx.scale());
+    let mut origin = anchor + Point::squared(offset);
+    if origin.x + box_size.width > size.width {
+        origin.x = anchor.x - offset - box_size.width;
+    }
+    if origin.y + box_size.height > size.height {
+        origin.y = anchor.y - offset - box_size.height;
+    }
+    context.gfx.draw_shape(&Shape::filled_rect(
+        Rect::new(origin, box_size),
+        context.get(&ChartTooltipBackground),
+    ));
+    context.gfx.draw_measured_text(
+        measured.translate_by(origin + Point::squared(padding)),
+        TextOrigin::TopLeft,
+    );
+}
+
+define_components! {
+    Chart {
+        /// The color of the grid lines drawn behind a [`Chart`].
+        ChartGridColor(Color, "grid_color", .surface.outline_variant)
+        /// The width of the lines drawn by a line [`Chart`].
+        ChartLineWidth(Dimension, "line_width", Dimension::Lp(Lp::points(2)))
+        /// The radius of the points drawn by a scatter [`Chart`].
+        ChartPointSize(Dimension, "point_size", Dimension::Lp(Lp::points(3)))
+        /// The background color of the tooltip shown when hovering a
+        /// [`Chart`].
+        ChartTooltipBackground(Color, "tooltip_background", .surface.highest_container)
+        /// The text color of the tooltip shown when hovering a [`Chart`].
+        ChartTooltipForeground(Color, "tooltip_foreground", @TextColor)
+    }
+}
+
+#[test]
+fn ticks() {
+    let axis = Axis::new((0.3, 9.2), 5, true);
+    assert_eq!(axis.min, 0.);
+    assert_eq!(axis.max, 10.);
+    assert_eq!(axis.ticks, [0., 2., 4., 6., 8., 10.]);
+    assert_eq!(axis.format(4.), "4");
+
+    let axis = Axis::new((0., 0.45), 4, true);
+    assert!((axis.step - 0.2).abs() < f64::EPSILON);
+    assert_eq!(axis.format(axis.ticks[1]), "0.2");
+
+    let axis = Axis::new((5., 5.), 4, true);
+    assert!(axis.min < 5. && axis.max > 5.);
+
+    assert_eq!(
+        data_extents(&[Series::from_values("a", [3., 7.])], true),
+        Some(((0., 1.), (0., 7.)))
+    );
+}
@@ -0,0 +1,334 @@
+//! A searchable list of commands.
+
+use std::fmt::{self, Debug};
+
+use figures::units::Lp;
+use kludgine::app::winit::keyboard::{Key, NamedKey};
+
+use crate::reactive::value::{Destination, Dynamic, MapEach, Source};
+use crate::widget::{MakeWidget, SharedCallback, WidgetList, HANDLED, IGNORED};
+use crate::widgets::button::ButtonKind;
+use crate::widgets::input::InputValue;
+use crate::widgets::layers::{Modal, ModalHandle};
+use crate::widgets::shortcuts::{Shortcut, ShortcutMap};
+
+/// A command that can be invoked from a [`CommandPalette`].
+#[derive(Clone)]
+pub struct Command {
+    name: String,
+    shortcut: Option<Shortcut>,
+    action: SharedCallback,
+}
+
+impl Command {
+    /// Returns a new command named `name` that invokes `action`.
+    pub fn new<F>(name: impl Into<String>, mut action: F) -> Self
+    where
+        F: FnMut() + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            shortcut: None,
+            action: SharedCallback::new(move |()| action()),
+        }
+    }
+
+    /// Sets the keyboard shortcut bound to this command and returns self.
+    ///
+    /// The shortcut is displayed in the command palette. Use
+    /// [`CommandPalette::shortcuts`] to create a [`ShortcutMap`] that invokes
+    /// commands when their shortcuts are pressed.
+    #[must_use]
+    pub fn shortcut(mut self, shortcut: impl Into<Shortcut>) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// Returns the name of this command.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the keyboard shortcut bound to this command, if any.
+    #[must_use]
+    pub const fn bound_shortcut(&self) -> Option<&Shortcut> {
+        self.shortcut.as_ref()
+    }
+
+    /// Invokes this command's action.
+    pub fn invoke(&self) {
+        self.action.invoke(());
+    }
+}
+
+impl Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Command")
+            .field("name", &self.name)
+            .field("shortcut", &self.shortcut)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A searchable list of [`Command`]s presented in a [`Modal`] layer.
+///
+/// When shown, the palette displays a text input that filters the commands
+/// using fuzzy matching as the user types. The arrow keys change the selected
+/// command, Enter invokes the selected command, and Escape dismisses the
+/// palette. Keyboard focus remains within the palette while it is shown.
+///
+/// ```rust
+/// use cushy::kludgine::app::winit::keyboard::ModifiersState;
+/// use cushy::widget::{MakeWidget, HANDLED};
+/// use cushy::widgets::command_palette::{Command, CommandPalette};
+/// use cushy::widgets::layers::Modal;
+/// use cushy::ModifiersStateExt;
+///
+/// let modal = Modal::new();
+/// let palette = CommandPalette::new(&modal)
+///     .with(Command::new("Save", || println!("Saving")).shortcut(("s", ModifiersState::PRIMARY)));
+///
+/// let ui = "Press Ctrl+Shift+P"
+///     .with_shortcut("p", ModifiersState::PRIMARY | ModifiersState::SHIFT, {
+///         let palette = palette.clone();
+///         move |_| {
+///             palette.show();
+///             HANDLED
+///         }
+///     })
+///     .and(modal)
+///     .into_layers();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommandPalette {
+    commands: Dynamic<Vec<Command>>,
+    modal: Modal,
+}
+
+impl CommandPalette {
+    /// Returns a new, empty command palette that is shown in `modal`.
+    #[must_use]
+    pub fn new(modal: &Modal) -> Self {
+        Self {
+            commands: Dynamic::default(),
+            modal: modal.clone(),
+        }
+    }
+
+    /// Adds `command` to this palette and returns self.
+    #[must_use]
+    pub fn with(self, command: Command) -> Self {
+        self.push(command);
+        self
+    }
+
+    /// Adds `command` to this palette.
+    pub fn push(&self, command: Command) {
+        self.commands.lock().push(command);
+    }
+
+    /// Returns a [`ShortcutMap`] that invokes each command in this palette
+    /// when its bound shortcut is pressed.
+    ///
+    /// Commands added after this function is called are not included in the
+    /// returned map.
+    #[must_use]
+    pub fn shortcuts(&self) -> ShortcutMap {
+        let mut map = ShortcutMap::default();
+        for command in self.commands.get() {
+            let Some(shortcut) = command.shortcut.clone() else {
+                continue;
+            };
+            map.insert(shortcut.key, shortcut.modifiers, move |_| {
+                command.invoke();
+                HANDLED
+            });
+        }
+        map
+    }
+
+    /// Shows this command palette.
+    pub fn show(&self) {
+        let handle = self.modal.new_handle();
+        handle.present(self.contents(&handle));
+    }
+
+    fn contents(&self, handle: &ModalHandle) -> impl MakeWidget {
+        let query = Dynamic::<String>::default();
+        let selected = Dynamic::new(0_usize);
+        let matches =
+            (&self.commands, &query).map_each(|(commands, query)| filter_commands(commands, query));
+        matches
+            .for_each({
+                let selected = selected.clone();
+                move |_| selected.set(0)
+            })
+            .persist();
+
+        let invoke_selected = {
+            let handle = handle.clone();
+            let matches = matches.clone();
+            let selected = selected.clone();
+            move || {
+                let command = matches.map_ref(|matches| matches.get(selected.get()).cloned());
+                if let Some(command) = command {
+                    handle.dismiss();
+                    command.invoke();
+                }
+            }
+        };
+
+        let input = query.into_input().placeholder("Type a command").on_key({
+            let matches = matches.clone();
+            let selected = selected.clone();
+            move |input| {
+                if !input.state.is_pressed() {
+                    return IGNORED;
+                }
+                match input.logical_key {
+                    Key::Named(NamedKey::ArrowDown) => {
+                        let count = matches.map_ref(Vec::len);
+                        selected.map_mut(|mut selected| {
+                            *selected = (*selected + 1).min(count.saturating_sub(1));
+                        });
+                    }
+                    Key::Named(NamedKey::ArrowUp) => {
+                        selected.map_mut(|mut selected| {
+                            *selected = selected.saturating_sub(1);
+                        });
+                    }
+                    Key::Named(NamedKey::Enter) => invoke_selected(),
+                    _ => return IGNORED,
+                }
+                HANDLED
+            }
+        });
+
+        let handle = handle.clone();
+        let results = matches.map_each(move |matches| {
+            if matches.is_empty() {
+                return WidgetList::new().and("No matching commands");
+            }
+
+            matches
+                .iter()
+                .enumerate()
+                .map(|(index, command)| {
+                    let caption = command.name.clone().expand();
+                    let caption = if let Some(shortcut) = &command.shortcut {
+                        caption
+                            .and(shortcut.to_string())
+                            .into_columns()
+                            .make_widget()
+                    } else {
+                        caption.make_widget()
+                    };
+                    let command = command.clone();
+                    let handle = handle.clone();
+                    caption
+                        .into_button()
+                        .kind(selected.map_each(move |selected| {
+                            if *selected == index {
+                                ButtonKind::Solid
+                            } else {
+                                ButtonKind::Transparent
+                            }
+                        }))
+                        .on_click(move |_| {
+                            handle.dismiss();
+                            command.invoke();
+                        })
+                })
+                .collect::<WidgetList>()
+        });
+
+        input
+            .and(
+                results
+                    .into_rows()
+                    .vertical_scroll()
+                    .height(..Lp::inches(4)),
+            )
+            .into_rows()
+            .width(Lp::inches(5))
+            .contain()
+    }
+}
+
+/// Returns the commands that match `query`, ordered from best to worst match.
+fn filter_commands(commands: &[Command], query: &str) -> Vec<Command> {
+    let mut matches = commands
+        .iter()
+        .filter_map(|command| fuzzy_score(query, &command.name).map(|score| (score, command)))
+        .collect::<Vec<_>>();
+    matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+    matches
+        .into_iter()
+        .map(|(_, command)| command.clone())
+        .collect()
+}
+
+/// Returns a score for how well `candidate` matches `query`, or `None` if
+/// `candidate` does not contain every character of `query` in order.
+///
+/// Matches that are consecutive or that begin words score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let mut query = query
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    let mut score = 0;
+    let mut previous = None::<char>;
+    let mut previous_matched = false;
+    for ch in candidate.chars() {
+        let Some(&next) = query.peek() else {
+            break;
+        };
+
+        if ch.to_lowercase().eq([next]) {
+            query.next();
+            score += 1;
+            if previous_matched {
+                score += 2;
+            }
+            let starts_word = previous.map_or(true, |previous| {
+                !previous.is_alphanumeric() || (previous.is_lowercase() && ch.is_uppercase())
+            });
+            if starts_word {
+                score += 3;
+            }
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous = Some(ch);
+    }
+
+    query.peek().is_none().then_some(score)
+}
+
+#[test]
+fn fuzzy_matching() {
+    assert_eq!(fuzzy_score("", "Anything"), Some(0));
+    assert!(fuzzy_score("svf", "Save File").is_some());
+    assert!(fuzzy_score("fs", "Save File").is_none());
+    assert!(fuzzy_score("save", "Save File") > fuzzy_score("sf", "Save File"));
+
+    let commands = [
+        Command::new("Open Settings", || {}),
+        Command::new("Save File", || {}),
+        Command::new("Close Window", || {}),
+    ];
+    let filtered = filter_commands(&commands, "se");
+    assert_eq!(
+        filtered.iter().map(Command::name).collect::<Vec<_>>(),
+        ["Open Settings", "Save File", "Close Window"]
+    );
+    let filtered = filter_commands(&commands, "sf");
+    assert_eq!(
+        filtered.iter().map(Command::name).collect::<Vec<_>>(),
+        ["Save File"]
+    );
+}
@@ -0,0 +1,472 @@
+//! A widget that draws connecting lines between other widgets.
+
+use std::fmt::{self, Debug};
+
+use figures::units::{Lp, Px};
+use figures::{FloatConversion, Point, Rect, ScreenScale, Size};
+use kludgine::shapes::{Shape, StrokeOptions};
+use kludgine::text::{Text, TextOrigin};
+use kludgine::{Color, DrawableExt};
+
+use crate::context::{GraphicsContext, Trackable};
+use crate::graphics::{DashPattern, LineCap, Path, PathBuilder, Stroke};
+use crate::reactive::value::{IntoValue, Source, Value};
+use crate::styles::components::TextColor;
+use crate::styles::Dimension;
+use crate::widget::{MakeWidget, WidgetId, WidgetRef, WrapperWidget};
+
+/// A line connecting two widgets drawn by [`Connectors`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connector {
+    /// The widget this connector begins at.
+    pub from: WidgetId,
+    /// The widget this connector ends at.
+    pub to: WidgetId,
+    /// The path this connector takes between the widgets.
+    pub routing: ConnectorRouting,
+    /// The ends of this connector that have arrowheads.
+    pub arrowheads: Arrowheads,
+    /// A label drawn at the midpoint of this connector.
+    pub label: Option<String>,
+    /// The color of this connector. When `None`, [`ConnectorColor`] is used.
+    pub color: Option<Color>,
+    /// If true, this connector is drawn as a dashed line.
+    pub dashed: bool,
+}
+
+impl Connector {
+    /// Returns an orthogonally routed connector from `from` to `to` with an
+    /// arrowhead at `to`.
+    #[must_use]
+    pub fn new(from: impl Into<WidgetId>, to: impl Into<WidgetId>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            routing: ConnectorRouting::default(),
+            arrowheads: Arrowheads::default(),
+            label: None,
+            color: None,
+            dashed: false,
+        }
+    }
+
+    /// Sets the routing of this connector and returns self.
+    #[must_use]
+    pub fn routing(mut self, routing: ConnectorRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Sets the ends of this connector that have arrowheads and returns self.
+    #[must_use]
+    pub fn arrowheads(mut self, arrowheads: Arrowheads) -> Self {
+        self.arrowheads = arrowheads;
+        self
+    }
+
+    /// Sets the label of this connector and returns self.
+    #[must_use]
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the color of this connector and returns self.
+    #[must_use]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Draws this connector as a dashed line and returns self.
+    #[must_use]
+    pub fn dashed(mut self) -> Self {
+        self.dashed = true;
+        self
+    }
+}
+
+/// The path a [`Connector`] takes between two widgets.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ConnectorRouting {
+    /// A straight line between the facing edges of the widgets.
+    Straight,
+    /// A series of horizontal and vertical lines between the facing edges of
+    /// the widgets.
+    #[default]
+    Orthogonal,
+    /// A smooth curve between the facing edges of the widgets.
+    Bezier,
+}
+
+/// The ends of a [`Connector`] that have arrowheads.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Arrowheads {
+    /// Neither end has an arrowhead.
+    None,
+    /// The start of the connector has an arrowhead.
+    Start,
+    /// The end of the connector has an arrowhead.
+    #[default]
+    End,
+    /// Both ends of the connector have arrowheads.
+    Both,
+}
+
+impl Arrowheads {
+    const fn start(self) -> bool {
+        matches!(self, Arrowheads::Start | Arrowheads::Both)
+    }
+
+    const fn end(self) -> bool {
+        matches!(self, Arrowheads::End | Arrowheads::Both)
+    }
+}
+
+/// A widget that draws [`Connector`]s between widgets above its child.
+///
+/// Connectors are routed each time this widget is drawn using the most recent
+/// layout of the connected widgets, which keeps them attached as the widgets
+/// move or resize. Connectors whose widgets have not been laid out are not
+/// drawn. The connected widgets do not need to be contained within this
+/// widget's child, but connectors are clipped to this widget's bounds.
+///
+/// ```rust
+/// use cushy::figures::units::Lp;
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::connectors::{Connector, Connectors};
+///
+/// let design = "Design".contain().make_widget();
+/// let build = "Build".contain().make_widget();
+/// let connector = Connector::new(design.id(), build.id()).label("blocks");
+///
+/// let tasks = design.and(build).into_columns().gutter(Lp::inches(1));
+/// let diagram = Connectors::new(tasks, vec![connector]);
+/// ```
+pub struct Connectors {
+    child: WidgetRef,
+    connectors: Value<Vec<Connector>>,
+}
+
+impl Connectors {
+    /// Returns a widget that draws `connectors` above `child`.
+    #[must_use]
+    pub fn new(child: impl MakeWidget, connectors: impl IntoValue<Vec<Connector>>) -> Self {
+        Self {
+            child: WidgetRef::new(child),
+            connectors: connectors.into_value(),
+        }
+    }
+
+    fn redraw_connector(
+        connector: &Connector,
+        from: Rect<f32>,
+        to: Rect<f32>,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        let scale = context.gfx.scale();
+        let width = context.get(&ConnectorLineWidth).into_px(scale).into_float();
+        let arrow_size = context.get(&ConnectorArrowSize).into_px(scale).into_float();
+        let color = connector
+            .color
+            .unwrap_or_else(|| context.get(&ConnectorColor));
+
+        let route = Route::new(from, to, connector.routing);
+        let start_inset = if connector.arrowheads.start() {
+            arrow_size
+        } else {
+            0.
+        };
+        let end_inset = if connector.arrowheads.end() {
+            arrow_size
+        } else {
+            0.
+        };
+
+        let mut stroke = Stroke::new(StrokeOptions::px_wide(Px::from_float(width)).colored(color))
+            .cap(LineCap::Butt);
+        if connector.dashed {
+            stroke = stroke.dashed(DashPattern::new([
+                Px::from_float(width * 3.),
+                Px::from_float(width * 2.),
+            ]));
+        }
+        context
+            .gfx
+            .stroke_path(&route.path(start_inset, end_inset), stroke);
+
+        if connector.arrowheads.start() {
+            let direction = Point::new(-route.start_direction.x, -route.start_direction.y);
+            let arrow = arrowhead(route.start, direction, arrow_size);
+            context.gfx.fill_path(&arrow, color);
+        }
+        if connector.arrowheads.end() {
+            let arrow = arrowhead(route.end, route.end_direction, arrow_size);
+            context.gfx.fill_path(&arrow, color);
+        }
+
+        if let Some(label) = &connector.label {
+            let padding = Lp::points(2).into_px(scale);
+            let measured = context
+                .gfx
+                .measure_text(Text::new(label, context.get(&ConnectorLabelColor)));
+            let size = measured.size + Size::squared(padding * 2);
+            let origin = px(route.midpoint()) - Point::new(size.width / 2, size.height / 2);
+            context.gfx.draw_shape(&Shape::filled_rect(
+                Rect::new(origin, size),
+                context.get(&ConnectorLabelBackground),
+            ));
+            context.gfx.draw_measured_text(
+                measured.translate_by(origin + Point::squared(padding)),
+                TextOrigin::TopLeft,
+            );
+        }
+    }
+}
+
+impl Debug for Connectors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connectors")
+            .field("child", &self.child)
+            .field("connectors", &self.connectors)
+            .finish()
+    }
+}
+
+impl WrapperWidget for Connectors {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn redraw_foreground(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        self.connectors.redraw_when_changed(context);
+        let origin = context.gfx.region().origin;
+        let connectors = self.connectors.get();
+        for connector in &connectors {
+            let Some(from) = widget_bounds(connector.from, origin, context) else {
+                continue;
+            };
+            let Some(to) = widget_bounds(connector.to, origin, context) else {
+                continue;
+            };
+            Self::redraw_connector(connector, from, to, context);
+        }
+    }
+}
+
+/// Returns the most recent layout of `id` relative to `origin`.
+fn widget_bounds(
+    id: WidgetId,
+    origin: Point<Px>,
+    context: &GraphicsContext<'_, '_, '_, '_>,
+) -> Option<Rect<f32>> {
+    let layout = id.find_in(context)?.last_layout()? - origin;
+    Some(Rect::new(
+        layout.origin.into_float(),
+        Size::new(
+            layout.size.width.into_float(),
+            layout.size.height.into_float(),
+        ),
+    ))
+}
+
+/// The path a connector takes between two rectangles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Route {
+    routing: ConnectorRouting,
+    start: Point<f32>,
+    /// The direction the route leaves `start`.
+    start_direction: Point<f32>,
+    end: Point<f32>,
+    /// The direction the route arrives at `end`.
+    end_direction: Point<f32>,
+}
+
+impl Route {
+    fn new(from: Rect<f32>, to: Rect<f32>, routing: ConnectorRouting) -> Self {
+        let from_center = center(from);
+        let to_center = center(to);
+        let delta = to_center - from_center;
+        // Connect the edges that face each other along the axis the widgets
+        // are furthest apart on.
+        let (start_direction, end_direction) = if delta.x.abs() >= delta.y.abs() {
+            let direction = Point::new(delta.x.signum(), 0.);
+            (direction, direction)
+        } else {
+            let direction = Point::new(0., delta.y.signum());
+            (direction, direction)
+        };
+        let start = edge(from, start_direction);
+        let end = edge(to, Point::new(-end_direction.x, -end_direction.y));
+        let (start_direction, end_direction) = if routing == ConnectorRouting::Straight {
+            let direction = normalize(end - start);
+            (direction, direction)
+        } else {
+            (start_direction, end_direction)
+        };
+        Self {
+            routing,
+            start,
+            start_direction,
+            end,
+            end_direction,
+        }
+    }
+
+    /// Returns the path of this route, leaving room for arrowheads of
+    /// `start_inset` and `end_inset` at each end.
+    fn path(&self, start_inset: f32, end_inset: f32) -> Path<Px> {
+        let start = offset(self.start, self.start_direction, start_inset);
+        let end = offset(self.end, self.end_direction, -end_inset);
+        let builder = PathBuilder::new(px(start));
+        match self.routing {
+            ConnectorRouting::Straight => builder.line_to(px(end)),
+            ConnectorRouting::Orthogonal => {
+                let (first, second) = self.elbows(start, end);
+                builder
+                    .line_to(px(first))
+                    .line_to(px(second))
+                    .line_to(px(end))
+            }
+            ConnectorRouting::Bezier => {
+                let (control1, control2) = self.controls(start, end);
+                builder.cubic_to(px(control1), px(control2), px(end))
+            }
+        }
+        .build()
+    }
+
+    /// Returns the corners of an orthogonal route between `start` and `end`.
+    fn elbows(&self, start: Point<f32>, end: Point<f32>) -> (Point<f32>, Point<f32>) {
+        if self.start_direction.x == 0. {
+            let middle = (start.y + end.y) / 2.;
+            (Point::new(start.x, middle), Point::new(end.x, middle))
+        } else {
+            let middle = (start.x + end.x) / 2.;
+            (Point::new(middle, start.y), Point::new(middle, end.y))
+        }
+    }
+
+    /// Returns the control points of a bezier route between `start` and `end`.
+    fn controls(&self, start: Point<f32>, end: Point<f32>) -> (Point<f32>, Point<f32>) {
+        let delta = end - start;
+        let distance = if self.start_direction.x == 0. {
+            delta.y.abs()
+        } else {
+            delta.x.abs()
+        } / 2.;
+        (
+            offset(start, self.start_direction, distance),
+            offset(end, self.end_direction, -distance),
+        )
+    }
+
+    /// Returns the point halfway along this route.
+    fn midpoint(&self) -> Point<f32> {
+        match self.routing {
+            ConnectorRouting::Straight => lerp(self.start, self.end, 0.5),
+            ConnectorRouting::Orthogonal => {
+                let (first, second) = self.elbows(self.start, self.end);
+                lerp(first, second, 0.5)
+            }
+            ConnectorRouting::Bezier => {
+                let (control1, control2) = self.controls(self.start, self.end);
+                Point::new(
+                    (self.start.x + 3. * control1.x + 3. * control2.x + self.end.x) / 8.,
+                    (self.start.y + 3. * control1.y + 3. * control2.y + self.end.y) / 8.,
+                )
+            }
+        }
+    }
+}
+
+/// Returns a triangle pointing in `direction` whose tip is at `tip`.
+fn arrowhead(tip: Point<f32>, direction: Point<f32>, size: f32) -> Path<Px> {
+    let base = offset(tip, direction, -size);
+    let normal = Point::new(-direction.y, direction.x);
+    Path::polygon([
+        px(tip),
+        px(offset(base, normal, size / 2.)),
+        px(offset(base, normal, -size / 2.)),
+    ])
+}
+
+fn center(rect: Rect<f32>) -> Point<f32> {
+    Point::new(
+        rect.origin.x + rect.size.width / 2.,
+        rect.origin.y + rect.size.height / 2.,
+    )
+}
+
+/// Returns the midpoint of the edge of `rect` facing `direction`.
+fn edge(rect: Rect<f32>, direction: Point<f32>) -> Point<f32> {
+    let center = center(rect);
+    Point::new(
+        center.x + direction.x * rect.size.width / 2.,
+        center.y + direction.y * rect.size.height / 2.,
+    )
+}
+
+fn normalize(vector: Point<f32>) -> Point<f32> {
+    let length = vector.x.hypot(vector.y);
+    if length > 0. {
+        Point::new(vector.x / length, vector.y / length)
+    } else {
+        Point::new(1., 0.)
+    }
+}
+
+fn offset(point: Point<f32>, direction: Point<f32>, distance: f32) -> Point<f32> {
+    Point::new(
+        point.x + direction.x * distance,
+        point.y + direction.y * distance,
+    )
+}
+
+fn lerp(a: Point<f32>, b: Point<f32>, t: f32) -> Point<f32> {
+    Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+fn px(point: Point<f32>) -> Point<Px> {
+    Point::new(Px::from_float(point.x), Px::from_float(point.y))
+}
+
+define_components! {
+    Connectors {
+        /// The color of the lines drawn by [`Connectors`].
+        ConnectorColor(Color, "color", .surface.outline)
+        /// The width of the lines drawn by [`Connectors`].
+        ConnectorLineWidth(Dimension, "line_width", Dimension::Lp(Lp::points(2)))
+        /// The length of the arrowheads drawn by [`Connectors`].
+        ConnectorArrowSize(Dimension, "arrow_size", Dimension::Lp(Lp::points(8)))
+        /// The background color of connector labels.
+        ConnectorLabelBackground(Color, "label_background", .surface.color)
+        /// The text color of connector labels.
+        ConnectorLabelColor(Color, "label_color", @TextColor)
+    }
+}
+
+#[test]
+fn routes() {
+    let from = Rect::new(Point::new(0., 0.), Size::new(10., 10.));
+    let to = Rect::new(Point::new(30., 20.), Size::new(10., 10.));
+
+    // The widgets are further apart horizontally, so the route connects the
+    // right edge of `from` to the left edge of `to`.
+    let route = Route::new(from, to, ConnectorRouting::Orthogonal);
+    assert_eq!(route.start, Point::new(10., 5.));
+    assert_eq!(route.end, Point::new(30., 25.));
+    assert_eq!(route.end_direction, Point::new(1., 0.));
+    assert_eq!(
+        route.elbows(route.start, route.end),
+        (Point::new(20., 5.), Point::new(20., 25.))
+    );
+    assert_eq!(route.midpoint(), Point::new(20., 15.));
+
+    // Stacked widgets connect their top and bottom edges.
+    let below = Rect::new(Point::new(0., 40.), Size::new(10., 10.));
+    let route = Route::new(from, below, ConnectorRouting::Straight);
+    assert_eq!(route.start, Point::new(5., 10.));
+    assert_eq!(route.end, Point::new(5., 40.));
+    assert_eq!(route.start_direction, Point::new(0., 1.));
+}
@@ -8,8 +8,11 @@ use kludgine::shapes::{CornerRadii, PathBuilder, Shape};
 use kludgine::Color;
 
 use crate::context::{EventContext, GraphicsContext, LayoutContext, WidgetContext};
+use crate::graphics::Path;
 use crate::reactive::value::{Dynamic, IntoValue, Source, Value};
-use crate::styles::components::{CornerRadius, IntrinsicPadding, Opacity, SurfaceColor};
+use crate::styles::components::{
+    CornerRadius, IntrinsicPadding, Opacity, SurfaceColor, WidgetBackgroundGradient,
+};
 use crate::styles::{Component, ContainerLevel, Dimension, Edges, RequireInvalidation, Styles};
 use crate::widget::{MakeWidget, RootBehavior, Widget, WidgetInstance, WidgetRef};
 use crate::ConstraintLimit;
@@ -216,11 +219,11 @@ impl Widget for Container {
 
     #[allow(clippy::too_many_lines)]
     fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
-        let opacity = context.get(&Opacity);
+        context.apply_opacity(context.get(&Opacity));
 
         let background = self.effective_background_color(context);
-        let background = background.with_alpha_f32(background.alpha_f32() * *opacity);
-        if background.alpha() > 0 {
+        let gradient = context.get(&WidgetBackgroundGradient);
+        if background.alpha() > 0 || !gradient.is_empty() {
             let shadow = self
                 .shadow
                 .get_tracking_invalidate(context)
@@ -246,6 +249,12 @@ impl Widget for Container {
                 corner_radii,
                 background,
             ));
+
+            if !gradient.is_empty() {
+                context
+                    .gfx
+                    .fill_path(&Path::round_rect(child_area, corner_radii), gradient);
+            }
         }
 
         let child = self.child.mounted(context);
@@ -950,3 +959,29 @@ impl IntoValue<ContainerShadow> for Dynamic<ContainerShadow<Lp>> {
         Value::Dynamic(self.map_each_cloned(ContainerShadow::<Dimension>::from))
     }
 }
+
+impl From<ContainerShadow> for Component {
+    fn from(value: ContainerShadow) -> Self {
+        Component::custom(value)
+    }
+}
+
+impl TryFrom<Component> for ContainerShadow {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Custom(custom) => custom
+                .downcast()
+                .copied()
+                .ok_or_else(|| Component::Custom(custom)),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for ContainerShadow {
+    fn requires_invalidation(&self) -> bool {
+        false
+    }
+}
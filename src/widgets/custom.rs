@@ -11,7 +11,7 @@ use crate::reactive::value::{IntoValue, Value};
 use crate::styles::VisualOrder;
 use crate::widget::{EventHandling, MakeWidget, WidgetRef, WrappedLayout, WrapperWidget, IGNORED};
 use crate::widgets::Space;
-use crate::window::{DeviceId, KeyEvent};
+use crate::window::{DeviceId, Gesture, KeyEvent};
 use crate::ConstraintLimit;
 
 /// A callback-based custom widget.
@@ -44,6 +44,7 @@ pub struct Custom {
     keyboard_input: Option<Box<dyn ThreeParamEventFunc<DeviceId, KeyEvent, bool, EventHandling>>>,
     mouse_wheel:
         Option<Box<dyn ThreeParamEventFunc<DeviceId, MouseScrollDelta, TouchPhase, EventHandling>>>,
+    gesture: Option<Box<dyn OneParamEventFunc<Gesture, EventHandling>>>,
     allow_blur: Option<Box<dyn EventFunc<bool>>>,
     advance_focus: Option<Box<dyn OneParamEventFunc<VisualOrder, EventHandling>>>,
 }
@@ -93,6 +94,7 @@ impl Custom {
             ime: None,
             keyboard_input: None,
             mouse_wheel: None,
+            gesture: None,
             allow_blur: None,
             advance_focus: None,
         }
@@ -436,6 +438,19 @@ impl Custom {
         self.mouse_wheel = Some(Box::new(mouse_wheel));
         self
     }
+
+    /// Invokes `gesture` when a touch gesture occurs.
+    ///
+    /// This callback corresponds to [`WrapperWidget::gesture`].
+    pub fn on_gesture<OnGesture>(mut self, gesture: OnGesture) -> Self
+    where
+        OnGesture: Send
+            + 'static
+            + for<'context> FnMut(Gesture, &mut EventContext<'context>) -> EventHandling,
+    {
+        self.gesture = Some(Box::new(gesture));
+        self
+    }
 }
 
 impl WrapperWidget for Custom {
@@ -629,6 +644,14 @@ impl WrapperWidget for Custom {
         }
     }
 
+    fn gesture(&mut self, gesture: Gesture, context: &mut EventContext<'_>) -> EventHandling {
+        if let Some(f) = &mut self.gesture {
+            f.invoke(gesture, context)
+        } else {
+            IGNORED
+        }
+    }
+
     fn advance_focus(
         &mut self,
         direction: VisualOrder,
@@ -0,0 +1,542 @@
+//! A widget that displays the differences between two texts.
+
+use std::ops::Range;
+
+use figures::units::{Px, UPx};
+use figures::{IntoSigned, IntoUnsigned, Point, Zero};
+use kludgine::Color;
+use similar::{ChangeTag, TextDiff};
+
+use crate::context::{GraphicsContext, WidgetContext};
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, MapEach, Source};
+use crate::styles::components::{FontFamily, TextColorVariant};
+use crate::styles::{FamilyOwned, FontFamilyList};
+use crate::widget::{
+    MakeWidget, MakeWidgetWithTag, WidgetId, WidgetInstance, WidgetList, WidgetRef, WidgetTag,
+    WrapperWidget,
+};
+use crate::widgets::rich_text::{RichText, SpanStyle};
+
+/// How a [`DiffView`] arranges the two texts being compared.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum DiffLayout {
+    /// The original text is shown on the left, and the changed text is shown
+    /// on the right.
+    #[default]
+    SideBySide,
+    /// Removed and added lines are interleaved in a single column.
+    Unified,
+}
+
+/// A widget that displays the differences between two texts.
+///
+/// Texts are compared line by line. Within lines that changed, the portions
+/// of the line that were changed are emphasized.
+///
+/// Runs of unchanged lines longer than the [number of context
+/// lines](Self::context_lines) are collapsed, and can be expanded by clicking
+/// them. A toolbar above the differences allows navigating between each
+/// change.
+///
+/// ```rust
+/// use cushy::widgets::diff_view::{DiffLayout, DiffView};
+///
+/// let diff = DiffView::new("one\ntwo\nthree\n", "one\n2\nthree\n").layout(DiffLayout::Unified);
+/// ```
+#[derive(Debug)]
+pub struct DiffView {
+    old: Dynamic<String>,
+    new: Dynamic<String>,
+    layout: Dynamic<DiffLayout>,
+    context_lines: usize,
+    current: Dynamic<Option<usize>>,
+}
+
+impl DiffView {
+    /// Returns a view of the differences between `old` and `new`.
+    pub fn new(old: impl IntoDynamic<String>, new: impl IntoDynamic<String>) -> Self {
+        Self {
+            old: old.into_dynamic(),
+            new: new.into_dynamic(),
+            layout: Dynamic::default(),
+            context_lines: 3,
+            current: Dynamic::default(),
+        }
+    }
+
+    /// Sets how the texts are arranged and returns self.
+    ///
+    /// By default, [`DiffLayout::SideBySide`] is used.
+    #[must_use]
+    pub fn layout(mut self, layout: impl IntoDynamic<DiffLayout>) -> Self {
+        self.layout = layout.into_dynamic();
+        self
+    }
+
+    /// Sets the number of unchanged lines shown around each change and
+    /// returns self.
+    ///
+    /// By default, 3 lines are shown.
+    #[must_use]
+    pub fn context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+
+    /// Uses `current` to store the index of the change being viewed and
+    /// returns self.
+    ///
+    /// Setting `current` scrolls the change into view.
+    #[must_use]
+    pub fn current_change(mut self, current: impl IntoDynamic<Option<usize>>) -> Self {
+        self.current = current.into_dynamic();
+        self
+    }
+}
+
+impl MakeWidgetWithTag for DiffView {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let context_lines = self.context_lines;
+        let hunks = Dynamic::<Vec<WidgetId>>::default();
+        let rows = (&self.old, &self.new, &self.layout).map_each({
+            let hunks = hunks.clone();
+            let current = self.current.clone();
+            move |(old, new, layout)| {
+                let lines = diff_lines(old, new);
+                let (rows, hunk_ids) = build_rows(&lines, *layout, context_lines);
+                hunks.set(hunk_ids);
+                current.set(None);
+                rows
+            }
+        });
+
+        let count = hunks.map_each(Vec::len);
+        let current = self.current;
+        let summary = (&current, &count).map_each(|(current, count)| match (current, count) {
+            (_, 0) => String::from("No changes"),
+            (Some(current), count) => format!("Change {} of {count}", current + 1),
+            (None, 1) => String::from("1 change"),
+            (None, count) => format!("{count} changes"),
+        });
+        let previous = "Previous"
+            .into_button()
+            .on_click({
+                let current = current.clone();
+                let count = count.clone();
+                move |_| {
+                    let count = count.get();
+                    current.map_mut(|mut current| {
+                        *current = match *current {
+                            Some(index) if index > 0 => Some(index - 1),
+                            _ => count.checked_sub(1),
+                        };
+                    });
+                }
+            })
+            .with_enabled(count.map_each(|count| *count > 0));
+        let next = "Next"
+            .into_button()
+            .on_click({
+                let current = current.clone();
+                let count = count.clone();
+                move |_| {
+                    let count = count.get();
+                    current.map_mut(|mut current| {
+                        *current = match *current {
+                            Some(index) if index + 1 < count => Some(index + 1),
+                            _ => (count > 0).then_some(0),
+                        };
+                    });
+                }
+            })
+            .with_enabled(count.map_each(|count| *count > 0));
+
+        let scroll = rows.into_rows().vertical_scroll();
+        let position = scroll.scroll.clone();
+        let changes = ChangeScroller {
+            child: WidgetRef::new(scroll),
+            position,
+            hunks,
+            current,
+            scrolled_to: None,
+        };
+
+        summary
+            .and(previous)
+            .and(next)
+            .into_columns()
+            .and(changes.expand())
+            .into_rows()
+            .with(&FontFamily, FontFamilyList::from(FamilyOwned::Monospace))
+            .make_with_tag(tag)
+    }
+}
+
+/// A single line of a diff.
+#[derive(Debug, Clone, PartialEq)]
+struct DiffLine {
+    tag: ChangeTag,
+    old: Option<usize>,
+    new: Option<usize>,
+    /// The contents of the line, with changed portions flagged `true`.
+    segments: Vec<(bool, String)>,
+}
+
+/// Returns the line-by-line differences between `old` and `new`.
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut lines = Vec::new();
+    for op in diff.ops() {
+        for change in diff.iter_inline_changes(op) {
+            let mut segments = change
+                .iter_strings_lossy()
+                .map(|(emphasized, text)| (emphasized, text.into_owned()))
+                .collect::<Vec<_>>();
+            if let Some((_, last)) = segments.last_mut() {
+                if last.ends_with('\n') {
+                    last.pop();
+                    if last.ends_with('\r') {
+                        last.pop();
+                    }
+                }
+            }
+            lines.push(DiffLine {
+                tag: change.tag(),
+                old: change.old_index(),
+                new: change.new_index(),
+                segments,
+            });
+        }
+    }
+    lines
+}
+
+/// A run of lines that are either all unchanged or all changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    Unchanged(Range<usize>),
+    Changed(Range<usize>),
+}
+
+/// Groups `lines` into runs of unchanged and changed lines.
+fn blocks(lines: &[DiffLine]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for index in 1..=lines.len() {
+        let unchanged = lines[start].tag == ChangeTag::Equal;
+        if index == lines.len() || (lines[index].tag == ChangeTag::Equal) != unchanged {
+            blocks.push(if unchanged {
+                Block::Unchanged(start..index)
+            } else {
+                Block::Changed(start..index)
+            });
+            start = index;
+        }
+    }
+    blocks
+}
+
+/// Returns the rows displaying `lines`, and the ids of the first row of each
+/// change.
+fn build_rows(
+    lines: &[DiffLine],
+    layout: DiffLayout,
+    context_lines: usize,
+) -> (WidgetList, Vec<WidgetId>) {
+    let number_width = lines
+        .iter()
+        .filter_map(|line| line.old.max(line.new))
+        .max()
+        .map_or(1, |last| (last + 1).to_string().len());
+    let row = |line: &DiffLine| match layout {
+        DiffLayout::SideBySide => {
+            let side = |number| DiffRow::new(Some(line), number, number_width, layout);
+            side(line.old)
+                .expand()
+                .and(side(line.new).expand())
+                .into_columns()
+                .make_widget()
+        }
+        DiffLayout::Unified => DiffRow::new(Some(line), None, number_width, layout).make_widget(),
+    };
+
+    let mut rows = WidgetList::new();
+    let mut hunks = Vec::new();
+    for block in blocks(lines) {
+        match block {
+            Block::Unchanged(range) => {
+                let before = if range.start == 0 { 0 } else { context_lines };
+                let after = if range.end == lines.len() {
+                    0
+                } else {
+                    context_lines
+                };
+                if range.len() <= before + after + 1 {
+                    rows.extend(lines[range].iter().map(row));
+                    continue;
+                }
+
+                let hidden = range.start + before..range.end - after;
+                rows.extend(lines[range.start..hidden.start].iter().map(row));
+                let collapsed = Dynamic::new(true);
+                rows.push(
+                    format!("Show {} unchanged lines", hidden.len())
+                        .into_button()
+                        .on_click({
+                            let collapsed = collapsed.clone();
+                            move |_| collapsed.set(false)
+                        })
+                        .collapse_vertically(collapsed.map_each(|collapsed| !collapsed)),
+                );
+                rows.push(
+                    lines[hidden.clone()]
+                        .iter()
+                        .map(row)
+                        .collect::<WidgetList>()
+                        .into_rows()
+                        .collapse_vertically(collapsed),
+                );
+                rows.extend(lines[hidden.end..range.end].iter().map(row));
+            }
+            Block::Changed(range) => {
+                let changed = &lines[range];
+                let block_rows = match layout {
+                    DiffLayout::Unified => changed.iter().map(row).collect::<Vec<_>>(),
+                    DiffLayout::SideBySide => {
+                        let removed = changed
+                            .iter()
+                            .filter(|line| line.tag == ChangeTag::Delete)
+                            .collect::<Vec<_>>();
+                        let added = changed
+                            .iter()
+                            .filter(|line| line.tag == ChangeTag::Insert)
+                            .collect::<Vec<_>>();
+                        (0..removed.len().max(added.len()))
+                            .map(|index| {
+                                let old = removed.get(index).copied();
+                                let new = added.get(index).copied();
+                                DiffRow::new(
+                                    old,
+                                    old.and_then(|line| line.old),
+                                    number_width,
+                                    layout,
+                                )
+                                .expand()
+                                .and(
+                                    DiffRow::new(
+                                        new,
+                                        new.and_then(|line| line.new),
+                                        number_width,
+                                        layout,
+                                    )
+                                    .expand(),
+                                )
+                                .into_columns()
+                                .make_widget()
+                            })
+                            .collect()
+                    }
+                };
+                hunks.push(block_rows[0].id());
+                rows.extend(block_rows);
+            }
+        }
+    }
+    (rows, hunks)
+}
+
+/// The colors used to style the text of a [`DiffRow`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct RowColors {
+    number: Color,
+    emphasis: Color,
+}
+
+/// A single line of a [`DiffView`].
+#[derive(Debug)]
+struct DiffRow {
+    child: WidgetRef,
+    tag: Option<ChangeTag>,
+    colors: Dynamic<RowColors>,
+}
+
+impl DiffRow {
+    /// Returns a row displaying `line`.
+    ///
+    /// In side-by-side layouts, `number` is the line number displayed. In
+    /// unified layouts, both line numbers are displayed along with a marker
+    /// indicating whether the line was added or removed.
+    fn new(
+        line: Option<&DiffLine>,
+        number: Option<usize>,
+        number_width: usize,
+        layout: DiffLayout,
+    ) -> Self {
+        let format_number = |number: Option<usize>| {
+            number.map_or_else(
+                || " ".repeat(number_width),
+                |number| format!("{:>number_width$}", number + 1),
+            )
+        };
+        let tag = line.map(|line| line.tag);
+        let marker = match tag {
+            Some(ChangeTag::Delete) => '-',
+            Some(ChangeTag::Insert) => '+',
+            _ => ' ',
+        };
+        let prefix = match (layout, line) {
+            (_, None) => String::new(),
+            (DiffLayout::SideBySide, Some(_)) => format!("{} {marker} ", format_number(number)),
+            (DiffLayout::Unified, Some(line)) => format!(
+                "{} {} {marker} ",
+                format_number(line.old),
+                format_number(line.new)
+            ),
+        };
+        let segments = line.map(|line| line.segments.clone()).unwrap_or_default();
+
+        let colors = Dynamic::<RowColors>::default();
+        let text = colors.map_each(move |colors| {
+            let mut text =
+                RichText::default().with(&prefix, SpanStyle::default().color(colors.number));
+            for (emphasized, segment) in &segments {
+                if *emphasized {
+                    text.push(segment, SpanStyle::default().color(colors.emphasis).bold());
+                } else {
+                    text.push(segment, SpanStyle::default());
+                }
+            }
+            text
+        });
+
+        Self {
+            child: WidgetRef::new(text),
+            tag,
+            colors,
+        }
+    }
+}
+
+impl WrapperWidget for DiffRow {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn background_color(&mut self, context: &WidgetContext<'_>) -> Option<Color> {
+        match self.tag {
+            Some(ChangeTag::Equal) => None,
+            Some(ChangeTag::Delete) => Some(context.get(&DiffRemovedBackground)),
+            Some(ChangeTag::Insert) => Some(context.get(&DiffAddedBackground)),
+            None => Some(context.get(&DiffMissingBackground)),
+        }
+    }
+
+    fn redraw_background(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let emphasis = match self.tag {
+            Some(ChangeTag::Insert) => context.get(&DiffAddedEmphasis),
+            _ => context.get(&DiffRemovedEmphasis),
+        };
+        self.colors.set(RowColors {
+            number: context.get(&TextColorVariant),
+            emphasis,
+        });
+    }
+}
+
+/// Scrolls the current change of a [`DiffView`] into view.
+#[derive(Debug)]
+struct ChangeScroller {
+    child: WidgetRef,
+    position: Dynamic<Point<UPx>>,
+    hunks: Dynamic<Vec<WidgetId>>,
+    current: Dynamic<Option<usize>>,
+    scrolled_to: Option<usize>,
+}
+
+impl WrapperWidget for ChangeScroller {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn redraw_background(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let current = self.current.get_tracking_redraw(context);
+        if current == self.scrolled_to {
+            return;
+        }
+        let Some(index) = current else {
+            self.scrolled_to = None;
+            return;
+        };
+        let Some(hunk) = self.hunks.map_ref(|hunks| hunks.get(index).copied()) else {
+            return;
+        };
+        let Some(hunk) = context
+            .tree
+            .widget(hunk)
+            .and_then(|hunk| hunk.last_layout())
+        else {
+            return;
+        };
+        let Some(view) = self
+            .child
+            .as_mounted(context)
+            .and_then(|view| view.last_layout())
+        else {
+            return;
+        };
+
+        self.scrolled_to = current;
+        self.position.map_mut(|mut position| {
+            // The layout of the change is offset by the current scroll.
+            let top = hunk.origin.y - view.origin.y + position.y.into_signed();
+            position.y = top.max(Px::ZERO).into_unsigned();
+        });
+    }
+}
+
+define_components! {
+    DiffView {
+        /// The background color of lines that were added in a [`DiffView`].
+        DiffAddedBackground(Color, "added_background", .tertiary.container)
+        /// The color of the changed portions of lines that were added in a
+        /// [`DiffView`].
+        DiffAddedEmphasis(Color, "added_emphasis", .tertiary.color)
+        /// The background color of lines that were removed in a [`DiffView`].
+        DiffRemovedBackground(Color, "removed_background", .error.container)
+        /// The color of the changed portions of lines that were removed in a
+        /// [`DiffView`].
+        DiffRemovedEmphasis(Color, "removed_emphasis", .error.color)
+        /// The background color of the empty side of a side-by-side
+        /// [`DiffView`] where a line was only added or removed.
+        DiffMissingBackground(Color, "missing_background", .surface.lowest_container)
+    }
+}
+
+#[test]
+fn diffing() {
+    let lines = diff_lines("a\nb\nc\nd\n", "a\nB\nc\nd\ne\n");
+    assert_eq!(
+        lines
+            .iter()
+            .map(|line| (line.tag, line.old, line.new))
+            .collect::<Vec<_>>(),
+        [
+            (ChangeTag::Equal, Some(0), Some(0)),
+            (ChangeTag::Delete, Some(1), None),
+            (ChangeTag::Insert, None, Some(1)),
+            (ChangeTag::Equal, Some(2), Some(2)),
+            (ChangeTag::Equal, Some(3), Some(3)),
+            (ChangeTag::Insert, None, Some(4)),
+        ]
+    );
+    assert_eq!(lines[4].segments, [(false, String::from("d"))]);
+    assert_eq!(
+        blocks(&lines),
+        [
+            Block::Unchanged(0..1),
+            Block::Changed(1..3),
+            Block::Unchanged(3..5),
+            Block::Changed(5..6),
+        ]
+    );
+}
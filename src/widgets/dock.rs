@@ -0,0 +1,775 @@
+//! A layout of panels that can be rearranged by the user.
+
+use std::mem;
+
+use ahash::AHashSet;
+use figures::units::{Lp, Px};
+use figures::{IntoSigned, Point, Rect, Size, Zero};
+use kludgine::app::winit::event::MouseButton;
+use kludgine::shapes::Shape;
+use kludgine::Color;
+
+use crate::context::{EventContext, GraphicsContext, WidgetContext};
+use crate::reactive::value::{Destination, Dynamic, IntoValue, Source, Switchable, Value};
+use crate::styles::components::HighlightColor;
+use crate::widget::{
+    EventHandling, MakeWidget, MakeWidgetWithTag, WidgetInstance, WidgetList, WidgetRef, WidgetTag,
+    WrapperWidget, IGNORED,
+};
+use crate::widgets::drag::{DragSource, DropTarget};
+use crate::widgets::grid::Orientation;
+use crate::widgets::{Space, Split};
+use crate::window::DeviceId;
+
+/// The opacity the highlight of a drop location is drawn with.
+const DROP_HIGHLIGHT_OPACITY: f32 = 0.3;
+
+/// The smallest ratio a restored layout's divider is placed at.
+const MINIMUM_RATIO: f32 = 0.05;
+
+/// A panel that can be displayed in a [`DockArea`].
+#[derive(Debug, Clone)]
+pub struct DockPanel {
+    id: String,
+    title: Value<String>,
+    contents: WidgetInstance,
+}
+
+impl DockPanel {
+    /// Returns a new panel that displays `contents` in a tab labeled `title`.
+    ///
+    /// `id` uniquely identifies this panel within its [`DockArea`] and is used
+    /// to refer to this panel in a [`DockLayout`].
+    #[must_use]
+    pub fn new(
+        id: impl Into<String>,
+        title: impl IntoValue<String>,
+        contents: impl MakeWidget,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into_value(),
+            contents: contents.make_widget(),
+        }
+    }
+
+    /// Returns the unique id of this panel.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// The arrangement of the panels in a [`DockArea`].
+///
+/// A layout refers to panels by their [id](DockPanel::id), which allows a
+/// layout to be saved and restored independently of the panels' contents.
+/// When the `serde` feature is enabled, this type can be serialized and
+/// deserialized.
+///
+/// ```rust
+/// use cushy::widgets::dock::DockLayout;
+///
+/// let layout = DockLayout::columns(
+///     DockLayout::tabs(["files"]),
+///     DockLayout::rows(
+///         DockLayout::tabs(["editor", "preview"]),
+///         DockLayout::tabs(["terminal"]),
+///     )
+///     .ratio(0.7),
+/// )
+/// .ratio(0.25);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DockLayout {
+    /// A group of panels displayed as tabs.
+    Tabs {
+        /// The ids of the panels in this group, in the order their tabs are
+        /// displayed.
+        panels: Vec<String>,
+        /// The index of the panel currently being displayed.
+        active: usize,
+    },
+    /// An area divided between two layouts.
+    Split {
+        /// The orientation of the two layouts. [`Orientation::Column`]
+        /// places the layouts side by side, while [`Orientation::Row`] places
+        /// `first` above `second`.
+        orientation: Orientation,
+        /// The fraction of the area given to `first`.
+        ratio: f32,
+        /// The layout displayed on the left or top of the area.
+        first: Box<DockLayout>,
+        /// The layout displayed on the right or bottom of the area.
+        second: Box<DockLayout>,
+    },
+}
+
+impl DockLayout {
+    /// Returns a group that displays `panels` as tabs.
+    pub fn tabs<Panels>(panels: Panels) -> Self
+    where
+        Panels: IntoIterator,
+        Panels::Item: Into<String>,
+    {
+        Self::Tabs {
+            panels: panels.into_iter().map(Into::into).collect(),
+            active: 0,
+        }
+    }
+
+    /// Returns a layout that places `first` to the left of `second`, evenly
+    /// dividing the available width.
+    #[must_use]
+    pub fn columns(first: DockLayout, second: DockLayout) -> Self {
+        Self::split(Orientation::Column, first, second)
+    }
+
+    /// Returns a layout that places `first` above `second`, evenly dividing
+    /// the available height.
+    #[must_use]
+    pub fn rows(first: DockLayout, second: DockLayout) -> Self {
+        Self::split(Orientation::Row, first, second)
+    }
+
+    fn split(orientation: Orientation, first: DockLayout, second: DockLayout) -> Self {
+        Self::Split {
+            orientation,
+            ratio: 0.5,
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    /// Sets the fraction of the area given to the first layout of a split
+    /// and returns self.
+    ///
+    /// This function has no effect on [`DockLayout::Tabs`].
+    #[must_use]
+    pub fn ratio(mut self, new_ratio: f32) -> Self {
+        if let Self::Split { ratio, .. } = &mut self {
+            *ratio = new_ratio;
+        }
+        self
+    }
+
+    /// Sets the index of the displayed panel of a tab group and returns self.
+    ///
+    /// This function has no effect on [`DockLayout::Split`].
+    #[must_use]
+    pub fn active(mut self, index: usize) -> Self {
+        if let Self::Tabs { active, .. } = &mut self {
+            *active = index;
+        }
+        self
+    }
+}
+
+/// A widget that arranges [`DockPanel`]s in groups of tabs that can be split
+/// and rearranged by the user.
+///
+/// Dragging a panel's tab onto a tab group shows where the panel will be
+/// placed. Dropping the tab near an edge of a group splits the group, while
+/// dropping it in the center moves the panel into the group. The dividers
+/// between split groups can be dragged to resize them.
+///
+/// The current arrangement can be retrieved with [`DockArea::layout`] and
+/// restored with [`DockArea::set_layout`].
+///
+/// ```rust
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::dock::{DockArea, DockLayout, DockPanel};
+///
+/// let dock = DockArea::new()
+///     .with(DockPanel::new("files", "Files", "File list"))
+///     .with(DockPanel::new("editor", "Editor", "Editor contents"))
+///     .with_layout(&DockLayout::columns(
+///         DockLayout::tabs(["files"]),
+///         DockLayout::tabs(["editor"]),
+///     ));
+///
+/// let saved = dock.layout();
+/// let ui = dock.clone().expand();
+/// dock.set_layout(&saved);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DockArea {
+    state: Dynamic<DockState>,
+}
+
+impl DockArea {
+    /// Returns a new dock area with no panels.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `panel` to this dock area and returns self.
+    #[must_use]
+    pub fn with(self, panel: DockPanel) -> Self {
+        self.push(panel);
+        self
+    }
+
+    /// Adds `panel` to this dock area.
+    ///
+    /// If a panel with the same id already exists, it is replaced by `panel`.
+    /// Otherwise, the panel is added to the first tab group.
+    pub fn push(&self, panel: DockPanel) {
+        let mut state = self.state.lock();
+        if let Some(existing) = state.panels.iter_mut().find(|p| p.id == panel.id) {
+            *existing = panel;
+        } else {
+            state.root.first_group().push(panel.id.clone());
+            state.panels.push(panel);
+        }
+    }
+
+    /// Applies `layout` and returns self.
+    #[must_use]
+    pub fn with_layout(self, layout: &DockLayout) -> Self {
+        self.set_layout(layout);
+        self
+    }
+
+    /// Arranges the panels of this dock area according to `layout`.
+    ///
+    /// Panels in `layout` that have not been added to this dock area are
+    /// ignored. Panels that are not in `layout` are added to the first tab
+    /// group.
+    pub fn set_layout(&self, layout: &DockLayout) {
+        let mut state = self.state.lock();
+        let mut placed = AHashSet::new();
+        let mut root = DockNode::from_layout(layout, &state.panels, &mut placed)
+            .unwrap_or_else(DockNode::empty_group);
+        for panel in &state.panels {
+            if !placed.contains(&panel.id) {
+                root.first_group().push(panel.id.clone());
+            }
+        }
+        state.root = root;
+    }
+
+    /// Returns the current arrangement of the panels in this dock area.
+    #[must_use]
+    pub fn layout(&self) -> DockLayout {
+        self.state.map_ref(|state| state.root.to_layout())
+    }
+
+    fn move_panel(&self, panel: &str, target: &[Branch], zone: DockZone) {
+        let mut state = self.state.lock();
+        // The panel is removed without collapsing empty groups to ensure
+        // `target` still refers to the same group.
+        if !state.root.remove_panel(panel) {
+            return;
+        }
+        if let Some(group) = state.root.node_mut(target) {
+            group.insert(panel.to_string(), zone);
+        }
+        if state.root.collapse() {
+            state.root = DockNode::empty_group();
+        }
+    }
+}
+
+impl MakeWidgetWithTag for DockArea {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        self.state
+            .clone()
+            .switcher(move |state, _| {
+                let mut path = Vec::new();
+                build_node(&self, &state.root, &state.panels, &mut path)
+            })
+            .make_with_tag(tag)
+    }
+}
+
+#[derive(Debug)]
+struct DockState {
+    panels: Vec<DockPanel>,
+    root: DockNode,
+}
+
+impl Default for DockState {
+    fn default() -> Self {
+        Self {
+            panels: Vec::new(),
+            root: DockNode::empty_group(),
+        }
+    }
+}
+
+/// A [`DockLayout`] whose ratios and active tabs are updated by the user.
+#[derive(Debug)]
+enum DockNode {
+    Tabs {
+        panels: Vec<String>,
+        active: Dynamic<usize>,
+    },
+    Split {
+        orientation: Orientation,
+        ratio: Dynamic<f32>,
+        first: Box<DockNode>,
+        second: Box<DockNode>,
+    },
+}
+
+/// A step taken from a [`DockNode::Split`] towards one of its children.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Branch {
+    First,
+    Second,
+}
+
+impl DockNode {
+    fn empty_group() -> Self {
+        Self::Tabs {
+            panels: Vec::new(),
+            active: Dynamic::new(0),
+        }
+    }
+
+    fn from_layout(
+        layout: &DockLayout,
+        known: &[DockPanel],
+        placed: &mut AHashSet<String>,
+    ) -> Option<Self> {
+        match layout {
+            DockLayout::Tabs { panels, active } => {
+                let panels = panels
+                    .iter()
+                    .filter(|id| known.iter().any(|panel| &panel.id == *id))
+                    .filter(|id| placed.insert((*id).clone()))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (!panels.is_empty()).then(|| Self::Tabs {
+                    active: Dynamic::new((*active).min(panels.len() - 1)),
+                    panels,
+                })
+            }
+            DockLayout::Split {
+                orientation,
+                ratio,
+                first,
+                second,
+            } => {
+                let first = Self::from_layout(first, known, placed);
+                let second = Self::from_layout(second, known, placed);
+                match (first, second) {
+                    (Some(first), Some(second)) => Some(Self::Split {
+                        orientation: *orientation,
+                        ratio: Dynamic::new(clamp_ratio(*ratio)),
+                        first: Box::new(first),
+                        second: Box::new(second),
+                    }),
+                    (Some(remaining), None) | (None, Some(remaining)) => Some(remaining),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+
+    fn to_layout(&self) -> DockLayout {
+        match self {
+            DockNode::Tabs { panels, active } => DockLayout::Tabs {
+                panels: panels.clone(),
+                active: active.get(),
+            },
+            DockNode::Split {
+                orientation,
+                ratio,
+                first,
+                second,
+            } => DockLayout::Split {
+                orientation: *orientation,
+                ratio: ratio.get(),
+                first: Box::new(first.to_layout()),
+                second: Box::new(second.to_layout()),
+            },
+        }
+    }
+
+    /// Returns the panels of the first tab group in this node.
+    fn first_group(&mut self) -> &mut Vec<String> {
+        match self {
+            DockNode::Tabs { panels, .. } => panels,
+            DockNode::Split { first, .. } => first.first_group(),
+        }
+    }
+
+    fn node_mut(&mut self, path: &[Branch]) -> Option<&mut DockNode> {
+        let Some((branch, remaining)) = path.split_first() else {
+            return Some(self);
+        };
+        match (self, branch) {
+            (DockNode::Split { first, .. }, Branch::First) => first.node_mut(remaining),
+            (DockNode::Split { second, .. }, Branch::Second) => second.node_mut(remaining),
+            (DockNode::Tabs { .. }, _) => None,
+        }
+    }
+
+    /// Removes `panel` from the group containing it, returning true if the
+    /// panel was found.
+    fn remove_panel(&mut self, panel: &str) -> bool {
+        match self {
+            DockNode::Tabs { panels, active } => {
+                let Some(index) = panels.iter().position(|id| id == panel) else {
+                    return false;
+                };
+                panels.remove(index);
+                let mut active = active.lock();
+                if *active > index || *active >= panels.len() {
+                    *active = active.saturating_sub(1);
+                }
+                true
+            }
+            DockNode::Split { first, second, .. } => {
+                first.remove_panel(panel) || second.remove_panel(panel)
+            }
+        }
+    }
+
+    /// Places `panel` in the `zone` of this node.
+    fn insert(&mut self, panel: String, zone: DockZone) {
+        let orientation = match zone {
+            DockZone::Center => {
+                let DockNode::Tabs { panels, active } = self else {
+                    return;
+                };
+                panels.push(panel);
+                active.set(panels.len() - 1);
+                return;
+            }
+            DockZone::Left | DockZone::Right => Orientation::Column,
+            DockZone::Top | DockZone::Bottom => Orientation::Row,
+        };
+
+        let existing = mem::replace(self, DockNode::empty_group());
+        let new_group = DockNode::Tabs {
+            panels: vec![panel],
+            active: Dynamic::new(0),
+        };
+        let (first, second) = if matches!(zone, DockZone::Left | DockZone::Top) {
+            (new_group, existing)
+        } else {
+            (existing, new_group)
+        };
+        *self = DockNode::Split {
+            orientation,
+            ratio: Dynamic::new(0.5),
+            first: Box::new(first),
+            second: Box::new(second),
+        };
+    }
+
+    /// Replaces splits containing empty groups with their other child.
+    /// Returns true if this node contains no panels.
+    fn collapse(&mut self) -> bool {
+        match self {
+            DockNode::Tabs { panels, .. } => panels.is_empty(),
+            DockNode::Split { first, second, .. } => match (first.collapse(), second.collapse()) {
+                (true, true) => true,
+                (true, false) => {
+                    *self = mem::replace(second, DockNode::empty_group());
+                    false
+                }
+                (false, true) => {
+                    *self = mem::replace(first, DockNode::empty_group());
+                    false
+                }
+                (false, false) => false,
+            },
+        }
+    }
+}
+
+fn clamp_ratio(ratio: f32) -> f32 {
+    if ratio.is_finite() {
+        ratio.clamp(MINIMUM_RATIO, 1. - MINIMUM_RATIO)
+    } else {
+        0.5
+    }
+}
+
+fn build_node(
+    area: &DockArea,
+    node: &DockNode,
+    panels: &[DockPanel],
+    path: &mut Vec<Branch>,
+) -> WidgetInstance {
+    match node {
+        DockNode::Tabs {
+            panels: ids,
+            active,
+        } => {
+            let group = ids
+                .iter()
+                .filter_map(|id| panels.iter().find(|panel| &panel.id == id))
+                .cloned()
+                .collect::<Vec<_>>();
+            build_group(area, group, active, path.clone())
+        }
+        DockNode::Split {
+            orientation,
+            ratio,
+            first,
+            second,
+        } => {
+            path.push(Branch::First);
+            let first = build_node(area, first, panels, path);
+            path.pop();
+            path.push(Branch::Second);
+            let second = build_node(area, second, panels, path);
+            path.pop();
+            Split::new(*orientation, first, second)
+                .ratio(ratio.clone())
+                .default_ratio(0.5)
+                .first_size(Lp::points(48)..)
+                .second_size(Lp::points(48)..)
+                .make_widget()
+        }
+    }
+}
+
+fn build_group(
+    area: &DockArea,
+    panels: Vec<DockPanel>,
+    active: &Dynamic<usize>,
+    path: Vec<Branch>,
+) -> WidgetInstance {
+    let tabs = panels
+        .iter()
+        .enumerate()
+        .map(|(index, panel)| {
+            DragSource::new(
+                DockTab {
+                    child: WidgetRef::new(panel.title.clone().pad()),
+                    index,
+                    active: active.clone(),
+                },
+                DockDrag {
+                    area: area.state.clone(),
+                    panel: panel.id.clone(),
+                },
+            )
+        })
+        .collect::<WidgetList>()
+        .into_columns();
+    let ids = panels
+        .iter()
+        .map(|panel| panel.id.clone())
+        .collect::<Vec<_>>();
+    let contents = active.clone().switcher(move |active, _| {
+        panels.get(*active).map_or_else(
+            || Space::clear().make_widget(),
+            |panel| panel.contents.clone(),
+        )
+    });
+
+    let size = Dynamic::new(Size::ZERO);
+    let zone = Dynamic::new(None);
+    DropTarget::new(DockGroup {
+        child: WidgetRef::new(tabs.and(contents.expand()).into_rows()),
+        size: size.clone(),
+        zone: zone.clone(),
+    })
+    .on_hover({
+        let state = area.state.clone();
+        let size = size.clone();
+        let zone = zone.clone();
+        move |payload: &DockDrag, location| {
+            let hovered = DockZone::at(location, size.get());
+            // Splitting a group with its only panel would leave an empty
+            // group behind.
+            let accepted = payload.area == state
+                && (hovered == DockZone::Center || ids.iter().any(|id| id != &payload.panel));
+            zone.set(accepted.then_some(hovered));
+            accepted
+        }
+    })
+    .on_leave({
+        let zone = zone.clone();
+        move |()| zone.set(None)
+    })
+    .on_drop({
+        let area = area.clone();
+        move |payload: DockDrag, location| {
+            zone.set(None);
+            area.move_panel(&payload.panel, &path, DockZone::at(location, size.get()));
+        }
+    })
+    .make_widget()
+}
+
+/// The payload of a [`DragSource`] for a panel's tab.
+#[derive(Debug, Clone)]
+struct DockDrag {
+    area: Dynamic<DockState>,
+    panel: String,
+}
+
+/// A location within a tab group that a panel can be dropped onto.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum DockZone {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Center,
+}
+
+impl DockZone {
+    /// Returns the zone containing `location` within an area of `size`.
+    ///
+    /// Each edge zone covers the quarter of the area closest to that edge.
+    fn at(location: Point<Px>, size: Size<Px>) -> Self {
+        let x = location.x.into_float() / size.width.into_float().max(1.);
+        let y = location.y.into_float() / size.height.into_float().max(1.);
+        let edges = [
+            (x, DockZone::Left),
+            (1. - x, DockZone::Right),
+            (y, DockZone::Top),
+            (1. - y, DockZone::Bottom),
+        ];
+        edges
+            .into_iter()
+            .filter(|(distance, _)| *distance < 0.25)
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map_or(DockZone::Center, |(_, zone)| zone)
+    }
+
+    /// Returns the area within `size` that a panel dropped in this zone will
+    /// occupy.
+    fn area(self, size: Size<Px>) -> Rect<Px> {
+        let half = Size::new(size.width / 2, size.height / 2);
+        match self {
+            DockZone::Left => Rect::new(Point::ZERO, Size::new(half.width, size.height)),
+            DockZone::Right => Rect::new(
+                Point::new(half.width, Px::ZERO),
+                Size::new(size.width - half.width, size.height),
+            ),
+            DockZone::Top => Rect::new(Point::ZERO, Size::new(size.width, half.height)),
+            DockZone::Bottom => Rect::new(
+                Point::new(Px::ZERO, half.height),
+                Size::new(size.width, size.height - half.height),
+            ),
+            DockZone::Center => Rect::from(size),
+        }
+    }
+}
+
+/// A tab group that highlights where a dragged panel will be placed.
+#[derive(Debug)]
+struct DockGroup {
+    child: WidgetRef,
+    size: Dynamic<Size<Px>>,
+    zone: Dynamic<Option<DockZone>>,
+}
+
+impl WrapperWidget for DockGroup {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn redraw_foreground(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let size = context.gfx.size().into_signed();
+        self.size.set(size);
+        if let Some(zone) = self.zone.get_tracking_redraw(context) {
+            let color = context.get(&HighlightColor);
+            context.gfx.draw_shape(&Shape::filled_rect(
+                zone.area(size),
+                color.with_alpha_f32(color.alpha_f32() * DROP_HIGHLIGHT_OPACITY),
+            ));
+        }
+    }
+}
+
+/// The tab of a panel, which displays the panel when pressed.
+#[derive(Debug)]
+struct DockTab {
+    child: WidgetRef,
+    index: usize,
+    active: Dynamic<usize>,
+}
+
+impl WrapperWidget for DockTab {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn background_color(&mut self, context: &WidgetContext<'_>) -> Option<Color> {
+        if self.active.get_tracking_redraw(context) == self.index {
+            Some(context.get(&ActiveTabBackground))
+        } else {
+            Some(context.get(&TabBackground))
+        }
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn mouse_down(
+        &mut self,
+        _location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        _context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button == MouseButton::Left {
+            self.active.set(self.index);
+        }
+        // The event is ignored so that the tab's drag source can begin a
+        // drag.
+        IGNORED
+    }
+}
+
+define_components! {
+    Dock {
+        /// The background color of the tab of the panel being displayed in a
+        /// tab group.
+        ActiveTabBackground(Color, "active_tab_background", .surface.highest_container)
+        /// The background color of the tabs of panels that are not being
+        /// displayed.
+        TabBackground(Color, "tab_background", .surface.low_container)
+    }
+}
+
+#[test]
+fn layout_round_trip() {
+    let area = DockArea::new()
+        .with(DockPanel::new("a", "A", "A"))
+        .with(DockPanel::new("b", "B", "B"))
+        .with(DockPanel::new("c", "C", "C"));
+    assert_eq!(area.layout(), DockLayout::tabs(["a", "b", "c"]));
+
+    let layout = DockLayout::columns(
+        DockLayout::tabs(["a", "unknown"]),
+        DockLayout::tabs(["b"]).active(3),
+    )
+    .ratio(0.25);
+    area.set_layout(&layout);
+    assert_eq!(
+        area.layout(),
+        DockLayout::columns(DockLayout::tabs(["a", "c"]), DockLayout::tabs(["b"])).ratio(0.25)
+    );
+}
+
+#[test]
+fn moving_panels() {
+    let area = DockArea::new()
+        .with(DockPanel::new("a", "A", "A"))
+        .with(DockPanel::new("b", "B", "B"));
+
+    area.move_panel("b", &[], DockZone::Bottom);
+    assert_eq!(
+        area.layout(),
+        DockLayout::rows(DockLayout::tabs(["a"]), DockLayout::tabs(["b"]))
+    );
+
+    // Moving the only panel out of a group collapses the split.
+    area.move_panel("b", &[Branch::First], DockZone::Center);
+    assert_eq!(area.layout(), DockLayout::tabs(["a", "b"]).active(1));
+}
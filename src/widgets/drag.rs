@@ -0,0 +1,615 @@
+//! Widgets for dragging values from one widget and dropping them onto
+//! another.
+
+use std::fmt::{self, Debug};
+use std::mem;
+use std::path::PathBuf;
+
+use figures::units::{Lp, Px};
+use figures::{Abs, IntoSigned, Point, Rect, Round, ScreenScale, Size};
+use kludgine::app::winit::event::MouseButton;
+use kludgine::app::winit::window::CursorIcon;
+use kludgine::shapes::{Shape, StrokeOptions};
+
+use crate::animation::ZeroToOne;
+use crate::context::{EventContext, GraphicsContext, LayoutContext};
+use crate::reactive::value::{IntoValue, Source, Value};
+use crate::styles::components::{HighlightColor, OutlineWidth};
+use crate::widget::{
+    Callback, EventHandling, MakeWidget, MountedWidget, WidgetId, WidgetRef, WrapperWidget,
+    HANDLED, IGNORED,
+};
+use crate::window::{DeviceId, DropEvent};
+use crate::ConstraintLimit;
+
+/// The opacity the drag ghost is drawn with.
+const GHOST_OPACITY: f32 = 0.75;
+
+/// A widget that allows dragging a value of type `T` to a [`DropTarget<T>`].
+///
+/// A drag begins when the primary mouse button is pressed on this widget and
+/// the cursor is moved a short distance. While dragging, a ghost follows the
+/// cursor. By default, the ghost is a translucent rectangle the size of this
+/// widget, but any widget can be used by calling [`Self::ghost()`].
+///
+/// Releasing the mouse button over a [`DropTarget<T>`] that accepts the
+/// payload invokes the target's [`on_drop`](DropTarget::on_drop) callback.
+///
+/// ```rust
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::drag::{DragSource, DropTarget};
+///
+/// let source = DragSource::new("Drag Me", String::from("Hello"));
+/// let target = DropTarget::<String>::new("Drop Here").on_drop(|payload, _location| {
+///     println!("Dropped {payload}");
+/// });
+/// let ui = source.and(target).into_columns();
+/// ```
+pub struct DragSource<T> {
+    child: WidgetRef,
+    payload: Value<T>,
+    ghost: Option<WidgetRef>,
+    on_drag_end: Option<Callback<bool>>,
+    state: DragState,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+enum DragState {
+    #[default]
+    Idle,
+    Pressed {
+        start: Point<Px>,
+    },
+    Dragging {
+        target: Option<WidgetId>,
+        accepted: bool,
+    },
+}
+
+impl<T> DragSource<T>
+where
+    T: Clone + Send + 'static,
+{
+    /// Returns a widget that allows dragging `payload` by dragging `child`.
+    #[must_use]
+    pub fn new(child: impl MakeWidget, payload: impl IntoValue<T>) -> Self {
+        Self {
+            child: WidgetRef::new(child),
+            payload: payload.into_value(),
+            ghost: None,
+            on_drag_end: None,
+            state: DragState::Idle,
+        }
+    }
+
+    /// Displays `ghost` beneath the cursor while dragging and returns self.
+    #[must_use]
+    pub fn ghost(mut self, ghost: impl MakeWidget) -> Self {
+        self.ghost = Some(WidgetRef::new(ghost));
+        self
+    }
+
+    /// Invokes `on_drag_end` when a drag from this widget ends and returns
+    /// self.
+    ///
+    /// The callback is invoked with true if the payload was dropped on a
+    /// [`DropTarget`] that accepted it.
+    #[must_use]
+    pub fn on_drag_end<F>(mut self, on_drag_end: F) -> Self
+    where
+        F: FnMut(bool) + Send + 'static,
+    {
+        self.on_drag_end = Some(Callback::new(on_drag_end));
+        self
+    }
+
+    fn find_target(
+        &self,
+        window_location: Point<Px>,
+        context: &mut EventContext<'_>,
+    ) -> Option<MountedWidget> {
+        let source = context.widget().id();
+        context
+            .tree
+            .widgets_under_point(window_location)
+            .into_iter()
+            .find(|widget| {
+                widget.id() != source && widget.lock().downcast_ref::<DropTarget<T>>().is_some()
+            })
+    }
+
+    fn update_target(&mut self, window_location: Point<Px>, context: &mut EventContext<'_>) {
+        let DragState::Dragging { target, .. } = self.state else {
+            return;
+        };
+        let new_target = self.find_target(window_location, context);
+        let new_target_id = new_target.as_ref().map(MountedWidget::id);
+        if target != new_target_id {
+            if let Some(previous) = target.and_then(|id| context.tree.widget(id)) {
+                with_target::<T, _>(&previous, context, |target, context| {
+                    target.drag_left(context);
+                });
+            }
+        }
+
+        let accepted = new_target.as_ref().is_some_and(|new_target| {
+            self.payload.map(|payload| {
+                with_target::<T, _>(new_target, context, |target, context| {
+                    let location =
+                        window_location - context.last_layout().unwrap_or_default().origin;
+                    target.drag_over(payload, location, context)
+                })
+                .unwrap_or(false)
+            })
+        });
+        if let Some(drag) = &mut context.cursor.drag {
+            drag.location = window_location;
+        }
+        self.state = DragState::Dragging {
+            target: new_target_id,
+            accepted,
+        };
+    }
+
+    fn end_drag(&mut self, dropped: bool, context: &mut EventContext<'_>) {
+        if !matches!(self.state, DragState::Dragging { .. }) {
+            self.state = DragState::Idle;
+            return;
+        }
+        self.state = DragState::Idle;
+
+        context.cursor.drag = None;
+        if let Some(ghost) = &mut self.ghost {
+            ghost.unmount_in(context);
+        }
+        context.set_needs_redraw();
+
+        if let Some(on_drag_end) = &mut self.on_drag_end {
+            on_drag_end.invoke(dropped);
+        }
+    }
+}
+
+impl<T> Debug for DragSource<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DragSource")
+            .field("child", &self.child)
+            .field("payload", &self.payload)
+            .field("ghost", &self.ghost)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> WrapperWidget for DragSource<T>
+where
+    T: Clone + Debug + Send + 'static,
+{
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn hover(
+        &mut self,
+        _location: Point<Px>,
+        _context: &mut EventContext<'_>,
+    ) -> Option<CursorIcon> {
+        Some(CursorIcon::Grab)
+    }
+
+    fn mouse_down(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        _context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button == MouseButton::Left {
+            self.state = DragState::Pressed { start: location };
+            HANDLED
+        } else {
+            IGNORED
+        }
+    }
+
+    fn mouse_drag(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) {
+        if button != MouseButton::Left {
+            return;
+        }
+        let Some(layout) = context.last_layout() else {
+            return;
+        };
+        let window_location = layout.origin + location;
+        match self.state {
+            DragState::Idle => return,
+            DragState::Pressed { start } => {
+                // The distance the cursor must move before a drag begins.
+                let threshold = Lp::points(4).into_px(context.kludgine.scale()).ceil();
+                let delta = (location - start).abs();
+                if delta.x < threshold && delta.y < threshold {
+                    return;
+                }
+
+                let ghost = self.ghost.as_mut().map(|ghost| ghost.mounted(context));
+                context.cursor.drag = Some(ActiveDrag {
+                    source_size: layout.size,
+                    ghost,
+                    grab_offset: start,
+                    location: window_location,
+                });
+                self.state = DragState::Dragging {
+                    target: None,
+                    accepted: false,
+                };
+            }
+            DragState::Dragging { .. } => {}
+        }
+
+        self.update_target(window_location, context);
+        context.set_needs_redraw();
+    }
+
+    fn mouse_up(
+        &mut self,
+        _location: Option<Point<Px>>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) {
+        if button != MouseButton::Left {
+            return;
+        }
+
+        let mut dropped = false;
+        let drag_location = context.cursor.drag.as_ref().map(|drag| drag.location);
+        if let (DragState::Dragging { target, accepted }, Some(window_location)) =
+            (self.state, drag_location)
+        {
+            if let Some(target) = target.and_then(|id| context.tree.widget(id)) {
+                with_target::<T, _>(&target, context, |target, context| {
+                    if accepted {
+                        let location =
+                            window_location - context.last_layout().unwrap_or_default().origin;
+                        dropped = target.drop_payload(self.payload.get(), location, context);
+                    }
+                    target.drag_left(context);
+                });
+            }
+        }
+
+        self.end_drag(dropped, context);
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        self.end_drag(false, context);
+        self.child.unmount_in(context);
+    }
+}
+
+fn with_target<T, R>(
+    target: &MountedWidget,
+    context: &mut EventContext<'_>,
+    callback: impl FnOnce(&mut DropTarget<T>, &mut EventContext<'_>) -> R,
+) -> Option<R>
+where
+    T: 'static,
+{
+    let mut target_context = context.for_other(target);
+    let mut widget = target.lock();
+    let drop_target = widget.downcast_mut::<DropTarget<T>>()?;
+    Some(callback(drop_target, &mut target_context))
+}
+
+/// A widget that accepts values of type `T` dropped from a [`DragSource<T>`].
+///
+/// While a payload is dragged over this widget and accepted, this widget is
+/// outlined using [`HighlightColor`].
+///
+/// Files dragged from the operating system can be accepted using a
+/// [`FileDropTarget`].
+pub struct DropTarget<T> {
+    child: WidgetRef,
+    on_hover: Option<Box<dyn FnMut(&T, Point<Px>) -> bool + Send>>,
+    on_leave: Option<Callback>,
+    on_drop: Option<Box<dyn FnMut(T, Point<Px>) + Send>>,
+    hovering: Option<bool>,
+}
+
+impl<T> DropTarget<T>
+where
+    T: Send + 'static,
+{
+    /// Returns a widget that accepts values dropped onto `child`.
+    #[must_use]
+    pub fn new(child: impl MakeWidget) -> Self {
+        Self {
+            child: WidgetRef::new(child),
+            on_hover: None,
+            on_leave: None,
+            on_drop: None,
+            hovering: None,
+        }
+    }
+
+    /// Invokes `on_hover` as a payload is dragged over this widget and returns
+    /// self.
+    ///
+    /// The callback receives the payload and the location of the cursor
+    /// relative to this widget. If the callback returns false, the payload
+    /// cannot be dropped at that location. When no callback is provided, all
+    /// payloads are accepted.
+    #[must_use]
+    pub fn on_hover<F>(mut self, on_hover: F) -> Self
+    where
+        F: FnMut(&T, Point<Px>) -> bool + Send + 'static,
+    {
+        self.on_hover = Some(Box::new(on_hover));
+        self
+    }
+
+    /// Invokes `on_leave` when a dragged payload leaves this widget or the
+    /// drag ends and returns self.
+    #[must_use]
+    pub fn on_leave<F>(mut self, on_leave: F) -> Self
+    where
+        F: FnMut(()) + Send + 'static,
+    {
+        self.on_leave = Some(Callback::new(on_leave));
+        self
+    }
+
+    /// Invokes `on_drop` when a payload is dropped onto this widget and
+    /// returns self.
+    ///
+    /// The callback receives the payload and the location of the cursor
+    /// relative to this widget.
+    #[must_use]
+    pub fn on_drop<F>(mut self, on_drop: F) -> Self
+    where
+        F: FnMut(T, Point<Px>) + Send + 'static,
+    {
+        self.on_drop = Some(Box::new(on_drop));
+        self
+    }
+
+    fn drag_over(
+        &mut self,
+        payload: &T,
+        location: Point<Px>,
+        context: &mut EventContext<'_>,
+    ) -> bool {
+        self.drag_over_all([payload], location, context)
+    }
+
+    fn drag_over_all<'a>(
+        &mut self,
+        payloads: impl IntoIterator<Item = &'a T>,
+        location: Point<Px>,
+        context: &mut EventContext<'_>,
+    ) -> bool
+    where
+        T: 'a,
+    {
+        let accepted = payloads.into_iter().all(|payload| {
+            self.on_hover
+                .as_mut()
+                .map_or(true, |on_hover| on_hover(payload, location))
+        });
+        if self.hovering != Some(accepted) {
+            self.hovering = Some(accepted);
+            context.set_needs_redraw();
+        }
+        accepted
+    }
+
+    fn drag_left(&mut self, context: &mut EventContext<'_>) {
+        if self.hovering.take().is_some() {
+            context.set_needs_redraw();
+            if let Some(on_leave) = &mut self.on_leave {
+                on_leave.invoke(());
+            }
+        }
+    }
+
+    fn drop_payload(
+        &mut self,
+        payload: T,
+        location: Point<Px>,
+        _context: &mut EventContext<'_>,
+    ) -> bool {
+        if let Some(on_drop) = &mut self.on_drop {
+            on_drop(payload, location);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<T> Debug for DropTarget<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DropTarget")
+            .field("child", &self.child)
+            .field("hovering", &self.hovering)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> WrapperWidget for DropTarget<T>
+where
+    T: Send + 'static,
+{
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn redraw_foreground(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        if self.hovering == Some(true) {
+            let color = context.get(&HighlightColor);
+            let width = context
+                .get(&OutlineWidth)
+                .into_px(context.gfx.scale())
+                .ceil();
+            context.stroke_outline(color, StrokeOptions::px_wide(width));
+        }
+    }
+}
+
+/// A widget that accepts files dragged from the operating system.
+///
+/// While files are dragged over this widget and accepted by its
+/// [`on_hover`](DropTarget::on_hover) callback, this widget is outlined using
+/// [`HighlightColor`]. When multiple files are dragged at once, they are only
+/// accepted if the callback accepts every file, and
+/// [`on_drop`](DropTarget::on_drop) is invoked once for each dropped file.
+///
+/// The target is located using the last known position of the cursor. Some
+/// platforms do not report the cursor's position while files are dragged over
+/// a window, in which case files can only be dropped on targets beneath the
+/// cursor's position when it last moved within the window. Files dropped
+/// elsewhere can be handled using
+/// [`Window::on_file_drop`](crate::window::Window::on_file_drop).
+///
+/// ```rust
+/// use cushy::widgets::drag::FileDropTarget;
+///
+/// let target = FileDropTarget::new("Drop images here")
+///     .on_hover(|path, _location| path.extension().is_some_and(|ext| ext == "png"))
+///     .on_drop(|path, _location| println!("Dropped {}", path.display()));
+/// ```
+pub type FileDropTarget = DropTarget<PathBuf>;
+
+/// Files from the operating system that are being dragged over a window.
+#[derive(Debug, Default)]
+pub(crate) struct FileHover {
+    paths: Vec<PathBuf>,
+    target: Option<WidgetId>,
+}
+
+/// Updates the [`FileDropTarget`]s of a window in response to `event`.
+pub(crate) fn handle_file_drop(event: DropEvent<PathBuf>, context: &mut EventContext<'_>) {
+    match event {
+        DropEvent::Hover(path) => {
+            context.cursor.file_hover.paths.push(path);
+            update_file_hover(context);
+        }
+        DropEvent::Dropped(path) => {
+            context
+                .cursor
+                .file_hover
+                .paths
+                .retain(|hovered| hovered != &path);
+            if let Some((target, location)) = file_target_under_cursor(context) {
+                with_target::<PathBuf, _>(&target, context, |target, context| {
+                    if target.drag_over(&path, location, context) {
+                        target.drop_payload(path, location, context);
+                    }
+                    target.drag_left(context);
+                });
+            }
+            if context.cursor.file_hover.paths.is_empty() {
+                leave_file_target(context);
+            }
+        }
+        DropEvent::Cancelled => {
+            context.cursor.file_hover.paths.clear();
+            leave_file_target(context);
+        }
+    }
+}
+
+/// Updates the [`FileDropTarget`] beneath the cursor while files are dragged
+/// over a window.
+pub(crate) fn update_file_hover(context: &mut EventContext<'_>) {
+    if context.cursor.file_hover.paths.is_empty() {
+        return;
+    }
+
+    let target = file_target_under_cursor(context);
+    let target_id = target.as_ref().map(|(target, _)| target.id());
+    if context.cursor.file_hover.target != target_id {
+        leave_file_target(context);
+    }
+    if let Some((target, location)) = target {
+        let paths = mem::take(&mut context.cursor.file_hover.paths);
+        with_target::<PathBuf, _>(&target, context, |target, context| {
+            target.drag_over_all(&paths, location, context);
+        });
+        context.cursor.file_hover.paths = paths;
+        context.cursor.file_hover.target = target_id;
+    }
+}
+
+fn leave_file_target(context: &mut EventContext<'_>) {
+    if let Some(previous) = context
+        .cursor
+        .file_hover
+        .target
+        .take()
+        .and_then(|id| context.tree.widget(id))
+    {
+        with_target::<PathBuf, _>(&previous, context, |target, context| {
+            target.drag_left(context);
+        });
+    }
+}
+
+fn file_target_under_cursor(context: &EventContext<'_>) -> Option<(MountedWidget, Point<Px>)> {
+    let location = context.cursor.location?;
+    let target = context
+        .tree
+        .widgets_under_point(location)
+        .into_iter()
+        .find(|widget| widget.lock().downcast_ref::<FileDropTarget>().is_some())?;
+    let relative = context.tree.window_to_widget(target.node_id, location)?;
+    Some((target, relative))
+}
+
+/// The state of a drag started by a [`DragSource`].
+#[derive(Debug)]
+pub(crate) struct ActiveDrag {
+    source_size: Size<Px>,
+    ghost: Option<MountedWidget>,
+    grab_offset: Point<Px>,
+    location: Point<Px>,
+}
+
+/// Draws the ghost of the active drag, if any, beneath the cursor.
+pub(crate) fn render_drag_ghost(context: &mut LayoutContext<'_, '_, '_, '_>) {
+    let Some(drag) = &context.cursor.drag else {
+        return;
+    };
+    let origin = drag.location - drag.grab_offset;
+    let source_size = drag.source_size;
+    if let Some(ghost) = drag.ghost.clone() {
+        let available = context.gfx.size().map(ConstraintLimit::SizeToFit);
+        let size = context.for_other(&ghost).layout(available).into_signed();
+        context.set_child_layout(&ghost, Rect::new(origin, size));
+        let mut ghost_context = context.for_other(&ghost);
+        ghost_context.apply_opacity(ZeroToOne::new(GHOST_OPACITY));
+        ghost_context.redraw();
+    } else {
+        let color = context.get(&HighlightColor);
+        let area = Rect::new(origin, source_size);
+        context.gfx.draw_shape(&Shape::filled_rect(
+            area,
+            color.with_alpha_f32(color.alpha_f32() * GHOST_OPACITY / 2.),
+        ));
+        context.gfx.draw_shape(&Shape::stroked_rect(
+            area,
+            StrokeOptions::px_wide(Px::new(1)).colored(color),
+        ));
+    }
+}
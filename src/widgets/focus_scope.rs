@@ -0,0 +1,273 @@
+//! A widget that controls how keyboard focus moves within its contents.
+
+use figures::units::Px;
+use figures::Size;
+
+use crate::context::{EventContext, LayoutContext};
+use crate::widget::{MakeWidget, WidgetId, WidgetRef, WrappedLayout, WrapperWidget};
+use crate::ConstraintLimit;
+
+/// A widget that manages keyboard focus for its child.
+///
+/// A focus scope can:
+///
+/// - [Trap focus](Self::trap_focus), causing Tab and Shift+Tab to wrap
+///   around within the scope rather than moving to widgets outside of it.
+/// - Focus its contents when it is mounted. By default, the first focusable
+///   widget is focused, but a specific widget can be chosen using
+///   [`initial_focus()`](Self::initial_focus).
+/// - Restore focus to the widget that was focused before the scope was
+///   mounted once the scope is unmounted. This is enabled by default, and can
+///   be controlled using [`restore_focus()`](Self::restore_focus).
+///
+/// ```rust
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::FocusScope;
+///
+/// let dialog = "Name"
+///     .and("Cancel".into_button())
+///     .and("OK".into_button())
+///     .into_rows()
+///     .focus_scope()
+///     .trap_focus()
+///     .focus_on_mount();
+/// ```
+#[derive(Debug)]
+pub struct FocusScope {
+    child: WidgetRef,
+    trap: bool,
+    restore: bool,
+    focus_on_mount: bool,
+    initial_focus: Option<WidgetId>,
+    previous_focus: Option<WidgetId>,
+    focus_pending: bool,
+}
+
+impl FocusScope {
+    /// Returns a new focus scope containing `child`.
+    #[must_use]
+    pub fn new(child: impl MakeWidget) -> Self {
+        Self {
+            child: WidgetRef::new(child),
+            trap: false,
+            restore: true,
+            focus_on_mount: false,
+            initial_focus: None,
+            previous_focus: None,
+            focus_pending: false,
+        }
+    }
+
+    /// Prevents keyboard focus from leaving this scope using Tab or Shift+Tab
+    /// and returns self.
+    ///
+    /// Focus can still be moved outside of the scope by clicking another
+    /// widget or by programmatically focusing it.
+    #[must_use]
+    pub fn trap_focus(mut self) -> Self {
+        self.trap = true;
+        self
+    }
+
+    /// Sets whether focus is restored to the previously focused widget when
+    /// this scope is unmounted, and returns self.
+    ///
+    /// Focus is only restored if the focused widget is within this scope, or
+    /// if no widget is focused, when the scope is unmounted. This is enabled
+    /// by default.
+    #[must_use]
+    pub fn restore_focus(mut self, restore: bool) -> Self {
+        self.restore = restore;
+        self
+    }
+
+    /// Focuses the first focusable widget in this scope when it is mounted
+    /// and returns self.
+    #[must_use]
+    pub fn focus_on_mount(mut self) -> Self {
+        self.focus_on_mount = true;
+        self
+    }
+
+    /// Focuses `widget` when this scope is mounted and returns self.
+    ///
+    /// `widget` should be contained within this scope. If it is not mounted
+    /// when this scope is first laid out, the first focusable widget in this
+    /// scope is focused instead.
+    #[must_use]
+    pub fn initial_focus(mut self, widget: impl Into<WidgetId>) -> Self {
+        self.initial_focus = Some(widget.into());
+        self.focus_on_mount = true;
+        self
+    }
+}
+
+impl WrapperWidget for FocusScope {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn mounted(&mut self, context: &mut EventContext<'_>) {
+        context
+            .tree
+            .set_focus_trap(context.widget().node_id, self.trap);
+        self.previous_focus = context
+            .tree
+            .focused_widget()
+            .and_then(|focused| context.tree.widget_from_node(focused))
+            .map(|focused| focused.id());
+        self.focus_pending = self.focus_on_mount;
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        if !self.restore {
+            return;
+        }
+        let Some(previous) = self
+            .previous_focus
+            .take()
+            .and_then(|previous| context.tree.widget(previous))
+        else {
+            return;
+        };
+
+        let focus_within = context.tree.focused_widget().map_or(true, |focused| {
+            context.tree.is_child(focused, context.widget().instance())
+        });
+        if focus_within {
+            context.for_other(&previous).focus();
+        }
+    }
+
+    fn position_child(
+        &mut self,
+        size: Size<Px>,
+        _available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> WrappedLayout {
+        if self.focus_pending {
+            self.focus_pending = false;
+            let target = self
+                .initial_focus
+                .and_then(|id| context.tree.widget(id))
+                .unwrap_or_else(|| self.child.mounted(context));
+            context.for_other(&target).focus();
+        }
+
+        WrappedLayout::from(size)
+    }
+}
+
+/// Returns a focusable widget that stores `name` in `focused` when it
+/// receives focus.
+#[cfg(test)]
+pub(crate) fn focus_probe(
+    name: &'static str,
+    focused: &crate::reactive::value::Dynamic<Option<&'static str>>,
+) -> crate::widget::WidgetInstance {
+    use figures::units::Lp;
+
+    use crate::reactive::value::Destination;
+    use crate::widgets::Custom;
+
+    let focused = focused.clone();
+    Custom::empty()
+        .on_accept_focus(|_| true)
+        .on_focus(move |_| focused.set(Some(name)))
+        .size(Size::squared(Lp::points(12)))
+        .make_widget()
+}
+
+#[cfg(test)]
+pub(crate) fn press_tab(recorder: &mut crate::window::VirtualRecorder) {
+    use kludgine::app::winit::event::{ElementState, Modifiers};
+    use kludgine::app::winit::keyboard::{Key, KeyCode, KeyLocation, NamedKey, PhysicalKey};
+
+    use crate::window::{DeviceId, KeyEvent};
+
+    for state in [ElementState::Pressed, ElementState::Released] {
+        let _handled = recorder.window.keyboard_input(
+            DeviceId::Virtual(0),
+            KeyEvent {
+                physical_key: PhysicalKey::Code(KeyCode::Tab),
+                logical_key: Key::Named(NamedKey::Tab),
+                text: None,
+                location: KeyLocation::Standard,
+                state,
+                repeat: false,
+                modifiers: Modifiers::default(),
+            },
+            true,
+        );
+    }
+    recorder.refresh().expect("error rendering");
+}
+
+#[test]
+fn trapped_scopes_focus_on_mount_and_wrap() {
+    use crate::reactive::value::{Dynamic, Source};
+
+    let focused = Dynamic::new(None);
+    let mut recorder = focus_probe("outside", &focused)
+        .and(
+            focus_probe("first", &focused)
+                .and(focus_probe("second", &focused))
+                .into_rows()
+                .focus_scope()
+                .trap_focus()
+                .focus_on_mount(),
+        )
+        .into_rows()
+        .build_recorder()
+        .finish()
+        .expect("error creating recorder");
+    recorder.refresh().expect("error rendering");
+    assert_eq!(focused.get(), Some("first"));
+
+    press_tab(&mut recorder);
+    assert_eq!(focused.get(), Some("second"));
+    // Tab wraps within the scope rather than reaching the outside widget.
+    press_tab(&mut recorder);
+    assert_eq!(focused.get(), Some("first"));
+}
+
+#[test]
+fn scopes_restore_focus_when_unmounted() {
+    use crate::reactive::value::{Destination, Dynamic, Source, Switchable};
+    use crate::widgets::Space;
+
+    let focused = Dynamic::new(None);
+    let show_dialog = Dynamic::new(false);
+    let mut recorder = focus_probe("main", &focused)
+        .focus_scope()
+        .focus_on_mount()
+        .and(show_dialog.switcher({
+            let focused = focused.clone();
+            move |show, _| {
+                if *show {
+                    focus_probe("dialog", &focused)
+                        .focus_scope()
+                        .focus_on_mount()
+                        .make_widget()
+                } else {
+                    Space::clear().make_widget()
+                }
+            }
+        }))
+        .into_rows()
+        .build_recorder()
+        .finish()
+        .expect("error creating recorder");
+    recorder.refresh().expect("error rendering");
+    assert_eq!(focused.get(), Some("main"));
+
+    show_dialog.set(true);
+    recorder.refresh().expect("error rendering");
+    recorder.refresh().expect("error rendering");
+    assert_eq!(focused.get(), Some("dialog"));
+
+    show_dialog.set(false);
+    recorder.refresh().expect("error rendering");
+    recorder.refresh().expect("error rendering");
+    assert_eq!(focused.get(), Some("main"));
+}
@@ -0,0 +1,223 @@
+//! A circular gauge that displays a value within a range.
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use figures::units::{Lp, Px, UPx};
+use figures::{Angle, FloatConversion, Point, ScreenScale, Size};
+use intentional::Cast;
+use kludgine::shapes::{Path, PathBuilder, Shape, StrokeOptions};
+use kludgine::{Color, DrawableExt, Origin};
+
+use crate::animation::easings::EaseOutQuadradic;
+use crate::animation::{AnimationHandle, AnimationTarget, IntoAnimate, Spawn};
+use crate::context::{GraphicsContext, LayoutContext};
+use crate::reactive::value::{Destination, Dynamic, IntoValue, Source, Value};
+use crate::styles::components::TextColor;
+use crate::styles::Dimension;
+use crate::widget::Widget;
+use crate::ConstraintLimit;
+
+/// The angle the track of a [`Gauge`] starts at, measured clockwise from the
+/// positive x axis.
+const START_DEGREES: f32 = 135.;
+
+/// The number of degrees the track of a [`Gauge`] spans.
+const SWEEP_DEGREES: f32 = 270.;
+
+/// How long the needle of a [`Gauge`] takes to move to a new value.
+const NEEDLE_DURATION: Duration = Duration::from_millis(250);
+
+/// A circular gauge that displays a value using a needle.
+///
+/// The gauge's track spans three quarters of a circle, starting at the
+/// bottom-left for the minimum value and ending at the bottom-right for the
+/// maximum value. Portions of the track can be colored using
+/// [zones](Self::zone). When the value changes, the needle animates to its new
+/// position.
+///
+/// ```rust
+/// use cushy::kludgine::Color;
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::Gauge;
+///
+/// let temperature = Dynamic::new(72.);
+/// let gauge = Gauge::new(temperature)
+///     .range(32.0..=212.)
+///     .zone(160.0..=212., Color::RED);
+/// ```
+#[derive(Debug)]
+pub struct Gauge {
+    value: Value<f64>,
+    range: RangeInclusive<f64>,
+    zones: Vec<(RangeInclusive<f64>, Color)>,
+    needle: Dynamic<f64>,
+    target: Option<f64>,
+    animation: AnimationHandle,
+}
+
+impl Gauge {
+    /// Returns a gauge that displays `value` within `0.0..=100.0`.
+    #[must_use]
+    pub fn new(value: impl IntoValue<f64>) -> Self {
+        Self {
+            value: value.into_value(),
+            range: 0.0..=100.,
+            zones: Vec::new(),
+            needle: Dynamic::new(0.),
+            target: None,
+            animation: AnimationHandle::new(),
+        }
+    }
+
+    /// Sets the range of values the gauge displays and returns self.
+    #[must_use]
+    pub fn range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Colors the portion of the track covering `range` with `color` and
+    /// returns self.
+    ///
+    /// Zones are drawn in the order they are added. When no zones are added,
+    /// the track is filled up to the current value using [`GaugeColor`].
+    #[must_use]
+    pub fn zone(mut self, range: RangeInclusive<f64>, color: Color) -> Self {
+        self.zones.push((range, color));
+        self
+    }
+
+    /// Returns the fraction of the track that `value` is at.
+    fn fraction(&self, value: f64) -> f32 {
+        let span = self.range.end() - self.range.start();
+        if span <= 0. || value.is_nan() {
+            return 0.;
+        }
+        ((value - self.range.start()) / span).clamp(0., 1.).cast()
+    }
+
+    fn draw_arc(
+        center: Point<Px>,
+        radius: Px,
+        start: f32,
+        end: f32,
+        track_size: Px,
+        color: Color,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        if end > start {
+            context.gfx.draw_shape(
+                &Path::arc(
+                    center,
+                    Size::squared(radius),
+                    Angle::degrees_f(START_DEGREES + start * SWEEP_DEGREES),
+                    Angle::degrees_f((end - start) * SWEEP_DEGREES),
+                )
+                .stroke(StrokeOptions::px_wide(track_size).colored(color)),
+            );
+        }
+    }
+}
+
+impl Widget for Gauge {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let value = self.value.get_tracking_redraw(context);
+        match self.target {
+            Some(target) if target == value => {}
+            Some(_) => {
+                self.target = Some(value);
+                self.animation = self
+                    .needle
+                    .transition_to(value)
+                    .over(NEEDLE_DURATION)
+                    .with_easing(EaseOutQuadradic)
+                    .spawn();
+            }
+            None => {
+                self.target = Some(value);
+                self.needle.set(value);
+            }
+        }
+        let needle = self.fraction(self.needle.get_tracking_redraw(context));
+
+        let scale = context.gfx.scale();
+        let track_size = context.get(&GaugeTrackSize).into_px(scale);
+        let size = context.gfx.region().size;
+        let center = Point::new(size.width / 2, size.height / 2);
+        let radius = size.width.min(size.height) / 2 - track_size;
+        if radius <= 0 {
+            return;
+        }
+
+        let track_color = context.get(&GaugeTrackColor);
+        Self::draw_arc(center, radius, 0., 1., track_size, track_color, context);
+        if self.zones.is_empty() {
+            let color = context.get(&GaugeColor);
+            Self::draw_arc(center, radius, 0., needle, track_size, color, context);
+        } else {
+            for (range, color) in &self.zones {
+                let start = self.fraction(*range.start());
+                let end = self.fraction(*range.end());
+                Self::draw_arc(center, radius, start, end, track_size, *color, context);
+            }
+        }
+
+        let needle_color = context.get(&GaugeNeedleColor);
+        let angle = (START_DEGREES + needle * SWEEP_DEGREES).to_radians();
+        let length = radius.into_float();
+        let tip = center
+            + Point::new(
+                Px::from_float(angle.cos() * length),
+                Px::from_float(angle.sin() * length),
+            );
+        context.gfx.draw_shape(
+            &PathBuilder::new(center)
+                .line_to(tip)
+                .build()
+                .stroke(StrokeOptions::px_wide(track_size / 2).colored(needle_color)),
+        );
+        context.gfx.draw_shape(
+            Shape::filled_circle(track_size, needle_color, Origin::Center).translate_by(center),
+        );
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        let size = context.get(&GaugeSize).into_upx(context.gfx.scale());
+        let size = available_space
+            .width
+            .fill_or_fit(size)
+            .min(available_space.height.fill_or_fit(size));
+        Size::squared(size)
+    }
+}
+
+define_components! {
+    Gauge {
+        /// The diameter a [`Gauge`] requests when it is not filling its
+        /// available space.
+        GaugeSize(Dimension, "size", Dimension::Lp(Lp::inches(1)))
+        /// The thickness of the track of a [`Gauge`].
+        GaugeTrackSize(Dimension, "track_size", Dimension::Lp(Lp::points(6)))
+        /// The color of the track of a [`Gauge`].
+        GaugeTrackColor(Color, "track_color", .surface.highest_container)
+        /// The color of the filled portion of a [`Gauge`]'s track when it has
+        /// no zones.
+        GaugeColor(Color, "color", .primary.color)
+        /// The color of the needle of a [`Gauge`].
+        GaugeNeedleColor(Color, "needle_color", @TextColor)
+    }
+}
+
+#[test]
+fn fractions() {
+    let gauge = Gauge::new(0.).range(-10.0..=10.);
+    assert!((gauge.fraction(0.) - 0.5).abs() < f32::EPSILON);
+    assert!(gauge.fraction(-20.).abs() < f32::EPSILON);
+    assert!((gauge.fraction(20.) - 1.).abs() < f32::EPSILON);
+    assert!(gauge.fraction(f64::NAN).abs() < f32::EPSILON);
+}
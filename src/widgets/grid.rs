@@ -133,15 +133,20 @@ impl<const ELEMENTS: usize> Grid<ELEMENTS> {
 
 impl<const COLUMNS: usize> Widget for Grid<COLUMNS> {
     fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
-        for (row, widgets) in self.live_rows.iter_mut().enumerate() {
-            if self.layout.others[row] > 0 {
-                for (column, cell) in widgets.iter().enumerate() {
-                    if self.layout[column].size > 0 {
-                        context.for_other(cell).redraw();
-                    }
-                }
-            }
-        }
+        let layout = &self.layout;
+        context.redraw_children(
+            self.live_rows
+                .iter()
+                .enumerate()
+                .filter(|(row, _)| layout.others[*row] > 0)
+                .flat_map(|(_, widgets)| {
+                    widgets
+                        .iter()
+                        .enumerate()
+                        .filter(|(column, _)| layout[*column].size > 0)
+                        .map(|(_, cell)| cell)
+                }),
+        );
     }
 
     fn mounted(&mut self, context: &mut EventContext<'_>) {
@@ -213,7 +218,7 @@ impl<const COLUMNS: usize> Widget for Grid<COLUMNS> {
 /// The orientation (Row/Column) of an [`Grid`] or
 /// [`Stack`](crate::widgets::Stack) widget.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
-
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Orientation {
     /// The child widgets should be displayed as rows.
     Row,
@@ -0,0 +1,685 @@
+//! A widget that displays a matrix of values as colored cells.
+
+use std::ops::{Range, RangeInclusive};
+
+use figures::units::{Lp, Px, UPx};
+use figures::{Point, Rect, ScreenScale, Size, Zero};
+use image::{DynamicImage, Rgba, RgbaImage};
+use intentional::Cast;
+use kludgine::app::winit::event::MouseButton;
+use kludgine::app::winit::window::CursorIcon;
+use kludgine::shapes::{Shape, StrokeOptions};
+use kludgine::text::{Text, TextOrigin};
+use kludgine::{AnyTexture, Color, DrawableExt, LazyTexture};
+
+use crate::animation::{LinearInterpolate, ZeroToOne};
+use crate::context::{EventContext, GraphicsContext, LayoutContext, Trackable};
+use crate::reactive::value::{
+    Destination, Dynamic, Generation, IntoDynamic, IntoValue, Source, Value,
+};
+use crate::styles::components::{HighlightColor, TextColor};
+use crate::styles::Dimension;
+use crate::widget::{EventHandling, Widget, HANDLED, IGNORED};
+use crate::window::DeviceId;
+use crate::ConstraintLimit;
+
+/// The number of bands the legend of a [`Heatmap`] is drawn with.
+const LEGEND_STEPS: u16 = 64;
+
+/// A two-dimensional matrix of values displayed by a [`Heatmap`].
+///
+/// Values that are not finite are treated as missing and are not drawn.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Matrix {
+    columns: usize,
+    values: Vec<f64>,
+}
+
+impl Matrix {
+    /// Returns a matrix with `columns` columns, with `values` stored in
+    /// row-major order.
+    ///
+    /// If the number of values is not a multiple of `columns`, the trailing
+    /// values are ignored.
+    #[must_use]
+    pub fn new(columns: usize, mut values: Vec<f64>) -> Self {
+        if columns == 0 {
+            values.clear();
+        } else {
+            values.truncate(values.len() / columns * columns);
+        }
+        Self { columns, values }
+    }
+
+    /// Returns a matrix containing `rows`.
+    ///
+    /// The matrix has as many columns as the longest row. Missing values in
+    /// shorter rows are treated as missing.
+    #[must_use]
+    pub fn from_rows<Row>(rows: impl IntoIterator<Item = Row>) -> Self
+    where
+        Row: IntoIterator<Item = f64>,
+    {
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let mut values = Vec::with_capacity(columns * rows.len());
+        for row in rows {
+            let missing = columns - row.len();
+            values.extend(row);
+            values.extend(std::iter::repeat(f64::NAN).take(missing));
+        }
+        Self { columns, values }
+    }
+
+    /// Returns the number of rows in this matrix.
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        if self.columns == 0 {
+            0
+        } else {
+            self.values.len() / self.columns
+        }
+    }
+
+    /// Returns the number of columns in this matrix.
+    #[must_use]
+    pub const fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the value at `row` and `column`, if it is within this matrix.
+    #[must_use]
+    pub fn get(&self, row: usize, column: usize) -> Option<f64> {
+        if column < self.columns {
+            self.values.get(row * self.columns + column).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the smallest and largest finite values in this matrix.
+    #[must_use]
+    pub fn value_range(&self) -> Option<RangeInclusive<f64>> {
+        self.values
+            .iter()
+            .filter(|value| value.is_finite())
+            .fold(None, |range, value| match range {
+                Some((min, max)) => Some((value.min(min), value.max(max))),
+                None => Some((*value, *value)),
+            })
+            .map(|(min, max)| min..=max)
+    }
+}
+
+/// A range of colors that values are mapped to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorScale {
+    colors: Vec<Color>,
+}
+
+impl ColorScale {
+    /// Returns a scale that blends evenly between each of `colors`, with the
+    /// first color representing the smallest value.
+    #[must_use]
+    pub fn new(colors: impl IntoIterator<Item = Color>) -> Self {
+        Self {
+            colors: colors.into_iter().collect(),
+        }
+    }
+
+    /// Returns a perceptually uniform scale from dark purple to yellow.
+    #[must_use]
+    pub fn viridis() -> Self {
+        Self::new([
+            Color::new(0x44, 0x01, 0x54, 255),
+            Color::new(0x3b, 0x52, 0x8b, 255),
+            Color::new(0x21, 0x91, 0x8c, 255),
+            Color::new(0x5e, 0xc9, 0x62, 255),
+            Color::new(0xfd, 0xe7, 0x25, 255),
+        ])
+    }
+
+    /// Returns the color at `fraction` of the way through this scale.
+    #[must_use]
+    pub fn color(&self, fraction: f32) -> Color {
+        match self.colors.as_slice() {
+            [] => Color::CLEAR_BLACK,
+            [only] => *only,
+            colors => {
+                let position = fraction.clamp(0., 1.) * (colors.len() - 1).cast::<f32>();
+                let index = (position.floor().cast::<usize>()).min(colors.len() - 2);
+                colors[index].lerp(&colors[index + 1], position - index.cast::<f32>())
+            }
+        }
+    }
+}
+
+impl Default for ColorScale {
+    fn default() -> Self {
+        Self::viridis()
+    }
+}
+
+/// A rectangular region of cells in a [`Matrix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellRange {
+    /// The range of rows.
+    pub rows: Range<usize>,
+    /// The range of columns.
+    pub columns: Range<usize>,
+}
+
+/// A widget that displays a [`Matrix`] of values as colored cells.
+///
+/// Values are mapped to colors using a [`ColorScale`], and a legend showing
+/// the scale is drawn beside the cells. Hovering a cell shows a tooltip with
+/// the cell's value and the labels of its row and column.
+///
+/// Dragging selects a rectangular region of cells to zoom into, and
+/// right-clicking returns to displaying the entire matrix.
+///
+/// Rather than drawing each cell as a separate shape, the displayed cells are
+/// rendered into a single texture containing at most one texel per pixel.
+/// When there are more cells than pixels, each texel displays the average of
+/// the cells it covers. This allows matrices with millions of cells to be
+/// displayed efficiently.
+///
+/// ```rust
+/// use cushy::widgets::heatmap::{Heatmap, Matrix};
+///
+/// let matrix = Matrix::from_rows((0..100).map(|row| (0..100).map(move |column| {
+///     f64::from(row * column)
+/// })));
+/// let heatmap = Heatmap::new(matrix)
+///     .row_labels((0..100).map(|row| format!("Row {row}")));
+/// ```
+#[derive(Debug)]
+pub struct Heatmap {
+    matrix: Value<Matrix>,
+    scale: Value<ColorScale>,
+    range: Option<RangeInclusive<f64>>,
+    row_labels: Vec<String>,
+    column_labels: Vec<String>,
+    zoom: Dynamic<Option<CellRange>>,
+    legend: bool,
+    displayed: CellRange,
+    plot: Size<Px>,
+    texture: Option<(TextureKey, AnyTexture)>,
+    cursor: Option<Point<Px>>,
+    selecting: Option<(Point<Px>, Point<Px>)>,
+}
+
+impl Heatmap {
+    /// Returns a heatmap that displays `matrix`.
+    #[must_use]
+    pub fn new(matrix: impl IntoValue<Matrix>) -> Self {
+        Self {
+            matrix: matrix.into_value(),
+            scale: Value::Constant(ColorScale::default()),
+            range: None,
+            row_labels: Vec::new(),
+            column_labels: Vec::new(),
+            zoom: Dynamic::default(),
+            legend: true,
+            displayed: CellRange {
+                rows: 0..0,
+                columns: 0..0,
+            },
+            plot: Size::ZERO,
+            texture: None,
+            cursor: None,
+            selecting: None,
+        }
+    }
+
+    /// Maps values to colors using `scale` and returns self.
+    ///
+    /// By default, [`ColorScale::viridis`] is used.
+    #[must_use]
+    pub fn color_scale(mut self, scale: impl IntoValue<ColorScale>) -> Self {
+        self.scale = scale.into_value();
+        self
+    }
+
+    /// Sets the range of values the color scale spans and returns self.
+    ///
+    /// By default, the range is the smallest and largest values in the
+    /// matrix.
+    #[must_use]
+    pub fn range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Sets the labels shown in tooltips for each row and returns self.
+    #[must_use]
+    pub fn row_labels<Label>(mut self, labels: impl IntoIterator<Item = Label>) -> Self
+    where
+        Label: Into<String>,
+    {
+        self.row_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the labels shown in tooltips for each column and returns self.
+    #[must_use]
+    pub fn column_labels<Label>(mut self, labels: impl IntoIterator<Item = Label>) -> Self
+    where
+        Label: Into<String>,
+    {
+        self.column_labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Uses `zoom` to control the region of cells that are displayed and
+    /// returns self.
+    ///
+    /// When `zoom` contains `None`, the entire matrix is displayed. Selecting
+    /// a region to zoom into updates `zoom`.
+    #[must_use]
+    pub fn zoom(mut self, zoom: impl IntoDynamic<Option<CellRange>>) -> Self {
+        self.zoom = zoom.into_dynamic();
+        self
+    }
+
+    /// Hides the legend and returns self.
+    #[must_use]
+    pub fn without_legend(mut self) -> Self {
+        self.legend = false;
+        self
+    }
+
+    /// Returns the row and column of the displayed cell at `location`.
+    fn cell_at(&self, location: Point<Px>) -> Option<(usize, usize)> {
+        if location.x < 0
+            || location.y < 0
+            || location.x >= self.plot.width
+            || location.y >= self.plot.height
+        {
+            return None;
+        }
+        let column = self.displayed.columns.start
+            + px_to_usize(location.x) * self.displayed.columns.len() / px_to_usize(self.plot.width);
+        let row = self.displayed.rows.start
+            + px_to_usize(location.y) * self.displayed.rows.len() / px_to_usize(self.plot.height);
+        Some((row, column))
+    }
+
+    fn draw_legend(
+        &self,
+        range: (f64, f64),
+        size: Size<Px>,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        let scale = context.gfx.scale();
+        let gap = Lp::points(6).into_px(scale);
+        let bar_width = Lp::points(12).into_px(scale);
+        let left = self.plot.width + gap;
+        self.scale.map(|color_scale| {
+            for step in 0..LEGEND_STEPS {
+                let top = size.height * i32::from(step) / i32::from(LEGEND_STEPS);
+                let bottom = size.height * (i32::from(step) + 1) / i32::from(LEGEND_STEPS);
+                let fraction = 1. - (f32::from(step) + 0.5) / f32::from(LEGEND_STEPS);
+                context.gfx.draw_shape(&Shape::filled_rect(
+                    Rect::new(Point::new(left, top), Size::new(bar_width, bottom - top)),
+                    color_scale.color(fraction),
+                ));
+            }
+        });
+
+        let text_color = context.get(&TextColor);
+        let label_left = left + bar_width + gap;
+        let max = context
+            .gfx
+            .measure_text(Text::new(&format_value(range.1), text_color));
+        context.gfx.draw_measured_text(
+            max.translate_by(Point::new(label_left, Px::ZERO)),
+            TextOrigin::TopLeft,
+        );
+        let min = context
+            .gfx
+            .measure_text(Text::new(&format_value(range.0), text_color));
+        context.gfx.draw_measured_text(
+            min.translate_by(Point::new(label_left, size.height - min.size.height)),
+            TextOrigin::TopLeft,
+        );
+    }
+
+    fn draw_tooltip(
+        &self,
+        cursor: Point<Px>,
+        size: Size<Px>,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        let Some((row, column)) = self.cell_at(cursor) else {
+            return;
+        };
+        let Some(value) = self.matrix.map(|matrix| matrix.get(row, column)) else {
+            return;
+        };
+        let row_label = self
+            .row_labels
+            .get(row)
+            .cloned()
+            .unwrap_or_else(|| format!("Row {row}"));
+        let column_label = self
+            .column_labels
+            .get(column)
+            .cloned()
+            .unwrap_or_else(|| format!("Column {column}"));
+        let text = format!("{row_label}, {column_label}\n{}", format_value(value));
+
+        let padding = Lp::points(4).into_px(context.gfx.scale());
+        let measured = context
+            .gfx
+            .measure_text(Text::new(&text, context.get(&HeatmapTooltipForeground)));
+        let box_size = measured.size + Size::squared(padding * 2);
+        let offset = Lp::points(12).into_px(context.gfx.scale());
+        let mut origin = cursor + Point::squared(offset);
+        if origin.x + box_size.width > size.width {
+            origin.x = cursor.x - offset - box_size.width;
+        }
+        if origin.y + box_size.height > size.height {
+            origin.y = cursor.y - offset - box_size.height;
+        }
+        context.gfx.draw_shape(&Shape::filled_rect(
+            Rect::new(origin, box_size),
+            context.get(&HeatmapTooltipBackground),
+        ));
+        context.gfx.draw_measured_text(
+            measured.translate_by(origin + Point::squared(padding)),
+            TextOrigin::TopLeft,
+        );
+    }
+}
+
+impl Widget for Heatmap {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        self.matrix.redraw_when_changed(context);
+        self.scale.redraw_when_changed(context);
+        let zoom = self.zoom.get_tracking_redraw(context);
+        context.apply_current_font_settings();
+
+        let size = context.gfx.region().size;
+        let legend_width = if self.legend {
+            context
+                .get(&HeatmapLegendWidth)
+                .into_px(context.gfx.scale())
+        } else {
+            Px::ZERO
+        };
+        self.plot = Size::new((size.width - legend_width).max(Px::ZERO), size.height);
+        let (rows, columns, value_range) = self.matrix.map(|matrix| {
+            (
+                matrix.rows(),
+                matrix.columns(),
+                self.range.clone().or_else(|| matrix.value_range()),
+            )
+        });
+        self.displayed = clamp_cells(zoom, rows, columns);
+        let Some(value_range) = value_range else {
+            return;
+        };
+        let value_range = value_range.into_inner();
+
+        if self.plot.width > 0
+            && self.plot.height > 0
+            && !self.displayed.rows.is_empty()
+            && !self.displayed.columns.is_empty()
+        {
+            let key = TextureKey {
+                matrix: self.matrix.generation(),
+                scale: self.scale.generation(),
+                displayed: self.displayed.clone(),
+                size: Size::new(
+                    self.displayed
+                        .columns
+                        .len()
+                        .min(px_to_usize(self.plot.width)),
+                    self.displayed.rows.len().min(px_to_usize(self.plot.height)),
+                ),
+                range: value_range,
+            };
+            if !matches!(&self.texture, Some((existing, _)) if existing == &key) {
+                let image = self.matrix.map(|matrix| {
+                    self.scale.map(|scale| {
+                        render_cells(matrix, &key.displayed, key.size, value_range, scale)
+                    })
+                });
+                let texture = AnyTexture::Lazy(LazyTexture::from_image(
+                    DynamicImage::ImageRgba8(image),
+                    kludgine::wgpu::FilterMode::Nearest,
+                ));
+                self.texture = Some((key, texture));
+            }
+            if let Some((_, texture)) = &self.texture {
+                context.gfx.draw_texture(
+                    texture,
+                    Rect::new(Point::ZERO, self.plot),
+                    ZeroToOne::ONE,
+                );
+            }
+        }
+
+        if let Some((start, end)) = self.selecting {
+            let min = Point::new(start.x.min(end.x), start.y.min(end.y));
+            let max = Point::new(start.x.max(end.x), start.y.max(end.y));
+            let width = Lp::points(1).into_px(context.gfx.scale()).max(Px::new(1));
+            context.gfx.draw_shape(&Shape::stroked_rect(
+                Rect::from_extents(min, max),
+                StrokeOptions::px_wide(width).colored(context.get(&HighlightColor)),
+            ));
+        }
+
+        if self.legend {
+            self.draw_legend(value_range, size, context);
+        }
+
+        if let (Some(cursor), None) = (self.cursor, self.selecting) {
+            self.draw_tooltip(cursor, size, context);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        _context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        available_space.map(ConstraintLimit::max)
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn hover(&mut self, location: Point<Px>, context: &mut EventContext<'_>) -> Option<CursorIcon> {
+        self.cursor = Some(location);
+        context.set_needs_redraw();
+        self.cell_at(location).map(|_| CursorIcon::Crosshair)
+    }
+
+    fn unhover(&mut self, context: &mut EventContext<'_>) {
+        self.cursor = None;
+        context.set_needs_redraw();
+    }
+
+    fn mouse_down(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        _context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        match button {
+            MouseButton::Left if self.cell_at(location).is_some() => {
+                self.selecting = Some((location, location));
+                HANDLED
+            }
+            MouseButton::Right => {
+                self.zoom.set(None);
+                HANDLED
+            }
+            _ => IGNORED,
+        }
+    }
+
+    fn mouse_drag(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) {
+        if let Some((_, end)) = &mut self.selecting {
+            *end = Point::new(
+                location.x.clamp(Px::ZERO, self.plot.width - 1),
+                location.y.clamp(Px::ZERO, self.plot.height - 1),
+            );
+            context.set_needs_redraw();
+        }
+    }
+
+    fn mouse_up(
+        &mut self,
+        _location: Option<Point<Px>>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) {
+        let Some((start, end)) = self.selecting.take() else {
+            return;
+        };
+        context.set_needs_redraw();
+        let (Some(start), Some(end)) = (self.cell_at(start), self.cell_at(end)) else {
+            return;
+        };
+        if start != end {
+            self.zoom.set(Some(CellRange {
+                rows: start.0.min(end.0)..start.0.max(end.0) + 1,
+                columns: start.1.min(end.1)..start.1.max(end.1) + 1,
+            }));
+        }
+    }
+}
+
+/// The information used to determine whether the texture of a [`Heatmap`]
+/// needs to be recreated.
+#[derive(Debug, PartialEq)]
+struct TextureKey {
+    matrix: Option<Generation>,
+    scale: Option<Generation>,
+    displayed: CellRange,
+    size: Size<usize>,
+    range: (f64, f64),
+}
+
+/// Returns `zoom` limited to a matrix with `rows` and `columns`, or the
+/// entire matrix if `zoom` is `None`.
+fn clamp_cells(zoom: Option<CellRange>, rows: usize, columns: usize) -> CellRange {
+    let clamp = |range: Range<usize>, len: usize| {
+        let start = range.start.min(len);
+        start..range.end.clamp(start, len)
+    };
+    match zoom {
+        Some(zoom) => CellRange {
+            rows: clamp(zoom.rows, rows),
+            columns: clamp(zoom.columns, columns),
+        },
+        None => CellRange {
+            rows: 0..rows,
+            columns: 0..columns,
+        },
+    }
+}
+
+/// Renders the `displayed` cells of `matrix` into an image of `size`, with
+/// each pixel containing the color of the average of the cells it covers.
+fn render_cells(
+    matrix: &Matrix,
+    displayed: &CellRange,
+    size: Size<usize>,
+    (min, max): (f64, f64),
+    scale: &ColorScale,
+) -> RgbaImage {
+    let rows = displayed.rows.len();
+    let columns = displayed.columns.len();
+    let mut totals = vec![(0., 0_u32); size.width * size.height];
+    for (row_offset, row) in displayed.rows.clone().enumerate() {
+        let y = row_offset * size.height / rows;
+        let values = &matrix.values[row * matrix.columns..(row + 1) * matrix.columns];
+        for (column_offset, value) in values[displayed.columns.clone()].iter().enumerate() {
+            if value.is_finite() {
+                let x = column_offset * size.width / columns;
+                let total = &mut totals[y * size.width + x];
+                total.0 += value;
+                total.1 += 1;
+            }
+        }
+    }
+
+    let span = max - min;
+    let mut image = RgbaImage::new(size.width.cast(), size.height.cast());
+    for (pixel, (total, count)) in image.pixels_mut().zip(totals) {
+        if count > 0 {
+            let average = total / f64::from(count);
+            let fraction = if span > 0. {
+                (average - min) / span
+            } else {
+                0.5
+            };
+            let color = scale.color(fraction.cast());
+            *pixel = Rgba([color.red(), color.green(), color.blue(), color.alpha()]);
+        }
+    }
+    image
+}
+
+fn px_to_usize(px: Px) -> usize {
+    usize::try_from(px.get()).unwrap_or_default()
+}
+
+/// Formats `value` for display in a legend or tooltip.
+fn format_value(value: f64) -> String {
+    let formatted = format!("{value:.3}");
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+define_components! {
+    Heatmap {
+        /// The width reserved for the legend of a [`Heatmap`].
+        HeatmapLegendWidth(Dimension, "legend_width", Dimension::Lp(Lp::points(64)))
+        /// The background color of the tooltip shown when hovering a
+        /// [`Heatmap`].
+        HeatmapTooltipBackground(Color, "tooltip_background", .surface.highest_container)
+        /// The text color of the tooltip shown when hovering a [`Heatmap`].
+        HeatmapTooltipForeground(Color, "tooltip_foreground", @TextColor)
+    }
+}
+
+#[test]
+fn rendering() {
+    let matrix = Matrix::from_rows([vec![0., 0., 5., 5.], vec![0., 0.]]);
+    assert_eq!(matrix.rows(), 2);
+    assert_eq!(matrix.columns(), 4);
+    assert!(matrix.get(1, 3).is_some_and(f64::is_nan));
+    assert_eq!(matrix.value_range(), Some(0.0..=5.));
+    assert_eq!(format_value(2.5), "2.5");
+    assert_eq!(format_value(100.), "100");
+
+    let scale = ColorScale::new([Color::BLACK, Color::WHITE]);
+    let displayed = clamp_cells(None, matrix.rows(), matrix.columns());
+    let image = render_cells(&matrix, &displayed, Size::new(2, 1), (0., 5.), &scale);
+    // The second pixel ignores the missing values in the second row.
+    assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(1, 0).0, [255, 255, 255, 255]);
+}
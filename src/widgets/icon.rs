@@ -0,0 +1,104 @@
+//! A widget that displays an icon from an icon font.
+
+use kludgine::Color;
+
+use crate::fonts::IconFont;
+use crate::reactive::value::{IntoValue, Value};
+use crate::styles::components::{FontFamily, LineHeight, TextColor, TextSize};
+use crate::styles::Dimension;
+use crate::widget::{MakeWidget, MakeWidgetWithTag, WidgetInstance, WidgetTag};
+use crate::widgets::label::LabelOverflow;
+use crate::widgets::Label;
+
+/// A widget that displays a single icon from an [`IconFont`].
+///
+/// Icons are sized using [`IconSize`], which defaults to the current text
+/// size, and are colored using [`IconColor`], which defaults to the current
+/// text color. Because icons are rendered as text, they are measured like any
+/// other text, which allows them to be combined with labels without needing
+/// to measure glyphs manually:
+///
+/// ```rust
+/// use cushy::fonts::IconFont;
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::Icon;
+///
+/// let symbols = IconFont::new("Material Symbols Outlined").with_icon("save", '\u{e161}');
+/// let save = Icon::named(&symbols, "save")
+///     .and("Save")
+///     .into_columns()
+///     .into_button();
+/// ```
+#[derive(Debug)]
+pub struct Icon {
+    font: IconFont,
+    text: Value<String>,
+    size: Option<Value<Dimension>>,
+}
+
+impl Icon {
+    /// Returns a widget that displays the icon named `name` from `font`.
+    ///
+    /// If `name` has not been registered with `font`, `name` is rendered
+    /// using the icon font. This allows fonts that use ligatures to map names
+    /// to icons to be used without registering each icon.
+    #[must_use]
+    pub fn named(font: &IconFont, name: impl IntoValue<String>) -> Self {
+        let text = name.into_value().map_each({
+            let font = font.clone();
+            move |name| font.text_for(name)
+        });
+        Self {
+            font: font.clone(),
+            text,
+            size: None,
+        }
+    }
+
+    /// Returns a widget that displays the icon at `codepoint` in `font`.
+    #[must_use]
+    pub fn codepoint(font: &IconFont, codepoint: impl IntoValue<char>) -> Self {
+        Self {
+            font: font.clone(),
+            text: codepoint
+                .into_value()
+                .map_each(|codepoint| String::from(*codepoint)),
+            size: None,
+        }
+    }
+
+    /// Sets the size of this icon and returns self.
+    ///
+    /// By default, [`IconSize`] is used.
+    #[must_use]
+    pub fn size(mut self, size: impl IntoValue<Dimension>) -> Self {
+        self.size = Some(size.into_value());
+        self
+    }
+}
+
+impl MakeWidgetWithTag for Icon {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let label = Label::new(self.text)
+            .overflow(LabelOverflow::Clip)
+            .with(&FontFamily, self.font.family().clone())
+            .with_dynamic(&TextColor, IconColor);
+        let label = if let Some(size) = self.size {
+            label.with(&TextSize, size.clone()).with(&LineHeight, size)
+        } else {
+            label
+                .with_dynamic(&TextSize, IconSize)
+                .with_dynamic(&LineHeight, IconSize)
+        };
+        label.make_with_tag(tag)
+    }
+}
+
+define_components! {
+    Icon {
+        /// The size of an [`Icon`].
+        IconSize(Dimension, "size", @TextSize)
+        /// The color of an [`Icon`].
+        IconColor(Color, "color", @TextColor)
+    }
+}
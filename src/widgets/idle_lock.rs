@@ -0,0 +1,177 @@
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use std::time::Duration;
+
+use figures::units::Lp;
+use kludgine::Color;
+
+use crate::idle;
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, Source, WeakDynamic};
+use crate::widget::{MakeWidget, MakeWidgetWithTag, WidgetInstance, WidgetTag};
+use crate::widgets::input::{InputValue, MaskedString};
+use crate::widgets::label::Displayable;
+use crate::widgets::{Space, Switcher};
+
+/// A widget that hides its contents after a period without user input.
+///
+/// Once no window has received input for the timeout, the contents are
+/// replaced by a lock screen. By default, the lock screen is blank and the
+/// contents are shown again as soon as input resumes.
+///
+/// When a password is required using [`IdleLock::password()`], the lock screen
+/// instead prompts for a password, and the contents are only shown again once
+/// a password is accepted.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use cushy::widgets::IdleLock;
+///
+/// let details = IdleLock::new(Duration::from_secs(5 * 60), "Account Details")
+///     .password(|password| password.as_str() == "hunter2");
+/// ```
+pub struct IdleLock {
+    contents: WidgetInstance,
+    timeout: Duration,
+    locked: Dynamic<bool>,
+    verify: Option<Arc<dyn Fn(&MaskedString) -> bool + Send + Sync>>,
+    lock_screen: Option<WidgetInstance>,
+}
+
+impl IdleLock {
+    /// Returns a widget that hides `contents` once no input has been received
+    /// for `timeout`.
+    #[must_use]
+    pub fn new(timeout: Duration, contents: impl MakeWidget) -> Self {
+        Self {
+            contents: contents.make_widget(),
+            timeout,
+            locked: Dynamic::new(false),
+            verify: None,
+            lock_screen: None,
+        }
+    }
+
+    /// Requires a password accepted by `verify` to show the contents after
+    /// they have been hidden, and returns self.
+    #[must_use]
+    pub fn password<F>(mut self, verify: F) -> Self
+    where
+        F: Fn(&MaskedString) -> bool + Send + Sync + 'static,
+    {
+        self.verify = Some(Arc::new(verify));
+        self
+    }
+
+    /// Shows `lock_screen` while the contents are hidden, and returns self.
+    ///
+    /// When a password is required, the password prompt is shown above
+    /// `lock_screen`.
+    #[must_use]
+    pub fn lock_screen(mut self, lock_screen: impl MakeWidget) -> Self {
+        self.lock_screen = Some(lock_screen.make_widget());
+        self
+    }
+
+    /// Uses `locked` to track whether the contents are hidden, and returns
+    /// self.
+    ///
+    /// Setting `locked` to true hides the contents immediately.
+    #[must_use]
+    pub fn locked(mut self, locked: impl IntoDynamic<bool>) -> Self {
+        self.locked = locked.into_dynamic();
+        self
+    }
+}
+
+impl MakeWidgetWithTag for IdleLock {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let requires_password = self.verify.is_some();
+        let idle = idle::idle_after(self.timeout);
+        let locked = self.locked.downgrade();
+        self.locked
+            .set_source(idle.clone().for_each_subsequent(move |is_idle| {
+                // Keep the idle watcher alive as long as `locked` is.
+                let _idle = &idle;
+                let Some(locked) = locked.upgrade() else {
+                    return;
+                };
+                if *is_idle {
+                    locked.set(true);
+                } else if !requires_password {
+                    locked.set(false);
+                }
+            }));
+
+        let lock_screen = match self.verify {
+            Some(verify) => {
+                let background = self
+                    .lock_screen
+                    .unwrap_or_else(|| Space::clear().make_widget());
+                background
+                    .and(password_prompt(verify, self.locked.downgrade()))
+                    .into_layers()
+                    .make_widget()
+            }
+            None => self
+                .lock_screen
+                .unwrap_or_else(|| Space::colored(Color::BLACK).make_widget()),
+        };
+
+        let contents = self.contents;
+        Switcher::mapping(self.locked, move |locked, _| {
+            if *locked {
+                lock_screen.clone()
+            } else {
+                contents.clone()
+            }
+        })
+        .make_with_tag(tag)
+    }
+}
+
+impl Debug for IdleLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdleLock")
+            .field("contents", &self.contents)
+            .field("timeout", &self.timeout)
+            .field("locked", &self.locked)
+            .field("requires_password", &self.verify.is_some())
+            .field("lock_screen", &self.lock_screen)
+            .finish()
+    }
+}
+
+fn password_prompt(
+    verify: Arc<dyn Fn(&MaskedString) -> bool + Send + Sync>,
+    locked: WeakDynamic<bool>,
+) -> WidgetInstance {
+    let password = Dynamic::new(MaskedString::default());
+    let error = Dynamic::new(String::new());
+    let unlock = {
+        let password = password.clone();
+        let error = error.clone();
+        move |_| {
+            let Some(locked) = locked.upgrade() else {
+                return;
+            };
+            let entered = password.take();
+            if verify(&entered) {
+                error.take();
+                locked.set(false);
+            } else {
+                error.set(String::from("Incorrect password"));
+            }
+        }
+    };
+
+    "Enter your password to unlock"
+        .and(password.into_input())
+        .and(error.into_label())
+        .and("Unlock".into_button().on_click(unlock).into_default())
+        .into_rows()
+        .contain()
+        .width(Lp::inches(3))
+        .centered()
+        .make_widget()
+}
@@ -12,6 +12,8 @@ use crate::context::{LayoutContext, Trackable};
 use crate::reactive::value::{IntoValue, Source, Value};
 use crate::styles::Dimension;
 use crate::widget::Widget;
+#[cfg(feature = "http")]
+use crate::widgets::UrlImage;
 use crate::ConstraintLimit;
 
 /// A widget that displays an image/texture.
@@ -36,6 +38,16 @@ impl Image {
         }
     }
 
+    /// Returns a widget that displays the image downloaded from `url`.
+    ///
+    /// The image is downloaded in the background. See [`UrlImage`] for more
+    /// information.
+    #[cfg(feature = "http")]
+    #[must_use]
+    pub fn from_url(url: impl Into<String>) -> UrlImage {
+        UrlImage::new(url)
+    }
+
     /// Applies the `scaling` strategies and returns self.
     #[must_use]
     pub fn scaling(mut self, scaling: impl IntoValue<ImageScaling>) -> Self {
@@ -1,10 +1,12 @@
 //! A text input widget.
 
+use std::any::Any;
 use std::borrow::{Borrow, BorrowMut, Cow};
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display, Formatter, Write};
 use std::hash::Hash;
-use std::ops::{Deref, DerefMut};
+use std::mem;
+use std::ops::{Deref, DerefMut, Range};
 use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
@@ -14,7 +16,7 @@ use figures::{
 };
 use intentional::Cast;
 use kludgine::app::winit::event::{ElementState, Ime};
-use kludgine::app::winit::keyboard::{Key, NamedKey};
+use kludgine::app::winit::keyboard::{Key, ModifiersState, NamedKey};
 use kludgine::app::winit::window::{CursorIcon, ImePurpose};
 use kludgine::shapes::{Shape, StrokeOptions};
 use kludgine::text::{MeasuredText, Text, TextOrigin};
@@ -24,14 +26,22 @@ use zeroize::Zeroizing;
 
 use crate::context::{EventContext, GraphicsContext, LayoutContext};
 use crate::reactive::value::{
-    Destination, Dynamic, Generation, IntoDynamic, IntoValue, Source, Value,
+    Destination, Dynamic, Generation, IntoDynamic, IntoReadOnly, IntoValue, ReadOnly, Source, Value,
 };
 use crate::styles::components::{HighlightColor, IntrinsicPadding, OutlineColor, TextColor};
+use crate::styles::{Component, Dimension, RequireInvalidation};
 use crate::utils::ModifiersExt;
 use crate::widget::{Callback, EventHandling, Widget, HANDLED, IGNORED};
+use crate::widgets::rich_text::RichText;
 use crate::window::KeyEvent;
 use crate::{ConstraintLimit, FitMeasuredSize, Lazy};
 
+mod filter;
+mod unicode;
+
+pub use self::filter::{InputFilter, InputMask};
+use self::unicode::{UnicodeEntry, UnicodeEntryEvent};
+
 const CURSOR_BLINK_DURATION: Duration = Duration::from_millis(500);
 
 /// A text input widget.
@@ -46,11 +56,57 @@ pub struct Input<Storage = String> {
     on_key: Option<Callback<KeyEvent, EventHandling>>,
     cache: Option<CachedLayout>,
     selection: SelectionState,
+    external_selection: Option<Dynamic<SelectionState>>,
+    published_selection: SelectionState,
+    caret_area: Option<Dynamic<Rect<Px>>>,
+    multiline: bool,
+    highlight: Option<ReadOnly<RichText>>,
     blink_state: BlinkState,
     needs_to_select_all: bool,
+    selection_restored: bool,
     mouse_buttons_down: usize,
     line_navigation_x_target: Option<Px>,
     window_focused: bool,
+    preedit: Option<Preedit>,
+    preedit_generation: usize,
+    filter: Option<InputFilter>,
+    unicode_entry: UnicodeEntry,
+}
+
+/// Text being composed by an input method editor.
+struct Preedit {
+    text: String,
+    cursor: Option<(usize, usize)>,
+}
+
+impl Preedit {
+    /// Returns `text` with this composition inserted at `offset`, along with
+    /// the range of the result occupied by the composition.
+    fn insert_into(&self, text: &str, offset: usize) -> (String, Range<usize>) {
+        let composed = [&text[..offset], &self.text, &text[offset..]].concat();
+        (composed, offset..offset + self.text.len())
+    }
+}
+
+/// Converts `offset` in the input's value to an offset in the displayed text
+/// that contains a composition at `preedit`.
+fn offset_with_preedit(offset: usize, preedit: &Range<usize>) -> usize {
+    if offset > preedit.start {
+        offset + preedit.len()
+    } else {
+        offset
+    }
+}
+
+/// Converts `offset` in the displayed text that contains a composition at
+/// `preedit` to an offset in the input's value. Offsets within the composition
+/// map to where it is inserted.
+fn offset_without_preedit(offset: usize, preedit: &Range<usize>) -> usize {
+    if offset >= preedit.end {
+        offset - preedit.len()
+    } else {
+        offset.min(preedit.start)
+    }
 }
 
 #[derive(Eq, PartialEq, Clone, Copy)]
@@ -58,16 +114,24 @@ struct CacheKey {
     generation: Generation,
     mask_generation: Option<Generation>,
     placeholder_generation: Option<Generation>,
+    highlight_generation: Option<Generation>,
     width: Option<Px>,
     color: Color,
+    selection_color: Color,
     mask_bytes: usize,
     cursor: Cursor,
     selection: Option<Cursor>,
+    preedit_generation: usize,
 }
 
 struct CachedLayout {
     bytes: usize,
+    /// The range of the measured text occupied by the IME preedit text.
+    preedit: Option<Range<usize>>,
     measured: MeasuredText<Px>,
+    /// The text measured using the [`SelectionForeground`] color, when it
+    /// differs from the text color and a selection exists.
+    selected: Option<MeasuredText<Px>>,
     placeholder: MeasuredText<Px>,
     key: CacheKey,
 }
@@ -119,11 +183,21 @@ where
             cache: None,
             blink_state: BlinkState::default(),
             selection: SelectionState::default(),
+            external_selection: None,
+            published_selection: SelectionState::default(),
+            caret_area: None,
+            multiline: false,
+            highlight: None,
             on_key: None,
             mouse_buttons_down: 0,
             needs_to_select_all: false,
+            selection_restored: false,
             line_navigation_x_target: None,
             window_focused: false,
+            preedit: None,
+            preedit_generation: 0,
+            filter: None,
+            unicode_entry: UnicodeEntry::default(),
         }
     }
 
@@ -147,6 +221,57 @@ where
         self
     }
 
+    /// Sets whether this input allows its value to span multiple lines and
+    /// returns self.
+    ///
+    /// When enabled, Enter inserts a new line, Page Up and Page Down move the
+    /// cursor by the height of the window, and the contents are no longer
+    /// selected when the input is focused.
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Synchronizes this input's selection with `selection` and returns self.
+    ///
+    /// `selection` is updated as the user interacts with this input. Setting
+    /// a new value to `selection` moves this input's cursor and selection.
+    pub fn with_selection(mut self, selection: impl IntoDynamic<SelectionState>) -> Self {
+        let selection = selection.into_dynamic();
+        self.published_selection = selection.get();
+        self.selection = self.published_selection;
+        self.external_selection = Some(selection);
+        self
+    }
+
+    /// Styles the contents of this input using the spans of `highlight` and
+    /// returns self.
+    ///
+    /// The spans of `highlight` are applied to this input's current value,
+    /// regardless of the text contained in `highlight`. This allows a syntax
+    /// highlighter to update `highlight` in response to changes to the value.
+    /// Spans are ignored when the input is masked.
+    pub fn highlighted(mut self, highlight: impl IntoReadOnly<RichText>) -> Self {
+        self.highlight = Some(highlight.into_read_only());
+        self
+    }
+
+    /// Applies `filter` to text entered into this input and returns self.
+    ///
+    /// The filter is applied to typed, pasted, and composed text before it is
+    /// stored in this input's value.
+    pub fn filtered(mut self, filter: InputFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Updates `caret_area` with the area the cursor occupies each time this
+    /// input is drawn.
+    pub(crate) fn with_caret_area(mut self, caret_area: Dynamic<Rect<Px>>) -> Self {
+        self.caret_area = Some(caret_area);
+        self
+    }
+
     /// Sets the `on_key` callback.
     ///
     /// This function is called for every keyboard input event. If [`HANDLED`]
@@ -159,6 +284,25 @@ where
         self
     }
 
+    fn receive_selection(&mut self) {
+        if let Some(external) = &self.external_selection {
+            let selection = external.get();
+            if selection != self.published_selection {
+                self.selection = selection;
+                self.published_selection = selection;
+            }
+        }
+    }
+
+    fn publish_selection(&mut self) {
+        if let Some(external) = &self.external_selection {
+            if self.selection != self.published_selection {
+                self.published_selection = self.selection;
+                external.set(self.selection);
+            }
+        }
+    }
+
     fn select_all(&mut self) {
         self.value.map_ref(|value| {
             let text = value.as_str();
@@ -236,7 +380,10 @@ where
         mode: CursorNavigationMode,
         context: &mut EventContext<'_>,
     ) {
-        if !matches!(mode, CursorNavigationMode::Line) {
+        if !matches!(
+            mode,
+            CursorNavigationMode::Line | CursorNavigationMode::Page
+        ) {
             self.line_navigation_x_target = None;
         }
 
@@ -246,8 +393,10 @@ where
         match mode {
             CursorNavigationMode::Grapheme => self.move_cursor_by_grapheme(direction),
             CursorNavigationMode::Word => self.move_cursor_by_word(direction),
-            CursorNavigationMode::Line => self.move_cursor_by_line(direction, context),
+            CursorNavigationMode::Line => self.move_cursor_by_line(direction, 1, context),
             CursorNavigationMode::LineExtent => self.move_cursor_by_line_extent(direction, context),
+            CursorNavigationMode::Page => self.move_cursor_by_page(direction, context),
+            CursorNavigationMode::Document => self.move_cursor_to_document_extent(direction),
         }
     }
 
@@ -338,7 +487,29 @@ where
         self.selection.cursor = self.cursor_from_point(position, context);
     }
 
-    fn move_cursor_by_line(&mut self, affinity: Affinity, context: &mut EventContext<'_>) {
+    fn move_cursor_to_document_extent(&mut self, affinity: Affinity) {
+        self.selection.cursor.offset = match affinity {
+            Affinity::Before => 0,
+            Affinity::After => self.value.map_ref(|value| value.as_str().len()),
+        };
+    }
+
+    fn move_cursor_by_page(&mut self, affinity: Affinity, context: &mut EventContext<'_>) {
+        let Some(cache) = self.cache.as_ref() else {
+            return;
+        };
+
+        let lines =
+            (visible_height(context).get() / cache.measured.line_height.get().max(1)).max(1);
+        self.move_cursor_by_line(affinity, lines, context);
+    }
+
+    fn move_cursor_by_line(
+        &mut self,
+        affinity: Affinity,
+        lines: i32,
+        context: &mut EventContext<'_>,
+    ) {
         let Some(cache) = self.cache.as_ref() else {
             return;
         };
@@ -355,10 +526,11 @@ where
         } else {
             self.line_navigation_x_target = Some(position.x);
         }
+        let distance = cache.measured.line_height.saturating_mul(Px::new(lines));
         match affinity {
-            Affinity::Before => position.y -= cache.measured.line_height,
+            Affinity::Before => position.y -= distance,
             Affinity::After => {
-                position.y += cache.measured.line_height;
+                position.y += distance;
             }
         };
 
@@ -410,13 +582,9 @@ where
             return;
         }
 
-        self.map_selected_text(|text| {
-            if let Some(mut clipboard) = context.cushy().clipboard_guard() {
-                match clipboard.set_text(text) {
-                    Ok(()) => {}
-                    Err(err) => tracing::error!("error copying to clipboard: {err}"),
-                }
-            }
+        self.map_selected_text(|text| match context.cushy().clipboard().set_text(text) {
+            Ok(()) | Err(arboard::Error::ClipboardNotSupported) => {}
+            Err(err) => tracing::error!("error copying to clipboard: {err}"),
         });
     }
 
@@ -426,6 +594,25 @@ where
         }
 
         let selected_range = self.selected_range();
+        if let Some(filter) = &self.filter {
+            let (start, end) = match selected_range {
+                (start, Some(end)) => (start.offset, end.offset),
+                (cursor, None) => (cursor.offset, cursor.offset),
+            };
+            self.value.map_mut(|mut value| {
+                let value = value.as_string_mut();
+                let end = end.min(value.len());
+                if let Some((filtered, cursor)) = filter.apply(value, start.min(end)..end, new_text)
+                {
+                    value.clear();
+                    value.push_str(&filtered);
+                    self.selection.cursor.offset = cursor;
+                    self.selection.start = None;
+                }
+            });
+            return;
+        }
+
         match selected_range {
             (start, Some(end)) => {
                 self.replace_range(start, end, new_text);
@@ -443,22 +630,29 @@ where
         };
     }
 
+    fn report_length(&mut self) {
+        if let Some(filter) = &mut self.filter {
+            self.value
+                .map_ref(|value| filter.value_changed(value.as_str()));
+        }
+    }
+
     fn paste_from_clipboard(&mut self, context: &mut EventContext<'_>) -> bool {
         if !context.enabled() {
             return false;
         }
 
-        match context
-            .cushy()
-            .clipboard_guard()
-            .map(|mut clipboard| clipboard.get_text())
-        {
-            Some(Ok(text)) => {
+        match context.cushy().clipboard().get_text() {
+            Ok(text) => {
                 self.replace_selection(&text, context);
                 true
             }
-            None | Some(Err(arboard::Error::ConversionFailure)) => false,
-            Some(Err(err)) => {
+            Err(
+                arboard::Error::ClipboardNotSupported
+                | arboard::Error::ConversionFailure
+                | arboard::Error::ContentNotAvailable,
+            ) => false,
+            Err(err) => {
                 tracing::error!("error retrieving clipboard contents: {err}");
                 false
             }
@@ -476,9 +670,10 @@ where
 
                 HANDLED
             }
-            (ElementState::Pressed, Key::Named(key @ (NamedKey::ArrowLeft | NamedKey::ArrowDown | NamedKey::ArrowUp | NamedKey::ArrowRight | NamedKey::Home | NamedKey::End)), _) => {
+            (ElementState::Pressed, Key::Named(key @ (NamedKey::ArrowLeft | NamedKey::ArrowDown | NamedKey::ArrowUp | NamedKey::ArrowRight | NamedKey::Home | NamedKey::End | NamedKey::PageUp | NamedKey::PageDown)), _)
+                if self.multiline || !matches!(key, NamedKey::PageUp | NamedKey::PageDown) => {
                 let modifiers = context.modifiers();
-                let affinity = if matches!(key, NamedKey::ArrowLeft | NamedKey::ArrowUp | NamedKey::Home) {
+                let affinity = if matches!(key, NamedKey::ArrowLeft | NamedKey::ArrowUp | NamedKey::Home | NamedKey::PageUp) {
                     Affinity::Before
                 } else {
                     Affinity::After
@@ -499,6 +694,9 @@ where
                 };
 
                 match key {
+                    #[cfg(any(target_os = "ios", target_os = "macos"))]
+                    NamedKey::ArrowUp | NamedKey::ArrowDown if modifiers.primary() => self.move_cursor(affinity, CursorNavigationMode::Document, context),
+                    NamedKey::Home | NamedKey::End if modifiers.primary() => self.move_cursor(affinity, CursorNavigationMode::Document, context),
                     #[cfg(any(target_os = "ios", target_os = "macos"))]
                     NamedKey::ArrowLeft | NamedKey::ArrowRight if modifiers.primary() => self.move_cursor(affinity, CursorNavigationMode::LineExtent, context),
                     #[cfg(not(any(target_os = "ios", target_os = "macos")))]
@@ -506,6 +704,7 @@ where
                     NamedKey::ArrowLeft | NamedKey::ArrowRight if modifiers.word_select() => self.move_cursor(affinity, CursorNavigationMode::Word, context),
                     NamedKey::ArrowLeft | NamedKey::ArrowRight => self.move_cursor(affinity, CursorNavigationMode::Grapheme, context),
                     NamedKey::ArrowDown | NamedKey::ArrowUp => self.move_cursor(affinity, CursorNavigationMode::Line, context),
+                    NamedKey::PageDown | NamedKey::PageUp => self.move_cursor(affinity, CursorNavigationMode::Page, context),
                     _ => tracing::warn!("unhandled key: {key:?}"),
                 }
 
@@ -532,23 +731,31 @@ where
                 HANDLED
             }
             (state, _, Some(text))
-                if !context.modifiers().primary()
+                if is_text_entry(context.modifiers().state())
                     && text != "\t" // tab
-                    && text != "\r" // enter/return
+                    && (self.multiline || text != "\r") // enter/return
                     && text != "\u{1b}" // escape
                     =>
             {
                 if state.is_pressed() {
+                    let text = if text == "\r" { "\n" } else { text };
                     self.replace_selection(text, context);
                 }
                 HANDLED
             }
+            // Dead keys and compose sequences produce text once the
+            // sequence completes. The keys that begin a sequence must not be
+            // handled as shortcuts by other widgets.
+            (_, Key::Dead(_), None) => HANDLED,
             (_, _, _) =>  IGNORED,
         }
     }
 
     fn layout_text(&mut self, width: Option<Px>, context: &mut GraphicsContext<'_, '_, '_, '_>) {
         context.invalidate_when_changed(&self.value);
+        if let Some(highlight) = &self.highlight {
+            context.invalidate_when_changed(highlight);
+        }
 
         let mut key = {
             let (cursor, selection) = self.selected_range();
@@ -556,13 +763,16 @@ where
                 generation: self.value.generation(),
                 mask_generation: self.mask_symbol.generation(),
                 placeholder_generation: self.placeholder.generation(),
+                highlight_generation: self.highlight.as_ref().and_then(ReadOnly::generation),
                 width,
                 color: context.get(&TextColor),
+                selection_color: context.get(&SelectionForeground),
                 mask_bytes: self
                     .mask_symbol
                     .map(|sym| sym.graphemes(true).next().map_or(0, str::len)),
                 cursor,
                 selection,
+                preedit_generation: self.preedit_generation,
             }
         };
         match &mut self.cache {
@@ -571,9 +781,11 @@ where
                     && cache.placeholder.can_render_to(&context.gfx)
                     && cache.key == key => {}
             _ => {
-                let (bytes, measured, placeholder, ) = self.value.map_ref(|storage| {
+                let (bytes, preedit, measured, selected, placeholder) = self.value.map_ref(|storage| {
                     let mut text = storage.as_str();
                     let mut bytes = text.len();
+                    let mut preedit = None;
+                    let composed;
 
                     self.mask_symbol.map(|mask_symbol| {
                         // Another thread could have updated the mask symbol
@@ -600,19 +812,51 @@ where
                         }
                     });
 
-                    context.apply_current_font_settings();
-                    let mut text = Text::new(text, key.color);
-                    if let Some(width) = width {
-                        text = text.wrap_at(width);
+                    // Display the text being composed inline at the cursor.
+                    // Masked inputs never display the composition.
+                    if let Some(composing) = self
+                        .preedit
+                        .as_ref()
+                        .filter(|_| key.mask_bytes == 0 && key.cursor.offset <= text.len())
+                    {
+                        let range;
+                        (composed, range) = composing.insert_into(text, key.cursor.offset);
+                        text = &composed;
+                        bytes = text.len();
+                        preedit = Some(range);
                     }
 
+                    context.apply_current_font_settings();
+                    let measure = |color: Color, context: &mut GraphicsContext<'_, '_, '_, '_>| {
+                        match &self.highlight {
+                            Some(highlight) if key.mask_bytes == 0 => {
+                                let buffer = highlight.map(|highlight| {
+                                    highlight.prepare_buffer(text, color, width, None, context)
+                                });
+                                context.gfx.measure_text_buffer(&buffer, color)
+                            }
+                            _ => {
+                                let mut text = Text::new(text, color);
+                                if let Some(width) = width {
+                                    text = text.wrap_at(width);
+                                }
+                                context.gfx.measure_text(text)
+                            }
+                        }
+                    };
+                    let measured = measure(key.color, context);
+                    let selected = (key.selection.is_some() && key.selection_color != key.color)
+                        .then(|| measure(key.selection_color, context));
+
                     let placeholder_color = context.theme().surface.on_color_variant;
                     let placeholder = self.placeholder.map(|placeholder| context.gfx.measure_text(Text::new(placeholder, placeholder_color)));
-                    (bytes, context.gfx.measure_text(text), placeholder)
+                    (bytes, preedit, measured, selected, placeholder)
                 });
                 self.cache = Some(CachedLayout {
                     bytes,
+                    preedit,
                     measured,
+                    selected,
                     placeholder,
                     key,
                 });
@@ -644,6 +888,10 @@ where
                         value[..selection.offset].graphemes(true).count() * cache.key.mask_bytes;
                 }
             });
+        } else if let (Some(preedit), Some(selection)) = (&cache.preedit, &mut selection) {
+            // The preedit text is inserted at the cursor, which shifts the
+            // end of a selection that extends past the cursor.
+            selection.offset = offset_with_preedit(selection.offset, preedit);
         }
 
         CacheInfo {
@@ -804,8 +1052,113 @@ where
         }
     }
 
+    fn set_preedit(&mut self, preedit: Option<Preedit>) {
+        if self.preedit.is_some() || preedit.is_some() {
+            self.preedit = preedit;
+            self.preedit_generation = self.preedit_generation.wrapping_add(1);
+        }
+    }
+
+    /// Returns the areas covered by the current selection, relative to the
+    /// text's origin.
+    fn selection_rects(&self, info: &CacheInfo<'_>, width: Px) -> Vec<Rect<Px>> {
+        let Some(selection) = info.selection else {
+            return Vec::new();
+        };
+        let (start, end) = if selection < info.cursor {
+            (selection, info.cursor)
+        } else {
+            (info.cursor, selection)
+        };
+        let line_height = info.cache.measured.line_height;
+
+        let (start_position, _) = self.point_from_cursor(info.cache, start, info.cache.bytes);
+        let (end_position, end_width) = self.point_from_cursor(info.cache, end, info.cache.bytes);
+
+        if start_position.y == end_position.y {
+            // Single line selection
+            return vec![Rect::new(
+                start_position,
+                Size::new(end_position.x - start_position.x, line_height),
+            )];
+        }
+
+        // From the start to the end of the first line,
+        let mut rects = vec![Rect::new(
+            start_position,
+            Size::new(width - start_position.x, line_height),
+        )];
+        // the lines between,
+        let bottom_of_first_line = start_position.y + line_height;
+        let distance_between = end_position.y - bottom_of_first_line;
+        if distance_between > 0 {
+            rects.push(Rect::new(
+                Point::new(Px::ZERO, bottom_of_first_line),
+                Size::new(width, distance_between),
+            ));
+        }
+        // and from the start of the last line to the end.
+        rects.push(Rect::new(
+            Point::new(Px::ZERO, end_position.y),
+            Size::new(end_position.x + end_width, line_height),
+        ));
+        rects
+    }
+
+    fn underline_preedit(
+        &self,
+        preedit: Range<usize>,
+        info: &CacheInfo<'_>,
+        padding: Point<Px>,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        let color = context.get(&TextColor);
+        let thickness = Lp::points(1).into_px(context.gfx.scale()).max(Px::new(1));
+        let line_height = info.cache.measured.line_height;
+        let width = context.gfx.size().width.into_signed();
+        let start = Cursor {
+            offset: preedit.start,
+            affinity: Affinity::Before,
+        };
+        let end = Cursor {
+            offset: preedit.end,
+            affinity: Affinity::Before,
+        };
+        let (start, _) = self.point_from_cursor(info.cache, start, info.cache.bytes);
+        let (end, _) = self.point_from_cursor(info.cache, end, info.cache.bytes);
+
+        // Composition text can wrap, so underline each line it occupies.
+        let mut line_start = start;
+        while line_start.y < end.y {
+            context.gfx.draw_shape(
+                Shape::filled_rect(
+                    Rect::new(
+                        Point::new(line_start.x, line_start.y + line_height - thickness),
+                        Size::new(width - line_start.x, thickness),
+                    ),
+                    color,
+                )
+                .translate_by(padding),
+            );
+            line_start = Point::new(Px::ZERO, line_start.y + line_height);
+        }
+        context.gfx.draw_shape(
+            Shape::filled_rect(
+                Rect::new(
+                    Point::new(line_start.x, end.y + line_height - thickness),
+                    Size::new(end.x - line_start.x, thickness),
+                ),
+                color,
+            )
+            .translate_by(padding),
+        );
+    }
+
     fn cursor_from_point(&mut self, location: Point<Px>, context: &mut EventContext<'_>) -> Cursor {
         let mut cursor = self.cached_cursor_from_point(location, context);
+        if let Some(preedit) = self.cache.as_ref().and_then(|cache| cache.preedit.clone()) {
+            cursor.offset = offset_without_preedit(cursor.offset, &preedit);
+        }
         if let Some(symbol) = self.mask.graphemes(true).next() {
             let grapheme_offset = cursor.offset / symbol.len();
             cursor.offset = self.value.map_ref(|value| {
@@ -945,7 +1298,8 @@ enum CursorNavigationMode {
     Word,
     LineExtent,
     Line,
-    // Document,
+    Page,
+    Document,
 }
 
 impl<Storage> Debug for Input<Storage>
@@ -985,6 +1339,7 @@ where
         self.needs_to_select_all = false;
         self.selection.cursor = self.cursor_from_point(location, context);
         self.selection.start = Some(self.selection.cursor);
+        self.publish_selection();
         context.set_needs_redraw();
         HANDLED
     }
@@ -1004,9 +1359,11 @@ where
         _button: kludgine::app::winit::event::MouseButton,
         context: &mut EventContext<'_>,
     ) {
+        self.receive_selection();
         let cursor_location = self.cursor_from_point(location, context);
         if self.selection.cursor != cursor_location {
             self.selection.cursor = cursor_location;
+            self.publish_selection();
             context.set_needs_redraw();
         }
         self.blink_state.force_on();
@@ -1024,14 +1381,20 @@ where
 
     #[allow(clippy::too_many_lines)]
     fn redraw(&mut self, context: &mut crate::context::GraphicsContext<'_, '_, '_, '_>) {
+        if let Some(external) = &self.external_selection {
+            context.redraw_when_changed(external);
+        }
+        self.receive_selection();
         if self.needs_to_select_all {
             self.needs_to_select_all = false;
             if self.selection.start.is_none() {
                 self.select_all();
             }
         }
+        self.publish_selection();
 
-        self.blink_state.update(context.elapsed());
+        let blink = context.get(&CaretBlinkStyle);
+        self.blink_state.update(context.elapsed(), blink);
         let window_focused = context.window().focused().get_tracking_redraw(context);
         if window_focused != self.window_focused {
             if window_focused {
@@ -1053,9 +1416,17 @@ where
 
         let focused = context.focused(false);
 
+        if let Some(caret_area) = &self.caret_area {
+            let (location, _) = self.point_from_cursor(info.cache, info.cursor, info.cache.bytes);
+            caret_area.set(Rect::new(
+                location + padding,
+                Size::new(Px::ZERO, info.cache.measured.line_height),
+            ));
+        }
+
         let highlight = if focused && window_focused {
             context.draw_focus_ring();
-            context.get(&HighlightColor)
+            context.get(&SelectionBackground)
         } else {
             let outline_color = context.get(&OutlineColor);
             context.stroke_outline::<Lp>(outline_color, StrokeOptions::default());
@@ -1063,8 +1434,13 @@ where
         };
 
         if focused {
+            // Place the IME candidate window beside the caret.
+            let (location, _) = self.point_from_cursor(info.cache, info.cursor, info.cache.bytes);
             context.set_ime_allowed(true);
-            context.set_ime_location(context.gfx.region());
+            context.set_ime_location(Rect::new(
+                context.gfx.region().origin + location + padding,
+                Size::new(Px::ZERO, info.cache.measured.line_height),
+            ));
             context.set_ime_purpose(if info.masked {
                 ImePurpose::Password
             } else {
@@ -1072,86 +1448,49 @@ where
             });
         }
 
-        if let Some(selection) = info.selection {
-            let (start, end) = if selection < info.cursor {
-                (selection, info.cursor)
-            } else {
-                (info.cursor, selection)
-            };
-
-            let (start_position, _) = self.point_from_cursor(info.cache, start, info.cache.bytes);
-            let (end_position, end_width) =
-                self.point_from_cursor(info.cache, end, info.cache.bytes);
+        let selection_rects = self.selection_rects(&info, size.width.into_signed());
+        for rect in &selection_rects {
+            context
+                .gfx
+                .draw_shape(Shape::filled_rect(*rect, highlight).translate_by(padding));
+        }
 
-            if start_position.y == end_position.y {
-                // Single line selection
-                let width = end_position.x - start_position.x;
-                context.gfx.draw_shape(
-                    Shape::filled_rect(
-                        Rect::new(
-                            start_position,
-                            Size::new(width, info.cache.measured.line_height),
-                        ),
-                        highlight,
-                    )
-                    .translate_by(padding),
-                );
-            } else {
-                // Draw from start to end of line,
-                let width = size.width.into_signed() - start_position.x;
-                context.gfx.draw_shape(
-                    Shape::filled_rect(
-                        Rect::new(
-                            start_position,
-                            Size::new(width, info.cache.measured.line_height),
-                        ),
-                        highlight,
-                    )
-                    .translate_by(padding),
-                );
-                // Fill region between
-                let bottom_of_first_line = start_position.y + info.cache.measured.line_height;
-                let distance_between = end_position.y - bottom_of_first_line;
-                if distance_between > 0 {
-                    context.gfx.draw_shape(
-                        Shape::filled_rect(
-                            Rect::new(
-                                Point::new(Px::ZERO, bottom_of_first_line),
-                                Size::new(size.width.into_signed(), distance_between),
-                            ),
-                            highlight,
-                        )
-                        .translate_by(padding),
-                    );
-                }
-                // Draw from 0 to end + width
-                context.gfx.draw_shape(
-                    Shape::filled_rect(
-                        Rect::new(
-                            Point::new(Px::ZERO, end_position.y),
-                            Size::new(end_position.x + end_width, info.cache.measured.line_height),
-                        ),
-                        highlight,
-                    )
-                    .translate_by(padding),
-                );
+        if selection_rects.is_empty() && focused && window_focused && context.enabled() {
+            let mut caret = info.cursor;
+            if let (
+                Some(preedit),
+                Some(Preedit {
+                    cursor: Some((preedit_cursor, _)),
+                    ..
+                }),
+            ) = (&info.cache.preedit, &self.preedit)
+            {
+                caret.offset = preedit.start + (*preedit_cursor).min(preedit.len());
             }
-        } else if focused && window_focused && context.enabled() {
-            let (location, _) = self.point_from_cursor(info.cache, info.cursor, info.cache.bytes);
+            let (location, _) = self.point_from_cursor(info.cache, caret, info.cache.bytes);
             if cursor_state.visible {
-                let cursor_width = Lp::points(2).into_px(context.gfx.scale());
+                let cursor_width = context
+                    .get(&CaretWidth)
+                    .into_px(context.gfx.scale())
+                    .max(Px::new(1));
                 context.gfx.draw_shape(
                     Shape::filled_rect(
                         Rect::new(
                             Point::new(location.x - cursor_width / 2, location.y),
                             Size::new(cursor_width, info.cache.measured.line_height),
                         ),
-                        highlight,
+                        context.get(&CaretColor),
                     )
                     .translate_by(padding),
                 );
             }
-            context.redraw_in(cursor_state.remaining_until_blink);
+            if matches!(blink, CaretBlink::Interval(interval) if !interval.is_zero()) {
+                context.redraw_in(cursor_state.remaining_until_blink);
+            }
+        }
+
+        if let Some(preedit) = &info.cache.preedit {
+            self.underline_preedit(preedit.clone(), &info, padding, context);
         }
 
         let text = if info.cache.bytes > 0 {
@@ -1162,6 +1501,18 @@ where
         context
             .gfx
             .draw_measured_text(text.translate_by(padding), TextOrigin::TopLeft);
+
+        // Redraw the selected portion of the text using the selection's
+        // foreground color.
+        if let Some(selected) = &info.cache.selected {
+            for rect in selection_rects {
+                let clip = Rect::new(rect.origin + padding, rect.size);
+                context.gfx.clipped_to(clip).draw_measured_text(
+                    selected.translate_by(padding - clip.origin),
+                    TextOrigin::TopLeft,
+                );
+            }
+        }
     }
 
     fn layout(
@@ -1200,7 +1551,42 @@ where
             on_key.invoke(input.clone())?;
         }
 
+        match self
+            .unicode_entry
+            .key_input(&input, context.modifiers().state())
+        {
+            UnicodeEntryEvent::Ignored => {}
+            UnicodeEntryEvent::Updated => {
+                self.set_preedit(
+                    self.unicode_entry
+                        .pending()
+                        .map(|text| Preedit { text, cursor: None }),
+                );
+                context.set_needs_redraw();
+                return HANDLED;
+            }
+            UnicodeEntryEvent::Finished {
+                character,
+                consumed,
+            } => {
+                self.set_preedit(None);
+                if let Some(character) = character {
+                    self.receive_selection();
+                    self.replace_selection(character.encode_utf8(&mut [0; 4]), context);
+                    self.publish_selection();
+                    self.report_length();
+                }
+                context.set_needs_redraw();
+                if consumed {
+                    return HANDLED;
+                }
+            }
+        }
+
+        self.receive_selection();
         let handled = self.handle_key(input, context);
+        self.publish_selection();
+        self.report_length();
 
         if handled.is_break() {
             context.set_needs_redraw();
@@ -1213,12 +1599,22 @@ where
 
     fn ime(&mut self, ime: Ime, context: &mut EventContext<'_>) -> EventHandling {
         match ime {
-            Ime::Enabled | Ime::Disabled => {}
+            Ime::Enabled => {}
+            Ime::Disabled => {
+                self.set_preedit(None);
+                context.set_needs_redraw();
+            }
             Ime::Preedit(text, cursor) => {
-                tracing::warn!("TODO: preview IME input {text}, cursor: {cursor:?}");
+                self.set_preedit((!text.is_empty()).then_some(Preedit { text, cursor }));
+                self.blink_state.force_on();
+                context.set_needs_redraw();
             }
             Ime::Commit(text) => {
+                self.set_preedit(None);
+                self.receive_selection();
                 self.replace_selection(&text, context);
+                self.publish_selection();
+                self.report_length();
                 context.set_needs_redraw();
             }
         }
@@ -1227,7 +1623,13 @@ where
     }
 
     fn focus(&mut self, context: &mut EventContext<'_>) {
-        if self.mouse_buttons_down == 0 {
+        // A selection transferred from a replaced input should not be
+        // overwritten when focus is also transferred.
+        let selection_restored = mem::take(&mut self.selection_restored);
+        if self.mouse_buttons_down == 0
+            && !selection_restored
+            && context.get(&SelectOnFocus).selects_all(self.multiline)
+        {
             self.needs_to_select_all = true;
         }
 
@@ -1241,15 +1643,58 @@ where
     }
 
     fn blur(&mut self, context: &mut EventContext<'_>) {
+        if self.unicode_entry.is_active() {
+            self.unicode_entry.cancel();
+        }
+        self.set_preedit(None);
         context.set_ime_allowed(false);
         context.set_needs_redraw();
     }
+
+    fn preserve_state(&mut self, _context: &mut EventContext<'_>) -> Option<Box<dyn Any + Send>> {
+        self.receive_selection();
+        Some(Box::new(self.selection))
+    }
+
+    fn restore_state(&mut self, state: Box<dyn Any + Send>, context: &mut EventContext<'_>) {
+        if let Ok(selection) = state.downcast::<SelectionState>() {
+            self.selection = *selection;
+            self.constrain_selection();
+            self.publish_selection();
+            self.selection_restored = true;
+            context.set_needs_redraw();
+        }
+    }
+}
+
+/// Returns the height of the portion of the current widget that is visible
+/// within its ancestors, such as the viewport of an enclosing
+/// [`Scroll`](crate::widgets::Scroll).
+///
+/// If the widget has not been laid out, the window's height is returned.
+fn visible_height(context: &EventContext<'_>) -> Px {
+    let window_height = context.window().inner_size().get().height.into_signed();
+    let Some(mut visible) = context.last_layout() else {
+        return window_height;
+    };
+    let mut ancestor = context.parent();
+    while let Some(widget) = ancestor {
+        if let Some(layout) = widget.last_layout() {
+            let Some(clipped) = visible.intersection(&layout) else {
+                return Px::ZERO;
+            };
+            visible = clipped;
+        }
+        ancestor = widget.parent();
+    }
+    visible.size.height.min(window_height)
 }
 
 #[derive(Clone, Copy)]
 struct BlinkState {
     visible: bool,
     remaining_until_blink: Duration,
+    interval: Duration,
 }
 
 impl Default for BlinkState {
@@ -1257,15 +1702,30 @@ impl Default for BlinkState {
         Self {
             visible: true,
             remaining_until_blink: CURSOR_BLINK_DURATION,
+            interval: CURSOR_BLINK_DURATION,
         }
     }
 }
 
 impl BlinkState {
-    pub fn update(&mut self, elapsed: Duration) {
-        let total_cycles = elapsed.as_nanos() / CURSOR_BLINK_DURATION.as_nanos();
+    pub fn update(&mut self, elapsed: Duration, blink: CaretBlink) {
+        match blink {
+            CaretBlink::Interval(interval) if !interval.is_zero() => {
+                if interval != self.interval {
+                    self.interval = interval;
+                    self.force_on();
+                    return;
+                }
+            }
+            _ => {
+                self.force_on();
+                return;
+            }
+        }
+
+        let total_cycles = elapsed.as_nanos() / self.interval.as_nanos();
         let remaining = Duration::from_nanos(
-            u64::try_from(elapsed.as_nanos() % CURSOR_BLINK_DURATION.as_nanos())
+            u64::try_from(elapsed.as_nanos() % self.interval.as_nanos())
                 .expect("remainder fits in u64"),
         );
         // If we have an odd number of totaal cycles, flip the visibility.
@@ -1277,14 +1737,142 @@ impl BlinkState {
             self.remaining_until_blink = remaining;
         } else {
             self.visible = !self.visible;
-            self.remaining_until_blink =
-                CURSOR_BLINK_DURATION - (remaining - self.remaining_until_blink);
+            self.remaining_until_blink = self.interval - (remaining - self.remaining_until_blink);
         }
     }
 
     pub fn force_on(&mut self) {
         self.visible = true;
-        self.remaining_until_blink = CURSOR_BLINK_DURATION;
+        self.remaining_until_blink = self.interval;
+    }
+}
+
+/// Returns true if text produced while `modifiers` are pressed should be
+/// inserted rather than treated as a shortcut.
+fn is_text_entry(modifiers: ModifiersState) -> bool {
+    // Windows reports AltGr as Control and Alt being pressed together. The
+    // characters it produces, such as `@` on German layouts, must still be
+    // entered.
+    !modifiers.primary()
+        || (cfg!(not(any(target_os = "macos", target_os = "ios"))) && modifiers.alt_key())
+}
+
+define_components! {
+    Input {
+        /// The color of the caret of an [`Input`] or
+        /// [`TextEditor`](crate::widgets::TextEditor).
+        CaretColor(Color, "caret_color", @HighlightColor)
+        /// The width of the caret of an [`Input`] or
+        /// [`TextEditor`](crate::widgets::TextEditor).
+        CaretWidth(Dimension, "caret_width", Dimension::Lp(Lp::points(2)))
+        /// How the caret of an [`Input`] or
+        /// [`TextEditor`](crate::widgets::TextEditor) blinks.
+        CaretBlinkStyle(CaretBlink, "caret_blink", CaretBlink::default())
+        /// The background color of selected text in an [`Input`] or
+        /// [`TextEditor`](crate::widgets::TextEditor).
+        SelectionBackground(Color, "selection_background", @HighlightColor)
+        /// The color of selected text in an [`Input`] or
+        /// [`TextEditor`](crate::widgets::TextEditor).
+        SelectionForeground(Color, "selection_foreground", @TextColor)
+        /// Whether an [`Input`] or [`TextEditor`](crate::widgets::TextEditor)
+        /// selects its contents when it receives focus.
+        SelectOnFocus(FocusSelection, "select_on_focus", FocusSelection::default())
+    }
+}
+
+/// How a text caret blinks.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CaretBlink {
+    /// The caret is always visible.
+    ///
+    /// Blinking can be distracting or uncomfortable for some users, and this
+    /// setting can be used to honor a user's preference to reduce motion.
+    Steady,
+    /// The caret toggles its visibility each time this duration elapses.
+    Interval(Duration),
+}
+
+impl Default for CaretBlink {
+    fn default() -> Self {
+        Self::Interval(CURSOR_BLINK_DURATION)
+    }
+}
+
+impl From<CaretBlink> for Component {
+    fn from(value: CaretBlink) -> Self {
+        Component::custom(value)
+    }
+}
+
+impl TryFrom<Component> for CaretBlink {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Custom(custom) => custom
+                .downcast()
+                .copied()
+                .ok_or_else(|| Component::Custom(custom)),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for CaretBlink {
+    fn requires_invalidation(&self) -> bool {
+        false
+    }
+}
+
+/// Whether a text input selects its contents when focused.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum FocusSelection {
+    /// Single-line inputs select their contents when focused using the
+    /// keyboard. Multi-line inputs keep their existing selection.
+    #[default]
+    Automatic,
+    /// All inputs select their contents when focused using the keyboard.
+    SelectAll,
+    /// Inputs keep their existing selection when focused.
+    Preserve,
+}
+
+impl FocusSelection {
+    /// Returns true if an input should select all of its contents when
+    /// focused.
+    #[must_use]
+    pub const fn selects_all(self, multiline: bool) -> bool {
+        match self {
+            Self::Automatic => !multiline,
+            Self::SelectAll => true,
+            Self::Preserve => false,
+        }
+    }
+}
+
+impl From<FocusSelection> for Component {
+    fn from(value: FocusSelection) -> Self {
+        Component::custom(value)
+    }
+}
+
+impl TryFrom<Component> for FocusSelection {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Custom(custom) => custom
+                .downcast()
+                .copied()
+                .ok_or_else(|| Component::Custom(custom)),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for FocusSelection {
+    fn requires_invalidation(&self) -> bool {
+        false
     }
 }
 
@@ -1552,3 +2140,103 @@ macro_rules! impl_cow_string {
 
 impl_cow_string!(CowString, false);
 impl_cow_string!(MaskedString, true);
+
+#[test]
+fn preedit_offsets() {
+    let preedit = Preedit {
+        text: String::from("xyz"),
+        cursor: None,
+    };
+    let (composed, range) = preedit.insert_into("abcd", 2);
+    assert_eq!(composed, "abxyzcd");
+    assert_eq!(range, 2..5);
+
+    assert_eq!(offset_with_preedit(1, &range), 1);
+    assert_eq!(offset_with_preedit(2, &range), 2);
+    assert_eq!(offset_with_preedit(3, &range), 6);
+
+    assert_eq!(offset_without_preedit(1, &range), 1);
+    assert_eq!(offset_without_preedit(4, &range), 2);
+    assert_eq!(offset_without_preedit(5, &range), 2);
+    assert_eq!(offset_without_preedit(6, &range), 3);
+}
+
+#[test]
+fn ime_composition_commits_into_value() {
+    use crate::widget::MakeWidget;
+
+    let value = Dynamic::new(String::from("ab"));
+    let mut recorder = value
+        .clone()
+        .into_input()
+        .build_recorder()
+        .size(Size::new(UPx::new(200), UPx::new(40)))
+        .finish()
+        .expect("error creating recorder");
+
+    // Composition is displayed without changing the value.
+    recorder
+        .window
+        .ime(&Ime::Preedit(String::from("ka"), Some((2, 2))));
+    recorder.refresh().expect("error rendering");
+    assert_eq!(value.get(), "ab");
+
+    recorder.window.ime(&Ime::Commit(String::from("か")));
+    recorder.refresh().expect("error rendering");
+    let committed = value.get();
+    assert!(
+        matches!(committed.as_str(), "かab" | "abか"),
+        "unexpected value {committed:?}"
+    );
+
+    // Cancelling a composition leaves the value unchanged.
+    recorder.window.ime(&Ime::Preedit(String::from("ki"), None));
+    recorder.window.ime(&Ime::Preedit(String::new(), None));
+    recorder.window.ime(&Ime::Disabled);
+    recorder.refresh().expect("error rendering");
+    assert_eq!(value.get(), committed);
+}
+
+#[test]
+fn caret_blinks_at_interval() {
+    let ms = Duration::from_millis;
+    let mut state = BlinkState::default();
+    state.update(ms(200), CaretBlink::default());
+    assert!(state.visible);
+    assert_eq!(state.remaining_until_blink, ms(300));
+    state.update(ms(400), CaretBlink::default());
+    assert!(!state.visible);
+    assert_eq!(state.remaining_until_blink, ms(400));
+    // Whole cycles toggle twice, leaving the visibility unchanged.
+    state.update(ms(1_000), CaretBlink::default());
+    assert!(!state.visible);
+    assert_eq!(state.remaining_until_blink, ms(400));
+
+    // Changing the interval restarts blinking with the caret visible.
+    state.update(ms(50), CaretBlink::Interval(ms(100)));
+    assert!(state.visible);
+    assert_eq!(state.remaining_until_blink, ms(100));
+    state.update(ms(150), CaretBlink::Interval(ms(100)));
+    assert!(!state.visible);
+    assert_eq!(state.remaining_until_blink, ms(50));
+
+    // Steady carets and zero intervals never hide the caret.
+    for blink in [CaretBlink::Steady, CaretBlink::Interval(Duration::ZERO)] {
+        state.update(ms(10_050), blink);
+        assert!(state.visible);
+    }
+}
+
+#[test]
+fn focus_selection() {
+    assert!(FocusSelection::Automatic.selects_all(false));
+    assert!(!FocusSelection::Automatic.selects_all(true));
+    assert!(FocusSelection::SelectAll.selects_all(false));
+    assert!(FocusSelection::SelectAll.selects_all(true));
+    assert!(!FocusSelection::Preserve.selects_all(false));
+    assert!(!FocusSelection::Preserve.selects_all(true));
+    assert_eq!(
+        FocusSelection::try_from(Component::from(FocusSelection::Preserve)),
+        Ok(FocusSelection::Preserve)
+    );
+}
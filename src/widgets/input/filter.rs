@@ -0,0 +1,349 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::widget::Callback;
+
+/// Rules applied to text as it is entered into an
+/// [`Input`](crate::widgets::Input).
+///
+/// Filters are applied to typed, pasted, and composed text before it is
+/// inserted into the input's value. Rules are applied in the order they are
+/// added, followed by the [maximum length](Self::max_length) and
+/// [mask](Self::mask).
+///
+/// ```rust
+/// use cushy::widgets::input::InputFilter;
+///
+/// let filter = InputFilter::new()
+///     .uppercase()
+///     .allowing(|ch| ch.is_ascii_alphanumeric())
+///     .max_length(8);
+/// ```
+#[derive(Default)]
+#[must_use]
+pub struct InputFilter {
+    rules: Vec<FilterRule>,
+    max_length: Option<usize>,
+    mask: Option<InputMask>,
+    on_length_changed: Option<Callback<usize>>,
+    reported_length: Option<usize>,
+}
+
+enum FilterRule {
+    Allow(Box<dyn Fn(char) -> bool + Send>),
+    Transform(Box<dyn Fn(&str) -> String + Send>),
+}
+
+impl InputFilter {
+    /// Returns a filter that accepts all text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes all characters that `predicate` returns false for and returns
+    /// self.
+    pub fn allowing<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(char) -> bool + Send + 'static,
+    {
+        self.rules.push(FilterRule::Allow(Box::new(predicate)));
+        self
+    }
+
+    /// Removes all characters not contained in `allowed` and returns self.
+    pub fn allowing_chars(self, allowed: impl Into<String>) -> Self {
+        let allowed = allowed.into();
+        self.allowing(move |ch| allowed.contains(ch))
+    }
+
+    /// Removes all characters that `predicate` returns true for and returns
+    /// self.
+    pub fn denying<F>(self, predicate: F) -> Self
+    where
+        F: Fn(char) -> bool + Send + 'static,
+    {
+        self.allowing(move |ch| !predicate(ch))
+    }
+
+    /// Removes all characters contained in `denied` and returns self.
+    pub fn denying_chars(self, denied: impl Into<String>) -> Self {
+        let denied = denied.into();
+        self.allowing(move |ch| !denied.contains(ch))
+    }
+
+    /// Replaces entered text with the result of `transform` and returns self.
+    pub fn transforming<F>(mut self, transform: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + 'static,
+    {
+        self.rules.push(FilterRule::Transform(Box::new(transform)));
+        self
+    }
+
+    /// Converts entered text to uppercase and returns self.
+    pub fn uppercase(self) -> Self {
+        self.transforming(str::to_uppercase)
+    }
+
+    /// Converts entered text to lowercase and returns self.
+    pub fn lowercase(self) -> Self {
+        self.transforming(str::to_lowercase)
+    }
+
+    /// Limits the value to `max_length` graphemes and returns self.
+    ///
+    /// Entered text that would cause the value to exceed this length is
+    /// truncated.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Formats the value using `mask` and returns self.
+    pub fn mask(mut self, mask: impl Into<InputMask>) -> Self {
+        self.mask = Some(mask.into());
+        self
+    }
+
+    /// Invokes `on_length_changed` with the number of graphemes in the value
+    /// each time the user changes its length.
+    ///
+    /// This can be used to display a live character counter alongside an
+    /// input with a [maximum length](Self::max_length).
+    pub fn on_length_changed<F>(mut self, on_length_changed: F) -> Self
+    where
+        F: FnMut(usize) + Send + 'static,
+    {
+        self.on_length_changed = Some(Callback::new(on_length_changed));
+        self
+    }
+
+    /// Returns the result of replacing `range` of `value` with `inserted`
+    /// after applying this filter, along with the byte offset the cursor
+    /// should be placed at.
+    ///
+    /// Returns `None` if the filter rejects the change.
+    #[must_use]
+    pub fn apply(
+        &self,
+        value: &str,
+        range: Range<usize>,
+        inserted: &str,
+    ) -> Option<(String, usize)> {
+        let mut inserted = inserted.to_string();
+        for rule in &self.rules {
+            inserted = match rule {
+                FilterRule::Allow(predicate) => {
+                    inserted.chars().filter(|ch| predicate(*ch)).collect()
+                }
+                FilterRule::Transform(transform) => transform(&inserted),
+            };
+        }
+
+        let (prefix, suffix) = (&value[..range.start], &value[range.end..]);
+        if let Some(max_length) = self.max_length {
+            let remaining = max_length
+                .saturating_sub(prefix.graphemes(true).count() + suffix.graphemes(true).count());
+            inserted = inserted.graphemes(true).take(remaining).collect();
+        }
+
+        if inserted.is_empty() && range.is_empty() {
+            return None;
+        }
+
+        if let Some(mask) = &self.mask {
+            Some(mask.replace(value, range, &inserted))
+        } else {
+            Some((
+                [prefix, &inserted, suffix].concat(),
+                range.start + inserted.len(),
+            ))
+        }
+    }
+
+    pub(super) fn value_changed(&mut self, value: &str) {
+        let Some(on_length_changed) = &mut self.on_length_changed else {
+            return;
+        };
+        let length = value.graphemes(true).count();
+        if self.reported_length != Some(length) {
+            self.reported_length = Some(length);
+            on_length_changed.invoke(length);
+        }
+    }
+}
+
+impl Debug for InputFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InputFilter")
+            .field("rules", &self.rules.len())
+            .field("max_length", &self.max_length)
+            .field("mask", &self.mask)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A template that formats the value of an [`Input`](crate::widgets::Input).
+///
+/// Each character of the template is either a placeholder that accepts
+/// entered characters or a literal that is inserted automatically:
+///
+/// - `#`: An ASCII digit.
+/// - `A`: An alphabetic character.
+/// - `*`: An alphanumeric character.
+/// - `?`: Any character.
+/// - `\`: Treats the next character as a literal.
+///
+/// All other characters are literals. For example, `##/##/####` formats
+/// `12312024` as `12/31/2024`. Literals are only inserted once a character
+/// follows them, and entered characters that do not match their placeholder
+/// are removed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InputMask {
+    slots: Vec<MaskSlot>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum MaskSlot {
+    Literal(char),
+    Digit,
+    Alphabetic,
+    Alphanumeric,
+    Any,
+}
+
+impl MaskSlot {
+    fn accepts(self, ch: char) -> bool {
+        match self {
+            MaskSlot::Literal(_) => false,
+            MaskSlot::Digit => ch.is_ascii_digit(),
+            MaskSlot::Alphabetic => ch.is_alphabetic(),
+            MaskSlot::Alphanumeric => ch.is_alphanumeric(),
+            MaskSlot::Any => true,
+        }
+    }
+}
+
+impl InputMask {
+    /// Returns a mask that formats values using `template`.
+    #[must_use]
+    pub fn new(template: &str) -> Self {
+        let mut slots = Vec::with_capacity(template.len());
+        let mut chars = template.chars();
+        while let Some(ch) = chars.next() {
+            slots.push(match ch {
+                '#' => MaskSlot::Digit,
+                'A' => MaskSlot::Alphabetic,
+                '*' => MaskSlot::Alphanumeric,
+                '?' => MaskSlot::Any,
+                '\\' => MaskSlot::Literal(chars.next().unwrap_or('\\')),
+                other => MaskSlot::Literal(other),
+            });
+        }
+        Self { slots }
+    }
+
+    /// Returns true if `value` fills every placeholder of this mask.
+    #[must_use]
+    pub fn is_complete(&self, value: &str) -> bool {
+        value.chars().count() == self.slots.len()
+    }
+
+    /// Returns `value` formatted using this mask.
+    #[must_use]
+    pub fn format(&self, value: &str) -> String {
+        let entered = self.entered(value);
+        self.layout(&entered, entered.len()).0
+    }
+
+    /// Returns the characters of `value` that were entered by the user,
+    /// removing any literals inserted by this mask.
+    fn entered(&self, value: &str) -> Vec<char> {
+        let mut slots = self.slots.iter();
+        value
+            .chars()
+            .filter(|ch| !matches!(slots.next(), Some(MaskSlot::Literal(literal)) if literal == ch))
+            .collect()
+    }
+
+    fn replace(&self, value: &str, range: Range<usize>, inserted: &str) -> (String, usize) {
+        let before = self.entered(&value[..range.start]);
+        let replaced = self.entered(&value[..range.end]).len();
+        let after = self.entered(value).split_off(replaced);
+
+        let mut entered = before;
+        entered.extend(inserted.chars());
+        let cursor_index = entered.len();
+        entered.extend(after);
+        self.layout(&entered, cursor_index)
+    }
+
+    /// Places `entered` into this mask's placeholders, returning the formatted
+    /// value and the byte offset after the character at `cursor_index`.
+    fn layout(&self, entered: &[char], cursor_index: usize) -> (String, usize) {
+        let mut formatted = String::new();
+        let mut cursor = 0;
+        let mut entered = entered.iter().copied().enumerate().peekable();
+        for slot in &self.slots {
+            if entered.peek().is_none() {
+                break;
+            }
+            if let MaskSlot::Literal(literal) = slot {
+                formatted.push(*literal);
+                continue;
+            }
+            // Discard entered characters this placeholder does not accept.
+            let Some((index, ch)) = entered.find(|(_, ch)| slot.accepts(*ch)) else {
+                break;
+            };
+            formatted.push(ch);
+            if index < cursor_index {
+                cursor = formatted.len();
+            }
+        }
+        (formatted, cursor)
+    }
+}
+
+impl From<&str> for InputMask {
+    fn from(template: &str) -> Self {
+        Self::new(template)
+    }
+}
+
+impl From<String> for InputMask {
+    fn from(template: String) -> Self {
+        Self::new(&template)
+    }
+}
+
+#[test]
+fn masks() {
+    let filter = InputFilter::new().mask("##/##/####");
+    assert_eq!(filter.apply("", 0..0, "12"), Some((String::from("12"), 2)));
+    assert_eq!(
+        filter.apply("12", 2..2, "3"),
+        Some((String::from("12/3"), 4))
+    );
+    assert_eq!(
+        filter.apply("12/3", 4..4, "x1/2024"),
+        Some((String::from("12/31/2024"), 10))
+    );
+    // Inserting in the middle shifts the following characters.
+    assert_eq!(
+        filter.apply("12/31/2024", 0..0, "0"),
+        Some((String::from("01/23/1202"), 1))
+    );
+
+    let filter = InputFilter::new()
+        .uppercase()
+        .denying_chars("0")
+        .max_length(4);
+    assert_eq!(
+        filter.apply("ab", 2..2, "c0de"),
+        Some((String::from("abCD"), 4))
+    );
+    assert_eq!(filter.apply("abCD", 4..4, "e"), None);
+}
@@ -0,0 +1,198 @@
+use kludgine::app::winit::event::ElementState;
+use kludgine::app::winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::window::KeyEvent;
+
+/// The maximum number of hex digits in a unicode scalar value.
+const MAX_DIGITS: usize = 6;
+
+/// Entry of characters by their hexadecimal code point.
+///
+/// Pressing Ctrl+Shift+U begins entering a code point. Hexadecimal digits
+/// are collected until Enter or Space is pressed, at which point the
+/// character is inserted. Escape cancels entry, and Backspace removes the last
+/// digit. This mirrors the unicode entry available in GTK applications, and
+/// provides a way to enter any character when no input method is available.
+#[derive(Debug, Default)]
+pub(super) struct UnicodeEntry {
+    digits: Option<String>,
+}
+
+/// The result of providing a key to a [`UnicodeEntry`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum UnicodeEntryEvent {
+    /// The key was not used for unicode entry.
+    Ignored,
+    /// The key was consumed and the pending entry may have changed.
+    Updated,
+    /// Entry finished, producing a character if a valid code point was
+    /// entered. If `consumed` is false, the key should continue to be
+    /// handled normally.
+    Finished {
+        character: Option<char>,
+        consumed: bool,
+    },
+}
+
+impl UnicodeEntry {
+    /// Returns true if a code point is currently being entered.
+    pub fn is_active(&self) -> bool {
+        self.digits.is_some()
+    }
+
+    /// Returns the text to display while entering a code point.
+    pub fn pending(&self) -> Option<String> {
+        self.digits.as_ref().map(|digits| format!("u{digits}"))
+    }
+
+    /// Cancels any pending entry.
+    pub fn cancel(&mut self) {
+        self.digits = None;
+    }
+
+    /// Processes `input`, which occurred while `modifiers` were pressed.
+    pub fn key_input(&mut self, input: &KeyEvent, modifiers: ModifiersState) -> UnicodeEntryEvent {
+        let Some(digits) = &mut self.digits else {
+            if input.state == ElementState::Pressed
+                && modifiers.control_key()
+                && modifiers.shift_key()
+                && !modifiers.alt_key()
+                && matches!(&input.logical_key, Key::Character(ch) if ch.eq_ignore_ascii_case("u"))
+            {
+                self.digits = Some(String::new());
+                return UnicodeEntryEvent::Updated;
+            }
+            return UnicodeEntryEvent::Ignored;
+        };
+
+        if input.state == ElementState::Released {
+            return UnicodeEntryEvent::Updated;
+        }
+
+        match &input.logical_key {
+            Key::Named(NamedKey::Enter | NamedKey::Space) => UnicodeEntryEvent::Finished {
+                character: self.finish(),
+                consumed: true,
+            },
+            Key::Named(NamedKey::Escape) => {
+                self.cancel();
+                UnicodeEntryEvent::Finished {
+                    character: None,
+                    consumed: true,
+                }
+            }
+            Key::Named(NamedKey::Backspace) => {
+                if digits.pop().is_none() {
+                    self.cancel();
+                }
+                UnicodeEntryEvent::Updated
+            }
+            // Holding Ctrl+Shift while typing the digits is allowed.
+            Key::Character(ch) if modifiers.control_key() && ch.eq_ignore_ascii_case("u") => {
+                UnicodeEntryEvent::Updated
+            }
+            Key::Character(ch)
+                if digits.len() < MAX_DIGITS && ch.chars().all(|ch| ch.is_ascii_hexdigit()) =>
+            {
+                digits.push_str(ch);
+                UnicodeEntryEvent::Updated
+            }
+            Key::Named(NamedKey::Shift | NamedKey::Control) => UnicodeEntryEvent::Updated,
+            _ => UnicodeEntryEvent::Finished {
+                character: self.finish(),
+                consumed: false,
+            },
+        }
+    }
+
+    fn finish(&mut self) -> Option<char> {
+        let digits = self.digits.take()?;
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+    }
+}
+
+#[cfg(test)]
+fn key(logical_key: Key) -> KeyEvent {
+    use kludgine::app::winit::event::Modifiers;
+    use kludgine::app::winit::keyboard::{KeyLocation, NativeKeyCode, PhysicalKey};
+
+    KeyEvent {
+        logical_key,
+        physical_key: PhysicalKey::Unidentified(NativeKeyCode::Xkb(0)),
+        text: None,
+        location: KeyLocation::Standard,
+        state: ElementState::Pressed,
+        repeat: false,
+        modifiers: Modifiers::default(),
+    }
+}
+
+#[test]
+fn unicode_sequences() {
+    fn enter(entry: &mut UnicodeEntry, keys: &str, finish: NamedKey) -> UnicodeEntryEvent {
+        let shortcut = ModifiersState::CONTROL | ModifiersState::SHIFT;
+        assert_eq!(
+            entry.key_input(&key(Key::Character("U".into())), shortcut),
+            UnicodeEntryEvent::Updated
+        );
+        for ch in keys.chars() {
+            assert_eq!(
+                entry.key_input(
+                    &key(Key::Character(ch.to_string().into())),
+                    ModifiersState::empty()
+                ),
+                UnicodeEntryEvent::Updated
+            );
+        }
+        entry.key_input(&key(Key::Named(finish)), ModifiersState::empty())
+    }
+
+    let mut entry = UnicodeEntry::default();
+    assert_eq!(
+        enter(&mut entry, "e9", NamedKey::Space),
+        UnicodeEntryEvent::Finished {
+            character: Some('é'),
+            consumed: true
+        }
+    );
+    assert_eq!(
+        enter(&mut entry, "1F600", NamedKey::Enter),
+        UnicodeEntryEvent::Finished {
+            character: Some('😀'),
+            consumed: true
+        }
+    );
+    // Surrogates are not valid characters.
+    assert_eq!(
+        enter(&mut entry, "d800", NamedKey::Enter),
+        UnicodeEntryEvent::Finished {
+            character: None,
+            consumed: true
+        }
+    );
+    assert_eq!(
+        enter(&mut entry, "41", NamedKey::Escape),
+        UnicodeEntryEvent::Finished {
+            character: None,
+            consumed: true
+        }
+    );
+    assert!(!entry.is_active());
+
+    // Other keys finish entry and continue to be handled.
+    assert_eq!(
+        enter(&mut entry, "41", NamedKey::Tab),
+        UnicodeEntryEvent::Finished {
+            character: Some('A'),
+            consumed: false
+        }
+    );
+
+    // Without the shortcut, keys are ignored.
+    assert_eq!(
+        entry.key_input(&key(Key::Character("u".into())), ModifiersState::empty()),
+        UnicodeEntryEvent::Ignored
+    );
+}
@@ -9,6 +9,7 @@ use kludgine::text::{MeasuredText, Text, TextOrigin};
 use kludgine::{cosmic_text, CanRenderTo, Color, DrawableExt};
 
 use super::input::CowString;
+use super::rich_text::RichText;
 use crate::context::{FontSettings, GraphicsContext, LayoutContext, Trackable, WidgetContext};
 use crate::reactive::value::{
     Dynamic, DynamicReader, Generation, IntoDynamic, IntoReadOnly, IntoValue, ReadOnly, Value,
@@ -29,6 +30,7 @@ pub struct Label<T> {
     pub overflow: Value<LabelOverflow>,
     displayed: String,
     prepared_text: WindowLocal<LabelCache>,
+    text_offset: Px,
 }
 
 impl<T> Label<T>
@@ -43,6 +45,7 @@ where
             overflow: Value::Constant(LabelOverflow::WordWrap),
             displayed: String::new(),
             prepared_text: WindowLocal::default(),
+            text_offset: Px::ZERO,
         }
     }
 
@@ -86,16 +89,23 @@ where
                 if cache.text.can_render_to(&context.gfx) && cache_key.is_valid_for(cache) => {}
             _ => {
                 let (measured, display_generation) = self.display.map(|text| {
-                    self.displayed.clear();
-                    if let Err(err) = write!(&mut self.displayed, "{}", text.as_display(context)) {
-                        tracing::error!("Error invoking Display: {err}");
-                    }
-                    (
+                    let measured = if let Some(rich) = text.rich_text() {
+                        let width = (width < Px::MAX).then_some(width);
+                        let buffer =
+                            rich.prepare_buffer(rich.text(), color, width, Some(align), context);
+                        context.gfx.measure_text_buffer(&buffer, color)
+                    } else {
+                        self.displayed.clear();
+                        if let Err(err) =
+                            write!(&mut self.displayed, "{}", text.as_display(context))
+                        {
+                            tracing::error!("Error invoking Display: {err}");
+                        }
                         context
                             .gfx
-                            .measure_text(Text::new(&self.displayed, color).align(align, width)),
-                        text.generation(context),
-                    )
+                            .measure_text(Text::new(&self.displayed, color).align(align, width))
+                    };
+                    (measured, text.generation(context))
                 });
                 cache_key.display_generation = display_generation;
                 self.prepared_text.set(
@@ -113,6 +123,26 @@ where
             .map(|cache| &cache.text)
             .expect("always initialized")
     }
+
+    /// Returns the byte offset of the glyph drawn at `location` during the
+    /// last redraw, if any.
+    #[cfg_attr(not(feature = "markdown"), allow(dead_code))]
+    pub(crate) fn offset_at(
+        &self,
+        location: Point<Px>,
+        context: &WidgetContext<'_>,
+    ) -> Option<usize> {
+        let measured = &self.prepared_text.get(context)?.text;
+        let location = location - Point::new(Px::ZERO, self.text_offset);
+        measured.glyphs.iter().find_map(|glyph| {
+            let mut rect = glyph.rect();
+            rect.origin.y = measured
+                .line_height
+                .saturating_mul(Px::new(i32::try_from(glyph.info.line).unwrap_or(i32::MAX)));
+            rect.size.height = measured.line_height;
+            rect.contains(location).then_some(glyph.info.start)
+        })
+    }
 }
 
 impl<T> Widget for Label<T>
@@ -142,6 +172,7 @@ where
             prepared_text.translate_by(Point::new(Px::ZERO, y_offset)),
             TextOrigin::TopLeft,
         );
+        self.text_offset = y_offset;
     }
 
     fn layout(
@@ -158,7 +189,8 @@ where
     }
 
     fn summarize(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        fmt.debug_tuple("Label").field(&self.display).finish()
+        self.display
+            .map(|display| fmt.debug_tuple("Label").field(display).finish())
     }
 
     fn unmounted(&mut self, context: &mut crate::context::EventContext<'_>) {
@@ -183,7 +215,10 @@ impl_make_widget!(
     Dynamic<String> => String,
     Dynamic<&'static str> => &'static str,
     Value<String> => String,
-    ReadOnly<String> => String
+    ReadOnly<String> => String,
+    Dynamic<RichText> => RichText,
+    Value<RichText> => RichText,
+    ReadOnly<RichText> => RichText
 );
 
 impl MakeWidgetWithTag for Cow<'_, str> {
@@ -263,6 +298,14 @@ pub trait DynamicDisplay {
     fn fmt(&self, context: &WidgetContext<'_>, f: &mut std::fmt::Formatter<'_>)
         -> std::fmt::Result;
 
+    /// Returns the styled text to display, if `self` contains [`RichText`].
+    ///
+    /// When this returns a value, [`Label`] renders the styled spans instead
+    /// of the output of [`DynamicDisplay::fmt`].
+    fn rich_text(&self) -> Option<&RichText> {
+        None
+    }
+
     /// Returns a type that implements [`Display`].
     fn as_display<'display, 'ctx>(
         &'display self,
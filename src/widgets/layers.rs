@@ -10,6 +10,7 @@ use easing_function::EasingFunction;
 use figures::units::{Lp, Px, UPx};
 use figures::{IntoSigned, IntoUnsigned, Point, Rect, Size, Zero};
 use intentional::Assert;
+use kludgine::app::winit::keyboard::{Key, NamedKey};
 
 use super::super::widget::MountedWidget;
 use super::{Custom, Space};
@@ -20,10 +21,11 @@ use crate::reactive::value::{
 };
 use crate::styles::components::{EasingIn, ScrimColor};
 use crate::widget::{
-    Callback, MakeWidget, MakeWidgetWithTag, MountedChildren, SharedCallback, Widget, WidgetId,
-    WidgetList, WidgetRef, WidgetTag, WrapperWidget,
+    Callback, EventHandling, MakeWidget, MakeWidgetWithTag, MountedChildren, SharedCallback,
+    Widget, WidgetId, WidgetList, WidgetRef, WidgetTag, WrapperWidget, HANDLED, IGNORED,
 };
 use crate::widgets::container::ContainerShadow;
+use crate::window::{DeviceId, KeyEvent};
 use crate::ConstraintLimit;
 
 /// A Z-direction stack of widgets.
@@ -53,9 +55,7 @@ impl Widget for Layers {
     fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
         self.synchronize_children(&mut context.as_event_context());
 
-        for mounted in self.mounted.children() {
-            context.for_other(mounted).redraw();
-        }
+        context.redraw_children(self.mounted.children());
     }
 
     fn summarize(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -937,6 +937,24 @@ impl Modal {
         DialogBuilder::new(self.new_handle(), message)
     }
 
+    /// Presents the widget returned by `contents` as the modal session,
+    /// returning a dynamic that contains the result of the session once it is
+    /// completed.
+    ///
+    /// The session is completed when [`ModalResult::complete`] is invoked. If
+    /// the session is dismissed any other way, such as by pressing Escape, the
+    /// result remains `None`.
+    pub fn present_for_result<T, Contents>(
+        &self,
+        contents: impl FnOnce(ModalResult<T>) -> Contents,
+    ) -> Dynamic<Option<T>>
+    where
+        T: Send + 'static,
+        Contents: MakeWidget,
+    {
+        self.new_handle().present_for_result(contents)
+    }
+
     /// Dismisses the modal session.
     pub fn dismiss(&self) {
         self.modal.lock().clear();
@@ -1005,7 +1023,9 @@ impl WrapperWidget for ModalLayer {
             if presented != modal_widget {
                 let modal_widget = modal_widget.clone();
                 *presented = modal_widget.clone();
-                layer_widgets[index * 2 + 1] = modal_widget.clone().centered().make_widget();
+                layer_widgets[index * 2 + 1] = ModalFrame::new(modal_widget.clone(), &self.modal)
+                    .centered()
+                    .make_widget();
 
                 self.focus_top_layer = true;
             }
@@ -1017,13 +1037,14 @@ impl WrapperWidget for ModalLayer {
                 Custom::new(Space::colored(context.get(&ScrimColor))).on_hit_test(|_, _| true),
             );
             self.presented.push(to_present.clone());
-            layer_widgets.push(to_present.clone().centered());
+            layer_widgets.push(ModalFrame::new(to_present.clone(), &self.modal).centered());
         }
 
         if self.presented.len() > modal.len() {
             self.presented.truncate(modal.len());
+            // Dismissing a layer that contains the focused widget restores
+            // focus to the most recently focused widget that remains.
             layer_widgets.truncate(modal.len() * 2);
-            self.focus_top_layer = true;
         }
 
         available_space
@@ -1053,6 +1074,65 @@ impl WrapperWidget for ModalLayer {
     }
 }
 
+/// A wrapper around the contents of a modal session that keeps keyboard focus
+/// within the contents and dismisses the session when Escape is pressed.
+#[derive(Debug)]
+struct ModalFrame {
+    child: WidgetRef,
+    contents: WidgetInstance,
+    modal: Dynamic<OrderedLots<WidgetInstance>>,
+}
+
+impl ModalFrame {
+    fn new(contents: WidgetInstance, modal: &Dynamic<OrderedLots<WidgetInstance>>) -> Self {
+        Self {
+            child: WidgetRef::new(contents.clone()),
+            contents,
+            modal: modal.clone(),
+        }
+    }
+}
+
+impl WrapperWidget for ModalFrame {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn mounted(&mut self, context: &mut EventContext<'_>) {
+        context.tree.set_focus_trap(context.widget().node_id, true);
+    }
+
+    fn keyboard_input(
+        &mut self,
+        _device_id: DeviceId,
+        input: KeyEvent,
+        _is_synthetic: bool,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if input.logical_key != Key::Named(NamedKey::Escape) {
+            return IGNORED;
+        }
+
+        // Allow a cancel button within the dialog to handle the key instead.
+        if context.tree.escape_widget().map_or(false, |escape| {
+            context.tree.is_child(escape, &self.contents)
+        }) {
+            return IGNORED;
+        }
+
+        if input.state.is_pressed() {
+            let mut modal = self.modal.lock();
+            if let Some(index) = modal
+                .iter()
+                .position(|presented| presented == &self.contents)
+            {
+                modal.truncate(index);
+            }
+        }
+        HANDLED
+    }
+}
+
 /// A marker type indicating a special [`DialogBuilder`] button type is not
 /// present.
 pub enum No {}
@@ -1250,6 +1330,29 @@ impl ModalHandle {
         &self.layer
     }
 
+    /// Presents the widget returned by `contents` as a modal dialog, updating
+    /// this handle to control it. Returns a dynamic that contains the result of
+    /// the dialog once it is completed.
+    ///
+    /// The dialog is completed when [`ModalResult::complete`] is invoked. If
+    /// the dialog is dismissed any other way, such as by pressing Escape, the
+    /// result remains `None`.
+    pub fn present_for_result<T, Contents>(
+        &self,
+        contents: impl FnOnce(ModalResult<T>) -> Contents,
+    ) -> Dynamic<Option<T>>
+    where
+        T: Send + 'static,
+        Contents: MakeWidget,
+    {
+        let result = Dynamic::new(None);
+        self.present(contents(ModalResult {
+            handle: self.clone(),
+            result: result.clone(),
+        }));
+        result
+    }
+
     /// Returns a builder for a modal dialog that displays `message` in a modal
     /// dialog above the dialog shown by this handle.
     pub fn build_dialog(&self, message: impl MakeWidget) -> DialogBuilder {
@@ -1265,6 +1368,52 @@ impl Drop for ModalHandle {
     }
 }
 
+/// A handle that completes a modal dialog with a result.
+///
+/// This type is provided to the function that creates the contents of a dialog
+/// presented using [`ModalHandle::present_for_result`] or
+/// [`Modal::present_for_result`].
+pub struct ModalResult<T> {
+    handle: ModalHandle,
+    result: Dynamic<Option<T>>,
+}
+
+impl<T> ModalResult<T> {
+    /// Stores `value` as the result of the dialog and dismisses it.
+    pub fn complete(&self, value: T) {
+        self.result.map_mut(|mut result| *result = Some(value));
+        self.handle.dismiss();
+    }
+
+    /// Dismisses the dialog without storing a result.
+    pub fn dismiss(&self) {
+        self.handle.dismiss();
+    }
+
+    /// Returns the handle of the dialog being presented.
+    ///
+    /// This can be used to present additional dialogs above this dialog.
+    #[must_use]
+    pub const fn handle(&self) -> &ModalHandle {
+        &self.handle
+    }
+}
+
+impl<T> Clone for ModalResult<T> {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle.clone(),
+            result: self.result.clone(),
+        }
+    }
+}
+
+impl<T> Debug for ModalResult<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModalResult").finish_non_exhaustive()
+    }
+}
+
 /// A target for a [`Modal`] session.
 pub trait ModalTarget: Send + 'static {
     /// Returns a new handle that can be used to show a dialog above `self`.
@@ -0,0 +1,653 @@
+//! A widget that displays formatted text written in Markdown.
+
+use std::fmt::Debug;
+use std::mem;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use ahash::AHashMap;
+use figures::units::{Lp, Px, UPx};
+use figures::{Point, Size};
+use kludgine::app::winit::event::MouseButton;
+use kludgine::app::winit::window::CursorIcon;
+use kludgine::cosmic_text::FamilyOwned;
+use kludgine::{AnyTexture, Color, LazyTexture};
+use parking_lot::Mutex;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+use crate::context::{EventContext, GraphicsContext, LayoutContext, WidgetContext};
+use crate::reactive::value::{Destination, Dynamic, IntoValue, Switchable, Value};
+use crate::styles::components::{FontFamily, PrimaryColor};
+use crate::styles::{Edges, FontFamilyList};
+use crate::widget::{
+    EventHandling, MakeWidget, MakeWidgetWithTag, SharedCallback, Widget, WidgetInstance,
+    WidgetList, WidgetTag, HANDLED, IGNORED,
+};
+use crate::widgets::label::LabelOverflow;
+use crate::widgets::rich_text::{RichText, SpanStyle};
+use crate::widgets::{Delimiter, Image, Label, Stack};
+use crate::window::DeviceId;
+use crate::{ConstraintLimit, Lazy};
+
+/// Images that have been loaded or are being loaded, by path.
+static IMAGES: Lazy<Mutex<AHashMap<PathBuf, Dynamic<Option<AnyTexture>>>>> =
+    Lazy::new(Mutex::default);
+
+/// A widget that displays text formatted using
+/// [CommonMark](https://commonmark.org/).
+///
+/// Headings, paragraphs, emphasis, lists, block quotes, code, thematic breaks,
+/// links, and images are supported. When the source is a
+/// [`Dynamic`](crate::reactive::value::Dynamic), the contents are rebuilt each
+/// time the source changes.
+///
+/// Links are only interactive when a callback is provided using
+/// [`Markdown::on_link`]. Images are only loaded when a directory is provided
+/// using [`Markdown::images_from`]; otherwise, their alternate text is
+/// displayed. Images are loaded in the background and cached by path, and
+/// their alternate text is displayed until they have been loaded.
+///
+/// ```rust
+/// use cushy::widgets::Markdown;
+///
+/// let docs = Markdown::new("# Hello\n\nThis is **bold** and *italic*.")
+///     .on_link(|url| println!("clicked {url}"));
+/// ```
+#[must_use]
+pub struct Markdown {
+    source: Value<String>,
+    renderer: Renderer,
+}
+
+impl Markdown {
+    /// Returns a widget that displays `source` as Markdown.
+    pub fn new(source: impl IntoValue<String>) -> Self {
+        Self {
+            source: source.into_value(),
+            renderer: Renderer::default(),
+        }
+    }
+
+    /// Invokes `on_link` with the destination of a link when it is clicked,
+    /// and returns self.
+    pub fn on_link<F>(mut self, on_link: F) -> Self
+    where
+        F: FnMut(String) + Send + 'static,
+    {
+        self.renderer.on_link = Some(SharedCallback::new(on_link));
+        self
+    }
+
+    /// Loads images with relative paths from the `directory` and returns self.
+    ///
+    /// Images that cannot be loaded and images with absolute URLs display
+    /// their alternate text. Images are loaded on a background thread.
+    pub fn images_from(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.renderer.images = Some(directory.into());
+        self
+    }
+}
+
+impl Debug for Markdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Markdown")
+            .field("source", &self.source)
+            .field("images", &self.renderer.images)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MakeWidgetWithTag for Markdown {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let renderer = self.renderer;
+        Stack::rows(self.source.map_each(move |source| renderer.render(source))).make_with_tag(tag)
+    }
+}
+
+#[derive(Clone, Default)]
+struct Renderer {
+    on_link: Option<SharedCallback<String>>,
+    images: Option<PathBuf>,
+}
+
+impl Renderer {
+    fn render(&self, source: &str) -> WidgetList {
+        self.build(parse(source))
+    }
+
+    fn build(&self, nodes: Vec<Node>) -> WidgetList {
+        nodes
+            .into_iter()
+            .map(|node| self.build_node(node))
+            .collect()
+    }
+
+    fn build_node(&self, node: Node) -> WidgetInstance {
+        match node {
+            Node::Heading(level, inline) => {
+                let text = InlineText::new(inline, self.on_link.clone());
+                match level {
+                    HeadingLevel::H1 => text.h1().make_widget(),
+                    HeadingLevel::H2 => text.h2().make_widget(),
+                    HeadingLevel::H3 => text.h3().make_widget(),
+                    HeadingLevel::H4 => text.h4().make_widget(),
+                    HeadingLevel::H5 => text.h5().make_widget(),
+                    HeadingLevel::H6 => text.h6().make_widget(),
+                }
+            }
+            Node::Paragraph(inline) => InlineText::new(inline, self.on_link.clone()).make_widget(),
+            Node::Image { url, alt } => self.image(&url, alt),
+            Node::List { start, items } => items
+                .into_iter()
+                .zip(0..)
+                .map(|(item, index)| {
+                    let marker = start.map_or_else(
+                        || String::from("\u{2022}"),
+                        |start| format!("{}.", start.saturating_add(index)),
+                    );
+                    marker
+                        .and(self.build(item).into_rows().expand_horizontally())
+                        .into_columns()
+                })
+                .collect::<WidgetList>()
+                .into_rows()
+                .pad_by(Edges::default().with_left(Lp::points(8)))
+                .make_widget(),
+            Node::Quote(children) => self
+                .build(children)
+                .into_rows()
+                .pad_by(Lp::points(8))
+                .contain()
+                .make_widget(),
+            Node::Code(code) => Label::new(code)
+                .overflow(LabelOverflow::Clip)
+                .with(&FontFamily, FontFamilyList::from(FamilyOwned::Monospace))
+                .contain()
+                .make_widget(),
+            Node::Rule => Delimiter::horizontal().make_widget(),
+        }
+    }
+
+    fn image(&self, url: &str, alt: String) -> WidgetInstance {
+        let alt = RichText::default().with(&alt, SpanStyle::default().italic());
+        let Some(path) = self
+            .images
+            .as_ref()
+            .filter(|_| !url.contains("://"))
+            .map(|directory| directory.join(url))
+        else {
+            return alt.make_widget();
+        };
+        load_image(path)
+            .switcher(move |image, _| match image {
+                Some(texture) => Image::new(texture.clone()).make_widget(),
+                None => alt.clone().make_widget(),
+            })
+            .make_widget()
+    }
+}
+
+/// Returns the image at `path`, beginning to load it in the background if it
+/// has not already been loaded.
+///
+/// The returned value contains `None` until the image has been loaded. Images
+/// that fail to load are removed from the cache so that they can be retried.
+fn load_image(path: PathBuf) -> Dynamic<Option<AnyTexture>> {
+    let mut images = IMAGES.lock();
+    if let Some(image) = images.get(&path) {
+        return image.clone();
+    }
+
+    let image = Dynamic::new(None);
+    images.insert(path.clone(), image.clone());
+    drop(images);
+
+    std::thread::spawn({
+        let image = image.clone();
+        move || match image::open(&path) {
+            Ok(loaded) => {
+                let texture = AnyTexture::Lazy(LazyTexture::from_image(
+                    loaded,
+                    kludgine::wgpu::FilterMode::Linear,
+                ));
+                image.map_mut(|mut image| *image = Some(texture));
+            }
+            Err(err) => {
+                tracing::warn!("error loading image {}: {err}", path.display());
+                IMAGES.lock().remove(&path);
+            }
+        }
+    });
+
+    image
+}
+
+/// A block of a parsed Markdown document.
+#[derive(Debug, PartialEq)]
+enum Node {
+    Heading(HeadingLevel, Inline),
+    Paragraph(Inline),
+    Image {
+        url: String,
+        alt: String,
+    },
+    List {
+        /// The number of the first item if the list is ordered.
+        start: Option<u64>,
+        items: Vec<Vec<Node>>,
+    },
+    Quote(Vec<Node>),
+    Code(String),
+    Rule,
+}
+
+/// The text of a paragraph or heading, laid out as a single block of text.
+#[derive(Debug, Default, PartialEq)]
+struct Inline {
+    text: RichText,
+    links: Vec<Link>,
+}
+
+impl Inline {
+    fn link_at(&self, offset: usize) -> Option<&Link> {
+        self.links.iter().find(|link| link.range.contains(&offset))
+    }
+
+    fn styled(&self, link_color: Color) -> RichText {
+        let mut text = self.text.clone();
+        for link in &self.links {
+            text.style_range(link.range.clone(), SpanStyle::default().color(link_color));
+        }
+        text
+    }
+}
+
+/// A range of [`Inline`] text that links to `url`.
+#[derive(Debug, PartialEq)]
+struct Link {
+    range: Range<usize>,
+    url: String,
+}
+
+fn parse(source: &str) -> Vec<Node> {
+    let mut state = ParseState {
+        blocks: vec![Block::new(BlockKind::Root)],
+        inline: Inline::default(),
+        strong: 0,
+        emphasis: 0,
+        link: None,
+        image: None,
+        code_block: None,
+    };
+    for event in Parser::new_ext(source, Options::ENABLE_TASKLISTS) {
+        state.handle(event);
+    }
+    state.finish_inline(None);
+    state
+        .blocks
+        .pop()
+        .map(|root| root.children)
+        .unwrap_or_default()
+}
+
+struct ParseState {
+    blocks: Vec<Block>,
+    inline: Inline,
+    strong: usize,
+    emphasis: usize,
+    /// The start of the link being parsed and its destination.
+    link: Option<(usize, String)>,
+    image: Option<(String, String)>,
+    code_block: Option<String>,
+}
+
+impl ParseState {
+    fn handle(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => {
+                if let Some(code) = &mut self.code_block {
+                    code.push_str(&text);
+                } else if let Some((_, alt)) = &mut self.image {
+                    alt.push_str(&text);
+                } else {
+                    self.inline.text.push(&text, self.span_style());
+                }
+            }
+            Event::Code(code) => {
+                self.inline.text.push(&code, self.span_style().monospace());
+            }
+            Event::SoftBreak => self.inline.text.push(" ", self.span_style()),
+            Event::HardBreak => self.inline.text.push("\n", self.span_style()),
+            Event::Rule => {
+                self.finish_inline(None);
+                self.push_block(Node::Rule);
+            }
+            Event::TaskListMarker(checked) => {
+                self.inline.text.push(
+                    if checked { "\u{2611} " } else { "\u{2610} " },
+                    SpanStyle::default(),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Paragraph | Tag::Heading { .. } => self.finish_inline(None),
+            Tag::BlockQuote { .. } => {
+                self.finish_inline(None);
+                self.blocks.push(Block::new(BlockKind::Quote));
+            }
+            Tag::CodeBlock(_) => {
+                self.finish_inline(None);
+                self.code_block = Some(String::new());
+            }
+            Tag::List(first_number) => {
+                self.finish_inline(None);
+                self.blocks.push(Block::new(BlockKind::List(first_number)));
+            }
+            Tag::Item => {
+                self.finish_inline(None);
+                self.blocks.push(Block::new(BlockKind::Item));
+            }
+            Tag::Emphasis => self.emphasis += 1,
+            Tag::Strong => self.strong += 1,
+            Tag::Link { dest_url, .. } => {
+                self.link = Some((self.inline.text.text().len(), dest_url.into_string()));
+            }
+            Tag::Image { dest_url, .. } => {
+                self.image = Some((dest_url.into_string(), String::new()));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => self.finish_inline(None),
+            TagEnd::Heading(level) => self.finish_inline(Some(level)),
+            TagEnd::BlockQuote { .. } => {
+                self.finish_inline(None);
+                if let Some(quote) = self.blocks.pop() {
+                    self.push_block(Node::Quote(quote.children));
+                }
+            }
+            TagEnd::CodeBlock => {
+                if let Some(mut code) = self.code_block.take() {
+                    code.truncate(code.trim_end_matches('\n').len());
+                    self.push_block(Node::Code(code));
+                }
+            }
+            TagEnd::List { .. } => {
+                self.finish_inline(None);
+                if let Some(list) = self.blocks.pop() {
+                    let start = match list.kind {
+                        BlockKind::List(start) => start,
+                        _ => None,
+                    };
+                    self.push_block(Node::List {
+                        start,
+                        items: list.items,
+                    });
+                }
+            }
+            TagEnd::Item => {
+                self.finish_inline(None);
+                if let Some(item) = self.blocks.pop() {
+                    if let Some(list) = self.blocks.last_mut() {
+                        list.items.push(item.children);
+                    }
+                }
+            }
+            TagEnd::Emphasis => self.emphasis = self.emphasis.saturating_sub(1),
+            TagEnd::Strong => self.strong = self.strong.saturating_sub(1),
+            TagEnd::Link => {
+                if let Some((start, url)) = self.link.take() {
+                    let end = self.inline.text.text().len();
+                    if start < end {
+                        self.inline.links.push(Link {
+                            range: start..end,
+                            url,
+                        });
+                    }
+                }
+            }
+            TagEnd::Image => {
+                if let Some((url, alt)) = self.image.take() {
+                    self.finish_inline(None);
+                    self.push_block(Node::Image { url, alt });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn span_style(&self) -> SpanStyle {
+        let mut style = SpanStyle::default();
+        if self.strong > 0 {
+            style = style.bold();
+        }
+        if self.emphasis > 0 {
+            style = style.italic();
+        }
+        style
+    }
+
+    fn finish_inline(&mut self, heading: Option<HeadingLevel>) {
+        let inline = mem::take(&mut self.inline);
+        // A link that continues past an image resumes at the start of the new
+        // block of text.
+        if let Some((start, _)) = &mut self.link {
+            *start = 0;
+        }
+        if inline.text.text().trim().is_empty() {
+            return;
+        }
+        self.push_block(match heading {
+            Some(level) => Node::Heading(level, inline),
+            None => Node::Paragraph(inline),
+        });
+    }
+
+    fn push_block(&mut self, node: Node) {
+        if let Some(block) = self.blocks.last_mut() {
+            block.children.push(node);
+        }
+    }
+}
+
+struct Block {
+    kind: BlockKind,
+    children: Vec<Node>,
+    items: Vec<Vec<Node>>,
+}
+
+impl Block {
+    fn new(kind: BlockKind) -> Self {
+        Self {
+            kind,
+            children: Vec::new(),
+            items: Vec::new(),
+        }
+    }
+}
+
+enum BlockKind {
+    Root,
+    Quote,
+    /// A list, containing the number of the first item if the list is ordered.
+    List(Option<u64>),
+    Item,
+}
+
+/// A paragraph of Markdown text, displayed as a single [`Label`] with
+/// clickable links.
+#[derive(Debug)]
+struct InlineText {
+    label: Label<RichText>,
+    inline: Inline,
+    link_color: Option<Color>,
+    on_link: Option<SharedCallback<String>>,
+}
+
+impl InlineText {
+    fn new(inline: Inline, on_link: Option<SharedCallback<String>>) -> Self {
+        Self {
+            label: Label::new(inline.text.clone()),
+            inline,
+            link_color: None,
+            on_link,
+        }
+    }
+
+    /// Restyles the links in the label when the [`LinkColor`] has changed.
+    fn update_link_color(&mut self, context: &WidgetContext<'_>) {
+        let link_color = context.get(&LinkColor);
+        if self.link_color != Some(link_color) {
+            self.link_color = Some(link_color);
+            if !self.inline.links.is_empty() {
+                self.label = Label::new(self.inline.styled(link_color));
+            }
+        }
+    }
+
+    fn link_at(&self, location: Point<Px>, context: &WidgetContext<'_>) -> Option<&Link> {
+        if self.on_link.is_none() {
+            return None;
+        }
+        let offset = self.label.offset_at(location, context)?;
+        self.inline.link_at(offset)
+    }
+}
+
+impl Widget for InlineText {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        self.update_link_color(context);
+        self.label.redraw(context);
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        self.update_link_color(context);
+        self.label.layout(available_space, context)
+    }
+
+    fn hit_test(&mut self, location: Point<Px>, context: &mut EventContext<'_>) -> bool {
+        self.link_at(location, context).is_some()
+    }
+
+    fn hover(&mut self, location: Point<Px>, context: &mut EventContext<'_>) -> Option<CursorIcon> {
+        self.link_at(location, context).map(|_| CursorIcon::Pointer)
+    }
+
+    fn mouse_down(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        let Some(link) = self.link_at(location, context) else {
+            return IGNORED;
+        };
+        let url = link.url.clone();
+        if let Some(on_link) = &self.on_link {
+            on_link.invoke(url);
+        }
+        HANDLED
+    }
+
+    fn summarize(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.label.summarize(fmt)
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        self.label.unmounted(context);
+    }
+}
+
+define_components! {
+    Markdown {
+        /// The color of links in a [`Markdown`] widget.
+        LinkColor(Color, "link_color", @PrimaryColor)
+    }
+}
+
+#[test]
+fn parses_blocks() {
+    fn text(nodes: &[Node]) -> &str {
+        match nodes {
+            [Node::Paragraph(inline)] => inline.text.text(),
+            other => unreachable!("expected a single paragraph: {other:?}"),
+        }
+    }
+
+    let nodes = parse(
+        "# Title\n\nSome *emphasized* text with a [link](https://example.com).\n\n\
+         - one\n- two\n\n1. first\n2. second\n\n> quoted\n\n```\ncode\n```\n\n---\n",
+    );
+    let [Node::Heading(HeadingLevel::H1, title), Node::Paragraph(paragraph), Node::List {
+        start: None,
+        items: bullets,
+    }, Node::List {
+        start: Some(1),
+        items: numbered,
+    }, Node::Quote(quote), Node::Code(code), Node::Rule] = &nodes[..]
+    else {
+        unreachable!("unexpected structure: {nodes:?}");
+    };
+
+    assert_eq!(title.text.text(), "Title");
+    // The paragraph is a single run of text rather than one piece per word.
+    assert_eq!(paragraph.text.text(), "Some emphasized text with a link.");
+    assert!(paragraph
+        .text
+        .spans()
+        .iter()
+        .any(|span| span.range == (5..15) && span.style == SpanStyle::default().italic()));
+    assert_eq!(
+        paragraph.links,
+        [Link {
+            range: 28..32,
+            url: String::from("https://example.com"),
+        }]
+    );
+    assert_eq!(
+        paragraph.link_at(30).map(|link| &*link.url),
+        Some("https://example.com")
+    );
+    assert!(paragraph.link_at(27).is_none());
+
+    assert_eq!(
+        bullets.iter().map(|item| text(item)).collect::<Vec<_>>(),
+        ["one", "two"]
+    );
+    assert_eq!(
+        numbered.iter().map(|item| text(item)).collect::<Vec<_>>(),
+        ["first", "second"]
+    );
+    assert_eq!(text(quote), "quoted");
+    assert_eq!(code, "code");
+}
+
+#[test]
+fn parses_inline_code_and_images() {
+    let nodes = parse("Run `cargo`\n![logo](logo.png)");
+    let [Node::Paragraph(paragraph), Node::Image { url, alt }] = &nodes[..] else {
+        unreachable!("unexpected structure: {nodes:?}");
+    };
+    assert_eq!(paragraph.text.text(), "Run cargo ");
+    assert!(paragraph
+        .text
+        .spans()
+        .iter()
+        .any(|span| span.range == (4..9) && span.style == SpanStyle::default().monospace()));
+    assert_eq!(url, "logo.png");
+    assert_eq!(alt, "logo");
+}
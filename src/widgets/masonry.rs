@@ -0,0 +1,268 @@
+//! A widget that arranges its children into balanced columns.
+
+use std::time::Duration;
+
+use ahash::AHashMap;
+use figures::units::{Lp, Px, UPx};
+use figures::{IntoSigned, IntoUnsigned, Point, Rect, Round, ScreenScale, Size, Zero};
+use intentional::Cast;
+
+use crate::animation::{AnimationHandle, AnimationTarget, LinearInterpolate, Spawn, ZeroToOne};
+use crate::context::{AsEventContext, GraphicsContext, LayoutContext, Trackable};
+use crate::reactive::value::{Destination, Dynamic, IntoValue, Source, Value};
+use crate::styles::components::{EasingIn, IntrinsicPadding};
+use crate::styles::{Dimension, FlexibleDimension};
+use crate::widget::{MountedChildren, Widget, WidgetId, WidgetList};
+use crate::ConstraintLimit;
+
+/// A widget that places its children into columns, adding each child to the
+/// bottom of the shortest column.
+///
+/// This layout is often called a masonry or waterfall layout, and is well
+/// suited for galleries of items with varying heights. Each child is given the
+/// full width of its column and is sized to fit vertically.
+///
+/// By default, as many columns as can fit while being at least 1.5 inches
+/// wide are used. See [`MasonryColumns`] for other options.
+///
+/// Children that are outside of the visible region, such as when this widget
+/// is placed inside of a [`Scroll`](crate::widgets::Scroll), are not drawn.
+/// When [`animated()`](Self::animated) is used, children smoothly move to
+/// their new positions when children are inserted or removed.
+#[derive(Debug)]
+pub struct Masonry {
+    /// The children to arrange.
+    pub children: Value<WidgetList>,
+    /// How the number of columns is determined.
+    pub columns: Value<MasonryColumns>,
+    /// The spacing to place between columns and between the children of each
+    /// column. When [`FlexibleDimension::Auto`] is set, [`IntrinsicPadding`]
+    /// will be used.
+    pub spacing: Value<Size<FlexibleDimension>>,
+    transition: Duration,
+    mounted: MountedChildren,
+    order: Vec<WidgetId>,
+    displayed: AHashMap<WidgetId, Point<Px>>,
+    moving_from: AHashMap<WidgetId, Point<Px>>,
+    progress: Dynamic<ZeroToOne>,
+    _animation: Option<AnimationHandle>,
+}
+
+impl Masonry {
+    /// Returns a new widget that arranges `children` into columns.
+    #[must_use]
+    pub fn new(children: impl IntoValue<WidgetList>) -> Self {
+        Self {
+            children: children.into_value(),
+            columns: Value::default(),
+            spacing: Value::Constant(Size::squared(FlexibleDimension::Auto)),
+            transition: Duration::ZERO,
+            mounted: MountedChildren::default(),
+            order: Vec::new(),
+            displayed: AHashMap::new(),
+            moving_from: AHashMap::new(),
+            progress: Dynamic::new(ZeroToOne::ONE),
+            _animation: None,
+        }
+    }
+
+    /// Sets how the number of columns is determined and returns self.
+    #[must_use]
+    pub fn columns(mut self, columns: impl IntoValue<MasonryColumns>) -> Self {
+        self.columns = columns.into_value();
+        self
+    }
+
+    /// Sets the spacing between children and returns self.
+    #[must_use]
+    pub fn spacing(mut self, spacing: impl IntoValue<Size<FlexibleDimension>>) -> Self {
+        self.spacing = spacing.into_value();
+        self
+    }
+
+    /// Animates children to their new positions over `duration` when
+    /// children are inserted or removed, and returns self.
+    #[must_use]
+    pub fn animated(mut self, duration: Duration) -> Self {
+        self.transition = duration;
+        self
+    }
+
+    /// Begins animating from the currently displayed positions if the
+    /// children have changed since the last layout.
+    fn note_order(&mut self, context: &mut LayoutContext<'_, '_, '_, '_>) {
+        let order = self
+            .mounted
+            .children()
+            .iter()
+            .map(|child| child.id())
+            .collect::<Vec<_>>();
+        if order == self.order {
+            return;
+        }
+
+        if !self.transition.is_zero() && !self.order.is_empty() {
+            self.moving_from = std::mem::take(&mut self.displayed);
+            self.progress.set(ZeroToOne::ZERO);
+            self._animation = Some(
+                self.progress
+                    .transition_to(ZeroToOne::ONE)
+                    .over(self.transition)
+                    .with_easing(context.get(&EasingIn))
+                    .spawn(),
+            );
+        }
+        self.order = order;
+    }
+}
+
+/// Determines the number of columns in a [`Masonry`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MasonryColumns {
+    /// Use a fixed number of columns.
+    Count(usize),
+    /// Use as many columns as can fit while each column is at least this
+    /// wide.
+    MinimumWidth(Dimension),
+}
+
+impl Default for MasonryColumns {
+    fn default() -> Self {
+        Self::MinimumWidth(Dimension::Lp(Lp::points(108)))
+    }
+}
+
+impl From<usize> for MasonryColumns {
+    fn from(count: usize) -> Self {
+        Self::Count(count)
+    }
+}
+
+impl From<Dimension> for MasonryColumns {
+    fn from(width: Dimension) -> Self {
+        Self::MinimumWidth(width)
+    }
+}
+
+/// Returns the index of the shortest column, preferring earlier columns when
+/// multiple columns have the same height.
+fn shortest_column(heights: &[Px]) -> usize {
+    heights
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, height)| **height)
+        .map_or(0, |(index, _)| index)
+}
+
+impl Widget for Masonry {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let Some(visible) = context.gfx.visible_rect() else {
+            return;
+        };
+        let visible = visible.into_signed();
+        let children = self.mounted.children();
+        context.redraw_children(children.iter().filter(|child| {
+            child
+                .last_layout()
+                .map_or(true, |layout| layout.intersects(&visible))
+        }));
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        self.children.invalidate_when_changed(context);
+        self.mounted
+            .synchronize_with(&self.children, &mut context.as_event_context());
+        self.note_order(context);
+        let progress = *self.progress.get_tracking_invalidate(context);
+
+        let scale = context.gfx.scale();
+        let spacing = self
+            .spacing
+            .get_tracking_invalidate(context)
+            .map(|dimension| match dimension {
+                FlexibleDimension::Auto => context.get(&IntrinsicPadding),
+                FlexibleDimension::Dimension(dimension) => dimension,
+            })
+            .into_px(scale)
+            .round();
+
+        let (columns, minimum_width) = match self.columns.get_tracking_invalidate(context) {
+            MasonryColumns::Count(count) => (Some(count.max(1)), Px::ZERO),
+            MasonryColumns::MinimumWidth(width) => (None, width.into_px(scale).max(Px::new(1))),
+        };
+        let available_width = available_space.width.max();
+        let (columns, column_width) = if available_width == UPx::MAX {
+            // There is no width to fill, so each column is as wide as the
+            // widest child.
+            let fit = available_space.map(|limit| ConstraintLimit::SizeToFit(limit.max()));
+            let widest = self
+                .mounted
+                .children()
+                .iter()
+                .map(|child| context.for_other(child).layout(fit).width.into_signed())
+                .max()
+                .unwrap_or_default();
+            (columns.unwrap_or(1), widest.max(minimum_width))
+        } else {
+            let width = available_width.into_signed();
+            let columns = columns.unwrap_or_else(|| {
+                ((width + spacing.width) / (minimum_width + spacing.width))
+                    .get()
+                    .max(1)
+                    .cast::<usize>()
+            });
+            let gaps = spacing.width * (columns - 1).cast::<i32>();
+            (
+                columns,
+                ((width - gaps) / columns.cast::<i32>()).max(Px::ZERO),
+            )
+        };
+
+        let child_space = Size::new(
+            ConstraintLimit::Fill(column_width.into_unsigned()),
+            ConstraintLimit::SizeToFit(available_space.height.max()),
+        );
+        let mut heights = vec![Px::ZERO; columns];
+        let mut displayed = AHashMap::with_capacity(self.mounted.children().len());
+        for child in self.mounted.children() {
+            let height = context
+                .for_other(child)
+                .layout(child_space)
+                .height
+                .into_signed();
+            let column = shortest_column(&heights);
+            let target = Point::new(
+                (column_width + spacing.width) * column.cast::<i32>(),
+                heights[column],
+            );
+            heights[column] = target.y + height + spacing.height;
+
+            let origin = self
+                .moving_from
+                .get(&child.id())
+                .map_or(target, |from| from.lerp(&target, progress));
+            displayed.insert(child.id(), origin);
+            context.set_child_layout(child, Rect::new(origin, Size::new(column_width, height)));
+        }
+        self.displayed = displayed;
+
+        let height = heights
+            .into_iter()
+            .max()
+            .map_or(Px::ZERO, |height| (height - spacing.height).max(Px::ZERO));
+        let width =
+            column_width * columns.cast::<i32>() + spacing.width * (columns - 1).cast::<i32>();
+        Size::new(width, height).into_unsigned()
+    }
+}
+
+#[test]
+fn shortest_column_prefers_first() {
+    assert_eq!(shortest_column(&[Px::new(10), Px::new(5), Px::new(5)]), 1);
+    assert_eq!(shortest_column(&[Px::ZERO; 3]), 0);
+    assert_eq!(shortest_column(&[]), 0);
+}
@@ -7,6 +7,8 @@ use std::time::Duration;
 use alot::LotId;
 use figures::units::{Px, UPx};
 use figures::{Angle, IntoSigned, Point, Rect, Round, ScreenScale, Size, Zero};
+use kludgine::app::winit::event::MouseButton;
+use kludgine::app::winit::keyboard::{Key, NamedKey};
 use kludgine::shapes::{PathBuilder, Shape, StrokeOptions};
 use kludgine::DrawableExt;
 
@@ -25,8 +27,9 @@ use crate::styles::components::{
 use crate::styles::Styles;
 use crate::widget::{
     Callback, EventHandling, MakeWidget, MakeWidgetWithTag, SharedNotify, Widget, WidgetId,
-    WidgetInstance, WidgetRef, WidgetTag, HANDLED,
+    WidgetInstance, WidgetRef, WidgetTag, WrapperWidget, HANDLED, IGNORED,
 };
+use crate::window::{DeviceId, KeyEvent};
 use crate::ConstraintLimit;
 
 #[derive(Debug, Clone)]
@@ -165,6 +168,8 @@ where
                     padding: UPx::ZERO,
                     selecting: None,
                     hover_location: None,
+                    keyboard_selection: None,
+                    open_keyboard_submenu: false,
                     mouse_down: false,
                     layer: overlay.clone(),
                     open_submenu: None,
@@ -454,6 +459,122 @@ where
     }
 }
 
+/// A widget that shows a [`Menu`] when clicked with the secondary mouse button.
+///
+/// The menu is shown at the cursor's location in an [`OverlayLayer`]. While
+/// this widget or its child has focus, the menu can also be shown by pressing
+/// the context menu key or Shift+F10, in which case it is shown below this
+/// widget.
+///
+/// Once shown, the menu's items can be navigated using the arrow keys and
+/// selected using Enter or Space. The menu is dismissed when an item is
+/// selected, when Escape is pressed, or when the menu loses focus.
+///
+/// Because the menu is shown when the secondary mouse button is pressed on
+/// this widget, child widgets that handle all mouse buttons, such as
+/// [`Button`], prevent the menu from being shown by clicking.
+pub struct ContextMenu {
+    child: WidgetRef,
+    menu: Arc<dyn SubmenuFactory>,
+    overlay: OverlayLayer,
+    open: Option<OpenMenuHandle>,
+}
+
+impl ContextMenu {
+    /// Returns a widget that shows `menu` in `overlay` when `child` is
+    /// clicked with the secondary mouse button.
+    #[must_use]
+    pub fn new<T>(child: impl MakeWidget, overlay: &OverlayLayer, menu: Menu<T>) -> Self
+    where
+        T: Unpin + Clone + Debug + Send + Sync + 'static,
+    {
+        Self {
+            child: WidgetRef::new(child),
+            menu: Arc::new(menu),
+            overlay: overlay.clone(),
+            open: None,
+        }
+    }
+
+    fn show(&mut self, position: impl FnOnce(MenuOverlay<'_>) -> MenuOverlay<'_>) {
+        if let Some(open) = self.open.take() {
+            open.dismiss();
+        }
+        let menu = self
+            .menu
+            .overlay_submenu_in(&self.overlay, Dynamic::default());
+        self.open = Some(position(menu).show());
+    }
+}
+
+impl Debug for ContextMenu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextMenu")
+            .field("child", &self.child)
+            .field("open", &self.open)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WrapperWidget for ContextMenu {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn mouse_down(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button != MouseButton::Right {
+            return IGNORED;
+        }
+        let Some(layout) = context.last_layout() else {
+            return IGNORED;
+        };
+
+        self.show(|menu| menu.at(layout.origin + location));
+        HANDLED
+    }
+
+    fn keyboard_input(
+        &mut self,
+        _device_id: DeviceId,
+        input: KeyEvent,
+        _is_synthetic: bool,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if !input.state.is_pressed() {
+            return IGNORED;
+        }
+        let requested = match input.logical_key {
+            Key::Named(NamedKey::ContextMenu) => true,
+            Key::Named(NamedKey::F10) => context.modifiers().state().shift_key(),
+            _ => false,
+        };
+        if !requested {
+            return IGNORED;
+        }
+
+        let id = context.widget().id();
+        self.show(|menu| menu.below(id));
+        HANDLED
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        if let Some(open) = self.open.take() {
+            open.dismiss();
+        }
+        self.child.unmount_in(context);
+    }
+}
+
 /// A handler for a selected [`MenuItem<T>`].
 #[derive(Debug, Clone)]
 pub struct MenuHandler<T>(SharedNotify<T>);
@@ -466,6 +587,8 @@ struct OpenMenu<T> {
     padding: UPx,
     selecting: Option<usize>,
     hover_location: Option<Point<Px>>,
+    keyboard_selection: Option<usize>,
+    open_keyboard_submenu: bool,
     mouse_down: bool,
     layer: OverlayLayer,
     open_submenu: Option<(usize, OpenMenuHandle)>,
@@ -475,11 +598,15 @@ struct OpenMenu<T> {
 }
 impl<T> OpenMenu<T> {
     fn update_visual_state(&mut self, context: &mut EventContext<'_>) {
-        let location = self.hover_location.unwrap_or(Point::squared(Px::new(-1)));
         self.selecting = None;
         for (index, rendered) in self.items.iter_mut().enumerate() {
-            let hovered = location.y >= rendered.y - self.padding
-                && location.y < rendered.y + rendered.height + self.padding;
+            let hovered = match self.hover_location {
+                Some(location) => {
+                    location.y >= rendered.y - self.padding
+                        && location.y < rendered.y + rendered.height + self.padding
+                }
+                None => self.keyboard_selection == Some(index),
+            };
             if let ItemKind::Item(item) = &mut rendered.item {
                 let enabled = item.enabled.get_tracking_redraw(context);
                 let new_state = if enabled {
@@ -491,7 +618,11 @@ impl<T> OpenMenu<T> {
                                 handle.dismiss();
                                 self.open_submenu = None;
                             }
-                        } else if let Some(factory) = &item.submenu {
+                        } else if let Some(factory) = item
+                            .submenu
+                            .as_ref()
+                            .filter(|_| self.hover_location.is_some() || self.open_keyboard_submenu)
+                        {
                             let last_layout = context.last_layout().expect("must have rendered");
                             let menu_location = Point::new(
                                 last_layout.origin.x + last_layout.size.width
@@ -541,6 +672,54 @@ impl<T> OpenMenu<T> {
     }
 }
 
+impl<T> OpenMenu<T>
+where
+    T: Clone,
+{
+    fn select(&self, index: usize) {
+        let ItemKind::Item(item) = &self.items[index].item else {
+            return;
+        };
+        self.on_click.0.notify(item.value.clone());
+        self.dismiss_all();
+    }
+
+    fn dismiss_all(&self) {
+        let mut shared = self.shared.lock();
+        for handle in shared.open_menus.drain() {
+            handle.dismiss();
+        }
+    }
+
+    fn move_keyboard_selection(&mut self, forward: bool, context: &mut EventContext<'_>) {
+        let enabled = |rendered: &RenderedItem<T>| match &rendered.item {
+            ItemKind::Item(item) => item.enabled.get(),
+            ItemKind::Separator => false,
+        };
+        let count = self.items.len();
+        let start = self.keyboard_selection.or(self.selecting);
+        let next = (1..=count)
+            .map(|offset| match (start, forward) {
+                (Some(start), true) => (start + offset) % count,
+                (Some(start), false) => (start + count - offset % count) % count,
+                (None, true) => offset - 1,
+                (None, false) => count - offset,
+            })
+            .find(|index| enabled(&self.items[*index]));
+        if let Some(next) = next {
+            self.keyboard_selection = Some(next);
+            self.hover_location = None;
+            self.update_visual_state(context);
+        }
+    }
+
+    fn open_selected_submenu(&mut self, context: &mut EventContext<'_>) {
+        self.open_keyboard_submenu = true;
+        self.update_visual_state(context);
+        self.open_keyboard_submenu = false;
+    }
+}
+
 impl<T> Widget for OpenMenu<T>
 where
     T: Unpin + Clone + Debug + Send + 'static,
@@ -718,6 +897,7 @@ where
         context: &mut crate::context::EventContext<'_>,
     ) -> Option<kludgine::app::winit::window::CursorIcon> {
         self.hover_location = Some(location);
+        self.keyboard_selection = None;
         self.update_visual_state(context);
         self.shared.lock().hovering.insert(context.widget().id());
         None
@@ -766,19 +946,41 @@ where
         _context: &mut crate::context::EventContext<'_>,
     ) {
         if let Some(index) = self.selecting {
-            let ItemKind::Item(item) = &self.items[index].item else {
-                return;
-            };
-            self.on_click.0.notify(item.value.clone());
-            let mut shared = self.shared.lock();
-            for handle in shared.open_menus.drain() {
-                handle.dismiss();
-            }
+            self.select(index);
         }
         self.hover_location = None;
         self.mouse_down = false;
     }
 
+    fn keyboard_input(
+        &mut self,
+        _device_id: DeviceId,
+        input: KeyEvent,
+        _is_synthetic: bool,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if !input.state.is_pressed() {
+            return IGNORED;
+        }
+
+        match input.logical_key {
+            Key::Named(NamedKey::ArrowDown) => self.move_keyboard_selection(true, context),
+            Key::Named(NamedKey::ArrowUp) => self.move_keyboard_selection(false, context),
+            Key::Named(NamedKey::ArrowRight) => self.open_selected_submenu(context),
+            Key::Named(NamedKey::Enter | NamedKey::Space) => match self.selecting {
+                Some(index) if self.items[index].submenu().is_some() => {
+                    self.open_selected_submenu(context);
+                }
+                Some(index) => self.select(index),
+                None => {}
+            },
+            Key::Named(NamedKey::Escape) => self.dismiss_all(),
+            _ => return IGNORED,
+        }
+
+        HANDLED
+    }
+
     fn accept_focus(&mut self, _context: &mut crate::context::EventContext<'_>) -> bool {
         true
     }
@@ -0,0 +1,365 @@
+//! A scrolling list of messages, such as a chat history.
+
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::mem;
+use std::sync::Arc;
+
+use ahash::AHashMap;
+use figures::units::UPx;
+use figures::{Point, Zero};
+
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, MapEachCloned, Source};
+use crate::widget::{
+    Callback, MakeWidget, MakeWidgetWithTag, WidgetId, WidgetInstance, WidgetList, WidgetTag,
+};
+
+type MakeMessage<T> = Box<dyn FnMut(&T, bool) -> WidgetInstance + Send>;
+type MakeSeparator<T> = Box<dyn FnMut(Option<&T>, &T) -> Option<WidgetInstance> + Send>;
+type IsGrouped<T> = Box<dyn Fn(&T, &T) -> bool + Send>;
+
+/// A vertically scrolling list of messages that behaves like a chat history.
+///
+/// - When the list is scrolled to the bottom, it remains at the bottom as new
+///   messages are added.
+/// - When the list is scrolled up and new messages are added to the end, a
+///   button showing the number of new messages is displayed. Clicking it
+///   scrolls to the bottom.
+/// - [Separators](Self::separators), such as the date messages were sent on,
+///   can be inserted between messages.
+/// - Consecutive messages can be [grouped](Self::grouped), allowing repeated
+///   information like the sender to be omitted.
+/// - When the list is scrolled to the top, [older messages can be
+///   loaded](Self::on_load_older). The messages that were visible remain in
+///   place while older messages are inserted above them.
+///
+/// Each message must have a unique key, which is used to reuse the widgets of
+/// messages that are already displayed when the list of messages changes.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::MessageList;
+///
+/// #[derive(Debug)]
+/// struct Message {
+///     id: u64,
+///     sender: String,
+///     text: String,
+/// }
+///
+/// let messages = Dynamic::new(Vec::<Message>::new());
+/// let list = MessageList::new(
+///     messages,
+///     |message: &Message| message.id,
+///     |message: &Message, grouped| {
+///         if grouped {
+///             message.text.clone().make_widget()
+///         } else {
+///             format!("{}: {}", message.sender, message.text).make_widget()
+///         }
+///     },
+/// )
+/// .grouped(|previous, message| previous.sender == message.sender);
+/// ```
+pub struct MessageList<T, Key> {
+    messages: Dynamic<Vec<T>>,
+    key: Arc<dyn Fn(&T) -> Key + Send + Sync>,
+    make_message: MakeMessage<T>,
+    separators: Option<MakeSeparator<T>>,
+    grouped: Option<IsGrouped<T>>,
+    on_load_older: Option<Callback>,
+}
+
+impl<T, Key> MessageList<T, Key>
+where
+    T: Send + 'static,
+    Key: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    /// Returns a list that displays `messages`.
+    ///
+    /// `key` must return a value that uniquely identifies each message.
+    /// `make_message` is invoked to create the widget for each message, and is
+    /// provided whether the message is [grouped](Self::grouped) with the
+    /// message before it.
+    pub fn new<GetKey, Make, Message>(
+        messages: impl IntoDynamic<Vec<T>>,
+        key: GetKey,
+        mut make_message: Make,
+    ) -> Self
+    where
+        GetKey: Fn(&T) -> Key + Send + Sync + 'static,
+        Make: FnMut(&T, bool) -> Message + Send + 'static,
+        Message: MakeWidget,
+    {
+        Self {
+            messages: messages.into_dynamic(),
+            key: Arc::new(key),
+            make_message: Box::new(move |message, grouped| {
+                make_message(message, grouped).make_widget()
+            }),
+            separators: None,
+            grouped: None,
+            on_load_older: None,
+        }
+    }
+
+    /// Inserts the widgets returned from `separator` between messages and
+    /// returns self.
+    ///
+    /// `separator` is invoked with the previous message, if any, and the
+    /// message that the separator would be placed before. If a widget is
+    /// returned, it is inserted before the message. Messages separated from
+    /// the previous message are never grouped with it.
+    #[must_use]
+    pub fn separators<F, Separator>(mut self, mut separator: F) -> Self
+    where
+        F: FnMut(Option<&T>, &T) -> Option<Separator> + Send + 'static,
+        Separator: MakeWidget,
+    {
+        self.separators = Some(Box::new(move |previous, message| {
+            separator(previous, message).map(MakeWidget::make_widget)
+        }));
+        self
+    }
+
+    /// Groups consecutive messages when `grouped` returns true and returns
+    /// self.
+    ///
+    /// `grouped` is invoked with the previous message and the message that
+    /// follows it.
+    #[must_use]
+    pub fn grouped<F>(mut self, grouped: F) -> Self
+    where
+        F: Fn(&T, &T) -> bool + Send + 'static,
+    {
+        self.grouped = Some(Box::new(grouped));
+        self
+    }
+
+    /// Invokes `load_older` when the list is scrolled to the top and returns
+    /// self.
+    ///
+    /// `load_older` should insert older messages at the start of the list of
+    /// messages. It is not invoked again until the list has been scrolled away
+    /// from the top, which happens automatically when older messages are
+    /// inserted. Because `load_older` may be invoked while the user interface
+    /// is being updated, slow operations should be performed in the
+    /// background.
+    #[must_use]
+    pub fn on_load_older<F>(mut self, mut load_older: F) -> Self
+    where
+        F: FnMut() + Send + 'static,
+    {
+        self.on_load_older = Some(Callback::new(move |()| load_older()));
+        self
+    }
+}
+
+impl<T, Key> Debug for MessageList<T, Key>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageList")
+            .field("messages", &self.messages)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, Key> MakeWidgetWithTag for MessageList<T, Key>
+where
+    T: Send + 'static,
+    Key: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let Self {
+            messages,
+            key,
+            mut make_message,
+            mut separators,
+            grouped,
+            on_load_older,
+        } = self;
+
+        let first_message = Dynamic::<Option<WidgetId>>::default();
+        let mut widgets = AHashMap::<(Key, bool), WidgetInstance>::new();
+        let rows = messages.map_each({
+            let key = key.clone();
+            let first_message = first_message.clone();
+            move |messages| {
+                let mut previous_widgets = mem::take(&mut widgets);
+                let mut rows = WidgetList::new();
+                let mut previous = None;
+                for message in messages {
+                    let separator = separators
+                        .as_mut()
+                        .and_then(|separators| separators(previous, message));
+                    let is_grouped = separator.is_none()
+                        && previous
+                            .zip(grouped.as_ref())
+                            .is_some_and(|(previous, grouped)| grouped(previous, message));
+                    if let Some(separator) = separator {
+                        rows.push(separator);
+                    }
+
+                    let widget_key = (key(message), is_grouped);
+                    let widget = previous_widgets
+                        .remove(&widget_key)
+                        .unwrap_or_else(|| make_message(message, is_grouped));
+                    if previous.is_none() {
+                        first_message.set(Some(widget.id()));
+                    }
+                    widgets.insert(widget_key, widget.clone());
+                    rows.push(widget);
+                    previous = Some(message);
+                }
+                rows
+            }
+        });
+
+        let anchor = Dynamic::<Option<WidgetId>>::default();
+        let scroll = rows.into_rows().vertical_scroll().anchor(anchor.clone());
+        let position = scroll.scroll.clone();
+        let max_scroll = scroll.max_scroll().clone();
+        let at_bottom =
+            (&position, &max_scroll).map_each_cloned(|(position, max)| position.y >= max.y);
+
+        let unread = Dynamic::new(0_usize);
+        at_bottom
+            .for_each_cloned({
+                let unread = unread.clone();
+                move |at_bottom| {
+                    if at_bottom {
+                        unread.set(0);
+                    }
+                }
+            })
+            .persist();
+        let mut last_message = messages.map_ref(|messages| messages.last().map(&*key));
+        messages
+            .for_each({
+                let unread = unread.clone();
+                let at_bottom = at_bottom.clone();
+                move |messages: &Vec<T>| {
+                    let last = messages.last().map(&*key);
+                    if last == last_message {
+                        return;
+                    }
+                    if !at_bottom.get() {
+                        let added = messages
+                            .iter()
+                            .rev()
+                            .take_while(|message| Some(key(message)) != last_message)
+                            .count();
+                        unread.map_mut(|mut unread| *unread += added);
+                    }
+                    last_message = last;
+                }
+            })
+            .persist();
+
+        if let Some(mut on_load_older) = on_load_older {
+            let mut load_older = LoadOlder::default();
+            position
+                .for_each_cloned({
+                    let max_scroll = max_scroll.clone();
+                    move |position: Point<UPx>| {
+                        let load = load_older.scrolled(
+                            position.y,
+                            max_scroll.get().y,
+                            first_message.get(),
+                        );
+                        anchor.set(load_older.anchor);
+                        if load {
+                            on_load_older.invoke(());
+                        }
+                    }
+                })
+                .persist();
+        }
+
+        let jump = unread
+            .map_each(|unread| {
+                if *unread == 1 {
+                    String::from("1 new message")
+                } else {
+                    format!("{unread} new messages")
+                }
+            })
+            .into_button()
+            .on_click(move |_| {
+                let max = max_scroll.get();
+                position.map_mut(|mut position| position.y = max.y);
+            })
+            .collapse_vertically(unread.map_each(|unread| *unread == 0))
+            .pad()
+            .align_bottom();
+
+        scroll.and(jump).into_layers().make_with_tag(tag)
+    }
+}
+
+/// Tracks when a [`MessageList`] should load older messages, and which
+/// message is kept in place while they are inserted.
+#[derive(Debug, Default)]
+struct LoadOlder {
+    requested: bool,
+    anchor: Option<WidgetId>,
+}
+
+impl LoadOlder {
+    /// Updates this state after the list was scrolled to `position`, returning
+    /// true if older messages should be loaded.
+    fn scrolled(
+        &mut self,
+        position: UPx,
+        max_scroll: UPx,
+        first_message: Option<WidgetId>,
+    ) -> bool {
+        if position > UPx::ZERO {
+            self.requested = false;
+            // Once older messages have been inserted above the anchored
+            // message, the scroll has been adjusted and the anchor is no
+            // longer needed.
+            if self.anchor != first_message {
+                self.anchor = None;
+            }
+            false
+        } else if !self.requested && max_scroll > UPx::ZERO {
+            self.requested = true;
+            self.anchor = first_message;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[test]
+fn load_older_anchors_until_applied() {
+    let first = WidgetTag::unique().id();
+    let older = WidgetTag::unique().id();
+    let mut load_older = LoadOlder::default();
+
+    // Nothing is loaded when the contents do not scroll.
+    assert!(!load_older.scrolled(UPx::ZERO, UPx::ZERO, Some(first)));
+    // Reaching the top requests older messages and anchors the first message.
+    assert!(load_older.scrolled(UPx::ZERO, UPx::new(100), Some(first)));
+    assert_eq!(load_older.anchor, Some(first));
+    // Older messages are only requested once while at the top.
+    assert!(!load_older.scrolled(UPx::ZERO, UPx::new(100), Some(first)));
+
+    // Scrolling away before the older messages arrive keeps the anchor.
+    assert!(!load_older.scrolled(UPx::new(10), UPx::new(100), Some(first)));
+    assert_eq!(load_older.anchor, Some(first));
+
+    // Once older messages are inserted and the scroll is adjusted, the anchor
+    // is cleared.
+    assert!(!load_older.scrolled(UPx::new(60), UPx::new(200), Some(older)));
+    assert_eq!(load_older.anchor, None);
+
+    // Returning to the top requests more messages.
+    assert!(load_older.scrolled(UPx::ZERO, UPx::new(200), Some(older)));
+    assert_eq!(load_older.anchor, Some(older));
+}
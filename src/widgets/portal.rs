@@ -0,0 +1,310 @@
+//! Widgets that display their contents in another location.
+
+use std::mem;
+
+use figures::units::{Px, UPx};
+use figures::{IntoSigned, IntoUnsigned, Point, Rect, Size, Zero};
+
+use crate::context::{AsEventContext, EventContext, GraphicsContext, LayoutContext};
+use crate::reactive::value::{Destination, Dynamic, DynamicRead, Source};
+use crate::widget::{MakeWidget, MakeWidgetWithTag, Widget, WidgetInstance, WidgetRef, WidgetTag};
+use crate::ConstraintLimit;
+
+/// A location that [`Portal`]s display their contents in.
+///
+/// A portal target is a widget that is typically placed in a
+/// [`Layers`](super::Layers) widget above the content containing its portals.
+/// Because the contents of a portal are mounted in the target, they are not
+/// clipped by any of the portal's parents, and all input is delivered to the
+/// contents directly. Contents are stacked in the order their portals were
+/// mounted, with the most recently mounted portal's contents on top.
+///
+/// A portal target can also be placed in another window. This allows a portal
+/// to display its contents in a separate window while the portal's location
+/// in the widget hierarchy controls how long they are displayed.
+#[derive(Debug, Clone, Default)]
+pub struct PortalTarget {
+    entries: Dynamic<PortalEntries>,
+}
+
+impl PortalTarget {
+    /// Returns a new, empty portal target.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of portals currently displaying their contents in
+    /// this target.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.map_ref(|entries| entries.entries.len())
+    }
+
+    /// Returns true if no portals are currently displaying their contents in
+    /// this target.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl MakeWidgetWithTag for PortalTarget {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        PortalLayer {
+            entries: self.entries,
+            widgets: Vec::new(),
+        }
+        .make_with_tag(tag)
+    }
+}
+
+/// The contents displayed in a [`PortalTarget`], in the order their portals
+/// were mounted.
+#[derive(Debug, Default)]
+struct PortalEntries {
+    next_id: u64,
+    entries: Vec<PortalEntry>,
+}
+
+impl PortalEntries {
+    fn push(&mut self, contents: WidgetInstance, anchor: Option<Dynamic<Option<Rect<Px>>>>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(PortalEntry {
+            id,
+            contents,
+            anchor,
+        });
+        id
+    }
+
+    fn remove(&mut self, id: u64) {
+        self.entries.retain(|entry| entry.id != id);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PortalEntry {
+    id: u64,
+    contents: WidgetInstance,
+    anchor: Option<Dynamic<Option<Rect<Px>>>>,
+}
+
+#[derive(Debug)]
+struct PortalWidget {
+    id: u64,
+    widget: WidgetRef,
+    anchor: Option<Dynamic<Option<Rect<Px>>>>,
+}
+
+#[derive(Debug)]
+struct PortalLayer {
+    entries: Dynamic<PortalEntries>,
+    widgets: Vec<PortalWidget>,
+}
+
+impl PortalLayer {
+    fn synchronize_widgets(&mut self, context: &mut EventContext<'_>) {
+        // The entries are cloned to ensure the lock isn't held while mounting
+        // or unmounting the contents, as the contents may contain portals of
+        // their own.
+        let entries = self.entries.read().entries.clone();
+
+        let mut existing = mem::take(&mut self.widgets);
+        for entry in entries {
+            let widget =
+                if let Some(index) = existing.iter().position(|widget| widget.id == entry.id) {
+                    existing.remove(index)
+                } else {
+                    PortalWidget {
+                        id: entry.id,
+                        widget: WidgetRef::new(entry.contents),
+                        anchor: entry.anchor,
+                    }
+                };
+            self.widgets.push(widget);
+        }
+
+        for mut removed in existing {
+            removed.widget.unmount_in(context);
+        }
+    }
+}
+
+impl Widget for PortalLayer {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        context.invalidate_when_changed(&self.entries);
+        self.synchronize_widgets(&mut context.as_event_context());
+
+        let mut visible = Vec::with_capacity(self.widgets.len());
+        for PortalWidget { widget, anchor, .. } in &mut self.widgets {
+            if anchor
+                .as_ref()
+                .map_or(true, |anchor| anchor.map_ref(Option::is_some))
+            {
+                visible.push(widget.mounted(context));
+            }
+        }
+
+        context.redraw_children(&visible);
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        context.invalidate_when_changed(&self.entries);
+        self.synchronize_widgets(&mut context.as_event_context());
+
+        // Anchors are window-relative, while child layouts are relative to
+        // this widget.
+        let origin = context
+            .last_layout()
+            .map_or(Point::ZERO, |layout| layout.origin);
+        let maximum = available_space.map(ConstraintLimit::max);
+        let mut size = Size::ZERO;
+        for PortalWidget { widget, anchor, .. } in &mut self.widgets {
+            let mounted = widget.mounted(context);
+            let layout = if let Some(anchor) = anchor {
+                let Some(anchor) = anchor.get_tracking_invalidate(context) else {
+                    context.set_child_layout(&mounted, Rect::from(Size::ZERO));
+                    continue;
+                };
+                let origin = anchor.origin - origin;
+                let remaining_height = (maximum.height.into_signed() - origin.y).max(Px::ZERO);
+                let child_size = context.for_other(&mounted).layout(Size::new(
+                    ConstraintLimit::Fill(anchor.size.width.into_unsigned()),
+                    ConstraintLimit::SizeToFit(remaining_height.into_unsigned()),
+                ));
+                Rect::new(origin, child_size.into_signed())
+            } else {
+                let child_size = context.for_other(&mounted).layout(available_space);
+                size = size.max(child_size);
+                Rect::from(child_size.into_signed())
+            };
+            context.set_child_layout(&mounted, layout);
+        }
+
+        // Anchored contents never impact the size of the target, similar to
+        // how overlays do not affect the layout of other layers.
+        size
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        for PortalWidget { widget, .. } in &mut self.widgets {
+            widget.unmount_in(context);
+        }
+    }
+}
+
+/// A widget that displays its contents in a [`PortalTarget`].
+///
+/// While a portal is mounted, its contents are displayed by its target. When
+/// the portal is unmounted, its contents are removed from the target. The
+/// contents are mounted as children of the target, so they are styled by and
+/// receive events through the target's location in the widget hierarchy,
+/// while the portal's location controls how long they are displayed. This
+/// allows contents to be displayed elsewhere, such as above a clipped scroll
+/// area or in another window.
+///
+/// By default, the contents fill the target. When [anchored](Self::anchored),
+/// the contents are instead positioned at the portal's location, which is
+/// useful for dropdowns that must escape the clipping of their parents. An
+/// anchored portal occupies the width it is offered and no height, and its
+/// contents are sized to fit below it. Anchoring is only meaningful when the
+/// target is in the same window as the portal.
+#[derive(Debug)]
+pub struct Portal {
+    contents: WidgetInstance,
+    target: PortalTarget,
+    anchor: Option<Dynamic<Option<Rect<Px>>>>,
+    id: Option<u64>,
+}
+
+impl Portal {
+    /// Returns a portal that displays `contents` in `target`.
+    #[must_use]
+    pub fn new(target: &PortalTarget, contents: impl MakeWidget) -> Self {
+        Self {
+            contents: contents.make_widget(),
+            target: target.clone(),
+            anchor: None,
+            id: None,
+        }
+    }
+
+    /// Positions the contents at this portal's location within the target
+    /// and returns self.
+    #[must_use]
+    pub fn anchored(mut self) -> Self {
+        self.anchor = Some(Dynamic::default());
+        self
+    }
+}
+
+impl Widget for Portal {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        if let Some(anchor) = &self.anchor {
+            anchor.set(context.last_layout());
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        _context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        if self.anchor.is_some() {
+            Size::new(available_space.width.min(), UPx::ZERO)
+        } else {
+            Size::ZERO
+        }
+    }
+
+    fn mounted(&mut self, _context: &mut EventContext<'_>) {
+        if self.id.is_none() {
+            self.id = Some(
+                self.target
+                    .entries
+                    .lock()
+                    .push(self.contents.clone(), self.anchor.clone()),
+            );
+        }
+    }
+
+    fn unmounted(&mut self, _context: &mut EventContext<'_>) {
+        if let Some(id) = self.id.take() {
+            self.target.entries.lock().remove(id);
+        }
+        if let Some(anchor) = &self.anchor {
+            anchor.set(None);
+        }
+    }
+}
+
+impl Drop for Portal {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.target.entries.lock().remove(id);
+        }
+    }
+}
+
+#[test]
+fn entries_keep_mount_order() {
+    use crate::widgets::Space;
+
+    let mut entries = PortalEntries::default();
+    let first = entries.push(Space::clear().make_widget(), None);
+    let second = entries.push(Space::clear().make_widget(), None);
+    entries.remove(first);
+    let third = entries.push(Space::clear().make_widget(), None);
+    let ids = entries
+        .entries
+        .iter()
+        .map(|entry| entry.id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [second, third]);
+}
@@ -0,0 +1,377 @@
+//! Text with styled spans.
+
+use std::fmt;
+use std::ops::Range;
+
+use figures::units::Px;
+use figures::{FloatConversion, ScreenScale};
+use kludgine::cosmic_text::{self, Family, FamilyOwned, Style, Weight};
+use kludgine::Color;
+
+use crate::context::{GraphicsContext, WidgetContext};
+use crate::widget::{MakeWidgetWithTag, WidgetInstance, WidgetTag};
+use crate::widgets::label::{DynamicDisplay, Label};
+
+/// Text where ranges of the text can be styled independently.
+///
+/// Each [`TextSpan`] applies a [`SpanStyle`] to a range of bytes in the text.
+/// Spans may overlap, in which case the properties of spans added later take
+/// precedence. Any properties that are not specified by a span use the
+/// widget's current style.
+///
+/// `RichText` can be displayed by a [`Label`], and can be used to highlight the
+/// contents of a [`TextEditor`](crate::widgets::TextEditor). Because spans are
+/// plain data, the output of a syntax highlighter can be converted into
+/// `RichText` and stored in a [`Dynamic`](crate::reactive::value::Dynamic) to
+/// update the displayed text.
+///
+/// ```rust
+/// use cushy::kludgine::Color;
+/// use cushy::widgets::rich_text::{RichText, SpanStyle};
+///
+/// let text = RichText::default()
+///     .with("fn", SpanStyle::default().color(Color::PURPLE).bold())
+///     .with_plain(" main() {}");
+/// assert_eq!(text.text(), "fn main() {}");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RichText {
+    text: String,
+    spans: Vec<TextSpan>,
+}
+
+impl RichText {
+    /// Returns rich text containing `text` without any styled spans.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Appends `text` styled with `style` and returns self.
+    #[must_use]
+    pub fn with(mut self, text: &str, style: SpanStyle) -> Self {
+        self.push(text, style);
+        self
+    }
+
+    /// Appends `text` without any additional styling and returns self.
+    #[must_use]
+    pub fn with_plain(mut self, text: &str) -> Self {
+        self.text.push_str(text);
+        self
+    }
+
+    /// Appends `text` styled with `style`.
+    pub fn push(&mut self, text: &str, style: SpanStyle) {
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.style_range(start..self.text.len(), style);
+    }
+
+    /// Applies `style` to `range` of the existing text.
+    ///
+    /// `range` is in bytes. Ranges that extend beyond the end of the text or
+    /// that do not fall on character boundaries are adjusted when the text is
+    /// displayed.
+    pub fn style_range(&mut self, range: Range<usize>, style: SpanStyle) {
+        if !range.is_empty() {
+            self.spans.push(TextSpan { range, style });
+        }
+    }
+
+    /// Removes all text and spans.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.spans.clear();
+    }
+
+    /// Returns the text without any styling.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the styled spans of this text.
+    #[must_use]
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    /// Returns an iterator over the runs of text that share the same style.
+    ///
+    /// The returned runs cover the entire text, in order. Overlapping spans
+    /// are combined into a single style for each run.
+    pub fn runs(&self) -> impl Iterator<Item = (&str, SpanStyle)> + '_ {
+        self.runs_in(&self.text)
+            .into_iter()
+            .map(|(range, style)| (&self.text[range], style))
+    }
+
+    /// Returns the runs of `text` produced by applying this value's spans.
+    ///
+    /// This allows the spans of this value to style a different string, such as
+    /// the contents of an editor that have changed since this value was last
+    /// updated.
+    fn runs_in(&self, text: &str) -> Vec<(Range<usize>, SpanStyle)> {
+        let clamp = |mut offset: usize| {
+            offset = offset.min(text.len());
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            offset
+        };
+
+        let mut boundaries = Vec::with_capacity(self.spans.len() * 2 + 2);
+        boundaries.push(0);
+        boundaries.push(text.len());
+        let mut starting = Vec::with_capacity(self.spans.len());
+        for (index, span) in self.spans.iter().enumerate() {
+            let start = clamp(span.range.start);
+            let end = clamp(span.range.end);
+            if start < end {
+                boundaries.push(start);
+                boundaries.push(end);
+                starting.push((start, end, index));
+            }
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        starting.sort_unstable_by_key(|(start, _, _)| *start);
+
+        let mut starting = starting.into_iter().peekable();
+        let mut active = Vec::<(usize, usize)>::new();
+        let mut runs = Vec::<(Range<usize>, SpanStyle)>::new();
+        for window in boundaries.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            active.retain(|(span_end, _)| *span_end > start);
+            while let Some((_, span_end, index)) =
+                starting.next_if(|(span_start, _, _)| *span_start <= start)
+            {
+                let insert_at = active.partition_point(|(_, other)| *other < index);
+                active.insert(insert_at, (span_end, index));
+            }
+
+            let style = active
+                .iter()
+                .fold(SpanStyle::default(), |style, (_, index)| {
+                    style.merged_with(self.spans[*index].style)
+                });
+            match runs.last_mut() {
+                Some((last, last_style)) if *last_style == style => last.end = end,
+                _ => runs.push((start..end, style)),
+            }
+        }
+        runs
+    }
+
+    /// Returns a text buffer containing `text` styled using this value's
+    /// spans and the current font settings of `context`.
+    pub(crate) fn prepare_buffer(
+        &self,
+        text: &str,
+        default_color: Color,
+        width: Option<Px>,
+        align: Option<cosmic_text::Align>,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) -> cosmic_text::Buffer {
+        let settings = context.current_font_settings();
+        let scale = context.gfx.scale();
+        let metrics = cosmic_text::Metrics::new(
+            settings.size.into_px(scale).into_float(),
+            settings.line_height.into_px(scale).into_float(),
+        );
+        let family = FamilyOwned::new(context.gfx.font_family());
+        let default_attrs = cosmic_text::Attrs::new()
+            .family(family.as_family())
+            .color(cosmic_color(default_color))
+            .weight(settings.weight)
+            .style(settings.style);
+        let runs = self.runs_in(text);
+
+        let font_system = context.gfx.font_system();
+        let mut buffer = cosmic_text::Buffer::new(font_system, metrics);
+        buffer.set_size(font_system, width.map(FloatConversion::into_float), None);
+        buffer.set_rich_text(
+            font_system,
+            runs.into_iter()
+                .map(|(range, style)| (&text[range], style.apply_to(default_attrs))),
+            default_attrs,
+            cosmic_text::Shaping::Advanced,
+        );
+        if align.is_some() {
+            for line in &mut buffer.lines {
+                line.set_align(align);
+            }
+        }
+        buffer.shape_until_scroll(font_system, false);
+        buffer
+    }
+}
+
+impl DynamicDisplay for RichText {
+    fn fmt(&self, _context: &WidgetContext<'_>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+
+    fn rich_text(&self) -> Option<&RichText> {
+        Some(self)
+    }
+}
+
+impl From<String> for RichText {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<&'_ str> for RichText {
+    fn from(text: &'_ str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl FromIterator<(String, SpanStyle)> for RichText {
+    fn from_iter<T: IntoIterator<Item = (String, SpanStyle)>>(iter: T) -> Self {
+        let mut text = Self::default();
+        for (run, style) in iter {
+            text.push(&run, style);
+        }
+        text
+    }
+}
+
+impl<'a> FromIterator<(&'a str, SpanStyle)> for RichText {
+    fn from_iter<T: IntoIterator<Item = (&'a str, SpanStyle)>>(iter: T) -> Self {
+        let mut text = Self::default();
+        for (run, style) in iter {
+            text.push(run, style);
+        }
+        text
+    }
+}
+
+impl MakeWidgetWithTag for RichText {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        Label::new(self).make_with_tag(tag)
+    }
+}
+
+/// A range of a [`RichText`] with a style applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    /// The range of bytes this span applies to.
+    pub range: Range<usize>,
+    /// The style of this span.
+    pub style: SpanStyle,
+}
+
+/// The style of a [`TextSpan`].
+///
+/// Properties that are `None` use the value from the widget's current style.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SpanStyle {
+    /// The color of the text.
+    pub color: Option<Color>,
+    /// The weight of the text.
+    pub weight: Option<Weight>,
+    /// The style of the text.
+    pub style: Option<Style>,
+    /// The font family of the text.
+    pub family: Option<Family<'static>>,
+}
+
+impl SpanStyle {
+    /// Sets the color of this style and returns self.
+    #[must_use]
+    pub const fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the weight of this style and returns self.
+    #[must_use]
+    pub const fn weight(mut self, weight: Weight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Sets the weight of this style to [`Weight::BOLD`] and returns self.
+    #[must_use]
+    pub const fn bold(self) -> Self {
+        self.weight(Weight::BOLD)
+    }
+
+    /// Sets the style of this style to [`Style::Italic`] and returns self.
+    #[must_use]
+    pub const fn italic(mut self) -> Self {
+        self.style = Some(Style::Italic);
+        self
+    }
+
+    /// Sets the font family of this style and returns self.
+    #[must_use]
+    pub const fn family(mut self, family: Family<'static>) -> Self {
+        self.family = Some(family);
+        self
+    }
+
+    /// Sets the font family of this style to [`Family::Monospace`] and returns
+    /// self.
+    #[must_use]
+    pub const fn monospace(self) -> Self {
+        self.family(Family::Monospace)
+    }
+
+    /// Returns the combination of this style and `other`, preferring the
+    /// properties of `other` when both are specified.
+    #[must_use]
+    pub fn merged_with(self, other: Self) -> Self {
+        Self {
+            color: other.color.or(self.color),
+            weight: other.weight.or(self.weight),
+            style: other.style.or(self.style),
+            family: other.family.or(self.family),
+        }
+    }
+
+    fn apply_to(self, mut attrs: cosmic_text::Attrs<'_>) -> cosmic_text::Attrs<'_> {
+        if let Some(color) = self.color {
+            attrs = attrs.color(cosmic_color(color));
+        }
+        if let Some(weight) = self.weight {
+            attrs = attrs.weight(weight);
+        }
+        if let Some(style) = self.style {
+            attrs = attrs.style(style);
+        }
+        if let Some(family) = self.family {
+            attrs = attrs.family(family);
+        }
+        attrs
+    }
+}
+
+fn cosmic_color(color: Color) -> cosmic_text::Color {
+    cosmic_text::Color::rgba(color.red(), color.green(), color.blue(), color.alpha())
+}
+
+#[test]
+fn overlapping_runs() {
+    let mut text = RichText::new("hello world");
+    text.style_range(0..5, SpanStyle::default().color(Color::RED));
+    text.style_range(3..8, SpanStyle::default().bold());
+    text.style_range(20..30, SpanStyle::default().italic());
+
+    let runs = text.runs().collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        [
+            ("hel", SpanStyle::default().color(Color::RED)),
+            ("lo", SpanStyle::default().color(Color::RED).bold()),
+            (" wo", SpanStyle::default().bold()),
+            ("rld", SpanStyle::default()),
+        ]
+    );
+}
@@ -0,0 +1,251 @@
+//! A widget that hosts multiple independent widget hierarchies.
+
+use figures::units::{Px, UPx};
+use figures::{FloatConversion, IntoSigned, IntoUnsigned, Point, Rect, Size, Zero};
+
+use crate::animation::ZeroToOne;
+use crate::context::{AsEventContext, EventContext, GraphicsContext, LayoutContext};
+use crate::reactive::value::{IntoValue, Value};
+use crate::widget::{MakeWidget, Widget, WidgetRef};
+use crate::ConstraintLimit;
+
+/// A region occupied by a root in a [`Roots`] widget.
+///
+/// Each coordinate is expressed as a fraction of the size of the [`Roots`]
+/// widget, allowing regions to adapt as a window is resized.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RootRegion {
+    /// The offset of the left edge of the region.
+    pub x: ZeroToOne,
+    /// The offset of the top edge of the region.
+    pub y: ZeroToOne,
+    /// The width of the region.
+    pub width: ZeroToOne,
+    /// The height of the region.
+    pub height: ZeroToOne,
+}
+
+impl RootRegion {
+    /// A region that occupies the entire widget.
+    pub const FULL: Self = Self {
+        x: ZeroToOne::ZERO,
+        y: ZeroToOne::ZERO,
+        width: ZeroToOne::ONE,
+        height: ZeroToOne::ONE,
+    };
+
+    /// Returns a new region from the given fractional coordinates.
+    #[must_use]
+    pub fn new(
+        x: impl Into<ZeroToOne>,
+        y: impl Into<ZeroToOne>,
+        width: impl Into<ZeroToOne>,
+        height: impl Into<ZeroToOne>,
+    ) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+            width: width.into(),
+            height: height.into(),
+        }
+    }
+
+    /// Returns a region aligned to the left edge that occupies `width` of the
+    /// available width.
+    #[must_use]
+    pub fn left(width: impl Into<ZeroToOne>) -> Self {
+        Self {
+            width: width.into(),
+            ..Self::FULL
+        }
+    }
+
+    /// Returns a region aligned to the right edge that occupies `width` of the
+    /// available width.
+    #[must_use]
+    pub fn right(width: impl Into<ZeroToOne>) -> Self {
+        let width = width.into();
+        Self {
+            x: width.one_minus(),
+            width,
+            ..Self::FULL
+        }
+    }
+
+    /// Returns a region aligned to the top edge that occupies `height` of the
+    /// available height.
+    #[must_use]
+    pub fn top(height: impl Into<ZeroToOne>) -> Self {
+        Self {
+            height: height.into(),
+            ..Self::FULL
+        }
+    }
+
+    /// Returns a region aligned to the bottom edge that occupies `height` of
+    /// the available height.
+    #[must_use]
+    pub fn bottom(height: impl Into<ZeroToOne>) -> Self {
+        let height = height.into();
+        Self {
+            y: height.one_minus(),
+            height,
+            ..Self::FULL
+        }
+    }
+
+    /// Returns the rectangle this region occupies within `size`.
+    ///
+    /// Edges are rounded down, ensuring that adjacent regions tile without
+    /// overlapping or leaving gaps.
+    #[must_use]
+    pub fn resolve(self, size: Size<UPx>) -> Rect<Px> {
+        let (left, right) = resolve_span(size.width, self.x, self.width);
+        let (top, bottom) = resolve_span(size.height, self.y, self.height);
+        Rect::new(
+            Point::new(left, top).into_signed(),
+            Size::new(right - left, bottom - top).into_signed(),
+        )
+    }
+}
+
+impl Default for RootRegion {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+fn resolve_span(total: UPx, offset: ZeroToOne, length: ZeroToOne) -> (UPx, UPx) {
+    let total_float = total.into_float();
+    let start = UPx::from_float((total_float * *offset).floor()).min(total);
+    let end = UPx::from_float((total_float * (*offset + *length)).floor()).min(total);
+    (start, end.max(start))
+}
+
+/// A widget that hosts multiple independent widget hierarchies, each laid out
+/// in its own [`RootRegion`].
+///
+/// Each root is laid out as if it were the root of its own window: it is
+/// given the exact size of its region, and is clipped to its region when
+/// drawn. A root's layout never depends on the contents of the other roots.
+///
+/// Invalidating a root, or changing a root's region, invalidates the layout of
+/// this widget and therefore of the window. When this widget is laid out
+/// again, roots that were not invalidated and whose regions did not change
+/// reuse their cached layouts.
+///
+/// This is useful for embedding scenarios where unrelated widget trees share a
+/// single window, such as a game viewport beneath an interface's chrome, or
+/// two documents shown side by side.
+///
+/// ```rust
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::roots::{RootRegion, Roots};
+///
+/// let roots = Roots::new()
+///     .with_root(RootRegion::left(0.5), "Left".centered())
+///     .with_root(RootRegion::right(0.5), "Right".centered());
+/// ```
+#[derive(Debug, Default)]
+pub struct Roots {
+    roots: Vec<Root>,
+}
+
+#[derive(Debug)]
+struct Root {
+    region: Value<RootRegion>,
+    widget: WidgetRef,
+}
+
+impl Roots {
+    /// Returns a widget with no roots.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { roots: Vec::new() }
+    }
+
+    /// Adds `root` to be displayed in `region` and returns self.
+    ///
+    /// Roots are drawn in the order they are added. If regions overlap, roots
+    /// added later will be drawn on top of roots added earlier.
+    #[must_use]
+    pub fn with_root(mut self, region: impl IntoValue<RootRegion>, root: impl MakeWidget) -> Self {
+        self.push_root(region, root);
+        self
+    }
+
+    /// Adds `root` to be displayed in `region`.
+    ///
+    /// Roots are drawn in the order they are added. If regions overlap, roots
+    /// added later will be drawn on top of roots added earlier.
+    pub fn push_root(&mut self, region: impl IntoValue<RootRegion>, root: impl MakeWidget) {
+        self.roots.push(Root {
+            region: region.into_value(),
+            widget: WidgetRef::new(root),
+        });
+    }
+
+    /// Returns the number of roots in this widget.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// Returns true if this widget has no roots.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+}
+
+impl Widget for Roots {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        for root in &mut self.roots {
+            let mounted = root.widget.mounted(&mut context.as_event_context());
+            context.for_other(&mounted).redraw();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        let size = available_space.map(ConstraintLimit::max);
+        for root in &mut self.roots {
+            let region = root.region.get_tracking_invalidate(context).resolve(size);
+            let mounted = root.widget.mounted(&mut context.as_event_context());
+            context
+                .for_other(&mounted)
+                .layout(region.size.into_unsigned().map(ConstraintLimit::Fill));
+            context.set_child_layout(&mounted, region);
+        }
+        size
+    }
+
+    fn mounted(&mut self, context: &mut EventContext<'_>) {
+        for root in &mut self.roots {
+            root.widget.mount_if_needed(context);
+        }
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        for root in &mut self.roots {
+            root.widget.unmount_in(context);
+        }
+    }
+}
+
+#[test]
+fn regions_tile() {
+    let size = Size::new(UPx::new(101), UPx::new(51));
+    let left = RootRegion::left(0.5).resolve(size);
+    let right = RootRegion::right(0.5).resolve(size);
+    assert_eq!(left.origin.x, Px::ZERO);
+    assert_eq!(left.origin.x + left.size.width, right.origin.x);
+    assert_eq!(right.origin.x + right.size.width, Px::new(101));
+    assert_eq!(left.size.height, Px::new(51));
+
+    let full = RootRegion::FULL.resolve(size);
+    assert_eq!(full, Rect::from(size.into_signed()));
+}
@@ -0,0 +1,566 @@
+//! A retained collection of shapes and text.
+
+use std::collections::hash_map;
+use std::fmt::{self, Debug};
+
+use ahash::AHashMap;
+use alot::{LotId, OrderedLots};
+use figures::units::{Px, UPx};
+use figures::{Angle, IntoSigned, Point, Rect, Size};
+use kludgine::shapes::{Path, PathBuilder, Shape, StrokeOptions};
+use kludgine::text::{MeasuredText, Text, TextOrigin};
+use kludgine::{Color, DrawableExt};
+
+use crate::context::{GraphicsContext, LayoutContext};
+use crate::reactive::value::Dynamic;
+use crate::widget::{MakeWidgetWithTag, Widget, WidgetInstance, WidgetTag};
+use crate::ConstraintLimit;
+
+/// A retained collection of shapes and text.
+///
+/// A [`Canvas`](crate::widgets::Canvas) invokes a function to draw all of its
+/// contents each time it is redrawn. A scene instead stores the items it
+/// draws, allowing each item to be added, changed, or removed individually.
+/// This is better suited to drawing large numbers of items where only a few
+/// items change at a time:
+///
+/// - The tessellated geometry or measured text of each item is cached until
+///   the item is changed.
+/// - Items that are outside of the visible region of the scene are not drawn.
+/// - When an item changes, the regions the item covered before and after the
+///   change are compared against the visible region of the scene. The scene
+///   is only redrawn when a change affects the visible region.
+///
+/// `Scene` is a cheaply cloneable handle. Use [`MakeWidget`] to display the
+/// scene, and keep a clone to modify it.
+///
+/// ```rust
+/// use cushy::figures::units::Px;
+/// use cushy::figures::{Point, Rect, Size};
+/// use cushy::kludgine::Color;
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::scene::{Scene, SceneItem, ShapeStyle};
+///
+/// let scene = Scene::new();
+/// let node = scene.push(SceneItem::Rect {
+///     rect: Rect::new(Point::new(Px::new(10), Px::new(10)), Size::squared(Px::new(50))),
+///     style: ShapeStyle::filled(Color::RED),
+/// });
+/// let widget = scene.clone().make_widget();
+///
+/// // Later, move the node.
+/// scene.update(node, |item| {
+///     if let SceneItem::Rect { rect, .. } = item {
+///         rect.origin.x += 10;
+///     }
+/// });
+/// ```
+///
+/// [`MakeWidget`]: crate::widget::MakeWidget
+#[derive(Debug, Clone, Default)]
+pub struct Scene {
+    data: Dynamic<SceneData>,
+}
+
+impl Scene {
+    /// Returns a new, empty scene.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `item` above all other items in this scene and returns its node.
+    pub fn push(&self, item: SceneItem) -> SceneNode {
+        let mut data = self.data.lock();
+        let bounds = item.bounds();
+        let id = data.items.push(SceneEntry {
+            item,
+            revision: 0,
+            text_size: None,
+        });
+        if !data.is_visible(bounds) {
+            data.prevent_notifications();
+        }
+        SceneNode(id)
+    }
+
+    /// Returns a clone of the item stored for `node`, if it is still in this
+    /// scene.
+    #[must_use]
+    pub fn get(&self, node: SceneNode) -> Option<SceneItem> {
+        let data = self.data.lock();
+        let index = data.items.index_of_id(node.0)?;
+        data.items.iter().nth(index).map(|entry| entry.item.clone())
+    }
+
+    /// Replaces the item stored for `node` with `item`.
+    ///
+    /// Returns false if `node` is no longer in this scene.
+    pub fn set(&self, node: SceneNode, item: SceneItem) -> bool {
+        self.update(node, |existing| *existing = item)
+    }
+
+    /// Invokes `update` with exclusive access to the item stored for `node`.
+    ///
+    /// Returns false if `node` is no longer in this scene.
+    pub fn update(&self, node: SceneNode, update: impl FnOnce(&mut SceneItem)) -> bool {
+        let mut data = self.data.lock();
+        let Some(index) = data.items.index_of_id(node.0) else {
+            data.prevent_notifications();
+            return false;
+        };
+        let entry = data
+            .items
+            .get_mut_by_index(index)
+            .expect("index just looked up");
+        let before = entry.bounds();
+        update(&mut entry.item);
+        entry.revision += 1;
+        entry.text_size = None;
+        let after = entry.bounds();
+        if !data.is_visible(before) && !data.is_visible(after) {
+            data.prevent_notifications();
+        }
+        true
+    }
+
+    /// Removes `node` from this scene, returning its item if it was still
+    /// in this scene.
+    pub fn remove(&self, node: SceneNode) -> Option<SceneItem> {
+        let mut data = self.data.lock();
+        let Some(index) = data.items.index_of_id(node.0) else {
+            data.prevent_notifications();
+            return None;
+        };
+        let entry = data.items.remove_by_index(index)?;
+        if !data.is_visible(entry.bounds()) {
+            data.prevent_notifications();
+        }
+        Some(entry.item)
+    }
+
+    /// Removes all items from this scene.
+    pub fn clear(&self) {
+        self.data.lock().items.clear();
+    }
+
+    /// Returns the number of items in this scene.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.data.lock().items.len()
+    }
+
+    /// Returns true if this scene contains no items.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.lock().items.is_empty()
+    }
+
+    /// Returns the top-most node whose bounds contain `point`.
+    ///
+    /// The bounds of text items are only known after they have been drawn.
+    /// Text that has not been drawn since it was last changed is never
+    /// returned.
+    #[must_use]
+    pub fn hit_test(&self, point: Point<Px>) -> Option<SceneNode> {
+        let data = self.data.lock();
+        let (index, _) = data
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.bounds().is_some_and(|bounds| bounds.contains(point)))
+            .last()?;
+        data.items.key(index).map(SceneNode)
+    }
+}
+
+impl MakeWidgetWithTag for Scene {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        SceneCanvas {
+            scene: self,
+            cache: AHashMap::new(),
+        }
+        .make_with_tag(tag)
+    }
+}
+
+/// A reference to an item in a [`Scene`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct SceneNode(LotId);
+
+/// An item that can be drawn in a [`Scene`].
+///
+/// All coordinates are relative to the top-left of the scene.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneItem {
+    /// A rectangle.
+    Rect {
+        /// The area of the rectangle.
+        rect: Rect<Px>,
+        /// How the rectangle is drawn.
+        style: ShapeStyle,
+    },
+    /// A circle.
+    Circle {
+        /// The center of the circle.
+        center: Point<Px>,
+        /// The radius of the circle.
+        radius: Px,
+        /// How the circle is drawn.
+        style: ShapeStyle,
+    },
+    /// A series of connected line segments.
+    Path {
+        /// The points to connect.
+        points: Vec<Point<Px>>,
+        /// If true, the last point is connected to the first point.
+        closed: bool,
+        /// How the path is drawn.
+        style: ShapeStyle,
+    },
+    /// A single line of text.
+    Text {
+        /// The text to draw.
+        text: String,
+        /// The location of the top-left of the text.
+        position: Point<Px>,
+        /// The color of the text.
+        color: Color,
+    },
+}
+
+impl SceneItem {
+    /// Returns the region this item covers, if it can be determined without
+    /// measuring.
+    fn bounds(&self) -> Option<Rect<Px>> {
+        match self {
+            SceneItem::Rect { rect, style } => Some(style.outset(*rect)),
+            SceneItem::Circle {
+                center,
+                radius,
+                style,
+            } => Some(style.outset(Rect::new(
+                *center - Point::squared(*radius),
+                Size::squared(*radius * 2),
+            ))),
+            SceneItem::Path { points, style, .. } => {
+                let (first, rest) = points.split_first()?;
+                let (min, max) = rest.iter().fold((*first, *first), |(min, max), point| {
+                    (
+                        Point::new(min.x.min(point.x), min.y.min(point.y)),
+                        Point::new(max.x.max(point.x), max.y.max(point.y)),
+                    )
+                });
+                Some(style.outset(Rect::from_extents(min, max)))
+            }
+            SceneItem::Text { .. } => None,
+        }
+    }
+
+    fn shapes(&self) -> Vec<Shape<Px, false>> {
+        let (path, style) = match self {
+            SceneItem::Rect { rect, style } => {
+                let mut shapes = Vec::with_capacity(2);
+                if let Some(fill) = style.fill {
+                    shapes.push(Shape::filled_rect(*rect, fill));
+                }
+                if let Some((color, width)) = style.stroke {
+                    shapes.push(Shape::stroked_rect(
+                        *rect,
+                        StrokeOptions::px_wide(width).colored(color),
+                    ));
+                }
+                return shapes;
+            }
+            SceneItem::Circle {
+                center,
+                radius,
+                style,
+            } => (
+                Path::arc(
+                    *center,
+                    Size::squared(*radius),
+                    Angle::degrees(0),
+                    Angle::degrees(360),
+                ),
+                style,
+            ),
+            SceneItem::Path {
+                points,
+                closed,
+                style,
+            } => {
+                let Some((first, rest)) = points.split_first() else {
+                    return Vec::new();
+                };
+                let path = rest
+                    .iter()
+                    .fold(PathBuilder::new(*first), |path, point| path.line_to(*point));
+                let path = if *closed { path.close() } else { path.build() };
+                (path, style)
+            }
+            SceneItem::Text { .. } => return Vec::new(),
+        };
+
+        let mut shapes = Vec::with_capacity(2);
+        if let Some(fill) = style.fill {
+            shapes.push(path.fill(fill));
+        }
+        if let Some((color, width)) = style.stroke {
+            shapes.push(path.stroke(StrokeOptions::px_wide(width).colored(color)));
+        }
+        shapes
+    }
+}
+
+/// How a shape in a [`Scene`] is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ShapeStyle {
+    /// The color to fill the shape with.
+    pub fill: Option<Color>,
+    /// The color and width of the outline of the shape.
+    pub stroke: Option<(Color, Px)>,
+}
+
+impl ShapeStyle {
+    /// Returns a style that fills shapes with `color`.
+    #[must_use]
+    pub const fn filled(color: Color) -> Self {
+        Self {
+            fill: Some(color),
+            stroke: None,
+        }
+    }
+
+    /// Returns a style that outlines shapes using `color` with a line
+    /// `width` pixels wide.
+    #[must_use]
+    pub const fn stroked(color: Color, width: Px) -> Self {
+        Self {
+            fill: None,
+            stroke: Some((color, width)),
+        }
+    }
+
+    /// Outlines shapes using `color` with a line `width` pixels wide and
+    /// returns self.
+    #[must_use]
+    pub const fn with_stroke(mut self, color: Color, width: Px) -> Self {
+        self.stroke = Some((color, width));
+        self
+    }
+
+    fn outset(&self, rect: Rect<Px>) -> Rect<Px> {
+        match self.stroke {
+            Some((_, width)) => rect.inset(-(width / 2 + 1)),
+            None => rect,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SceneData {
+    items: OrderedLots<SceneEntry>,
+    /// The region of the scene that was visible when it was last drawn.
+    visible: Option<Rect<Px>>,
+}
+
+impl SceneData {
+    fn is_visible(&self, bounds: Option<Rect<Px>>) -> bool {
+        match (self.visible, bounds) {
+            (Some(visible), Some(bounds)) => visible.intersection(&bounds).is_some(),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SceneEntry {
+    item: SceneItem,
+    revision: u64,
+    text_size: Option<Size<Px>>,
+}
+
+impl SceneEntry {
+    fn bounds(&self) -> Option<Rect<Px>> {
+        match &self.item {
+            SceneItem::Text { position, .. } => {
+                self.text_size.map(|size| Rect::new(*position, size))
+            }
+            item => item.bounds(),
+        }
+    }
+}
+
+struct SceneCanvas {
+    scene: Scene,
+    cache: AHashMap<LotId, CachedGraphic>,
+}
+
+impl Debug for SceneCanvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SceneCanvas")
+            .field("scene", &self.scene)
+            .finish_non_exhaustive()
+    }
+}
+
+struct CachedGraphic {
+    revision: u64,
+    contents: CachedContents,
+}
+
+enum CachedContents {
+    Shapes(Vec<Shape<Px, false>>),
+    Text(MeasuredText<Px>),
+}
+
+impl Widget for SceneCanvas {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        context.redraw_when_changed(&self.scene.data);
+        context.apply_current_font_settings();
+        let region = context.gfx.region();
+        let visible = context.gfx.visible_rect().map(|visible| {
+            Rect::new(
+                visible.origin.into_signed() - region.origin,
+                visible.size.into_signed(),
+            )
+        });
+
+        let mut data = self.scene.data.lock();
+        data.prevent_notifications();
+        data.visible = visible;
+        self.cache
+            .retain(|id, _| data.items.index_of_id(*id).is_some());
+
+        for index in 0..data.items.len() {
+            let id = data.items.key(index).expect("index in bounds");
+            let entry = data.items.get_mut_by_index(index).expect("index in bounds");
+            if let (Some(visible), Some(bounds)) = (visible, entry.bounds()) {
+                if visible.intersection(&bounds).is_none() {
+                    continue;
+                }
+            }
+
+            let cached = match self.cache.entry(id) {
+                hash_map::Entry::Occupied(cached) if cached.get().revision == entry.revision => {
+                    cached.into_mut()
+                }
+                cached => {
+                    let contents = if let SceneItem::Text { text, color, .. } = &entry.item {
+                        let measured = context.gfx.measure_text(Text::new(text, *color));
+                        entry.text_size = Some(measured.size);
+                        CachedContents::Text(measured)
+                    } else {
+                        CachedContents::Shapes(entry.item.shapes())
+                    };
+                    let graphic = CachedGraphic {
+                        revision: entry.revision,
+                        contents,
+                    };
+                    match cached {
+                        hash_map::Entry::Occupied(mut cached) => {
+                            cached.insert(graphic);
+                            cached.into_mut()
+                        }
+                        hash_map::Entry::Vacant(cached) => cached.insert(graphic),
+                    }
+                }
+            };
+
+            match &cached.contents {
+                CachedContents::Shapes(shapes) => {
+                    for shape in shapes {
+                        context.gfx.draw_shape(shape);
+                    }
+                }
+                CachedContents::Text(text) => {
+                    let SceneItem::Text { position, .. } = &entry.item else {
+                        unreachable!("cached text for a shape")
+                    };
+                    context
+                        .gfx
+                        .draw_measured_text(text.translate_by(*position), TextOrigin::TopLeft);
+                }
+            }
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        _context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        available_space.map(ConstraintLimit::max)
+    }
+
+    fn unmounted(&mut self, _context: &mut crate::context::EventContext<'_>) {
+        self.cache.clear();
+    }
+}
+
+#[test]
+fn changes_outside_visible_region_do_not_invalidate() {
+    use figures::Zero;
+
+    use crate::reactive::value::Source;
+
+    let square = |x: i32| SceneItem::Rect {
+        rect: Rect::new(Point::new(Px::new(x), Px::ZERO), Size::squared(Px::new(10))),
+        style: ShapeStyle::filled(Color::RED),
+    };
+    let scene = Scene::new();
+    {
+        let mut data = scene.data.lock();
+        data.prevent_notifications();
+        data.visible = Some(Rect::new(Point::ZERO, Size::squared(Px::new(100))));
+    }
+
+    let generation = scene.data.generation();
+    let hidden = scene.push(square(500));
+    assert_eq!(scene.data.generation(), generation);
+    assert!(scene.update(hidden, |item| *item = square(600)));
+    assert_eq!(scene.data.generation(), generation);
+
+    // Moving an item into the visible region invalidates the scene.
+    assert!(scene.set(hidden, square(50)));
+    let generation = scene.data.generation();
+    let visible = scene.push(square(20));
+    assert_ne!(scene.data.generation(), generation);
+
+    // Removing a visible item invalidates the scene, but removing an item
+    // that no longer exists does not.
+    assert_eq!(scene.remove(visible), Some(square(20)));
+    let generation = scene.data.generation();
+    assert_eq!(scene.remove(visible), None);
+    assert_eq!(scene.data.generation(), generation);
+    assert_eq!(scene.len(), 1);
+}
+
+#[test]
+fn hit_test_finds_topmost_item() {
+    use figures::Zero;
+
+    let scene = Scene::new();
+    let below = scene.push(SceneItem::Rect {
+        rect: Rect::new(Point::ZERO, Size::squared(Px::new(100))),
+        style: ShapeStyle::filled(Color::RED),
+    });
+    let above = scene.push(SceneItem::Circle {
+        center: Point::squared(Px::new(50)),
+        radius: Px::new(10),
+        style: ShapeStyle::stroked(Color::BLUE, Px::new(4)),
+    });
+    // Text has no bounds until it is measured.
+    scene.push(SceneItem::Text {
+        text: String::from("hello"),
+        position: Point::ZERO,
+        color: Color::BLACK,
+    });
+
+    assert_eq!(scene.hit_test(Point::squared(Px::new(50))), Some(above));
+    // The outline of the circle extends its bounds.
+    assert_eq!(scene.hit_test(Point::squared(Px::new(39))), Some(above));
+    assert_eq!(scene.hit_test(Point::squared(Px::new(5))), Some(below));
+    assert_eq!(scene.hit_test(Point::squared(Px::new(200))), None);
+}
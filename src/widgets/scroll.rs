@@ -1,7 +1,8 @@
 //! A container that scrolls its contents on a virtual surface.
 
+use std::any::Any;
 use std::mem;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use figures::units::{Lp, Px, UPx};
 use figures::{
@@ -9,20 +10,24 @@ use figures::{
 };
 use intentional::Cast;
 use kempt::Set;
+use kludgine::app::winit::dpi::PhysicalPosition;
 use kludgine::app::winit::event::{MouseScrollDelta, TouchPhase};
 use kludgine::app::winit::window::CursorIcon;
 use kludgine::shapes::{CornerRadii, Shape};
 use kludgine::Color;
 
+use crate::animation::easings::EaseOutQuadradic;
 use crate::animation::{AnimationHandle, AnimationTarget, IntoAnimate, Spawn, ZeroToOne};
-use crate::context::{AsEventContext, EventContext, LayoutContext};
+use crate::context::{AsEventContext, EventContext, GraphicsContext, LayoutContext};
 use crate::reactive::value::{
     Destination, Dynamic, DynamicReader, IntoDynamic, IntoValue, MapEachCloned, Source, Value,
 };
-use crate::styles::components::{EasingIn, EasingOut, LineHeight, PrimaryColor, SurfaceColor};
-use crate::styles::Dimension;
+use crate::styles::components::{
+    Easing, EasingIn, EasingOut, LineHeight, PrimaryColor, SurfaceColor,
+};
+use crate::styles::{Component, Dimension, RequireInvalidation};
 use crate::widget::{EventHandling, MakeWidget, Widget, WidgetId, WidgetRef, HANDLED, IGNORED};
-use crate::window::DeviceId;
+use crate::window::{DeviceId, ScrollSource};
 use crate::ConstraintLimit;
 
 // TODO is this useful enough to make public?
@@ -101,6 +106,24 @@ where
 }
 
 /// A widget that supports scrolling its contents.
+///
+/// When scrolled using a touchscreen, the contents continue scrolling after
+/// the finger is lifted, gradually slowing to a stop. The [`ScrollMomentum`]
+/// component controls which scroll sources have this behavior. When using
+/// [`ScrollChaining::Overscroll`], contents that reach an edge while slowing
+/// down bounce past the edge before springing back into place.
+///
+/// Holding shift while scrolling with a vertical mouse wheel scrolls
+/// horizontally. When a scroll event contains movement on an axis this widget
+/// cannot scroll, that portion of the event is passed to this widget's
+/// parents. This allows a horizontal scroll inside of a vertical scroll to
+/// only handle horizontal scrolling.
+///
+/// When a nested scroll reaches the end of its contents, the
+/// [`ScrollChainingPolicy`] component controls whether the remaining scroll is
+/// passed to outer scroll views ([`ScrollChaining::Chain`]), discarded
+/// ([`ScrollChaining::Clamp`]), or shown as a brief stretch past the edge
+/// ([`ScrollChaining::Overscroll`]).
 #[derive(Debug)]
 pub struct Scroll {
     contents: WidgetRef,
@@ -116,6 +139,91 @@ pub struct Scroll {
     max_scroll: DynamicReader<Point<UPx>>,
     vertical_widget: OwnedWidget<ScrollBar>,
     horizontal_widget: OwnedWidget<ScrollBar>,
+    touch: TouchVelocity,
+    inertia: AnimationHandle,
+    overscroll: Dynamic<Point<Px>>,
+    overscroll_animation: AnimationHandle,
+    anchor: Value<Option<WidgetId>>,
+    anchor_offset: Option<(WidgetId, Point<Px>)>,
+    reveal: Value<Option<WidgetId>>,
+    revealed: Option<WidgetId>,
+    reveal_behavior: Value<ScrollBehavior>,
+    reveal_animation: AnimationHandle,
+}
+
+/// How a [`Scroll`] moves when revealing a widget.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ScrollBehavior {
+    /// The scroll position changes immediately.
+    #[default]
+    Instant,
+    /// The scroll position animates over the duration using the
+    /// [`Easing`] component.
+    Animated(Duration),
+}
+
+/// The speed of a finger scrolling a [`Scroll`].
+#[derive(Debug, Default)]
+struct TouchVelocity {
+    /// The velocity in pixels per second.
+    velocity: Point<f32>,
+    last_moved: Option<Instant>,
+}
+
+/// How long a [`Scroll`] continues scrolling after a touch is lifted.
+const INERTIA_DURATION: Duration = Duration::from_millis(750);
+
+/// If a finger rests for longer than this before being lifted, no inertial
+/// scrolling occurs.
+const INERTIA_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The portion of a scroll past the end of the contents that is applied when
+/// using [`ScrollChaining::Overscroll`].
+const OVERSCROLL_RESISTANCE: f32 = 0.3;
+
+/// How long overscrolled contents take to return to their resting position.
+const OVERSCROLL_DURATION: Duration = Duration::from_millis(300);
+
+/// The motion of an inertial scroll along a single axis.
+struct InertiaAxis {
+    start: Px,
+    distance: Px,
+    max: Px,
+    /// The portion of `distance` that can be traveled before reaching an
+    /// edge.
+    traveled: f32,
+}
+
+impl InertiaAxis {
+    fn new(start: Px, velocity_distance: f32, max: Px) -> Self {
+        let distance = -Px::from(velocity_distance);
+        let end = (start + distance).clamp(Px::ZERO, max);
+        let traveled = if distance == Px::ZERO {
+            1.
+        } else {
+            (end - start).into_float() / distance.into_float()
+        };
+        Self {
+            start,
+            distance,
+            max,
+            traveled,
+        }
+    }
+
+    fn target(&self, portion: f32) -> UPx {
+        (self.start + Px::from_float(self.distance.into_float() * portion))
+            .clamp(Px::ZERO, self.max)
+            .into_unsigned()
+    }
+
+    fn bounce(&self, amount: f32, limit: Px) -> Px {
+        if self.traveled < 1. {
+            Px::from_float(amount).clamp(-limit, limit)
+        } else {
+            Px::ZERO
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -173,6 +281,16 @@ impl Scroll {
             max_scroll,
             horizontal_widget: OwnedWidget::new(horizontal),
             vertical_widget: OwnedWidget::new(vertical),
+            touch: TouchVelocity::default(),
+            inertia: AnimationHandle::new(),
+            overscroll: Dynamic::default(),
+            overscroll_animation: AnimationHandle::new(),
+            anchor: Value::Constant(None),
+            anchor_offset: None,
+            reveal: Value::Constant(None),
+            revealed: None,
+            reveal_behavior: Value::Constant(ScrollBehavior::Instant),
+            reveal_animation: AnimationHandle::new(),
         }
     }
 
@@ -209,6 +327,44 @@ impl Scroll {
         self
     }
 
+    /// Keeps the widget identified by `anchor` at the same location within
+    /// this view when the contents change, and returns self.
+    ///
+    /// When widgets are inserted or removed before the anchor, such as when
+    /// older messages are loaded above a chat history, the scroll is adjusted
+    /// by the distance the anchor moved so that the visible contents do not
+    /// jump. Scrolling by the user is unaffected.
+    #[must_use]
+    pub fn anchor(mut self, anchor: impl IntoValue<Option<WidgetId>>) -> Self {
+        self.anchor = anchor.into_value();
+        self
+    }
+
+    /// Scrolls to reveal the widget identified by `target` each time it
+    /// changes, and returns self.
+    ///
+    /// The scroll is adjusted by the minimum amount needed to show the
+    /// target, which makes this useful for jumping to a search result. When
+    /// the target is larger than this view, its leading edge is shown.
+    ///
+    /// Widgets can also reveal themselves using
+    /// [`WidgetContext::scroll_into_view`](crate::context::WidgetContext::scroll_into_view).
+    #[must_use]
+    pub fn reveal(mut self, target: impl IntoValue<Option<WidgetId>>) -> Self {
+        self.reveal = target.into_value();
+        self
+    }
+
+    /// Sets how this view scrolls when revealing the target of
+    /// [`Scroll::reveal`], and returns self.
+    ///
+    /// The default behavior is [`ScrollBehavior::Instant`].
+    #[must_use]
+    pub fn reveal_behavior(mut self, behavior: impl IntoValue<ScrollBehavior>) -> Self {
+        self.reveal_behavior = behavior.into_value();
+        self
+    }
+
     /// Returns a reader for the maximum scroll value.
     ///
     /// This represents the maximum amount that the scroll can be moved by.
@@ -217,6 +373,52 @@ impl Scroll {
         &self.max_scroll
     }
 
+    /// Adjusts the scroll by the distance the anchor widget has moved within
+    /// the contents since the last layout.
+    ///
+    /// This must be called after the contents have been laid out but before
+    /// their layout has been set, while the anchor's layout is still relative
+    /// to the contents.
+    fn preserve_anchor(
+        &mut self,
+        content_size: Size<UPx>,
+        control_size: Size<UPx>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) {
+        let anchor = self.anchor.get_tracking_invalidate(context);
+        let offset = anchor.and_then(|anchor| {
+            let layout = context.tree.widget(anchor)?.last_layout()?;
+            Some((anchor, layout.origin))
+        });
+        let previous = mem::replace(&mut self.anchor_offset, offset);
+        let (Some((previous, previous_offset)), Some((anchor, offset))) = (previous, offset) else {
+            return;
+        };
+        if previous != anchor || previous_offset == offset {
+            return;
+        }
+
+        let max_scroll = Point::new(
+            content_size.width.saturating_sub(control_size.width),
+            content_size.height.saturating_sub(control_size.height),
+        );
+        let mut scroll = self.scroll.get();
+        let moved = offset - previous_offset;
+        if self.enabled.x {
+            scroll.x = (scroll.x.into_signed() + moved.x)
+                .max(Px::ZERO)
+                .into_unsigned()
+                .min(max_scroll.x);
+        }
+        if self.enabled.y {
+            scroll.y = (scroll.y.into_signed() + moved.y)
+                .max(Px::ZERO)
+                .into_unsigned()
+                .min(max_scroll.y);
+        }
+        self.scroll.set(scroll);
+    }
+
     /// Returns a reader for the size of the scrollable area.
     #[must_use]
     pub fn content_size(&self) -> DynamicReader<Size<UPx>> {
@@ -229,6 +431,198 @@ impl Scroll {
         self.control_size.create_reader()
     }
 
+    /// Scrolls to the target of [`Scroll::reveal`] when it changes, and to any
+    /// widgets within this view that requested to be scrolled into view.
+    fn reveal_requested(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let mut requests = context.tree.reveal_requests();
+        let reveal = self.reveal.get_tracking_redraw(context);
+        if reveal != self.revealed {
+            self.revealed = reveal;
+            if let Some(target) = reveal {
+                requests.push((target, self.reveal_behavior.get()));
+            }
+        }
+
+        for (target, behavior) in requests {
+            let Some(target) = context
+                .tree
+                .widget(target)
+                .filter(|target| {
+                    context
+                        .tree
+                        .is_child(target.node_id, self.contents.widget())
+                })
+                .and_then(|target| target.last_layout())
+            else {
+                continue;
+            };
+            self.reveal_area(target, behavior, context);
+        }
+    }
+
+    fn reveal_area(
+        &mut self,
+        target: Rect<Px>,
+        behavior: ScrollBehavior,
+        context: &mut GraphicsContext<'_, '_, '_, '_>,
+    ) {
+        let Some(visible) = context.last_layout() else {
+            return;
+        };
+        let max_scroll = self.max_scroll.get();
+        let mut scroll = self.scroll.get();
+        if self.enabled.x {
+            let delta = reveal_delta(
+                visible.origin.x,
+                visible.size.width,
+                target.origin.x,
+                target.size.width,
+            );
+            scroll.x = (scroll.x.into_signed() + delta)
+                .max(Px::ZERO)
+                .into_unsigned()
+                .min(max_scroll.x);
+        }
+        if self.enabled.y {
+            let delta = reveal_delta(
+                visible.origin.y,
+                visible.size.height,
+                target.origin.y,
+                target.size.height,
+            );
+            scroll.y = (scroll.y.into_signed() + delta)
+                .max(Px::ZERO)
+                .into_unsigned()
+                .min(max_scroll.y);
+        }
+
+        self.inertia.clear();
+        match behavior {
+            ScrollBehavior::Instant => {
+                self.reveal_animation.clear();
+                self.scroll.set(scroll);
+            }
+            ScrollBehavior::Animated(duration) => {
+                self.reveal_animation = self
+                    .scroll
+                    .transition_to(scroll)
+                    .over(duration)
+                    .with_easing(context.get(&Easing))
+                    .spawn();
+            }
+        }
+    }
+
+    fn track_touch(&mut self, delta: MouseScrollDelta, phase: TouchPhase) {
+        let now = Instant::now();
+        match (phase, delta, self.touch.last_moved) {
+            (TouchPhase::Started, _, _) | (_, MouseScrollDelta::LineDelta(..), _) => {
+                self.touch.velocity = Point::ZERO;
+            }
+            (_, MouseScrollDelta::PixelDelta(delta), Some(last_moved)) => {
+                let elapsed = now.saturating_duration_since(last_moved).as_secs_f32();
+                if elapsed > 0. {
+                    self.touch.velocity =
+                        Point::new(delta.x.cast::<f32>(), delta.y.cast()) / elapsed;
+                }
+            }
+            (_, MouseScrollDelta::PixelDelta(_), None) => {}
+        }
+        self.touch.last_moved = Some(now);
+    }
+
+    /// Continues scrolling in the direction the finger was moving when it was
+    /// lifted, slowing to a stop.
+    ///
+    /// When `bounce` is true and the contents reach an edge before stopping,
+    /// the remaining speed carries the contents past the edge before they
+    /// spring back into place.
+    fn start_inertia(&mut self, bounce: bool) {
+        let Some(last_moved) = self.touch.last_moved.take() else {
+            return;
+        };
+        if last_moved.elapsed() > INERTIA_TIMEOUT {
+            return;
+        }
+
+        // With a quadratic ease-out, the starting speed is twice the average
+        // speed.
+        let distance = self.touch.velocity * INERTIA_DURATION.as_secs_f32() / 2.;
+        let max_scroll = self.max_scroll.get().into_signed();
+        let scroll = self.scroll.get().into_signed();
+        let x = InertiaAxis::new(scroll.x, distance.x, max_scroll.x);
+        let y = InertiaAxis::new(scroll.y, distance.y, max_scroll.y);
+        let traveled = x.traveled.min(y.traveled);
+
+        if !bounce || traveled >= 1. {
+            self.inertia = self
+                .scroll
+                .transition_to(Point::new(x.target(1.), y.target(1.)))
+                .over(INERTIA_DURATION)
+                .with_easing(EaseOutQuadradic)
+                .spawn();
+            return;
+        }
+
+        // The eased position reaches `traveled` of the distance when the
+        // remaining portion of the speed is the square root of the remaining
+        // distance.
+        let remaining_speed = (1. - traveled).sqrt();
+        let until_edge = INERTIA_DURATION.mul_f32(1. - remaining_speed);
+        let limit = self.control_size.get().into_signed() / 4;
+        let bounce_by = self.touch.velocity
+            * remaining_speed
+            * OVERSCROLL_DURATION.as_secs_f32()
+            * OVERSCROLL_RESISTANCE
+            / 2.;
+        let bounce_to = Point::new(
+            x.bounce(bounce_by.x, limit.width),
+            y.bounce(bounce_by.y, limit.height),
+        );
+        self.inertia = self
+            .scroll
+            .transition_to(Point::new(x.target(traveled), y.target(traveled)))
+            .over(until_edge)
+            .with_easing(EaseOutQuadradic)
+            .and_then(
+                self.overscroll
+                    .transition_to(bounce_to)
+                    .over(OVERSCROLL_DURATION / 2)
+                    .with_easing(EaseOutQuadradic),
+            )
+            .and_then(
+                self.overscroll
+                    .transition_to(Point::ZERO)
+                    .over(OVERSCROLL_DURATION)
+                    .with_easing(EaseOutQuadradic),
+            )
+            .spawn();
+    }
+
+    /// Offsets the contents past the edge they were scrolled against by a
+    /// portion of `amount`, and then springs them back into place.
+    fn overscroll(&mut self, amount: Point<f32>, context: &mut EventContext<'_>) {
+        if amount.x.abs() < 1. && amount.y.abs() < 1. {
+            return;
+        }
+        let limit = self.control_size.get().into_signed() / 4;
+        let current = self.overscroll.get();
+        let offset = Point::new(
+            (current.x + Px::from_float(amount.x * OVERSCROLL_RESISTANCE))
+                .clamp(-limit.width, limit.width),
+            (current.y + Px::from_float(amount.y * OVERSCROLL_RESISTANCE))
+                .clamp(-limit.height, limit.height),
+        );
+        self.overscroll.set(offset);
+        self.overscroll_animation = self
+            .overscroll
+            .transition_to(Point::ZERO)
+            .over(OVERSCROLL_DURATION)
+            .with_easing(EaseOutQuadradic)
+            .spawn();
+        context.set_needs_redraw();
+    }
+
     fn show_scrollbars(&mut self, context: &mut EventContext<'_>) {
         let mut horizontal = self.horizontal_widget.expect_made_mut().widget().lock();
         horizontal
@@ -243,6 +637,17 @@ impl Widget for Scroll {
         self.contents.unmount_in(context);
     }
 
+    fn preserve_state(&mut self, _context: &mut EventContext<'_>) -> Option<Box<dyn Any + Send>> {
+        Some(Box::new(self.scroll.get()))
+    }
+
+    fn restore_state(&mut self, state: Box<dyn Any + Send>, _context: &mut EventContext<'_>) {
+        // The scroll is clamped to the new contents once they are measured.
+        if let Ok(scroll) = state.downcast::<Point<UPx>>() {
+            self.scroll.set(*scroll);
+        }
+    }
+
     fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
         true
     }
@@ -269,7 +674,9 @@ impl Widget for Scroll {
             .unhover(context);
     }
 
-    fn redraw(&mut self, context: &mut crate::context::GraphicsContext<'_, '_, '_, '_>) {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        self.reveal_requested(context);
+
         let contents = self.contents.mounted(&mut context.as_event_context());
         context.for_other(&contents).redraw();
         if self.enabled.x {
@@ -321,6 +728,7 @@ impl Widget for Scroll {
                 new_content_size.height.into_unsigned()
             },
         );
+        self.preserve_anchor(new_content_size, new_control_size, context);
 
         let horizontal = self
             .horizontal_widget
@@ -361,11 +769,12 @@ impl Widget for Scroll {
             ),
         );
         let scroll = self.scroll.get_tracking_invalidate(context);
+        let overscroll = self.overscroll.get_tracking_invalidate(context);
 
         self.control_size.set(new_control_size);
 
         let region = Rect::new(
-            -scroll.into_signed(),
+            overscroll - scroll.into_signed(),
             new_content_size
                 .min(Size::new(UPx::MAX, UPx::MAX) - scroll.max(Point::default()))
                 .into_signed(),
@@ -379,27 +788,86 @@ impl Widget for Scroll {
         &mut self,
         _device_id: DeviceId,
         delta: MouseScrollDelta,
-        _phase: TouchPhase,
+        phase: TouchPhase,
         context: &mut EventContext<'_>,
     ) -> EventHandling {
-        let mut handled = false;
+        self.inertia.clear();
+        self.reveal_animation.clear();
+        let has_momentum = context
+            .get(&ScrollMomentum)
+            .applies_to(context.scroll_source());
+        if has_momentum {
+            self.track_touch(delta, phase);
+        }
+
+        // Holding shift while using a vertical-only wheel scrolls horizontally.
+        let delta = if context.modifiers().state().shift_key() {
+            vertical_to_horizontal(delta)
+        } else {
+            delta
+        };
+
+        let mut scrolled = Point::new(false, false);
+        let mut remaining = Point::new(0., 0.);
         {
             let mut vertical = self.vertical_widget.expect_made().widget().lock();
-            handled |= vertical
-                .downcast_mut::<ScrollBar>()
-                .expect("a ScrollBar")
-                .mouse_wheel(delta, context)
-                .is_break();
+            let vertical = vertical.downcast_mut::<ScrollBar>().expect("a ScrollBar");
+            remaining.y = vertical.wheel_amount(delta);
+            if self.enabled.y {
+                (scrolled.y, remaining.y) = vertical.scroll_by(remaining.y, context);
+            }
             let mut horizontal = self.horizontal_widget.expect_made().widget().lock();
-            handled |= horizontal
-                .downcast_mut::<ScrollBar>()
-                .expect("a ScrollBar")
-                .mouse_wheel(delta, context)
-                .is_break();
+            let horizontal = horizontal.downcast_mut::<ScrollBar>().expect("a ScrollBar");
+            remaining.x = horizontal.wheel_amount(delta);
+            if self.enabled.x {
+                (scrolled.x, remaining.x) = horizontal.scroll_by(remaining.x, context);
+            }
+        }
+
+        // Determine whether the scroll that reached the end of this widget's
+        // contents is kept by this widget or passed to its parents.
+        let mut claimed = false;
+        let chaining = context.get(&ScrollChainingPolicy);
+        if chaining != ScrollChaining::Chain {
+            let max_scroll = self.max_scroll.get();
+            let can_scroll = Point::new(
+                self.enabled.x && max_scroll.x > 0,
+                self.enabled.y && max_scroll.y > 0,
+            );
+            if chaining == ScrollChaining::Overscroll {
+                self.overscroll(
+                    Point::new(
+                        if can_scroll.x { remaining.x } else { 0. },
+                        if can_scroll.y { remaining.y } else { 0. },
+                    ),
+                    context,
+                );
+            }
+            if can_scroll.x {
+                claimed |= remaining.x.abs() > 0.;
+                remaining.x = 0.;
+            }
+            if can_scroll.y {
+                claimed |= remaining.y.abs() > 0.;
+                remaining.y = 0.;
+            }
+        }
+
+        if has_momentum && phase == TouchPhase::Ended {
+            self.start_inertia(chaining == ScrollChaining::Overscroll);
         }
-        if handled {
+        if scrolled.x || scrolled.y {
             self.show_scrollbars(context);
             context.set_needs_redraw();
+        }
+        if scrolled.x || scrolled.y || claimed {
+            // Allow parents to scroll by the amount this widget could not.
+            if remaining.x.abs() >= 1. || remaining.y.abs() >= 1. {
+                context.set_unhandled_scroll(MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+                    f64::from(remaining.x),
+                    f64::from(remaining.y),
+                )));
+            }
 
             HANDLED
         } else {
@@ -476,6 +944,33 @@ impl DragInfo {
     }
 }
 
+/// Converts a scroll that only has a vertical component into a horizontal
+/// scroll.
+fn vertical_to_horizontal(delta: MouseScrollDelta) -> MouseScrollDelta {
+    match delta {
+        MouseScrollDelta::LineDelta(x, y) if x == 0. => MouseScrollDelta::LineDelta(y, 0.),
+        MouseScrollDelta::PixelDelta(delta) if delta.x == 0. => {
+            MouseScrollDelta::PixelDelta(PhysicalPosition::new(delta.y, 0.))
+        }
+        delta => delta,
+    }
+}
+
+/// Returns the distance to scroll along one axis to show the span of `length`
+/// at `start` within the visible span. When the span is larger than the
+/// visible span, its start is kept visible.
+fn reveal_delta(visible_start: Px, visible_length: Px, start: Px, length: Px) -> Px {
+    let visible_end = visible_start + visible_length;
+    let end = start + length;
+    if start < visible_start {
+        start - visible_start
+    } else if end > visible_end {
+        (end - visible_end).min(start - visible_start)
+    } else {
+        Px::ZERO
+    }
+}
+
 fn constrain_child(constraint: ConstraintLimit, measured: UPx) -> UPx {
     match constraint {
         ConstraintLimit::Fill(size) => size.min(measured),
@@ -594,12 +1089,29 @@ impl ScrollBar {
         delta: MouseScrollDelta,
         context: &mut EventContext<'_>,
     ) -> EventHandling {
+        if self.scroll_by(self.wheel_amount(delta), context).0 {
+            HANDLED
+        } else {
+            IGNORED
+        }
+    }
+
+    /// Returns the number of pixels `delta` scrolls along this bar's axis.
+    fn wheel_amount(&self, delta: MouseScrollDelta) -> f32 {
         let amount = match delta {
             MouseScrollDelta::LineDelta(x, y) => Point::new(x, y) * self.line_height.into_float(),
             MouseScrollDelta::PixelDelta(px) => Point::new(px.x.cast(), px.y.cast()),
         };
-        let amount = if self.vertical { amount.y } else { amount.x };
+        if self.vertical {
+            amount.y
+        } else {
+            amount.x
+        }
+    }
 
+    /// Scrolls by `amount` pixels, returning whether the scroll changed and
+    /// the portion of `amount` that could not be scrolled.
+    fn scroll_by(&mut self, amount: f32, context: &mut EventContext<'_>) -> (bool, f32) {
         let mut scroll = self.scroll.lock();
         let old_scroll = *scroll;
         let new_scroll = Self::constrained_scroll(
@@ -607,12 +1119,15 @@ impl ScrollBar {
             self.max_scroll.get(),
         );
         if old_scroll == new_scroll {
-            IGNORED
+            (false, amount)
         } else {
             *scroll = new_scroll;
             drop(scroll);
             self.show(context);
-            HANDLED
+            (
+                true,
+                amount - (old_scroll.into_float() - new_scroll.into_float()),
+            )
         }
     }
 
@@ -698,7 +1213,7 @@ impl ScrollBar {
 }
 
 impl Widget for ScrollBar {
-    fn redraw(&mut self, context: &mut crate::context::GraphicsContext<'_, '_, '_, '_>) {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
         let scroll = self.scroll.get_tracking_redraw(context);
         let content_size = self.content_size.get_tracking_redraw(context);
         let control_size = context.gfx.region().size.into_unsigned();
@@ -928,5 +1443,179 @@ define_components! {
         ScrollBarThumbOutlineThickness(Dimension, "thumb_outline_size", Dimension::Lp(Lp::points(1)))
         /// The thickness of the outline drawn around the scroll bar thumb.
         ScrollBarThumbCornerRadius(CornerRadii<Dimension>, "corner_radius", |context| CornerRadii::from(context.get(&ScrollBarThickness)))
+        /// Controls what happens when a [`Scroll`] is scrolled past the end of
+        /// its contents.
+        ScrollChainingPolicy(ScrollChaining, "chaining", ScrollChaining::Chain)
+        /// Controls which scroll sources continue scrolling a [`Scroll`] after
+        /// they are released.
+        ScrollMomentum(Momentum, "momentum", Momentum::Touch)
+    }
+}
+
+/// The behavior of a [`Scroll`] when it is scrolled past the end of its
+/// contents.
+///
+/// This is controlled using the [`ScrollChainingPolicy`] component, which is
+/// inherited by nested scroll views.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ScrollChaining {
+    /// The portion of the scroll that could not be applied is passed to the
+    /// parent widgets, allowing an outer scroll view to continue scrolling.
+    #[default]
+    Chain,
+    /// The portion of the scroll that could not be applied is ignored. Outer
+    /// scroll views only scroll when the cursor is not above an inner scroll
+    /// view that can scroll.
+    Clamp,
+    /// Like [`ScrollChaining::Clamp`], but the contents are briefly moved
+    /// past their edge before springing back into place.
+    Overscroll,
+}
+
+impl From<ScrollChaining> for Component {
+    fn from(value: ScrollChaining) -> Self {
+        Component::custom(value)
+    }
+}
+
+impl TryFrom<Component> for ScrollChaining {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Custom(custom) => custom
+                .downcast()
+                .copied()
+                .ok_or_else(|| Component::Custom(custom)),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for ScrollChaining {
+    fn requires_invalidation(&self) -> bool {
+        false
+    }
+}
+
+/// The scroll sources that continue scrolling a [`Scroll`] after they are
+/// released.
+///
+/// This is controlled using the [`ScrollMomentum`] component, which is
+/// inherited by nested scroll views.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Momentum {
+    /// Scrolling stops as soon as the scroll source is released.
+    None,
+    /// Scrolling continues after a finger is lifted from a touchscreen.
+    #[default]
+    Touch,
+    /// Scrolling continues after a finger is lifted from a touchscreen or a
+    /// touchpad.
+    ///
+    /// Some platforms, such as macOS, already continue delivering scroll
+    /// events after a touchpad gesture ends. This option is intended for
+    /// platforms that do not.
+    TouchAndTouchpad,
+}
+
+impl Momentum {
+    /// Returns true if scrolling from `source` should continue after it is
+    /// released.
+    #[must_use]
+    pub const fn applies_to(self, source: ScrollSource) -> bool {
+        matches!(
+            (self, source),
+            (Self::Touch | Self::TouchAndTouchpad, ScrollSource::Touch)
+                | (Self::TouchAndTouchpad, ScrollSource::Touchpad)
+        )
     }
 }
+
+impl From<Momentum> for Component {
+    fn from(value: Momentum) -> Self {
+        Component::custom(value)
+    }
+}
+
+impl TryFrom<Component> for Momentum {
+    type Error = Component;
+
+    fn try_from(value: Component) -> Result<Self, Self::Error> {
+        match value {
+            Component::Custom(custom) => custom
+                .downcast()
+                .copied()
+                .ok_or_else(|| Component::Custom(custom)),
+            other => Err(other),
+        }
+    }
+}
+
+impl RequireInvalidation for Momentum {
+    fn requires_invalidation(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn inertia_stops_at_edges() {
+    // Flinging upward by 300px from 100px reaches the top a third of the way.
+    let axis = InertiaAxis::new(Px::new(100), 300., Px::new(1_000));
+    assert!((axis.traveled - 1. / 3.).abs() < f32::EPSILON);
+    assert_eq!(axis.target(axis.traveled), UPx::ZERO);
+    assert_eq!(axis.bounce(50., Px::new(20)), Px::new(20));
+
+    let axis = InertiaAxis::new(Px::new(100), -300., Px::new(1_000));
+    assert!((axis.traveled - 1.).abs() < f32::EPSILON);
+    assert_eq!(axis.target(1.), UPx::new(400));
+    assert_eq!(axis.bounce(50., Px::new(20)), Px::ZERO);
+}
+
+#[test]
+fn reveal_delta_scrolls_minimally() {
+    let delta = |start, length| reveal_delta(Px::new(100), Px::new(50), start, length);
+    // Fully visible spans don't scroll.
+    assert_eq!(delta(Px::new(110), Px::new(20)), Px::ZERO);
+    // Spans above or below the visible area are scrolled to the nearest edge.
+    assert_eq!(delta(Px::new(80), Px::new(10)), Px::new(-20));
+    assert_eq!(delta(Px::new(140), Px::new(20)), Px::new(10));
+    // Spans taller than the visible area keep their start visible.
+    assert_eq!(delta(Px::new(120), Px::new(200)), Px::new(20));
+    assert_eq!(delta(Px::new(50), Px::new(200)), Px::new(-50));
+}
+
+#[test]
+fn reveal_scrolls_target_into_view() {
+    use crate::widget::{MakeWidgetWithTag, WidgetTag};
+    use crate::widgets::Space;
+
+    let (tag, target) = WidgetTag::new();
+    let reveal = Dynamic::new(None);
+    let mut recorder = Space::colored(Color::RED)
+        .height(Px::new(100))
+        .and(
+            Space::colored(Color::BLUE)
+                .height(Px::new(100))
+                .make_with_tag(tag),
+        )
+        .into_rows()
+        .vertical_scroll()
+        .reveal(reveal.clone())
+        .build_recorder()
+        .size(Size::squared(UPx::new(32)))
+        .finish()
+        .expect("error creating recorder");
+    assert_eq!(
+        recorder.pixel_color(Point::squared(UPx::new(4))),
+        Color::RED
+    );
+
+    reveal.set(Some(target));
+    recorder.refresh().expect("error rendering");
+    recorder.refresh().expect("error rendering");
+    assert_eq!(
+        recorder.pixel_color(Point::squared(UPx::new(4))),
+        Color::BLUE
+    );
+}
@@ -1,10 +1,15 @@
 //! A keyboard shortcut handling widget.
 
+use std::fmt::{self, Display};
+use std::sync::Arc;
+
 use ahash::AHashMap;
 use kludgine::app::winit::keyboard::{
     Key, KeyCode, ModifiersState, NamedKey, NativeKey, NativeKeyCode, PhysicalKey, SmolStr,
 };
+use parking_lot::Mutex;
 
+use crate::telemetry::{self, ActionSource, TelemetryEvent};
 use crate::widget::{
     EventHandling, MakeWidget, SharedCallback, WidgetRef, WrapperWidget, HANDLED, IGNORED,
 };
@@ -12,8 +17,22 @@ use crate::window::KeyEvent;
 use crate::{ModifiersExt, ModifiersStateExt};
 
 /// A collection of keyboard shortcut handlers.
+///
+/// In addition to single key combinations, a `ShortcutMap` supports two-step
+/// chords, such as `Ctrl+K` followed by `Ctrl+S`. When the first step of a
+/// chord is pressed, the next key press is consumed by the map regardless of
+/// whether it completes a registered chord.
+///
+/// When a binding is inserted that prevents an existing binding from being
+/// invoked, the existing binding is replaced and a [`ShortcutConflict`] is
+/// recorded. The recorded conflicts can be inspected using
+/// [`ShortcutMap::conflicts`].
 #[derive(Default, Debug, Clone)]
-pub struct ShortcutMap(AHashMap<Shortcut, ShortcutConfig>);
+pub struct ShortcutMap {
+    bindings: AHashMap<Shortcut, ShortcutBinding>,
+    conflicts: Vec<ShortcutConflict>,
+    pending_chord: Arc<Mutex<Option<Shortcut>>>,
+}
 
 impl ShortcutMap {
     /// Inserts a handler that invokes `callback` once when `key` is pressed
@@ -70,6 +89,88 @@ impl ShortcutMap {
         self.insert_shortcut_inner(key.into(), modifiers, true, SharedCallback::new(callback));
     }
 
+    /// Inserts a handler that invokes `callback` once when `first` is pressed
+    /// followed by `second`.
+    ///
+    /// ```rust
+    /// use cushy::kludgine::app::winit::keyboard::ModifiersState;
+    /// use cushy::widget::HANDLED;
+    /// use cushy::widgets::shortcuts::ShortcutMap;
+    /// use cushy::ModifiersStateExt;
+    ///
+    /// let shortcuts = ShortcutMap::default().with_chord(
+    ///     ("k", ModifiersState::PRIMARY),
+    ///     ("s", ModifiersState::PRIMARY),
+    ///     |_| {
+    ///         println!("Save all");
+    ///         HANDLED
+    ///     },
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_chord<F>(
+        mut self,
+        first: impl Into<Shortcut>,
+        second: impl Into<Shortcut>,
+        callback: F,
+    ) -> Self
+    where
+        F: FnMut(KeyEvent) -> EventHandling + Send + 'static,
+    {
+        self.insert_chord(first, second, callback);
+        self
+    }
+
+    /// Inserts a handler that invokes `callback` once when `first` is pressed
+    /// followed by `second`.
+    pub fn insert_chord<F>(
+        &mut self,
+        first: impl Into<Shortcut>,
+        second: impl Into<Shortcut>,
+        callback: F,
+    ) where
+        F: FnMut(KeyEvent) -> EventHandling + Send + 'static,
+    {
+        let first = first.into();
+        let second = second.into();
+        let config = ShortcutConfig {
+            repeat: false,
+            callback: SharedCallback::new(callback),
+        };
+
+        let (first, first_variation) = first.into_variations();
+        let (second, second_variation) = second.into_variations();
+        for prefix in Some(first.clone()).into_iter().chain(first_variation) {
+            let report = prefix == first;
+            let binding = self
+                .bindings
+                .entry(prefix.clone())
+                .or_insert_with(|| ShortcutBinding::Chord(AHashMap::new()));
+            if let ShortcutBinding::Callback(_) = binding {
+                if report {
+                    self.conflicts.push(ShortcutConflict::new(
+                        vec![first.clone(), second.clone()],
+                        vec![prefix.clone()],
+                    ));
+                }
+                *binding = ShortcutBinding::Chord(AHashMap::new());
+            }
+            let ShortcutBinding::Chord(steps) = binding else {
+                unreachable!("replaced above")
+            };
+
+            if steps.insert(second.clone(), config.clone()).is_some() && report {
+                self.conflicts.push(ShortcutConflict::new(
+                    vec![first.clone(), second.clone()],
+                    vec![first.clone(), second.clone()],
+                ));
+            }
+            if let Some(second_variation) = &second_variation {
+                steps.insert(second_variation.clone(), config.clone());
+            }
+        }
+    }
+
     fn insert_shortcut_inner(
         &mut self,
         key: ShortcutKey,
@@ -81,10 +182,43 @@ impl ShortcutMap {
         let config = ShortcutConfig { repeat, callback };
 
         if let Some(second) = second {
-            self.0.insert(second, config.clone());
+            self.bindings
+                .insert(second, ShortcutBinding::Callback(config.clone()));
         }
 
-        self.0.insert(first, config);
+        if let Some(replaced) = self
+            .bindings
+            .insert(first.clone(), ShortcutBinding::Callback(config))
+        {
+            match replaced {
+                ShortcutBinding::Callback(_) => {
+                    self.conflicts
+                        .push(ShortcutConflict::new(vec![first.clone()], vec![first]));
+                }
+                ShortcutBinding::Chord(steps) => {
+                    for step in steps.into_keys() {
+                        self.conflicts.push(ShortcutConflict::new(
+                            vec![first.clone()],
+                            vec![first.clone(), step],
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the conflicts that have been detected while inserting
+    /// bindings into this map.
+    #[must_use]
+    pub fn conflicts(&self) -> &[ShortcutConflict] {
+        &self.conflicts
+    }
+
+    /// Returns true if the first step of a chord has been pressed and the map
+    /// is waiting for the next key press.
+    #[must_use]
+    pub fn is_chord_pending(&self) -> bool {
+        self.pending_chord.lock().is_some()
     }
 
     /// Invokes any associated handlers for `input`.
@@ -92,46 +226,107 @@ impl ShortcutMap {
     /// Returns whether the event has been handled or not.
     #[must_use]
     pub fn input(&self, input: KeyEvent) -> EventHandling {
-        for modifiers in FuzzyModifiers(input.modifiers.state()) {
-            let physical_match = self.0.get(&Shortcut {
-                key: ShortcutKey::Physical(input.physical_key),
-                modifiers,
-            });
-            let logical_match = self.0.get(&Shortcut {
-                key: ShortcutKey::Logical(input.logical_key.clone()),
-                modifiers,
-            });
-            match (physical_match, logical_match) {
-                (Some(physical), Some(logical)) if physical.callback != logical.callback => {
-                    // Prefer an exact physical key match.
-                    if input.state.is_pressed()
-                        && (!input.repeat || physical.repeat)
-                        && physical.callback.invoke(input.clone()).is_break()
-                    {
-                        return HANDLED;
-                    }
+        let mut pending_chord = self.pending_chord.lock();
+        if let Some(prefix) = pending_chord.as_ref() {
+            // Releasing the first step and pressing modifiers for the second
+            // step should not cancel the chord.
+            if !input.state.is_pressed() || is_modifier(&input.logical_key) {
+                return IGNORED;
+            }
 
-                    return if input.state.is_pressed() && (!input.repeat || logical.repeat) {
-                        logical.callback.invoke(input)
-                    } else {
-                        IGNORED
-                    };
-                }
-                (Some(callback), _) | (_, Some(callback)) => {
-                    return if input.state.is_pressed() && (!input.repeat || callback.repeat) {
-                        callback.callback.invoke(input)
-                    } else {
-                        IGNORED
-                    };
+            let prefix = pending_chord.take().expect("checked above");
+            drop(pending_chord);
+            if let Some(ShortcutBinding::Chord(steps)) = self.bindings.get(&prefix) {
+                let (physical, logical) = find_shortcut(steps, &input);
+                let _handling = invoke_shortcut(
+                    physical.map(|(_, config)| config),
+                    logical.map(|(_, config)| config),
+                    input,
+                );
+            }
+            return HANDLED;
+        }
+
+        let (physical, logical) = find_shortcut(&self.bindings, &input);
+        match (physical, logical) {
+            (Some((shortcut, ShortcutBinding::Chord(_))), _)
+            | (None, Some((shortcut, ShortcutBinding::Chord(_)))) => {
+                if input.state.is_pressed() && !input.repeat {
+                    *pending_chord = Some(shortcut.clone());
+                    HANDLED
+                } else {
+                    IGNORED
                 }
-                _ => {}
+            }
+            (physical, logical) => {
+                drop(pending_chord);
+                invoke_shortcut(
+                    physical.and_then(|(_, binding)| binding.as_callback()),
+                    logical.and_then(|(_, binding)| binding.as_callback()),
+                    input,
+                )
             }
         }
+    }
+}
 
-        IGNORED
+type ShortcutMatch<'a, T> = Option<(&'a Shortcut, &'a T)>;
+
+fn find_shortcut<'a, T>(
+    map: &'a AHashMap<Shortcut, T>,
+    input: &KeyEvent,
+) -> (ShortcutMatch<'a, T>, ShortcutMatch<'a, T>) {
+    for modifiers in FuzzyModifiers(input.modifiers.state()) {
+        let physical_match = map.get_key_value(&Shortcut {
+            key: ShortcutKey::Physical(input.physical_key),
+            modifiers,
+        });
+        let logical_match = map.get_key_value(&Shortcut {
+            key: ShortcutKey::Logical(input.logical_key.clone()),
+            modifiers,
+        });
+        if physical_match.is_some() || logical_match.is_some() {
+            return (physical_match, logical_match);
+        }
+    }
+
+    (None, None)
+}
+
+fn invoke_shortcut(
+    physical: Option<&ShortcutConfig>,
+    logical: Option<&ShortcutConfig>,
+    input: KeyEvent,
+) -> EventHandling {
+    match (physical, logical) {
+        (Some(physical), Some(logical)) if physical.callback != logical.callback => {
+            // Prefer an exact physical key match.
+            if physical.invoke(input.clone()).is_break() {
+                return HANDLED;
+            }
+
+            logical.invoke(input)
+        }
+        (Some(config), _) | (_, Some(config)) => config.invoke(input),
+        (None, None) => IGNORED,
     }
 }
 
+fn is_modifier(key: &Key) -> bool {
+    matches!(
+        key,
+        Key::Named(
+            NamedKey::Alt
+                | NamedKey::AltGraph
+                | NamedKey::Control
+                | NamedKey::Shift
+                | NamedKey::Super
+                | NamedKey::Meta
+                | NamedKey::Hyper
+        )
+    )
+}
+
 /// An iterator that attempts one fallback towards a common shortcut modifier.
 ///
 /// The precedence for the fallback is: Primary, Control, Super.
@@ -210,15 +405,52 @@ impl Shortcuts {
         self.shortcuts.insert_repeating(key, modifiers, callback);
         self
     }
+
+    /// Invokes `callback` when `first` is pressed followed by `second`.
+    ///
+    /// This shortcut will only be invoked if focus is within a child of this
+    /// widget, or if this widget becomes the root widget of a window.
+    #[must_use]
+    pub fn with_chord<F>(
+        mut self,
+        first: impl Into<Shortcut>,
+        second: impl Into<Shortcut>,
+        callback: F,
+    ) -> Self
+    where
+        F: FnMut(KeyEvent) -> EventHandling + Send + 'static,
+    {
+        self.shortcuts.insert_chord(first, second, callback);
+        self
+    }
 }
 
+/// A key combination in a [`ShortcutMap`].
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct Shortcut {
+pub struct Shortcut {
+    /// The key that is pressed.
     pub key: ShortcutKey,
+    /// The modifiers that must be pressed along with `key`.
     pub modifiers: ModifiersState,
 }
 
 impl Shortcut {
+    /// Returns a shortcut for `key` pressed while `modifiers` are pressed.
+    #[must_use]
+    pub fn new(key: impl Into<ShortcutKey>, modifiers: ModifiersState) -> Self {
+        Self {
+            key: key.into(),
+            modifiers,
+        }
+    }
+
+    /// Returns a shortcut for `key` pressed while the platform's
+    /// [primary modifier](ModifiersStateExt::PRIMARY) is pressed.
+    #[must_use]
+    pub fn primary(key: impl Into<ShortcutKey>) -> Self {
+        Self::new(key, ModifiersState::PRIMARY)
+    }
+
     fn into_variations(self) -> (Shortcut, Option<Shortcut>) {
         let modifiers = self.modifiers;
         let extra = match &self.key {
@@ -243,6 +475,55 @@ impl Shortcut {
     }
 }
 
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        const MODIFIERS: [(ModifiersState, &str); 4] = [
+            (ModifiersState::CONTROL, "Ctrl"),
+            (ModifiersState::ALT, "Option"),
+            (ModifiersState::SHIFT, "Shift"),
+            (ModifiersState::SUPER, "Cmd"),
+        ];
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        const MODIFIERS: [(ModifiersState, &str); 4] = [
+            (ModifiersState::CONTROL, "Ctrl"),
+            (ModifiersState::ALT, "Alt"),
+            (ModifiersState::SHIFT, "Shift"),
+            (ModifiersState::SUPER, "Super"),
+        ];
+
+        for (modifier, name) in MODIFIERS {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match &self.key {
+            ShortcutKey::Logical(Key::Character(ch)) => f.write_str(&ch.to_uppercase()),
+            ShortcutKey::Logical(Key::Named(key)) => write!(f, "{key:?}"),
+            ShortcutKey::Logical(key) => write!(f, "{key:?}"),
+            ShortcutKey::Physical(PhysicalKey::Code(code)) => {
+                let code = format!("{code:?}");
+                let code = code
+                    .strip_prefix("Key")
+                    .or_else(|| code.strip_prefix("Digit"))
+                    .unwrap_or(&code);
+                f.write_str(code)
+            }
+            ShortcutKey::Physical(key) => write!(f, "{key:?}"),
+        }
+    }
+}
+
+impl<K> From<(K, ModifiersState)> for Shortcut
+where
+    K: Into<ShortcutKey>,
+{
+    fn from((key, modifiers): (K, ModifiersState)) -> Self {
+        Self::new(key, modifiers)
+    }
+}
+
 impl From<PhysicalKey> for ShortcutKey {
     fn from(key: PhysicalKey) -> Self {
         ShortcutKey::Physical(key)
@@ -291,12 +572,59 @@ impl From<NativeKeyCode> for ShortcutKey {
     }
 }
 
+#[derive(Debug, Clone)]
+enum ShortcutBinding {
+    Callback(ShortcutConfig),
+    Chord(AHashMap<Shortcut, ShortcutConfig>),
+}
+
+impl ShortcutBinding {
+    fn as_callback(&self) -> Option<&ShortcutConfig> {
+        match self {
+            ShortcutBinding::Callback(config) => Some(config),
+            ShortcutBinding::Chord(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ShortcutConfig {
     repeat: bool,
     callback: SharedCallback<KeyEvent, EventHandling>,
 }
 
+impl ShortcutConfig {
+    fn invoke(&self, input: KeyEvent) -> EventHandling {
+        if input.state.is_pressed() && (!input.repeat || self.repeat) {
+            telemetry::emit(|| {
+                TelemetryEvent::ActionInvoked(ActionSource::Shortcut {
+                    key: input.logical_key.clone(),
+                    modifiers: input.modifiers.state(),
+                })
+            });
+            self.callback.invoke(input)
+        } else {
+            IGNORED
+        }
+    }
+}
+
+/// A binding in a [`ShortcutMap`] that replaced an existing binding.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ShortcutConflict {
+    /// The key combinations of the binding that was inserted.
+    pub inserted: Vec<Shortcut>,
+    /// The key combinations of the binding that can no longer be invoked.
+    pub replaced: Vec<Shortcut>,
+}
+
+impl ShortcutConflict {
+    fn new(inserted: Vec<Shortcut>, replaced: Vec<Shortcut>) -> Self {
+        tracing::warn!("shortcut {inserted:?} conflicts with {replaced:?}");
+        Self { inserted, replaced }
+    }
+}
+
 /// A key used in a [`Shortcuts`] widget.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum ShortcutKey {
@@ -327,3 +655,98 @@ impl WrapperWidget for Shortcuts {
         self.shortcuts.input(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use kludgine::app::winit::event::{ElementState, Modifiers};
+    use kludgine::app::winit::keyboard::{Key, KeyCode, KeyLocation, ModifiersState, PhysicalKey};
+
+    use super::{Shortcut, ShortcutConflict, ShortcutMap};
+    use crate::widget::HANDLED;
+    use crate::window::KeyEvent;
+
+    fn key(character: &str, code: KeyCode, state: ElementState) -> KeyEvent {
+        KeyEvent {
+            logical_key: Key::Character(character.into()),
+            physical_key: PhysicalKey::Code(code),
+            text: None,
+            location: KeyLocation::Standard,
+            state,
+            repeat: false,
+            modifiers: Modifiers::from(ModifiersState::CONTROL),
+        }
+    }
+
+    #[test]
+    fn chords() {
+        let invoked = Arc::new(AtomicUsize::new(0));
+        let shortcuts = ShortcutMap::default().with_chord(
+            ("k", ModifiersState::CONTROL),
+            ("s", ModifiersState::CONTROL),
+            {
+                let invoked = invoked.clone();
+                move |_| {
+                    invoked.fetch_add(1, Ordering::Relaxed);
+                    HANDLED
+                }
+            },
+        );
+
+        assert!(shortcuts
+            .input(key("k", KeyCode::KeyK, ElementState::Pressed))
+            .is_break());
+        assert!(shortcuts.is_chord_pending());
+        assert!(shortcuts
+            .input(key("k", KeyCode::KeyK, ElementState::Released))
+            .is_continue());
+        assert!(shortcuts
+            .input(key("s", KeyCode::KeyS, ElementState::Pressed))
+            .is_break());
+        assert!(!shortcuts.is_chord_pending());
+        assert_eq!(invoked.load(Ordering::Relaxed), 1);
+
+        // A second step that doesn't match is consumed without invoking the
+        // callback.
+        assert!(shortcuts
+            .input(key("k", KeyCode::KeyK, ElementState::Pressed))
+            .is_break());
+        assert!(shortcuts
+            .input(key("x", KeyCode::KeyX, ElementState::Pressed))
+            .is_break());
+        assert!(!shortcuts.is_chord_pending());
+        assert_eq!(invoked.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn conflicts() {
+        let shortcuts = ShortcutMap::default()
+            .with_shortcut("k", ModifiersState::CONTROL, |_| HANDLED)
+            .with_chord(
+                ("k", ModifiersState::CONTROL),
+                ("s", ModifiersState::CONTROL),
+                |_| HANDLED,
+            )
+            .with_shortcut("p", ModifiersState::CONTROL, |_| HANDLED)
+            .with_shortcut("p", ModifiersState::CONTROL, |_| HANDLED);
+
+        let ctrl_k = Shortcut::new("k", ModifiersState::CONTROL);
+        let ctrl_s = Shortcut::new("s", ModifiersState::CONTROL);
+        let ctrl_p = Shortcut::new("p", ModifiersState::CONTROL);
+        assert_eq!(
+            shortcuts.conflicts(),
+            &[
+                ShortcutConflict {
+                    inserted: vec![ctrl_k.clone(), ctrl_s],
+                    replaced: vec![ctrl_k],
+                },
+                ShortcutConflict {
+                    inserted: vec![ctrl_p.clone()],
+                    replaced: vec![ctrl_p],
+                },
+            ]
+        );
+    }
+}
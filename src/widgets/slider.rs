@@ -45,6 +45,21 @@ where
     ///
     /// This defaults to `0.05`/5%.
     pub step: Value<ZeroToOne>,
+    /// The smallest distance allowed between the start and end of a ranged
+    /// slider, as a percentage of the slider's range.
+    ///
+    /// This defaults to `0.0`, which allows the start and end to be equal.
+    pub minimum_gap: Value<ZeroToOne>,
+    /// The number of intervals to divide the slider's range into using tick
+    /// marks.
+    ///
+    /// This defaults to `0`, which draws no tick marks.
+    pub ticks: Value<u16>,
+    /// When true and [`Self::ticks()`] is non-zero, the slider's values are
+    /// restricted to the tick marks.
+    ///
+    /// This defaults to `false`.
+    pub snap_to_ticks: Value<bool>,
     knob_visible: bool,
     interactive: bool,
     knob_size: UPx,
@@ -92,6 +107,9 @@ where
             knob_visible: true,
             interactive: true,
             step: Value::Constant(ZeroToOne::new(0.05)),
+            minimum_gap: Value::Constant(ZeroToOne::ZERO),
+            ticks: Value::Constant(0),
+            snap_to_ticks: Value::Constant(false),
             knob_size: UPx::ZERO,
             horizontal: true,
             rendered_size: Px::ZERO,
@@ -129,6 +147,55 @@ where
         self
     }
 
+    /// Sets the smallest distance allowed between the start and end of a
+    /// ranged slider, as a percentage of the slider's range, and returns self.
+    ///
+    /// This defaults to `0.0`, which allows the start and end to be equal.
+    #[must_use]
+    pub fn minimum_gap(mut self, percent: impl IntoValue<ZeroToOne>) -> Self {
+        self.minimum_gap = percent.into_value();
+        self
+    }
+
+    /// Divides this slider's range into `intervals` using evenly spaced tick
+    /// marks and returns self.
+    ///
+    /// Tick marks are drawn at the minimum and maximum values as well as
+    /// between each interval.
+    #[must_use]
+    pub fn ticks(mut self, intervals: impl IntoValue<u16>) -> Self {
+        self.ticks = intervals.into_value();
+        self
+    }
+
+    /// Sets whether this slider's values are restricted to its
+    /// [tick marks](Self::ticks()) and returns self.
+    ///
+    /// When snapping, keyboard and mouse wheel input move the slider by one
+    /// tick at a time.
+    #[must_use]
+    pub fn snap_to_ticks(mut self, snap: impl IntoValue<bool>) -> Self {
+        self.snap_to_ticks = snap.into_value();
+        self
+    }
+
+    /// Returns the number of tick intervals values should snap to, if
+    /// snapping is enabled.
+    fn snapping_intervals(&self) -> Option<u16> {
+        let ticks = self.ticks.get();
+        (ticks > 0 && self.snap_to_ticks.get()).then_some(ticks)
+    }
+
+    fn snap(&self, percent: f32) -> f32 {
+        match self.snapping_intervals() {
+            Some(intervals) => {
+                let intervals = f32::from(intervals);
+                (percent * intervals).round() / intervals
+            }
+            None => percent,
+        }
+    }
+
     /// Updates this slider to not show knobs and returns self.
     ///
     /// This also prevents the slider from being focused.
@@ -215,6 +282,20 @@ where
             );
         }
 
+        // Draw the tick marks
+        if spec.ticks > 0 {
+            let tick_center = start_inset + half_track;
+            for tick in 0..=spec.ticks {
+                let offset = track_length * (f32::from(tick) / f32::from(spec.ticks));
+                context.gfx.draw_shape(
+                    Shape::filled_circle(spec.tick_size / 2, spec.tick_color, Origin::Center)
+                        .translate_by(self.flip_pt_if_vertical(
+                            Point::new(offset + spec.half_knob, tick_center) + inset,
+                        )),
+                );
+            }
+        }
+
         // Draw the knob
         if spec.knob_size > 0 {
             let focus = context.focused(true).then_some(self.focused_knob).flatten();
@@ -292,7 +373,7 @@ where
         };
         let track_width = self.rendered_size - knob_size;
         let position = position.clamp(Px::ZERO, track_width);
-        let percent = position.into_float() / track_width.into_float();
+        let percent = self.snap(position.into_float() / track_width.into_float());
 
         let min = self.minimum.get();
         let max = self.maximum.get();
@@ -319,16 +400,23 @@ where
                     Knob::End
                 }
             };
+            let gap = *self.minimum_gap.get();
             match knob {
                 Knob::Start => {
-                    if &value <= end {
+                    let limit = *end.percent_between(&min, &max) - gap;
+                    if gap > 0. && percent > limit {
+                        start = min.lerp(&max, limit.max(0.));
+                    } else if &value <= end {
                         start = value;
                     } else {
                         start = end.clone();
                     }
                 }
                 Knob::End => {
-                    if value >= start {
+                    let limit = *start.percent_between(&min, &max) + gap;
+                    if gap > 0. && percent < limit {
+                        opt_end = Some(min.lerp(&max, limit.min(1.)));
+                    } else if value >= start {
                         opt_end = Some(value);
                     } else {
                         opt_end = Some(start.clone());
@@ -357,13 +445,18 @@ where
         };
         let min = self.minimum.get();
         let max = self.maximum.get();
-        let step = self.step.get();
-        let mut current_percent = current.percent_between(&min, &max);
+        let (step, mut current_percent) = match self.snapping_intervals() {
+            Some(intervals) => (
+                1. / f32::from(intervals),
+                ZeroToOne::new(self.snap(*current.percent_between(&min, &max))),
+            ),
+            None => (*self.step.get(), current.percent_between(&min, &max)),
+        };
         let new_value = loop {
             let next = if forwards {
-                *current_percent + *step * factor
+                *current_percent + step * factor
             } else {
-                *current_percent - *step * factor
+                *current_percent - step * factor
             };
             if next < 0. {
                 break min.clone();
@@ -377,9 +470,17 @@ where
             }
         };
         // Check that the new value didn't go past the other marker, or min/max.
+        let gap = *self.minimum_gap.get();
+        let new_percent = *new_value.percent_between(&min, &max);
         let valid_relative_to_other = match (&other, focus) {
-            (Some(end), Knob::Start) => new_value < *end,
-            (Some(start), Knob::End) => new_value > *start,
+            (Some(end), Knob::Start) => {
+                new_value < *end
+                    && new_percent <= *end.percent_between(&min, &max) - gap + GAP_TOLERANCE
+            }
+            (Some(start), Knob::End) => {
+                new_value > *start
+                    && new_percent >= *start.percent_between(&min, &max) + gap - GAP_TOLERANCE
+            }
             (None, _) => true,
         };
         if valid_relative_to_other && new_value >= min && new_value <= max {
@@ -439,6 +540,9 @@ where
         }
 
         let half_knob = knob_size / 2;
+        let ticks = self.ticks.get_tracking_redraw(context);
+        let tick_color = context.get(&TickColor);
+        let tick_size = context.get(&TickSize).into_px(context.gfx.scale());
 
         let (mut start_value, mut end_value) =
             T::into_parts(self.value.get_tracking_redraw(context));
@@ -493,6 +597,9 @@ where
                 knob_color,
                 track_color,
                 inactive_track_color,
+                ticks,
+                tick_color,
+                tick_size,
             },
             context,
         );
@@ -745,8 +852,15 @@ struct TrackSpec {
     knob_color: Color,
     track_color: Color,
     inactive_track_color: Color,
+    ticks: u16,
+    tick_color: Color,
+    tick_size: Px,
 }
 
+/// The amount of rounding error allowed when checking the
+/// [minimum gap](Slider::minimum_gap) of a ranged [`Slider`].
+const GAP_TOLERANCE: f32 = 0.0001;
+
 impl TrackSpec {
     fn if_knobbed<R>(&self, knobbed: impl FnOnce() -> R) -> R
     where
@@ -780,6 +894,10 @@ define_components! {
         InactiveTrackColor(Color, "inactive_track_color", |context| context.get(&OpaqueWidgetColor))
         /// The color of the track that the knob rests.
         DisabledInactiveTrackColor(Color, "disabled_inactive_track_color", |context| context.get(&OpaqueWidgetColor))
+        /// The color of the tick marks drawn on a [`Slider`].
+        TickColor(Color, "tick_color", .surface.outline_variant)
+        /// The diameter of the tick marks drawn on a [`Slider`].
+        TickSize(Dimension, "tick_size", Dimension::Lp(Lp::points(3)))
     }
 }
 
@@ -0,0 +1,219 @@
+//! A compact chart of a series of values.
+
+use std::ops::RangeInclusive;
+
+use figures::units::{Lp, Px, UPx};
+use figures::{FloatConversion, Point, Rect, ScreenScale, Size};
+use intentional::Cast;
+use kludgine::shapes::{PathBuilder, Shape, StrokeOptions};
+use kludgine::Color;
+
+use crate::context::{GraphicsContext, LayoutContext, Trackable};
+use crate::reactive::value::{IntoValue, Source, Value};
+use crate::styles::Dimension;
+use crate::widget::Widget;
+use crate::ConstraintLimit;
+
+/// A small chart that displays the trend of a series of values, intended to be
+/// displayed inline with other content.
+///
+/// When the series contains more values than there are pixels available, the
+/// values are combined so that each pixel displays the minimum and maximum of
+/// the values it covers. This keeps spikes visible regardless of how many
+/// values are displayed.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::Sparkline;
+///
+/// let requests_per_second = Dynamic::new(vec![12., 18., 9., 22., 30.]);
+/// let line = Sparkline::new(requests_per_second.clone());
+/// let bars = Sparkline::new(requests_per_second).bars();
+/// ```
+#[derive(Debug)]
+pub struct Sparkline {
+    values: Value<Vec<f64>>,
+    range: Option<RangeInclusive<f64>>,
+    bars: bool,
+    buckets: Vec<(f64, f64)>,
+}
+
+impl Sparkline {
+    /// Returns a sparkline that draws `values` as a line.
+    #[must_use]
+    pub fn new(values: impl IntoValue<Vec<f64>>) -> Self {
+        Self {
+            values: values.into_value(),
+            range: None,
+            bars: false,
+            buckets: Vec::new(),
+        }
+    }
+
+    /// Draws the values as bars instead of a line and returns self.
+    #[must_use]
+    pub fn bars(mut self) -> Self {
+        self.bars = true;
+        self
+    }
+
+    /// Sets the range of values that spans the height of the sparkline and
+    /// returns self.
+    ///
+    /// By default, the range is the minimum and maximum of the values being
+    /// displayed. For bars, the default range always includes `0`.
+    #[must_use]
+    pub fn range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+impl Widget for Sparkline {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        self.values.redraw_when_changed(context);
+        let size = context.gfx.region().size;
+        let columns = usize::try_from(size.width.get()).unwrap_or_default();
+        self.values
+            .map(|values| downsample(values, columns, &mut self.buckets));
+        if self.buckets.is_empty() {
+            return;
+        }
+
+        let (min, max) = self.range.clone().map_or_else(
+            || {
+                let bounds = self
+                    .buckets
+                    .iter()
+                    .fold((f64::MAX, f64::MIN), |(min, max), bucket| {
+                        (min.min(bucket.0), max.max(bucket.1))
+                    });
+                if self.bars {
+                    (bounds.0.min(0.), bounds.1.max(0.))
+                } else {
+                    bounds
+                }
+            },
+            RangeInclusive::into_inner,
+        );
+        let span = max - min;
+        let height = size.height.into_float();
+        let y = |value: f64| {
+            let fraction = if span > 0. {
+                ((value - min) / span).clamp(0., 1.)
+            } else {
+                0.5
+            };
+            Px::from_float(height - fraction.cast::<f32>() * height)
+        };
+        let column_width = size.width.into_float() / self.buckets.len().cast::<f32>();
+        let x = |index: usize| Px::from_float(index.cast::<f32>() * column_width);
+
+        let color = context.get(&SparklineColor);
+        if self.bars {
+            let baseline = y(0.);
+            for (index, &(low, high)) in self.buckets.iter().enumerate() {
+                // Buckets that cross zero extend in both directions.
+                let top = y(high.max(0.)).min(baseline);
+                let bottom = y(low.min(0.)).max(baseline);
+                let left = x(index);
+                let right = x(index + 1).max(left + 1);
+                context.gfx.draw_shape(&Shape::filled_rect(
+                    Rect::new(
+                        Point::new(left, top),
+                        Size::new(right - left, (bottom - top).max(Px::new(1))),
+                    ),
+                    color,
+                ));
+            }
+        } else {
+            let center = column_width / 2.;
+            let point_x = |index: usize| x(index) + Px::from_float(center);
+            let (first_low, first_high) = self.buckets[0];
+            let mut path = PathBuilder::new(Point::new(point_x(0), y(first_low)));
+            if first_high > first_low {
+                path = path.line_to(Point::new(point_x(0), y(first_high)));
+            }
+            for (index, &(low, high)) in self.buckets.iter().enumerate().skip(1) {
+                path = path.line_to(Point::new(point_x(index), y(low)));
+                if high > low {
+                    path = path.line_to(Point::new(point_x(index), y(high)));
+                }
+            }
+            let width = context.get(&SparklineWidth).into_px(context.gfx.scale());
+            context.gfx.draw_shape(
+                &path
+                    .build()
+                    .stroke(StrokeOptions::px_wide(width).colored(color)),
+            );
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        let scale = context.gfx.scale();
+        Size::new(
+            available_space
+                .width
+                .fill_or_fit(Lp::inches(1).into_upx(scale)),
+            available_space
+                .height
+                .fill_or_fit(context.get(&SparklineHeight).into_upx(scale)),
+        )
+    }
+}
+
+/// Fills `buckets` with the minimum and maximum of each of up to `columns`
+/// equally sized groups of `values`.
+///
+/// When there are fewer values than columns, each value is placed in its own
+/// bucket.
+fn downsample(values: &[f64], columns: usize, buckets: &mut Vec<(f64, f64)>) {
+    buckets.clear();
+    if values.len() <= columns {
+        buckets.extend(values.iter().map(|value| (*value, *value)));
+        return;
+    } else if columns == 0 {
+        return;
+    }
+
+    let per_column = values.len().cast::<f64>() / columns.cast::<f64>();
+    for column in 0..columns {
+        let start = (column.cast::<f64>() * per_column).cast::<usize>();
+        let end = ((column + 1).cast::<f64>() * per_column)
+            .cast::<usize>()
+            .clamp(start + 1, values.len());
+        buckets.push(
+            values[start..end]
+                .iter()
+                .fold((f64::MAX, f64::MIN), |(min, max), value| {
+                    (min.min(*value), max.max(*value))
+                }),
+        );
+    }
+}
+
+define_components! {
+    Sparkline {
+        /// The color of a [`Sparkline`].
+        SparklineColor(Color, "color", .primary.color)
+        /// The width of the line drawn by a [`Sparkline`].
+        SparklineWidth(Dimension, "line_width", Dimension::Lp(Lp::points(1)))
+        /// The height a [`Sparkline`] requests when it is not filling its
+        /// available space.
+        SparklineHeight(Dimension, "height", Dimension::Lp(Lp::points(18)))
+    }
+}
+
+#[test]
+fn downsampling() {
+    let mut buckets = Vec::new();
+    downsample(&[1., 2., 3.], 10, &mut buckets);
+    assert_eq!(buckets, [(1., 1.), (2., 2.), (3., 3.)]);
+
+    downsample(&[1., 5., -2., 3., 0., 0.], 3, &mut buckets);
+    assert_eq!(buckets, [(1., 5.), (-2., 3.), (0., 0.)]);
+}
@@ -0,0 +1,371 @@
+//! A numeric input with buttons to step its value.
+
+use std::fmt::{self, Debug, Display};
+use std::str::FromStr;
+
+use intentional::Cast;
+use kludgine::app::winit::event::MouseScrollDelta;
+use kludgine::app::winit::keyboard::{Key, NamedKey};
+
+use crate::reactive::value::{
+    Destination, Dynamic, IntoDynamic, IntoValue, MapEach, Source, Validation, Validations, Value,
+};
+use crate::widget::{MakeWidget, MakeWidgetWithTag, WidgetInstance, WidgetTag, HANDLED, IGNORED};
+use crate::widgets::input::InputValue;
+use crate::widgets::Custom;
+
+/// The number of pixels a mouse wheel must scroll to step a [`SpinBox`] once.
+const PIXELS_PER_STEP: f32 = 16.;
+
+/// The number of steps taken when Page Up or Page Down is pressed.
+const PAGE_STEPS: i16 = 10;
+
+/// A text input for a number with buttons to increment and decrement it.
+///
+/// The value can also be stepped using the up and down arrow keys, the page up
+/// and page down keys, and the mouse wheel. Text that cannot be parsed or that
+/// is outside of the allowed range is reported as a validation error and is not
+/// stored in the value.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::SpinBox;
+///
+/// let quantity = Dynamic::new(1_u32);
+/// let spin_box = SpinBox::new(quantity).minimum(1).maximum(99);
+/// ```
+pub struct SpinBox<T> {
+    value: Dynamic<T>,
+    minimum: Value<T>,
+    maximum: Value<T>,
+    step: Value<T>,
+    validations: Option<Validations>,
+    hint: Value<String>,
+}
+
+impl<T> SpinBox<T>
+where
+    T: SpinValue,
+{
+    /// Returns a new spin box that edits `value`.
+    ///
+    /// By default, the value can be set to any value the type can represent,
+    /// and each step changes the value by [`SpinValue::STEP`].
+    #[must_use]
+    pub fn new(value: impl IntoDynamic<T>) -> Self {
+        Self {
+            value: value.into_dynamic(),
+            minimum: Value::Constant(T::MIN),
+            maximum: Value::Constant(T::MAX),
+            step: Value::Constant(T::STEP),
+            validations: None,
+            hint: Value::default(),
+        }
+    }
+
+    /// Sets the smallest allowed value and returns self.
+    #[must_use]
+    pub fn minimum(mut self, minimum: impl IntoValue<T>) -> Self {
+        self.minimum = minimum.into_value();
+        self
+    }
+
+    /// Sets the largest allowed value and returns self.
+    #[must_use]
+    pub fn maximum(mut self, maximum: impl IntoValue<T>) -> Self {
+        self.maximum = maximum.into_value();
+        self
+    }
+
+    /// Sets the amount the value changes by for each step and returns self.
+    #[must_use]
+    pub fn step(mut self, step: impl IntoValue<T>) -> Self {
+        self.step = step.into_value();
+        self
+    }
+
+    /// Links this spin box's validation with `validations` and returns self.
+    ///
+    /// Parsing and range errors will be included when checking `validations`.
+    #[must_use]
+    pub fn validations(mut self, validations: &Validations) -> Self {
+        self.validations = Some(validations.clone());
+        self
+    }
+
+    /// Sets the hint message to be displayed when there is no validation
+    /// error and returns self.
+    #[must_use]
+    pub fn hint(mut self, hint: impl IntoValue<String>) -> Self {
+        self.hint = hint.into_value();
+        self
+    }
+}
+
+impl<T> Debug for SpinBox<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpinBox")
+            .field("value", &self.value)
+            .field("minimum", &self.minimum)
+            .field("maximum", &self.maximum)
+            .field("step", &self.step)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> MakeWidgetWithTag for SpinBox<T>
+where
+    T: SpinValue,
+{
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let minimum = self.minimum.into_dynamic();
+        let maximum = self.maximum.into_dynamic();
+        let text = Dynamic::new(self.value.map_ref(ToString::to_string));
+
+        let parsed = (&text, &minimum, &maximum)
+            .map_each(|(text, minimum, maximum)| parse(text, minimum, maximum));
+        parsed
+            .for_each_cloned({
+                let value = self.value.clone();
+                move |parsed| {
+                    if let Ok(parsed) = parsed {
+                        value.set(parsed);
+                    }
+                }
+            })
+            .persist();
+        self.value
+            .for_each_cloned({
+                let text = text.clone();
+                move |value| {
+                    let matches =
+                        text.map_ref(|text| text.trim().parse::<T>().ok() == Some(value.clone()));
+                    if !matches {
+                        text.set(value.to_string());
+                    }
+                }
+            })
+            .persist();
+
+        let validation: Dynamic<Validation> = match &self.validations {
+            Some(validations) => validations.validate_result(parsed),
+            None => parsed.into_dynamic(),
+        };
+
+        let stepper = Stepper {
+            value: self.value.clone(),
+            step: self.step,
+            minimum: minimum.clone(),
+            maximum: maximum.clone(),
+        };
+
+        let input = text.into_input().on_key({
+            let stepper = stepper.clone();
+            move |input| {
+                let steps = match input.logical_key {
+                    Key::Named(NamedKey::ArrowUp) => 1,
+                    Key::Named(NamedKey::ArrowDown) => -1,
+                    Key::Named(NamedKey::PageUp) => PAGE_STEPS,
+                    Key::Named(NamedKey::PageDown) => -PAGE_STEPS,
+                    _ => return IGNORED,
+                };
+                if input.state.is_pressed() {
+                    stepper.step(steps);
+                }
+                HANDLED
+            }
+        });
+
+        let can_decrement = (&self.value, &minimum).map_each(|(value, minimum)| value > minimum);
+        let can_increment = (&self.value, &maximum).map_each(|(value, maximum)| value < maximum);
+        let decrement = "-"
+            .into_button()
+            .on_click({
+                let stepper = stepper.clone();
+                move |_| stepper.step(-1)
+            })
+            .with_enabled(can_decrement);
+        let increment = "+"
+            .into_button()
+            .on_click({
+                let stepper = stepper.clone();
+                move |_| stepper.step(1)
+            })
+            .with_enabled(can_increment);
+
+        let mut scrolled = 0.;
+        Custom::new(
+            input
+                .expand()
+                .and(decrement)
+                .and(increment)
+                .into_columns()
+                .validation(validation)
+                .hint(self.hint),
+        )
+        .mouse_wheel(move |_device, delta, _phase, context| {
+            if !context.enabled() {
+                return IGNORED;
+            }
+            scrolled += match delta {
+                MouseScrollDelta::LineDelta(_, y) => y,
+                MouseScrollDelta::PixelDelta(pt) => pt.y.cast::<f32>() / PIXELS_PER_STEP,
+            };
+            let steps = scrolled.trunc();
+            scrolled -= steps;
+            if steps.abs() >= 1. {
+                stepper.step(steps.cast());
+            }
+            HANDLED
+        })
+        .make_with_tag(tag)
+    }
+}
+
+/// Changes the value of a [`SpinBox`] in increments of its step.
+#[derive(Clone)]
+struct Stepper<T> {
+    value: Dynamic<T>,
+    step: Value<T>,
+    minimum: Dynamic<T>,
+    maximum: Dynamic<T>,
+}
+
+impl<T> Stepper<T>
+where
+    T: SpinValue,
+{
+    fn step(&self, steps: i16) {
+        let minimum = self.minimum.get();
+        let maximum = self.maximum.get();
+        let step = self.step.get();
+        self.value.map_mut(|mut value| {
+            let stepped = value.stepped(&step, steps);
+            let stepped = if stepped < minimum {
+                minimum
+            } else if stepped > maximum {
+                maximum
+            } else {
+                stepped
+            };
+            if *value != stepped {
+                *value = stepped;
+            }
+        });
+    }
+}
+
+fn parse<T>(text: &str, minimum: &T, maximum: &T) -> Result<T, SpinBoxError<T>>
+where
+    T: SpinValue,
+{
+    let value = text
+        .trim()
+        .parse::<T>()
+        .map_err(|_| SpinBoxError::Invalid)?;
+    if value < *minimum {
+        Err(SpinBoxError::BelowMinimum(minimum.clone()))
+    } else if value > *maximum {
+        Err(SpinBoxError::AboveMaximum(maximum.clone()))
+    } else {
+        Ok(value)
+    }
+}
+
+/// An error parsing the text entered into a [`SpinBox`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SpinBoxError<T> {
+    /// The text is not a number.
+    Invalid,
+    /// The number is less than the minimum allowed value.
+    BelowMinimum(T),
+    /// The number is greater than the maximum allowed value.
+    AboveMaximum(T),
+}
+
+impl<T> Display for SpinBoxError<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpinBoxError::Invalid => f.write_str("not a valid number"),
+            SpinBoxError::BelowMinimum(minimum) => write!(f, "must be at least {minimum}"),
+            SpinBoxError::AboveMaximum(maximum) => write!(f, "must be at most {maximum}"),
+        }
+    }
+}
+
+/// A number that can be edited by a [`SpinBox`].
+pub trait SpinValue: Clone + Debug + Display + FromStr + PartialOrd + Send + 'static {
+    /// The smallest value of this type.
+    const MIN: Self;
+    /// The largest value of this type.
+    const MAX: Self;
+    /// The default amount to change the value by for each step.
+    const STEP: Self;
+
+    /// Returns the result of adding `step` to this value `steps` times,
+    /// saturating at this type's bounds.
+    #[must_use]
+    fn stepped(&self, step: &Self, steps: i16) -> Self;
+}
+
+macro_rules! impl_integer_spin_value {
+    ($($type:ident),+) => {
+        $(
+            impl SpinValue for $type {
+                const MAX: Self = $type::MAX;
+                const MIN: Self = $type::MIN;
+                const STEP: Self = 1;
+
+                #[allow(clippy::unnecessary_fallible_conversions)]
+                fn stepped(&self, step: &Self, steps: i16) -> Self {
+                    let amount = $type::try_from(steps.unsigned_abs())
+                        .map_or($type::MAX, |steps| step.saturating_mul(steps));
+                    if steps.is_negative() {
+                        self.saturating_sub(amount)
+                    } else {
+                        self.saturating_add(amount)
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_integer_spin_value!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_float_spin_value {
+    ($($type:ident),+) => {
+        $(
+            impl SpinValue for $type {
+                const MAX: Self = $type::MAX;
+                const MIN: Self = $type::MIN;
+                const STEP: Self = 1.;
+
+                fn stepped(&self, step: &Self, steps: i16) -> Self {
+                    (self + step * $type::from(steps)).clamp($type::MIN, $type::MAX)
+                }
+            }
+        )+
+    };
+}
+
+impl_float_spin_value!(f32, f64);
+
+#[test]
+fn parsing_and_stepping() {
+    assert_eq!(parse("  5 ", &0_u8, &10), Ok(5));
+    assert_eq!(parse("five", &0_u8, &10), Err(SpinBoxError::Invalid));
+    assert_eq!(parse("11", &0_u8, &10), Err(SpinBoxError::AboveMaximum(10)));
+    assert_eq!(parse("-1", &0_i8, &10), Err(SpinBoxError::BelowMinimum(0)));
+
+    assert_eq!(250_u8.stepped(&3, 2), 255);
+    assert_eq!(2_u8.stepped(&3, -1), 0);
+    assert_eq!(0_i8.stepped(&1, 300), i8::MAX);
+    assert!((0.5_f32.stepped(&0.25, -2)).abs() < f32::EPSILON);
+}
@@ -0,0 +1,318 @@
+//! A widget that divides its area between two children.
+
+use std::time::Instant;
+
+use figures::units::{Lp, Px, UPx};
+use figures::{FloatConversion, Fraction, IntoSigned, Point, Rect, Round, ScreenScale, Size, Zero};
+use kludgine::app::winit::event::MouseButton;
+use kludgine::app::winit::window::CursorIcon;
+use kludgine::shapes::Shape;
+use kludgine::Color;
+
+use crate::context::{EventContext, GraphicsContext, LayoutContext};
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, Source};
+use crate::styles::components::HighlightColor;
+use crate::styles::{Dimension, DimensionRange};
+use crate::widget::{EventHandling, MakeWidget, Widget, WidgetRef, HANDLED, IGNORED};
+use crate::widgets::grid::Orientation;
+use crate::window::DeviceId;
+use crate::ConstraintLimit;
+
+/// A widget that divides its area between two children, separated by a
+/// divider that can be dragged to resize them.
+///
+/// The fraction of the area given to the first child is stored in a
+/// [`Dynamic<f32>`](Dynamic), which allows the divider's position to be
+/// observed and persisted. Double-clicking the divider restores the
+/// [default ratio](Self::default_ratio).
+///
+/// ```rust
+/// use cushy::figures::units::Lp;
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::Split;
+///
+/// let ratio = Dynamic::new(0.25);
+/// let split = Split::columns("Sidebar", "Contents")
+///     .ratio(ratio.clone())
+///     .first_size(Lp::inches(1)..Lp::inches(3));
+/// ```
+#[derive(Debug)]
+pub struct Split {
+    orientation: Orientation,
+    first: WidgetRef,
+    second: WidgetRef,
+    ratio: Dynamic<f32>,
+    default_ratio: Option<f32>,
+    first_size: DimensionRange,
+    second_size: DimensionRange,
+    divider: Rect<Px>,
+    available: UPx,
+    hovering: bool,
+    dragging: bool,
+    last_press: Option<Instant>,
+}
+
+impl Split {
+    /// Returns a widget that divides its area between `first` and `second`
+    /// in `orientation`.
+    ///
+    /// [`Orientation::Column`] places `first` to the left of `second`, while
+    /// [`Orientation::Row`] places `first` above `second`.
+    #[must_use]
+    pub fn new(orientation: Orientation, first: impl MakeWidget, second: impl MakeWidget) -> Self {
+        Self {
+            orientation,
+            first: WidgetRef::new(first),
+            second: WidgetRef::new(second),
+            ratio: Dynamic::new(0.5),
+            default_ratio: None,
+            first_size: DimensionRange::default(),
+            second_size: DimensionRange::default(),
+            divider: Rect::default(),
+            available: UPx::ZERO,
+            hovering: false,
+            dragging: false,
+            last_press: None,
+        }
+    }
+
+    /// Returns a widget that places `first` to the left of `second`.
+    #[must_use]
+    pub fn columns(first: impl MakeWidget, second: impl MakeWidget) -> Self {
+        Self::new(Orientation::Column, first, second)
+    }
+
+    /// Returns a widget that places `first` above `second`.
+    #[must_use]
+    pub fn rows(first: impl MakeWidget, second: impl MakeWidget) -> Self {
+        Self::new(Orientation::Row, first, second)
+    }
+
+    /// Uses `ratio` as the fraction of the area given to the first child and
+    /// returns self.
+    ///
+    /// When the user drags the divider, `ratio` is updated. Unless
+    /// [`Self::default_ratio`] is called, the ratio restored when the divider
+    /// is double-clicked is the initial value of `ratio`.
+    #[must_use]
+    pub fn ratio(mut self, ratio: impl IntoDynamic<f32>) -> Self {
+        self.ratio = ratio.into_dynamic();
+        self
+    }
+
+    /// Sets the ratio restored when the divider is double-clicked and returns
+    /// self.
+    #[must_use]
+    pub fn default_ratio(mut self, ratio: f32) -> Self {
+        self.default_ratio = Some(ratio);
+        self
+    }
+
+    /// Restricts the size of the first child to `range` and returns self.
+    #[must_use]
+    pub fn first_size(mut self, range: impl Into<DimensionRange>) -> Self {
+        self.first_size = range.into();
+        self
+    }
+
+    /// Restricts the size of the second child to `range` and returns self.
+    #[must_use]
+    pub fn second_size(mut self, range: impl Into<DimensionRange>) -> Self {
+        self.second_size = range.into();
+        self
+    }
+
+    /// Returns the size of the first child when it is given `ratio` of the
+    /// available space, honoring the size ranges of both children.
+    fn first_measurement(&self, ratio: f32, scale: Fraction) -> UPx {
+        let ratio = if ratio.is_finite() {
+            ratio.clamp(0., 1.)
+        } else {
+            0.5
+        };
+        let first = UPx::from_float(self.available.into_float() * ratio);
+        let first = self.first_size.clamp(first, scale).min(self.available);
+        let second = self
+            .second_size
+            .clamp(self.available - first, scale)
+            .min(self.available);
+        self.available - second
+    }
+}
+
+impl Widget for Split {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let first = self.first.mounted(context);
+        let second = self.second.mounted(context);
+        context.redraw_children([&first, &second]);
+
+        let color = if self.hovering || self.dragging {
+            context.get(&HighlightColor)
+        } else {
+            context.get(&DividerColor)
+        };
+        context
+            .gfx
+            .draw_shape(&Shape::filled_rect(self.divider, color));
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        let ratio = self.ratio.get_tracking_invalidate(context);
+        self.default_ratio.get_or_insert(ratio);
+
+        let scale = context.gfx.scale();
+        let thickness = context.get(&DividerThickness).into_upx(scale).ceil();
+        let (measured, other_limit) = self.orientation.split_size(available_space);
+        let measured = measured.max();
+        self.available = measured.max(thickness) - thickness;
+        let first_measured = self.first_measurement(ratio, scale);
+        let second_measured = self.available - first_measured;
+
+        let first = self.first.mounted(context);
+        let second = self.second.mounted(context);
+        let first_size = context.for_other(&first).layout(
+            self.orientation
+                .make_size(ConstraintLimit::Fill(first_measured), other_limit),
+        );
+        let second_size = context.for_other(&second).layout(
+            self.orientation
+                .make_size(ConstraintLimit::Fill(second_measured), other_limit),
+        );
+        let other = match other_limit {
+            ConstraintLimit::Fill(other) => other,
+            ConstraintLimit::SizeToFit(_) => self
+                .orientation
+                .split_size(first_size)
+                .1
+                .max(self.orientation.split_size(second_size).1),
+        };
+
+        context.set_child_layout(
+            &first,
+            Rect::new(
+                Point::ZERO,
+                self.orientation.make_size(first_measured, other),
+            )
+            .into_signed(),
+        );
+        self.divider = Rect::new(
+            self.orientation.make_point(first_measured, UPx::ZERO),
+            self.orientation.make_size(thickness, other),
+        )
+        .into_signed();
+        context.set_child_layout(
+            &second,
+            Rect::new(
+                self.orientation
+                    .make_point(first_measured + thickness, UPx::ZERO),
+                self.orientation.make_size(second_measured, other),
+            )
+            .into_signed(),
+        );
+
+        self.orientation.make_size(measured, other)
+    }
+
+    fn hit_test(&mut self, location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        self.divider.contains(location)
+    }
+
+    fn hover(
+        &mut self,
+        _location: Point<Px>,
+        context: &mut EventContext<'_>,
+    ) -> Option<CursorIcon> {
+        if !self.hovering {
+            self.hovering = true;
+            context.set_needs_redraw();
+        }
+        Some(match self.orientation {
+            Orientation::Column => CursorIcon::ColResize,
+            Orientation::Row => CursorIcon::RowResize,
+        })
+    }
+
+    fn unhover(&mut self, context: &mut EventContext<'_>) {
+        self.hovering = false;
+        context.set_needs_redraw();
+    }
+
+    fn mouse_down(
+        &mut self,
+        _location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button != MouseButton::Left {
+            return IGNORED;
+        }
+
+        let now = Instant::now();
+        let threshold = context.cushy().multi_click_threshold();
+        if self
+            .last_press
+            .take()
+            .is_some_and(|last_press| now.saturating_duration_since(last_press) < threshold)
+        {
+            if let Some(default_ratio) = self.default_ratio {
+                self.ratio.set(default_ratio);
+            }
+        } else {
+            self.last_press = Some(now);
+        }
+
+        self.dragging = true;
+        context.set_needs_redraw();
+        HANDLED
+    }
+
+    fn mouse_drag(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) {
+        if self.available == UPx::ZERO {
+            return;
+        }
+        let position = match self.orientation {
+            Orientation::Column => location.x - self.divider.size.width / 2,
+            Orientation::Row => location.y - self.divider.size.height / 2,
+        };
+        let ratio = position.into_float() / self.available.into_float();
+        let first = self.first_measurement(ratio, context.kludgine.scale());
+        self.ratio
+            .set(first.into_float() / self.available.into_float());
+    }
+
+    fn mouse_up(
+        &mut self,
+        _location: Option<Point<Px>>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) {
+        self.dragging = false;
+        context.set_needs_redraw();
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        self.first.unmount_in(context);
+        self.second.unmount_in(context);
+    }
+}
+
+define_components! {
+    Split {
+        /// The color of the divider between the children of a [`Split`].
+        DividerColor(Color, "divider_color", .surface.outline_variant)
+        /// The thickness of the divider between the children of a [`Split`].
+        DividerThickness(Dimension, "divider_thickness", Dimension::Lp(Lp::points(4)))
+    }
+}
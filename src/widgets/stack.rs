@@ -138,9 +138,7 @@ impl Stack {
 
 impl Widget for Stack {
     fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
-        for child in &self.synced_children {
-            context.for_other(child).redraw();
-        }
+        context.redraw_children(&self.synced_children);
     }
 
     fn mounted(&mut self, context: &mut EventContext<'_>) {
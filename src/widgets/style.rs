@@ -1,5 +1,8 @@
+use figures::Size;
+
 use super::validated::HintTextColor;
-use crate::context::EventContext;
+use super::Scroll;
+use crate::context::{EventContext, LayoutContext};
 use crate::reactive::value::{Destination, IntoValue, Mutable, Value};
 use crate::styles::components::{
     FontFamily, FontStyle, FontWeight, Heading1FontFamily, Heading1Style, Heading1Weight,
@@ -7,26 +10,38 @@ use crate::styles::components::{
     Heading3Weight, Heading4FontFamily, Heading4Style, Heading4Weight, Heading5FontFamily,
     Heading5Style, Heading5Weight, Heading6FontFamily, Heading6Style, Heading6Weight, LineHeight,
     LineHeight1, LineHeight2, LineHeight3, LineHeight4, LineHeight5, LineHeight6, LineHeight7,
-    LineHeight8, TextColor, TextSize, TextSize1, TextSize2, TextSize3, TextSize4, TextSize5,
-    TextSize6, TextSize7, TextSize8,
+    LineHeight8, Overflow, TextColor, TextSize, TextSize1, TextSize2, TextSize3, TextSize4,
+    TextSize5, TextSize6, TextSize7, TextSize8,
+};
+use crate::styles::{
+    ComponentDefinition, IntoComponentValue, IntoDynamicComponentValue, OverflowPolicy, Styles,
 };
-use crate::styles::{ComponentDefinition, IntoComponentValue, IntoDynamicComponentValue, Styles};
-use crate::widget::{MakeWidget, WidgetRef, WrapperWidget};
+use crate::widget::{MakeWidget, WidgetInstance, WidgetRef, WrapperWidget};
+use crate::ConstraintLimit;
 
 /// A widget that applies a set of [`Styles`] to all contained widgets.
+///
+/// When the [`Overflow`] component of this widget is
+/// [`OverflowPolicy::Scroll`], the contained widgets are placed in a
+/// [`Scroll`].
 #[derive(Debug)]
 pub struct Style {
     styles: Value<Styles>,
     child: WidgetRef,
+    contents: WidgetInstance,
+    scrolling: bool,
 }
 
 impl Style {
     /// Returns a new widget that applies `styles` to `child` and any children
     /// it may have.
     pub fn new(styles: impl IntoValue<Styles>, child: impl MakeWidget) -> Self {
+        let contents = child.make_widget();
         Self {
             styles: styles.into_value(),
-            child: WidgetRef::new(child),
+            child: WidgetRef::new(contents.clone()),
+            contents,
+            scrolling: false,
         }
     }
 
@@ -229,4 +244,22 @@ impl WrapperWidget for Style {
     fn mounted(&mut self, context: &mut EventContext<'_>) {
         context.attach_styles(self.styles.clone());
     }
+
+    fn adjust_child_constraints(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<ConstraintLimit> {
+        let scrolling = context.get(&Overflow) == OverflowPolicy::Scroll;
+        if scrolling != self.scrolling {
+            self.scrolling = scrolling;
+            self.child.unmount_in(context);
+            self.child = if scrolling {
+                WidgetRef::new(Scroll::new(self.contents.clone()))
+            } else {
+                WidgetRef::new(self.contents.clone())
+            };
+        }
+        available_space
+    }
 }
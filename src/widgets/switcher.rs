@@ -1,13 +1,16 @@
 use std::fmt::Debug;
 use std::mem;
 
-use ahash::HashMap;
+use ahash::{AHashMap, HashMap};
+use figures::units::Px;
 use figures::Size;
 use kludgine::KludgineId;
 
-use crate::context::{AsEventContext, LayoutContext};
+use crate::context::{AsEventContext, EventContext, LayoutContext};
 use crate::reactive::value::{Dynamic, DynamicReader, IntoDynamic, IntoReader, Source};
-use crate::widget::{MountedWidget, WidgetInstance, WidgetRef, WrapperWidget};
+use crate::styles::components::LayoutOrder;
+use crate::tree::PreservedState;
+use crate::widget::{MountedWidget, WidgetInstance, WidgetRef, WrappedLayout, WrapperWidget};
 use crate::window::WindowLocal;
 use crate::ConstraintLimit;
 
@@ -17,6 +20,7 @@ pub struct Switcher {
     source: DynamicReader<WidgetInstance>,
     child: WidgetRef,
     pending_unmount: HashMap<KludgineId, MountedWidget>,
+    preserve_state: bool,
 }
 
 impl Switcher {
@@ -48,8 +52,70 @@ impl Switcher {
             source,
             child,
             pending_unmount: HashMap::default(),
+            preserve_state: false,
         }
     }
+
+    /// Transfers state from the current contents to the new contents each
+    /// time the contents are replaced, and returns self.
+    ///
+    /// Each widget in the current contents that has a
+    /// [state key](crate::widget::MakeWidget::with_state_key) transfers its
+    /// state to the widget in the new contents with the same key. This allows
+    /// refreshing the contents, such as after reloading the data being
+    /// displayed, without resetting scroll positions, keyboard focus, or text
+    /// selections.
+    ///
+    /// ```rust
+    /// use cushy::reactive::value::Dynamic;
+    /// use cushy::widget::{MakeWidget, WidgetList};
+    /// use cushy::widgets::Switcher;
+    ///
+    /// let rows = Dynamic::new(vec![String::from("a"), String::from("b")]);
+    /// let page = Switcher::mapping(rows.clone(), |rows, _| {
+    ///     rows.iter()
+    ///         .map(String::as_str)
+    ///         .collect::<WidgetList>()
+    ///         .into_rows()
+    ///         .vertical_scroll()
+    ///         .with_state_key("rows")
+    /// })
+    /// .preserving_state();
+    /// ```
+    #[must_use]
+    pub fn preserving_state(mut self) -> Self {
+        self.preserve_state = true;
+        self
+    }
+}
+
+/// Collects the preserved state of `widget` and all of its children.
+fn collect_preserved_states(
+    widget: &MountedWidget,
+    context: &mut EventContext<'_>,
+    states: &mut AHashMap<String, PreservedState>,
+) {
+    if let Some(key) = widget.instance().state_key() {
+        let focused = context.tree.focused_widget() == Some(widget.node_id);
+        let state = widget
+            .lock()
+            .as_widget()
+            .preserve_state(&mut context.for_other(widget));
+        if states
+            .insert(key.to_string(), PreservedState { state, focused })
+            .is_some()
+        {
+            tracing::warn!(
+                "multiple widgets share the state key {key:?}; only the state of the last \
+                 one will be preserved"
+            );
+        }
+    }
+
+    let order = context.get(&LayoutOrder);
+    for child in widget.visually_ordered_children(order) {
+        collect_preserved_states(&child, context, states);
+    }
 }
 
 impl WrapperWidget for Switcher {
@@ -69,6 +135,20 @@ impl WrapperWidget for Switcher {
 
         let current_source = self.source.get_tracking_invalidate(context);
         if &current_source != self.child.widget() {
+            if self.preserve_state {
+                if let Some(current) = self.child.as_mounted(context).cloned() {
+                    let mut states = AHashMap::new();
+                    collect_preserved_states(
+                        &current,
+                        &mut context.as_event_context(),
+                        &mut states,
+                    );
+                    context
+                        .tree
+                        .preserve_states(context.widget().node_id, states);
+                }
+            }
+
             // immediately unmount in the current context.
             self.child.unmount_in(context);
             let old_mounts = <WindowLocal<MountedWidget>>::from(mem::replace(
@@ -91,4 +171,123 @@ impl WrapperWidget for Switcher {
 
         available_space
     }
+
+    fn position_child(
+        &mut self,
+        size: Size<Px>,
+        _available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> WrappedLayout {
+        if self.preserve_state {
+            // Any state that was not claimed by the new contents is no longer
+            // needed.
+            context
+                .tree
+                .discard_preserved_states(context.widget().node_id);
+        }
+        WrappedLayout::from(size)
+    }
+}
+
+#[test]
+fn preserves_keyed_scroll_offsets() {
+    use std::sync::Arc;
+
+    use figures::units::UPx;
+    use figures::Point;
+    use parking_lot::Mutex;
+
+    use crate::reactive::value::Destination;
+    use crate::widget::MakeWidget;
+    use crate::widgets::{Scroll, Space};
+
+    let generation = Dynamic::new(0_usize);
+    let offsets = Arc::new(Mutex::new(Vec::new()));
+    let mut recorder = Switcher::mapping(generation.clone(), {
+        let offsets = offsets.clone();
+        move |_, _| {
+            let scroll = Scroll::vertical(Space::clear().height(Px::new(400)));
+            offsets.lock().push(scroll.scroll.clone());
+            scroll.with_state_key("rows")
+        }
+    })
+    .preserving_state()
+    .build_recorder()
+    .size(Size::new(UPx::new(100), UPx::new(100)))
+    .finish()
+    .expect("error creating recorder");
+
+    let first = offsets.lock()[0].clone();
+    first.set(Point::new(UPx::new(0), UPx::new(50)));
+    recorder.refresh().expect("error rendering");
+
+    generation.set(1);
+    recorder.refresh().expect("error rendering");
+    recorder.refresh().expect("error rendering");
+    let replacement = offsets.lock().last().cloned().expect("replaced");
+    assert_eq!(offsets.lock().len(), 2);
+    assert_eq!(replacement.get(), Point::new(UPx::new(0), UPx::new(50)));
+}
+
+#[test]
+fn preserves_input_selection_and_focus() {
+    use figures::units::UPx;
+    use figures::Point;
+    use kludgine::app::winit::event::{ElementState, Ime, MouseButton};
+
+    use crate::reactive::value::Destination;
+    use crate::widget::MakeWidget;
+    use crate::widgets::input::{Cursor, InputValue, SelectionState};
+    use crate::window::DeviceId;
+
+    let text = Dynamic::new(String::from("hello"));
+    let generation = Dynamic::new(0_usize);
+    let selection = Dynamic::<SelectionState>::default();
+    let mut recorder = Switcher::mapping(generation.clone(), {
+        let text = text.clone();
+        let selection = selection.clone();
+        move |generation, _| {
+            let mut input = text.clone().into_input();
+            // Only the first input shares its selection with the test.
+            if *generation == 0 {
+                input = input.with_selection(selection.clone());
+            }
+            input.with_state_key("input")
+        }
+    })
+    .preserving_state()
+    .build_recorder()
+    .size(Size::new(UPx::new(200), UPx::new(40)))
+    .finish()
+    .expect("error creating recorder");
+
+    // Click the input to focus it, and then select "el".
+    recorder
+        .window
+        .cursor_moved(DeviceId::Virtual(0), Point::new(Px::new(20), Px::new(15)));
+    for state in [ElementState::Pressed, ElementState::Released] {
+        recorder
+            .window
+            .mouse_input(DeviceId::Virtual(0), state, MouseButton::Left);
+    }
+    selection.set(SelectionState {
+        cursor: Cursor {
+            offset: 3,
+            ..Cursor::default()
+        },
+        start: Some(Cursor {
+            offset: 1,
+            ..Cursor::default()
+        }),
+    });
+    recorder.refresh().expect("error rendering");
+
+    generation.set(1);
+    recorder.refresh().expect("error rendering");
+    recorder.refresh().expect("error rendering");
+
+    // Committed text is only delivered to the focused widget, and it replaces
+    // the selection.
+    recorder.window.ime(&Ime::Commit(String::from("X")));
+    assert_eq!(text.get(), "hXlo");
 }
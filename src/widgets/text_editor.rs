@@ -0,0 +1,205 @@
+//! A multi-line text editing widget.
+
+use figures::units::{Px, UPx};
+use figures::{IntoUnsigned, Point, Rect, Size};
+
+use crate::reactive::value::{
+    Destination, Dynamic, DynamicReader, IntoDynamic, IntoReadOnly, IntoValue, ReadOnly, Source,
+    Value,
+};
+use crate::widget::{MakeWidgetWithTag, WidgetInstance, WidgetTag};
+use crate::widgets::input::{Input, SelectionState};
+use crate::widgets::rich_text::RichText;
+use crate::widgets::Scroll;
+
+/// A multi-line text editor.
+///
+/// The editor's text and selection are stored in [`Dynamic`]s, allowing them to
+/// be observed and updated while the editor is displayed. The editor scrolls
+/// vertically and keeps the cursor visible as it is moved.
+///
+/// In addition to the editing shortcuts supported by [`Input`], Enter inserts
+/// a new line, Page Up and Page Down move the cursor by a page, and
+/// Ctrl+Home/Ctrl+End (Cmd+Up/Cmd+Down on Apple platforms) move the cursor to
+/// the start or end of the text.
+///
+/// The caret and selection are styled using the same components as [`Input`],
+/// such as [`CaretColor`](crate::widgets::input::CaretColor) and
+/// [`SelectionBackground`](crate::widgets::input::SelectionBackground). These
+/// components can be applied to the editor directly.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::TextEditor;
+///
+/// let notes = Dynamic::new(String::from("First line\nSecond line"));
+/// let editor = TextEditor::new(notes);
+/// ```
+#[must_use]
+pub struct TextEditor {
+    text: Dynamic<String>,
+    selection: Dynamic<SelectionState>,
+    placeholder: Value<String>,
+    highlight: Option<ReadOnly<RichText>>,
+    soft_wrap: bool,
+}
+
+impl TextEditor {
+    /// Returns a new editor that edits `text`.
+    pub fn new(text: impl IntoDynamic<String>) -> Self {
+        Self {
+            text: text.into_dynamic(),
+            selection: Dynamic::default(),
+            placeholder: Value::default(),
+            highlight: None,
+            soft_wrap: true,
+        }
+    }
+
+    /// Synchronizes this editor's selection with `selection` and returns self.
+    pub fn with_selection(mut self, selection: impl IntoDynamic<SelectionState>) -> Self {
+        self.selection = selection.into_dynamic();
+        self
+    }
+
+    /// Sets the `placeholder` text, which is displayed when the editor is
+    /// empty, and returns self.
+    pub fn placeholder(mut self, placeholder: impl IntoValue<String>) -> Self {
+        self.placeholder = placeholder.into_value();
+        self
+    }
+
+    /// Styles the text being edited using the spans of `highlight` and returns
+    /// self.
+    ///
+    /// The spans of `highlight` are applied to the editor's current text. To
+    /// keep the styles in sync with edits, derive `highlight` from
+    /// [`Self::text()`], for example by using
+    /// [`map_each`](crate::reactive::value::Source::map_each) to invoke a
+    /// syntax highlighter.
+    pub fn highlighted(mut self, highlight: impl IntoReadOnly<RichText>) -> Self {
+        self.highlight = Some(highlight.into_read_only());
+        self
+    }
+
+    /// Sets whether lines that are wider than the editor are wrapped and
+    /// returns self.
+    ///
+    /// Soft wrapping is enabled by default. When disabled, the editor scrolls
+    /// horizontally to display long lines.
+    pub fn soft_wrap(mut self, soft_wrap: bool) -> Self {
+        self.soft_wrap = soft_wrap;
+        self
+    }
+
+    /// Returns the text being edited.
+    #[must_use]
+    pub const fn text(&self) -> &Dynamic<String> {
+        &self.text
+    }
+
+    /// Returns the current selection of this editor.
+    #[must_use]
+    pub const fn selection(&self) -> &Dynamic<SelectionState> {
+        &self.selection
+    }
+}
+
+impl MakeWidgetWithTag for TextEditor {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let caret = Dynamic::new(Rect::<Px>::default());
+        let mut input = Input::new(self.text)
+            .placeholder(self.placeholder)
+            .multiline(true)
+            .with_selection(self.selection)
+            .with_caret_area(caret.clone());
+        if let Some(highlight) = self.highlight {
+            input = input.highlighted(highlight);
+        }
+        let scroll = if self.soft_wrap {
+            Scroll::vertical(input)
+        } else {
+            Scroll::new(input)
+        };
+
+        let offset = scroll.scroll.clone();
+        let visible = scroll.control_size();
+        caret
+            .for_each_cloned(move |caret| scroll_to_caret(caret, &offset, &visible))
+            .persist();
+
+        scroll.make_with_tag(tag)
+    }
+}
+
+fn scroll_to_caret(
+    caret: Rect<Px>,
+    offset: &Dynamic<Point<UPx>>,
+    visible: &DynamicReader<Size<UPx>>,
+) {
+    let visible = visible.get();
+    if visible.width == 0 || visible.height == 0 {
+        return;
+    }
+
+    let (top_left, bottom_right) = caret.extents();
+    let top_left = top_left.into_unsigned();
+    let bottom_right = bottom_right.into_unsigned();
+    let mut scroll = offset.get();
+    if top_left.y < scroll.y {
+        scroll.y = top_left.y;
+    } else if bottom_right.y > scroll.y + visible.height {
+        scroll.y = bottom_right.y - visible.height;
+    }
+    if top_left.x < scroll.x {
+        scroll.x = top_left.x;
+    } else if bottom_right.x > scroll.x + visible.width {
+        scroll.x = bottom_right.x - visible.width;
+    }
+    offset.set(scroll);
+}
+
+#[test]
+fn selection_components_apply_to_editor() {
+    use kludgine::app::winit::event::{ElementState, MouseButton};
+    use kludgine::Color;
+
+    use crate::widget::MakeWidget;
+    use crate::widgets::input::{Cursor, SelectionBackground};
+    use crate::window::DeviceId;
+
+    let text = " ".repeat(40);
+    let selection = Dynamic::<SelectionState>::default();
+    let mut recorder = TextEditor::new(text.clone())
+        .with_selection(selection.clone())
+        .with(&SelectionBackground, Color::RED)
+        .build_recorder()
+        .size(Size::new(UPx::new(200), UPx::new(60)))
+        .finish()
+        .expect("error creating recorder");
+
+    // Clicking the editor focuses it.
+    recorder
+        .window
+        .cursor_moved(DeviceId::Virtual(0), Point::new(Px::new(100), Px::new(30)));
+    for state in [ElementState::Pressed, ElementState::Released] {
+        recorder
+            .window
+            .mouse_input(DeviceId::Virtual(0), state, MouseButton::Left);
+    }
+    selection.set(SelectionState {
+        cursor: Cursor {
+            offset: text.len(),
+            ..Cursor::default()
+        },
+        start: Some(Cursor::default()),
+    });
+    recorder.refresh().expect("error rendering");
+    recorder.refresh().expect("error rendering");
+
+    recorder.assert_pixel_color(
+        Point::new(UPx::new(16), UPx::new(16)),
+        Color::RED,
+        "selection background",
+    );
+}
@@ -0,0 +1,240 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use figures::units::{Lp, Px, UPx};
+use figures::{IntoSigned, Point, Rect, Round, ScreenScale, Size, Zero};
+use kludgine::app::winit::event::MouseButton;
+use kludgine::shapes::{PathBuilder, Shape, StrokeOptions};
+use parking_lot::Mutex;
+
+use crate::context::{EventContext, GraphicsContext, LayoutContext};
+use crate::styles::components::TextColor;
+use crate::widget::{
+    EventHandling, MakeWidget, Widget, WidgetList, WidgetRef, WrapperWidget, HANDLED, IGNORED,
+};
+use crate::widgets::button::ButtonKind;
+use crate::window::{DeviceId, WindowHandle};
+use crate::ConstraintLimit;
+
+/// A title bar for windows without platform decorations.
+///
+/// When a window is opened with
+/// [`decorated(false)`](crate::window::Window::decorated), the platform no
+/// longer provides a way to move, minimize, maximize, or close the window.
+/// This widget displays a title alongside minimize, maximize, and close
+/// buttons, and restores the remaining behaviors:
+///
+/// - Dragging the title bar moves the window.
+/// - Double-clicking the title bar maximizes the window, or restores it if it
+///   is already maximized.
+///
+/// To allow resizing a window from its edges, use
+/// [`Window::client_decorations`](crate::window::Window::client_decorations),
+/// which provides resize borders around the window's contents.
+///
+/// ```rust
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::TitleBar;
+///
+/// let contents = TitleBar::new("My App")
+///     .and("Hello, World!".centered().expand())
+///     .into_rows()
+///     .into_window()
+///     .decorated(false);
+/// ```
+#[derive(Debug)]
+pub struct TitleBar {
+    child: WidgetRef,
+    window: Arc<Mutex<Option<WindowHandle>>>,
+    last_press: Option<Instant>,
+}
+
+impl TitleBar {
+    /// Returns a title bar that displays `title` before the window's caption
+    /// buttons.
+    #[must_use]
+    pub fn new(title: impl MakeWidget) -> Self {
+        let window = Arc::new(Mutex::new(None::<WindowHandle>));
+        let buttons = Caption::ALL
+            .into_iter()
+            .map(|caption| {
+                let window = window.clone();
+                CaptionGlyph(caption)
+                    .into_button()
+                    .kind(ButtonKind::Transparent)
+                    .on_click(move |_| {
+                        if let Some(window) = &*window.lock() {
+                            caption.perform(window);
+                        }
+                    })
+            })
+            .collect::<WidgetList>()
+            .into_columns()
+            .gutter(Px::ZERO);
+        Self {
+            child: WidgetRef::new(title.align_left().expand().and(buttons).into_columns()),
+            window,
+            last_press: None,
+        }
+    }
+}
+
+impl WrapperWidget for TitleBar {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn mounted(&mut self, context: &mut EventContext<'_>) {
+        *self.window.lock() = Some(context.window().handle());
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        *self.window.lock() = None;
+        self.child.unmount_in(context);
+    }
+
+    fn hit_test(&mut self, _location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn mouse_down(
+        &mut self,
+        _location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button != MouseButton::Left {
+            return IGNORED;
+        }
+
+        let now = Instant::now();
+        let threshold = context.cushy().multi_click_threshold();
+        if self
+            .last_press
+            .take()
+            .is_some_and(|last_press| now.saturating_duration_since(last_press) < threshold)
+        {
+            toggle_maximized(context);
+        } else {
+            self.last_press = Some(now);
+            if let Some(winit) = context.window().winit() {
+                if let Err(err) = winit.drag_window() {
+                    tracing::warn!("error starting window drag: {err}");
+                }
+            }
+        }
+        HANDLED
+    }
+}
+
+/// Maximizes the window of `context`, or restores it if it is already
+/// maximized.
+fn toggle_maximized(context: &EventContext<'_>) {
+    let window = context.window();
+    let maximized = window.winit().is_some_and(|winit| winit.is_maximized());
+    let handle = window.handle();
+    if maximized {
+        handle.restore();
+    } else {
+        handle.maximize();
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Caption {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+impl Caption {
+    const ALL: [Self; 3] = [Self::Minimize, Self::Maximize, Self::Close];
+
+    fn perform(self, window: &WindowHandle) {
+        match self {
+            Caption::Minimize => window.minimize(),
+            Caption::Maximize => window.execute(|context| toggle_maximized(context)),
+            Caption::Close => window.request_close(),
+        }
+    }
+}
+
+/// The glyph drawn inside of a caption button.
+#[derive(Debug)]
+struct CaptionGlyph(Caption);
+
+impl Widget for CaptionGlyph {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let scale = context.gfx.scale();
+        let stroke = StrokeOptions::px_wide(Lp::points(1).into_px(scale).ceil())
+            .colored(context.get(&TextColor));
+        let half_line = stroke.line_width / 2;
+        let size = context.gfx.size().into_signed();
+        let area = Rect::new(
+            Point::new(half_line, half_line),
+            Size::new(
+                size.width - stroke.line_width,
+                size.height - stroke.line_width,
+            ),
+        );
+        let (top_left, bottom_right) = area.extents();
+        let line = |from: Point<Px>, to: Point<Px>| {
+            PathBuilder::new(from).line_to(to).build().stroke(stroke)
+        };
+        match self.0 {
+            Caption::Minimize => {
+                let middle = top_left.y + area.size.height / 2;
+                context.gfx.draw_shape(&line(
+                    Point::new(top_left.x, middle),
+                    Point::new(bottom_right.x, middle),
+                ));
+            }
+            Caption::Maximize => {
+                context.gfx.draw_shape(&Shape::stroked_rect(area, stroke));
+            }
+            Caption::Close => {
+                context.gfx.draw_shape(&line(top_left, bottom_right));
+                context.gfx.draw_shape(&line(
+                    Point::new(bottom_right.x, top_left.y),
+                    Point::new(top_left.x, bottom_right.y),
+                ));
+            }
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        Size::squared(Lp::points(8).into_upx(context.gfx.scale()).ceil())
+    }
+}
+
+#[test]
+fn close_button_requests_close() {
+    use kludgine::app::winit::event::ElementState;
+
+    use crate::reactive::value::Source;
+    use crate::widgets::Space;
+
+    let mut recorder = TitleBar::new(Space::clear())
+        .and(Space::clear().expand())
+        .into_rows()
+        .build_recorder()
+        .size(Size::new(UPx::new(120), UPx::new(60)))
+        .finish()
+        .expect("error creating recorder");
+    recorder.refresh().expect("error rendering");
+
+    // The close button is the rightmost caption button.
+    let device = DeviceId::Virtual(0);
+    recorder.window.cursor_moved(device, Point::px(114, 6));
+    for state in [ElementState::Pressed, ElementState::Released] {
+        recorder
+            .window
+            .mouse_input(device, state, MouseButton::Left);
+    }
+    assert!(recorder.window.state().dynamic.close_requested.get());
+}
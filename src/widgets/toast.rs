@@ -0,0 +1,349 @@
+//! A layer for showing transient notifications.
+
+use std::time::Duration;
+
+use alot::{LotId, OrderedLots};
+use figures::units::Lp;
+use kludgine::Color;
+
+use crate::animation::{AnimationHandle, IntoAnimate, Spawn};
+use crate::reactive::value::{Destination, Dynamic, Source};
+use crate::styles::components::{TextColor, WidgetBackground};
+use crate::widget::{
+    MakeWidget, MakeWidgetWithTag, SharedCallback, WidgetInstance, WidgetList, WidgetTag,
+};
+use crate::widgets::button::ButtonKind;
+use crate::widgets::Style;
+
+/// A layer that displays transient notifications, commonly referred to as
+/// toasts.
+///
+/// Designed to be used in a [`Layers`](super::Layers) widget. Toasts are
+/// stacked in the bottom-right corner of the layer, with the most recently
+/// shown toast at the bottom. When more than [`Toasts::max_visible`] toasts are
+/// shown, the oldest toasts are dismissed.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use cushy::widget::MakeWidget;
+/// use cushy::widgets::toast::Toasts;
+///
+/// let toasts = Toasts::new();
+/// let ui = "Content".and(toasts.clone()).into_layers();
+///
+/// toasts
+///     .build("File saved")
+///     .success()
+///     .timeout(Duration::from_secs(2))
+///     .show();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Toasts {
+    state: Dynamic<ToastState>,
+}
+
+impl Toasts {
+    /// The duration toasts are shown for unless another timeout is specified.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Returns a new toast layer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of toasts that can be shown at once and returns
+    /// self.
+    ///
+    /// By default, 5 toasts can be shown at once.
+    #[must_use]
+    pub fn max_visible(self, max_visible: usize) -> Self {
+        let mut state = self.state.lock();
+        state.max_visible = max_visible.max(1);
+        state.enforce_limit();
+        drop(state);
+        self
+    }
+
+    /// Shows `message` as an informational toast that is dismissed after
+    /// [`Self::DEFAULT_TIMEOUT`].
+    pub fn show(&self, message: impl MakeWidget) -> ToastHandle {
+        self.build(message).show()
+    }
+
+    /// Returns a builder for a toast that displays `message`.
+    pub fn build(&self, message: impl MakeWidget) -> ToastBuilder<'_> {
+        ToastBuilder {
+            toasts: self,
+            message: message.make_widget(),
+            severity: ToastSeverity::Info,
+            action: None,
+            timeout: Some(Self::DEFAULT_TIMEOUT),
+        }
+    }
+
+    /// Returns the number of toasts currently being shown.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.state.map_ref(|state| state.entries.len())
+    }
+
+    /// Dismisses all toasts.
+    pub fn dismiss_all(&self) {
+        self.state.lock().entries.clear();
+    }
+
+    fn dismiss(&self, id: LotId) {
+        let mut state = self.state.lock();
+        state.entries.remove(id);
+    }
+}
+
+impl MakeWidgetWithTag for Toasts {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        self.state
+            .map_each(|state| {
+                state
+                    .entries
+                    .iter()
+                    .map(|entry| entry.widget.clone())
+                    .collect::<WidgetList>()
+            })
+            .into_rows()
+            .width(..Lp::inches(4))
+            .align_bottom()
+            .align_right()
+            .pad()
+            .make_with_tag(tag)
+    }
+}
+
+#[derive(Debug)]
+struct ToastState {
+    entries: OrderedLots<ToastEntry>,
+    max_visible: usize,
+}
+
+impl ToastState {
+    fn enforce_limit(&mut self) {
+        while self.entries.len() > self.max_visible {
+            self.entries.remove_by_index(0);
+        }
+    }
+}
+
+impl Default for ToastState {
+    fn default() -> Self {
+        Self {
+            entries: OrderedLots::new(),
+            max_visible: 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ToastEntry {
+    widget: WidgetInstance,
+    _timeout: Option<AnimationHandle>,
+}
+
+/// The severity of a toast, which controls its colors.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ToastSeverity {
+    /// The toast is informational.
+    #[default]
+    Info,
+    /// The toast reports that an operation succeeded.
+    Success,
+    /// The toast warns about a potential problem.
+    Warning,
+    /// The toast reports an error.
+    Error,
+}
+
+impl ToastSeverity {
+    fn style(self, toast: impl MakeWidget) -> Style {
+        match self {
+            ToastSeverity::Info => toast
+                .with_dynamic(&TextColor, InfoToastForeground)
+                .with_local_dynamic(&WidgetBackground, InfoToastBackground),
+            ToastSeverity::Success => toast
+                .with_dynamic(&TextColor, SuccessToastForeground)
+                .with_local_dynamic(&WidgetBackground, SuccessToastBackground),
+            ToastSeverity::Warning => toast
+                .with_dynamic(&TextColor, WarningToastForeground)
+                .with_local_dynamic(&WidgetBackground, WarningToastBackground),
+            ToastSeverity::Error => toast
+                .with_dynamic(&TextColor, ErrorToastForeground)
+                .with_local_dynamic(&WidgetBackground, ErrorToastBackground),
+        }
+    }
+}
+
+/// A builder for a toast shown in a [`Toasts`] layer.
+#[must_use = "toasts are not shown until ToastBuilder::show is called"]
+pub struct ToastBuilder<'a> {
+    toasts: &'a Toasts,
+    message: WidgetInstance,
+    severity: ToastSeverity,
+    action: Option<(WidgetInstance, SharedCallback)>,
+    timeout: Option<Duration>,
+}
+
+impl ToastBuilder<'_> {
+    /// Sets the severity of this toast and returns self.
+    pub fn severity(mut self, severity: ToastSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Sets the severity of this toast to [`ToastSeverity::Info`] and returns
+    /// self.
+    pub fn info(self) -> Self {
+        self.severity(ToastSeverity::Info)
+    }
+
+    /// Sets the severity of this toast to [`ToastSeverity::Success`] and
+    /// returns self.
+    pub fn success(self) -> Self {
+        self.severity(ToastSeverity::Success)
+    }
+
+    /// Sets the severity of this toast to [`ToastSeverity::Warning`] and
+    /// returns self.
+    pub fn warning(self) -> Self {
+        self.severity(ToastSeverity::Warning)
+    }
+
+    /// Sets the severity of this toast to [`ToastSeverity::Error`] and returns
+    /// self.
+    pub fn error(self) -> Self {
+        self.severity(ToastSeverity::Error)
+    }
+
+    /// Adds an action button displaying `caption` to this toast and returns
+    /// self.
+    ///
+    /// When the button is clicked, `on_click` is invoked and the toast is
+    /// dismissed.
+    pub fn action<F>(mut self, caption: impl MakeWidget, mut on_click: F) -> Self
+    where
+        F: FnMut() + Send + 'static,
+    {
+        self.action = Some((
+            caption.make_widget(),
+            SharedCallback::new(move |()| on_click()),
+        ));
+        self
+    }
+
+    /// Sets the duration this toast is shown for before being automatically
+    /// dismissed and returns self.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Prevents this toast from being automatically dismissed and returns
+    /// self.
+    ///
+    /// The toast is shown until it is dismissed by the user, by its
+    /// [`ToastHandle`], or by being replaced by newer toasts.
+    pub fn persistent(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Shows this toast and returns a handle that can be used to dismiss it.
+    pub fn show(self) -> ToastHandle {
+        let handle = ToastHandle {
+            toasts: self.toasts.clone(),
+            id: Dynamic::default(),
+        };
+
+        let mut contents = self.message.expand().make_widget();
+        if let Some((caption, on_click)) = self.action {
+            contents = contents
+                .and(caption.into_button().on_click({
+                    let handle = handle.clone();
+                    move |_| {
+                        on_click.invoke(());
+                        handle.dismiss();
+                    }
+                }))
+                .into_columns()
+                .make_widget();
+        }
+        let widget = self
+            .severity
+            .style(
+                contents
+                    .and(
+                        "\u{d7}"
+                            .into_button()
+                            .kind(ButtonKind::Transparent)
+                            .on_click({
+                                let handle = handle.clone();
+                                move |_| handle.dismiss()
+                            }),
+                    )
+                    .into_columns()
+                    .pad(),
+            )
+            .make_widget();
+
+        let timeout = self.timeout.map(|timeout| {
+            let handle = handle.clone();
+            timeout.on_complete(move || handle.dismiss()).spawn()
+        });
+
+        let mut state = self.toasts.state.lock();
+        let id = state.entries.push(ToastEntry {
+            widget,
+            _timeout: timeout,
+        });
+        state.enforce_limit();
+        drop(state);
+        handle.id.set(Some(id));
+
+        handle
+    }
+}
+
+/// A handle to a toast shown in a [`Toasts`] layer.
+#[derive(Debug, Clone)]
+pub struct ToastHandle {
+    toasts: Toasts,
+    id: Dynamic<Option<LotId>>,
+}
+
+impl ToastHandle {
+    /// Dismisses this toast, if it is still being shown.
+    pub fn dismiss(&self) {
+        if let Some(id) = self.id.take() {
+            self.toasts.dismiss(id);
+        }
+    }
+}
+
+define_components! {
+    Toast {
+        /// The background color of an informational toast.
+        InfoToastBackground(Color, "info_background", .primary.container)
+        /// The text color of an informational toast.
+        InfoToastForeground(Color, "info_foreground", .primary.on_container)
+        /// The background color of a toast reporting success.
+        SuccessToastBackground(Color, "success_background", .tertiary.container)
+        /// The text color of a toast reporting success.
+        SuccessToastForeground(Color, "success_foreground", .tertiary.on_container)
+        /// The background color of a toast containing a warning.
+        WarningToastBackground(Color, "warning_background", .secondary.container)
+        /// The text color of a toast containing a warning.
+        WarningToastForeground(Color, "warning_foreground", .secondary.on_container)
+        /// The background color of a toast reporting an error.
+        ErrorToastBackground(Color, "error_background", .error.container)
+        /// The text color of a toast reporting an error.
+        ErrorToastForeground(Color, "error_foreground", .error.on_container)
+    }
+}
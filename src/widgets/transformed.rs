@@ -0,0 +1,157 @@
+use figures::units::UPx;
+use figures::{
+    Angle, FloatConversion, IntoSigned, IntoUnsigned, Point, Rect, ScreenScale, Size, Zero,
+};
+
+use crate::context::{AsEventContext, GraphicsContext, LayoutContext};
+use crate::graphics::Transform;
+use crate::reactive::value::{IntoValue, Value};
+use crate::styles::Dimension;
+use crate::widget::{MakeWidget, WidgetRef, WrappedLayout, WrapperWidget};
+use crate::ConstraintLimit;
+
+/// A widget that rotates, scales, and translates its child.
+///
+/// The child is scaled and rotated around its center. This widget's layout
+/// occupies the bounding box of the scaled and rotated child, which allows
+/// placing rotated content such as a vertical caption alongside other
+/// widgets. When the rotation is closer to a quarter turn than to a half
+/// turn, the child is laid out with the available width and height swapped.
+///
+/// The translation only affects where the child is drawn and does not affect
+/// layout. Portions of the child that are translated outside of this widget's
+/// bounds are clipped.
+///
+/// Mouse and touch input is mapped back through the inverse transform before
+/// it is delivered to the child, so hit testing matches what is drawn.
+#[derive(Debug)]
+pub struct Transformed {
+    child: WidgetRef,
+    rotation: Value<Angle>,
+    scale: Value<f32>,
+    translation: Value<Point<Dimension>>,
+    parent_transform: Option<Transform>,
+}
+
+impl Transformed {
+    /// Returns a widget that draws `child` without any transformation.
+    #[must_use]
+    pub fn new(child: impl MakeWidget) -> Self {
+        Self {
+            child: WidgetRef::new(child),
+            rotation: Value::Constant(Angle::degrees(0)),
+            scale: Value::Constant(1.),
+            translation: Value::Constant(Point::ZERO),
+            parent_transform: None,
+        }
+    }
+
+    /// Rotates the child clockwise by `rotation` and returns self.
+    #[must_use]
+    pub fn rotated_by(mut self, rotation: impl IntoValue<Angle>) -> Self {
+        self.rotation = rotation.into_value();
+        self
+    }
+
+    /// Scales the child by `scale` and returns self.
+    #[must_use]
+    pub fn scaled_by(mut self, scale: impl IntoValue<f32>) -> Self {
+        self.scale = scale.into_value();
+        self
+    }
+
+    /// Offsets where the child is drawn by `translation` and returns self.
+    #[must_use]
+    pub fn translated_by(mut self, translation: impl IntoValue<Point<Dimension>>) -> Self {
+        self.translation = translation.into_value();
+        self
+    }
+}
+
+fn scale_and_rotation(scale: f32, rotation: Angle) -> Transform {
+    Transform {
+        scale: if scale.is_finite() && scale > 0. {
+            scale
+        } else {
+            0.
+        },
+        rotation,
+        ..Transform::default()
+    }
+}
+
+impl WrapperWidget for Transformed {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn layout_child(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> WrappedLayout {
+        let transform = scale_and_rotation(
+            self.scale.get_tracking_invalidate(context),
+            self.rotation.get_tracking_invalidate(context),
+        );
+
+        let (sin, cos) = transform
+            .rotation
+            .into_degrees::<f32>()
+            .to_radians()
+            .sin_cos();
+        let available_space = if sin.abs() > cos.abs() {
+            Size::new(available_space.height, available_space.width)
+        } else {
+            available_space
+        };
+        let unscale = |limit: UPx| {
+            if limit == UPx::MAX || transform.scale <= 0. {
+                limit
+            } else {
+                UPx::from_float(limit.into_float() / transform.scale)
+            }
+        };
+        let child_space = available_space.map(|limit| match limit {
+            ConstraintLimit::Fill(limit) => ConstraintLimit::Fill(unscale(limit)),
+            ConstraintLimit::SizeToFit(limit) => ConstraintLimit::SizeToFit(unscale(limit)),
+        });
+
+        let child = self.child.mounted(&mut context.as_event_context());
+        let child_size = context.for_other(&child).layout(child_space).into_signed();
+        let bounds = transform.bounds_of(Rect::from(child_size));
+        let size = bounds.size.into_unsigned();
+        let signed_size = size.into_signed();
+
+        WrappedLayout {
+            child: Rect::new(
+                Point::new(
+                    (signed_size.width - child_size.width) / 2,
+                    (signed_size.height - child_size.height) / 2,
+                ),
+                child_size,
+            ),
+            size,
+        }
+    }
+
+    fn redraw_background(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let scale = context.gfx.scale();
+        let translation = self.translation.get_tracking_redraw(context);
+        let translation = Point::new(translation.x.into_px(scale), translation.y.into_px(scale));
+        let region = context.gfx.region();
+        let center = region.origin + Point::new(region.size.width / 2, region.size.height / 2);
+
+        let mut transform = scale_and_rotation(self.scale.get(), self.rotation.get());
+        transform.translation = (center + translation).into_float();
+
+        self.parent_transform = context.gfx.transform();
+        context.gfx.apply_transform(
+            Transform::translated(Point::new(-center.x, -center.y)).then(transform),
+        );
+    }
+
+    fn redraw_foreground(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        context.gfx.set_transform(self.parent_transform.take());
+    }
+}
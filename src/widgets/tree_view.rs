@@ -0,0 +1,527 @@
+//! A widget that displays hierarchical data.
+
+use std::fmt::Debug;
+
+use ahash::AHashMap;
+use figures::units::{Lp, Px, UPx};
+use figures::{Angle, IntoSigned, Point, Rect, Round, ScreenScale, Size, Zero};
+use kludgine::app::winit::event::MouseButton;
+use kludgine::app::winit::keyboard::{Key, NamedKey};
+use kludgine::app::winit::window::CursorIcon;
+use kludgine::shapes::{PathBuilder, Shape, StrokeOptions};
+use kludgine::{Color, DrawableExt};
+
+use crate::context::{AsEventContext, EventContext, GraphicsContext, LayoutContext};
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, IntoValue, Source, Value};
+use crate::styles::components::{HighlightColor, IntrinsicPadding, LineHeight, OutlineColor};
+use crate::styles::Dimension;
+use crate::widget::{
+    EventHandling, MakeWidget, Widget, WidgetId, WidgetInstance, WidgetRef, HANDLED, IGNORED,
+};
+use crate::window::{DeviceId, KeyEvent};
+use crate::ConstraintLimit;
+
+/// A node in a [`TreeView`].
+///
+/// Each node has a value that identifies it when it is selected, a label that
+/// is displayed for the node, and zero or more child nodes.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct TreeNode<T> {
+    value: T,
+    label: WidgetInstance,
+    children: Vec<TreeNode<T>>,
+    expanded: Dynamic<bool>,
+}
+
+impl<T> TreeNode<T> {
+    /// Returns a new collapsed node with no children.
+    pub fn new(value: T, label: impl MakeWidget) -> Self {
+        Self {
+            value,
+            label: label.make_widget(),
+            children: Vec::new(),
+            expanded: Dynamic::new(false),
+        }
+    }
+
+    /// Adds `child` to this node and returns self.
+    pub fn with_child(mut self, child: TreeNode<T>) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Adds `children` to this node and returns self.
+    pub fn with_children(mut self, children: impl IntoIterator<Item = TreeNode<T>>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Sets whether this node's children are shown and returns self.
+    ///
+    /// If a [`Dynamic`] is provided, it will be updated as the node is
+    /// expanded and collapsed.
+    pub fn expanded(mut self, expanded: impl IntoDynamic<bool>) -> Self {
+        self.expanded = expanded.into_dynamic();
+        self
+    }
+
+    /// Returns the value of this node.
+    #[must_use]
+    pub const fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the children of this node.
+    #[must_use]
+    pub fn children(&self) -> &[TreeNode<T>] {
+        &self.children
+    }
+
+    /// Returns the dynamic that controls whether this node's children are
+    /// shown.
+    #[must_use]
+    pub const fn is_expanded(&self) -> &Dynamic<bool> {
+        &self.expanded
+    }
+}
+
+/// A widget that displays hierarchical data as a tree of expandable nodes.
+///
+/// Each visible node is displayed on its own row, indented beneath its parent
+/// with guides that connect it to its siblings. Nodes with children display an
+/// indicator that can be clicked to expand or collapse the node.
+///
+/// When focused, the tree view supports keyboard navigation:
+///
+/// - Up and Down select the previous and next visible node.
+/// - Right expands the selected node, or selects its first child if it is
+///   already expanded.
+/// - Left collapses the selected node, or selects its parent if it is already
+///   collapsed.
+/// - Home and End select the first and last visible node.
+/// - Enter and Space toggle whether the selected node is expanded.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widgets::tree_view::{TreeNode, TreeView};
+///
+/// let selected = Dynamic::new(None);
+/// let tree = TreeView::new(
+///     vec![TreeNode::new("src", "src")
+///         .with_child(TreeNode::new("lib.rs", "lib.rs"))
+///         .with_child(TreeNode::new("main.rs", "main.rs"))],
+///     selected,
+/// );
+/// ```
+#[derive(Debug)]
+pub struct TreeView<T> {
+    nodes: Value<Vec<TreeNode<T>>>,
+    selected: Dynamic<Option<T>>,
+    labels: AHashMap<WidgetId, WidgetRef>,
+    rows: Vec<TreeRow<T>>,
+    indent: UPx,
+    hovered_indicator: Option<usize>,
+}
+
+#[derive(Debug)]
+struct TreeRow<T> {
+    value: T,
+    label: WidgetId,
+    depth: u32,
+    parent: Option<usize>,
+    expanded: Option<Dynamic<bool>>,
+    area: Rect<UPx>,
+}
+
+impl<T> TreeView<T>
+where
+    T: Clone + PartialEq + Debug + Send + 'static,
+{
+    /// Returns a new tree view that displays `nodes` and stores the value of
+    /// the selected node in `selected`.
+    #[must_use]
+    pub fn new(
+        nodes: impl IntoValue<Vec<TreeNode<T>>>,
+        selected: impl IntoDynamic<Option<T>>,
+    ) -> Self {
+        Self {
+            nodes: nodes.into_value(),
+            selected: selected.into_dynamic(),
+            labels: AHashMap::new(),
+            rows: Vec::new(),
+            indent: UPx::ZERO,
+            hovered_indicator: None,
+        }
+    }
+
+    fn synchronize_rows(&mut self, context: &mut EventContext<'_>) {
+        self.rows.clear();
+        let mut seen = Vec::new();
+        self.nodes.map_tracking_invalidate(context, |nodes| {
+            flatten_nodes(nodes, 0, None, &mut self.rows, &mut seen, context);
+            for node in nodes {
+                collect_labels(node, &mut self.labels);
+            }
+        });
+
+        self.labels.retain(|id, label| {
+            let keep = seen.contains(id);
+            if !keep {
+                label.unmount_in(context);
+            }
+            keep
+        });
+    }
+
+    fn selected_row(&self) -> Option<usize> {
+        self.selected.map_ref(|selected| {
+            let selected = selected.as_ref()?;
+            self.rows.iter().position(|row| &row.value == selected)
+        })
+    }
+
+    fn select_row(&mut self, index: usize, context: &mut EventContext<'_>) {
+        if let Some(row) = self.rows.get(index) {
+            self.selected.set(Some(row.value.clone()));
+            context.set_needs_redraw();
+        }
+    }
+
+    fn row_at(&self, location: Point<Px>) -> Option<usize> {
+        let location = Point::new(location.x.max(Px::ZERO), location.y.max(Px::ZERO));
+        self.rows
+            .iter()
+            .position(|row| row.area.into_signed().contains(location))
+    }
+
+    fn indicator_contains(&self, index: usize, location: Point<Px>) -> bool {
+        let row = &self.rows[index];
+        if row.expanded.is_none() {
+            return false;
+        }
+        let start = self.indent * row.depth;
+        location.x >= start.into_signed() && location.x < (start + self.indent).into_signed()
+    }
+
+    fn handle_key(&mut self, key: &Key, context: &mut EventContext<'_>) -> bool {
+        let selected = self.selected_row();
+        let last = self.rows.len().checked_sub(1);
+        let target = match (key, selected) {
+            (Key::Named(NamedKey::ArrowUp), Some(index)) => index.checked_sub(1),
+            (Key::Named(NamedKey::ArrowDown), Some(index)) => {
+                Some(index + 1).filter(|index| *index < self.rows.len())
+            }
+            (Key::Named(NamedKey::ArrowUp | NamedKey::End), None)
+            | (Key::Named(NamedKey::End), Some(_)) => last,
+            (Key::Named(NamedKey::ArrowDown | NamedKey::Home), None)
+            | (Key::Named(NamedKey::Home), Some(_)) => Some(0).filter(|_| !self.rows.is_empty()),
+            (Key::Named(NamedKey::ArrowRight), Some(index)) => {
+                let row = &self.rows[index];
+                match &row.expanded {
+                    Some(expanded) if expanded.get() => Some(index + 1),
+                    Some(expanded) => {
+                        expanded.set(true);
+                        None
+                    }
+                    None => None,
+                }
+            }
+            (Key::Named(NamedKey::ArrowLeft), Some(index)) => {
+                let row = &self.rows[index];
+                match &row.expanded {
+                    Some(expanded) if expanded.get() => {
+                        expanded.set(false);
+                        None
+                    }
+                    _ => row.parent,
+                }
+            }
+            (Key::Named(NamedKey::Enter | NamedKey::Space), Some(index)) => {
+                if let Some(expanded) = &self.rows[index].expanded {
+                    expanded.toggle();
+                }
+                None
+            }
+            (
+                Key::Named(
+                    NamedKey::ArrowLeft | NamedKey::ArrowRight | NamedKey::Enter | NamedKey::Space,
+                ),
+                None,
+            ) => None,
+            _ => return false,
+        };
+
+        if let Some(target) = target {
+            self.select_row(target, context);
+        }
+        true
+    }
+}
+
+fn flatten_nodes<T>(
+    nodes: &[TreeNode<T>],
+    depth: u32,
+    parent: Option<usize>,
+    rows: &mut Vec<TreeRow<T>>,
+    seen: &mut Vec<WidgetId>,
+    context: &EventContext<'_>,
+) where
+    T: Clone,
+{
+    for node in nodes {
+        let index = rows.len();
+        seen.push(node.label.id());
+        let expanded = if node.children.is_empty() {
+            None
+        } else {
+            Some(node.expanded.clone())
+        };
+        rows.push(TreeRow {
+            value: node.value.clone(),
+            label: node.label.id(),
+            depth,
+            parent,
+            expanded,
+            area: Rect::default(),
+        });
+        if !node.children.is_empty() && node.expanded.get_tracking_invalidate(context) {
+            flatten_nodes(&node.children, depth + 1, Some(index), rows, seen, context);
+        }
+    }
+}
+
+fn collect_labels<T>(node: &TreeNode<T>, labels: &mut AHashMap<WidgetId, WidgetRef>) {
+    labels
+        .entry(node.label.id())
+        .or_insert_with(|| WidgetRef::new(node.label.clone()));
+    for child in &node.children {
+        collect_labels(child, labels);
+    }
+}
+
+impl<T> Widget for TreeView<T>
+where
+    T: Clone + PartialEq + Debug + Send + 'static,
+{
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        context.redraw_when_changed(&self.selected);
+        let selected = self.selected_row();
+        let indent = self.indent;
+        let guide_color = context.get(&TreeGuideColor);
+        let line_width = Lp::points(1).into_px(context.gfx.scale()).round();
+        let guide_options = StrokeOptions::px_wide(line_width).colored(guide_color);
+
+        if let Some(selected) = selected {
+            let mut color = context.get(&TreeSelectionColor);
+            if !context.focused(true) {
+                color = color.with_alpha(color.alpha() / 2);
+            }
+            context.gfx.draw_shape(&Shape::filled_rect(
+                self.rows[selected].area.into_signed(),
+                color,
+            ));
+        }
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let area = row.area.into_signed();
+
+            // Draw a guide for each ancestor, connecting this row to the rest
+            // of its siblings.
+            for level in 0..row.depth {
+                let x = (indent * level + indent / 2).into_signed();
+                context.gfx.draw_shape(
+                    &PathBuilder::new(Point::new(x, area.origin.y))
+                        .line_to(Point::new(x, area.origin.y + area.size.height))
+                        .build()
+                        .stroke(guide_options),
+                );
+            }
+
+            if let Some(expanded) = &row.expanded {
+                let expanded = expanded.get_tracking_redraw(context);
+                let color = if self.hovered_indicator == Some(index) {
+                    context.get(&OutlineColor)
+                } else {
+                    guide_color
+                };
+                let size = (indent / 2).into_signed();
+                let radius = size / 2;
+                let path = PathBuilder::new(Point::new(radius, Px::ZERO))
+                    .line_to(Point::new(radius, Px::ZERO).rotate_by(Angle::degrees(120)))
+                    .line_to(Point::new(radius, Px::ZERO).rotate_by(Angle::degrees(240)))
+                    .close();
+                let center = Point::new(
+                    (indent * row.depth + indent / 2).into_signed(),
+                    area.origin.y + area.size.height / 2,
+                );
+                let angle = if expanded {
+                    Angle::degrees(90)
+                } else {
+                    Angle::degrees(0)
+                };
+                context
+                    .gfx
+                    .draw_shape(path.fill(color).translate_by(center).rotate_by(angle));
+            }
+
+            if let Some(label) = self.labels.get_mut(&row.label) {
+                let label = label.mounted(context);
+                context.for_other(&label).redraw();
+            }
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        self.synchronize_rows(&mut context.as_event_context());
+
+        let indent = context
+            .get(&TreeIndent)
+            .into_upx(context.gfx.scale())
+            .round();
+        self.indent = indent;
+        let padding = context
+            .get(&IntrinsicPadding)
+            .into_upx(context.gfx.scale())
+            .round();
+
+        let mut y = UPx::ZERO;
+        let mut width = UPx::ZERO;
+        for row in &mut self.rows {
+            let inset = indent * (row.depth + 1);
+            let mut label_space = available_space;
+            label_space.width -= inset + padding;
+            let label = self
+                .labels
+                .get_mut(&row.label)
+                .expect("label synchronized")
+                .mounted(context);
+            let label_size = context.for_other(&label).layout(Size::new(
+                ConstraintLimit::SizeToFit(label_space.width.max()),
+                ConstraintLimit::SizeToFit(label_space.height.max()),
+            ));
+            let height = label_size.height.max(indent);
+            context.set_child_layout(
+                &label,
+                Rect::new(
+                    Point::new(inset, y + (height - label_size.height) / 2),
+                    label_size,
+                )
+                .into_signed(),
+            );
+            row.area = Rect::new(Point::new(UPx::ZERO, y), Size::new(UPx::ZERO, height));
+            width = width.max(inset + label_size.width + padding);
+            y += height;
+        }
+
+        let width = available_space.width.fit_measured(width);
+        for row in &mut self.rows {
+            row.area.size.width = width;
+        }
+
+        Size::new(width, y)
+    }
+
+    fn accept_focus(&mut self, _context: &mut EventContext<'_>) -> bool {
+        true
+    }
+
+    fn focus(&mut self, context: &mut EventContext<'_>) {
+        context.set_needs_redraw();
+    }
+
+    fn blur(&mut self, context: &mut EventContext<'_>) {
+        context.set_needs_redraw();
+    }
+
+    fn hit_test(&mut self, location: Point<Px>, _context: &mut EventContext<'_>) -> bool {
+        self.row_at(location).is_some()
+    }
+
+    fn hover(&mut self, location: Point<Px>, context: &mut EventContext<'_>) -> Option<CursorIcon> {
+        let hovered = self
+            .row_at(location)
+            .filter(|index| self.indicator_contains(*index, location));
+        if self.hovered_indicator != hovered {
+            self.hovered_indicator = hovered;
+            context.set_needs_redraw();
+        }
+
+        hovered.map(|_| CursorIcon::Pointer)
+    }
+
+    fn unhover(&mut self, context: &mut EventContext<'_>) {
+        if self.hovered_indicator.take().is_some() {
+            context.set_needs_redraw();
+        }
+    }
+
+    fn mouse_down(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        _button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        let Some(index) = self.row_at(location) else {
+            return IGNORED;
+        };
+
+        context.focus();
+        if self.indicator_contains(index, location) {
+            if let Some(expanded) = &self.rows[index].expanded {
+                expanded.toggle();
+            }
+        } else {
+            self.select_row(index, context);
+        }
+        HANDLED
+    }
+
+    fn keyboard_input(
+        &mut self,
+        _device_id: DeviceId,
+        input: KeyEvent,
+        _is_synthetic: bool,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if !input.state.is_pressed() {
+            return IGNORED;
+        }
+
+        if self.handle_key(&input.logical_key, context) {
+            HANDLED
+        } else {
+            IGNORED
+        }
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        for label in self.labels.values_mut() {
+            label.unmount_in(context);
+        }
+    }
+
+    fn summarize(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("TreeView")
+            .field("rows", &self.rows.len())
+            .field("selected", &self.selected)
+            .finish()
+    }
+}
+
+define_components! {
+    TreeView {
+        /// The amount each level of a [`TreeView`] is indented by.
+        TreeIndent(Dimension, "indent", @LineHeight)
+        /// The color of the indentation guides and expansion indicators of a
+        /// [`TreeView`].
+        TreeGuideColor(Color, "guide_color", @OutlineColor)
+        /// The background color of the selected row of a [`TreeView`].
+        TreeSelectionColor(Color, "selection_color", @HighlightColor)
+    }
+}
@@ -0,0 +1,310 @@
+//! An image widget that downloads its contents from a URL.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::io::Read;
+use std::sync::Arc;
+
+use ahash::AHashMap;
+use kludgine::{AnyTexture, LazyTexture};
+use parking_lot::Mutex;
+
+use crate::reactive::value::{Destination, Dynamic, IntoValue, Switchable, Value};
+use crate::widget::{MakeWidget, MakeWidgetWithTag, WidgetInstance, WidgetTag};
+use crate::widgets::image::ImageScaling;
+use crate::widgets::{Image, ProgressBar, Space};
+use crate::Lazy;
+
+/// The maximum number of images that are downloaded at the same time.
+const MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+/// Images that have been downloaded or are being downloaded, by URL.
+static CACHE: Lazy<Mutex<AHashMap<String, Dynamic<ImageLoad>>>> = Lazy::new(Mutex::default);
+
+/// The threads that download and decode images.
+static DOWNLOADS: Lazy<WorkerPool> = Lazy::new(|| WorkerPool::new(MAX_CONCURRENT_DOWNLOADS));
+
+/// An image that is downloaded from a URL in the background.
+///
+/// While the image is downloading, a placeholder widget is shown. By default,
+/// the placeholder is a spinner. If the image cannot be downloaded or decoded,
+/// the [fallback](Self::fallback) widget is shown instead.
+///
+/// Decoded images are cached by URL for the lifetime of the application, and
+/// multiple widgets displaying the same URL share a single download. At most
+/// four images are downloaded at once, and additional images wait for an
+/// earlier download to finish. Failed downloads are not cached, allowing them
+/// to be retried when the URL is shown again. Use [`UrlImage::clear_cache`] to
+/// release cached images.
+///
+/// The image formats that can be decoded are controlled by the features
+/// enabled on the [`image`](https://docs.rs/image) crate. By default, only PNG
+/// images are supported.
+///
+/// ```rust
+/// use cushy::widgets::Image;
+///
+/// let avatar = Image::from_url("https://example.com/avatar.png")
+///     .placeholder("Loading...")
+///     .fallback("No avatar");
+/// ```
+#[derive(Debug)]
+pub struct UrlImage {
+    url: String,
+    scaling: Value<ImageScaling>,
+    placeholder: Option<WidgetInstance>,
+    fallback: Option<WidgetInstance>,
+}
+
+impl UrlImage {
+    /// Returns a widget that displays the image downloaded from `url`.
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            scaling: Value::default(),
+            placeholder: None,
+            fallback: None,
+        }
+    }
+
+    /// Applies the `scaling` strategies to the downloaded image and returns
+    /// self.
+    #[must_use]
+    pub fn scaling(mut self, scaling: impl IntoValue<ImageScaling>) -> Self {
+        self.scaling = scaling.into_value();
+        self
+    }
+
+    /// Shows `placeholder` while the image is downloading and returns self.
+    #[must_use]
+    pub fn placeholder(mut self, placeholder: impl MakeWidget) -> Self {
+        self.placeholder = Some(placeholder.make_widget());
+        self
+    }
+
+    /// Shows `fallback` if the image cannot be downloaded or decoded and
+    /// returns self.
+    ///
+    /// By default, nothing is shown when an image fails to load.
+    #[must_use]
+    pub fn fallback(mut self, fallback: impl MakeWidget) -> Self {
+        self.fallback = Some(fallback.make_widget());
+        self
+    }
+
+    /// Removes all downloaded images from the cache.
+    ///
+    /// Widgets that are already displaying an image continue to display it.
+    pub fn clear_cache() {
+        CACHE.lock().clear();
+    }
+
+    /// Returns the state of loading `url`, beginning the download if it has
+    /// not already been started.
+    fn load(url: &str) -> Dynamic<ImageLoad> {
+        Self::load_with(url, |url, load| {
+            DOWNLOADS.spawn(move || {
+                let result = download(&url).map(|image| {
+                    AnyTexture::Lazy(LazyTexture::from_image(
+                        image,
+                        kludgine::wgpu::FilterMode::Linear,
+                    ))
+                });
+                finish_loading(&url, &load, result);
+            });
+        })
+    }
+
+    /// Returns the cached state of loading `url`, invoking `start` to begin
+    /// loading it if it is not already cached.
+    fn load_with(url: &str, start: impl FnOnce(String, Dynamic<ImageLoad>)) -> Dynamic<ImageLoad> {
+        let mut cache = CACHE.lock();
+        if let Some(load) = cache.get(url) {
+            return load.clone();
+        }
+
+        let load = Dynamic::new(ImageLoad::Loading);
+        cache.insert(url.to_string(), load.clone());
+        drop(cache);
+
+        start(url.to_string(), load.clone());
+        load
+    }
+}
+
+impl MakeWidgetWithTag for UrlImage {
+    fn make_with_tag(self, tag: WidgetTag) -> WidgetInstance {
+        let placeholder = self
+            .placeholder
+            .unwrap_or_else(|| ProgressBar::indeterminant().spinner().make_widget());
+        let fallback = self
+            .fallback
+            .unwrap_or_else(|| Space::clear().make_widget());
+        let scaling = self.scaling;
+        Self::load(&self.url)
+            .switcher(move |load, _| match load {
+                ImageLoad::Loading => placeholder.clone(),
+                ImageLoad::Loaded(texture) => Image::new(texture.clone())
+                    .scaling(scaling.clone())
+                    .make_widget(),
+                ImageLoad::Failed(_) => fallback.clone(),
+            })
+            .make_with_tag(tag)
+    }
+}
+
+/// Stores the result of loading `url` in `load`, removing failed downloads
+/// from the cache.
+fn finish_loading(url: &str, load: &Dynamic<ImageLoad>, result: Result<AnyTexture, UrlImageError>) {
+    let result = match result {
+        Ok(texture) => ImageLoad::Loaded(texture),
+        Err(err) => {
+            tracing::warn!("error loading image from {url}: {err}");
+            CACHE.lock().remove(url);
+            ImageLoad::Failed(err)
+        }
+    };
+    load.map_mut(|mut load| *load = result);
+}
+
+/// A pool of up to `limit` threads that run queued jobs in order.
+///
+/// Threads are spawned as jobs are queued and exit once no jobs remain.
+#[derive(Clone)]
+struct WorkerPool {
+    limit: usize,
+    state: Arc<Mutex<PoolState>>,
+}
+
+#[derive(Default)]
+struct PoolState {
+    jobs: VecDeque<Box<dyn FnOnce() + Send>>,
+    workers: usize,
+}
+
+impl WorkerPool {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            state: Arc::default(),
+        }
+    }
+
+    fn spawn(&self, job: impl FnOnce() + Send + 'static) {
+        let mut state = self.state.lock();
+        state.jobs.push_back(Box::new(job));
+        if state.workers < self.limit {
+            state.workers += 1;
+            let pool = self.clone();
+            std::thread::spawn(move || pool.work());
+        }
+    }
+
+    fn work(&self) {
+        loop {
+            let mut state = self.state.lock();
+            let Some(job) = state.jobs.pop_front() else {
+                state.workers -= 1;
+                break;
+            };
+            drop(state);
+            job();
+        }
+    }
+}
+
+/// The state of downloading an image.
+#[derive(Debug)]
+enum ImageLoad {
+    Loading,
+    Loaded(AnyTexture),
+    Failed(UrlImageError),
+}
+
+fn download(url: &str) -> Result<image::DynamicImage, UrlImageError> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|err| UrlImageError::Request(err.to_string()))?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|err| UrlImageError::Request(err.to_string()))?;
+    image::load_from_memory(&bytes).map_err(|err| UrlImageError::Decode(err.to_string()))
+}
+
+/// An error loading a [`UrlImage`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UrlImageError {
+    /// The image could not be downloaded.
+    Request(String),
+    /// The downloaded data could not be decoded as an image.
+    Decode(String),
+}
+
+impl Display for UrlImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UrlImageError::Request(err) => write!(f, "error downloading image: {err}"),
+            UrlImageError::Decode(err) => write!(f, "error decoding image: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for UrlImageError {}
+
+#[test]
+fn cache_shares_loads_by_url() {
+    use crate::reactive::value::Source;
+
+    let url = "cushy-test://cache_shares_loads_by_url";
+    let mut started = 0;
+    let first = UrlImage::load_with(url, |_, _| started += 1);
+    let second = UrlImage::load_with(url, |_, _| started += 1);
+    assert_eq!(started, 1);
+
+    first.map_mut(|mut load| *load = ImageLoad::Failed(UrlImageError::Decode(String::new())));
+    assert!(second.map_ref(|load| matches!(load, ImageLoad::Failed(_))));
+
+    // Failed loads are removed from the cache so that they can be retried.
+    finish_loading(
+        url,
+        &first,
+        Err(UrlImageError::Request(String::from("offline"))),
+    );
+    let _retry = UrlImage::load_with(url, |_, _| started += 1);
+    assert_eq!(started, 2);
+    CACHE.lock().remove(url);
+}
+
+#[test]
+fn worker_pool_limits_concurrency() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    const JOBS: usize = 8;
+    let pool = WorkerPool::new(2);
+    let running = Arc::new(AtomicUsize::new(0));
+    let most_running = Arc::new(AtomicUsize::new(0));
+    let (finished, finished_receiver) = std::sync::mpsc::channel();
+    for _ in 0..JOBS {
+        let running = running.clone();
+        let most_running = most_running.clone();
+        let finished = finished.clone();
+        pool.spawn(move || {
+            let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+            most_running.fetch_max(now_running, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+            finished.send(()).unwrap();
+        });
+    }
+    for _ in 0..JOBS {
+        finished_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("job not run");
+    }
+    let most_running = most_running.load(Ordering::SeqCst);
+    assert!((1..=2).contains(&most_running));
+}
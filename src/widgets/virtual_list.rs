@@ -13,7 +13,8 @@ use crate::figures::{IntoSigned, Point, Rect, Round, Size, Zero};
 use crate::kludgine::app::winit::event::{MouseScrollDelta, TouchPhase};
 use crate::kludgine::app::winit::window::CursorIcon;
 use crate::reactive::value::{
-    Destination, Dynamic, DynamicReader, IntoDynamic, IntoValue, MapEachCloned, Source, Watcher,
+    Destination, Dynamic, DynamicReader, IntoDynamic, IntoValue, MapEachCloned, Source, Value,
+    Watcher,
 };
 use crate::widget::{
     Callback, EventHandling, MakeWidget, MountedWidget, Widget, WidgetInstance, HANDLED, IGNORED,
@@ -21,8 +22,16 @@ use crate::widget::{
 use crate::widgets::scroll::ScrollBar;
 use crate::window::DeviceId;
 
+/// The number of rows that have scrolled out of view that are kept for reuse
+/// by default.
+const DEFAULT_RECYCLE_LIMIT: usize = 32;
+
 #[derive(Debug)]
-struct RowMaker(Callback<usize, WidgetInstance>);
+struct RowMaker {
+    make_row: Callback<usize, WidgetInstance>,
+    recycled: VecDeque<(usize, WidgetInstance)>,
+    recycle_limit: usize,
+}
 
 impl RowMaker {
     fn make_row(
@@ -30,10 +39,27 @@ impl RowMaker {
         index: usize,
         context: &mut LayoutContext<'_, '_, '_, '_>,
     ) -> VirtualListItem {
+        let row = if let Some(position) = self.recycled.iter().position(|(row, _)| *row == index) {
+            self.recycled.remove(position).expect("valid position").1
+        } else {
+            self.make_row.invoke(index)
+        };
         VirtualListItem {
             index,
-            mounted: context.push_child(self.0.invoke(index)),
+            mounted: context.push_child(row),
+        }
+    }
+
+    fn recycle(&mut self, item: &VirtualListItem, context: &mut LayoutContext<'_, '_, '_, '_>) {
+        context.remove_child(&item.mounted);
+        if self.recycle_limit == 0 {
+            return;
+        }
+        while self.recycled.len() >= self.recycle_limit {
+            self.recycled.pop_front();
         }
+        self.recycled
+            .push_back((item.index, item.mounted.instance().clone()));
     }
 }
 
@@ -68,6 +94,9 @@ pub struct VirtualList {
     item_size: Dynamic<Size<UPx>>,
 
     visible_range: Dynamic<Range<usize>>,
+
+    anchor: Value<Option<usize>>,
+    anchor_offset: Option<(usize, Px)>,
 }
 
 impl VirtualList {
@@ -75,8 +104,10 @@ impl VirtualList {
     /// each row as needed by invoking `make_row`.
     ///
     /// `make_row` will be called each time a new row becomes visible. As rows
-    /// are no longer visible, they will be freed, ensuring a minimum number of
-    /// widgets is kept in memory at any given time.
+    /// are no longer visible, they will be unmounted, ensuring a minimum number
+    /// of widgets is kept in memory at any given time. A limited number of
+    /// unmounted rows are kept for reuse. See
+    /// [`recycle_limit()`](Self::recycle_limit) for more information.
     ///
     /// Each row will be sized to match the first visible row. To ensure all
     /// rows have a consistent size, use the [`Resize`](../Resize) widget.
@@ -85,7 +116,11 @@ impl VirtualList {
         MakeRow: FnMut(usize) -> Row + Send + 'static,
         Row: MakeWidget,
     {
-        let make_row = RowMaker(Callback::new(move |row| make_row(row).make_widget()));
+        let make_row = RowMaker {
+            make_row: Callback::new(move |row| make_row(row).make_widget()),
+            recycled: VecDeque::new(),
+            recycle_limit: DEFAULT_RECYCLE_LIMIT,
+        };
         let scroll = Dynamic::<Point<UPx>>::default();
         let item_size = Dynamic::new(Size::ZERO);
         let item_count = item_count.into_value().into_dynamic().into_reader();
@@ -141,9 +176,38 @@ impl VirtualList {
             item_size,
             item_count,
             visible_range: Dynamic::default(),
+
+            anchor: Value::Constant(None),
+            anchor_offset: None,
         }
     }
 
+    /// Keeps the row at the index contained in `anchor` at the same location
+    /// within this list when the index changes, and returns self.
+    ///
+    /// When rows are inserted or removed before the anchored row, such as when
+    /// older messages are loaded above a chat history, update `anchor` with
+    /// the row's new index. The list will scroll by the distance the row moved
+    /// so that the visible rows do not jump.
+    #[must_use]
+    pub fn anchor(mut self, anchor: impl IntoValue<Option<usize>>) -> Self {
+        self.anchor = anchor.into_value();
+        self
+    }
+
+    /// Sets the maximum number of rows that have scrolled out of view to keep
+    /// for reuse and returns self.
+    ///
+    /// When a row that was recently scrolled out of view becomes visible
+    /// again, its previously created widget is remounted instead of invoking
+    /// `make_row` again. Setting this to `0` disables reusing rows. By default,
+    /// up to 32 rows are kept.
+    #[must_use]
+    pub fn recycle_limit(mut self, limit: usize) -> Self {
+        self.make_row.recycle_limit = limit;
+        self
+    }
+
     /// Returns a [`Watcher`] that when notified will force this list to reload
     /// its contents, including the currently visible rows.
     pub const fn content_watcher(&self) -> &Watcher {
@@ -186,7 +250,7 @@ impl VirtualList {
 
     fn clear(&mut self, context: &mut LayoutContext<'_, '_, '_, '_>) {
         for item in self.items.drain(..) {
-            context.remove_child(&item.mounted);
+            self.make_row.recycle(&item, context);
         }
     }
 
@@ -246,6 +310,9 @@ impl VirtualList {
         if generation != self.contents_generation {
             self.contents_generation = generation;
             self.clear(context);
+            // The contents have changed, so none of the previously created
+            // rows can be reused.
+            self.make_row.recycled.clear();
         }
         let mut item_size = self.calculate_item_size(available_space, context).ceil();
 
@@ -267,12 +334,14 @@ impl VirtualList {
         let max_scroll_x = item_size.width.saturating_sub(new_control_size.width);
         let max_scroll_y = content_height.saturating_sub(new_control_size.height);
         let scroll = scroll.min(Point::new(max_scroll_x, max_scroll_y));
+        let scroll = self.preserve_anchor(scroll, item_size.height, max_scroll_y, context);
 
-        let start_item = (scroll.y.floor() / item_size.height).floor().get() as usize;
-        let end_item = ((scroll.y.ceil() + new_control_size.height) / item_size.height)
-            .ceil()
-            .get() as usize;
-        let end_item = end_item.min(item_count - 1);
+        let (start_item, end_item) = visible_rows(
+            scroll.y,
+            new_control_size.height,
+            item_size.height,
+            item_count,
+        );
 
         self.visible_range.set(start_item..end_item);
 
@@ -292,14 +361,16 @@ impl VirtualList {
                 .front()
                 .map_or(false, |item| item.index < start_item)
             {
-                context.remove_child(&self.items.pop_front().expect("at least one item").mounted);
+                let item = self.items.pop_front().expect("at least one item");
+                self.make_row.recycle(&item, context);
             }
             while self
                 .items
                 .back()
                 .map_or(false, |item| item.index > end_item)
             {
-                context.remove_child(&self.items.pop_back().expect("at least one item").mounted);
+                let item = self.items.pop_back().expect("at least one item");
+                self.make_row.recycle(&item, context);
             }
             // no extend front :(
             for item in (start_item..first).rev() {
@@ -331,6 +402,27 @@ impl VirtualList {
         new_control_size
     }
 
+    /// Returns `scroll` adjusted to keep the anchored row at the same location
+    /// it was displayed at during the last layout.
+    fn preserve_anchor(
+        &mut self,
+        mut scroll: Point<UPx>,
+        item_height: UPx,
+        max_scroll_y: UPx,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Point<UPx> {
+        let anchor = self.anchor.get_tracking_invalidate(context);
+        if let (Some(anchor), Some((previous, offset))) = (anchor, self.anchor_offset) {
+            if anchor != previous {
+                scroll.y = anchored_scroll(item_height, anchor, offset, max_scroll_y);
+                self.scroll.set(scroll);
+            }
+        }
+        self.anchor_offset =
+            anchor.map(|anchor| (anchor, anchor_offset(item_height, anchor, scroll.y)));
+        scroll
+    }
+
     fn calculate_item_size(
         &mut self,
         available_space: Size<ConstraintLimit>,
@@ -448,3 +540,111 @@ impl Widget for VirtualList {
         }
     }
 }
+
+/// Returns the offset of the top of the row at `index` from the top of the
+/// list's contents.
+fn row_top(item_height: UPx, index: usize) -> UPx {
+    item_height.saturating_mul(UPx::new(u32::try_from(index).unwrap_or(u32::MAX)))
+}
+
+/// Returns the distance from the top of the visible area to the top of the row
+/// at `index` when scrolled to `scroll_y`.
+fn anchor_offset(item_height: UPx, index: usize, scroll_y: UPx) -> Px {
+    let top = row_top(item_height, index);
+    let to_signed = |distance: UPx| Px::new(i32::try_from(distance.get()).unwrap_or(i32::MAX));
+    if top >= scroll_y {
+        to_signed(top - scroll_y)
+    } else {
+        -to_signed(scroll_y - top)
+    }
+}
+
+/// Returns the vertical scroll that displays the row at `index` `offset`
+/// pixels from the top of the visible area, limited to `max_scroll_y`.
+fn anchored_scroll(item_height: UPx, index: usize, offset: Px, max_scroll_y: UPx) -> UPx {
+    let top = row_top(item_height, index);
+    let distance = offset.saturating_abs().into_unsigned();
+    let scroll = if offset >= 0 {
+        top.saturating_sub(distance)
+    } else {
+        top.saturating_add(distance)
+    };
+    scroll.min(max_scroll_y)
+}
+
+/// Returns the first and last rows that are at least partially visible when
+/// the contents are scrolled to `scroll_y`.
+fn visible_rows(
+    scroll_y: UPx,
+    control_height: UPx,
+    item_height: UPx,
+    item_count: usize,
+) -> (usize, usize) {
+    let item_height = item_height.max(UPx::new(1));
+    let end = (scroll_y.ceil().saturating_add(control_height) / item_height)
+        .ceil()
+        .get() as usize;
+    let end = end.min(item_count.saturating_sub(1));
+    let start = ((scroll_y.floor() / item_height).floor().get() as usize).min(end);
+    (start, end)
+}
+
+#[test]
+fn visible_rows_cover_the_visible_area() {
+    // Scrolled to the top, 100px of 10px rows shows rows 0 through 10, the
+    // last of which is needed once any fractional scrolling occurs.
+    assert_eq!(
+        visible_rows(UPx::ZERO, UPx::new(100), UPx::new(10), 1_000),
+        (0, 10)
+    );
+    // Partially scrolled into row 2.
+    assert_eq!(
+        visible_rows(UPx::new(25), UPx::new(100), UPx::new(10), 1_000),
+        (2, 12)
+    );
+    // The last row is limited by the number of rows.
+    assert_eq!(
+        visible_rows(UPx::new(950), UPx::new(100), UPx::new(10), 100),
+        (95, 99)
+    );
+    // Zero-height rows do not divide by zero.
+    assert_eq!(visible_rows(UPx::ZERO, UPx::new(100), UPx::ZERO, 5), (0, 4));
+}
+
+#[test]
+fn row_top_saturates() {
+    assert_eq!(row_top(UPx::new(10), 3), UPx::new(30));
+    assert_eq!(row_top(UPx::new(10), usize::MAX), UPx::MAX);
+    assert_eq!(row_top(UPx::MAX, 2), UPx::MAX);
+}
+
+#[test]
+fn anchors_scroll_to_moved_index() {
+    let max_scroll = UPx::new(10_000);
+    // Row 5 is displayed 20px below the top of the visible area.
+    let offset = anchor_offset(UPx::new(10), 5, UPx::new(30));
+    assert_eq!(offset, Px::new(20));
+    // Three rows were inserted above it, so the list scrolls by three rows.
+    assert_eq!(
+        anchored_scroll(UPx::new(10), 8, offset, max_scroll),
+        UPx::new(60)
+    );
+
+    // A row partially scrolled above the top keeps its negative offset.
+    let offset = anchor_offset(UPx::new(10), 2, UPx::new(25));
+    assert_eq!(offset, Px::new(-5));
+    assert_eq!(
+        anchored_scroll(UPx::new(10), 4, offset, max_scroll),
+        UPx::new(45)
+    );
+
+    // Scrolling is limited to the range of the contents.
+    assert_eq!(
+        anchored_scroll(UPx::new(10), 0, Px::new(50), max_scroll),
+        UPx::ZERO
+    );
+    assert_eq!(
+        anchored_scroll(UPx::new(10), usize::MAX, Px::ZERO, max_scroll),
+        max_scroll
+    );
+}
@@ -0,0 +1,228 @@
+//! A widget that shows, hides, or collapses its contents.
+
+use std::time::Duration;
+
+use figures::units::UPx;
+use figures::{FloatConversion, IntoSigned, Rect, Size};
+
+use crate::animation::{AnimationHandle, AnimationTarget, Spawn, ZeroToOne};
+use crate::context::{AsEventContext, EventContext, GraphicsContext, LayoutContext};
+use crate::reactive::value::{Destination, Dynamic, IntoValue, Source, Value};
+use crate::styles::components::{EasingIn, EasingOut};
+use crate::widget::{MakeWidget, Widget, WidgetRef};
+use crate::ConstraintLimit;
+
+/// Whether a [`Visible`] widget's contents are shown and whether they occupy
+/// space in the layout.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum Visibility {
+    /// The contents are shown.
+    #[default]
+    Visible,
+    /// The contents are not shown, but still occupy their space in the layout.
+    Hidden,
+    /// The contents are not shown and do not occupy any space in the layout.
+    Collapsed,
+}
+
+impl Visibility {
+    /// Returns true if this is [`Visibility::Visible`].
+    #[must_use]
+    pub const fn is_visible(self) -> bool {
+        matches!(self, Self::Visible)
+    }
+
+    fn opacity(self) -> ZeroToOne {
+        if self.is_visible() {
+            ZeroToOne::ONE
+        } else {
+            ZeroToOne::ZERO
+        }
+    }
+
+    fn extent(self) -> ZeroToOne {
+        if self == Self::Collapsed {
+            ZeroToOne::ZERO
+        } else {
+            ZeroToOne::ONE
+        }
+    }
+}
+
+impl From<bool> for Visibility {
+    /// Returns [`Visibility::Visible`] for true and [`Visibility::Collapsed`]
+    /// for false.
+    fn from(visible: bool) -> Self {
+        if visible {
+            Self::Visible
+        } else {
+            Self::Collapsed
+        }
+    }
+}
+
+/// A widget that shows, hides, or collapses its contents based on a
+/// [`Visibility`].
+///
+/// Contents that are hidden or collapsed are not drawn and do not receive
+/// mouse input. Unlike [`Collapse`](crate::widgets::Collapse), which only
+/// releases space along a single axis, this widget can also hide its contents
+/// while keeping their space reserved.
+///
+/// By default, changes are applied immediately. Use
+/// [`animated()`](Self::animated) to fade the contents in and out and to
+/// smoothly grow and shrink the space collapsed contents occupy.
+#[derive(Debug)]
+pub struct Visible {
+    child: WidgetRef,
+    visibility: Value<Visibility>,
+    transition: Duration,
+    applied: Option<Visibility>,
+    opacity: Dynamic<ZeroToOne>,
+    extent: Dynamic<ZeroToOne>,
+    _animation: Option<AnimationHandle>,
+}
+
+impl Visible {
+    /// Returns a widget that shows `child` according to `visibility`.
+    #[must_use]
+    pub fn new(visibility: impl IntoValue<Visibility>, child: impl MakeWidget) -> Self {
+        Self {
+            child: WidgetRef::new(child),
+            visibility: visibility.into_value(),
+            transition: Duration::ZERO,
+            applied: None,
+            opacity: Dynamic::new(ZeroToOne::ONE),
+            extent: Dynamic::new(ZeroToOne::ONE),
+            _animation: None,
+        }
+    }
+
+    /// Animates changes in visibility over `duration` and returns self.
+    #[must_use]
+    pub fn animated(mut self, duration: Duration) -> Self {
+        self.transition = duration;
+        self
+    }
+
+    fn apply_visibility(&mut self, context: &mut LayoutContext<'_, '_, '_, '_>) {
+        let visibility = self.visibility.get_tracking_invalidate(context);
+        if self.applied == Some(visibility) {
+            return;
+        }
+
+        let opacity = visibility.opacity();
+        let extent = visibility.extent();
+        // The initial visibility is applied immediately.
+        if self.applied.is_none() || self.transition.is_zero() {
+            self._animation = None;
+            self.opacity.set(opacity);
+            self.extent.set(extent);
+        } else {
+            let easing = if visibility.is_visible() {
+                context.get(&EasingIn)
+            } else {
+                context.get(&EasingOut)
+            };
+            self._animation = Some(
+                (
+                    self.opacity.transition_to(opacity),
+                    self.extent.transition_to(extent),
+                )
+                    .over(self.transition)
+                    .with_easing(easing)
+                    .spawn(),
+            );
+        }
+        self.applied = Some(visibility);
+    }
+}
+
+impl Widget for Visible {
+    fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let opacity = self.opacity.get_tracking_redraw(context);
+        if opacity > 0. {
+            context.apply_opacity(opacity);
+            let child = self.child.mounted(&mut context.as_event_context());
+            context.for_other(&child).redraw();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<UPx> {
+        self.apply_visibility(context);
+        let extent = *self.extent.get_tracking_invalidate(context);
+
+        let child = self.child.mounted(&mut context.as_event_context());
+        let size = context.for_other(&child).layout(available_space);
+        let size = if extent < 1. {
+            size.map(|dimension| UPx::from_float(dimension.into_float() * extent))
+        } else {
+            size
+        };
+        context.set_child_layout(&child, Rect::from(size.into_signed()));
+        size
+    }
+
+    fn unmounted(&mut self, context: &mut EventContext<'_>) {
+        self.child.unmount_in(context);
+    }
+
+    fn summarize(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Visible")
+            .field("visibility", &self.visibility)
+            .field("child", &self.child)
+            .finish()
+    }
+}
+
+#[test]
+fn hidden_keeps_space_and_collapsed_releases_it() {
+    use figures::units::Px;
+    use figures::Point;
+    use kludgine::Color;
+
+    use crate::widgets::Space;
+
+    let visibility = Dynamic::new(Visibility::Visible);
+    let mut recorder = Space::colored(Color::RED)
+        .height(Px::new(8))
+        .visible(visibility.clone())
+        .and(Space::colored(Color::BLUE).expand())
+        .into_rows()
+        .gutter(Px::ZERO)
+        .build_recorder()
+        .size(Size::new(UPx::new(8), UPx::new(16)))
+        .finish()
+        .expect("error creating recorder");
+    let top = Point::new(UPx::new(4), UPx::new(2));
+    let bottom = Point::new(UPx::new(4), UPx::new(14));
+    recorder.assert_pixel_color(top, Color::RED, "visible");
+    recorder.assert_pixel_color(bottom, Color::BLUE, "sibling");
+
+    visibility.set(Visibility::Hidden);
+    recorder.refresh().expect("error rendering");
+    let hidden = recorder.pixel_color(top);
+    assert_ne!(hidden, Color::RED, "hidden contents were drawn");
+    assert_ne!(hidden, Color::BLUE, "hidden contents released their space");
+    recorder.assert_pixel_color(bottom, Color::BLUE, "sibling");
+
+    visibility.set(Visibility::Collapsed);
+    recorder.refresh().expect("error rendering");
+    recorder.assert_pixel_color(top, Color::BLUE, "collapsed");
+    recorder.assert_pixel_color(bottom, Color::BLUE, "sibling");
+
+    visibility.set(Visibility::Visible);
+    recorder.refresh().expect("error rendering");
+    recorder.assert_pixel_color(top, Color::RED, "visible");
+}
+
+#[test]
+fn visibility_from_bool() {
+    assert_eq!(Visibility::from(true), Visibility::Visible);
+    assert_eq!(Visibility::from(false), Visibility::Collapsed);
+    assert!(!Visibility::Hidden.is_visible());
+}
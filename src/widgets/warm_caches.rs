@@ -0,0 +1,122 @@
+use figures::units::Px;
+use figures::{Point, Rect, Size};
+use kludgine::text::Text;
+use kludgine::{AnyTexture, Color};
+
+use crate::animation::ZeroToOne;
+use crate::context::GraphicsContext;
+use crate::reactive::value::{Destination, Dynamic, DynamicReader, Source};
+use crate::widget::{MakeWidget, WidgetRef, WrapperWidget};
+
+/// A set of assets to prepare before they are first displayed.
+///
+/// Shaping text and uploading images to the GPU happens the first time they
+/// are drawn. When the content that will be shown after a splash screen is
+/// known ahead of time, this work can be performed while the splash screen is
+/// displayed by wrapping the splash screen in a [`WarmCaches`] widget.
+///
+/// Render pipelines are created by the renderer when each window opens and
+/// are not affected by a warmup.
+#[derive(Debug, Clone, Default)]
+pub struct CacheWarmup {
+    text: Vec<String>,
+    textures: Vec<AnyTexture>,
+    warm: Dynamic<bool>,
+}
+
+impl CacheWarmup {
+    /// Returns an empty set of assets.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `text` to the set of text to prepare and returns self.
+    #[must_use]
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text.push(text.into());
+        self
+    }
+
+    /// Adds `texture` to the set of textures to upload and returns self.
+    #[must_use]
+    pub fn with_texture(mut self, texture: impl Into<AnyTexture>) -> Self {
+        self.textures.push(texture.into());
+        self
+    }
+
+    /// Returns a reader that becomes true once these assets have been
+    /// prepared.
+    #[must_use]
+    pub fn is_warm(&self) -> DynamicReader<bool> {
+        self.warm.create_reader()
+    }
+}
+
+/// A widget that prepares a [`CacheWarmup`] the first time it is drawn.
+///
+/// Text is prepared using the font settings in effect for this widget. The
+/// assets are drawn invisibly behind the child.
+#[derive(Debug)]
+pub struct WarmCaches {
+    child: WidgetRef,
+    warmup: CacheWarmup,
+}
+
+impl WarmCaches {
+    /// Returns a widget that displays `child` and prepares `warmup` the first
+    /// time it is drawn.
+    #[must_use]
+    pub fn new(warmup: CacheWarmup, child: impl MakeWidget) -> Self {
+        Self {
+            child: WidgetRef::new(child),
+            warmup,
+        }
+    }
+}
+
+impl WrapperWidget for WarmCaches {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn redraw_background(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        if self.warmup.warm.get() {
+            return;
+        }
+
+        for text in &self.warmup.text {
+            context
+                .gfx
+                .draw_text(Text::<Px>::new(text, Color::CLEAR_BLACK));
+        }
+
+        let target = Rect::new(Point::default(), Size::new(Px::new(1), Px::new(1)));
+        for texture in &self.warmup.textures {
+            context.gfx.draw_texture(texture, target, ZeroToOne::ZERO);
+        }
+
+        self.warmup.warm.set(true);
+    }
+}
+
+#[test]
+fn warms_on_first_draw_without_showing_assets() {
+    use figures::units::UPx;
+
+    use crate::widgets::Space;
+
+    let warmup = CacheWarmup::new().with_text("Loading complete");
+    let is_warm = warmup.is_warm();
+    let mut recorder = WarmCaches::new(warmup, Space::clear().expand())
+        .build_recorder()
+        .with_alpha()
+        .size(Size::squared(UPx::new(32)))
+        .finish()
+        .expect("error creating recorder");
+    assert!(!is_warm.get());
+
+    recorder.refresh().expect("error rendering");
+    assert!(is_warm.get());
+    assert_eq!(recorder.pixel_color(Point::squared(UPx::new(4))).alpha(), 0);
+}
@@ -88,9 +88,7 @@ impl Wrap {
 
 impl Widget for Wrap {
     fn redraw(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
-        for child in self.mounted.children() {
-            context.for_other(child).redraw();
-        }
+        context.redraw_children(self.mounted.children());
     }
 
     #[allow(clippy::too_many_lines)]
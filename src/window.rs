@@ -1,6 +1,7 @@
 //! Types for displaying a [`Widget`](crate::widget::Widget) inside of a desktop
 //! window.
 
+use std::any::Any;
 use std::cell::RefCell;
 use std::collections::hash_map;
 use std::ffi::OsStr;
@@ -14,10 +15,10 @@ use std::string::ToString;
 use std::sync::{mpsc, Arc, OnceLock};
 use std::time::{Duration, Instant};
 
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
 use alot::LotId;
 use arboard::Clipboard;
-use figures::units::{Px, UPx};
+use figures::units::{Lp, Px, UPx};
 use figures::{
     FloatConversion, Fraction, IntoSigned, IntoUnsigned, Point, Ranged, Rect, Round, ScreenScale,
     Size, UPx2D, Zero,
@@ -26,11 +27,12 @@ use image::{DynamicImage, RgbImage, RgbaImage};
 use intentional::{Assert, Cast};
 use kludgine::app::winit::dpi::{PhysicalPosition, PhysicalSize};
 use kludgine::app::winit::event::{
-    ElementState, Ime, Modifiers, MouseButton, MouseScrollDelta, TouchPhase,
+    ElementState, Ime, Modifiers, MouseButton, MouseScrollDelta, Touch, TouchPhase,
 };
 use kludgine::app::winit::keyboard::{
     Key, KeyLocation, ModifiersState, NamedKey, NativeKeyCode, PhysicalKey, SmolStr,
 };
+use kludgine::app::winit::monitor::MonitorHandle;
 use kludgine::app::winit::window::{Cursor, Fullscreen, Icon, WindowButtons, WindowLevel};
 use kludgine::app::{winit, WindowAttributes, WindowBehavior as _};
 use kludgine::cosmic_text::{fontdb, Family, FamilyOwned};
@@ -39,7 +41,9 @@ use kludgine::shapes::Shape;
 use kludgine::wgpu::{self, CompositeAlphaMode, COPY_BYTES_PER_ROW_ALIGNMENT};
 use kludgine::{Color, DrawableExt, Kludgine, KludgineId, Origin, Texture};
 use parking_lot::{Mutex, MutexGuard};
-use sealed::{Ize, PreShowCallback, WindowExecute};
+use sealed::{
+    FullscreenChange, Ize, Ownership, PrePresentCallback, PreShowCallback, WindowExecute,
+};
 use tracing::Level;
 use unicode_segmentation::UnicodeSegmentation;
 
@@ -49,24 +53,61 @@ use crate::animation::{
 use crate::app::{Application, Cushy, Open, PendingApp, Run};
 use crate::context::sealed::{InvalidationStatus, Trackable as _};
 use crate::context::{
-    AsEventContext, EventContext, Exclusive, GraphicsContext, LayoutContext, Trackable,
-    WidgetContext,
+    AsEventContext, EventContext, Exclusive, FocusDirection, GraphicsContext, LayoutContext,
+    Trackable, WidgetContext,
 };
+use crate::debug::{dominant_colors, ContrastIssue, ContrastReport, TreeSnapshot};
 use crate::fonts::FontCollection;
 use crate::graphics::{FontState, Graphics};
 use crate::reactive::value::{
     Destination, Dynamic, DynamicReader, IntoDynamic, IntoValue, Source, Tracked, Value,
 };
-use crate::styles::{Edges, FontFamilyList, ThemePair};
+use crate::styles::{ColorExt, DimensionRange, Edges, FontFamilyList, ThemePair};
+use crate::telemetry::{self, FrameTimeTracker, TelemetryEvent};
 use crate::tree::Tree;
 use crate::utils::ModifiersExt;
 use crate::widget::{
-    EventHandling, MakeWidget, MountedWidget, Notify, OnceCallback, RootBehavior, SharedCallback,
-    WidgetId, WidgetInstance, HANDLED, IGNORED,
+    Callback, EventHandling, MakeWidget, MountedWidget, Notify, OnceCallback, RootBehavior,
+    SharedCallback, WidgetId, WidgetInstance, WidgetRef, HANDLED, IGNORED,
+};
+use crate::widgets::drag::{
+    handle_file_drop, render_drag_ghost, update_file_hover, ActiveDrag, FileHover,
 };
-use crate::widgets::shortcuts::{ShortcutKey, ShortcutMap};
+use crate::widgets::layers::Direction;
 use crate::window::sealed::WindowCommand;
-use crate::{App, ConstraintLimit, MaybeLocalized};
+use crate::{idle, App, ConstraintLimit, MaybeLocalized};
+
+mod announcements;
+mod decorations;
+mod fuzz;
+mod gestures;
+mod hit_test;
+mod magnifier;
+mod persistence;
+mod popup;
+mod post_process;
+mod presentation;
+mod registry;
+mod virtual_app;
+
+pub use self::announcements::{Announcement, Politeness};
+use self::decorations::ClientDecorated;
+pub use self::decorations::ClientDecorations;
+pub use self::fuzz::{FuzzEvent, FuzzFailure, FuzzViolation, InputFuzzer};
+use self::gestures::GestureRecognizer;
+pub use self::gestures::{Gesture, GestureShortcuts};
+pub use self::hit_test::{HitTestMask, MaskShape};
+pub use self::magnifier::{Magnifier, MagnifierFocus};
+pub use self::persistence::{WindowGeometry, WindowPersistence};
+pub use self::popup::Popup;
+use self::post_process::PostProcessor;
+pub use self::post_process::{PostProcess, MAX_POST_PROCESS_UNIFORMS};
+pub use self::presentation::{PointerHighlight, Presenter};
+use self::registry::MessageHandlers;
+pub use self::registry::WindowsRegistry;
+pub(crate) use self::virtual_app::VirtualWindowRequests;
+pub use self::virtual_app::{VirtualApp, VirtualMonitor, VirtualWindowId};
+pub use crate::widgets::shortcuts::{Shortcut, ShortcutConflict, ShortcutKey, ShortcutMap};
 
 /// A platform-dependent window implementation.
 pub trait PlatformWindowImplementation {
@@ -255,6 +296,13 @@ pub trait PlatformWindow {
     fn occluded(&self) -> &Dynamic<bool>;
     /// Returns the current inner size of the window.
     fn inner_size(&self) -> &Dynamic<Size<UPx>>;
+    /// Returns the dynamic that is synchronized with the window's effective
+    /// scale, if this window tracks it.
+    ///
+    /// The default implementation returns `None`.
+    fn effective_scale(&self) -> Option<&Dynamic<Fraction>> {
+        None
+    }
     /// Returns the current outer size of the window.
     fn outer_size(&self) -> Size<UPx>;
     /// Returns the shared application resources.
@@ -293,6 +341,58 @@ pub trait PlatformWindow {
     fn winit(&self) -> Option<&Arc<winit::window::Window>>;
 }
 
+/// A frame that is about to be presented to a window.
+///
+/// See [`Window::on_pre_present`] for more information.
+pub struct PrePresent<'a> {
+    encoder: &'a mut wgpu::CommandEncoder,
+    view: &'a wgpu::TextureView,
+    size: Size<UPx>,
+    device: &'a wgpu::Device,
+    queue: &'a wgpu::Queue,
+}
+
+impl PrePresent<'_> {
+    /// The format of the texture returned from [`view()`](Self::view).
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// Returns the command encoder to record commands into.
+    ///
+    /// The recorded commands are submitted after the callback returns and
+    /// before the window's contents are rendered to its surface.
+    pub fn encoder(&mut self) -> &mut wgpu::CommandEncoder {
+        self.encoder
+    }
+
+    /// Returns the view of the texture that is composited on top of the
+    /// window's contents.
+    ///
+    /// The texture has been cleared to transparent, has the format
+    /// [`Self::FORMAT`], and is the size of the window's surface.
+    #[must_use]
+    pub const fn view(&self) -> &wgpu::TextureView {
+        self.view
+    }
+
+    /// Returns the size of the texture returned from [`view()`](Self::view).
+    #[must_use]
+    pub const fn size(&self) -> Size<UPx> {
+        self.size
+    }
+
+    /// Returns the device the window is rendered with.
+    #[must_use]
+    pub const fn device(&self) -> &wgpu::Device {
+        self.device
+    }
+
+    /// Returns the queue the window is rendered with.
+    #[must_use]
+    pub const fn queue(&self) -> &wgpu::Queue {
+        self.queue
+    }
+}
+
 /// A currently running Cushy window.
 pub struct RunningWindow<W> {
     window: W,
@@ -302,6 +402,7 @@ pub struct RunningWindow<W> {
     focused: Dynamic<bool>,
     occluded: Dynamic<bool>,
     inner_size: Dynamic<Size<UPx>>,
+    effective_scale: Dynamic<Fraction>,
     close_requested: Option<SharedCallback<(), bool>>,
 }
 
@@ -318,6 +419,7 @@ where
         focused: &Dynamic<bool>,
         occluded: &Dynamic<bool>,
         inner_size: &Dynamic<Size<UPx>>,
+        effective_scale: &Dynamic<Fraction>,
         close_requested: &Option<SharedCallback<(), bool>>,
     ) -> Self {
         Self {
@@ -328,10 +430,28 @@ where
             focused: focused.clone(),
             occluded: occluded.clone(),
             inner_size: inner_size.clone(),
+            effective_scale: effective_scale.clone(),
             close_requested: close_requested.clone(),
         }
     }
 
+    /// Asserts in debug builds that the current thread is the thread this
+    /// window's events are handled on.
+    ///
+    /// The underlying platform window must only be accessed from its thread.
+    /// Virtual windows have no platform window and may be driven from any
+    /// thread.
+    fn debug_assert_window_thread(&self) {
+        debug_assert!(
+            self.window.winit().is_none()
+                || self
+                    .invalidation_status
+                    .thread()
+                    .map_or(true, |thread| thread == std::thread::current().id()),
+            "the platform window must only be accessed from its window's thread"
+        );
+    }
+
     /// Returns the [`KludgineId`] of this window.
     ///
     /// Each window has its own unique `KludgineId`.
@@ -379,6 +499,17 @@ where
         &self.inner_size
     }
 
+    /// Returns a dynamic that is updated whenever this window's effective
+    /// scale changes.
+    ///
+    /// The effective scale changes when the window is moved to a display with
+    /// a different DPI scale, or when the window's zoom or content scale
+    /// override is changed.
+    #[must_use]
+    pub const fn effective_scale(&self) -> &Dynamic<Fraction> {
+        &self.effective_scale
+    }
+
     /// Returns a locked mutex guard to the OS's clipboard, if one was able to be
     /// initialized when the window opened.
     #[must_use]
@@ -439,6 +570,10 @@ where
         &self.inner_size
     }
 
+    fn effective_scale(&self) -> Option<&Dynamic<Fraction>> {
+        Some(&self.effective_scale)
+    }
+
     fn outer_size(&self) -> Size<UPx> {
         self.window.outer_size()
     }
@@ -468,34 +603,42 @@ where
     }
 
     fn set_ime_allowed(&self, allowed: bool) {
+        self.debug_assert_window_thread();
         self.window.set_ime_allowed(allowed);
     }
 
     fn set_ime_purpose(&self, purpose: winit::window::ImePurpose) {
+        self.debug_assert_window_thread();
         self.window.set_ime_purpose(purpose);
     }
 
     fn set_cursor(&mut self, cursor: Cursor) {
+        self.debug_assert_window_thread();
         self.window.set_cursor(cursor);
     }
 
     fn set_min_inner_size(&self, min_size: Option<Size<UPx>>) {
+        self.debug_assert_window_thread();
         self.window.set_min_inner_size(min_size);
     }
 
     fn set_max_inner_size(&self, max_size: Option<Size<UPx>>) {
+        self.debug_assert_window_thread();
         self.window.set_max_inner_size(max_size);
     }
 
     fn request_inner_size(&mut self, inner_size: Size<UPx>) -> Option<Size<UPx>> {
+        self.debug_assert_window_thread();
         self.window.request_inner_size(inner_size)
     }
 
     fn set_ime_location(&self, location: Rect<Px>) {
+        self.debug_assert_window_thread();
         self.window.set_ime_location(location);
     }
 
     fn winit(&self) -> Option<&Arc<winit::window::Window>> {
+        self.debug_assert_window_thread();
         self.window.winit()
     }
 }
@@ -548,17 +691,30 @@ where
     attributes: WindowAttributes,
     on_closed: Option<OnceCallback>,
     on_init: Option<PreShowCallback>,
+    on_pre_present: Option<PrePresentCallback>,
     on_open: Option<OnceCallback<WindowHandle>>,
+    on_first_frame: Option<OnceCallback>,
+    on_announcement: Option<Callback<Announcement>>,
+    spatial_navigation: Value<bool>,
+    client_decorations: Option<Value<ClientDecorations>>,
     inner_size: Option<Dynamic<Size<UPx>>>,
+    size_to_content: Option<SizeToContent>,
     zoom: Option<Dynamic<Fraction>>,
+    content_scale: Option<Value<Option<Fraction>>>,
+    effective_scale: Option<Dynamic<Fraction>>,
     occluded: Option<Dynamic<bool>>,
     focused: Option<Dynamic<bool>>,
     theme_mode: Option<Value<ThemeMode>>,
     content_protected: Option<Value<bool>>,
     cursor_hittest: Option<Value<bool>>,
+    hit_test_mask: Option<Value<HitTestMask>>,
+    clear_color: Option<Value<Color>>,
+    magnifier: Option<Magnifier>,
+    pointer_highlight: Option<PointerHighlight>,
     cursor_visible: Option<Value<bool>>,
     cursor_position: Option<Dynamic<Point<Px>>>,
     window_level: Option<Value<WindowLevel>>,
+    owner: Option<Ownership>,
     decorated: Option<Value<bool>>,
     maximized: Option<Dynamic<bool>>,
     minimized: Option<Dynamic<bool>>,
@@ -568,13 +724,18 @@ where
     outer_size: Option<Dynamic<Size<UPx>>>,
     inner_position: Option<Dynamic<Point<Px>>>,
     outer_position: Option<Dynamic<Point<Px>>>,
+    current_monitor: Option<Dynamic<Option<MonitorHandle>>>,
+    persistence: Option<WindowPersistence>,
     close_requested: Option<SharedCallback<(), bool>>,
     icon: Option<Value<Option<RgbaImage>>>,
     modifiers: Option<Dynamic<Modifiers>>,
     enabled_buttons: Option<Value<WindowButtons>>,
     fullscreen: Option<Value<Option<Fullscreen>>>,
     shortcuts: Value<ShortcutMap>,
+    gesture_shortcuts: GestureShortcuts,
     on_file_drop: Option<Notify<FileDrop>>,
+    on_file_hover: Option<Notify<FileDrop>>,
+    message_handlers: MessageHandlers,
 }
 
 impl<Behavior> Default for Window<Behavior>
@@ -628,6 +789,9 @@ where
             title: Value::Constant(title.into()),
             attributes: WindowAttributes::default(),
             on_open: None,
+            on_first_frame: None,
+            on_announcement: None,
+            spatial_navigation: Value::Constant(false),
             on_closed: None,
             context,
             load_system_fonts: true,
@@ -651,12 +815,20 @@ where
             vsync: true,
             close_requested: None,
             zoom: None,
+            content_scale: None,
+            effective_scale: None,
             resize_to_fit: Value::Constant(false),
+            size_to_content: None,
             content_protected: None,
             cursor_hittest: None,
+            hit_test_mask: None,
+            clear_color: None,
+            magnifier: None,
+            pointer_highlight: None,
             cursor_visible: None,
             cursor_position: None,
             window_level: None,
+            owner: None,
             decorated: None,
             maximized: None,
             minimized: None,
@@ -666,13 +838,20 @@ where
             outer_size: None,
             inner_position: None,
             outer_position: None,
+            current_monitor: None,
+            persistence: None,
             icon: None,
             modifiers: None,
             enabled_buttons: None,
             fullscreen: None,
             shortcuts: Value::default(),
+            gesture_shortcuts: GestureShortcuts::default(),
             on_init: None,
+            on_pre_present: None,
+            client_decorations: None,
             on_file_drop: None,
+            on_file_hover: None,
+            message_handlers: MessageHandlers::default(),
         }
     }
 
@@ -766,6 +945,13 @@ where
     }
 
     /// Sets the full screen mode for this window.
+    ///
+    /// [`Fullscreen::Borderless`] covers a monitor with this window without
+    /// changing the monitor's video mode. [`Fullscreen::Exclusive`] switches
+    /// the monitor to one of its video modes, which can be enumerated using
+    /// [`App::monitors`]. The mode can be changed while the window is open
+    /// using [`WindowHandle::set_fullscreen`] or
+    /// [`WindowHandle::toggle_fullscreen`].
     pub fn fullscreen(mut self, fullscreen: impl IntoValue<Option<Fullscreen>>) -> Self {
         let fullscreen = fullscreen.into_value();
         self.attributes.fullscreen = fullscreen.get();
@@ -829,6 +1015,34 @@ where
         self
     }
 
+    /// Sets `monitor` to be a dynamic updated with the monitor this window is
+    /// on.
+    ///
+    /// The monitor is updated as the window moves between displays, allowing
+    /// layouts to react to the display a window is shown on. Setting this
+    /// dynamic to a new value has no effect. To move a window to another
+    /// monitor, use [`outer_position`](Self::outer_position) or
+    /// [`fullscreen`](Self::fullscreen).
+    pub fn current_monitor(mut self, monitor: impl IntoDynamic<Option<MonitorHandle>>) -> Self {
+        self.current_monitor = Some(monitor.into_dynamic());
+        self
+    }
+
+    /// Restores this window's location, size, and maximized state from
+    /// `persistence` when opened, and updates `persistence` as the window
+    /// changes.
+    ///
+    /// This function uses the dynamics provided to
+    /// [`outer_position`](Self::outer_position),
+    /// [`inner_size`](Self::inner_size), [`maximized`](Self::maximized),
+    /// [`minimized`](Self::minimized), and
+    /// [`current_monitor`](Self::current_monitor) if they have been set, and
+    /// creates them otherwise.
+    pub fn persisted(mut self, persistence: &WindowPersistence) -> Self {
+        self.persistence = Some(persistence.clone());
+        self
+    }
+
     /// Sets `position` to be a dynamic synchronized with this window's inner
     /// position.
     ///
@@ -846,6 +1060,43 @@ where
         self
     }
 
+    /// Opens this window sized to fit its contents, and resizes the window
+    /// whenever the size of its contents changes.
+    ///
+    /// The contents are measured at the window's scale without being
+    /// constrained by the window's current size. Unless [`Window::visible`]
+    /// has been used to control the window's visibility, the window is hidden
+    /// until it has been resized to fit its contents.
+    pub fn size_to_content(self) -> Self {
+        self.size_to_content_within(.., ..)
+    }
+
+    /// Opens this window sized to fit its contents, clamped to `width` and
+    /// `height`, and resizes the window whenever the size of its contents
+    /// changes.
+    ///
+    /// The contents are measured at the window's scale, limited to the maximum
+    /// `width` and `height`. Unless [`Window::visible`] has been used to
+    /// control the window's visibility, the window is hidden until it has been
+    /// resized to fit its contents.
+    pub fn size_to_content_within(
+        mut self,
+        width: impl Into<DimensionRange>,
+        height: impl Into<DimensionRange>,
+    ) -> Self {
+        self.resize_to_fit = Value::Constant(true);
+        let reveal = self.visible.is_none();
+        if reveal {
+            self.attributes.visible = false;
+        }
+        self.size_to_content = Some(SizeToContent {
+            limits: Size::new(width.into(), height.into()),
+            reveal,
+            fit_attempted: false,
+        });
+        self
+    }
+
     /// Prevents the window contents from being captured by other apps.
     pub fn content_protected(mut self, protected: impl IntoValue<bool>) -> Self {
         let protected = protected.into_value();
@@ -855,11 +1106,24 @@ where
     }
 
     /// Controls whether the cursor should interact with this window or not.
+    ///
+    /// When `false`, cursor events pass through this window to the
+    /// applications beneath it. When `hittest` is a [`Dynamic`], click-through
+    /// can be toggled while the window is open.
     pub fn cursor_hittest(mut self, hittest: impl IntoValue<bool>) -> Self {
         self.cursor_hittest = Some(hittest.into_value());
         self
     }
 
+    /// Restricts the regions of this window that accept cursor input to
+    /// `mask`.
+    ///
+    /// See [`HitTestMask`] for more information.
+    pub fn hit_test_mask(mut self, mask: impl IntoValue<HitTestMask>) -> Self {
+        self.hit_test_mask = Some(mask.into_value());
+        self
+    }
+
     /// Sets whether the cursor is visible when above this window.
     pub fn cursor_visible(mut self, visible: impl IntoValue<bool>) -> Self {
         self.cursor_visible = Some(visible.into_value());
@@ -900,6 +1164,37 @@ where
         self
     }
 
+    /// Makes this window owned by `owner`.
+    ///
+    /// An owned window is kept above other windows, is minimized and restored
+    /// along with its owner, and is closed when its owner is closed. This is
+    /// useful for tool palettes and inspectors that should never be hidden
+    /// behind the window they belong to.
+    ///
+    /// Unless a level is set using [`Self::window_level`], owned windows use
+    /// [`WindowLevel::AlwaysOnTop`].
+    pub fn owned_by(mut self, owner: &WindowHandle) -> Self {
+        self.owner = Some(Ownership {
+            window: owner.clone(),
+            modal: false,
+        });
+        self
+    }
+
+    /// Makes this window a modal window owned by `owner`.
+    ///
+    /// In addition to the behaviors described in [`Self::owned_by`], `owner`
+    /// ignores keyboard, mouse, and touch input while this window is open.
+    /// Clicking on `owner` focuses this window instead. Mouse buttons pressed
+    /// on `owner` before this window opened are still released normally.
+    pub fn modal_for(mut self, owner: &WindowHandle) -> Self {
+        self.owner = Some(Ownership {
+            window: owner.clone(),
+            modal: true,
+        });
+        self
+    }
+
     /// Provides a dynamic that is updated with the minimized status of this
     /// window.
     pub fn minimized(mut self, minimized: impl IntoDynamic<bool>) -> Self {
@@ -936,6 +1231,53 @@ where
         self
     }
 
+    /// Magnifies this window's contents using `magnifier`.
+    ///
+    /// See [`Magnifier`] for more information.
+    pub fn magnifier(mut self, magnifier: Magnifier) -> Self {
+        self.magnifier = Some(magnifier);
+        self
+    }
+
+    /// Draws `highlight` around the cursor and where the mouse is clicked,
+    /// above this window's contents.
+    ///
+    /// See [`PointerHighlight`] for more information.
+    pub fn pointer_highlight(mut self, highlight: PointerHighlight) -> Self {
+        self.pointer_highlight = Some(highlight);
+        self
+    }
+
+    /// Shows this window fullscreen on the monitor `presenter` is presenting
+    /// on, and draws the presenter's [`PointerHighlight`].
+    ///
+    /// See [`Presenter`] for more information.
+    pub fn presented_by(self, presenter: &Presenter) -> Self {
+        self.fullscreen(presenter.fullscreen().clone())
+            .pointer_highlight(presenter.pointer_highlight().clone())
+    }
+
+    /// Fills this window with `color` before drawing its contents, instead of
+    /// the theme's surface color.
+    ///
+    /// When combined with [`Self::transparent`], translucent colors allow the
+    /// applications beneath this window to be seen, and
+    /// [`Color::CLEAR_BLACK`] leaves only this window's widgets visible.
+    pub fn clear_color(mut self, color: impl IntoValue<Color>) -> Self {
+        self.clear_color = Some(color.into_value());
+        self
+    }
+
+    /// Draws `decorations` around this window's contents instead of using the
+    /// platform's window decorations.
+    ///
+    /// This makes the window transparent and removes the platform's
+    /// decorations. See [`ClientDecorations`] for more information.
+    pub fn client_decorations(mut self, decorations: impl IntoValue<ClientDecorations>) -> Self {
+        self.client_decorations = Some(decorations.into_value());
+        self.transparent().decorated(false)
+    }
+
     /// Controls the visibility of this window.
     pub fn visible(mut self, visible: impl IntoDynamic<bool>) -> Self {
         let visible = visible.into_dynamic();
@@ -953,6 +1295,31 @@ where
         self
     }
 
+    /// Overrides the DPI scale reported by the window server with
+    /// `content_scale`.
+    ///
+    /// When `Some`, the window's contents are rendered as if the window were
+    /// on a display with the given scale. This allows testing high-DPI layouts
+    /// on a low-DPI display and vice versa. When `None`, the scale reported by
+    /// the window server is used. The [`zoom`](Self::zoom) factor is applied
+    /// in addition to this scale.
+    pub fn content_scale(mut self, content_scale: impl IntoValue<Option<Fraction>>) -> Self {
+        self.content_scale = Some(content_scale.into_value());
+        self
+    }
+
+    /// Sets `effective_scale` to be the dynamic updated with this window's
+    /// effective scale.
+    ///
+    /// The effective scale is the product of the DPI scale (or the
+    /// [`content_scale`](Self::content_scale) override) and the
+    /// [`zoom`](Self::zoom) factor. It is updated when the window moves
+    /// between displays with different DPI scales.
+    pub fn effective_scale(mut self, effective_scale: impl IntoDynamic<Fraction>) -> Self {
+        self.effective_scale = Some(effective_scale.into_dynamic());
+        self
+    }
+
     /// Sets the [`ThemeMode`] for this window.
     ///
     /// If a [`ThemeMode`] is provided, the window will be set to this theme
@@ -996,6 +1363,46 @@ where
         self
     }
 
+    /// Invokes `on_first_frame` once this window has rendered its first frame.
+    ///
+    /// This can be used to replace another window, such as a splash screen,
+    /// without showing this window before its contents are drawn.
+    pub fn on_first_frame<Function>(mut self, on_first_frame: Function) -> Self
+    where
+        Function: FnOnce() + Send + 'static,
+    {
+        self.on_first_frame = Some(OnceCallback::new(|()| on_first_frame()));
+        self
+    }
+
+    /// Invokes `on_announcement` for each [`Announcement`] made by widgets in
+    /// this window.
+    ///
+    /// This can be used to forward announcements to a platform's assistive
+    /// technology. When no handler is set, announcements are logged using
+    /// `tracing`.
+    pub fn on_announcement<Function>(mut self, on_announcement: Function) -> Self
+    where
+        Function: FnMut(Announcement) + Send + 'static,
+    {
+        self.on_announcement = Some(Callback::new(on_announcement));
+        self
+    }
+
+    /// Enables moving keyboard focus using the arrow keys when
+    /// `spatial_navigation` is true.
+    ///
+    /// When enabled, pressing an arrow key that the focused widget does not
+    /// handle moves focus to the nearest focusable widget in that direction,
+    /// based on where widgets were last laid out. This is useful for
+    /// interfaces designed to be navigated using a remote control or game
+    /// controller. Explicit neighbors can be assigned using
+    /// [`MakeWidget::with_focus_neighbor()`](crate::widget::MakeWidget::with_focus_neighbor).
+    pub fn spatial_navigation(mut self, spatial_navigation: impl IntoValue<bool>) -> Self {
+        self.spatial_navigation = spatial_navigation.into_value();
+        self
+    }
+
     /// Invokes `on_init` before the window initialization begins.
     pub fn on_init<Function>(mut self, on_init: Function) -> Self
     where
@@ -1005,6 +1412,21 @@ where
         self
     }
 
+    /// Invokes `on_pre_present` each time this window renders a frame.
+    ///
+    /// `on_pre_present` is invoked before each frame is presented and receives
+    /// a [`PrePresent`] containing a command encoder and the view of a texture
+    /// the size of the window's surface. Anything rendered into the texture is
+    /// composited on top of the window's contents before the frame is
+    /// presented, which allows drawing overlays using custom wgpu pipelines.
+    pub fn on_pre_present<Function>(mut self, on_pre_present: Function) -> Self
+    where
+        Function: FnMut(&mut PrePresent<'_>) + Send + 'static,
+    {
+        self.on_pre_present = Some(PrePresentCallback::new(on_pre_present));
+        self
+    }
+
     /// Invokes `on_close` when this window is closed.
     pub fn on_close<Function>(mut self, on_close: Function) -> Self
     where
@@ -1026,7 +1448,27 @@ where
         self
     }
 
-    /// Invokes `on_file_drop` when a file is hovered or dropped on this window.
+    /// Invokes `on_message` when a `Message` is sent to this window using
+    /// [`WindowHandle::send_message()`].
+    ///
+    /// `on_message` is invoked on the window's thread. Only one handler can be
+    /// registered for each message type; registering another handler for the
+    /// same type replaces the previous handler.
+    pub fn on_message<Message, Function>(mut self, on_message: Function) -> Self
+    where
+        Message: Send + 'static,
+        Function: FnMut(Message, &mut EventContext<'_>) + Send + 'static,
+    {
+        self.message_handlers.insert(on_message);
+        self
+    }
+
+    /// Invokes `on_file_drop` when a file from the operating system is dropped
+    /// on this window.
+    ///
+    /// When multiple files are dropped at once, `on_file_drop` is invoked once
+    /// for each file. Files can also be dropped on individual widgets using
+    /// [`FileDropTarget`](crate::widgets::drag::FileDropTarget).
     pub fn on_file_drop<Function>(self, on_file_drop: Function) -> Self
     where
         Function: FnMut(FileDrop) + Send + 'static,
@@ -1034,12 +1476,34 @@ where
         self.on_file_drop_notify(on_file_drop)
     }
 
-    /// Notifies `on_file_drop` when a file is hovered or dropped on this window.
+    /// Notifies `on_file_drop` when a file from the operating system is
+    /// dropped on this window.
     pub fn on_file_drop_notify(mut self, on_file_drop: impl Into<Notify<FileDrop>>) -> Self {
         self.on_file_drop = Some(on_file_drop.into());
         self
     }
 
+    /// Invokes `on_file_hover` when a file from the operating system is
+    /// dragged over this window or when the hovering files leave this window.
+    ///
+    /// When multiple files are dragged at once, `on_file_hover` is invoked
+    /// with [`DropEvent::Hover`] once for each file. When the files are
+    /// dragged away from the window or the drag is cancelled,
+    /// [`DropEvent::Cancelled`] is sent.
+    pub fn on_file_hover<Function>(self, on_file_hover: Function) -> Self
+    where
+        Function: FnMut(FileDrop) + Send + 'static,
+    {
+        self.on_file_hover_notify(on_file_hover)
+    }
+
+    /// Notifies `on_file_hover` when a file from the operating system is
+    /// dragged over this window or when the hovering files leave this window.
+    pub fn on_file_hover_notify(mut self, on_file_hover: impl Into<Notify<FileDrop>>) -> Self {
+        self.on_file_hover = Some(on_file_hover.into());
+        self
+    }
+
     /// Sets the window's title.
     pub fn titled(mut self, title: impl IntoValue<MaybeLocalized>) -> Self {
         self.title = title.into_value();
@@ -1092,6 +1556,15 @@ where
         self
     }
 
+    /// Sets the shortcuts performed when pen and touch gestures are not
+    /// handled by any widget in this window.
+    ///
+    /// See [`GestureShortcuts`] for the default behavior.
+    pub fn gesture_shortcuts(mut self, shortcuts: GestureShortcuts) -> Self {
+        self.gesture_shortcuts = shortcuts;
+        self
+    }
+
     /// Invokes `callback` when `key` is pressed while `modifiers` are pressed.
     /// If the shortcut is held, the callback will be invoked on repeat events.
     ///
@@ -1109,6 +1582,23 @@ where
             .map_mut(|mut shortcuts| shortcuts.insert_repeating(key, modifiers, callback));
         self
     }
+
+    /// Invokes `callback` when `first` is pressed followed by `second`.
+    ///
+    /// Widgets have a chance to handle keyboard input before the Window.
+    pub fn with_chord<F>(
+        mut self,
+        first: impl Into<Shortcut>,
+        second: impl Into<Shortcut>,
+        callback: F,
+    ) -> Self
+    where
+        F: FnMut(KeyEvent) -> EventHandling + Send + 'static,
+    {
+        self.shortcuts
+            .map_mut(|mut shortcuts| shortcuts.insert_chord(first, second, callback));
+        self
+    }
 }
 
 impl<Behavior> Run for Window<Behavior>
@@ -1130,61 +1620,103 @@ where
     where
         App: Application + ?Sized,
     {
-        let this = self.make_window();
-        let app_app = app.as_app();
+        let mut this = self.make_window();
+        if this.owner.is_some() && this.window_level.is_none() {
+            this.attributes.window_level = WindowLevel::AlwaysOnTop;
+            this.window_level = Some(Value::Constant(WindowLevel::AlwaysOnTop));
+        }
+        if let Some(persistence) = this.persistence.take() {
+            persistence.attach(&mut this, &app.as_app());
+        }
         let handle = this.pending.handle();
+        let settings = sealed::WindowSettings {
+            app: app.as_app(),
+            title: this.title,
+            redraw_status: this.pending.0.redraw_status.clone(),
+            on_open: this.on_open,
+            on_first_frame: this.on_first_frame,
+            on_announcement: this.on_announcement,
+            spatial_navigation: this.spatial_navigation,
+            on_init: this.on_init,
+            on_pre_present: this.on_pre_present,
+            client_decorations: this.client_decorations,
+            on_closed: this.on_closed,
+            transparent: this.attributes.transparent,
+            attributes: Some(this.attributes),
+            occluded: this.occluded.unwrap_or_default(),
+            focused: this.focused.unwrap_or_default(),
+            inner_size: this.inner_size.unwrap_or_default(),
+            theme: Some(this.theme),
+            theme_mode: this.theme_mode,
+            font_data_to_load: this.fonts,
+            serif_font_family: this.serif_font_family,
+            sans_serif_font_family: this.sans_serif_font_family,
+            fantasy_font_family: this.fantasy_font_family,
+            monospace_font_family: this.monospace_font_family,
+            cursive_font_family: this.cursive_font_family,
+            vsync: this.vsync,
+            multisample_count: this.multisample_count,
+            close_requested: this.close_requested,
+            zoom: this.zoom.unwrap_or_else(|| Dynamic::new(Fraction::ONE)),
+            content_scale: this.content_scale.unwrap_or_default(),
+            effective_scale: this
+                .effective_scale
+                .unwrap_or_else(|| Dynamic::new(Fraction::ONE)),
+            resize_to_fit: this.resize_to_fit,
+            size_to_content: this.size_to_content,
+            content_protected: this.content_protected.unwrap_or_default(),
+            cursor_hittest: this.cursor_hittest.unwrap_or_else(|| Value::Constant(true)),
+            hit_test_mask: this.hit_test_mask.unwrap_or_default(),
+            clear_color: this.clear_color,
+            magnifier: this.magnifier,
+            pointer_highlight: this.pointer_highlight,
+            cursor_visible: this.cursor_visible.unwrap_or_else(|| Value::Constant(true)),
+            cursor_position: this.cursor_position.unwrap_or_default(),
+            window_level: this.window_level.unwrap_or_default(),
+            owner: this.owner,
+            decorated: this.decorated.unwrap_or_else(|| Value::Constant(true)),
+            maximized: this.maximized.unwrap_or_default(),
+            minimized: this.minimized.unwrap_or_default(),
+            resizable: this.resizable.unwrap_or_else(|| Value::Constant(true)),
+            resize_increments: this.resize_increments.unwrap_or_default(),
+            visible: this.visible.unwrap_or_default(),
+            inner_position: this.inner_position.unwrap_or_default(),
+            outer_position: this.outer_position.unwrap_or_default(),
+            current_monitor: this.current_monitor.unwrap_or_default(),
+            outer_size: this.outer_size.unwrap_or_default(),
+            window_icon: this.icon.unwrap_or_default(),
+            modifiers: this.modifiers.unwrap_or_default(),
+            enabled_buttons: this
+                .enabled_buttons
+                .unwrap_or(Value::Constant(WindowButtons::all())),
+            fullscreen: this.fullscreen.unwrap_or_default(),
+            shortcuts: this.shortcuts,
+            gesture_shortcuts: this.gesture_shortcuts,
+            on_file_drop: this.on_file_drop,
+            message_handlers: this.message_handlers,
+            on_file_hover: this.on_file_hover,
+        };
+
+        if let Some(requests) = settings.app.virtual_windows().cloned() {
+            let contents: Box<dyn Any + Send> = Box::new(this.context);
+            if let Ok(contents) = contents.downcast::<WidgetInstance>() {
+                requests.push(*contents, settings, this.pending);
+            } else {
+                // Virtual apps can only display widgets. The window is treated
+                // as if it was closed immediately after opening.
+                tracing::error!("virtual apps can only open windows containing widgets");
+                let state = WindowDynamicState::default();
+                state.close_requested.set(true);
+                this.pending.opened_virtual(&state);
+            }
+            return Ok(handle);
+        }
+
         OpenWindow::<T::Behavior>::open_with(
             app,
             sealed::Context {
                 user: this.context,
-                settings: RefCell::new(sealed::WindowSettings {
-                    app: app_app,
-                    title: this.title,
-                    redraw_status: this.pending.0.redraw_status.clone(),
-                    on_open: this.on_open,
-                    on_init: this.on_init,
-                    on_closed: this.on_closed,
-                    transparent: this.attributes.transparent,
-                    attributes: Some(this.attributes),
-                    occluded: this.occluded.unwrap_or_default(),
-                    focused: this.focused.unwrap_or_default(),
-                    inner_size: this.inner_size.unwrap_or_default(),
-                    theme: Some(this.theme),
-                    theme_mode: this.theme_mode,
-                    font_data_to_load: this.fonts,
-                    serif_font_family: this.serif_font_family,
-                    sans_serif_font_family: this.sans_serif_font_family,
-                    fantasy_font_family: this.fantasy_font_family,
-                    monospace_font_family: this.monospace_font_family,
-                    cursive_font_family: this.cursive_font_family,
-                    vsync: this.vsync,
-                    multisample_count: this.multisample_count,
-                    close_requested: this.close_requested,
-                    zoom: this.zoom.unwrap_or_else(|| Dynamic::new(Fraction::ONE)),
-                    resize_to_fit: this.resize_to_fit,
-                    content_protected: this.content_protected.unwrap_or_default(),
-                    cursor_hittest: this.cursor_hittest.unwrap_or_else(|| Value::Constant(true)),
-                    cursor_visible: this.cursor_visible.unwrap_or_else(|| Value::Constant(true)),
-                    cursor_position: this.cursor_position.unwrap_or_default(),
-                    window_level: this.window_level.unwrap_or_default(),
-                    decorated: this.decorated.unwrap_or_else(|| Value::Constant(true)),
-                    maximized: this.maximized.unwrap_or_default(),
-                    minimized: this.minimized.unwrap_or_default(),
-                    resizable: this.resizable.unwrap_or_else(|| Value::Constant(true)),
-                    resize_increments: this.resize_increments.unwrap_or_default(),
-                    visible: this.visible.unwrap_or_default(),
-                    inner_position: this.inner_position.unwrap_or_default(),
-                    outer_position: this.outer_position.unwrap_or_default(),
-                    outer_size: this.outer_size.unwrap_or_default(),
-                    window_icon: this.icon.unwrap_or_default(),
-                    modifiers: this.modifiers.unwrap_or_default(),
-                    enabled_buttons: this
-                        .enabled_buttons
-                        .unwrap_or(Value::Constant(WindowButtons::all())),
-                    fullscreen: this.fullscreen.unwrap_or_default(),
-                    shortcuts: this.shortcuts,
-                    on_file_drop: this.on_file_drop,
-                }),
+                settings: RefCell::new(settings),
                 pending: this.pending,
             },
         )?;
@@ -1322,6 +1854,26 @@ pub trait WindowBehavior: Sized + 'static {
         true
     }
 
+    /// A file from the operating system is being dragged over the window.
+    ///
+    /// When multiple files are dragged at once, this function is invoked once
+    /// for each file. When the files are dragged away from the window or the
+    /// drag is cancelled, this function is invoked with `None`.
+    #[allow(unused_variables)]
+    fn file_hovered<W>(&mut self, path: Option<&Path>, window: &mut W)
+    where
+        W: PlatformWindow,
+    {
+    }
+
+    /// A file from the operating system has been dropped on the window.
+    #[allow(unused_variables)]
+    fn file_dropped<W>(&mut self, path: &Path, window: &mut W)
+    where
+        W: PlatformWindow,
+    {
+    }
+
     /// Runs this behavior as an application.
     fn run() -> crate::Result
     where
@@ -1344,6 +1896,9 @@ struct OpenWindow<T> {
     contents: Drawing,
     cursor: CursorState,
     mouse_buttons: AHashMap<DeviceId, AHashMap<MouseButton, WidgetId>>,
+    coalesced_input: CoalescedInput,
+    scroll_gestures: AHashSet<DeviceId>,
+    gestures: GestureRecognizer,
     redraw_status: InvalidationStatus,
     initial_frame: bool,
     occluded: Dynamic<bool>,
@@ -1354,6 +1909,7 @@ struct OpenWindow<T> {
     min_inner_size: Option<Size<UPx>>,
     max_inner_size: Option<Size<UPx>>,
     resize_to_fit: Value<bool>,
+    size_to_content: Option<SizeToContent>,
     theme: Option<DynamicReader<ThemePair>>,
     current_theme: ThemePair,
     theme_mode: Value<ThemeMode>,
@@ -1361,15 +1917,28 @@ struct OpenWindow<T> {
     fonts: FontState,
     app: App,
     on_closed: Option<OnceCallback>,
+    on_pre_present: Option<PrePresentCallback>,
+    on_first_frame: Option<OnceCallback>,
+    on_announcement: Option<Callback<Announcement>>,
+    spatial_navigation: Value<bool>,
     vsync: bool,
-    dpi_scale: Dynamic<Fraction>,
+    dpi_scale: Tracked<Dynamic<Fraction>>,
     zoom: Tracked<Dynamic<Fraction>>,
+    content_scale: Tracked<Value<Option<Fraction>>>,
+    effective_scale: Dynamic<Fraction>,
     close_requested: Option<SharedCallback<(), bool>>,
     content_protected: Tracked<Value<bool>>,
     cursor_hittest: Tracked<Value<bool>>,
+    hit_test_mask: Value<HitTestMask>,
+    cursor_masked: bool,
+    clear_color: Option<Value<Color>>,
+    magnifier: Option<Magnifier>,
+    pointer_highlight: Option<PointerHighlight>,
     cursor_visible: Tracked<Value<bool>>,
     cursor_position: Tracked<Dynamic<Point<Px>>>,
     window_level: Tracked<Value<WindowLevel>>,
+    owner: Option<Ownership>,
+    owned_windows: Vec<Ownership>,
     decorated: Tracked<Value<bool>>,
     maximized: Tracked<Dynamic<bool>>,
     minimized: Tracked<Dynamic<bool>>,
@@ -1377,6 +1946,7 @@ struct OpenWindow<T> {
     resize_increments: Tracked<Value<Size<UPx>>>,
     visible: Tracked<Dynamic<bool>>,
     outer_position: Tracked<Dynamic<Point<Px>>>,
+    current_monitor: Dynamic<Option<MonitorHandle>>,
     inner_position: Dynamic<Point<Px>>,
     window_icon: Tracked<Value<Option<RgbaImage>>>,
     enabled_buttons: Tracked<Value<WindowButtons>>,
@@ -1384,7 +1954,12 @@ struct OpenWindow<T> {
     modifiers: Dynamic<Modifiers>,
     shortcuts: Value<ShortcutMap>,
     on_file_drop: Option<Notify<FileDrop>>,
+    on_file_hover: Option<Notify<FileDrop>>,
+    message_handlers: MessageHandlers,
+    registered_handle: Option<WindowHandle>,
     disabled_resize_automatically: bool,
+    frame_times: FrameTimeTracker,
+    deferred_fonts_loaded: bool,
 }
 
 impl<T> OpenWindow<T>
@@ -1402,6 +1977,53 @@ where
                 .map_or(true, |close| close.invoke(()))
     }
 
+    /// Returns the open modal window owned by this window, if any. Input to
+    /// this window is ignored while a modal window is open.
+    fn modal_window(&self) -> Option<&WindowHandle> {
+        self.owned_windows
+            .iter()
+            .find(|owned| owned.modal)
+            .map(|owned| &owned.window)
+    }
+
+    /// Returns true if a mouse button event should be ignored because a modal
+    /// window owned by this window is open.
+    ///
+    /// Releases of buttons that were pressed before the modal window opened
+    /// are still delivered so that the widget handling the press is released.
+    fn modal_blocks_mouse_input(
+        &self,
+        device_id: DeviceId,
+        state: ElementState,
+        button: MouseButton,
+    ) -> bool {
+        self.modal_window().is_some()
+            && (state.is_pressed()
+                || !self
+                    .mouse_buttons
+                    .get(&device_id)
+                    .is_some_and(|buttons| buttons.contains_key(&button)))
+    }
+
+    /// Starts tracking a window owned by this window. If this window is
+    /// minimized, the owned window is minimized too.
+    fn owned_window_opened(&mut self, owned: Ownership) {
+        if *self.minimized.peek() {
+            owned.window.inner.send(WindowCommand::OwnerMinimized(true));
+        }
+        self.owned_windows.push(owned);
+    }
+
+    /// Minimizes or restores each window owned by this window.
+    fn minimize_owned_windows(&self, minimized: bool) {
+        for owned in &self.owned_windows {
+            owned
+                .window
+                .inner
+                .send(WindowCommand::OwnerMinimized(minimized));
+        }
+    }
+
     fn keyboard_activate_widget<W>(
         &mut self,
         is_pressed: bool,
@@ -1619,6 +2241,73 @@ where
         fonts
     }
 
+    fn activate_focused_widget<W>(
+        &mut self,
+        is_pressed: bool,
+        window: &mut W,
+        kludgine: &mut Kludgine,
+    ) where
+        W: PlatformWindow,
+    {
+        let target = self.tree.focused_widget().unwrap_or(self.root.node_id);
+        let target = self.tree.widget_from_node(target).expect("missing widget");
+        let mut target = EventContext::new(
+            WidgetContext::new(
+                target,
+                &self.current_theme,
+                window,
+                &mut self.fonts,
+                self.theme_mode.get(),
+                &mut self.cursor,
+                #[cfg(feature = "localization")]
+                &self.app.cushy().data.localizations,
+            ),
+            kludgine,
+        );
+
+        if is_pressed {
+            if target.active() {
+                target.deactivate();
+                target.apply_pending_state();
+            }
+            target.activate();
+        } else {
+            target.deactivate();
+        }
+    }
+
+    fn move_focus_in_direction<W>(
+        &mut self,
+        direction: FocusDirection,
+        window: &mut W,
+        kludgine: &mut Kludgine,
+    ) where
+        W: PlatformWindow,
+    {
+        let focused = self.tree.focused_widget();
+        let target = focused.unwrap_or(self.root.node_id);
+        let target = self.tree.widget_from_node(target).expect("missing widget");
+        let mut target = EventContext::new(
+            WidgetContext::new(
+                target,
+                &self.current_theme,
+                window,
+                &mut self.fonts,
+                self.theme_mode.get(),
+                &mut self.cursor,
+                #[cfg(feature = "localization")]
+                &self.app.cushy().data.localizations,
+            ),
+            kludgine,
+        );
+
+        if focused.is_some() {
+            target.focus_in_direction(direction);
+        } else {
+            target.advance_focus();
+        }
+    }
+
     fn handle_window_keyboard_input<W>(
         &mut self,
         window: &mut W,
@@ -1628,6 +2317,15 @@ where
     where
         W: PlatformWindow,
     {
+        if let Some(magnifier) = &self.magnifier {
+            let modifiers = window.modifiers();
+            if modifiers.primary()
+                && modifiers.state().alt_key()
+                && magnifier.shortcut(input.physical_key, input.state.is_pressed())
+            {
+                return HANDLED;
+            }
+        }
         match input.logical_key {
             Key::Character(ch) if ch == "w" && window.modifiers().primary() => {
                 if !input.repeat
@@ -1640,34 +2338,7 @@ where
                 HANDLED
             }
             Key::Named(NamedKey::Space) if !window.modifiers().possible_shortcut() => {
-                let target = self.tree.focused_widget().unwrap_or(self.root.node_id);
-                let target = self.tree.widget_from_node(target).expect("missing widget");
-                let mut target = EventContext::new(
-                    WidgetContext::new(
-                        target,
-                        &self.current_theme,
-                        window,
-                        &mut self.fonts,
-                        self.theme_mode.get(),
-                        &mut self.cursor,
-                        #[cfg(feature = "localization")]
-                        &self.app.cushy().data.localizations,
-                    ),
-                    kludgine,
-                );
-
-                match input.state {
-                    ElementState::Pressed => {
-                        if target.active() {
-                            target.deactivate();
-                            target.apply_pending_state();
-                        }
-                        target.activate();
-                    }
-                    ElementState::Released => {
-                        target.deactivate();
-                    }
-                }
+                self.activate_focused_widget(input.state.is_pressed(), window, kludgine);
                 HANDLED
             }
 
@@ -1699,6 +2370,23 @@ where
                 }
                 HANDLED
             }
+            Key::Named(
+                arrow @ (NamedKey::ArrowUp
+                | NamedKey::ArrowDown
+                | NamedKey::ArrowLeft
+                | NamedKey::ArrowRight),
+            ) if self.spatial_navigation.get() && !window.modifiers().possible_shortcut() => {
+                if input.state.is_pressed() {
+                    let direction = match arrow {
+                        NamedKey::ArrowUp => FocusDirection::Up,
+                        NamedKey::ArrowDown => FocusDirection::Down,
+                        NamedKey::ArrowLeft => FocusDirection::Left,
+                        _ => FocusDirection::Right,
+                    };
+                    self.move_focus_in_direction(direction, window, kludgine);
+                }
+                HANDLED
+            }
             Key::Named(NamedKey::Enter) => {
                 self.keyboard_activate_widget(
                     input.state.is_pressed(),
@@ -1741,6 +2429,7 @@ where
         W: PlatformWindowImplementation,
     {
         let redraw_status = settings.redraw_status.clone();
+        redraw_status.set_thread();
         if let Value::Dynamic(title) = &settings.title {
             let handle = window.handle(redraw_status.clone());
             title
@@ -1760,6 +2449,9 @@ where
         let dpi_scale = Dynamic::new(graphics.dpi_scale());
         settings.inner_position.set(window.inner_position());
         settings.outer_position.set(window.outer_position());
+        if let Some(winit) = window.winit() {
+            settings.current_monitor.set(winit.current_monitor());
+        }
 
         let theme_mode = match settings.theme_mode.take() {
             Some(Value::Dynamic(dynamic)) => {
@@ -1771,7 +2463,18 @@ where
         };
 
         let tree = Tree::default();
-        let root = tree.push_boxed(behavior.make_root(), None);
+        let root = behavior.make_root();
+        let root = match settings.client_decorations.take() {
+            Some(decorations) => ClientDecorated::new(
+                decorations,
+                settings.maximized.clone(),
+                settings.fullscreen.clone(),
+                WidgetRef::new(root),
+            )
+            .make_widget(),
+            None => root,
+        };
+        let root = tree.push_boxed(root, None);
 
         let theme = settings.theme.unwrap_or_default();
         let (current_theme, theme) = match theme {
@@ -1779,6 +2482,11 @@ where
             Value::Dynamic(dynamic) => (dynamic.get(), Some(dynamic.into_reader())),
         };
 
+        let frame_times = FrameTimeTracker::new(window.handle(redraw_status.clone()));
+        telemetry::emit(|| TelemetryEvent::WindowOpened {
+            window: frame_times.window().clone(),
+        });
+
         if let Some(on_open) = settings.on_open {
             let handle = window.handle(redraw_status.clone());
             on_open.invoke(handle);
@@ -1792,8 +2500,15 @@ where
             cursor: CursorState {
                 location: None,
                 widget: None,
+                drag: None,
+                file_hover: FileHover::default(),
+                scroll_source: ScrollSource::default(),
+                unhandled_scroll: None,
             },
             mouse_buttons: AHashMap::default(),
+            coalesced_input: CoalescedInput::default(),
+            scroll_gestures: AHashSet::default(),
+            gestures: GestureRecognizer::new(settings.gesture_shortcuts),
             redraw_status,
             initial_frame: true,
             occluded: settings.occluded,
@@ -1803,6 +2518,7 @@ where
             min_inner_size: None,
             max_inner_size: None,
             resize_to_fit: settings.resize_to_fit,
+            size_to_content: settings.size_to_content,
             current_theme,
             theme,
             theme_mode,
@@ -1810,15 +2526,28 @@ where
             fonts,
             app,
             on_closed: settings.on_closed,
+            on_pre_present: settings.on_pre_present,
+            on_first_frame: settings.on_first_frame,
+            on_announcement: settings.on_announcement,
+            spatial_navigation: settings.spatial_navigation,
             vsync: settings.vsync,
             close_requested: settings.close_requested,
-            dpi_scale,
+            dpi_scale: Tracked::from(dpi_scale),
             zoom: Tracked::from(settings.zoom),
+            content_scale: Tracked::from(settings.content_scale),
+            effective_scale: settings.effective_scale,
             content_protected: Tracked::from(settings.content_protected).ignoring_first(),
             cursor_hittest: Tracked::from(settings.cursor_hittest),
+            hit_test_mask: settings.hit_test_mask,
+            cursor_masked: false,
+            clear_color: settings.clear_color,
+            magnifier: settings.magnifier,
+            pointer_highlight: settings.pointer_highlight,
             cursor_visible: Tracked::from(settings.cursor_visible),
             cursor_position: Tracked::from(settings.cursor_position),
             window_level: Tracked::from(settings.window_level).ignoring_first(),
+            owner: settings.owner,
+            owned_windows: Vec::new(),
             decorated: Tracked::from(settings.decorated).ignoring_first(),
             maximized: Tracked::from(settings.maximized),
             minimized: Tracked::from(settings.minimized),
@@ -1828,13 +2557,19 @@ where
             outer_size: settings.outer_size,
             inner_position: settings.inner_position,
             outer_position: Tracked::from(settings.outer_position).ignoring_first(),
+            current_monitor: settings.current_monitor,
             window_icon: Tracked::from(settings.window_icon).ignoring_first_if(Option::is_none),
             modifiers: settings.modifiers,
             enabled_buttons: Tracked::from(settings.enabled_buttons).ignoring_first(),
             fullscreen: Tracked::from(settings.fullscreen).ignoring_first(),
             shortcuts: settings.shortcuts,
             on_file_drop: settings.on_file_drop,
+            on_file_hover: settings.on_file_hover,
+            message_handlers: settings.message_handlers,
+            registered_handle: None,
             disabled_resize_automatically: false,
+            frame_times,
+            deferred_fonts_loaded: false,
         };
 
         this.synchronize_platform_window(&mut window);
@@ -1855,10 +2590,22 @@ where
 
         self.redraw_status.refresh_received();
         graphics.reset_text_attributes();
-        if let Some(zoom) = self.zoom.updated() {
-            graphics.set_zoom(*zoom);
-            self.redraw_status.invalidate(self.root.id());
+        let zoom_changed = self.zoom.updated().is_some();
+        let content_scale_changed = self.content_scale.updated().is_some();
+        let dpi_scale_changed = self.dpi_scale.updated().is_some();
+        if zoom_changed || content_scale_changed || dpi_scale_changed {
+            let zoom = *self.zoom.peek();
+            // Overriding the content scale is implemented by adjusting the
+            // zoom to counteract the DPI scale reported by the window server.
+            graphics.set_zoom(match *self.content_scale.peek() {
+                Some(content_scale) => zoom * content_scale / *self.dpi_scale.peek(),
+                None => zoom,
+            });
+            // Every resolved dimension, font size, and rasterized asset depends
+            // on the scale, so the entire tree must be laid out again.
+            self.root.invalidate();
         }
+        self.effective_scale.set(graphics.scale());
 
         self.tree
             .new_frame(self.redraw_status.invalidations().drain());
@@ -1870,6 +2617,7 @@ where
     {
         let cushy = self.app.cushy().clone();
         let _guard = cushy.enter_runtime();
+        let frame_start = Instant::now();
 
         self.synchronize_platform_window(&mut window);
         self.new_frame(graphics);
@@ -1884,14 +2632,76 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         );
+
+        // Deliver the input events that were held until this frame.
+        let pending_cursor = self.coalesced_input.cursor.take();
+        let pending_wheel = std::mem::take(&mut self.coalesced_input.wheel);
+        if pending_cursor.is_some() || !pending_wheel.is_empty() {
+            let location = self.cursor.location;
+            let mut context = EventContext::new(
+                WidgetContext::new(
+                    self.root.clone(),
+                    &self.current_theme,
+                    &mut window,
+                    &mut self.fonts,
+                    self.theme_mode.get(),
+                    &mut self.cursor,
+                    #[cfg(feature = "localization")]
+                    &self.app.cushy().data.localizations,
+                ),
+                graphics,
+            );
+            if let (Some(device_id), Some(location)) = (pending_cursor, location) {
+                dispatch_cursor_moved(
+                    &mut context,
+                    &self.tree,
+                    &self.mouse_buttons,
+                    device_id,
+                    location,
+                );
+            }
+            for PendingWheel {
+                device_id,
+                delta,
+                phase,
+                source,
+            } in pending_wheel
+            {
+                context.cursor.scroll_source = source;
+                let widget = self
+                    .tree
+                    .hovered_widget()
+                    .and_then(|hovered| self.tree.widget_from_node(hovered))
+                    .unwrap_or_else(|| self.root.clone());
+                let _handled = recursively_handle_scroll(
+                    &mut context.for_other(&widget),
+                    device_id,
+                    delta,
+                    phase,
+                );
+            }
+            drop(context);
+
+            // Widgets invalidated while handling the events can be redrawn in
+            // this frame.
+            self.tree
+                .new_frame(self.redraw_status.invalidations().drain());
+        }
+        self.tree.clear_render_info();
+
         let root_mode = self.constrain_window_resizing(resizable, &mut window, graphics);
 
         let fonts_changed = self.fonts.next_frame(graphics.font_system().db_mut());
         if fonts_changed {
             graphics.rebuild_font_system();
         }
+        if let Some(on_pre_present) = &mut self.on_pre_present {
+            on_pre_present.render_overlay(window.inner_size().get(), graphics);
+        }
+
         let graphics = self.contents.new_frame(graphics);
         let mut context = GraphicsContext {
             widget: WidgetContext::new(
@@ -1912,17 +2722,27 @@ where
         let mut layout_context = LayoutContext::new(&mut context);
         let window_size = layout_context.gfx.size();
 
-        if !self.transparent {
+        if let Some(clear_color) = &self.clear_color {
+            clear_color.redraw_when_changed(&layout_context);
+        } else if !self.transparent {
             let background_color = layout_context.theme().surface.color;
             layout_context.graphics.gfx.fill(background_color);
         }
 
-        let layout_size =
-            layout_context.layout(if matches!(root_mode, RootMode::Expand | RootMode::Align) {
-                window_size.map(ConstraintLimit::Fill)
-            } else {
-                window_size.map(ConstraintLimit::SizeToFit)
-            });
+        let scale = layout_context.gfx.scale();
+        let layout_size = if let Some(size_to_content) = &self.size_to_content {
+            layout_context
+                .layout(
+                    size_to_content
+                        .maximum(scale)
+                        .map(ConstraintLimit::SizeToFit),
+                )
+                .max(size_to_content.minimum(scale))
+        } else if matches!(root_mode, RootMode::Expand | RootMode::Align) {
+            layout_context.layout(window_size.map(ConstraintLimit::Fill))
+        } else {
+            layout_context.layout(window_size.map(ConstraintLimit::SizeToFit))
+        };
         let actual_size = if root_mode == RootMode::Align {
             window_size.max(layout_size)
         } else {
@@ -1937,6 +2757,25 @@ where
             Self::mount_and_focus_root(&self.root, &mut layout_context);
         }
 
+        let magnified = self.magnifier.as_ref().and_then(|magnifier| {
+            magnifier.displayed().redraw_when_changed(&layout_context);
+            magnifier.focus().redraw_when_changed(&layout_context);
+            let focused = layout_context
+                .tree
+                .focused_widget()
+                .and_then(|focused| layout_context.tree.widget_from_node(focused))
+                .and_then(|focused| focused.last_layout());
+            magnifier.transform(
+                window_size.into_signed(),
+                layout_context.cursor.location,
+                focused,
+            )
+        });
+        if let Some(transform) = magnified {
+            layout_context.gfx.apply_transform(transform);
+        }
+
+        let reveal_requests = layout_context.tree.pending_reveal_requests();
         if render_size.width < window_size.width || render_size.height < window_size.height {
             layout_context
                 .clipped_to(Rect::from(render_size.into_signed()))
@@ -1944,6 +2783,25 @@ where
         } else {
             layout_context.redraw();
         }
+        layout_context.tree.clear_reveal_requests(reveal_requests);
+        if magnified.is_some() {
+            layout_context.gfx.set_transform(None);
+        }
+        render_drag_ghost(&mut layout_context);
+        if let Some(highlight) = &self.pointer_highlight {
+            highlight.render(&mut layout_context);
+        }
+        if let Some(overlay) = self
+            .on_pre_present
+            .as_ref()
+            .and_then(PrePresentCallback::overlay)
+        {
+            layout_context.gfx.draw_texture(
+                overlay,
+                Rect::from(overlay.size().into_signed()),
+                ZeroToOne::ONE,
+            );
+        }
 
         let resizable = resizable
             && !Self::enforce_fixed_size(
@@ -1971,6 +2829,17 @@ where
             None
         };
 
+        if let Some(size_to_content) = &mut self.size_to_content {
+            // The window is revealed once it fits its contents, or after one
+            // attempt to fit has been made in case the requested size could not
+            // be honored.
+            if size_to_content.reveal && (new_size.is_none() || size_to_content.fit_attempted) {
+                size_to_content.reveal = false;
+                self.visible.source().set(true);
+            }
+            size_to_content.fit_attempted = true;
+        }
+
         if let Some(new_size) = new_size {
             self.inner_size.set_and_read(new_size);
             self.outer_size.set(layout_context.window().outer_size());
@@ -1978,6 +2847,19 @@ where
         }
 
         layout_context.as_event_context().update_hovered_widget();
+        for announcement in layout_context.tree.take_announcements() {
+            if let Some(on_announcement) = &mut self.on_announcement {
+                on_announcement.invoke(announcement);
+            } else {
+                tracing::info!(
+                    target: "cushy::announcements",
+                    politeness = ?announcement.politeness,
+                    "{}",
+                    announcement.message
+                );
+            }
+        }
+        self.frame_times.record(frame_start.elapsed());
     }
 
     fn mount_and_focus_root(root: &MountedWidget, context: &mut LayoutContext<'_, '_, '_, '_>) {
@@ -2027,6 +2909,7 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         );
         if self.behavior.close_requested(&mut window) {
@@ -2044,6 +2927,7 @@ where
         self.inner_size.set_and_read(new_size);
         self.outer_size.set(window.outer_size());
         self.update_ized(window);
+        self.update_current_monitor(window);
         self.root.invalidate();
     }
 
@@ -2052,6 +2936,15 @@ where
         self.inner_position.set(new_inner_position);
     }
 
+    fn update_current_monitor<W>(&self, window: &W)
+    where
+        W: PlatformWindowImplementation,
+    {
+        if let Some(winit) = window.winit() {
+            self.current_monitor.set(winit.current_monitor());
+        }
+    }
+
     fn update_ized<W>(&mut self, window: &W)
     where
         W: PlatformWindowImplementation,
@@ -2061,6 +2954,9 @@ where
             // request if you read this and need them.
             self.maximized.set_and_read(winit.is_maximized());
             if let Some(minimized) = winit.is_minimized() {
+                if *self.minimized.peek() != minimized {
+                    self.minimize_owned_windows(minimized);
+                }
                 self.minimized.set_and_read(minimized);
             }
             self.decorated.set_and_read(winit.is_decorated());
@@ -2144,6 +3040,13 @@ where
 
     pub fn set_focused(&mut self, focused: bool) {
         self.focused.set(focused);
+        self.tree.window_activation_changed(focused);
+        let cushy = self.app.cushy();
+        if focused && cushy.data.clipboard_changes.is_observed() {
+            // Other applications may have changed the clipboard while this
+            // window was not focused.
+            cushy.clipboard().refresh();
+        }
     }
 
     pub fn set_occluded<W>(&mut self, window: &W, occluded: bool)
@@ -2154,6 +3057,57 @@ where
         self.update_ized(window);
     }
 
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad_input<W>(
+        &mut self,
+        window: W,
+        kludgine: &mut Kludgine,
+        intent: crate::gamepad::GamepadIntent,
+    ) where
+        W: PlatformWindowImplementation,
+    {
+        use crate::gamepad::GamepadIntent;
+
+        let cushy = self.app.cushy().clone();
+        let _guard = cushy.enter_runtime();
+        let mut window = RunningWindow::new(
+            window,
+            kludgine.id(),
+            &self.redraw_status,
+            &self.app,
+            &self.focused,
+            &self.occluded,
+            self.inner_size.source(),
+            &self.effective_scale,
+            &self.close_requested,
+        );
+        match intent {
+            GamepadIntent::Confirm(is_pressed) => {
+                if self.tree.focused_widget().is_some() {
+                    self.activate_focused_widget(is_pressed, &mut window, kludgine);
+                } else {
+                    self.keyboard_activate_widget(
+                        is_pressed,
+                        self.tree.default_widget(),
+                        &mut window,
+                        kludgine,
+                    );
+                }
+            }
+            GamepadIntent::Cancel(is_pressed) => {
+                self.keyboard_activate_widget(
+                    is_pressed,
+                    self.tree.escape_widget(),
+                    &mut window,
+                    kludgine,
+                );
+            }
+            GamepadIntent::Navigate(direction) => {
+                self.move_focus_in_direction(direction, &mut window, kludgine);
+            }
+        }
+    }
+
     pub fn keyboard_input<W>(
         &mut self,
         window: W,
@@ -2165,6 +3119,7 @@ where
     where
         W: PlatformWindowImplementation,
     {
+        idle::note_input();
         let cushy = self.app.cushy().clone();
         let _guard = cushy.enter_runtime();
         let mut window = RunningWindow::new(
@@ -2175,8 +3130,25 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         );
+        self.deliver_keyboard_input(&mut window, kludgine, device_id, input, is_synthetic)
+    }
+
+    /// Delivers `input` to the focused widget and its parents, followed by
+    /// the window's shortcuts and built-in key handling.
+    fn deliver_keyboard_input<W>(
+        &mut self,
+        window: &mut W,
+        kludgine: &mut Kludgine,
+        device_id: DeviceId,
+        input: KeyEvent,
+        is_synthetic: bool,
+    ) -> EventHandling
+    where
+        W: PlatformWindow,
+    {
         let target = self.tree.focused_widget().unwrap_or(self.root.node_id);
         let Some(target) = self.tree.widget_from_node(target) else {
             return IGNORED;
@@ -2185,7 +3157,7 @@ where
             WidgetContext::new(
                 target,
                 &self.current_theme,
-                &mut window,
+                window,
                 &mut self.fonts,
                 self.theme_mode.get(),
                 &mut self.cursor,
@@ -2212,13 +3184,143 @@ where
 
         drop(target);
 
-        self.handle_window_keyboard_input(&mut window, kludgine, input)
+        self.handle_window_keyboard_input(window, kludgine, input)
     }
 
     pub fn mouse_wheel<W>(
+        &mut self,
+        mut window: W,
+        kludgine: &mut Kludgine,
+        device_id: DeviceId,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+    ) -> EventHandling
+    where
+        W: PlatformWindowImplementation,
+    {
+        idle::note_input();
+        let source = self.scroll_source(device_id, delta, phase);
+        let delta = scale_scroll_delta(delta, self.app.cushy().scroll_multiplier(source));
+        if self.hovered_coalesces_input() {
+            // The event is delivered when the next frame is prepared.
+            self.coalesced_input
+                .push_wheel(device_id, delta, phase, source);
+            window.set_needs_redraw();
+            return HANDLED;
+        }
+
+        let widget = self
+            .tree
+            .hovered_widget()
+            .and_then(|hovered| self.tree.widget_from_node(hovered))
+            .unwrap_or_else(|| self.tree.widget(self.root.id()).expect("missing widget"));
+        self.dispatch_wheel(window, kludgine, widget, device_id, delta, phase, source)
+    }
+
+    /// Determines what kind of device produced a scroll event.
+    ///
+    /// Line-based deltas are always produced by mouse wheels. Pixel-based
+    /// deltas are produced by touchpads when they are part of a gesture that
+    /// has started and ended phases, and otherwise are produced by
+    /// high-resolution mouse wheels.
+    fn scroll_source(
+        &mut self,
+        device_id: DeviceId,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+    ) -> ScrollSource {
+        let is_gesture = match (delta, phase) {
+            (MouseScrollDelta::LineDelta(..), _) => false,
+            (MouseScrollDelta::PixelDelta(_), TouchPhase::Started) => {
+                self.scroll_gestures.insert(device_id);
+                true
+            }
+            (MouseScrollDelta::PixelDelta(_), TouchPhase::Moved) => {
+                self.scroll_gestures.contains(&device_id)
+            }
+            (MouseScrollDelta::PixelDelta(_), TouchPhase::Ended | TouchPhase::Cancelled) => {
+                self.scroll_gestures.remove(&device_id)
+            }
+        };
+        if is_gesture {
+            ScrollSource::Touchpad
+        } else {
+            ScrollSource::Wheel
+        }
+    }
+
+    pub fn touch<W>(&mut self, window: W, kludgine: &mut Kludgine, touch: Touch) -> EventHandling
+    where
+        W: PlatformWindowImplementation,
+    {
+        idle::note_input();
+        let location = Point::<Px>::from(touch.location);
+        // The distance a finger can move while still being considered at rest.
+        let slop = Lp::points(6).into_px(kludgine.scale());
+        let update = match touch.phase {
+            TouchPhase::Started => {
+                let Some(widget) = self.tree.widgets_under_point(location).into_iter().next()
+                else {
+                    return IGNORED;
+                };
+                Some(self.gestures.started(
+                    touch.id,
+                    touch.device_id.into(),
+                    location,
+                    widget.id(),
+                    window.handle(self.redraw_status.clone()),
+                ))
+            }
+            TouchPhase::Moved => self.gestures.moved(touch.id, location, slop),
+            TouchPhase::Ended | TouchPhase::Cancelled => self.gestures.ended(
+                touch.id,
+                location,
+                touch.phase,
+                slop,
+                self.app.cushy().multi_click_threshold(),
+                Lp::points(36).into_px(kludgine.scale()),
+            ),
+        };
+        let Some(update) = update else {
+            return IGNORED;
+        };
+        let Some(widget) = self.tree.widget(update.widget) else {
+            return IGNORED;
+        };
+
+        // Dragging a finger moves the contents along with it, which matches
+        // the direction of pixel-based scroll deltas.
+        let delta = scale_scroll_delta(
+            MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+                f64::from(update.delta.x.get()),
+                f64::from(update.delta.y.get()),
+            )),
+            self.app.cushy().scroll_multiplier(ScrollSource::Touch),
+        );
+        let handled = self.dispatch_touch(
+            window,
+            kludgine,
+            widget,
+            update.gestures,
+            touch.device_id.into(),
+            delta,
+            touch.phase,
+        );
+        if touch.phase == TouchPhase::Moved && handled.is_break() {
+            self.gestures.movement_handled(touch.id);
+        }
+        handled
+    }
+
+    /// Delivers `gestures` to `widget`. If none of the gestures are handled,
+    /// `delta` is delivered as a touch scroll.
+    #[allow(clippy::too_many_arguments)]
+    fn dispatch_touch<W>(
         &mut self,
         window: W,
         kludgine: &mut Kludgine,
+        widget: MountedWidget,
+        gestures: Vec<Gesture>,
         device_id: DeviceId,
         delta: MouseScrollDelta,
         phase: TouchPhase,
@@ -2226,6 +3328,7 @@ where
     where
         W: PlatformWindowImplementation,
     {
+        self.cursor.scroll_source = ScrollSource::Touch;
         let cushy = self.app.cushy().clone();
         let _guard = cushy.enter_runtime();
         let mut window = RunningWindow::new(
@@ -2236,19 +3339,110 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         );
-        let widget = self
-            .tree
-            .hovered_widget()
-            .and_then(|hovered| self.tree.widget_from_node(hovered))
-            .unwrap_or_else(|| self.tree.widget(self.root.id()).expect("missing widget"));
 
+        let (mut handled, flicks) = self.deliver_scroll(
+            &mut window,
+            kludgine,
+            widget,
+            gestures,
+            device_id,
+            delta,
+            phase,
+        );
+        if handled.is_break() {
+            return HANDLED;
+        }
+
+        // Unhandled flicks are delivered as key presses.
+        for key in flicks
+            .into_iter()
+            .filter_map(|direction| self.gestures.flick_key(direction))
+        {
+            handled = HANDLED;
+            for state in [ElementState::Pressed, ElementState::Released] {
+                let input = KeyEvent {
+                    physical_key: PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+                    logical_key: key.clone(),
+                    text: None,
+                    location: KeyLocation::Standard,
+                    state,
+                    repeat: false,
+                    modifiers: Modifiers::default(),
+                };
+                self.deliver_keyboard_input(&mut window, kludgine, device_id, input, true);
+            }
+        }
+        handled
+    }
+
+    fn dispatch_wheel<W>(
+        &mut self,
+        window: W,
+        kludgine: &mut Kludgine,
+        widget: MountedWidget,
+        device_id: DeviceId,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+        source: ScrollSource,
+    ) -> EventHandling
+    where
+        W: PlatformWindowImplementation,
+    {
+        self.cursor.scroll_source = source;
+        let cushy = self.app.cushy().clone();
+        let _guard = cushy.enter_runtime();
+        let mut window = RunningWindow::new(
+            window,
+            kludgine.id(),
+            &self.redraw_status,
+            &self.app,
+            &self.focused,
+            &self.occluded,
+            self.inner_size.source(),
+            &self.effective_scale,
+            &self.close_requested,
+        );
+
+        self.deliver_scroll(
+            &mut window,
+            kludgine,
+            widget,
+            Vec::new(),
+            device_id,
+            delta,
+            phase,
+        )
+        .0
+    }
+
+    /// Delivers `gestures` to `widget`, and then delivers `delta` as a scroll
+    /// if no gestures were handled. Each event bubbles up to the widget's
+    /// parents until it is handled.
+    ///
+    /// Returns whether any event was handled, along with the directions of any
+    /// flicks that were not handled.
+    #[allow(clippy::too_many_arguments)]
+    fn deliver_scroll<W>(
+        &mut self,
+        window: &mut W,
+        kludgine: &mut Kludgine,
+        widget: MountedWidget,
+        gestures: Vec<Gesture>,
+        device_id: DeviceId,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+    ) -> (EventHandling, Vec<Direction>)
+    where
+        W: PlatformWindow,
+    {
         let mut widget = EventContext::new(
             WidgetContext::new(
                 widget,
                 &self.current_theme,
-                &mut window,
+                window,
                 &mut self.fonts,
                 self.theme_mode.get(),
                 &mut self.cursor,
@@ -2257,21 +3451,30 @@ where
             ),
             kludgine,
         );
-        if recursively_handle_event(&mut widget, |widget| {
-            widget.mouse_wheel(device_id, delta, phase)
-        })
-        .is_some()
-        {
-            HANDLED
-        } else {
-            IGNORED
+        let mut handled = IGNORED;
+        let mut flicks = Vec::new();
+        for gesture in gestures {
+            let flick = match &gesture {
+                Gesture::Flick { direction, .. } => Some(*direction),
+                _ => None,
+            };
+            if gestures::dispatch_gesture(&mut widget, gesture).is_break() {
+                handled = HANDLED;
+            } else if let Some(direction) = flick {
+                flicks.push(direction);
+            }
         }
+        if handled.is_continue() {
+            handled = recursively_handle_scroll(&mut widget, device_id, delta, phase);
+        }
+        (handled, flicks)
     }
 
     fn ime<W>(&mut self, window: W, kludgine: &mut Kludgine, ime: &Ime) -> EventHandling
     where
         W: PlatformWindowImplementation,
     {
+        idle::note_input();
         let cushy = self.app.cushy().clone();
         let _guard = cushy.enter_runtime();
         let mut window = RunningWindow::new(
@@ -2282,6 +3485,7 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         );
         let widget = self
@@ -2312,13 +3516,53 @@ where
 
     fn cursor_moved<W>(
         &mut self,
-        window: W,
+        mut window: W,
         kludgine: &mut Kludgine,
         device_id: DeviceId,
         position: impl Into<Point<Px>>,
     ) where
         W: PlatformWindowImplementation,
     {
+        idle::note_input();
+        let location = position.into();
+        // Widget-based masks are applied by hovering only widgets that accept
+        // the cursor.
+        self.cursor_masked = !self
+            .hit_test_mask
+            .map(|mask| mask.contains(location, || true));
+        if self.cursor_masked {
+            self.cursor_left(window, kludgine);
+            return;
+        }
+        self.cursor.location = Some(location);
+        self.cursor_position.set_and_read(location);
+        if let Some(highlight) = &self.pointer_highlight {
+            highlight.cursor_moved(Some(location), window.inner_size().into_signed());
+        }
+        if self
+            .magnifier
+            .as_ref()
+            .is_some_and(Magnifier::follows_cursor)
+        {
+            window.set_needs_redraw();
+        }
+
+        let coalesces = self.hovered_coalesces_input()
+            || self.mouse_buttons.get(&device_id).is_some_and(|buttons| {
+                buttons
+                    .values()
+                    .filter_map(|handler| self.tree.widget(*handler))
+                    .any(|handler| handler.coalesces_input())
+            });
+        if coalesces {
+            // The latest location is delivered when the next frame is
+            // prepared.
+            self.coalesced_input.cursor = Some(device_id);
+            window.set_needs_redraw();
+            return;
+        }
+        self.coalesced_input.cursor = None;
+
         let cushy = self.app.cushy().clone();
         let _guard = cushy.enter_runtime();
         let mut window = RunningWindow::new(
@@ -2329,14 +3573,11 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         );
 
-        let location = position.into();
-        self.cursor.location = Some(location);
-        self.cursor_position.set_and_read(location);
-
-        EventContext::new(
+        let mut context = EventContext::new(
             WidgetContext::new(
                 self.root.clone(),
                 &self.current_theme,
@@ -2348,34 +3589,23 @@ where
                 &self.app.cushy().data.localizations,
             ),
             kludgine,
-        )
-        .update_hovered_widget();
+        );
+        dispatch_cursor_moved(
+            &mut context,
+            &self.tree,
+            &self.mouse_buttons,
+            device_id,
+            location,
+        );
+    }
 
-        if let Some(state) = self.mouse_buttons.get(&device_id) {
-            // Mouse Drag
-            for (button, handler) in state {
-                let Some(handler) = self.tree.widget(*handler) else {
-                    continue;
-                };
-                let mut context = EventContext::new(
-                    WidgetContext::new(
-                        handler.clone(),
-                        &self.current_theme,
-                        &mut window,
-                        &mut self.fonts,
-                        self.theme_mode.get(),
-                        &mut self.cursor,
-                        #[cfg(feature = "localization")]
-                        &self.app.cushy().data.localizations,
-                    ),
-                    kludgine,
-                );
-                let Some(last_rendered_at) = context.last_layout() else {
-                    continue;
-                };
-                context.mouse_drag(location - last_rendered_at.origin, device_id, *button);
-            }
-        }
+    /// Returns true if the hovered widget coalesces high-frequency input
+    /// events.
+    fn hovered_coalesces_input(&self) -> bool {
+        self.tree
+            .hovered_widget()
+            .and_then(|hovered| self.tree.widget_from_node(hovered))
+            .is_some_and(|hovered| hovered.coalesces_input())
     }
 
     fn cursor_left<W>(&mut self, window: W, kludgine: &mut Kludgine)
@@ -2385,6 +3615,10 @@ where
         let cushy = self.app.cushy().clone();
         let _guard = cushy.enter_runtime();
         self.cursor.location = None;
+        self.coalesced_input.cursor = None;
+        if let Some(highlight) = &self.pointer_highlight {
+            highlight.cursor_moved(None, window.inner_size().into_signed());
+        }
         self.cursor_position
             .set_and_read(Point::squared(Px::new(-1)));
         if self.cursor.widget.take().is_some() {
@@ -2396,6 +3630,7 @@ where
                 &self.focused,
                 &self.occluded,
                 self.inner_size.source(),
+                &self.effective_scale,
                 &self.close_requested,
             );
 
@@ -2434,6 +3669,7 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         );
         if let (Some(location), Some(hovered)) = (
@@ -2458,10 +3694,9 @@ where
                     kludgine,
                 ),
                 |context| {
-                    let Some(layout) = context.last_layout() else {
+                    let Some(relative) = context.window_to_widget(location) else {
                         return IGNORED;
                     };
-                    let relative = location - layout.origin;
                     context.mouse_down(relative, device_id, button)
                 },
             ) {
@@ -2508,6 +3743,7 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         );
         let Some(device_buttons) = self.mouse_buttons.get_mut(&device_id) else {
@@ -2537,13 +3773,7 @@ where
             kludgine,
         );
 
-        let relative = if let (Some(last_rendered), Some(location)) =
-            (context.last_layout(), cursor_location)
-        {
-            Some(location - last_rendered.origin)
-        } else {
-            None
-        };
+        let relative = cursor_location.and_then(|location| context.window_to_widget(location));
 
         context.mouse_up(relative, device_id, button);
         HANDLED
@@ -2560,23 +3790,86 @@ where
     where
         W: PlatformWindowImplementation,
     {
+        idle::note_input();
         let cushy = self.app.cushy().clone();
         let _guard = cushy.enter_runtime();
+        if state.is_pressed() {
+            let in_mask = self.cursor.location.map_or(true, |location| {
+                self.hit_test_mask
+                    .map(|mask| mask.contains(location, || self.cursor.widget.is_some()))
+            });
+            if self.cursor_masked || !in_mask {
+                return IGNORED;
+            }
+            if let (Some(highlight), Some(location)) =
+                (&self.pointer_highlight, self.cursor.location)
+            {
+                highlight.clicked(location, window.inner_size().into_signed());
+            }
+            if let Some(on_double_tap) = self
+                .gestures
+                .button_pressed(button, cushy.multi_click_threshold())
+            {
+                on_double_tap.invoke(window.handle(self.redraw_status.clone()));
+                return HANDLED;
+            }
+        }
         match state {
             ElementState::Pressed => self.mouse_down(window, kludgine, device_id, button),
             ElementState::Released => self.mouse_up(window, kludgine, device_id, button),
         }
     }
 
-    fn handle_drop(
-        &mut self,
-        drop: DropEvent<PathBuf>,
-        window: &kludgine::app::Window<'_, WindowCommand>,
-    ) {
-        if let Some(on_file_drop) = &mut self.on_file_drop {
-            on_file_drop.notify(FileDrop {
-                window: WindowHandle::new(window.handle(), self.redraw_status.clone()),
-                drop,
+    fn handle_drop<W>(&mut self, event: DropEvent<PathBuf>, window: W, kludgine: &mut Kludgine)
+    where
+        W: PlatformWindowImplementation,
+    {
+        let cushy = self.app.cushy().clone();
+        let _guard = cushy.enter_runtime();
+        let mut window = RunningWindow::new(
+            window,
+            kludgine.id(),
+            &self.redraw_status,
+            &self.app,
+            &self.focused,
+            &self.occluded,
+            self.inner_size.source(),
+            &self.effective_scale,
+            &self.close_requested,
+        );
+
+        match &event {
+            DropEvent::Hover(path) => self
+                .behavior
+                .file_hovered(Some(path.as_path()), &mut window),
+            DropEvent::Dropped(path) => self.behavior.file_dropped(path, &mut window),
+            DropEvent::Cancelled => self.behavior.file_hovered(None, &mut window),
+        }
+
+        let mut context = EventContext::new(
+            WidgetContext::new(
+                self.root.clone(),
+                &self.current_theme,
+                &mut window,
+                &mut self.fonts,
+                self.theme_mode.get(),
+                &mut self.cursor,
+                #[cfg(feature = "localization")]
+                &self.app.cushy().data.localizations,
+            ),
+            kludgine,
+        );
+        handle_file_drop(event.clone(), &mut context);
+        drop(context);
+
+        let callback = match &event {
+            DropEvent::Dropped(_) => &mut self.on_file_drop,
+            DropEvent::Hover(_) | DropEvent::Cancelled => &mut self.on_file_hover,
+        };
+        if let Some(callback) = callback {
+            callback.notify(FileDrop {
+                window: window.handle(),
+                drop: event,
             });
         }
     }
@@ -2589,6 +3882,32 @@ enum RootMode {
     Align,
 }
 
+/// The configuration of a window that is sized to fit its contents.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SizeToContent {
+    limits: Size<DimensionRange>,
+    reveal: bool,
+    fit_attempted: bool,
+}
+
+impl SizeToContent {
+    fn minimum(&self, scale: Fraction) -> Size<UPx> {
+        self.limits.map(|range| {
+            range
+                .minimum()
+                .map_or(UPx::ZERO, |minimum| minimum.into_upx(scale))
+        })
+    }
+
+    fn maximum(&self, scale: Fraction) -> Size<UPx> {
+        self.limits.map(|range| {
+            range
+                .maximum()
+                .map_or(UPx::MAX, |maximum| maximum.into_upx(scale))
+        })
+    }
+}
+
 impl<T> kludgine::app::WindowBehavior<WindowCommand> for OpenWindow<T>
 where
     T: WindowBehavior,
@@ -2619,6 +3938,7 @@ where
             &settings.focused,
             &settings.occluded,
             &settings.inner_size,
+            &settings.effective_scale,
             &settings.close_requested,
         );
         drop(settings);
@@ -2644,6 +3964,21 @@ where
         let inner_size = window.inner_size();
         self.resized(inner_size, &window);
 
+        let handle = WindowHandle::new(window.handle(), self.redraw_status.clone());
+        self.app.windows().register(handle.clone());
+        #[cfg(feature = "gamepad")]
+        crate::gamepad::register_window(handle.clone(), self.focused.clone(), self.app.windows());
+        if let Some(owner) = &self.owner {
+            owner
+                .window
+                .inner
+                .send(WindowCommand::OwnedOpened(Ownership {
+                    window: handle.clone(),
+                    modal: owner.modal,
+                }));
+        }
+        self.registered_handle = Some(handle);
+
         self.behavior.initialized(&mut RunningWindow::new(
             window,
             kludgine.id(),
@@ -2652,6 +3987,7 @@ where
             &self.focused,
             &self.occluded,
             self.inner_size.source(),
+            &self.effective_scale,
             &self.close_requested,
         ));
     }
@@ -2682,10 +4018,14 @@ where
 
     fn focus_changed(
         &mut self,
-        window: kludgine::app::Window<'_, WindowCommand>,
+        mut window: kludgine::app::Window<'_, WindowCommand>,
         _kludgine: &mut Kludgine,
     ) {
         self.set_focused(window.focused());
+        if window.focused() {
+            // Redrawing applies any focus restoration.
+            window.set_needs_redraw();
+        }
     }
 
     fn occlusion_changed(
@@ -2698,10 +4038,21 @@ where
 
     fn render<'pass>(
         &'pass mut self,
-        _window: kludgine::app::Window<'_, WindowCommand>,
+        mut window: kludgine::app::Window<'_, WindowCommand>,
         graphics: &mut kludgine::RenderingGraphics<'_, 'pass>,
     ) {
+        // Fonts that were deferred are loaded once the first frame has been
+        // rendered without them.
+        if !self.deferred_fonts_loaded {
+            self.deferred_fonts_loaded = true;
+            if self.fonts.load_deferred() {
+                window.set_needs_redraw();
+            }
+        }
         self.contents.render(1., graphics);
+        if let Some(on_first_frame) = self.on_first_frame.take() {
+            on_first_frame.invoke(());
+        }
     }
 
     fn initial_window_attributes(context: &Self::Context) -> kludgine::app::WindowAttributes {
@@ -2739,6 +4090,7 @@ where
                 &self.focused,
                 &self.occluded,
                 self.inner_size.source(),
+                &self.effective_scale,
                 &self.close_requested,
             ),
         )
@@ -2753,7 +4105,9 @@ where
     // }
 
     fn clear_color(&self) -> Option<kludgine::Color> {
-        Some(if self.transparent {
+        Some(if let Some(clear_color) = &self.clear_color {
+            clear_color.get()
+        } else if self.transparent {
             kludgine::Color::CLEAR_BLACK
         } else {
             kludgine::Color::BLACK
@@ -2777,7 +4131,8 @@ where
         mut window: kludgine::app::Window<'_, WindowCommand>,
         kludgine: &mut Kludgine,
     ) {
-        self.dpi_scale.set(kludgine.dpi_scale());
+        self.dpi_scale.source().set(kludgine.dpi_scale());
+        self.update_current_monitor(&window);
         window.set_needs_redraw();
     }
 
@@ -2795,32 +4150,33 @@ where
         _kludgine: &mut Kludgine,
     ) {
         self.moved(window.inner_position(), window.outer_position());
+        self.update_current_monitor(&window);
     }
 
     fn dropped_file(
         &mut self,
         window: kludgine::app::Window<'_, WindowCommand>,
-        _kludgine: &mut Kludgine,
+        kludgine: &mut Kludgine,
         path: PathBuf,
     ) {
-        self.handle_drop(DropEvent::Dropped(path), &window);
+        self.handle_drop(DropEvent::Dropped(path), window, kludgine);
     }
 
     fn hovered_file(
         &mut self,
         window: kludgine::app::Window<'_, WindowCommand>,
-        _kludgine: &mut Kludgine,
+        kludgine: &mut Kludgine,
         path: PathBuf,
     ) {
-        self.handle_drop(DropEvent::Hover(path), &window);
+        self.handle_drop(DropEvent::Hover(path), window, kludgine);
     }
 
     fn hovered_file_cancelled(
         &mut self,
         window: kludgine::app::Window<'_, WindowCommand>,
-        _kludgine: &mut Kludgine,
+        kludgine: &mut Kludgine,
     ) {
-        self.handle_drop(DropEvent::Cancelled, &window);
+        self.handle_drop(DropEvent::Cancelled, window, kludgine);
     }
 
     // fn received_character(&mut self, window: kludgine::app::Window<'_, ()>, char: char) {}
@@ -2833,6 +4189,9 @@ where
         input: winit::event::KeyEvent,
         is_synthetic: bool,
     ) {
+        if self.modal_window().is_some() {
+            return;
+        }
         let event = KeyEvent::from_winit(input, window.modifiers());
         self.keyboard_input(window, kludgine, device_id.into(), event, is_synthetic);
     }
@@ -2845,9 +4204,24 @@ where
         delta: MouseScrollDelta,
         phase: TouchPhase,
     ) {
+        if self.modal_window().is_some() {
+            return;
+        }
         self.mouse_wheel(window, kludgine, device_id.into(), delta, phase);
     }
 
+    fn touch(
+        &mut self,
+        window: kludgine::app::Window<'_, WindowCommand>,
+        kludgine: &mut Kludgine,
+        touch: Touch,
+    ) {
+        if self.modal_window().is_some() {
+            return;
+        }
+        self.touch(window, kludgine, touch);
+    }
+
     fn modifiers_changed(
         &mut self,
         window: kludgine::app::Window<'_, WindowCommand>,
@@ -2862,6 +4236,9 @@ where
         kludgine: &mut Kludgine,
         ime: Ime,
     ) {
+        if self.modal_window().is_some() {
+            return;
+        }
         self.ime(window, kludgine, &ime);
     }
 
@@ -2872,6 +4249,9 @@ where
         device_id: winit::event::DeviceId,
         position: PhysicalPosition<f64>,
     ) {
+        if self.modal_window().is_some() {
+            return;
+        }
         self.cursor_moved(window, kludgine, device_id.into(), position);
     }
 
@@ -2892,7 +4272,16 @@ where
         state: ElementState,
         button: MouseButton,
     ) {
-        self.mouse_input(window, kludgine, device_id.into(), state, button);
+        let device_id = device_id.into();
+        if self.modal_blocks_mouse_input(device_id, state, button) {
+            if state.is_pressed() {
+                if let Some(modal) = self.modal_window() {
+                    modal.inner.send(WindowCommand::Focus);
+                }
+            }
+            return;
+        }
+        self.mouse_input(window, kludgine, device_id, state, button);
     }
 
     fn theme_changed(
@@ -2927,6 +4316,7 @@ where
                     &self.focused,
                     &self.occluded,
                     self.inner_size.source(),
+                    &self.effective_scale,
                     &self.close_requested,
                 );
                 if self.behavior.close_requested(&mut window) {
@@ -2946,6 +4336,28 @@ where
             WindowCommand::Focus => {
                 window.winit().focus_window();
             }
+            WindowCommand::OwnedOpened(owned) => {
+                self.owned_window_opened(owned);
+            }
+            WindowCommand::OwnedClosed(closed) => {
+                self.owned_windows.retain(|owned| owned.window != closed);
+            }
+            WindowCommand::OwnerMinimized(minimized) => {
+                window.winit().set_minimized(minimized);
+            }
+            WindowCommand::Fullscreen(change) => {
+                let fullscreen = match change {
+                    FullscreenChange::Set(fullscreen) => fullscreen,
+                    FullscreenChange::Toggle(fullscreen) => {
+                        window.winit().fullscreen().is_none().then_some(fullscreen)
+                    }
+                };
+                if let Value::Dynamic(dynamic) = self.fullscreen.source() {
+                    dynamic.set(fullscreen);
+                } else {
+                    window.winit().set_fullscreen(fullscreen);
+                }
+            }
             WindowCommand::Ize(ize) => {
                 let (minimize, maximize) = match ize {
                     Some(Ize::Maximize) => (false, true),
@@ -2972,6 +4384,7 @@ where
                     &self.focused,
                     &self.occluded,
                     self.inner_size.source(),
+                    &self.effective_scale,
                     &self.close_requested,
                 );
                 let mut context = EventContext::new(
@@ -2989,6 +4402,41 @@ where
                 );
                 func.execute(&mut context);
             }
+            WindowCommand::Message(message) => {
+                let Some(handler) = self.message_handlers.get_mut(&*message) else {
+                    tracing::warn!("no handler registered for message sent to window");
+                    return;
+                };
+                let mut window = RunningWindow::new(
+                    window,
+                    kludgine.id(),
+                    &self.redraw_status,
+                    &self.app,
+                    &self.focused,
+                    &self.occluded,
+                    self.inner_size.source(),
+                    &self.effective_scale,
+                    &self.close_requested,
+                );
+                let mut context = EventContext::new(
+                    WidgetContext::new(
+                        self.root.clone(),
+                        &self.current_theme,
+                        &mut window,
+                        &mut self.fonts,
+                        self.theme_mode.get(),
+                        &mut self.cursor,
+                        #[cfg(feature = "localization")]
+                        &self.app.cushy().data.localizations,
+                    ),
+                    kludgine,
+                );
+                handler.handle(message, &mut context);
+            }
+            #[cfg(feature = "gamepad")]
+            WindowCommand::Gamepad(intent) => {
+                self.gamepad_input(window, kludgine, intent);
+            }
         }
     }
 
@@ -3033,9 +4481,21 @@ where
 
 impl<Behavior> Drop for OpenWindow<Behavior> {
     fn drop(&mut self) {
+        if let Some(handle) = self.registered_handle.take() {
+            self.app.windows().unregister(&handle);
+            if let Some(owner) = &self.owner {
+                owner.window.inner.send(WindowCommand::OwnedClosed(handle));
+            }
+        }
+        for owned in self.owned_windows.drain(..) {
+            owned.window.request_close();
+        }
         if let Some(on_closed) = self.on_closed.take() {
             on_closed.invoke(());
         }
+        telemetry::emit(|| TelemetryEvent::WindowClosed {
+            window: self.frame_times.window().clone(),
+        });
     }
 }
 
@@ -3051,10 +4511,148 @@ fn recursively_handle_event(
     }
 }
 
+/// Delivers a scroll event to the widget in `context`, followed by its parents
+/// until the event is fully handled.
+///
+/// Widgets that only handle part of the event can pass the remainder to their
+/// parents using [`EventContext::set_unhandled_scroll()`].
+fn recursively_handle_scroll(
+    context: &mut EventContext<'_>,
+    device_id: DeviceId,
+    delta: MouseScrollDelta,
+    phase: TouchPhase,
+) -> EventHandling {
+    context.cursor.unhandled_scroll = None;
+    let (handled, delta) = match context.mouse_wheel(device_id, delta, phase) {
+        HANDLED => match context.cursor.unhandled_scroll.take() {
+            Some(remaining) => (HANDLED, remaining),
+            None => return HANDLED,
+        },
+        IGNORED => (IGNORED, delta),
+    };
+    match context.parent() {
+        Some(parent) => {
+            let parent_handled =
+                recursively_handle_scroll(&mut context.for_other(&parent), device_id, delta, phase);
+            if handled.is_break() {
+                handled
+            } else {
+                parent_handled
+            }
+        }
+        None => handled,
+    }
+}
+
+/// Updates the hovered widget and any widgets being dragged by `device_id` after
+/// the cursor moved to `location`.
+///
+/// `context` must be the context of the root widget.
+fn dispatch_cursor_moved(
+    context: &mut EventContext<'_>,
+    tree: &Tree,
+    mouse_buttons: &AHashMap<DeviceId, AHashMap<MouseButton, WidgetId>>,
+    device_id: DeviceId,
+    location: Point<Px>,
+) {
+    context.update_hovered_widget();
+    update_file_hover(context);
+
+    if let Some(state) = mouse_buttons.get(&device_id) {
+        // Mouse Drag
+        for (button, handler) in state {
+            let Some(handler) = tree.widget(*handler) else {
+                continue;
+            };
+            let mut context = context.for_other(&handler);
+            let Some(relative) = context.window_to_widget(location) else {
+                continue;
+            };
+            context.mouse_drag(relative, device_id, *button);
+        }
+    }
+}
+
+/// Input events waiting to be delivered to widgets that coalesce input.
+#[derive(Default)]
+struct CoalescedInput {
+    cursor: Option<DeviceId>,
+    wheel: Vec<PendingWheel>,
+}
+
+impl CoalescedInput {
+    fn push_wheel(
+        &mut self,
+        device_id: DeviceId,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+        source: ScrollSource,
+    ) {
+        if let Some(pending) = self
+            .wheel
+            .last_mut()
+            .filter(|pending| pending.device_id == device_id && pending.source == source)
+        {
+            let combined = match (pending.delta, delta) {
+                (
+                    MouseScrollDelta::LineDelta(pending_x, pending_y),
+                    MouseScrollDelta::LineDelta(x, y),
+                ) => Some(MouseScrollDelta::LineDelta(pending_x + x, pending_y + y)),
+                (MouseScrollDelta::PixelDelta(pending), MouseScrollDelta::PixelDelta(delta)) => {
+                    Some(MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+                        pending.x + delta.x,
+                        pending.y + delta.y,
+                    )))
+                }
+                _ => None,
+            };
+            if let Some(combined) = combined {
+                pending.delta = combined;
+                pending.phase = phase;
+                return;
+            }
+        }
+
+        // Deltas that cannot be combined with the pending delta are delivered
+        // after it.
+        self.wheel.push(PendingWheel {
+            device_id,
+            delta,
+            phase,
+            source,
+        });
+    }
+}
+
+/// A mouse wheel event waiting to be delivered.
+struct PendingWheel {
+    device_id: DeviceId,
+    delta: MouseScrollDelta,
+    phase: TouchPhase,
+    source: ScrollSource,
+}
+
+/// Returns `delta` multiplied by `multiplier`.
+fn scale_scroll_delta(delta: MouseScrollDelta, multiplier: f32) -> MouseScrollDelta {
+    match delta {
+        MouseScrollDelta::LineDelta(x, y) => {
+            MouseScrollDelta::LineDelta(x * multiplier, y * multiplier)
+        }
+        MouseScrollDelta::PixelDelta(delta) => MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+            delta.x * f64::from(multiplier),
+            delta.y * f64::from(multiplier),
+        )),
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct CursorState {
     pub(crate) location: Option<Point<Px>>,
     pub(crate) widget: Option<WidgetCursorState>,
+    pub(crate) drag: Option<ActiveDrag>,
+    pub(crate) file_hover: FileHover,
+    pub(crate) scroll_source: ScrollSource,
+    pub(crate) unhandled_scroll: Option<MouseScrollDelta>,
 }
 
 #[derive(Eq, PartialEq)]
@@ -3073,8 +4671,9 @@ pub(crate) mod sealed {
     use image::{DynamicImage, RgbaImage};
     use kludgine::app::winit;
     use kludgine::app::winit::event::Modifiers;
+    use kludgine::app::winit::monitor::MonitorHandle;
     use kludgine::app::winit::window::{Fullscreen, UserAttentionType, WindowButtons, WindowLevel};
-    use kludgine::Color;
+    use kludgine::{wgpu, Color, Texture};
 
     use crate::context::sealed::InvalidationStatus;
     use crate::context::EventContext;
@@ -3083,7 +4682,10 @@ pub(crate) mod sealed {
     use crate::styles::{FontFamilyList, ThemePair};
     use crate::widget::{Notify, OnceCallback, SharedCallback};
     use crate::widgets::shortcuts::ShortcutMap;
-    use crate::window::{FileDrop, PendingWindow, ThemeMode, WindowAttributes, WindowHandle};
+    use crate::window::{
+        FileDrop, GestureShortcuts, HitTestMask, Magnifier, PendingWindow, PrePresent,
+        SizeToContent, ThemeMode, WindowAttributes, WindowHandle,
+    };
     use crate::{App, MaybeLocalized};
 
     pub struct Context<C> {
@@ -3101,6 +4703,8 @@ pub(crate) mod sealed {
         pub focused: Dynamic<bool>,
         pub inner_size: Dynamic<Size<UPx>>,
         pub zoom: Dynamic<Fraction>,
+        pub content_scale: Value<Option<Fraction>>,
+        pub effective_scale: Dynamic<Fraction>,
         pub theme: Option<Value<ThemePair>>,
         pub theme_mode: Option<Value<ThemeMode>>,
         pub transparent: bool,
@@ -3111,17 +4715,28 @@ pub(crate) mod sealed {
         pub cursive_font_family: FontFamilyList,
         pub font_data_to_load: FontCollection,
         pub on_open: Option<OnceCallback<WindowHandle>>,
+        pub on_first_frame: Option<OnceCallback>,
+        pub on_announcement: Option<Callback<Announcement>>,
+        pub spatial_navigation: Value<bool>,
         pub on_init: Option<PreShowCallback>,
+        pub on_pre_present: Option<PrePresentCallback>,
+        pub client_decorations: Option<Value<ClientDecorations>>,
         pub on_closed: Option<OnceCallback>,
         pub vsync: bool,
         pub multisample_count: NonZeroU32,
         pub resize_to_fit: Value<bool>,
+        pub size_to_content: Option<SizeToContent>,
         pub close_requested: Option<SharedCallback<(), bool>>,
         pub content_protected: Value<bool>,
         pub cursor_hittest: Value<bool>,
+        pub hit_test_mask: Value<HitTestMask>,
+        pub clear_color: Option<Value<Color>>,
+        pub magnifier: Option<Magnifier>,
+        pub pointer_highlight: Option<PointerHighlight>,
         pub cursor_visible: Value<bool>,
         pub cursor_position: Dynamic<Point<Px>>,
         pub window_level: Value<WindowLevel>,
+        pub owner: Option<Ownership>,
         pub decorated: Value<bool>,
         pub maximized: Dynamic<bool>,
         pub minimized: Dynamic<bool>,
@@ -3130,13 +4745,17 @@ pub(crate) mod sealed {
         pub visible: Dynamic<bool>,
         pub inner_position: Dynamic<Point<Px>>,
         pub outer_position: Dynamic<Point<Px>>,
+        pub current_monitor: Dynamic<Option<MonitorHandle>>,
         pub outer_size: Dynamic<Size<UPx>>,
         pub window_icon: Value<Option<RgbaImage>>,
         pub modifiers: Dynamic<Modifiers>,
         pub enabled_buttons: Value<WindowButtons>,
         pub fullscreen: Value<Option<Fullscreen>>,
         pub shortcuts: Value<ShortcutMap>,
+        pub gesture_shortcuts: GestureShortcuts,
         pub on_file_drop: Option<Notify<FileDrop>>,
+        pub message_handlers: MessageHandlers,
+        pub on_file_hover: Option<Notify<FileDrop>>,
     }
 
     pub struct WindowExecute(Box<dyn ExecuteFunc>);
@@ -3183,8 +4802,25 @@ pub(crate) mod sealed {
         RequestUserAttention(Option<UserAttentionType>),
         Focus,
         Ize(Option<Ize>),
+        Fullscreen(FullscreenChange),
         SetTitle(MaybeLocalized),
         Execute(WindowExecute),
+        Message(Box<dyn Any + Send>),
+        OwnedOpened(Ownership),
+        OwnedClosed(WindowHandle),
+        OwnerMinimized(bool),
+        #[cfg(feature = "gamepad")]
+        Gamepad(crate::gamepad::GamepadIntent),
+    }
+
+    /// The relationship between an owned window and its owner.
+    ///
+    /// Owned windows store their owner's handle, while owners store the
+    /// handle of each window they own.
+    #[derive(Debug, Clone)]
+    pub struct Ownership {
+        pub window: WindowHandle,
+        pub modal: bool,
     }
 
     #[derive(Debug, Clone)]
@@ -3193,6 +4829,12 @@ pub(crate) mod sealed {
         Minimize,
     }
 
+    #[derive(Debug, Clone)]
+    pub enum FullscreenChange {
+        Set(Option<Fullscreen>),
+        Toggle(Fullscreen),
+    }
+
     pub trait CaptureFormat {
         const HAS_ALPHA: bool;
 
@@ -3203,6 +4845,92 @@ pub(crate) mod sealed {
 
     pub struct PreShowCallback(pub Box<dyn PreShowFn>);
 
+    pub struct PrePresentCallback {
+        callback: Box<dyn PrePresentFn>,
+        overlay: Option<Texture>,
+    }
+
+    impl PrePresentCallback {
+        pub fn new(callback: impl PrePresentFn) -> Self {
+            Self {
+                callback: Box::new(callback),
+                overlay: None,
+            }
+        }
+
+        /// Clears the overlay, resizing it to `size` if needed, and invokes the
+        /// callback to record commands that render into it.
+        pub fn render_overlay(&mut self, size: Size<UPx>, graphics: &kludgine::Graphics<'_>) {
+            if size.width.get() == 0 || size.height.get() == 0 {
+                self.overlay = None;
+                return;
+            }
+            if self
+                .overlay
+                .as_ref()
+                .is_some_and(|overlay| overlay.size() != size)
+            {
+                self.overlay = None;
+            }
+            let overlay = self.overlay.get_or_insert_with(|| {
+                Texture::new(
+                    graphics,
+                    size,
+                    PrePresent::FORMAT,
+                    wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                    wgpu::FilterMode::Linear,
+                )
+            });
+
+            let device = graphics.device();
+            let queue = graphics.queue();
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("cushy pre-present"),
+            });
+            drop(encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("cushy pre-present"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: overlay.view(),
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            }));
+            self.callback.pre_present(&mut PrePresent {
+                encoder: &mut encoder,
+                view: overlay.view(),
+                size,
+                device,
+                queue,
+            });
+            queue.submit([encoder.finish()]);
+        }
+
+        /// Returns the overlay rendered by the last call to
+        /// [`render_overlay()`](Self::render_overlay).
+        pub fn overlay(&self) -> Option<&Texture> {
+            self.overlay.as_ref()
+        }
+    }
+
+    pub trait PrePresentFn: Send + 'static {
+        fn pre_present(&mut self, frame: &mut PrePresent<'_>);
+    }
+
+    impl<F> PrePresentFn for F
+    where
+        F: FnMut(&mut PrePresent<'_>) + Send + 'static,
+    {
+        fn pre_present(&mut self, frame: &mut PrePresent<'_>) {
+            self(frame);
+        }
+    }
+
     pub trait PreShowFn: Send + 'static {
         fn pre_show(&mut self, winit: &winit::window::Window);
     }
@@ -3349,6 +5077,44 @@ impl WindowHandle {
         self.inner.send(sealed::WindowCommand::RequestClose);
     }
 
+    /// Requests that the window is minimized.
+    pub fn minimize(&self) {
+        self.inner.send(WindowCommand::Ize(Some(Ize::Minimize)));
+    }
+
+    /// Requests that the window is maximized.
+    pub fn maximize(&self) {
+        self.inner.send(WindowCommand::Ize(Some(Ize::Maximize)));
+    }
+
+    /// Requests that the window is restored from being minimized or
+    /// maximized.
+    pub fn restore(&self) {
+        self.inner.send(WindowCommand::Ize(None));
+    }
+
+    /// Requests that the window changes its fullscreen mode.
+    ///
+    /// `None` exits fullscreen. If the window's fullscreen mode is controlled
+    /// by a dynamic passed to [`Window::fullscreen`], the dynamic is updated
+    /// with the new mode.
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.inner
+            .send(WindowCommand::Fullscreen(FullscreenChange::Set(fullscreen)));
+    }
+
+    /// Requests that the window enters `fullscreen` if it is not fullscreen,
+    /// or exits fullscreen if it is.
+    ///
+    /// If the window's fullscreen mode is controlled by a dynamic passed to
+    /// [`Window::fullscreen`], the dynamic is updated with the new mode.
+    pub fn toggle_fullscreen(&self, fullscreen: Fullscreen) {
+        self.inner
+            .send(WindowCommand::Fullscreen(FullscreenChange::Toggle(
+                fullscreen,
+            )));
+    }
+
     /// Requests that the window redraws.
     pub fn redraw(&self) {
         if self.redraw_status.should_send_refresh() {
@@ -3362,23 +5128,138 @@ impl WindowHandle {
         }
     }
 
-    /// Marks `widget` as invalidated, and if needed, refreshes the window.
-    pub fn invalidate(&self, widget: WidgetId) {
-        if self.redraw_status.invalidate(widget) {
-            self.redraw();
-        }
+    /// Marks `widget` as invalidated, and if needed, refreshes the window.
+    pub fn invalidate(&self, widget: WidgetId) {
+        if self.redraw_status.invalidate(widget) {
+            self.redraw();
+        }
+    }
+
+    /// Executes `func` on the window thread.
+    pub fn execute<F>(&self, func: F)
+    where
+        F: FnOnce(&mut EventContext<'_>) + Send + 'static,
+    {
+        self.inner
+            .send(WindowCommand::Execute(WindowExecute::new(func)));
+    }
+
+    /// Sends `message` to this window.
+    ///
+    /// The message is delivered on the window's thread to the handler
+    /// registered for `Message` using [`Window::on_message()`]. Messages with
+    /// no registered handler are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - [`WindowThreadError::Closed`]: The window has been closed.
+    /// - [`WindowThreadError::Unsupported`]: The window is a virtual window,
+    ///   which cannot receive messages.
+    pub fn send_message<Message>(&self, message: Message) -> Result<(), WindowThreadError>
+    where
+        Message: Send + 'static,
+    {
+        self.inner
+            .try_send(WindowCommand::Message(Box::new(message)))
+    }
+
+    /// Sends `intent` to this window, returning false if the window can no
+    /// longer receive it.
+    #[cfg(feature = "gamepad")]
+    pub(crate) fn send_gamepad_intent(&self, intent: crate::gamepad::GamepadIntent) -> bool {
+        self.inner.try_send(WindowCommand::Gamepad(intent)).is_ok()
+    }
+
+    /// Returns true if the current thread is the thread this window's events
+    /// are handled on.
+    ///
+    /// Returns false if the window has not been opened yet.
+    #[must_use]
+    pub fn is_window_thread(&self) -> bool {
+        self.redraw_status.thread() == Some(std::thread::current().id())
     }
 
     /// Executes `func` on the window thread.
-    pub fn execute<F>(&self, func: F)
+    ///
+    /// Unlike [`Self::execute`], this function reports when `func` cannot be
+    /// executed.
+    ///
+    /// # Errors
+    ///
+    /// - [`WindowThreadError::Closed`]: The window has been closed.
+    /// - [`WindowThreadError::Unsupported`]: The window is a virtual window,
+    ///   which has no thread to execute `func` on.
+    pub fn try_execute<F>(&self, func: F) -> Result<(), WindowThreadError>
     where
         F: FnOnce(&mut EventContext<'_>) + Send + 'static,
     {
         self.inner
-            .send(WindowCommand::Execute(WindowExecute::new(func)));
+            .try_send(WindowCommand::Execute(WindowExecute::new(func)))
+    }
+
+    /// Executes `func` on the window thread, blocking the current thread until
+    /// it has completed and returning its result.
+    ///
+    /// # Errors
+    ///
+    /// - [`WindowThreadError::WouldDeadlock`]: The current thread is the
+    ///   window's thread, which cannot execute `func` while this function is
+    ///   waiting for it to do so.
+    /// - [`WindowThreadError::NotOpen`]: The window has not been opened yet.
+    /// - [`WindowThreadError::Closed`]: The window was closed before `func`
+    ///   was executed.
+    /// - [`WindowThreadError::Unsupported`]: The window is a virtual window,
+    ///   which has no thread to execute `func` on.
+    pub fn try_execute_blocking<F, R>(&self, func: F) -> Result<R, WindowThreadError>
+    where
+        F: FnOnce(&mut EventContext<'_>) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        if self.is_window_thread() {
+            return Err(WindowThreadError::WouldDeadlock);
+        } else if self.redraw_status.thread().is_none() {
+            return Err(WindowThreadError::NotOpen);
+        }
+
+        let (sender, receiver) = mpsc::sync_channel(1);
+        self.try_execute(move |context| {
+            let _result = sender.send(func(context));
+        })?;
+        receiver.recv().map_err(|_| WindowThreadError::Closed)
+    }
+}
+
+/// An error from an operation that must be performed on a window's thread.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WindowThreadError {
+    /// The window has not been opened yet.
+    NotOpen,
+    /// The window has been closed.
+    Closed,
+    /// The operation would wait for the window's thread while being invoked
+    /// from the window's thread, which would never finish.
+    WouldDeadlock,
+    /// The window does not support this operation.
+    Unsupported,
+}
+
+impl std::fmt::Display for WindowThreadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowThreadError::NotOpen => f.write_str("the window has not been opened"),
+            WindowThreadError::Closed => f.write_str("the window has been closed"),
+            WindowThreadError::WouldDeadlock => f.write_str(
+                "the operation waits for the window's thread and cannot be called from it",
+            ),
+            WindowThreadError::Unsupported => {
+                f.write_str("the window does not support this operation")
+            }
+        }
     }
 }
 
+impl std::error::Error for WindowThreadError {}
+
 impl Eq for WindowHandle {}
 
 impl PartialEq for WindowHandle {
@@ -3401,11 +5282,32 @@ enum InnerWindowHandle {
 }
 
 impl InnerWindowHandle {
+    fn try_send(&self, message: WindowCommand) -> Result<(), WindowThreadError> {
+        match self {
+            InnerWindowHandle::Pending(pending) => {
+                if let Some(handle) = pending.handle.get() {
+                    handle.send(message).map_err(|_| WindowThreadError::Closed)
+                } else if let Some(state) = pending.virtual_state.get() {
+                    InnerWindowHandle::Virtual(state.clone()).try_send(message)
+                } else {
+                    pending.commands.lock().push(message);
+                    Ok(())
+                }
+            }
+            InnerWindowHandle::Known(handle) => {
+                handle.send(message).map_err(|_| WindowThreadError::Closed)
+            }
+            InnerWindowHandle::Virtual(_) => Err(WindowThreadError::Unsupported),
+        }
+    }
+
     fn send(&self, message: WindowCommand) {
         match self {
             InnerWindowHandle::Pending(pending) => {
                 if let Some(handle) = pending.handle.get() {
                     let _result = handle.send(message);
+                } else if let Some(state) = pending.virtual_state.get() {
+                    InnerWindowHandle::Virtual(state.clone()).send(message);
                 } else {
                     pending.commands.lock().push(message);
                 }
@@ -3420,10 +5322,19 @@ impl InnerWindowHandle {
                 WindowCommand::Execute(_func) => {
                     tracing::error!("ignoring execution of window function on virtual window");
                 }
+                WindowCommand::Message(_) => {
+                    tracing::error!("ignoring message sent to virtual window");
+                }
+                #[cfg(feature = "gamepad")]
+                WindowCommand::Gamepad(_) => {}
                 WindowCommand::ResetDeadKeys
+                | WindowCommand::OwnedOpened(_)
+                | WindowCommand::OwnedClosed(_)
+                | WindowCommand::OwnerMinimized(_)
                 | WindowCommand::RequestUserAttention(_)
                 | WindowCommand::Focus
                 | WindowCommand::Ize(_)
+                | WindowCommand::Fullscreen(_)
                 | WindowCommand::Sync => {}
             },
         };
@@ -3488,11 +5399,28 @@ impl PendingWindow {
 
         WindowHandle::new(handle, self.0.redraw_status.clone())
     }
+
+    /// Routes this window's handle to the virtual window using `state`,
+    /// delivering any commands sent before it was opened.
+    fn opened_virtual(self, state: &WindowDynamicState) {
+        let InnerWindowHandle::Pending(pending) = &self.0.inner else {
+            unreachable!("always pending")
+        };
+
+        let initialized = pending.virtual_state.set(state.clone());
+        assert!(initialized.is_ok());
+
+        let virtual_handle = InnerWindowHandle::Virtual(state.clone());
+        for command in pending.commands.lock().drain(..) {
+            virtual_handle.send(command);
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 struct PendingWindowHandle {
     handle: OnceLock<kludgine::app::WindowHandle<WindowCommand>>,
+    virtual_state: OnceLock<WindowDynamicState>,
     commands: Mutex<Vec<WindowCommand>>,
 }
 
@@ -3696,6 +5624,9 @@ pub struct StandaloneWindowBuilder {
     transparent: bool,
     zoom: Dynamic<Fraction>,
     resize_to_fit: Value<bool>,
+    theme: Option<Value<ThemePair>>,
+    theme_mode: Option<Value<ThemeMode>>,
+    post_processing: Vec<PostProcess>,
 }
 
 impl StandaloneWindowBuilder {
@@ -3710,6 +5641,9 @@ impl StandaloneWindowBuilder {
             zoom: Dynamic::new(Fraction::ONE),
             transparent: false,
             resize_to_fit: Value::Constant(false),
+            theme: None,
+            theme_mode: None,
+            post_processing: Vec::new(),
         }
     }
 
@@ -3754,37 +5688,55 @@ impl StandaloneWindowBuilder {
         self
     }
 
+    /// Applies `theme` to the widgets in this window.
+    #[must_use]
+    pub fn themed(mut self, theme: impl IntoValue<ThemePair>) -> Self {
+        self.theme = Some(theme.into_value());
+        self
+    }
+
+    /// Sets the [`ThemeMode`] for this window.
+    #[must_use]
+    pub fn themed_mode(mut self, theme_mode: impl IntoValue<ThemeMode>) -> Self {
+        self.theme_mode = Some(theme_mode.into_value());
+        self
+    }
+
+    /// Applies `effect` to the rendered contents of this window.
+    ///
+    /// Effects are applied in the order they are added. Rendering with effects
+    /// renders the window's contents into an intermediate texture, so
+    /// additional drawing passed to
+    /// [`CushyWindow::render_with()`] also has the effects applied.
+    #[must_use]
+    pub fn post_process(mut self, effect: PostProcess) -> Self {
+        self.post_processing.push(effect);
+        self
+    }
+
     /// Returns the initialized window.
     #[must_use]
     pub fn finish<W>(self, window: W, device: &wgpu::Device, queue: &wgpu::Queue) -> CushyWindow
     where
         W: PlatformWindowImplementation,
     {
-        let mut kludgine = Kludgine::new(
-            device,
-            queue,
-            wgpu::TextureFormat::Rgba8UnormSrgb,
-            wgpu::MultisampleState {
-                count: self.multisample_count.get(),
-                ..Default::default()
-            },
-            self.initial_size,
-            self.scale,
-        );
-        let window = OpenWindow::<WidgetInstance>::new(
+        let redraw_status = InvalidationStatus::default();
+        for uniform in self.post_processing.iter().flat_map(PostProcess::uniforms) {
+            window.redraw_when_changed(uniform, &redraw_status);
+        }
+        CushyWindow::new(
             self.widget,
             window,
-            &mut kludgine::Graphics::new(&mut kludgine, device, queue),
             sealed::WindowSettings {
                 app: App::standalone(),
-                redraw_status: InvalidationStatus::default(),
+                redraw_status,
                 title: Value::default(),
                 attributes: None,
                 occluded: Dynamic::default(),
                 focused: Dynamic::default(),
                 inner_size: Dynamic::default(),
-                theme: None,
-                theme_mode: None,
+                theme: self.theme,
+                theme_mode: self.theme_mode,
                 transparent: self.transparent,
                 serif_font_family: FontFamilyList::default(),
                 sans_serif_font_family: FontFamilyList::default(),
@@ -3793,17 +5745,28 @@ impl StandaloneWindowBuilder {
                 cursive_font_family: FontFamilyList::default(),
                 font_data_to_load: FontCollection::default(),
                 on_open: None,
+                on_first_frame: None,
+                on_announcement: None,
+                spatial_navigation: Value::Constant(false),
                 on_closed: None,
                 vsync: false,
                 multisample_count: self.multisample_count,
                 close_requested: None,
                 zoom: self.zoom,
+                content_scale: Value::Constant(None),
+                effective_scale: Dynamic::new(Fraction::ONE),
                 resize_to_fit: self.resize_to_fit,
+                size_to_content: None,
                 content_protected: Value::Constant(false),
                 cursor_hittest: Value::Constant(true),
+                hit_test_mask: Value::default(),
+                clear_color: None,
+                magnifier: None,
+                pointer_highlight: None,
                 cursor_visible: Value::Constant(true),
                 cursor_position: Dynamic::default(),
                 window_level: Value::default(),
+                owner: None,
                 decorated: Value::Constant(true),
                 maximized: Dynamic::new(false),
                 minimized: Dynamic::new(false),
@@ -3812,18 +5775,27 @@ impl StandaloneWindowBuilder {
                 visible: Dynamic::new(true),
                 inner_position: Dynamic::default(),
                 outer_position: Dynamic::default(),
+                current_monitor: Dynamic::default(),
                 outer_size: Dynamic::default(),
                 window_icon: Value::Constant(None),
                 modifiers: Dynamic::default(),
                 enabled_buttons: Value::dynamic(WindowButtons::all()),
                 fullscreen: Value::default(),
                 shortcuts: Value::default(),
+                gesture_shortcuts: GestureShortcuts::default(),
                 on_init: None,
+                on_pre_present: None,
+                client_decorations: None,
                 on_file_drop: None,
+                on_file_hover: None,
+                message_handlers: MessageHandlers::default(),
             },
-        );
-
-        CushyWindow { window, kludgine }
+            self.initial_size,
+            self.scale,
+            self.post_processing,
+            device,
+            queue,
+        )
     }
 
     /// Returns an initialized [`VirtualWindow`].
@@ -3849,9 +5821,52 @@ impl StandaloneWindowBuilder {
 pub struct CushyWindow {
     window: OpenWindow<WidgetInstance>,
     kludgine: Kludgine,
+    post_processor: Option<PostProcessor>,
+    multisample_count: u32,
 }
 
 impl CushyWindow {
+    #[allow(clippy::too_many_arguments)]
+    fn new<W>(
+        widget: WidgetInstance,
+        window: W,
+        settings: sealed::WindowSettings,
+        initial_size: Size<UPx>,
+        scale: f32,
+        post_processing: Vec<PostProcess>,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Self
+    where
+        W: PlatformWindowImplementation,
+    {
+        let multisample_count = settings.multisample_count.get();
+        let mut kludgine = Kludgine::new(
+            device,
+            queue,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::MultisampleState {
+                count: multisample_count,
+                ..Default::default()
+            },
+            initial_size,
+            scale,
+        );
+        let window = OpenWindow::<WidgetInstance>::new(
+            widget,
+            window,
+            &mut kludgine::Graphics::new(&mut kludgine, device, queue),
+            settings,
+        );
+
+        Self {
+            window,
+            kludgine,
+            post_processor: PostProcessor::new(post_processing, multisample_count),
+            multisample_count,
+        }
+    }
+
     /// Prepares all necessary resources and operations necessary to render the
     /// next frame.
     pub fn prepare<W>(&mut self, window: W, device: &wgpu::Device, queue: &wgpu::Queue)
@@ -3888,6 +5903,15 @@ impl CushyWindow {
         queue: &wgpu::Queue,
         additional_drawing: Option<&Drawing>,
     ) -> Option<wgpu::SubmissionIndex> {
+        if self.post_processor.is_some() {
+            return Some(self.render_post_processed(
+                pass,
+                self.multisample_count,
+                device,
+                queue,
+                additional_drawing,
+            ));
+        }
         let mut frame = self.kludgine.next_frame();
         let mut gfx = frame.render(pass, device, queue);
         self.window.contents.render(1., &mut gfx);
@@ -3906,6 +5930,32 @@ impl CushyWindow {
         device: &wgpu::Device,
         queue: &wgpu::Queue,
     ) -> Option<wgpu::SubmissionIndex> {
+        if self.post_processor.is_some() {
+            let load = match load_op {
+                wgpu::LoadOp::Clear(color) => wgpu::LoadOp::Clear(color.into()),
+                wgpu::LoadOp::Load => wgpu::LoadOp::Load,
+            };
+            return Some(self.render_post_processed(
+                &wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: texture.view(),
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load,
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                },
+                1,
+                device,
+                queue,
+                None,
+            ));
+        }
         let mut frame = self.kludgine.next_frame();
         let mut gfx = frame.render_into(texture, load_op, device, queue);
         self.window.contents.render(1., &mut gfx);
@@ -3913,6 +5963,48 @@ impl CushyWindow {
         frame.submit(queue)
     }
 
+    /// Renders the contents into the post processor's texture, and then
+    /// applies each effect, rendering the final effect using `target`.
+    fn render_post_processed(
+        &mut self,
+        target: &wgpu::RenderPassDescriptor<'_>,
+        target_sample_count: u32,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        additional_drawing: Option<&Drawing>,
+    ) -> wgpu::SubmissionIndex {
+        let post_processor = self
+            .post_processor
+            .as_mut()
+            .assert("only called with effects");
+        let size = self.kludgine.size();
+        post_processor.prepare(
+            size,
+            &kludgine::Graphics::new(&mut self.kludgine, device, queue),
+        );
+
+        let mut frame = self.kludgine.next_frame();
+        let mut gfx = frame.render(
+            &wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(post_processor.scene_attachment())],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            },
+            device,
+            queue,
+        );
+        self.window.contents.render(1., &mut gfx);
+        if let Some(additional) = additional_drawing {
+            additional.render(1., &mut gfx);
+        }
+        drop(gfx);
+        frame.submit(queue);
+
+        post_processor.apply(target, target_sample_count, device, queue)
+    }
+
     /// Returns a new [`kludgine::Graphics`] context for this window.
     #[must_use]
     pub fn graphics<'gfx>(
@@ -3979,7 +6071,13 @@ impl CushyWindow {
     ) where
         W: PlatformWindowImplementation,
     {
+        let new_zoom: Fraction = new_zoom.into();
         self.kludgine.resize(new_size, new_scale, new_zoom, queue);
+        self.window.zoom.source().set(new_zoom);
+        self.window
+            .dpi_scale
+            .source()
+            .set(self.kludgine.dpi_scale());
         self.window.resized(new_size, window);
     }
 
@@ -4082,6 +6180,35 @@ pub struct VirtualWindow {
 }
 
 impl VirtualWindow {
+    /// Returns a new virtual window that displays `widget` using `settings`.
+    pub(crate) fn from_settings(
+        widget: WidgetInstance,
+        settings: sealed::WindowSettings,
+        size: Size<UPx>,
+        scale: f32,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Self {
+        let mut state = VirtualState::new();
+        state.size = size;
+        let cushy = CushyWindow::new(
+            widget,
+            &mut state,
+            settings,
+            size,
+            scale,
+            Vec::new(),
+            device,
+            queue,
+        );
+
+        Self {
+            cushy,
+            state,
+            last_rendered_at: None,
+        }
+    }
+
     /// Prepares all necessary resources and operations necessary to render the
     /// next frame.
     ///
@@ -4375,6 +6502,8 @@ pub struct VirtualRecorderBuilder<Format> {
     scale: f32,
     format: PhantomData<Format>,
     resize_to_fit: bool,
+    print_friendly: bool,
+    post_processing: Vec<PostProcess>,
 }
 
 impl VirtualRecorderBuilder<Rgb8> {
@@ -4386,6 +6515,8 @@ impl VirtualRecorderBuilder<Rgb8> {
             scale: 1.0,
             format: PhantomData,
             resize_to_fit: false,
+            print_friendly: false,
+            post_processing: Vec::new(),
         }
     }
 
@@ -4398,6 +6529,8 @@ impl VirtualRecorderBuilder<Rgb8> {
             size: self.size,
             scale: self.scale,
             resize_to_fit: self.resize_to_fit,
+            print_friendly: self.print_friendly,
+            post_processing: self.post_processing,
             format: PhantomData,
         }
     }
@@ -4437,9 +6570,41 @@ where
         self
     }
 
+    /// Renders the contents using a print-friendly theme.
+    ///
+    /// The contents are rendered in light mode using
+    /// [`ThemePair::print_friendly()`], which uses a white background, black
+    /// text, and hides shadows. Only the recorder's virtual window uses this
+    /// theme: any on-screen windows are unaffected.
+    ///
+    /// Animations are not paused. Each capture reflects the state of any
+    /// running animations at the time it is rendered.
+    #[must_use]
+    pub fn print_friendly(mut self) -> Self {
+        self.print_friendly = true;
+        self
+    }
+
+    /// Applies `effect` to each captured frame.
+    ///
+    /// Effects are applied in the order they are added. See
+    /// [`PostProcess`] for more information.
+    #[must_use]
+    pub fn post_process(mut self, effect: PostProcess) -> Self {
+        self.post_processing.push(effect);
+        self
+    }
+
     /// Returns an initialized [`VirtualRecorder`].
     pub fn finish(self) -> Result<VirtualRecorder<Format>, VirtualRecorderError> {
-        VirtualRecorder::new(self.size, self.scale, self.resize_to_fit, self.contents)
+        VirtualRecorder::new_with(
+            self.size,
+            self.scale,
+            self.resize_to_fit,
+            self.print_friendly,
+            self.post_processing,
+            self.contents,
+        )
     }
 }
 
@@ -4536,32 +6701,57 @@ where
         resize_to_fit: bool,
         contents: impl MakeWidget,
     ) -> Result<Self, VirtualRecorderError> {
-        let wgpu = wgpu::Instance::default();
-        let adapter =
-            pollster::block_on(wgpu.request_adapter(&wgpu::RequestAdapterOptions::default()))
-                .ok_or(VirtualRecorderError::NoAdapter)?;
-        let (device, queue) = pollster::block_on(adapter.request_device(
-            &wgpu::DeviceDescriptor {
-                label: None,
-                required_features: Kludgine::REQURED_FEATURES,
-                required_limits: Kludgine::adjust_limits(wgpu::Limits::downlevel_webgl2_defaults()),
-                memory_hints: wgpu::MemoryHints::MemoryUsage,
-            },
-            None,
-        ))?;
+        Self::new_with(size, scale, resize_to_fit, false, Vec::new(), contents)
+    }
+
+    fn new_with(
+        size: Size<UPx>,
+        scale: f32,
+        resize_to_fit: bool,
+        print_friendly: bool,
+        post_processing: Vec<PostProcess>,
+        contents: impl MakeWidget,
+    ) -> Result<Self, VirtualRecorderError> {
+        let (device, queue) = request_virtual_device()?;
 
-        let window = contents
+        let mut builder = contents
             .build_standalone_window()
             .size(size)
             .scale(scale)
             .transparent()
-            .resize_to_fit(resize_to_fit)
-            .finish_virtual(&device, &queue);
+            .resize_to_fit(resize_to_fit);
+        if print_friendly {
+            builder = builder
+                .themed(ThemePair::default().print_friendly())
+                .themed_mode(ThemeMode::Light);
+        }
+        for effect in post_processing {
+            builder = builder.post_process(effect);
+        }
+        let window = builder.finish_virtual(&device, &queue);
+
+        let mut recorder = Self::from_window(window, Arc::new(device), Arc::new(queue));
+        recorder.refresh()?;
+
+        if resize_to_fit && recorder.window.state.size != recorder.window.size() {
+            recorder.refresh()?;
+        }
+        Ok(recorder)
+    }
 
-        let mut recorder = Self {
+    /// Returns a recorder of `window`, which must have been created using
+    /// `device` and `queue`.
+    ///
+    /// Nothing is captured until [`Self::refresh()`] is called.
+    pub(crate) fn from_window(
+        window: VirtualWindow,
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+    ) -> Self {
+        Self {
             window,
-            device: Arc::new(device),
-            queue: Arc::new(queue),
+            device,
+            queue,
             cursor: Dynamic::default(),
             cursor_graphic: Drawing::default(),
             cursor_visible: false,
@@ -4569,13 +6759,7 @@ where
             data: Vec::new(),
             data_size: Size::ZERO,
             format: PhantomData,
-        };
-        recorder.refresh()?;
-
-        if resize_to_fit && recorder.window.state.size != recorder.window.size() {
-            recorder.refresh()?;
         }
-        Ok(recorder)
     }
 
     /// Returns the tightly-packed captured bytes.
@@ -4607,7 +6791,9 @@ where
     ///
     /// # Panics
     ///
-    /// This function panics if the color is not the expected color.
+    /// This function panics if the color is not the expected color. The panic
+    /// message includes a [`TreeSnapshot`] of the window to help diagnose the
+    /// failure.
     #[track_caller]
     pub fn assert_pixel_color<Unit>(&self, location: Point<Unit>, expected: Color, component: &str)
     where
@@ -4623,15 +6809,71 @@ where
             .max(color.alpha().abs_diff(expected.alpha()));
         assert!(
             max_delta <= 1,
-            "assertion failed: {component} at {location:?} was {color:?}, not {expected:?}"
+            "assertion failed: {component} at {location:?} was {color:?}, not {expected:?}\nwidget tree:\n{}",
+            self.tree_snapshot()
         );
     }
 
+    /// Returns a snapshot of the window's widget tree.
+    ///
+    /// The bounds in the snapshot are from the most recent layout, which is
+    /// performed by [`refresh()`](Self::refresh). Snapshots can be compared
+    /// against a stored baseline using [`TreeSnapshot::assert_matches()`].
+    #[must_use]
+    pub fn tree_snapshot(&self) -> TreeSnapshot {
+        self.window.cushy.window.tree.snapshot()
+    }
+
     /// Returns the current contents as an image.
     pub fn image(&self) -> DynamicImage {
         Format::load_image(self.bytes(), self.data_size)
     }
 
+    /// Scans each widget in the last captured frame and returns the widgets
+    /// whose foreground and background colors have a contrast ratio below
+    /// `minimum_ratio`.
+    ///
+    /// Only untransformed widgets without children are scanned. The most
+    /// common color in each widget's region is treated as its background, and
+    /// the color that contrasts the most with it, ignoring antialiased edges,
+    /// is treated as its foreground. Level AA of the Web Content Accessibility
+    /// Guidelines requires a ratio of [`WCAG_AA_CONTRAST`](crate::debug::WCAG_AA_CONTRAST)
+    /// for normal text.
+    #[must_use]
+    pub fn contrast_report(&self, minimum_ratio: f32) -> ContrastReport {
+        let width = i32::try_from(self.data_size.width.get()).unwrap_or(i32::MAX);
+        let height = i32::try_from(self.data_size.height.get()).unwrap_or(i32::MAX);
+        let mut report = ContrastReport::default();
+        for (widget, region) in self.window.cushy.window.tree.rendered_leaves() {
+            let (min, max) = region.extents();
+            let columns = min.x.get().max(0)..max.x.get().min(width);
+            let rows = min.y.get().max(0)..max.y.get().min(height);
+            let pixels = rows.flat_map(|y| {
+                columns.clone().map(move |x| {
+                    self.pixel_color(Point::new(
+                        UPx::new(x.unsigned_abs()),
+                        UPx::new(y.unsigned_abs()),
+                    ))
+                })
+            });
+            let Some((background, foreground)) = dominant_colors(pixels) else {
+                continue;
+            };
+            let ratio = foreground.contrast_ratio(background);
+            if ratio < minimum_ratio {
+                report.issues.push(ContrastIssue {
+                    description: format!("{widget:?} at {:?}", region.origin),
+                    widget: Some(widget),
+                    region: Some(region),
+                    foreground,
+                    background,
+                    ratio,
+                });
+            }
+        }
+        report
+    }
+
     fn recreate_buffers_if_needed(&mut self, size: Size<UPx>, bytes: u64, bytes_per_row: u32) {
         if self
             .capture
@@ -4762,6 +7004,23 @@ where
     }
 }
 
+/// Returns a new device and queue for rendering virtual windows.
+pub(crate) fn request_virtual_device() -> Result<(wgpu::Device, wgpu::Queue), VirtualRecorderError>
+{
+    let wgpu = wgpu::Instance::default();
+    let adapter = pollster::block_on(wgpu.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        .ok_or(VirtualRecorderError::NoAdapter)?;
+    Ok(pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: None,
+            required_features: Kludgine::REQURED_FEATURES,
+            required_limits: Kludgine::adjust_limits(wgpu::Limits::downlevel_webgl2_defaults()),
+            memory_hints: wgpu::MemoryHints::MemoryUsage,
+        },
+        None,
+    ))?)
+}
+
 fn copy_buffer_aligned_bytes_per_row(width: u32) -> u32 {
     width.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT
 }
@@ -5073,6 +7332,29 @@ impl From<winit::event::DeviceId> for DeviceId {
     }
 }
 
+/// The kind of device that produced a scroll event.
+///
+/// The source of the scroll event being handled can be retrieved using
+/// [`EventContext::scroll_source()`](crate::context::EventContext::scroll_source).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ScrollSource {
+    /// A mouse wheel.
+    ///
+    /// Most mouse wheels scroll in lines, but some high-resolution wheels
+    /// report their movement in pixels.
+    #[default]
+    Wheel,
+    /// A touchpad or another precision scrolling device.
+    ///
+    /// Touchpads report their movement in pixels.
+    Touchpad,
+    /// A finger dragged across a touchscreen.
+    ///
+    /// Touch scrolling reports its movement in pixels. Widgets that support
+    /// kinetic scrolling should continue scrolling after the finger is lifted.
+    Touch,
+}
+
 struct FrameAssembler {
     sender: mpsc::SyncSender<(Box<Capture>, Duration)>,
     result: mpsc::Receiver<Result<Vec<Frame>, VirtualRecorderError>>,
@@ -5201,3 +7483,166 @@ impl KeyEvent {
         }
     }
 }
+
+#[test]
+fn coalesced_wheel_queues_incompatible_deltas() {
+    let mut input = CoalescedInput::default();
+    let wheel = DeviceId::Virtual(0);
+    let other = DeviceId::Virtual(1);
+    input.push_wheel(
+        wheel,
+        MouseScrollDelta::LineDelta(0., 1.),
+        TouchPhase::Moved,
+        ScrollSource::Wheel,
+    );
+    input.push_wheel(
+        wheel,
+        MouseScrollDelta::LineDelta(0., 2.),
+        TouchPhase::Moved,
+        ScrollSource::Wheel,
+    );
+    input.push_wheel(
+        wheel,
+        MouseScrollDelta::PixelDelta(PhysicalPosition::new(0., 4.)),
+        TouchPhase::Moved,
+        ScrollSource::Wheel,
+    );
+    input.push_wheel(
+        other,
+        MouseScrollDelta::PixelDelta(PhysicalPosition::new(0., 8.)),
+        TouchPhase::Moved,
+        ScrollSource::Touchpad,
+    );
+    input.push_wheel(
+        other,
+        MouseScrollDelta::PixelDelta(PhysicalPosition::new(0., 8.)),
+        TouchPhase::Ended,
+        ScrollSource::Touchpad,
+    );
+
+    let pending = input
+        .wheel
+        .iter()
+        .map(|pending| (pending.device_id, pending.delta, pending.phase))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pending,
+        [
+            (
+                wheel,
+                MouseScrollDelta::LineDelta(0., 3.),
+                TouchPhase::Moved
+            ),
+            (
+                wheel,
+                MouseScrollDelta::PixelDelta(PhysicalPosition::new(0., 4.)),
+                TouchPhase::Moved
+            ),
+            (
+                other,
+                MouseScrollDelta::PixelDelta(PhysicalPosition::new(0., 16.)),
+                TouchPhase::Ended
+            ),
+        ]
+    );
+}
+
+#[cfg(test)]
+fn queued_commands<T>(
+    handle: &WindowHandle,
+    mut filter: impl FnMut(&WindowCommand) -> Option<T>,
+) -> Vec<T> {
+    let InnerWindowHandle::Pending(pending) = &handle.inner else {
+        unreachable!("test handles are pending")
+    };
+    pending
+        .commands
+        .lock()
+        .iter()
+        .filter_map(&mut filter)
+        .collect()
+}
+
+#[cfg(test)]
+fn owner_recorder() -> VirtualRecorder {
+    use crate::widgets::Space;
+
+    Space::clear()
+        .build_recorder()
+        .size(Size::squared(UPx::new(8)))
+        .finish()
+        .expect("error creating recorder")
+}
+
+#[test]
+fn owned_windows_minimize_and_close_with_owner() {
+    let mut recorder = owner_recorder();
+    let owner = &mut recorder.window.cushy.window;
+    let palette = PendingWindow::default().handle();
+    let minimized_palette = PendingWindow::default().handle();
+    let owner_minimized = |command: &WindowCommand| match command {
+        WindowCommand::OwnerMinimized(minimized) => Some(*minimized),
+        _ => None,
+    };
+
+    owner.owned_window_opened(Ownership {
+        window: palette.clone(),
+        modal: false,
+    });
+    assert!(queued_commands(&palette, owner_minimized).is_empty());
+    assert!(owner.modal_window().is_none());
+
+    owner.minimize_owned_windows(true);
+    owner.minimized.set_and_read(true);
+    assert_eq!(queued_commands(&palette, owner_minimized), [true]);
+
+    // Windows opened while the owner is minimized start minimized.
+    owner.owned_window_opened(Ownership {
+        window: minimized_palette.clone(),
+        modal: false,
+    });
+    assert_eq!(queued_commands(&minimized_palette, owner_minimized), [true]);
+
+    owner.minimize_owned_windows(false);
+    assert_eq!(queued_commands(&palette, owner_minimized), [true, false]);
+
+    // Closing the owner closes the windows it owns.
+    drop(recorder);
+    for owned in [palette, minimized_palette] {
+        let close_requests = queued_commands(&owned, |command| {
+            matches!(command, WindowCommand::RequestClose).then_some(())
+        });
+        assert_eq!(close_requests.len(), 1);
+    }
+}
+
+#[test]
+fn modal_windows_block_owner_mouse_input() {
+    let mut recorder = owner_recorder();
+    let owner = &mut recorder.window.cushy.window;
+    let device = DeviceId::Virtual(0);
+    let modal = PendingWindow::default().handle();
+    assert!(!owner.modal_blocks_mouse_input(device, ElementState::Pressed, MouseButton::Left));
+
+    // A button pressed before the modal window opens.
+    owner
+        .mouse_buttons
+        .entry(device)
+        .or_default()
+        .insert(MouseButton::Left, crate::widget::WidgetTag::unique().id());
+    owner.owned_window_opened(Ownership {
+        window: modal.clone(),
+        modal: true,
+    });
+    assert_eq!(owner.modal_window(), Some(&modal));
+
+    assert!(owner.modal_blocks_mouse_input(device, ElementState::Pressed, MouseButton::Left));
+    assert!(owner.modal_blocks_mouse_input(device, ElementState::Pressed, MouseButton::Right));
+    assert!(owner.modal_blocks_mouse_input(device, ElementState::Released, MouseButton::Right));
+    // Releasing the tracked button is still delivered.
+    assert!(!owner.modal_blocks_mouse_input(device, ElementState::Released, MouseButton::Left));
+
+    owner.owned_windows.retain(|owned| owned.window != modal);
+    assert!(owner.modal_window().is_none());
+    assert!(!owner.modal_blocks_mouse_input(device, ElementState::Pressed, MouseButton::Right));
+}
@@ -0,0 +1,63 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A message to be read aloud by assistive technologies, such as a screen
+/// reader.
+///
+/// Announcements are made using
+/// [`WidgetContext::announce()`](crate::context::WidgetContext::announce) and
+/// are delivered to the window's
+/// [announcement handler](crate::window::Window::on_announcement). When a
+/// window has no handler, announcements are logged using `tracing`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Announcement {
+    /// The message to announce.
+    pub message: String,
+    /// How urgently the message should be announced.
+    pub politeness: Politeness,
+}
+
+impl Display for Announcement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// How urgently an [`Announcement`] should interrupt the user.
+///
+/// These values mirror the politeness settings of ARIA live regions.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Politeness {
+    /// The announcement is made once the user is idle, such as after the
+    /// screen reader finishes its current speech.
+    ///
+    /// This is appropriate for status updates, such as the number of search
+    /// results found.
+    #[default]
+    Polite,
+    /// The announcement interrupts any current speech.
+    ///
+    /// This is appropriate for time-sensitive information, such as errors
+    /// that prevent an action from completing.
+    Assertive,
+}
+
+#[test]
+fn announcements_are_delivered_in_order_once() {
+    use crate::tree::Tree;
+
+    let tree = Tree::default();
+    let error = Announcement {
+        message: String::from("Name is required"),
+        politeness: Politeness::Assertive,
+    };
+    let status = Announcement {
+        message: String::from("3 results found"),
+        politeness: Politeness::default(),
+    };
+    tree.announce(error.clone());
+    tree.announce(status.clone());
+
+    assert_eq!(tree.take_announcements(), [error, status]);
+    assert!(tree.take_announcements().is_empty());
+    assert_eq!(Politeness::default(), Politeness::Polite);
+}
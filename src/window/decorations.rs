@@ -0,0 +1,313 @@
+use figures::units::{Lp, Px};
+use figures::{Fraction, IntoSigned, IntoUnsigned, Point, Rect, Round, ScreenScale, Size, Zero};
+use kludgine::app::winit::event::MouseButton;
+use kludgine::app::winit::window::{CursorIcon, Fullscreen, ResizeDirection};
+use kludgine::shapes::{CornerRadii, Shape};
+
+use crate::context::{AsEventContext, EventContext, GraphicsContext, LayoutContext, WidgetContext};
+use crate::reactive::value::{Dynamic, Source, Value};
+use crate::styles::{Dimension, Edges};
+use crate::widget::{
+    EventHandling, RootBehavior, WidgetRef, WrappedLayout, WrapperWidget, HANDLED, IGNORED,
+};
+use crate::widgets::container::{render_shadow, ContainerShadow};
+use crate::window::DeviceId;
+use crate::{ConstraintLimit, FitMeasuredSize};
+
+/// Decorations drawn by Cushy around the contents of a frameless window.
+///
+/// Some platforms, most notably many Wayland compositors, do not draw shadows
+/// or round the corners of windows that do not have server-side decorations.
+/// When a window is configured to use client decorations, Cushy makes the
+/// window transparent and draws these decorations itself:
+///
+/// - Each shadow in [`shadows`](Self::shadows) is drawn outside of the content
+///   area, in order.
+/// - The window's background is filled using [`corner_radius`](Self::corner_radius).
+/// - Dragging within [`resize_border`](Self::resize_border) of the content
+///   area resizes the window.
+///
+/// The window's contents are positioned inside of the area reserved for the
+/// shadows, and all sizes reported by the window, such as its inner size,
+/// continue to include the area reserved for the shadows. The shadows are not
+/// drawn while the window is maximized or fullscreen.
+///
+/// Presses that land on the shadow outside of the resize border are ignored.
+/// Winit does not support restricting a window's input region, so these
+/// presses are not delivered to the windows beneath.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientDecorations {
+    /// The shadows to draw outside of the window's content area.
+    pub shadows: Vec<ContainerShadow>,
+    /// The radius of the corners of the window's content area.
+    pub corner_radius: CornerRadii<Dimension>,
+    /// The distance outside of the content area that can be dragged to
+    /// resize the window.
+    pub resize_border: Dimension,
+}
+
+impl ClientDecorations {
+    /// Returns decorations without shadows or rounded corners.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            shadows: Vec::new(),
+            corner_radius: CornerRadii::from(Dimension::ZERO),
+            resize_border: Dimension::Lp(Lp::points(6)),
+        }
+    }
+
+    /// Adds `shadow` to the list of shadows and returns self.
+    ///
+    /// Shadows are drawn in the order they are added.
+    #[must_use]
+    pub fn with_shadow(mut self, shadow: ContainerShadow) -> Self {
+        self.shadows.push(shadow);
+        self
+    }
+
+    /// Sets the corner radius of the content area and returns self.
+    #[must_use]
+    pub fn corner_radius(mut self, radius: impl Into<CornerRadii<Dimension>>) -> Self {
+        self.corner_radius = radius.into();
+        self
+    }
+
+    /// Sets the width of the region outside of the content area that resizes
+    /// the window and returns self.
+    #[must_use]
+    pub fn resize_border(mut self, border: impl Into<Dimension>) -> Self {
+        self.resize_border = border.into();
+        self
+    }
+
+    fn margin(&self, scale: Fraction) -> Edges<Px> {
+        let resize_border = self.resize_border.into_px(scale).ceil();
+        let mut margin = Edges {
+            left: resize_border,
+            top: resize_border,
+            right: resize_border,
+            bottom: resize_border,
+        };
+        for shadow in &self.shadows {
+            let shadow = shadow.into_px(scale).ceil();
+            let extent = shadow.spread + shadow.blur_radius;
+            margin.left = margin.left.max(extent - shadow.offset.x);
+            margin.right = margin.right.max(extent + shadow.offset.x);
+            margin.top = margin.top.max(extent - shadow.offset.y);
+            margin.bottom = margin.bottom.max(extent + shadow.offset.y);
+        }
+        margin
+    }
+}
+
+impl Default for ClientDecorations {
+    /// Returns decorations with a soft drop shadow and rounded corners.
+    fn default() -> Self {
+        Self::new()
+            .with_shadow(
+                ContainerShadow::new(Point::new(Dimension::ZERO, Dimension::Lp(Lp::points(4))))
+                    .blur_radius(Dimension::Lp(Lp::points(16))),
+            )
+            .corner_radius(Dimension::Lp(Lp::points(8)))
+    }
+}
+
+/// The root widget of a window using [`ClientDecorations`].
+#[derive(Debug)]
+pub(super) struct ClientDecorated {
+    decorations: Value<ClientDecorations>,
+    maximized: Dynamic<bool>,
+    fullscreen: Value<Option<Fullscreen>>,
+    child: WidgetRef,
+    content: Rect<Px>,
+}
+
+impl ClientDecorated {
+    pub(super) fn new(
+        decorations: Value<ClientDecorations>,
+        maximized: Dynamic<bool>,
+        fullscreen: Value<Option<Fullscreen>>,
+        child: WidgetRef,
+    ) -> Self {
+        Self {
+            decorations,
+            maximized,
+            fullscreen,
+            child,
+            content: Rect::default(),
+        }
+    }
+
+    fn is_undecorated(&self, context: &WidgetContext<'_>) -> bool {
+        self.maximized.get_tracking_invalidate(context)
+            || self.fullscreen.get_tracking_invalidate(context).is_some()
+    }
+
+    fn margin(&self, context: &mut EventContext<'_>) -> Edges<Px> {
+        if self.is_undecorated(context) {
+            Edges::from(Px::ZERO)
+        } else {
+            let scale = context.kludgine.scale();
+            self.decorations
+                .map_tracking_invalidate(context, |decorations| decorations.margin(scale))
+        }
+    }
+
+    fn resize_direction(
+        &self,
+        location: Point<Px>,
+        context: &mut EventContext<'_>,
+    ) -> Option<ResizeDirection> {
+        if self.is_undecorated(context) || self.content.contains(location) {
+            return None;
+        }
+
+        let border = self
+            .decorations
+            .map_tracking_invalidate(context, |decorations| decorations.resize_border)
+            .into_px(context.kludgine.scale())
+            .ceil();
+        let (top_left, bottom_right) = self.content.extents();
+        if location.x < top_left.x - border
+            || location.y < top_left.y - border
+            || location.x >= bottom_right.x + border
+            || location.y >= bottom_right.y + border
+        {
+            return None;
+        }
+
+        // Presses alongside the ends of an edge resize diagonally, making the
+        // corners easier to grab.
+        let corner = border * 2;
+        let west = location.x < top_left.x + corner;
+        let east = location.x >= bottom_right.x - corner;
+        let north = location.y < top_left.y + corner;
+        let south = location.y >= bottom_right.y - corner;
+        Some(match (north, south, west, east) {
+            (true, _, true, _) => ResizeDirection::NorthWest,
+            (true, _, _, true) => ResizeDirection::NorthEast,
+            (_, true, true, _) => ResizeDirection::SouthWest,
+            (_, true, _, true) => ResizeDirection::SouthEast,
+            (true, _, _, _) => ResizeDirection::North,
+            (_, true, _, _) => ResizeDirection::South,
+            (_, _, true, _) => ResizeDirection::West,
+            _ => ResizeDirection::East,
+        })
+    }
+}
+
+impl WrapperWidget for ClientDecorated {
+    fn child_mut(&mut self) -> &mut WidgetRef {
+        &mut self.child
+    }
+
+    fn root_behavior(&mut self, context: &mut EventContext<'_>) -> Option<RootBehavior> {
+        let margin = self.margin(context);
+        Some(RootBehavior::Pad(margin.map(Dimension::Px)))
+    }
+
+    fn redraw_background(&mut self, context: &mut GraphicsContext<'_, '_, '_, '_>) {
+        let background = context.theme().surface.color;
+        if self.is_undecorated(context) {
+            context.gfx.fill(background);
+            return;
+        }
+
+        let scale = context.gfx.scale();
+        let (corner_radii, shadows) =
+            self.decorations
+                .map_tracking_redraw(context, |decorations| {
+                    (
+                        decorations.corner_radius.into_px(scale).ceil(),
+                        decorations
+                            .shadows
+                            .iter()
+                            .map(|shadow| shadow.into_px(scale).ceil())
+                            .collect::<Vec<_>>(),
+                    )
+                });
+        let max_radius = self.content.size.width.min(self.content.size.height) / 2;
+        let corner_radii = corner_radii.map(|radius| radius.min(max_radius));
+
+        for shadow in &shadows {
+            render_shadow(&self.content, corner_radii, shadow, background, context);
+        }
+
+        context.gfx.draw_shape(&Shape::filled_round_rect(
+            self.content,
+            corner_radii,
+            background,
+        ));
+    }
+
+    fn adjust_child_constraints(
+        &mut self,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> Size<ConstraintLimit> {
+        let margin = self
+            .margin(&mut context.as_event_context())
+            .map(IntoUnsigned::into_unsigned);
+        Size::new(
+            available_space.width - margin.width(),
+            available_space.height - margin.height(),
+        )
+    }
+
+    fn position_child(
+        &mut self,
+        size: Size<Px>,
+        available_space: Size<ConstraintLimit>,
+        context: &mut LayoutContext<'_, '_, '_, '_>,
+    ) -> WrappedLayout {
+        let margin = self.margin(&mut context.as_event_context());
+        let size = available_space
+            .fit_measured(size + margin.size())
+            .into_signed();
+        self.content = Rect::new(Point::new(margin.left, margin.top), size - margin.size());
+        WrappedLayout {
+            child: self.content,
+            size: size.into_unsigned(),
+        }
+    }
+
+    fn hit_test(&mut self, location: Point<Px>, context: &mut EventContext<'_>) -> bool {
+        self.resize_direction(location, context).is_some()
+    }
+
+    fn hover(&mut self, location: Point<Px>, context: &mut EventContext<'_>) -> Option<CursorIcon> {
+        self.resize_direction(location, context)
+            .map(|direction| match direction {
+                ResizeDirection::North => CursorIcon::NResize,
+                ResizeDirection::South => CursorIcon::SResize,
+                ResizeDirection::East => CursorIcon::EResize,
+                ResizeDirection::West => CursorIcon::WResize,
+                ResizeDirection::NorthEast => CursorIcon::NeResize,
+                ResizeDirection::NorthWest => CursorIcon::NwResize,
+                ResizeDirection::SouthEast => CursorIcon::SeResize,
+                ResizeDirection::SouthWest => CursorIcon::SwResize,
+            })
+    }
+
+    fn mouse_down(
+        &mut self,
+        location: Point<Px>,
+        _device_id: DeviceId,
+        button: MouseButton,
+        context: &mut EventContext<'_>,
+    ) -> EventHandling {
+        if button != MouseButton::Left {
+            return IGNORED;
+        }
+        let Some(direction) = self.resize_direction(location, context) else {
+            return IGNORED;
+        };
+        if let Some(winit) = context.window().winit() {
+            if let Err(err) = winit.drag_resize_window(direction) {
+                tracing::warn!("error starting window resize: {err}");
+            }
+        }
+        HANDLED
+    }
+}
@@ -0,0 +1,717 @@
+use std::f32::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ahash::AHashMap;
+use figures::units::Px;
+use figures::{Angle, FloatConversion, Point};
+use kludgine::app::winit::event::{MouseButton, TouchPhase};
+use kludgine::app::winit::keyboard::{Key, NamedKey};
+
+use crate::animation::{AnimationHandle, IntoAnimate, Spawn};
+use crate::context::EventContext;
+use crate::widget::{EventHandling, SharedCallback, WidgetId, HANDLED, IGNORED};
+use crate::widgets::layers::Direction;
+use crate::window::{DeviceId, WindowHandle};
+
+/// How long a finger must rest in place to produce a [`Gesture::LongPress`].
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+
+/// The longest a touch can last while still producing a [`Gesture::Flick`].
+const FLICK_DURATION: Duration = Duration::from_millis(250);
+
+/// A high-level gesture recognized from touchscreen input.
+///
+/// Gestures are delivered to
+/// [`Widget::gesture()`](crate::widget::Widget::gesture), starting with the
+/// widget beneath the first finger involved and continuing to its parents
+/// until a widget handles the gesture. All locations are relative to the
+/// widget receiving the gesture.
+///
+/// When a widget handles a [`Gesture::Pinch`] or [`Gesture::Rotate`], the
+/// fingers involved do not also produce scroll events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// Two fingers moved closer together or further apart.
+    Pinch {
+        /// The point halfway between the two fingers.
+        center: Point<Px>,
+        /// The factor the distance between the fingers changed by since the
+        /// previous pinch event.
+        scale: f32,
+        /// The phase of this gesture.
+        phase: TouchPhase,
+    },
+    /// Two fingers rotated around each other.
+    Rotate {
+        /// The point halfway between the two fingers.
+        center: Point<Px>,
+        /// The clockwise rotation since the previous rotate event.
+        rotation: Angle,
+        /// The phase of this gesture.
+        phase: TouchPhase,
+    },
+    /// A finger rested in place without being lifted.
+    LongPress {
+        /// The location of the finger.
+        location: Point<Px>,
+    },
+    /// A finger tapped twice in quick succession.
+    ///
+    /// The maximum delay between the taps is the app's
+    /// [multi-click threshold](crate::Cushy::multi_click_threshold).
+    DoubleTap {
+        /// The location of the second tap.
+        location: Point<Px>,
+    },
+    /// A finger or pen quickly swiped in a direction and was lifted.
+    Flick {
+        /// The location the flick ended at.
+        location: Point<Px>,
+        /// The direction of the flick.
+        direction: Direction,
+    },
+}
+
+impl Gesture {
+    /// Returns the location of this gesture.
+    #[must_use]
+    pub const fn location(&self) -> Point<Px> {
+        match self {
+            Gesture::Pinch { center, .. } | Gesture::Rotate { center, .. } => *center,
+            Gesture::LongPress { location }
+            | Gesture::DoubleTap { location }
+            | Gesture::Flick { location, .. } => *location,
+        }
+    }
+
+    fn with_location(mut self, new_location: Point<Px>) -> Self {
+        match &mut self {
+            Gesture::Pinch { center, .. } | Gesture::Rotate { center, .. } => {
+                *center = new_location;
+            }
+            Gesture::LongPress { location }
+            | Gesture::DoubleTap { location }
+            | Gesture::Flick { location, .. } => {
+                *location = new_location;
+            }
+        }
+        self
+    }
+}
+
+/// Shortcuts performed when pen and touch gestures are not handled by any
+/// widget.
+///
+/// By default:
+///
+/// - Pressing and holding sends a secondary (right) mouse click to the
+///   widgets beneath the finger, which opens
+///   [context menus](crate::widget::MakeWidget::context_menu).
+/// - Flicking left or right sends a [`NamedKey::BrowserBack`] or
+///   [`NamedKey::BrowserForward`] key press to the focused widget and the
+///   window's [shortcuts](crate::window::Window::with_shortcuts).
+/// - Double tapping a pen's barrel button does nothing until
+///   [`Self::on_barrel_double_tap`] is used.
+///
+/// Widgets that need raw pen or touch input, such as drawing surfaces, can
+/// opt out of these shortcuts using
+/// [`MakeWidget::without_gesture_shortcuts()`](crate::widget::MakeWidget::without_gesture_shortcuts).
+#[derive(Debug, Clone)]
+pub struct GestureShortcuts {
+    context_menu_on_hold: bool,
+    flicks: [Option<Key>; 4],
+    barrel: Option<(MouseButton, SharedCallback<WindowHandle>)>,
+}
+
+impl GestureShortcuts {
+    /// Returns shortcuts with every gesture disabled.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            context_menu_on_hold: false,
+            flicks: [None, None, None, None],
+            barrel: None,
+        }
+    }
+
+    /// Sets whether pressing and holding sends a secondary click, and returns
+    /// self.
+    #[must_use]
+    pub fn context_menu_on_hold(mut self, enabled: bool) -> Self {
+        self.context_menu_on_hold = enabled;
+        self
+    }
+
+    /// Sets the key pressed when flicking in `direction`, and returns self.
+    ///
+    /// When `key` is `None`, flicking in `direction` does nothing.
+    #[must_use]
+    pub fn flick(mut self, direction: Direction, key: impl Into<Option<Key>>) -> Self {
+        self.flicks[flick_index(direction)] = key.into();
+        self
+    }
+
+    /// Invokes `on_double_tap` when `button` is pressed twice within the app's
+    /// [multi-click threshold](crate::Cushy::multi_click_threshold), and
+    /// returns self.
+    ///
+    /// Platforms do not report pen barrel buttons separately from mouse
+    /// buttons. Most tablet drivers report the barrel button as
+    /// [`MouseButton::Right`] or [`MouseButton::Middle`], depending on how the
+    /// driver is configured. The second press is not delivered to widgets.
+    #[must_use]
+    pub fn on_barrel_double_tap<F>(mut self, button: MouseButton, on_double_tap: F) -> Self
+    where
+        F: FnMut(WindowHandle) + Send + 'static,
+    {
+        self.barrel = Some((button, SharedCallback::new(on_double_tap)));
+        self
+    }
+}
+
+impl Default for GestureShortcuts {
+    fn default() -> Self {
+        Self::none()
+            .context_menu_on_hold(true)
+            .flick(Direction::Left, Key::Named(NamedKey::BrowserBack))
+            .flick(Direction::Right, Key::Named(NamedKey::BrowserForward))
+    }
+}
+
+fn flick_index(direction: Direction) -> usize {
+    match direction {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+/// Converts touch events into [`Gesture`]s.
+pub(super) struct GestureRecognizer {
+    shortcuts: GestureShortcuts,
+    touches: AHashMap<u64, TrackedTouch>,
+    pinch: Option<[u64; 2]>,
+    last_tap: Option<(Instant, Point<Px>)>,
+    last_barrel_press: Option<Instant>,
+}
+
+struct TrackedTouch {
+    widget: WidgetId,
+    started_at: Instant,
+    start: Point<Px>,
+    scrolled: bool,
+    last: Point<Px>,
+    moved: bool,
+    long_press: AnimationHandle,
+    long_pressed: Arc<AtomicBool>,
+}
+
+/// The result of a touch being processed by a [`GestureRecognizer`].
+pub(super) struct TouchUpdate {
+    /// The widget the touch began on.
+    pub widget: WidgetId,
+    /// The distance the touch moved.
+    pub delta: Point<Px>,
+    /// The gestures recognized from this touch.
+    pub gestures: Vec<Gesture>,
+}
+
+impl GestureRecognizer {
+    pub fn new(shortcuts: GestureShortcuts) -> Self {
+        Self {
+            shortcuts,
+            touches: AHashMap::new(),
+            pinch: None,
+            last_tap: None,
+            last_barrel_press: None,
+        }
+    }
+
+    /// Begins tracking touch `id` from `device_id` on `widget`.
+    ///
+    /// `window` receives a [`Gesture::LongPress`] if the finger rests in place.
+    pub fn started(
+        &mut self,
+        id: u64,
+        device_id: DeviceId,
+        location: Point<Px>,
+        widget: WidgetId,
+        window: WindowHandle,
+    ) -> TouchUpdate {
+        let long_pressed = Arc::new(AtomicBool::new(false));
+        let context_menu = self.shortcuts.context_menu_on_hold;
+        let long_press = LONG_PRESS_DURATION
+            .on_complete({
+                let long_pressed = long_pressed.clone();
+                move || {
+                    long_pressed.store(true, Ordering::Relaxed);
+                    window.execute(move |context| {
+                        let Some(widget) = context.tree.widget(widget) else {
+                            return;
+                        };
+                        let mut context = context.for_other(&widget);
+                        if dispatch_gesture(&mut context, Gesture::LongPress { location })
+                            .is_continue()
+                            && context_menu
+                        {
+                            secondary_click(&mut context, location, device_id);
+                        }
+                    });
+                }
+            })
+            .spawn();
+        self.touches.insert(
+            id,
+            TrackedTouch {
+                widget,
+                started_at: Instant::now(),
+                start: location,
+                scrolled: false,
+                last: location,
+                moved: false,
+                long_press,
+                long_pressed,
+            },
+        );
+
+        let mut update = TouchUpdate {
+            widget,
+            delta: Point::default(),
+            gestures: Vec::new(),
+        };
+        if self.pinch.is_some() || self.touches.len() != 2 {
+            return update;
+        }
+
+        for touch in self.touches.values_mut() {
+            // Fingers involved in a pinch are no longer taps or presses.
+            touch.moved = true;
+            touch.long_press.clear();
+        }
+        let Some(first_id) = self.touches.keys().copied().find(|other| *other != id) else {
+            return update;
+        };
+        let ids = [first_id, id];
+        let (first, second) = (&self.touches[&ids[0]], &self.touches[&ids[1]]);
+        update.widget = first.widget;
+        let center = midpoint(first.last, second.last);
+        update.gestures = vec![
+            Gesture::Pinch {
+                center,
+                scale: 1.,
+                phase: TouchPhase::Started,
+            },
+            Gesture::Rotate {
+                center,
+                rotation: Angle::degrees(0),
+                phase: TouchPhase::Started,
+            },
+        ];
+        self.pinch = Some(ids);
+        update
+    }
+
+    /// Updates the location of touch `id`.
+    ///
+    /// `slop` is the distance a finger can move while still being considered
+    /// at rest.
+    pub fn moved(&mut self, id: u64, location: Point<Px>, slop: Px) -> Option<TouchUpdate> {
+        let touch = self.touches.get_mut(&id)?;
+        let previous = touch.last;
+        touch.last = location;
+        if !touch.moved && distance(touch.start, location) > slop.into_float() {
+            touch.moved = true;
+            touch.long_press.clear();
+        }
+        let mut update = TouchUpdate {
+            widget: touch.widget,
+            delta: location - previous,
+            gestures: Vec::new(),
+        };
+
+        let Some(pinch) = self.pinch.filter(|pinch| pinch.contains(&id)) else {
+            return Some(update);
+        };
+        let other = if pinch[0] == id { pinch[1] } else { pinch[0] };
+        let anchor = self.touches.get(&other)?.last;
+        let before = (previous - anchor).into_float();
+        let after = (location - anchor).into_float();
+        let before_length = before.x.hypot(before.y);
+        let scale = if before_length > 0. {
+            after.x.hypot(after.y) / before_length
+        } else {
+            1.
+        };
+        let mut rotation = after.y.atan2(after.x) - before.y.atan2(before.x);
+        if rotation > PI {
+            rotation -= 2. * PI;
+        } else if rotation < -PI {
+            rotation += 2. * PI;
+        }
+
+        let center = midpoint(location, anchor);
+        update.widget = self.touches.get(&pinch[0])?.widget;
+        update.gestures = vec![
+            Gesture::Pinch {
+                center,
+                scale,
+                phase: TouchPhase::Moved,
+            },
+            Gesture::Rotate {
+                center,
+                rotation: Angle::degrees_f(rotation.to_degrees()),
+                phase: TouchPhase::Moved,
+            },
+        ];
+        Some(update)
+    }
+
+    /// Stops tracking touch `id`.
+    ///
+    /// `double_tap` is the maximum delay between two taps that form a
+    /// [`Gesture::DoubleTap`], and `flick_distance` is the minimum distance a
+    /// touch must travel to form a [`Gesture::Flick`].
+    pub fn ended(
+        &mut self,
+        id: u64,
+        location: Point<Px>,
+        phase: TouchPhase,
+        slop: Px,
+        double_tap: Duration,
+        flick_distance: Px,
+    ) -> Option<TouchUpdate> {
+        let touch = self.touches.remove(&id)?;
+        touch.long_press.clear();
+        let mut update = TouchUpdate {
+            widget: touch.widget,
+            delta: location - touch.last,
+            gestures: Vec::new(),
+        };
+
+        if let Some(pinch) = self.pinch.filter(|pinch| pinch.contains(&id)) {
+            self.pinch = None;
+            let other = if pinch[0] == id { pinch[1] } else { pinch[0] };
+            let anchor = self
+                .touches
+                .get(&other)
+                .map_or(location, |other| other.last);
+            let center = midpoint(location, anchor);
+            update.widget = if pinch[0] == id {
+                touch.widget
+            } else {
+                self.touches
+                    .get(&pinch[0])
+                    .map_or(touch.widget, |first| first.widget)
+            };
+            update.gestures = vec![
+                Gesture::Pinch {
+                    center,
+                    scale: 1.,
+                    phase,
+                },
+                Gesture::Rotate {
+                    center,
+                    rotation: Angle::degrees(0),
+                    phase,
+                },
+            ];
+        } else if phase == TouchPhase::Ended && touch.moved && !touch.scrolled {
+            if let Some(direction) = flick_direction(
+                location - touch.start,
+                touch.started_at.elapsed(),
+                flick_distance,
+            ) {
+                update.gestures.push(Gesture::Flick {
+                    location,
+                    direction,
+                });
+            }
+        } else if phase == TouchPhase::Ended
+            && !touch.moved
+            && !touch.long_pressed.load(Ordering::Relaxed)
+        {
+            let now = Instant::now();
+            match self.last_tap.take() {
+                Some((tapped_at, tapped))
+                    if now.saturating_duration_since(tapped_at) <= double_tap
+                        && distance(tapped, location) <= slop.into_float() * 2. =>
+                {
+                    update.gestures.push(Gesture::DoubleTap { location });
+                }
+                _ => {
+                    self.last_tap = Some((now, location));
+                }
+            }
+        }
+
+        Some(update)
+    }
+
+    /// Notes that a widget handled the movement of touch `id`, preventing it
+    /// from becoming a [`Gesture::Flick`].
+    pub fn movement_handled(&mut self, id: u64) {
+        if let Some(touch) = self.touches.get_mut(&id) {
+            touch.scrolled = true;
+        }
+    }
+
+    /// Returns the key to press for an unhandled flick in `direction`.
+    pub fn flick_key(&self, direction: Direction) -> Option<Key> {
+        self.shortcuts.flicks[flick_index(direction)].clone()
+    }
+
+    /// Notes that `button` was pressed, returning the barrel double tap
+    /// callback if this press completes a double tap.
+    pub fn button_pressed(
+        &mut self,
+        button: MouseButton,
+        double_tap: Duration,
+    ) -> Option<SharedCallback<WindowHandle>> {
+        let (barrel_button, callback) = self.shortcuts.barrel.as_ref()?;
+        if *barrel_button != button {
+            return None;
+        }
+        let now = Instant::now();
+        match self.last_barrel_press.take() {
+            Some(pressed_at) if now.saturating_duration_since(pressed_at) <= double_tap => {
+                Some(callback.clone())
+            }
+            _ => {
+                self.last_barrel_press = Some(now);
+                None
+            }
+        }
+    }
+}
+
+/// Returns the direction of a touch that moved `delta` over `elapsed` if it
+/// was fast and far enough to be a flick.
+fn flick_direction(delta: Point<Px>, elapsed: Duration, flick_distance: Px) -> Option<Direction> {
+    if elapsed > FLICK_DURATION {
+        return None;
+    }
+    let delta = delta.into_float();
+    if delta.x.hypot(delta.y) < flick_distance.into_float() {
+        return None;
+    }
+    Some(if delta.x.abs() >= delta.y.abs() {
+        if delta.x < 0. {
+            Direction::Left
+        } else {
+            Direction::Right
+        }
+    } else if delta.y < 0. {
+        Direction::Up
+    } else {
+        Direction::Down
+    })
+}
+
+/// Sends a secondary click at `location`, which is in window coordinates, to
+/// the widget of `context` and then to its parents until it is handled.
+fn secondary_click(context: &mut EventContext<'_>, location: Point<Px>, device_id: DeviceId) {
+    let handler = super::recursively_handle_event(context, |context| {
+        let Some(relative) = context.window_to_widget(location) else {
+            return IGNORED;
+        };
+        context.mouse_down(relative, device_id, MouseButton::Right)
+    });
+    if let Some(handler) = handler {
+        let mut handler = context.for_other(&handler);
+        let relative = handler.window_to_widget(location);
+        handler.mouse_up(relative, device_id, MouseButton::Right);
+    }
+}
+
+/// Delivers `gesture`, which is in window coordinates, to the widget of
+/// `context` and then to its parents until it is handled.
+pub(super) fn dispatch_gesture(context: &mut EventContext<'_>, gesture: Gesture) -> EventHandling {
+    let location = gesture.location();
+    let relative = context.window_to_widget(location).unwrap_or(location);
+    if context.gesture(gesture.with_location(relative)).is_break() {
+        return HANDLED;
+    }
+    match context.parent() {
+        Some(parent) => dispatch_gesture(&mut context.for_other(&parent), gesture),
+        None => IGNORED,
+    }
+}
+
+fn midpoint(a: Point<Px>, b: Point<Px>) -> Point<Px> {
+    Point::new((a.x + b.x) / 2, (a.y + b.y) / 2)
+}
+
+fn distance(a: Point<Px>, b: Point<Px>) -> f32 {
+    let delta = (b - a).into_float();
+    delta.x.hypot(delta.y)
+}
+
+#[test]
+fn flicks_require_speed_and_distance() {
+    let distance = Px::new(40);
+    let fast = Duration::from_millis(100);
+    assert_eq!(
+        flick_direction(Point::px(-80, 10), fast, distance),
+        Some(Direction::Left)
+    );
+    assert_eq!(
+        flick_direction(Point::px(5, 60), fast, distance),
+        Some(Direction::Down)
+    );
+    assert_eq!(flick_direction(Point::px(20, 0), fast, distance), None);
+    assert_eq!(
+        flick_direction(Point::px(80, 0), Duration::from_secs(1), distance),
+        None
+    );
+}
+
+#[cfg(test)]
+fn test_recognizer() -> (
+    GestureRecognizer,
+    impl FnMut(&mut GestureRecognizer, u64, Point<Px>),
+) {
+    use crate::widget::WidgetTag;
+    use crate::window::PendingWindow;
+
+    let widget = WidgetTag::unique().id();
+    let window = PendingWindow::default().handle();
+    (
+        GestureRecognizer::new(GestureShortcuts::default()),
+        move |recognizer: &mut GestureRecognizer, id, location| {
+            recognizer.started(id, DeviceId::Virtual(0), location, widget, window.clone());
+        },
+    )
+}
+
+#[test]
+fn two_fingers_pinch_and_rotate() {
+    let (mut recognizer, mut start) = test_recognizer();
+    start(&mut recognizer, 1, Point::px(0, 0));
+    start(&mut recognizer, 2, Point::px(100, 0));
+    assert_eq!(recognizer.pinch, Some([1, 2]));
+    assert!(!recognizer.touches[&1].long_press.is_running());
+
+    // Moving the second finger to twice the distance and a quarter turn
+    // clockwise scales by 2 and rotates by 90 degrees.
+    let update = recognizer
+        .moved(2, Point::px(0, 200), Px::new(6))
+        .expect("tracked");
+    let [Gesture::Pinch {
+        center,
+        scale,
+        phase: TouchPhase::Moved,
+    }, Gesture::Rotate {
+        rotation,
+        phase: TouchPhase::Moved,
+        ..
+    }] = update.gestures[..]
+    else {
+        unreachable!("expected pinch and rotate: {:?}", update.gestures)
+    };
+    assert_eq!(center, Point::px(0, 100));
+    assert!((scale - 2.).abs() < 0.001);
+    assert!((rotation.into_degrees::<f32>() - 90.).abs() < 0.1);
+
+    // Lifting a finger ends the pinch without producing taps or flicks.
+    let update = recognizer
+        .ended(
+            2,
+            Point::px(0, 200),
+            TouchPhase::Ended,
+            Px::new(6),
+            Duration::from_secs(1),
+            Px::new(36),
+        )
+        .expect("tracked");
+    assert!(matches!(
+        update.gestures[..],
+        [
+            Gesture::Pinch {
+                phase: TouchPhase::Ended,
+                ..
+            },
+            Gesture::Rotate {
+                phase: TouchPhase::Ended,
+                ..
+            }
+        ]
+    ));
+    assert_eq!(recognizer.pinch, None);
+    assert!(recognizer.touches[&1].moved);
+}
+
+#[test]
+fn taps_in_quick_succession_double_tap() {
+    let (mut recognizer, mut start) = test_recognizer();
+    let mut tap = |recognizer: &mut GestureRecognizer, location: Point<Px>, threshold| {
+        start(recognizer, 1, location);
+        recognizer
+            .ended(
+                1,
+                location,
+                TouchPhase::Ended,
+                Px::new(6),
+                threshold,
+                Px::new(36),
+            )
+            .expect("tracked")
+            .gestures
+    };
+    let threshold = Duration::from_secs(60);
+    assert!(tap(&mut recognizer, Point::px(10, 10), threshold).is_empty());
+    assert_eq!(
+        tap(&mut recognizer, Point::px(14, 12), threshold),
+        [Gesture::DoubleTap {
+            location: Point::px(14, 12)
+        }]
+    );
+    // A third tap begins a new double tap.
+    assert!(tap(&mut recognizer, Point::px(14, 12), threshold).is_empty());
+    // Taps too far apart are not double taps.
+    assert!(tap(&mut recognizer, Point::px(100, 100), threshold).is_empty());
+    // Taps slower than the threshold are not double taps.
+    std::thread::sleep(Duration::from_millis(5));
+    assert!(tap(
+        &mut recognizer,
+        Point::px(100, 100),
+        Duration::from_millis(1)
+    )
+    .is_empty());
+}
+
+#[test]
+fn long_presses_are_not_taps() {
+    let (mut recognizer, mut start) = test_recognizer();
+    start(&mut recognizer, 1, Point::px(10, 10));
+    assert!(recognizer.touches[&1].long_press.is_running());
+
+    // Moving within the slop keeps the long press pending.
+    recognizer.moved(1, Point::px(13, 10), Px::new(6));
+    assert!(recognizer.touches[&1].long_press.is_running());
+
+    // Once the long press completes, lifting the finger is not a tap.
+    recognizer.touches[&1]
+        .long_pressed
+        .store(true, Ordering::Relaxed);
+    let update = recognizer
+        .ended(
+            1,
+            Point::px(13, 10),
+            TouchPhase::Ended,
+            Px::new(6),
+            Duration::from_secs(60),
+            Px::new(36),
+        )
+        .expect("tracked");
+    assert!(update.gestures.is_empty());
+    assert_eq!(recognizer.last_tap, None);
+
+    // Moving beyond the slop cancels the long press.
+    start(&mut recognizer, 2, Point::px(10, 10));
+    recognizer.moved(2, Point::px(30, 10), Px::new(6));
+    assert!(recognizer.touches[&2].moved);
+    assert!(!recognizer.touches[&2].long_press.is_running());
+}
@@ -0,0 +1,116 @@
+use figures::units::Px;
+#[cfg(test)]
+use figures::Size;
+use figures::{FloatConversion, Point, Rect};
+
+/// The regions of a window that accept cursor input.
+///
+/// A mask is set using
+/// [`Window::hit_test_mask`](crate::window::Window::hit_test_mask). Cursor
+/// movement and mouse buttons outside of the mask are not delivered to the
+/// window's widgets: to widgets, the cursor appears to have left the window.
+///
+/// Operating systems do not allow a portion of a window to pass cursor events
+/// through to the applications beneath it. To allow clicks to reach other
+/// applications, disable
+/// [`Window::cursor_hittest`](crate::window::Window::cursor_hittest). Because
+/// a window no longer receives cursor events while hit testing is disabled,
+/// it must be re-enabled through another input source, such as a global
+/// hotkey.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum HitTestMask {
+    /// The entire window accepts cursor input.
+    #[default]
+    Window,
+    /// Only locations where a widget accepts cursor input, as determined by
+    /// [`Widget::hit_test`](crate::widget::Widget::hit_test), are part of the
+    /// mask.
+    Widgets,
+    /// Only locations inside of one of these shapes are part of the mask.
+    Shapes(Vec<MaskShape>),
+}
+
+impl HitTestMask {
+    /// Returns a mask containing a single `shape`.
+    #[must_use]
+    pub fn shape(shape: MaskShape) -> Self {
+        Self::Shapes(vec![shape])
+    }
+
+    /// Returns true if `location`, in window coordinates, is inside of this
+    /// mask.
+    ///
+    /// `widget_hit` is invoked to determine the result for
+    /// [`HitTestMask::Widgets`].
+    pub(crate) fn contains(&self, location: Point<Px>, widget_hit: impl FnOnce() -> bool) -> bool {
+        match self {
+            HitTestMask::Window => true,
+            HitTestMask::Widgets => widget_hit(),
+            HitTestMask::Shapes(shapes) => shapes.iter().any(|shape| shape.contains(location)),
+        }
+    }
+}
+
+impl From<MaskShape> for HitTestMask {
+    fn from(shape: MaskShape) -> Self {
+        Self::shape(shape)
+    }
+}
+
+impl From<Vec<MaskShape>> for HitTestMask {
+    fn from(shapes: Vec<MaskShape>) -> Self {
+        Self::Shapes(shapes)
+    }
+}
+
+/// A shape within a [`HitTestMask`], in window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaskShape {
+    /// A rectangle.
+    Rect(Rect<Px>),
+    /// An ellipse that fills a rectangle.
+    Ellipse(Rect<Px>),
+}
+
+impl MaskShape {
+    /// Returns true if `location` is inside of this shape.
+    #[must_use]
+    pub fn contains(&self, location: Point<Px>) -> bool {
+        match self {
+            MaskShape::Rect(rect) => rect.contains(location),
+            MaskShape::Ellipse(bounds) => {
+                let radii = bounds.size.into_float() / 2.;
+                if radii.width <= 0. || radii.height <= 0. {
+                    return false;
+                }
+                let delta = (location - bounds.origin).into_float();
+                ((delta.x - radii.width) / radii.width).powi(2)
+                    + ((delta.y - radii.height) / radii.height).powi(2)
+                    <= 1.
+            }
+        }
+    }
+}
+
+impl From<Rect<Px>> for MaskShape {
+    fn from(rect: Rect<Px>) -> Self {
+        Self::Rect(rect)
+    }
+}
+
+#[test]
+fn ellipses_exclude_corners() {
+    let ellipse = MaskShape::Ellipse(Rect::new(Point::px(0, 0), Size::px(100, 50)));
+    assert!(ellipse.contains(Point::px(50, 25)));
+    assert!(ellipse.contains(Point::px(1, 25)));
+    assert!(!ellipse.contains(Point::px(5, 5)));
+    assert!(!ellipse.contains(Point::px(101, 25)));
+
+    let mask = HitTestMask::Shapes(vec![
+        MaskShape::Rect(Rect::new(Point::px(0, 0), Size::px(10, 10))),
+        ellipse,
+    ]);
+    assert!(mask.contains(Point::px(5, 5), || false));
+    assert!(!mask.contains(Point::px(200, 5), || true));
+    assert!(HitTestMask::Widgets.contains(Point::px(200, 5), || true));
+}
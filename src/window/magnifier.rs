@@ -0,0 +1,270 @@
+use std::time::Duration;
+
+use figures::units::Px;
+use figures::{FloatConversion, Point, Rect, Size};
+use kludgine::app::winit::keyboard::{KeyCode, PhysicalKey};
+
+use crate::animation::easings::EaseOutQuadradic;
+use crate::animation::{AnimationHandle, AnimationTarget, Spawn};
+use crate::graphics::Transform;
+use crate::reactive::value::{Destination, Dynamic, Source};
+
+/// The smallest magnification a [`Magnifier`] can display.
+const MINIMUM_ZOOM: f32 = 1.;
+
+/// Temporarily magnifies a window's contents around a focal point.
+///
+/// A magnifier is attached to a window using
+/// [`Window::magnifier`](crate::window::Window::magnifier). While the zoom is
+/// greater than 1, the window's contents are drawn scaled up around the
+/// [`MagnifierFocus`], and input is mapped back to the magnified contents so
+/// that widgets can continue to be used. Because the contents are redrawn at
+/// the magnified scale rather than stretched, text and shapes remain crisp.
+///
+/// Changes to the zoom are animated. While a magnifier is attached, the
+/// window handles these keyboard shortcuts when the focused widget does not
+/// handle them:
+///
+/// | Shortcut | Action |
+/// |----------|--------|
+/// | Primary + Alt + `=` | [`zoom_in()`](Self::zoom_in) |
+/// | Primary + Alt + `-` | [`zoom_out()`](Self::zoom_out) |
+/// | Primary + Alt + `0` | [`reset()`](Self::reset) |
+///
+/// The primary modifier is Command on Apple platforms and Control on all
+/// other platforms.
+#[derive(Debug, Clone)]
+pub struct Magnifier {
+    zoom: Dynamic<f32>,
+    displayed: Dynamic<f32>,
+    focus: Dynamic<MagnifierFocus>,
+    step: f32,
+    maximum: f32,
+}
+
+impl Magnifier {
+    /// Returns a new magnifier that is not zoomed, that follows the cursor,
+    /// and that animates zoom changes over 150 milliseconds.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::animated(Duration::from_millis(150))
+    }
+
+    /// Returns a new magnifier that is not zoomed, that follows the cursor,
+    /// and that animates zoom changes over `transition`.
+    #[must_use]
+    pub fn animated(transition: Duration) -> Self {
+        let zoom = Dynamic::new(MINIMUM_ZOOM);
+        let displayed = Dynamic::new(MINIMUM_ZOOM);
+        let mut animation = AnimationHandle::new();
+        zoom.for_each_subsequent({
+            let displayed = displayed.clone();
+            move |zoom| {
+                animation = displayed
+                    .transition_to(zoom.max(MINIMUM_ZOOM))
+                    .over(transition)
+                    .with_easing(EaseOutQuadradic)
+                    .spawn();
+            }
+        })
+        .persist();
+        Self {
+            zoom,
+            displayed,
+            focus: Dynamic::new(MagnifierFocus::Cursor),
+            step: 1.5,
+            maximum: 8.,
+        }
+    }
+
+    /// Sets the factor the zoom is multiplied or divided by when zooming in or
+    /// out, and returns self.
+    ///
+    /// The default step is 1.5.
+    #[must_use]
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step.max(1.);
+        self
+    }
+
+    /// Sets the largest zoom [`zoom_in()`](Self::zoom_in) can reach, and
+    /// returns self.
+    ///
+    /// The default maximum is 8.
+    #[must_use]
+    pub fn maximum(mut self, maximum: f32) -> Self {
+        self.maximum = maximum.max(MINIMUM_ZOOM);
+        self
+    }
+
+    /// Returns the dynamic controlling the target magnification.
+    ///
+    /// A value of 1 displays the window's contents without magnification.
+    /// Values less than 1 are treated as 1.
+    #[must_use]
+    pub const fn zoom(&self) -> &Dynamic<f32> {
+        &self.zoom
+    }
+
+    /// Returns the dynamic controlling what the magnifier is centered on.
+    #[must_use]
+    pub const fn focus(&self) -> &Dynamic<MagnifierFocus> {
+        &self.focus
+    }
+
+    /// Multiplies the zoom by this magnifier's step, up to its maximum.
+    pub fn zoom_in(&self) {
+        self.zoom
+            .map_mut(|mut zoom| *zoom = (*zoom * self.step).min(self.maximum));
+    }
+
+    /// Divides the zoom by this magnifier's step, down to no magnification.
+    pub fn zoom_out(&self) {
+        self.zoom
+            .map_mut(|mut zoom| *zoom = (*zoom / self.step).max(MINIMUM_ZOOM));
+    }
+
+    /// Removes all magnification.
+    pub fn reset(&self) {
+        self.zoom.set(MINIMUM_ZOOM);
+    }
+
+    /// Returns the zoom currently being displayed, which differs from
+    /// [`zoom()`](Self::zoom) while a change is being animated.
+    pub(crate) fn displayed(&self) -> &Dynamic<f32> {
+        &self.displayed
+    }
+
+    /// Returns true if the magnified contents need to be redrawn when the
+    /// cursor moves.
+    pub(crate) fn follows_cursor(&self) -> bool {
+        self.displayed.get() > MINIMUM_ZOOM && self.focus.get() == MagnifierFocus::Cursor
+    }
+
+    /// Performs the action for `key` if it is one of this magnifier's
+    /// shortcuts, returning true if it was.
+    pub(crate) fn shortcut(&self, key: PhysicalKey, pressed: bool) -> bool {
+        let PhysicalKey::Code(code) = key else {
+            return false;
+        };
+        let action: fn(&Self) = match code {
+            KeyCode::Equal | KeyCode::NumpadAdd => Self::zoom_in,
+            KeyCode::Minus | KeyCode::NumpadSubtract => Self::zoom_out,
+            KeyCode::Digit0 | KeyCode::Numpad0 => Self::reset,
+            _ => return false,
+        };
+        if pressed {
+            action(self);
+        }
+        true
+    }
+
+    /// Returns the transform that magnifies a window of `size`, or `None` if
+    /// the contents are not magnified.
+    ///
+    /// `cursor` is the cursor's location, and `focused` is the region of the
+    /// focused widget, both in window coordinates.
+    pub(crate) fn transform(
+        &self,
+        size: Size<Px>,
+        cursor: Option<Point<Px>>,
+        focused: Option<Rect<Px>>,
+    ) -> Option<Transform> {
+        let zoom = self.displayed.get();
+        if zoom <= MINIMUM_ZOOM {
+            return None;
+        }
+        let center = |region: Rect<Px>| {
+            Point::new(
+                region.origin.x + region.size.width / 2,
+                region.origin.y + region.size.height / 2,
+            )
+        };
+        let focal = match self.focus.get() {
+            MagnifierFocus::Cursor => cursor.map(Focal::Fixed),
+            MagnifierFocus::FocusedWidget => focused
+                .map(|region| Focal::Centered(center(region)))
+                .or(cursor.map(Focal::Fixed)),
+            MagnifierFocus::Point(point) => Some(Focal::Centered(point)),
+        }
+        .unwrap_or_else(|| Focal::Centered(center(Rect::from(size))));
+        let origin = magnified_origin(focal, size, zoom);
+        Some(Transform {
+            scale: zoom,
+            translation: Point::new(-origin.x * zoom, -origin.y * zoom),
+            ..Transform::default()
+        })
+    }
+}
+
+impl Default for Magnifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a [`Magnifier`] is centered on.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum MagnifierFocus {
+    /// The contents beneath the cursor remain beneath the cursor, and the
+    /// magnified region follows the cursor as it moves.
+    #[default]
+    Cursor,
+    /// The focused widget is centered in the window when possible. When no
+    /// widget is focused, the cursor is followed.
+    FocusedWidget,
+    /// This location, in window coordinates, is centered in the window when
+    /// possible.
+    Point(Point<Px>),
+}
+
+#[derive(Clone, Copy)]
+enum Focal {
+    /// This location should not move when magnified.
+    Fixed(Point<Px>),
+    /// This location should be centered in the window.
+    Centered(Point<Px>),
+}
+
+/// Returns the location in window coordinates that is drawn at the top-left
+/// corner of a window of `size` when magnified by `zoom` around `focal`.
+///
+/// The magnified region never extends past the edges of the window.
+fn magnified_origin(focal: Focal, size: Size<Px>, zoom: f32) -> Point<f32> {
+    let size = size.into_float();
+    let visible = Size::new(size.width / zoom, size.height / zoom);
+    let origin = match focal {
+        Focal::Fixed(point) => {
+            let point = point.into_float();
+            Point::new(point.x * (1. - 1. / zoom), point.y * (1. - 1. / zoom))
+        }
+        Focal::Centered(point) => {
+            let point = point.into_float();
+            Point::new(point.x - visible.width / 2., point.y - visible.height / 2.)
+        }
+    };
+    Point::new(
+        origin.x.clamp(0., (size.width - visible.width).max(0.)),
+        origin.y.clamp(0., (size.height - visible.height).max(0.)),
+    )
+}
+
+#[test]
+fn magnified_regions_stay_in_window() {
+    let size = Size::px(400, 200);
+    // Zooming around the cursor keeps the cursor's location fixed.
+    assert_eq!(
+        magnified_origin(Focal::Fixed(Point::px(100, 100)), size, 2.),
+        Point::new(50., 50.)
+    );
+    // Centering a location shows the region around it.
+    assert_eq!(
+        magnified_origin(Focal::Centered(Point::px(200, 100)), size, 2.),
+        Point::new(100., 50.)
+    );
+    // Locations near the edge are not centered past the window's edge.
+    assert_eq!(
+        magnified_origin(Focal::Centered(Point::px(390, 10)), size, 2.),
+        Point::new(200., 0.)
+    );
+}
@@ -0,0 +1,377 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use figures::units::{Px, UPx};
+use figures::{IntoSigned, IntoUnsigned, Point, Rect, Size};
+use kludgine::app::winit;
+use kludgine::app::winit::monitor::MonitorHandle;
+
+use crate::reactive::value::{Destination, Dynamic, IntoDynamic, MapEach, Source};
+use crate::window::{Window, WindowBehavior};
+use crate::App;
+
+/// The height of the region at the top of a window that must be on a monitor
+/// for the window's saved position to be restored.
+const GRAB_HEIGHT: i32 = 32;
+
+/// The location, size, and state of a window that can be restored when the
+/// window is opened again.
+///
+/// All measurements are in physical pixels.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowGeometry {
+    /// The location of the window, including decorations.
+    pub position: Point<Px>,
+    /// The size of the window, excluding decorations.
+    pub size: Size<UPx>,
+    /// True if the window is maximized.
+    ///
+    /// While a window is maximized, `position` and `size` continue to
+    /// describe the window before it was maximized.
+    pub maximized: bool,
+    /// The name of the monitor the window is on, if known.
+    pub monitor: Option<String>,
+}
+
+impl WindowGeometry {
+    /// Returns this geometry adjusted to be visible on one of `monitors`.
+    ///
+    /// The top of the window must be on a monitor to keep its position,
+    /// preferring the monitor the window was last on. If the top of the
+    /// window is not on any monitor, such as when its monitor has been
+    /// disconnected, the window is centered on the first monitor. In either
+    /// case the window is shrunk and moved to fit within the monitor.
+    fn restorable(&self, monitors: impl IntoIterator<Item = (Option<String>, Rect<Px>)>) -> Self {
+        let monitors = monitors.into_iter().collect::<Vec<_>>();
+        let size = self.size.into_signed();
+        let grab_point = Point::new(
+            self.position.x + size.width / 2,
+            self.position.y + size.height.min(Px::new(GRAB_HEIGHT)) / 2,
+        );
+        let contains_window =
+            |(_, region): &&(Option<String>, Rect<Px>)| region.contains(grab_point);
+        let (monitor, centered) = match monitors
+            .iter()
+            .filter(|(name, _)| name.is_some() && *name == self.monitor)
+            .find(contains_window)
+            .or_else(|| monitors.iter().find(contains_window))
+        {
+            Some(monitor) => (monitor, false),
+            None => match monitors.first() {
+                Some(monitor) => (monitor, true),
+                None => return self.clone(),
+            },
+        };
+
+        let (name, region) = monitor;
+        let size = Size::new(
+            size.width.min(region.size.width),
+            size.height.min(region.size.height),
+        );
+        let (region_min, region_max) = region.extents();
+        let position = if centered {
+            region.origin + (region.size - size) / 2
+        } else {
+            Point::new(
+                self.position
+                    .x
+                    .min(region_max.x - size.width)
+                    .max(region_min.x),
+                self.position
+                    .y
+                    .min(region_max.y - size.height)
+                    .max(region_min.y),
+            )
+        };
+        Self {
+            position,
+            size: size.into_unsigned(),
+            maximized: self.maximized,
+            monitor: name.clone(),
+        }
+    }
+
+    /// Returns this geometry in the text format used by
+    /// [`WindowPersistence::file`].
+    fn to_text(&self) -> String {
+        let mut text = format!(
+            "position = {} {}\nsize = {} {}\nmaximized = {}\n",
+            self.position.x.get(),
+            self.position.y.get(),
+            self.size.width.get(),
+            self.size.height.get(),
+            self.maximized
+        );
+        if let Some(monitor) = &self.monitor {
+            text.push_str("monitor = ");
+            text.push_str(monitor);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Parses geometry written by [`to_text()`](Self::to_text).
+    fn from_text(text: &str) -> Option<Self> {
+        fn pair<T: std::str::FromStr>(value: &str) -> Option<(T, T)> {
+            let (first, second) = value.split_once(' ')?;
+            Some((first.parse().ok()?, second.parse().ok()?))
+        }
+
+        let mut position = None;
+        let mut size = None;
+        let mut maximized = false;
+        let mut monitor = None;
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once(" = ") else {
+                continue;
+            };
+            match key {
+                "position" => position = pair::<i32>(value).map(|(x, y)| Point::px(x, y)),
+                "size" => size = pair::<u32>(value).map(|(w, h)| Size::upx(w, h)),
+                "maximized" => maximized = value == "true",
+                "monitor" => monitor = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        Some(Self {
+            position: position?,
+            size: size?,
+            maximized,
+            monitor,
+        })
+    }
+}
+
+/// Remembers the location, size, and state of a window between runs.
+///
+/// A persistence is attached to a window using
+/// [`Window::persisted`](crate::window::Window::persisted). When the window is
+/// opened, the saved [`WindowGeometry`] is restored after being adjusted to
+/// fit the currently connected monitors. As the window is moved, resized,
+/// maximized, or moved between monitors, the geometry is updated once the
+/// window has stopped changing for the [delay](Self::delay).
+///
+/// The geometry can be stored anywhere:
+///
+/// - [`WindowPersistence::file`] reads and writes the geometry to a file.
+/// - [`geometry()`](Self::geometry) returns the [`Dynamic`] containing the
+///   geometry, which can be observed to store it elsewhere. With the `serde`
+///   feature enabled, both [`WindowGeometry`] and the dynamic can be
+///   serialized.
+///
+/// ```rust
+/// use cushy::widget::MakeWidget;
+/// use cushy::window::WindowPersistence;
+///
+/// let window = "Hello, World!"
+///     .into_window()
+///     .persisted(&WindowPersistence::file("window.txt"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WindowPersistence {
+    geometry: Dynamic<Option<WindowGeometry>>,
+    delay: Duration,
+}
+
+impl WindowPersistence {
+    /// Returns a persistence with no saved geometry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_geometry(Dynamic::new(None))
+    }
+
+    /// Returns a persistence that restores and updates `geometry`.
+    #[must_use]
+    pub fn with_geometry(geometry: impl IntoDynamic<Option<WindowGeometry>>) -> Self {
+        Self {
+            geometry: geometry.into_dynamic(),
+            delay: Duration::from_millis(500),
+        }
+    }
+
+    /// Returns a persistence that restores the geometry saved at `path`, and
+    /// saves the geometry to `path` each time it is updated.
+    ///
+    /// If `path` does not exist or cannot be parsed, the window is opened
+    /// without restoring any geometry.
+    #[must_use]
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        let path = Arc::new(path.into());
+        let geometry = std::fs::read_to_string(&*path)
+            .ok()
+            .and_then(|text| WindowGeometry::from_text(&text));
+        let this = Self::with_geometry(Dynamic::new(geometry));
+        this.geometry
+            .for_each_subsequent(move |geometry| {
+                if let Some(geometry) = geometry {
+                    save(&path, geometry);
+                }
+            })
+            .persist();
+        this
+    }
+
+    /// Sets how long a window must stop changing before its geometry is
+    /// updated, and returns self.
+    ///
+    /// The default delay is 500 milliseconds.
+    #[must_use]
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Returns the dynamic containing the saved geometry.
+    #[must_use]
+    pub const fn geometry(&self) -> &Dynamic<Option<WindowGeometry>> {
+        &self.geometry
+    }
+
+    /// Restores the saved geometry into `window`, and begins updating the
+    /// saved geometry as the window changes.
+    pub(crate) fn attach<Behavior>(&self, window: &mut Window<Behavior>, app: &App)
+    where
+        Behavior: WindowBehavior,
+    {
+        let position = window
+            .outer_position
+            .get_or_insert_with(Dynamic::default)
+            .clone();
+        let size = window
+            .inner_size
+            .get_or_insert_with(Dynamic::default)
+            .clone();
+        let maximized = window
+            .maximized
+            .get_or_insert_with(Dynamic::default)
+            .clone();
+        let minimized = window
+            .minimized
+            .get_or_insert_with(Dynamic::default)
+            .clone();
+        let monitor = window
+            .current_monitor
+            .get_or_insert_with(Dynamic::default)
+            .clone();
+
+        if let Some(saved) = self.geometry.get() {
+            let monitors = app
+                .monitors()
+                .map(|monitors| {
+                    monitors
+                        .primary
+                        .into_iter()
+                        .chain(monitors.available)
+                        .map(|monitor| (monitor.name(), monitor.region()))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let restored = saved.restorable(monitors);
+            window.attributes.position =
+                Some(winit::dpi::Position::Physical(restored.position.into()));
+            window.attributes.inner_size = Some(winit::dpi::Size::Physical(restored.size.into()));
+            window.attributes.maximized = restored.maximized;
+            position.set(restored.position);
+            size.set(restored.size);
+            maximized.set(restored.maximized);
+        }
+
+        let geometry = self.geometry.clone();
+        (&position, &size, &maximized, &minimized, &monitor)
+            .map_each(|(position, size, maximized, minimized, monitor)| {
+                // Minimized windows report meaningless locations on some
+                // platforms.
+                (!minimized && size.width > 0 && size.height > 0).then(|| WindowGeometry {
+                    position: *position,
+                    size: *size,
+                    maximized: *maximized,
+                    monitor: monitor.as_ref().and_then(MonitorHandle::name),
+                })
+            })
+            .debounced_with_delay(self.delay)
+            .for_each_subsequent(move |observed| {
+                let Some(observed) = observed.clone() else {
+                    return;
+                };
+                geometry.map_mut(|mut saved| {
+                    let updated = match (&*saved, observed.maximized) {
+                        // Keep the location the window will be restored to.
+                        (Some(saved), true) => WindowGeometry {
+                            maximized: true,
+                            monitor: observed.monitor,
+                            ..saved.clone()
+                        },
+                        _ => observed,
+                    };
+                    if saved.as_ref() != Some(&updated) {
+                        *saved = Some(updated);
+                    }
+                });
+            })
+            .persist();
+    }
+}
+
+impl Default for WindowPersistence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn save(path: &Path, geometry: &WindowGeometry) {
+    if let Err(err) = std::fs::write(path, geometry.to_text()) {
+        tracing::warn!("error saving window geometry to {}: {err}", path.display());
+    }
+}
+
+#[test]
+fn geometry_is_restored_onto_connected_monitors() {
+    let geometry = WindowGeometry {
+        position: Point::px(2000, 100),
+        size: Size::upx(800, 600),
+        maximized: false,
+        monitor: Some(String::from("right")),
+    };
+    assert_eq!(
+        WindowGeometry::from_text(&geometry.to_text()),
+        Some(geometry.clone())
+    );
+
+    let left = (
+        Some(String::from("left")),
+        Rect::new(Point::px(0, 0), Size::px(1920, 1080)),
+    );
+    let right = (
+        Some(String::from("right")),
+        Rect::new(Point::px(1920, 0), Size::px(1280, 720)),
+    );
+
+    // The window's monitor is still connected.
+    assert_eq!(geometry.restorable([left.clone(), right]), geometry);
+
+    // The window's monitor was disconnected.
+    assert_eq!(
+        geometry.restorable([left]),
+        WindowGeometry {
+            position: Point::px(560, 240),
+            monitor: Some(String::from("left")),
+            ..geometry.clone()
+        }
+    );
+
+    // The window no longer fits on its monitor.
+    let small = (
+        Some(String::from("right")),
+        Rect::new(Point::px(1920, 0), Size::px(640, 480)),
+    );
+    assert_eq!(
+        geometry.restorable([small]),
+        WindowGeometry {
+            position: Point::px(1920, 0),
+            size: Size::upx(640, 480),
+            ..geometry
+        }
+    );
+}
@@ -0,0 +1,204 @@
+use figures::units::{Px, UPx};
+use figures::{IntoSigned, Point, Rect, Size};
+
+use crate::context::WidgetContext;
+use crate::reactive::value::{Destination, Dynamic, Source};
+use crate::widget::{MakeWidget, WidgetInstance};
+use crate::widgets::layers::Direction;
+use crate::window::{MakeWindow, WindowHandle};
+use crate::Open;
+
+/// A borderless window that is positioned next to a widget.
+///
+/// Unlike an [`OverlayLayer`](crate::widgets::layers::OverlayLayer), a popup
+/// is not clipped to the window containing the widget it is anchored to. This
+/// allows menus and dropdown lists near the edge of a window to overflow the
+/// window, like native menus do.
+///
+/// The popup is sized to fit its contents and is placed in the preferred
+/// [`Direction`] from its anchor. If there is not enough room on the monitor
+/// in that direction, the popup is flipped to the opposite side of its anchor
+/// and shifted to remain on screen. The popup is repositioned whenever the
+/// size of its contents changes.
+///
+/// Popups are owned by the window that opened them (see
+/// [`Window::owned_by`](crate::window::Window::owned_by)), and are not
+/// activated when opened, leaving keyboard focus in the owning window. Popups
+/// are not dismissed automatically: use the returned [`WindowHandle`] to close
+/// the popup.
+#[derive(Debug)]
+pub struct Popup {
+    contents: WidgetInstance,
+    direction: Direction,
+    anchor: Option<Rect<Px>>,
+}
+
+impl Popup {
+    /// Returns a new popup that displays `contents` below its anchor.
+    #[must_use]
+    pub fn new(contents: impl MakeWidget) -> Self {
+        Self {
+            contents: contents.make_widget(),
+            direction: Direction::Down,
+            anchor: None,
+        }
+    }
+
+    /// Sets the preferred direction to place the popup relative to its anchor
+    /// and returns self.
+    #[must_use]
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Anchors this popup to `location` instead of the widget opening the
+    /// popup, and returns self.
+    ///
+    /// `location` is in window coordinates, such as the location of a mouse
+    /// event. This is useful for context menus that should appear at the
+    /// cursor.
+    #[must_use]
+    pub fn at(mut self, location: Point<Px>) -> Self {
+        self.anchor = Some(Rect::new(location, Size::default()));
+        self
+    }
+
+    /// Opens this popup anchored to the widget of `context`.
+    ///
+    /// Returns `Ok(None)` if the popup could not be opened because the window
+    /// is not managed by a running application, such as when rendering a
+    /// virtual window. Returns `Ok(None)` if no location was provided using
+    /// [`Self::at`] and the widget has not been laid out yet.
+    pub fn open(self, context: &WidgetContext<'_>) -> crate::Result<Option<WindowHandle>> {
+        let window = context.window();
+        let Some(mut app) = window.app().cloned() else {
+            return Ok(None);
+        };
+        let Some(anchor) = self.anchor.or_else(|| context.last_layout()) else {
+            return Ok(None);
+        };
+        let window_origin: Point<Px> = window
+            .winit()
+            .and_then(|winit| winit.inner_position().ok())
+            .map(Into::into)
+            .unwrap_or_default();
+        let anchor = Rect::new(anchor.origin + window_origin, anchor.size);
+
+        let bounds = app.monitors().and_then(|monitors| {
+            monitors
+                .available
+                .into_iter()
+                .find(|monitor| monitor.region().contains(anchor.origin))
+                .or(monitors.primary)
+                .map(|monitor| monitor.region())
+        });
+        let direction = self.direction;
+        let position = Dynamic::new(place_popup(anchor, Size::default(), direction, bounds));
+        let size = Dynamic::<Size<UPx>>::default();
+        size.for_each_subsequent({
+            let position = position.clone();
+            move |size| {
+                position.set(place_popup(anchor, size.into_signed(), direction, bounds));
+            }
+        })
+        .persist();
+
+        self.contents
+            .make_window()
+            .owned_by(&window.handle())
+            .decorated(false)
+            .resizable(false)
+            .focused(false)
+            .outer_position(position, false)
+            .inner_size(size)
+            .size_to_content()
+            .open(&mut app)
+            .map(Some)
+    }
+}
+
+/// Returns the screen location to place a popup of `size` relative to
+/// `anchor` in `direction`, keeping the popup within `bounds` when possible.
+fn place_popup(
+    anchor: Rect<Px>,
+    size: Size<Px>,
+    direction: Direction,
+    bounds: Option<Rect<Px>>,
+) -> Point<Px> {
+    let (anchor_tl, anchor_br) = anchor.extents();
+    let mut origin = match direction {
+        Direction::Up => Point::new(anchor_tl.x, anchor_tl.y - size.height),
+        Direction::Right => Point::new(anchor_br.x, anchor_tl.y),
+        Direction::Down => Point::new(anchor_tl.x, anchor_br.y),
+        Direction::Left => Point::new(anchor_tl.x - size.width, anchor_tl.y),
+    };
+    let Some(bounds) = bounds else {
+        return origin;
+    };
+    let (bounds_tl, bounds_br) = bounds.extents();
+
+    match direction {
+        Direction::Up | Direction::Down => {
+            let above = anchor_tl.y - bounds_tl.y;
+            let below = bounds_br.y - anchor_br.y;
+            if direction == Direction::Down && below < size.height && above > below {
+                origin.y = anchor_tl.y - size.height;
+            } else if direction == Direction::Up && above < size.height && below > above {
+                origin.y = anchor_br.y;
+            }
+            origin.x = shift_into(origin.x, size.width, bounds_tl.x, bounds_br.x);
+        }
+        Direction::Left | Direction::Right => {
+            let before = anchor_tl.x - bounds_tl.x;
+            let after = bounds_br.x - anchor_br.x;
+            if direction == Direction::Right && after < size.width && before > after {
+                origin.x = anchor_tl.x - size.width;
+            } else if direction == Direction::Left && before < size.width && after > before {
+                origin.x = anchor_br.x;
+            }
+            origin.y = shift_into(origin.y, size.height, bounds_tl.y, bounds_br.y);
+        }
+    }
+
+    origin
+}
+
+/// Shifts `start` so that `start..start + length` is within `min..max`,
+/// preferring to keep `min` visible if the range does not fit.
+fn shift_into(start: Px, length: Px, min: Px, max: Px) -> Px {
+    start.min(max - length).max(min)
+}
+
+#[test]
+fn popups_flip_and_shift_to_fit() {
+    let bounds = Some(Rect::new(Point::px(0, 0), Size::px(1000, 800)));
+    let size = Size::px(200, 300);
+
+    // Room below the anchor.
+    let anchor = Rect::new(Point::px(100, 100), Size::px(50, 20));
+    assert_eq!(
+        place_popup(anchor, size, Direction::Down, bounds),
+        Point::px(100, 120)
+    );
+
+    // Not enough room below, so the popup flips above the anchor.
+    let anchor = Rect::new(Point::px(100, 700), Size::px(50, 20));
+    assert_eq!(
+        place_popup(anchor, size, Direction::Down, bounds),
+        Point::px(100, 400)
+    );
+
+    // Near the right edge, so the popup shifts left to remain on screen.
+    let anchor = Rect::new(Point::px(950, 100), Size::px(50, 20));
+    assert_eq!(
+        place_popup(anchor, size, Direction::Down, bounds),
+        Point::px(800, 120)
+    );
+
+    // Without bounds, the preferred placement is always used.
+    assert_eq!(
+        place_popup(anchor, size, Direction::Right, None),
+        Point::px(1000, 100)
+    );
+}
@@ -0,0 +1,514 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use figures::units::UPx;
+use figures::Size;
+use intentional::{Assert, Cast};
+use kludgine::{wgpu, Texture};
+
+use crate::debug::ColorVisionDeficiency;
+use crate::reactive::value::{IntoValue, Source, Value};
+
+/// The maximum number of uniform values a [`PostProcess`] can have.
+pub const MAX_POST_PROCESS_UNIFORMS: usize = 16;
+
+/// The size of the uniform buffer: the target size, the number of values, and
+/// the values themselves.
+const UNIFORMS_SIZE: usize = 16 + MAX_POST_PROCESS_UNIFORMS * 4;
+
+/// The format of the textures used between passes.
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// The shader source prepended to each effect.
+const PRELUDE: &str = r"
+struct PostProcessUniforms {
+    size: vec2<f32>,
+    count: u32,
+    padding: u32,
+    values: array<vec4<f32>, 4>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+@group(0) @binding(2) var<uniform> uniforms: PostProcessUniforms;
+
+fn uniform_value(index: u32) -> f32 {
+    return uniforms.values[index / 4u][index % 4u];
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // A single triangle that covers the entire target.
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+";
+
+const GRAYSCALE: &str = r"
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(source, source_sampler, in.uv);
+    let luminance = dot(color.rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return vec4<f32>(mix(color.rgb, vec3<f32>(luminance), uniform_value(0u)), color.a);
+}
+";
+
+/// A shader effect applied to the rendered contents of a window.
+///
+/// Effects are written in WGSL and are applied in the order they are added to
+/// a window. Each effect must provide a fragment entry point named `fs_main`
+/// that accepts a `VertexOutput` and returns the color of the pixel. The
+/// following declarations are provided to each effect:
+///
+/// - `source`: A `texture_2d<f32>` containing the window's contents or the
+///   result of the previous effect.
+/// - `source_sampler`: A `sampler` for `source`.
+/// - `VertexOutput`: A struct whose `uv` field contains the texture coordinate
+///   of the pixel being shaded.
+/// - `uniforms.size`: A `vec2<f32>` containing the size of the window in
+///   pixels.
+/// - `uniform_value(index: u32) -> f32`: Returns the current value of the
+///   uniform at `index`, in the order they were added using
+///   [`with_uniform()`](Self::with_uniform).
+///
+/// Uniforms can be [`Dynamic`](crate::reactive::value::Dynamic)s, in which
+/// case the window is redrawn each time a uniform changes.
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::window::PostProcess;
+///
+/// let strength = Dynamic::new(0.5_f32);
+/// let vignette = PostProcess::new(
+///     r"
+///     @fragment
+///     fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+///         let color = textureSample(source, source_sampler, in.uv);
+///         let distance = length(in.uv - vec2<f32>(0.5));
+///         let shade = 1.0 - distance * uniform_value(0u);
+///         return vec4<f32>(color.rgb * shade, color.a);
+///     }
+///     ",
+/// )
+/// .with_uniform(strength);
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct PostProcess {
+    shader: Cow<'static, str>,
+    uniforms: Vec<Value<f32>>,
+}
+
+impl PostProcess {
+    /// Returns a new effect that shades each pixel using the `fs_main`
+    /// function in `shader`.
+    pub fn new(shader: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            shader: shader.into(),
+            uniforms: Vec::new(),
+        }
+    }
+
+    /// Returns an effect that removes color from the window's contents.
+    ///
+    /// `amount` ranges from `0.0`, which leaves the contents unchanged, to
+    /// `1.0`, which renders the contents in grayscale.
+    pub fn grayscale(amount: impl IntoValue<f32>) -> Self {
+        Self::new(GRAYSCALE).with_uniform(amount)
+    }
+
+    /// Returns an effect that simulates how the window's contents are
+    /// perceived with `deficiency`.
+    ///
+    /// When `deficiency` is `None`, the contents are unchanged. This allows
+    /// toggling the simulation at runtime, such as by using the value returned
+    /// from
+    /// [`DebugContext::color_vision_deficiency()`](crate::debug::DebugContext::color_vision_deficiency).
+    pub fn color_vision_deficiency(
+        deficiency: impl IntoValue<Option<ColorVisionDeficiency>>,
+    ) -> Self {
+        let mut shader = String::from(
+            "@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(source, source_sampler, in.uv);
+    var simulation: mat3x3<f32>;
+    switch u32(uniform_value(0u)) {
+",
+        );
+        for deficiency in ColorVisionDeficiency::ALL {
+            let values = deficiency
+                .matrix()
+                .iter()
+                .flatten()
+                .map(|value| format!("{value:?}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            // The matrices are row-major, while WGSL's constructor is
+            // column-major.
+            let _ = writeln!(
+                shader,
+                "        case {}u: {{ simulation = transpose(mat3x3<f32>({values})); }}",
+                ColorVisionDeficiency::shader_index(Some(deficiency))
+            );
+        }
+        shader.push_str(
+            "        default: { return color; }
+    }
+    return vec4<f32>(clamp(simulation * color.rgb, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+}
+",
+        );
+        Self::new(shader).with_uniform(
+            deficiency
+                .into_value()
+                .map_each(|deficiency| ColorVisionDeficiency::shader_index(*deficiency)),
+        )
+    }
+
+    /// Adds a uniform value to this effect and returns self.
+    ///
+    /// The value can be read in the shader using `uniform_value(index)`, where
+    /// `index` is the number of uniforms added before this one.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if this effect already has
+    /// [`MAX_POST_PROCESS_UNIFORMS`] uniforms.
+    pub fn with_uniform(mut self, value: impl IntoValue<f32>) -> Self {
+        assert!(
+            self.uniforms.len() < MAX_POST_PROCESS_UNIFORMS,
+            "post-process effects support at most {MAX_POST_PROCESS_UNIFORMS} uniforms"
+        );
+        self.uniforms.push(value.into_value());
+        self
+    }
+
+    /// Returns the uniforms of this effect.
+    pub(crate) fn uniforms(&self) -> &[Value<f32>] {
+        &self.uniforms
+    }
+
+    fn uniform_bytes(&self, size: Size<UPx>) -> [u8; UNIFORMS_SIZE] {
+        let mut bytes = [0; UNIFORMS_SIZE];
+        bytes[0..4].copy_from_slice(&size.width.get().cast::<f32>().to_le_bytes());
+        bytes[4..8].copy_from_slice(&size.height.get().cast::<f32>().to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.uniforms.len().cast::<u32>().to_le_bytes());
+        for (index, value) in self.uniforms.iter().enumerate() {
+            let offset = 16 + index * 4;
+            bytes[offset..offset + 4].copy_from_slice(&value.get().to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// Renders a window's contents into a texture and applies a series of
+/// [`PostProcess`] effects to it.
+pub(crate) struct PostProcessor {
+    effects: Vec<EffectState>,
+    multisample_count: u32,
+    resources: Option<Resources>,
+    targets: Option<Targets>,
+}
+
+struct EffectState {
+    effect: PostProcess,
+    module: Option<wgpu::ShaderModule>,
+    uniforms: Option<wgpu::Buffer>,
+    pipelines: Vec<(u32, wgpu::RenderPipeline)>,
+}
+
+impl EffectState {
+    fn pipeline(
+        &mut self,
+        sample_count: u32,
+        layout: &wgpu::PipelineLayout,
+        device: &wgpu::Device,
+    ) -> &wgpu::RenderPipeline {
+        let index = if let Some(index) = self
+            .pipelines
+            .iter()
+            .position(|(count, _)| *count == sample_count)
+        {
+            index
+        } else {
+            let module = self.module.as_ref().assert("prepared before rendering");
+            let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("cushy post-process"),
+                layout: Some(layout),
+                vertex: wgpu::VertexState {
+                    module,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: sample_count,
+                    ..Default::default()
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module,
+                    entry_point: Some("fs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    targets: &[Some(FORMAT.into())],
+                }),
+                multiview: None,
+                cache: None,
+            });
+            self.pipelines.push((sample_count, pipeline));
+            self.pipelines.len() - 1
+        };
+        &self.pipelines[index].1
+    }
+}
+
+struct Resources {
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl Resources {
+    fn new(device: &wgpu::Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cushy post-process"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cushy post-process"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("cushy post-process"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Self {
+            bind_group_layout,
+            pipeline_layout,
+            sampler,
+        }
+    }
+}
+
+struct Targets {
+    scene: Texture,
+    multisampled: Option<Texture>,
+    intermediate: Texture,
+}
+
+impl PostProcessor {
+    /// Returns a processor for `effects`, or `None` if `effects` is empty.
+    pub fn new(effects: Vec<PostProcess>, multisample_count: u32) -> Option<Self> {
+        (!effects.is_empty()).then(|| Self {
+            effects: effects
+                .into_iter()
+                .map(|effect| EffectState {
+                    effect,
+                    module: None,
+                    uniforms: None,
+                    pipelines: Vec::new(),
+                })
+                .collect(),
+            multisample_count,
+            resources: None,
+            targets: None,
+        })
+    }
+
+    /// Prepares the resources needed to process a frame of `size`.
+    pub fn prepare(&mut self, size: Size<UPx>, graphics: &kludgine::Graphics<'_>) {
+        let device = graphics.device();
+        self.resources.get_or_insert_with(|| Resources::new(device));
+
+        for state in &mut self.effects {
+            state.module.get_or_insert_with(|| {
+                device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("cushy post-process"),
+                    source: wgpu::ShaderSource::Wgsl(Cow::Owned(format!(
+                        "{PRELUDE}\n{}",
+                        state.effect.shader
+                    ))),
+                })
+            });
+            let uniforms = state.uniforms.get_or_insert_with(|| {
+                device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("cushy post-process"),
+                    size: UNIFORMS_SIZE.cast(),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                })
+            });
+            graphics
+                .queue()
+                .write_buffer(uniforms, 0, &state.effect.uniform_bytes(size));
+        }
+
+        if self
+            .targets
+            .as_ref()
+            .map_or(true, |targets| targets.scene.size() != size)
+        {
+            let usage =
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+            self.targets = Some(Targets {
+                scene: Texture::new(graphics, size, FORMAT, usage, wgpu::FilterMode::Linear),
+                multisampled: (self.multisample_count > 1).then(|| {
+                    Texture::multisampled(
+                        graphics,
+                        self.multisample_count,
+                        size,
+                        FORMAT,
+                        wgpu::TextureUsages::RENDER_ATTACHMENT,
+                        wgpu::FilterMode::Linear,
+                    )
+                }),
+                intermediate: Texture::new(graphics, size, FORMAT, usage, wgpu::FilterMode::Linear),
+            });
+        }
+    }
+
+    /// Returns the attachment the window's contents should be rendered to.
+    pub fn scene_attachment(&self) -> wgpu::RenderPassColorAttachment<'_> {
+        let targets = self.targets.as_ref().assert("prepared before rendering");
+        let (view, resolve_target) = match &targets.multisampled {
+            Some(multisampled) => (multisampled.view(), Some(targets.scene.view())),
+            None => (targets.scene.view(), None),
+        };
+        wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                store: wgpu::StoreOp::Store,
+            },
+        }
+    }
+
+    /// Applies each effect to the rendered contents, rendering the final
+    /// effect using `target`, whose attachment has `sample_count` samples.
+    pub fn apply(
+        &mut self,
+        target: &wgpu::RenderPassDescriptor<'_>,
+        sample_count: u32,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> wgpu::SubmissionIndex {
+        let resources = self.resources.as_ref().assert("prepared before rendering");
+        let targets = self.targets.as_ref().assert("prepared before rendering");
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("cushy post-process"),
+        });
+        let last = self.effects.len() - 1;
+        for (index, state) in self.effects.iter_mut().enumerate() {
+            // Each effect reads the output of the previous effect, alternating
+            // between the two textures.
+            let (source, destination) = if index % 2 == 0 {
+                (&targets.scene, &targets.intermediate)
+            } else {
+                (&targets.intermediate, &targets.scene)
+            };
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("cushy post-process"),
+                layout: &resources.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(source.view()),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&resources.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: state
+                            .uniforms
+                            .as_ref()
+                            .assert("prepared before rendering")
+                            .as_entire_binding(),
+                    },
+                ],
+            });
+
+            let (pipeline, mut pass) = if index == last {
+                (
+                    state.pipeline(sample_count, &resources.pipeline_layout, device),
+                    encoder.begin_render_pass(target),
+                )
+            } else {
+                (
+                    state.pipeline(1, &resources.pipeline_layout, device),
+                    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: Some("cushy post-process"),
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: destination.view(),
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                                store: wgpu::StoreOp::Store,
+                            },
+                        })],
+                        depth_stencil_attachment: None,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    }),
+                )
+            };
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        queue.submit([encoder.finish()])
+    }
+}
+
+#[test]
+fn uniform_layout() {
+    let effect = PostProcess::new("").with_uniform(0.5).with_uniform(2.);
+    let bytes = effect.uniform_bytes(Size::upx(640, 480));
+    let float = |offset: usize| f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    assert!((float(0) - 640.).abs() < f32::EPSILON);
+    assert!((float(4) - 480.).abs() < f32::EPSILON);
+    assert_eq!(u32::from_le_bytes(bytes[8..12].try_into().unwrap()), 2);
+    assert!((float(16) - 0.5).abs() < f32::EPSILON);
+    assert!((float(20) - 2.).abs() < f32::EPSILON);
+}
@@ -0,0 +1,352 @@
+use std::time::{Duration, Instant};
+
+use figures::units::{Lp, Px};
+use figures::{FloatConversion, IntoSigned, Point, ScreenScale, Size};
+use kludgine::app::winit::monitor::MonitorHandle;
+use kludgine::app::winit::window::Fullscreen;
+use kludgine::app::Monitor;
+use kludgine::shapes::{Shape, StrokeOptions};
+use kludgine::{Color, DrawableExt, Origin};
+
+use crate::context::LayoutContext;
+use crate::reactive::value::{Destination, Dynamic, Source};
+use crate::widget::MakeWidget;
+use crate::window::Window;
+
+/// The interval between redraws while click flashes are animating.
+const FLASH_FRAME: Duration = Duration::from_millis(16);
+
+/// Highlights the cursor and flashes mouse clicks in a window.
+///
+/// A highlight is attached to a window using
+/// [`Window::pointer_highlight`]. While [enabled](Self::enabled), a
+/// translucent halo is drawn around the cursor, and a ring expands from each
+/// location clicked. The highlight is drawn above the window's contents and
+/// does not affect layout or input.
+///
+/// Clones of a highlight share their state. When the same highlight is
+/// attached to multiple windows, each window draws the cursor's most recent
+/// location, scaled to the window's size. [`Presenter::mirror`] uses this to
+/// show the cursor of the presenting window on the mirrored display.
+#[derive(Debug, Clone)]
+pub struct PointerHighlight {
+    enabled: Dynamic<bool>,
+    state: Dynamic<PointerState>,
+    halo_color: Color,
+    click_color: Color,
+    radius: Lp,
+    flash_duration: Duration,
+}
+
+impl PointerHighlight {
+    /// Returns a new, enabled highlight with a yellow halo and red click
+    /// flashes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            enabled: Dynamic::new(true),
+            state: Dynamic::default(),
+            halo_color: Color::new(255, 220, 0, 96),
+            click_color: Color::new(255, 64, 64, 224),
+            radius: Lp::points(18),
+            flash_duration: Duration::from_millis(400),
+        }
+    }
+
+    /// Sets the color of the halo drawn around the cursor, and returns self.
+    #[must_use]
+    pub fn halo_color(mut self, color: Color) -> Self {
+        self.halo_color = color;
+        self
+    }
+
+    /// Sets the color of the rings drawn when the mouse is clicked, and
+    /// returns self.
+    #[must_use]
+    pub fn click_color(mut self, color: Color) -> Self {
+        self.click_color = color;
+        self
+    }
+
+    /// Sets the radius of the halo drawn around the cursor, and returns self.
+    ///
+    /// Click flashes expand to one and a half times this radius.
+    #[must_use]
+    pub fn radius(mut self, radius: Lp) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets how long each click flash is displayed, and returns self.
+    #[must_use]
+    pub fn flash_duration(mut self, duration: Duration) -> Self {
+        self.flash_duration = duration;
+        self
+    }
+
+    /// Returns the dynamic controlling whether this highlight is drawn.
+    #[must_use]
+    pub const fn enabled(&self) -> &Dynamic<bool> {
+        &self.enabled
+    }
+
+    /// Shows this highlight if it is hidden, or hides it if it is shown.
+    pub fn toggle(&self) {
+        self.enabled.toggle();
+    }
+
+    /// Records the cursor's location in a window of `size`.
+    pub(crate) fn cursor_moved(&self, location: Option<Point<Px>>, size: Size<Px>) {
+        let pointer = location.map(|location| normalize(location, size));
+        self.state.map_mut(|mut state| state.pointer = pointer);
+    }
+
+    /// Records a click at `location` in a window of `size`.
+    pub(crate) fn clicked(&self, location: Point<Px>, size: Size<Px>) {
+        if !self.enabled.get() {
+            return;
+        }
+        let location = normalize(location, size);
+        self.state.map_mut(|mut state| {
+            state.clicks.push(ClickFlash {
+                location,
+                at: Instant::now(),
+            });
+        });
+    }
+
+    /// Draws this highlight above the window's contents.
+    pub(crate) fn render(&self, context: &mut LayoutContext<'_, '_, '_, '_>) {
+        context.redraw_when_changed(&self.enabled);
+        context.redraw_when_changed(&self.state);
+        if !self.enabled.get() {
+            return;
+        }
+
+        let size = context.gfx.size().into_signed();
+        let radius = self.radius.into_px(context.gfx.scale());
+        let now = Instant::now();
+        let (pointer, clicks) = self.state.map_mut(|mut state| {
+            state.prune(now, self.flash_duration);
+            (state.pointer, state.clicks.clone())
+        });
+
+        if let Some(pointer) = pointer {
+            context.gfx.draw_shape(
+                Shape::filled_circle(radius, self.halo_color, Origin::Center)
+                    .translate_by(denormalize(pointer, size)),
+            );
+        }
+
+        for click in &clicks {
+            let progress = flash_progress(click.at, now, self.flash_duration);
+            let ring = radius.into_float() * (0.5 + progress);
+            let color = self
+                .click_color
+                .with_alpha_f32(self.click_color.alpha_f32() * (1. - progress));
+            context.gfx.draw_shape(
+                Shape::stroked_circle(
+                    Px::from_float(ring),
+                    Origin::Center,
+                    StrokeOptions::px_wide((radius / 6).max(Px::new(1))).colored(color),
+                )
+                .translate_by(denormalize(click.location, size)),
+            );
+        }
+
+        if !clicks.is_empty() {
+            context.redraw_in(FLASH_FRAME);
+        }
+    }
+}
+
+impl Default for PointerHighlight {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Default)]
+struct PointerState {
+    pointer: Option<Point<f32>>,
+    clicks: Vec<ClickFlash>,
+}
+
+impl PointerState {
+    fn prune(&mut self, now: Instant, flash_duration: Duration) {
+        self.clicks
+            .retain(|click| now.saturating_duration_since(click.at) < flash_duration);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ClickFlash {
+    location: Point<f32>,
+    at: Instant,
+}
+
+/// Returns how far along a flash that started `at` is, from 0 to 1.
+fn flash_progress(at: Instant, now: Instant, duration: Duration) -> f32 {
+    if duration.is_zero() {
+        return 1.;
+    }
+    (now.saturating_duration_since(at).as_secs_f32() / duration.as_secs_f32()).min(1.)
+}
+
+/// Converts `location` into a fraction of `size`.
+fn normalize(location: Point<Px>, size: Size<Px>) -> Point<f32> {
+    let location = location.into_float();
+    let size = size.into_float();
+    Point::new(
+        location.x / size.width.max(1.),
+        location.y / size.height.max(1.),
+    )
+}
+
+/// Converts a fraction of `size` into a location.
+fn denormalize(point: Point<f32>, size: Size<Px>) -> Point<Px> {
+    let size = size.into_float();
+    Point::new(
+        Px::from_float(point.x * size.width),
+        Px::from_float(point.y * size.height),
+    )
+}
+
+/// Presents a window's contents fullscreen on a chosen monitor.
+///
+/// A presenter supports two ways of presenting:
+///
+/// - **Extend**: A window opened with [`Window::presented_by`] moves to the
+///   presenting monitor and becomes fullscreen while presenting, and returns
+///   to its previous location when presenting stops.
+/// - **Mirror**: [`Presenter::mirror`] returns a window that is only visible
+///   while presenting, displaying its own copy of the contents fullscreen on
+///   the presenting monitor. Widgets can only be shown in one window, so the
+///   mirrored contents should be built from the same [`Dynamic`]s as the
+///   original contents to stay in sync.
+///
+/// Both approaches draw the presenter's [`PointerHighlight`], which can be
+/// toggled at runtime using [`PointerHighlight::enabled`].
+///
+/// ```rust
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widget::MakeWidget;
+/// use cushy::window::Presenter;
+///
+/// let presenter = Presenter::new();
+/// let count = Dynamic::new(0_u32);
+/// let contents = || count.to_label().centered().expand();
+///
+/// let window = contents().into_window();
+/// let mirror = presenter.mirror(contents());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Presenter {
+    monitor: Dynamic<Option<MonitorHandle>>,
+    fullscreen: Dynamic<Option<Fullscreen>>,
+    presenting: Dynamic<bool>,
+    highlight: PointerHighlight,
+}
+
+impl Presenter {
+    /// Returns a presenter that is not presenting, using the default
+    /// [`PointerHighlight`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_highlight(PointerHighlight::new())
+    }
+
+    /// Returns a presenter that is not presenting, using `highlight` to draw
+    /// the cursor and clicks.
+    #[must_use]
+    pub fn with_highlight(highlight: PointerHighlight) -> Self {
+        let monitor = Dynamic::new(None::<MonitorHandle>);
+        let fullscreen = monitor.map_each(|monitor| {
+            monitor
+                .clone()
+                .map(|monitor| Fullscreen::Borderless(Some(monitor)))
+        });
+        let presenting = monitor.map_each(Option::is_some);
+        Self {
+            monitor,
+            fullscreen,
+            presenting,
+            highlight,
+        }
+    }
+
+    /// Begins presenting on `monitor`.
+    ///
+    /// If already presenting, the presentation moves to `monitor`.
+    pub fn present_on(&self, monitor: &Monitor) {
+        self.monitor.set(Some(monitor.handle().clone()));
+    }
+
+    /// Stops presenting.
+    pub fn stop(&self) {
+        self.monitor.set(None);
+    }
+
+    /// Stops presenting if presenting, otherwise begins presenting on
+    /// `monitor`.
+    pub fn toggle(&self, monitor: &Monitor) {
+        if self.presenting.get() {
+            self.stop();
+        } else {
+            self.present_on(monitor);
+        }
+    }
+
+    /// Returns a dynamic that contains true while presenting.
+    #[must_use]
+    pub const fn presenting(&self) -> &Dynamic<bool> {
+        &self.presenting
+    }
+
+    /// Returns the highlight drawn by windows presented by this presenter.
+    #[must_use]
+    pub const fn pointer_highlight(&self) -> &PointerHighlight {
+        &self.highlight
+    }
+
+    /// Returns a window that displays `contents` fullscreen on the presenting
+    /// monitor while presenting, and is hidden otherwise.
+    ///
+    /// The returned window still needs to be opened.
+    #[must_use]
+    pub fn mirror(&self, contents: impl MakeWidget) -> Window {
+        contents
+            .into_window()
+            .visible(self.presenting.clone())
+            .presented_by(self)
+    }
+
+    pub(crate) fn fullscreen(&self) -> &Dynamic<Option<Fullscreen>> {
+        &self.fullscreen
+    }
+}
+
+impl Default for Presenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn pointer_locations_scale_between_windows() {
+    let presenter = Size::px(800, 600);
+    let mirror = Size::px(1600, 1200);
+    let location = normalize(Point::px(200, 150), presenter);
+    assert_eq!(location, Point::new(0.25, 0.25));
+    assert_eq!(denormalize(location, presenter), Point::px(200, 150));
+    assert_eq!(denormalize(location, mirror), Point::px(400, 300));
+
+    let start = Instant::now();
+    let duration = Duration::from_millis(400);
+    assert!(flash_progress(start, start, duration).abs() < f32::EPSILON);
+    assert!(
+        (flash_progress(start, start + Duration::from_millis(200), duration) - 0.5).abs()
+            < f32::EPSILON
+    );
+    assert!((flash_progress(start, start + duration * 2, duration) - 1.).abs() < f32::EPSILON);
+}
@@ -0,0 +1,211 @@
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+
+use ahash::AHashMap;
+
+use crate::context::EventContext;
+use crate::reactive::value::{Dynamic, DynamicReader, Source};
+use crate::window::WindowHandle;
+
+/// The windows currently open in an application.
+///
+/// The registry is returned from [`App::windows()`](crate::App::windows).
+/// Windows are added once they have been opened by the operating system and
+/// are removed when they are closed.
+///
+/// # Coordinating multiple windows
+///
+/// Windows can communicate in two ways:
+///
+/// - **Shared [`Dynamic`]s:** A `Dynamic` can be cloned and used by widgets in
+///   any number of windows. Each window that reads a `Dynamic` while it is
+///   being drawn, such as by using it as a label's text, is redrawn when the
+///   `Dynamic` changes. Widgets that requested invalidation are invalidated in
+///   every window that contains them. No additional synchronization is
+///   needed.
+/// - **Messages:** A value of any `Send` type can be sent to a window using
+///   [`WindowHandle::send_message()`]. The message is delivered on the
+///   window's thread to the handler registered for its type using
+///   [`Window::on_message()`](crate::window::Window::on_message). To send a
+///   message to every open window, use [`broadcast()`](Self::broadcast).
+///
+/// ```rust
+/// use cushy::widget::MakeWidget;
+/// use cushy::window::{MakeWindow, Window};
+///
+/// #[derive(Clone)]
+/// enum Message {
+///     Reload,
+/// }
+///
+/// let window = "Editor"
+///     .make_window()
+///     .on_message(|message: Message, _context| match message {
+///         Message::Reload => println!("reloading"),
+///     });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WindowsRegistry {
+    windows: Dynamic<Vec<WindowHandle>>,
+}
+
+impl WindowsRegistry {
+    /// Returns a handle to each open window.
+    #[must_use]
+    pub fn handles(&self) -> Vec<WindowHandle> {
+        self.windows.get()
+    }
+
+    /// Returns a reader of the handles of the open windows, which is updated
+    /// as windows are opened and closed.
+    #[must_use]
+    pub fn open_windows(&self) -> DynamicReader<Vec<WindowHandle>> {
+        self.windows.create_reader()
+    }
+
+    /// Returns the number of open windows.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.windows.map_ref(Vec::len)
+    }
+
+    /// Returns true if no windows are open.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if `window` is open.
+    #[must_use]
+    pub fn contains(&self, window: &WindowHandle) -> bool {
+        self.windows.map_ref(|windows| windows.contains(window))
+    }
+
+    /// Sends a clone of `message` to each open window, returning the number of
+    /// windows it was sent to.
+    ///
+    /// See [`WindowHandle::send_message()`] for more information.
+    pub fn broadcast<Message>(&self, message: &Message) -> usize
+    where
+        Message: Clone + Send + 'static,
+    {
+        self.handles()
+            .into_iter()
+            .filter(|window| window.send_message(message.clone()).is_ok())
+            .count()
+    }
+
+    pub(crate) fn register(&self, window: WindowHandle) {
+        self.windows.lock().push(window);
+    }
+
+    pub(crate) fn unregister(&self, window: &WindowHandle) {
+        self.windows.lock().retain(|open| open != window);
+    }
+}
+
+/// The handlers for messages sent to a window, keyed by message type.
+#[derive(Default)]
+pub(crate) struct MessageHandlers(AHashMap<TypeId, Box<dyn MessageHandler>>);
+
+impl MessageHandlers {
+    pub fn insert<Message, Function>(&mut self, handler: Function)
+    where
+        Message: Send + 'static,
+        Function: FnMut(Message, &mut EventContext<'_>) + Send + 'static,
+    {
+        self.0.insert(
+            TypeId::of::<Message>(),
+            Box::new(Handler(handler, PhantomData)),
+        );
+    }
+
+    pub fn get_mut(&mut self, message: &(dyn Any + Send)) -> Option<&mut dyn MessageHandler> {
+        self.0
+            .get_mut(&message.type_id())
+            .map(|handler| &mut **handler)
+    }
+}
+
+pub(crate) trait MessageHandler: Send {
+    fn handle(&mut self, message: Box<dyn Any + Send>, context: &mut EventContext<'_>);
+}
+
+struct Handler<Message, Function>(Function, PhantomData<fn(Message)>);
+
+impl<Message, Function> MessageHandler for Handler<Message, Function>
+where
+    Message: Send + 'static,
+    Function: FnMut(Message, &mut EventContext<'_>) + Send,
+{
+    fn handle(&mut self, message: Box<dyn Any + Send>, context: &mut EventContext<'_>) {
+        if let Ok(message) = message.downcast::<Message>() {
+            (self.0)(*message, context);
+        }
+    }
+}
+
+#[test]
+fn registry_tracks_open_windows() {
+    use crate::window::PendingWindow;
+
+    let registry = WindowsRegistry::default();
+    let open_windows = registry.open_windows();
+    let first = PendingWindow::default().handle();
+    let second = PendingWindow::default().handle();
+    assert!(registry.is_empty());
+
+    registry.register(first.clone());
+    registry.register(second.clone());
+    assert_eq!(registry.len(), 2);
+    assert!(registry.contains(&first));
+    assert_eq!(open_windows.get(), vec![first.clone(), second.clone()]);
+    // Windows that haven't been opened yet queue the message.
+    assert_eq!(registry.broadcast(&"hello"), 2);
+
+    registry.unregister(&first);
+    assert!(!registry.contains(&first));
+    assert!(registry.contains(&second));
+    assert_eq!(open_windows.get(), vec![second]);
+}
+
+#[test]
+fn message_handlers_are_keyed_by_type() {
+    struct Reload;
+
+    let mut handlers = MessageHandlers::default();
+    handlers.insert(|_: Reload, _context| {});
+    let reload: Box<dyn Any + Send> = Box::new(Reload);
+    assert!(handlers.get_mut(&*reload).is_some());
+    let other: Box<dyn Any + Send> = Box::new(1_u32);
+    assert!(handlers.get_mut(&*other).is_none());
+}
+
+#[test]
+fn shared_dynamics_update_every_window() {
+    use figures::units::UPx;
+    use figures::{Point, Size};
+    use kludgine::Color;
+
+    use crate::reactive::value::Destination;
+    use crate::widget::MakeWidget;
+    use crate::widgets::Space;
+
+    let color = Dynamic::new(Color::RED);
+    let mut recorders = [(); 2].map(|()| {
+        Space::colored(color.clone())
+            .build_recorder()
+            .size(Size::squared(UPx::new(8)))
+            .finish()
+            .expect("error creating recorder")
+    });
+    for recorder in &recorders {
+        recorder.assert_pixel_color(Point::squared(UPx::new(4)), Color::RED, "initial");
+    }
+
+    color.set(Color::BLUE);
+    for recorder in &mut recorders {
+        recorder.refresh().expect("error rendering");
+        recorder.assert_pixel_color(Point::squared(UPx::new(4)), Color::BLUE, "shared");
+    }
+}
@@ -0,0 +1,636 @@
+use std::sync::Arc;
+
+use figures::units::Px;
+use figures::{Fraction, IntoSigned, Point, Rect, Size, Zero};
+use kludgine::app::winit::event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase};
+use kludgine::app::{AppEvent, AsApplication};
+use parking_lot::Mutex;
+
+use super::sealed::{self, WindowCommand};
+use super::{
+    request_virtual_device, CaptureFormat, DeviceId, KeyEvent, MakeWindow, PendingWindow,
+    VirtualRecorder, VirtualRecorderError, VirtualWindow, WindowHandle,
+};
+use crate::app::{App, Application, Cushy, Open};
+use crate::reactive::value::{Destination, Dynamic, Source};
+use crate::widget::{EventHandling, WidgetInstance, IGNORED};
+
+/// A headless app that opens its windows as virtual windows.
+///
+/// Windows opened in a virtual app, including windows opened by widgets in
+/// response to input such as dialogs and tool palettes, are rendered
+/// offscreen. Each window can be captured separately using the
+/// [`VirtualRecorder`] returned from [`VirtualApp::window`], which makes
+/// multi-window behaviors testable without a display.
+///
+/// The app simulates a desktop containing one or more [`VirtualMonitor`]s.
+/// Windows are positioned on this desktop, and cursor input is delivered to
+/// the topmost visible window beneath the cursor. Keyboard input is delivered
+/// to the focused window. Like most desktop environments, pressing a mouse
+/// button focuses the window beneath the cursor, and newly opened windows are
+/// focused.
+///
+/// Only windows whose contents are widgets can be opened in a virtual app.
+/// Windows with a custom [`WindowBehavior`](super::WindowBehavior) that are
+/// opened by widgets using the app are never shown, and an error is logged.
+///
+/// ```rust,no_run
+/// use cushy::reactive::value::Dynamic;
+/// use cushy::widget::MakeWidget;
+/// use cushy::window::{Rgb8, VirtualApp};
+///
+/// let mut app = VirtualApp::<Rgb8>::new().unwrap();
+/// let main = app.open("Main Window");
+/// // Windows opened without focus remain beneath the focused window.
+/// let palette = app.open("Tool Palette".into_window().focused(Dynamic::new(false)));
+/// app.refresh().unwrap();
+/// assert_eq!(app.focused(), Some(main));
+/// app.window(palette).unwrap().image().save("palette.png").unwrap();
+/// ```
+pub struct VirtualApp<Format = super::Rgb8> {
+    app: App,
+    requests: VirtualWindowRequests,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    windows: Vec<VirtualAppWindow<Format>>,
+    monitors: Vec<VirtualMonitor>,
+    focused: Option<VirtualWindowId>,
+    hovered: Option<VirtualWindowId>,
+    next_id: u64,
+}
+
+impl<Format> VirtualApp<Format>
+where
+    Format: CaptureFormat,
+{
+    /// Returns a new virtual app without any open windows.
+    ///
+    /// The app's desktop contains a single 1920x1080 monitor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a graphics device cannot be initialized.
+    pub fn new() -> Result<Self, VirtualRecorderError> {
+        let (device, queue) = request_virtual_device()?;
+        let requests = VirtualWindowRequests::default();
+        Ok(Self {
+            app: App::virtual_app(requests.clone()),
+            requests,
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            windows: Vec::new(),
+            monitors: vec![VirtualMonitor::new(
+                "Virtual Monitor",
+                Rect::new(Point::ZERO, Size::px(1920, 1080)),
+            )],
+            focused: None,
+            hovered: None,
+            next_id: 0,
+        })
+    }
+
+    /// Opens `window` in this app, returning its id.
+    pub fn open<W>(&mut self, window: W) -> VirtualWindowId
+    where
+        W: MakeWindow<Behavior = WidgetInstance>,
+    {
+        let handle = window
+            .open(&mut self.app)
+            .expect("virtual windows always open");
+        self.update_windows();
+        self.id_of(&handle)
+            .expect("virtual window opened synchronously")
+    }
+
+    /// Returns the ids of the open windows, ordered from the bottommost
+    /// window to the topmost window.
+    #[must_use]
+    pub fn windows(&self) -> Vec<VirtualWindowId> {
+        self.windows.iter().map(|window| window.id).collect()
+    }
+
+    /// Returns the recorder of the window with `id`, if it is open.
+    #[must_use]
+    pub fn window(&self, id: VirtualWindowId) -> Option<&VirtualRecorder<Format>> {
+        self.find(id).map(|window| &window.recorder)
+    }
+
+    /// Returns an exclusive reference to the recorder of the window with
+    /// `id`, if it is open.
+    #[must_use]
+    pub fn window_mut(&mut self, id: VirtualWindowId) -> Option<&mut VirtualRecorder<Format>> {
+        self.find_mut(id).map(|window| &mut window.recorder)
+    }
+
+    /// Returns the id of the window `handle` refers to, if it is open.
+    #[must_use]
+    pub fn id_of(&self, handle: &WindowHandle) -> Option<VirtualWindowId> {
+        self.windows
+            .iter()
+            .find(|window| &window.handle == handle)
+            .map(|window| window.id)
+    }
+
+    /// Returns true if the window with `id` is open.
+    #[must_use]
+    pub fn is_open(&self, id: VirtualWindowId) -> bool {
+        self.find(id).is_some()
+    }
+
+    /// Returns true if the window with `id` is open and visible.
+    #[must_use]
+    pub fn is_visible(&self, id: VirtualWindowId) -> bool {
+        self.find(id).is_some_and(|window| window.visible.get())
+    }
+
+    /// Returns the id of the focused window, if any of this app's windows are
+    /// focused.
+    #[must_use]
+    pub const fn focused(&self) -> Option<VirtualWindowId> {
+        self.focused
+    }
+
+    /// Focuses the window with `id` and raises it above the other windows.
+    ///
+    /// Returns false if the window is not open.
+    pub fn focus(&mut self, id: VirtualWindowId) -> bool {
+        let Some(index) = self.windows.iter().position(|window| window.id == id) else {
+            return false;
+        };
+        let window = self.windows.remove(index);
+        self.windows.push(window);
+        self.set_focused(Some(id));
+        true
+    }
+
+    /// Removes focus from all of this app's windows, simulating the user
+    /// switching to another app.
+    pub fn unfocus(&mut self) {
+        self.set_focused(None);
+    }
+
+    /// Returns the location of the top-left corner of the window with `id` on
+    /// the virtual desktop.
+    #[must_use]
+    pub fn position(&self, id: VirtualWindowId) -> Option<Point<Px>> {
+        self.find(id).map(|window| window.position)
+    }
+
+    /// Moves the window with `id` so that its top-left corner is at
+    /// `position` on the virtual desktop.
+    ///
+    /// If the window moves to a monitor with a different scale, the window's
+    /// DPI scale is updated.
+    pub fn set_position(&mut self, id: VirtualWindowId, position: Point<Px>) {
+        if let Some(window) = self.find_mut(id) {
+            window.position = position;
+            window.recorder.window.set_position(position);
+        }
+        self.update_scales();
+    }
+
+    /// Returns the monitors of the virtual desktop.
+    #[must_use]
+    pub fn monitors(&self) -> &[VirtualMonitor] {
+        &self.monitors
+    }
+
+    /// Replaces the monitors of the virtual desktop.
+    ///
+    /// The first monitor is treated as the primary monitor. Windows are not
+    /// moved, but each window's DPI scale is updated to match the monitor it
+    /// is on.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `monitors` is empty.
+    pub fn set_monitors(&mut self, monitors: impl IntoIterator<Item = VirtualMonitor>) {
+        self.monitors = monitors.into_iter().collect();
+        assert!(
+            !self.monitors.is_empty(),
+            "at least one monitor is required"
+        );
+        self.update_scales();
+    }
+
+    /// Returns the monitor the window with `id` is on.
+    ///
+    /// A window is on the monitor that contains its top-left corner. Windows
+    /// that are not on any monitor are treated as being on the primary
+    /// monitor.
+    #[must_use]
+    pub fn monitor_of(&self, id: VirtualWindowId) -> Option<&VirtualMonitor> {
+        self.find(id)
+            .map(|window| monitor_at(&self.monitors, window.position))
+    }
+
+    /// Moves the cursor to `location` on the virtual desktop.
+    ///
+    /// The cursor movement is delivered to the topmost visible window beneath
+    /// `location`. If the cursor moves out of a window, the window is notified
+    /// that the cursor has left it.
+    pub fn cursor_moved(&mut self, location: Point<Px>) {
+        let hovered = self.window_at(location);
+        if hovered != self.hovered {
+            self.cursor_left();
+        }
+        self.hovered = hovered;
+        if let Some(window) = hovered.and_then(|id| self.find_mut(id)) {
+            let local = location - window.position;
+            window.recorder.set_cursor_position(local);
+            window
+                .recorder
+                .window
+                .cursor_moved(DeviceId::Virtual(0), local);
+        }
+        self.update_windows();
+    }
+
+    /// Moves the cursor off of all of this app's windows.
+    pub fn cursor_left(&mut self) {
+        if let Some(window) = self.hovered.take().and_then(|id| self.find_mut(id)) {
+            window.recorder.window.cursor_left();
+        }
+        self.update_windows();
+    }
+
+    /// Presses or releases `button` on the window beneath the cursor.
+    ///
+    /// Pressing a button focuses the window beneath the cursor.
+    pub fn mouse_input(&mut self, state: ElementState, button: MouseButton) -> EventHandling {
+        let Some(hovered) = self.hovered.filter(|id| self.is_open(*id)) else {
+            return IGNORED;
+        };
+        if state.is_pressed() && self.focused != Some(hovered) {
+            self.focus(hovered);
+        }
+        let handled = self.find_mut(hovered).map_or(IGNORED, |window| {
+            window
+                .recorder
+                .window
+                .mouse_input(DeviceId::Virtual(0), state, button)
+        });
+        self.update_windows();
+        handled
+    }
+
+    /// Delivers mouse wheel input to the window beneath the cursor.
+    pub fn mouse_wheel(&mut self, delta: MouseScrollDelta, phase: TouchPhase) -> EventHandling {
+        let handled = self
+            .hovered
+            .and_then(|id| self.find_mut(id))
+            .map_or(IGNORED, |window| {
+                window
+                    .recorder
+                    .window
+                    .mouse_wheel(DeviceId::Virtual(0), delta, phase)
+            });
+        self.update_windows();
+        handled
+    }
+
+    /// Delivers keyboard input to the focused window.
+    pub fn keyboard_input(&mut self, input: KeyEvent, is_synthetic: bool) -> EventHandling {
+        let handled = self
+            .focused
+            .and_then(|id| self.find_mut(id))
+            .map_or(IGNORED, |window| {
+                window
+                    .recorder
+                    .window
+                    .keyboard_input(DeviceId::Virtual(0), input, is_synthetic)
+            });
+        self.update_windows();
+        handled
+    }
+
+    /// Redraws and captures each visible window.
+    ///
+    /// Windows that have been requested to open are opened, and windows that
+    /// have been requested to close are closed before rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a window's contents cannot be captured.
+    pub fn refresh(&mut self) -> Result<(), VirtualRecorderError> {
+        self.update_windows();
+        for window in &mut self.windows {
+            if window.visible.get() {
+                window.recorder.refresh()?;
+            }
+        }
+        // Rendering may open or close windows.
+        self.update_windows();
+        Ok(())
+    }
+
+    fn find(&self, id: VirtualWindowId) -> Option<&VirtualAppWindow<Format>> {
+        self.windows.iter().find(|window| window.id == id)
+    }
+
+    fn find_mut(&mut self, id: VirtualWindowId) -> Option<&mut VirtualAppWindow<Format>> {
+        self.windows.iter_mut().find(|window| window.id == id)
+    }
+
+    /// Returns the topmost visible window containing `location`.
+    fn window_at(&self, location: Point<Px>) -> Option<VirtualWindowId> {
+        self.windows
+            .iter()
+            .rev()
+            .filter(|window| window.visible.get())
+            .find(|window| {
+                Rect::new(window.position, window.recorder.window.size().into_signed())
+                    .contains(location)
+            })
+            .map(|window| window.id)
+    }
+
+    fn set_focused(&mut self, focused: Option<VirtualWindowId>) {
+        if self.focused == focused {
+            return;
+        }
+        if let Some(window) = self.focused.and_then(|id| self.find_mut(id)) {
+            window.recorder.window.set_focused(false);
+        }
+        self.focused = focused;
+        if let Some(window) = focused.and_then(|id| self.find_mut(id)) {
+            window.recorder.window.set_focused(true);
+        }
+    }
+
+    /// Opens requested windows and removes closed windows.
+    fn update_windows(&mut self) {
+        for request in self.requests.take() {
+            self.open_requested(request);
+        }
+
+        for window in &mut self.windows {
+            if window.recorder.window.state().dynamic.close_requested.get() {
+                window.recorder.window.request_close();
+            }
+        }
+
+        let closed = self
+            .windows
+            .iter()
+            .filter(|window| window.recorder.window.closed())
+            .map(|window| window.id)
+            .collect::<Vec<_>>();
+        if closed.is_empty() {
+            return;
+        }
+        self.windows
+            .retain(|window| !window.recorder.window.closed());
+        if self.hovered.is_some_and(|id| closed.contains(&id)) {
+            self.hovered = None;
+        }
+        if self.focused.is_some_and(|id| closed.contains(&id)) {
+            // Focus returns to the topmost remaining window.
+            self.focused = None;
+            let next = self
+                .windows
+                .iter()
+                .rev()
+                .find(|window| window.visible.get())
+                .map(|window| window.id);
+            self.set_focused(next);
+        }
+    }
+
+    fn open_requested(&mut self, request: VirtualWindowRequest) {
+        let VirtualWindowRequest {
+            contents,
+            mut settings,
+            pending,
+        } = request;
+        let id = VirtualWindowId(self.next_id);
+        self.next_id += 1;
+
+        let attributes = settings.attributes.take().unwrap_or_default();
+        let position = attributes.position.map_or_else(
+            || {
+                // Cascade new windows from the primary monitor's origin.
+                let offset = Px::new(32 * i32::try_from(self.windows.len() % 10).unwrap_or(0));
+                self.monitors[0].region.origin + Point::squared(offset)
+            },
+            |position| {
+                let position = position.to_physical::<i32>(1.);
+                Point::px(position.x, position.y)
+            },
+        );
+        let scale = monitor_at(&self.monitors, position).scale;
+        let size = attributes.inner_size.map_or_else(
+            || {
+                let size = settings.inner_size.get();
+                if size.is_zero() {
+                    Size::upx(800, 600)
+                } else {
+                    size
+                }
+            },
+            |size| {
+                let size = size.to_physical::<u32>(f64::from(scale));
+                Size::upx(size.width, size.height)
+            },
+        );
+        let visible = settings.visible.clone();
+        if attributes.visible {
+            visible.set(true);
+        }
+
+        let mut window = VirtualWindow::from_settings(
+            contents,
+            settings,
+            size,
+            scale,
+            &self.device,
+            &self.queue,
+        );
+        window.set_position(position);
+        let handle = pending.handle();
+        pending.opened_virtual(&window.state().dynamic);
+        self.windows.push(VirtualAppWindow {
+            id,
+            handle,
+            visible,
+            position,
+            recorder: VirtualRecorder::from_window(window, self.device.clone(), self.queue.clone()),
+        });
+
+        if attributes.active {
+            self.set_focused(Some(id));
+        } else if let Some(focused) = self.focused {
+            // Keep the focused window above windows opened without focus.
+            self.focus(focused);
+        }
+    }
+
+    /// Updates each window's DPI scale to match the monitor it is on.
+    fn update_scales(&mut self) {
+        for window in &mut self.windows {
+            let scale = Fraction::from(monitor_at(&self.monitors, window.position).scale);
+            if window.recorder.window.dpi_scale() != scale {
+                let size = window.recorder.window.state().size;
+                window.recorder.window.resize(size, scale, &self.queue);
+            }
+        }
+    }
+}
+
+impl<Format> Application for VirtualApp<Format> {
+    fn cushy(&self) -> &Cushy {
+        self.app.cushy()
+    }
+
+    fn as_app(&self) -> App {
+        self.app.clone()
+    }
+}
+
+impl<Format> AsApplication<AppEvent<WindowCommand>> for VirtualApp<Format> {
+    fn as_application(&self) -> &dyn kludgine::app::Application<AppEvent<WindowCommand>> {
+        self.app.as_application()
+    }
+
+    fn as_application_mut(&mut self) -> &mut dyn kludgine::app::Application<AppEvent<WindowCommand>>
+    where
+        AppEvent<WindowCommand>: kludgine::app::Message,
+    {
+        self.app.as_application_mut()
+    }
+}
+
+/// Returns the monitor containing `location`, or the primary monitor if no
+/// monitor contains it.
+fn monitor_at(monitors: &[VirtualMonitor], location: Point<Px>) -> &VirtualMonitor {
+    monitors
+        .iter()
+        .find(|monitor| monitor.region.contains(location))
+        .unwrap_or(&monitors[0])
+}
+
+struct VirtualAppWindow<Format> {
+    id: VirtualWindowId,
+    handle: WindowHandle,
+    visible: Dynamic<bool>,
+    position: Point<Px>,
+    recorder: VirtualRecorder<Format>,
+}
+
+/// The id of a window opened in a [`VirtualApp`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct VirtualWindowId(u64);
+
+/// A simulated monitor of a [`VirtualApp`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualMonitor {
+    name: String,
+    region: Rect<Px>,
+    scale: f32,
+}
+
+impl VirtualMonitor {
+    /// Returns a monitor named `name` that occupies `region` of the virtual
+    /// desktop, with a DPI scale of 1.
+    #[must_use]
+    pub fn new(name: impl Into<String>, region: Rect<Px>) -> Self {
+        Self {
+            name: name.into(),
+            region,
+            scale: 1.,
+        }
+    }
+
+    /// Sets the DPI scale of windows on this monitor, and returns self.
+    #[must_use]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Returns the name of this monitor.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the region of the virtual desktop this monitor occupies.
+    #[must_use]
+    pub const fn region(&self) -> Rect<Px> {
+        self.region
+    }
+
+    /// Returns the DPI scale of windows on this monitor.
+    #[must_use]
+    pub const fn scale(&self) -> f32 {
+        self.scale
+    }
+}
+
+/// Windows that have been opened in a virtual [`App`] and are waiting to be
+/// created by its [`VirtualApp`].
+#[derive(Clone, Default)]
+pub(crate) struct VirtualWindowRequests(Arc<Mutex<Vec<VirtualWindowRequest>>>);
+
+impl VirtualWindowRequests {
+    pub(crate) fn push(
+        &self,
+        contents: WidgetInstance,
+        settings: sealed::WindowSettings,
+        pending: PendingWindow,
+    ) {
+        self.0.lock().push(VirtualWindowRequest {
+            contents,
+            settings,
+            pending,
+        });
+    }
+
+    fn take(&self) -> Vec<VirtualWindowRequest> {
+        std::mem::take(&mut *self.0.lock())
+    }
+}
+
+struct VirtualWindowRequest {
+    contents: WidgetInstance,
+    settings: sealed::WindowSettings,
+    pending: PendingWindow,
+}
+
+#[test]
+fn windows_use_the_scale_of_their_monitor() {
+    let monitors = [
+        VirtualMonitor::new("Primary", Rect::new(Point::ZERO, Size::px(1920, 1080))),
+        VirtualMonitor::new(
+            "Secondary",
+            Rect::new(Point::px(1920, 0), Size::px(3840, 2160)),
+        )
+        .with_scale(2.),
+    ];
+    assert_eq!(monitor_at(&monitors, Point::px(100, 100)).name(), "Primary");
+    assert_eq!(monitor_at(&monitors, Point::px(2000, 100)).scale(), 2.);
+    // Windows off of every monitor belong to the primary monitor.
+    assert_eq!(monitor_at(&monitors, Point::px(-100, 0)).name(), "Primary");
+}
+
+#[test]
+fn windows_open_and_close() {
+    use crate::widget::MakeWidget;
+
+    let mut app = VirtualApp::<super::Rgb8>::new().expect("error creating app");
+    let main = app.open("Main");
+    let dialog = "Dialog".into_window();
+    let dialog_handle = dialog.handle().clone();
+    let dialog = app.open(dialog);
+    app.refresh().expect("error rendering");
+    assert_eq!(app.windows(), [main, dialog]);
+    assert_eq!(app.id_of(&dialog_handle), Some(dialog));
+    assert_eq!(app.focused(), Some(dialog));
+
+    dialog_handle.request_close();
+    app.refresh().expect("error rendering");
+    assert!(!app.is_open(dialog));
+    assert_eq!(app.windows(), [main]);
+    // Focus returns to the remaining window.
+    assert_eq!(app.focused(), Some(main));
+}